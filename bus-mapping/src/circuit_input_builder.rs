@@ -15,6 +15,7 @@ use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
     operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    precompile::EcrecoverAuxData,
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
@@ -23,7 +24,9 @@ pub use block::{Block, BlockContext};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
-    self, geth_types,
+    self,
+    chain_spec::ChainSpec,
+    geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
     Address, GethExecStep, GethExecTrace, ToWord, Word,
 };
@@ -35,7 +38,7 @@ pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
 use std::collections::HashMap;
-pub use transaction::{Transaction, TransactionContext};
+pub use transaction::{Transaction, TransactionContext, MAX_ACCESS_LIST_ENTRIES};
 
 /// Circuit Setup Parameters
 #[derive(Debug, Clone, Copy)]
@@ -68,6 +71,48 @@ pub struct CircuitsParams {
     /// calculated, so the same circuit will not be able to prove different
     /// witnesses.
     pub max_keccak_rows: usize,
+    /// Maximum number of ecRecover precompile calls whose public key
+    /// recovery is verified in the signature verifier of the Tx Circuit
+    pub max_ecrecover: usize,
+    /// Pad the SHA-256 circuit to a static capacity of this number of rows.
+    /// When 0, the SHA-256 circuit number of rows will be dynamically
+    /// calculated, so the same circuit will not be able to prove different
+    /// witnesses.
+    pub max_sha256_rows: usize,
+    /// Pad the RIPEMD-160 circuit to a static capacity of this number of
+    /// rows. When 0, the RIPEMD-160 circuit number of rows will be
+    /// dynamically calculated, so the same circuit will not be able to prove
+    /// different witnesses.
+    pub max_ripemd160_rows: usize,
+    /// Pad the MODEXP circuit to a static capacity of this number of rows.
+    /// When 0, the MODEXP circuit number of rows will be dynamically
+    /// calculated, so the same circuit will not be able to prove different
+    /// witnesses.
+    pub max_modexp_rows: usize,
+    /// Maximum number of ecAdd precompile calls verified in the ECC circuit.
+    /// When 0, the ECC circuit is sized to the calls of the block, so the
+    /// same circuit will not be able to prove different witnesses.
+    pub max_ec_add: usize,
+    /// Maximum number of ecMul precompile calls verified in the ECC circuit.
+    /// When 0, the ECC circuit is sized to the calls of the block, so the
+    /// same circuit will not be able to prove different witnesses.
+    pub max_ec_mul: usize,
+    /// Maximum number of pairs of points of the ecPairing precompile calls
+    /// verified in the pairing circuit. When 0, the pairing circuit is sized
+    /// to the pairs of the block, so the same circuit will not be able to
+    /// prove different witnesses.
+    pub max_ec_pairing_pairs: usize,
+    /// Maximum number of rounds of the BLAKE2 F precompile calls verified in
+    /// the Blake2f circuit, where each call takes two more rounds for its
+    /// initialization and finalization. When 0, the Blake2f circuit is sized
+    /// to the calls of the block, so the same circuit will not be able to
+    /// prove different witnesses.
+    pub max_blake2f_rounds: usize,
+    /// Maximum number of rows of the RLP circuit, one per byte of the
+    /// encodings of the transactions for signing and hashing. When 0, the
+    /// RLP circuit is sized to the transactions of the block, so the same
+    /// circuit will not be able to prove different witnesses.
+    pub max_rlp_rows: usize,
 }
 
 impl Default for CircuitsParams {
@@ -84,6 +129,15 @@ impl Default for CircuitsParams {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp_rows: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_ec_pairing_pairs: 0,
+            max_blake2f_rounds: 0,
+            max_rlp_rows: 0,
         }
     }
 }
@@ -337,6 +391,10 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     }
     // EVM Circuit
     keccak_inputs.extend_from_slice(&block.sha3_inputs);
+    // ecRecover calls, verified by the SignVerify Chip
+    keccak_inputs.extend_from_slice(&keccak_inputs_ecrecover(
+        &block.precompile_events.get_ecrecover_events(),
+    ));
     // MPT Circuit
     // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
     Ok(keccak_inputs)
@@ -358,6 +416,19 @@ pub fn keccak_inputs_sign_verify(sigs: &[SignData]) -> Vec<Vec<u8>> {
     inputs
 }
 
+/// Generate the keccak inputs required by the SignVerify Chip from the
+/// ecRecover calls, which are the public keys that could be recovered.
+pub fn keccak_inputs_ecrecover(events: &[EcrecoverAuxData]) -> Vec<Vec<u8>> {
+    events
+        .iter()
+        .filter_map(|event| event.recover_pk())
+        .map(|pk| {
+            let pk_le = pk_bytes_le(&pk);
+            pk_bytes_swap_endianness(&pk_le).to_vec()
+        })
+        .collect()
+}
+
 /// Generate the keccak inputs required by the Tx Circuit from the transactions.
 pub fn keccak_inputs_tx_circuit(
     txs: &[geth_types::Transaction],
@@ -380,8 +451,12 @@ pub fn keccak_inputs_tx_circuit(
     // Keccak inputs from SignVerify Chip
     let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
     inputs.extend_from_slice(&sign_verify_inputs);
-    // NOTE: We don't verify the Tx Hash in the circuit yet, so we don't have more
-    // hash inputs.
+    // Keccak inputs from RLP Circuit: the encodings of the txs for the sign hash
+    // and the tx hash
+    for tx in txs.iter() {
+        inputs.push(tx.rlp_unsigned(chain_id).to_vec());
+        inputs.push(tx.rlp_signed(chain_id).to_vec());
+    }
     Ok(inputs)
 }
 
@@ -415,6 +490,7 @@ type EthBlock = eth_types::Block<eth_types::Transaction>;
 pub struct BuilderClient<P: JsonRpcClient> {
     cli: GethClient<P>,
     chain_id: Word,
+    chain_spec: ChainSpec,
     circuits_params: CircuitsParams,
 }
 
@@ -471,16 +547,31 @@ pub fn build_state_code_db(
 }
 
 impl<P: JsonRpcClient> BuilderClient<P> {
-    /// Create a new BuilderClient
+    /// Create a new BuilderClient for a chain with a known specification
     pub async fn new(
         client: GethClient<P>,
         circuits_params: CircuitsParams,
     ) -> Result<Self, Error> {
         let chain_id = client.get_chain_id().await?;
+        let chain_spec = ChainSpec::from_chain_id(chain_id).ok_or(Error::EthTypeError(
+            eth_types::Error::UnsupportedChain(chain_id),
+        ))?;
+
+        Self::new_with_chain_spec(client, chain_spec, circuits_params).await
+    }
+
+    /// Create a new BuilderClient for a chain with the given specification
+    pub async fn new_with_chain_spec(
+        client: GethClient<P>,
+        chain_spec: ChainSpec,
+        circuits_params: CircuitsParams,
+    ) -> Result<Self, Error> {
+        let chain_id = client.get_chain_id().await?;
 
         Ok(Self {
             cli: client,
             chain_id: chain_id.into(),
+            chain_spec,
             circuits_params,
         })
     }
@@ -618,6 +709,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
     ) -> Result<CircuitInputBuilder, Error> {
         let block = Block::new(
             self.chain_id,
+            &self.chain_spec,
             history_hashes,
             prev_state_root,
             eth_block,
@@ -5,9 +5,15 @@ use super::{
 };
 use crate::{
     operation::{OperationContainer, RWCounter},
+    precompile::{PrecompileEvent, PrecompileEvents},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word};
+use eth_types::{
+    chain_spec::{ChainSpec, Hardfork},
+    evm_unimplemented,
+    geth_types::block_blob_base_fee,
+    Address, Word,
+};
 use std::collections::HashMap;
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -57,6 +63,8 @@ pub struct BlockSteps {
 pub struct Block {
     /// chain id
     pub chain_id: Word,
+    /// hardfork whose rules apply to the block
+    pub hardfork: Hardfork,
     /// history hashes contains most recent 256 block hashes in history, where
     /// the lastest one is at history_hashes[history_hashes.len() - 1].
     pub history_hashes: Vec<Word>,
@@ -72,6 +80,8 @@ pub struct Block {
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
     /// State root of the previous block
     pub prev_state_root: Word,
     /// Container of operations done in this block.
@@ -86,6 +96,8 @@ pub struct Block {
     pub sha3_inputs: Vec<Vec<u8>>,
     /// Exponentiation events in the block.
     pub exp_events: Vec<ExpEvent>,
+    /// Precompile events in the block.
+    pub precompile_events: PrecompileEvents,
     /// Circuits Setup Paramteres
     pub circuits_params: CircuitsParams,
     /// Original block from geth
@@ -96,6 +108,7 @@ impl Block {
     /// Create a new block.
     pub fn new(
         chain_id: Word,
+        chain_spec: &ChainSpec,
         history_hashes: Vec<Word>,
         prev_state_root: Word,
         eth_block: &eth_types::Block<eth_types::Transaction>,
@@ -108,21 +121,27 @@ impl Block {
             );
         }
 
+        let number = eth_block
+            .number
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
+            .low_u64();
+        let hardfork = chain_spec
+            .hardfork(number, eth_block.timestamp.low_u64())
+            .ok_or(Error::EthTypeError(eth_types::Error::UnsupportedHardfork))?;
+
         Ok(Self {
             chain_id,
+            hardfork,
             history_hashes,
             coinbase: eth_block
                 .author
                 .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?,
             gas_limit: eth_block.gas_limit.low_u64(),
-            number: eth_block
-                .number
-                .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
-                .low_u64()
-                .into(),
+            number: number.into(),
             timestamp: eth_block.timestamp,
             difficulty: eth_block.difficulty,
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            blob_base_fee: block_blob_base_fee(eth_block)?,
             prev_state_root,
             container: OperationContainer::new(),
             txs: Vec::new(),
@@ -138,6 +157,7 @@ impl Block {
             },
             copy_events: Vec::new(),
             exp_events: Vec::new(),
+            precompile_events: PrecompileEvents::default(),
             sha3_inputs: Vec::new(),
             circuits_params,
             eth_block: eth_block.clone(),
@@ -164,4 +184,8 @@ impl Block {
     pub fn add_exp_event(&mut self, event: ExpEvent) {
         self.exp_events.push(event);
     }
+    /// Push a precompile event to the block.
+    pub fn add_precompile_event(&mut self, event: PrecompileEvent) {
+        self.precompile_events.events.push(event);
+    }
 }
@@ -1,8 +1,11 @@
 //! Execution step related module.
 
 use crate::{
-    circuit_input_builder::CallContext, error::ExecError, exec_trace::OperationRef,
-    operation::RWCounter, precompile::PrecompileCalls,
+    circuit_input_builder::CallContext,
+    error::ExecError,
+    exec_trace::OperationRef,
+    operation::RWCounter,
+    precompile::{PrecompileAuxData, PrecompileCalls},
 };
 use eth_types::{
     evm_types::{Gas, GasCost, OpcodeId, ProgramCounter},
@@ -49,6 +52,9 @@ pub struct ExecStep {
     pub copy_rw_counter_delta: u64,
     /// Error generated by this step
     pub error: Option<ExecError>,
+    /// Optional auxiliary data that is attached to precompile call internal
+    /// states.
+    pub aux_data: Option<PrecompileAuxData>,
 }
 
 impl ExecStep {
@@ -76,6 +82,7 @@ impl ExecStep {
             bus_mapping_instance: Vec::new(),
             copy_rw_counter_delta: 0,
             error: None,
+            aux_data: None,
         }
     }
 
@@ -202,8 +209,15 @@ pub enum CopyDataType {
     TxLog,
     /// When the destination rows are not directly for copying but for a special
     /// scenario where we wish to accumulate the value (RLC) over all rows.
-    /// This is used for Copy Lookup from SHA3 opcode verification.
+    /// This is used for Copy Lookup from SHA3 opcode verification. As a
+    /// source, the RLC of the copied bytes is accumulated over the read rows
+    /// instead, which is used to write the output of precompiles to memory.
     RlcAcc,
+    /// When the destination for the copy event is the same memory as the
+    /// source, as in MCOPY. All the source bytes are read before any of the
+    /// destination bytes is written, so that overlapping ranges are copied
+    /// as if through an intermediate buffer.
+    MemoryCopy,
 }
 
 impl From<CopyDataType> for usize {
@@ -286,20 +300,34 @@ impl CopyEvent {
 
     // increase in rw counter from the start of the copy event to step index
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
+        if self.dst_type == CopyDataType::MemoryCopy {
+            // The reads take the first `length` rw counters and the writes the
+            // following `length` ones.
+            let step = u64::try_from(step_index / 2).unwrap();
+            return if step_index % 2 == 1 || step_index == self.bytes.len() * 2 {
+                u64::try_from(self.bytes.len()).unwrap() + step
+            } else {
+                step
+            };
+        }
         let source_rw_increase = match self.src_type {
-            CopyDataType::Bytecode | CopyDataType::TxCalldata => 0,
+            CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
             CopyDataType::Memory => std::cmp::min(
                 u64::try_from(step_index + 1).unwrap() / 2,
                 self.src_addr_end
                     .checked_sub(self.src_addr)
                     .unwrap_or_default(),
             ),
-            CopyDataType::RlcAcc | CopyDataType::TxLog | CopyDataType::Padding => unreachable!(),
+            CopyDataType::TxLog | CopyDataType::Padding | CopyDataType::MemoryCopy => {
+                unreachable!()
+            }
         };
         let destination_rw_increase = match self.dst_type {
             CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
             CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
-            CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
+            CopyDataType::TxCalldata | CopyDataType::Padding | CopyDataType::MemoryCopy => {
+                unreachable!()
+            }
         };
         source_rw_increase + destination_rw_increase
     }
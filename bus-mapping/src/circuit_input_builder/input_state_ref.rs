@@ -13,12 +13,14 @@ use crate::{
         StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
         RW,
     },
+    precompile::{self, PrecompileEvent},
     state_db::{CodeDB, StateDB},
     Error,
 };
 use eth_types::{
     evm_types::{
         gas_utils::memory_expansion_gas_cost, Gas, GasCost, MemoryAddress, OpcodeId, StackAddress,
+        INVALID_INIT_CODE_FIRST_BYTE, MAX_CODE_SIZE,
     },
     Address, Bytecode, GethExecStep, ToAddress, ToBigEndian, ToWord, Word, H256, U256,
 };
@@ -705,7 +707,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        precompile::is_precompiled(address, self.block.hardfork)
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step.
@@ -816,6 +818,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             OperationRef(Target::TxAccessListAccount, idx) => {
                 let operation = &self.block.container.tx_access_list_account[*idx];
                 if operation.rw().is_write() && operation.reversible() {
@@ -832,6 +842,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TxCreatedAccount, idx) => {
+                let operation = &self.block.container.tx_created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxCreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             OperationRef(Target::TxRefund, idx) => {
                 let operation = &self.block.container.tx_refund[*idx];
                 if operation.rw().is_write() && operation.reversible() {
@@ -858,6 +876,10 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::Storage(op) => {
                 self.sdb.set_storage(&op.address, &op.key, &op.value);
             }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
             OpEnum::TxAccessListAccount(op) => {
                 if !op.is_warm_prev && op.is_warm {
                     self.sdb.add_account_to_access_list(op.address);
@@ -876,6 +898,14 @@ impl<'a> CircuitInputStateRef<'a> {
                         .remove_account_storage_from_access_list(&(op.address, op.key));
                 }
             }
+            OpEnum::TxCreatedAccount(op) => {
+                if !op.is_created_prev && op.is_created {
+                    self.sdb.mark_account_created_in_tx(op.address);
+                }
+                if op.is_created_prev && !op.is_created {
+                    self.sdb.unmark_account_created_in_tx(&op.address);
+                }
+            }
             OpEnum::Account(op) => self.check_update_sdb_account(RW::WRITE, op),
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
@@ -884,8 +914,10 @@ impl<'a> CircuitInputStateRef<'a> {
         };
     }
 
-    /// Handle a reversion group
-    fn handle_reversion(&mut self) {
+    /// Handle a reversion group. The reverted operations are attached to the
+    /// steps that did the original writes, which can be steps of
+    /// `current_exec_steps` that are not yet pushed to the transaction.
+    pub(crate) fn handle_reversion(&mut self, current_exec_steps: &mut [&mut ExecStep]) {
         let reversion_group = self
             .tx_ctx
             .reversion_groups
@@ -902,9 +934,16 @@ impl<'a> CircuitInputStateRef<'a> {
                     false,
                     op,
                 );
-                self.tx.steps_mut()[step_index]
-                    .bus_mapping_instance
-                    .push(rev_op_ref);
+                let num_steps = self.tx.steps().len();
+                if step_index >= num_steps {
+                    current_exec_steps[step_index - num_steps]
+                        .bus_mapping_instance
+                        .push(rev_op_ref);
+                } else {
+                    self.tx.steps_mut()[step_index]
+                        .bus_mapping_instance
+                        .push(rev_op_ref);
+                }
             }
         }
 
@@ -933,7 +972,9 @@ impl<'a> CircuitInputStateRef<'a> {
         let (return_data_offset, return_data_length) = {
             if !self.call()?.is_root {
                 let (offset, length) = match step.op {
-                    OpcodeId::RETURN | OpcodeId::REVERT => {
+                    // RETURN from a creation call which fails the code deposit
+                    // checks doesn't return any data.
+                    OpcodeId::RETURN | OpcodeId::REVERT if exec_step.error.is_none() => {
                         let offset = step.stack.nth_last(0)?.as_usize();
                         let length = step.stack.nth_last(1)?.as_usize();
                         // At the moment it conflicts with `call_ctx` and `caller_ctx`.
@@ -981,7 +1022,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
         // Handle reversion if this call doesn't end successfully
         if !call.is_success {
-            self.handle_reversion();
+            self.handle_reversion(&mut [exec_step]);
         }
 
         // If current call has caller.
@@ -1020,7 +1061,9 @@ impl<'a> CircuitInputStateRef<'a> {
         let geth_step = steps
             .get(0)
             .ok_or(Error::InternalError("invalid index 0"))?;
-        let is_return_revert = geth_step.op == OpcodeId::REVERT || geth_step.op == OpcodeId::RETURN;
+        let is_return_revert = (geth_step.op == OpcodeId::REVERT
+            || geth_step.op == OpcodeId::RETURN)
+            && exec_step.error.is_none();
 
         if !is_return_revert && !call.is_success {
             // add call failure ops for exception cases
@@ -1060,7 +1103,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
         let [last_callee_return_data_offset, last_callee_return_data_length] = match geth_step.op {
             OpcodeId::STOP => [Word::zero(); 2],
-            OpcodeId::REVERT | OpcodeId::RETURN => {
+            OpcodeId::REVERT | OpcodeId::RETURN if is_return_revert => {
                 let offset = geth_step.stack.nth_last(0)?;
                 let length = geth_step.stack.nth_last(1)?;
                 // This is the convention we are using for memory addresses so that there is no
@@ -1092,7 +1135,14 @@ impl<'a> CircuitInputStateRef<'a> {
         } else {
             0
         };
-        let gas_refund = geth_step.gas.0 - memory_expansion_gas_cost - code_deposit_cost;
+        // SELFDESTRUCT consumes its own gas cost before the remaining gas is returned.
+        let selfdestruct_gas_cost = if geth_step.op == OpcodeId::SELFDESTRUCT {
+            geth_step.gas_cost.0
+        } else {
+            0
+        };
+        let gas_refund =
+            geth_step.gas.0 - memory_expansion_gas_cost - code_deposit_cost - selfdestruct_gas_cost;
 
         let caller_gas_left = if is_return_revert || call.is_success {
             geth_step_next.gas.0 - gas_refund
@@ -1162,6 +1212,11 @@ impl<'a> CircuitInputStateRef<'a> {
         self.block.add_exp_event(event)
     }
 
+    /// Push a precompile event to the state.
+    pub fn push_precompile_event(&mut self, event: PrecompileEvent) {
+        self.block.add_precompile_event(event)
+    }
+
     pub(crate) fn get_step_err(
         &self,
         step: &GethExecStep,
@@ -1171,7 +1226,7 @@ impl<'a> CircuitInputStateRef<'a> {
             return Ok(Some(get_step_reported_error(&step.op, error)));
         }
 
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        if matches!(step.op, OpcodeId::INVALID(_)) || !step.op.is_enabled_in(self.block.hardfork) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
@@ -1220,6 +1275,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -1249,14 +1305,17 @@ impl<'a> CircuitInputStateRef<'a> {
                 if call.is_create() {
                     let offset = step.stack.nth_last(0)?;
                     let length = step.stack.nth_last(1)?;
-                    if length > Word::from(0x6000u64) {
+                    if length > Word::from(MAX_CODE_SIZE) {
                         return Ok(Some(ExecError::MaxCodeSizeExceeded));
                     } else if length > Word::zero()
                         && !call_ctx.memory.is_empty()
-                        && call_ctx.memory.0.get(offset.low_u64() as usize) == Some(&0xef)
+                        && call_ctx.memory.0.get(offset.low_u64() as usize)
+                            == Some(&INVALID_INIT_CODE_FIRST_BYTE)
                     {
                         return Ok(Some(ExecError::InvalidCreationCode));
-                    } else if Word::from(200u64) * length > Word::from(step.gas.0) {
+                    } else if Word::from(GasCost::CODE_DEPOSIT_BYTE_COST.as_u64()) * length
+                        > Word::from(step.gas.0 - step.gas_cost.0)
+                    {
                         return Ok(Some(ExecError::CodeStoreOutOfGas));
                     } else {
                         return Err(Error::UnexpectedExecStepError(
@@ -1318,11 +1377,15 @@ impl<'a> CircuitInputStateRef<'a> {
             }
 
             // Address collision
-            if matches!(step.op, OpcodeId::CREATE2) {
-                let address = self.create2_address(step)?;
+            if matches!(step.op, OpcodeId::CREATE | OpcodeId::CREATE2) {
+                let address = if step.op == OpcodeId::CREATE {
+                    self.create_address()?
+                } else {
+                    self.create2_address(step)?
+                };
 
-                let (found, _) = self.sdb.get_account(&address);
-                if found {
+                let (found, account) = self.sdb.get_account(&address);
+                if found && account.is_contract_address_collision() {
                     return Ok(Some(ExecError::ContractAddressCollision));
                 }
             }
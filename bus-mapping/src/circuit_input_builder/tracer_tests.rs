@@ -451,10 +451,14 @@ fn tracer_err_address_collision() {
             ..Account::zero()
         },
     );
-    builder
-        .builder
-        .sdb
-        .set_account(&create2_address, Account::zero());
+    // The contract created by the first CREATE2 has nonce 1 (EIP-161)
+    builder.builder.sdb.set_account(
+        &create2_address,
+        Account {
+            nonce: 1,
+            ..Account::zero()
+        },
+    );
     assert_eq!(
         builder.state_ref().get_step_err(step, next_step).unwrap(),
         Some(ExecError::ContractAddressCollision)
@@ -2,7 +2,10 @@
 
 use std::collections::BTreeMap;
 
-use eth_types::{evm_types::Memory, geth_types, GethExecTrace};
+use eth_types::{
+    evm_types::{Memory, MAX_BLOB_HASHES_PER_TX},
+    geth_types, GethExecTrace,
+};
 use ethers_core::utils::get_contract_address;
 
 use crate::{
@@ -12,6 +15,10 @@ use crate::{
 
 use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecStep};
 
+/// Maximum number of addresses and storage keys in the access list of a
+/// transaction (EIP-2930), which are all warmed in the BeginTx step.
+pub const MAX_ACCESS_LIST_ENTRIES: usize = 16;
+
 #[derive(Debug, Default)]
 /// Context of a [`Transaction`] which can mutate in an [`ExecStep`].
 pub struct TransactionContext {
@@ -256,10 +263,22 @@ impl Transaction {
             }
         };
 
+        let tx: geth_types::Transaction = eth_tx.try_into()?;
+        let (addresses_len, storage_keys_len) = tx.access_list_len();
+        let access_list_len = (addresses_len + storage_keys_len) as usize;
+        if access_list_len > MAX_ACCESS_LIST_ENTRIES {
+            return Err(Error::UnsupportedAccessListLen(access_list_len));
+        }
+        if tx.blob_versioned_hashes.len() > MAX_BLOB_HASHES_PER_TX {
+            return Err(Error::InvalidBlobVersionedHashesLen(
+                tx.blob_versioned_hashes.len(),
+            ));
+        }
+        let access_list_gas_cost = tx.access_list_gas_cost();
         Ok(Self {
-            tx: eth_tx.into(),
+            tx,
             invalid_tx: is_invalid,
-            access_list_gas_cost: 0,
+            access_list_gas_cost,
             calls: vec![call],
             steps: Vec::new(),
         })
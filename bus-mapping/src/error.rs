@@ -5,9 +5,13 @@ use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
 use ethers_providers::ProviderError;
 use std::error::Error as StdError;
 
-use crate::geth_errors::{
-    GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
-    GETH_ERR_STACK_UNDERFLOW,
+use crate::{
+    geth_errors::{
+        GETH_ERR_CODE_STORE_OUT_OF_GAS, GETH_ERR_CONTRACT_ADDRESS_COLLISION,
+        GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_INVALID_CODE, GETH_ERR_MAX_CODE_SIZE_EXCEEDED,
+        GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW, GETH_ERR_STACK_UNDERFLOW,
+    },
+    precompile::PrecompileCalls,
 };
 
 /// Error type for any BusMapping related failure.
@@ -40,6 +44,20 @@ pub enum Error {
     ExecutionError(ExecError),
     /// Internal Code error
     InternalError(&'static str),
+    /// Call to a precompiled contract with an input which can't be proven,
+    /// like MODEXP operands longer than
+    /// [`MODEXP_SIZE_LIMIT`](crate::precompile::MODEXP_SIZE_LIMIT).
+    UnsupportedPrecompileInput(PrecompileCalls),
+    /// Call to a precompiled contract which isn't supported by the circuits,
+    /// like the point evaluation precompile of Cancun (EIP-4844).
+    UnsupportedPrecompile(Address),
+    /// Transaction with an access list of more addresses and storage keys
+    /// than [`MAX_ACCESS_LIST_ENTRIES`](crate::circuit_input_builder::MAX_ACCESS_LIST_ENTRIES).
+    UnsupportedAccessListLen(usize),
+    /// Transaction with more blob versioned hashes than
+    /// [`MAX_BLOB_HASHES_PER_TX`](eth_types::evm_types::MAX_BLOB_HASHES_PER_TX)
+    /// (EIP-4844).
+    InvalidBlobVersionedHashesLen(usize),
 }
 
 impl From<eth_types::Error> for Error {
@@ -167,6 +185,14 @@ pub(crate) fn get_step_reported_error(op: &OpcodeId, error: &str) -> ExecError {
         ExecError::StackOverflow
     } else if error.starts_with(GETH_ERR_STACK_UNDERFLOW) {
         ExecError::StackUnderflow
+    } else if error == GETH_ERR_CONTRACT_ADDRESS_COLLISION {
+        ExecError::ContractAddressCollision
+    } else if error == GETH_ERR_INVALID_CODE {
+        ExecError::InvalidCreationCode
+    } else if error == GETH_ERR_MAX_CODE_SIZE_EXCEEDED {
+        ExecError::MaxCodeSizeExceeded
+    } else if error == GETH_ERR_CODE_STORE_OUT_OF_GAS {
+        ExecError::CodeStoreOutOfGas
     } else {
         panic!("Unknown GethExecStep.error: {}", error);
     }
@@ -1,12 +1,16 @@
 use super::TxExecSteps;
 use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
-    operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountStorageOp,
+        TxCreatedAccountOp, TxReceiptField, TxRefundOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
+    chain_spec::Hardfork,
+    evm_types::{gas_utils::init_code_gas_cost, GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
     evm_unimplemented, ToWord, Word,
 };
 use ethers_core::utils::get_contract_address;
@@ -64,8 +68,13 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         nonce_prev.into(),
     )?;
 
-    // Add caller and callee into access list
-    for address in [call.caller_address, call.address] {
+    // Add caller, callee and, since Shanghai, coinbase (EIP-3651) into access
+    // list
+    let mut warm_addresses = vec![call.caller_address, call.address];
+    if state.block.hardfork.is_enabled(Hardfork::Shanghai) {
+        warm_addresses.push(state.block.coinbase);
+    }
+    for address in warm_addresses {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
             &mut exec_step,
@@ -76,11 +85,46 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
+    // Warm the addresses and storage keys of the access list (EIP-2930)
+    let access_list = state.tx.tx.access_list.clone().unwrap_or_default();
+    for item in access_list.0 {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(item.address);
+        state.tx_accesslist_account_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            item.address,
+            true,
+            is_warm_prev,
+        )?;
+        for key in item.storage_keys {
+            let key = key.to_word();
+            let is_warm_prev = !state
+                .sdb
+                .add_account_storage_to_access_list((item.address, key));
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                TxAccessListAccountStorageOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: item.address,
+                    key,
+                    is_warm: true,
+                    is_warm_prev,
+                },
+            );
+        }
+    }
+
+    // Since Shanghai, the initialization code of a creation tx is also
+    // charged per word (EIP-3860). The access list is charged per address and
+    // storage key (EIP-2930).
     let intrinsic_gas_cost = if state.tx.is_create() {
         GasCost::CREATION_TX.as_u64()
+            + init_code_gas_cost(state.block.hardfork, state.tx.tx.call_data.len() as u64)
     } else {
         GasCost::TX.as_u64()
-    } + state.tx.tx.call_data_gas_cost();
+    } + state.tx.tx.call_data_gas_cost()
+        + state.tx.access_list_gas_cost;
 
     // Don't pay any fee or transfer any ETH for invalid transactions
     let (gas_cost, value, fee) = if state.tx.invalid_tx {
@@ -155,6 +199,15 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     ) {
         // 1. Creation transaction.
         (true, _, _) => {
+            state.push_op_reversible(
+                &mut exec_step,
+                TxCreatedAccountOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: call.address,
+                    is_created: true,
+                    is_created_prev: false,
+                },
+            )?;
             state.push_op_reversible(
                 &mut exec_step,
                 AccountOp {
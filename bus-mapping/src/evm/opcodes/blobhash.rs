@@ -0,0 +1,180 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::{GethExecStep, ToWord, Word};
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        // Stack read of the blob index
+        let stack_position = geth_step.stack.last_filled();
+        let index = geth_step.stack.last()?;
+        state.stack_read(&mut exec_step, stack_position, index)?;
+
+        // CallContext read of the TxId
+        let tx_id = state.tx_ctx.id();
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        );
+
+        // Out of range indexes resolve to zero
+        let blob_hashes = &state.tx.tx.blob_versioned_hashes;
+        let value = if index < Word::from(blob_hashes.len()) {
+            blob_hashes[index.as_usize()].to_word()
+        } else {
+            Word::zero()
+        };
+
+        // Stack write of the blob versioned hash
+        state.stack_write(&mut exec_step, stack_position, value)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blobhash_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        evm::OpcodeId,
+        mock::BlockData,
+        operation::{CallContextField, CallContextOp, StackOp, RW},
+        Error,
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{StackAddress, MAX_BLOB_HASHES_PER_TX},
+        geth_types::GethData,
+        Hash, ToWord, Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn versioned_hash(byte: u8) -> Hash {
+        let mut hash = Hash::repeat_byte(byte);
+        hash.0[0] = 0x01; // versioned hash prefix
+        hash
+    }
+
+    fn test_ok(index: u64, expected: Word) -> Result<(), Error> {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+        let blob_hashes = vec![versioned_hash(0x01), versioned_hash(0x02)];
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(blob_hashes.clone());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+
+        assert_eq!(
+            [0, 2]
+                .map(|idx| &builder.block.container.stack
+                    [step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress(1023usize), index.into())
+                ),
+                (RW::WRITE, &StackOp::new(1, StackAddress(1023usize), expected)),
+            ]
+        );
+
+        assert_eq!(
+            {
+                let operation =
+                    &builder.block.container.call_context[step.bus_mapping_instance[1].as_usize()];
+                (operation.rw(), operation.op())
+            },
+            (
+                RW::READ,
+                &CallContextOp {
+                    call_id,
+                    field: CallContextField::TxId,
+                    value: Word::one(),
+                }
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn blobhash_opcode_impl() -> Result<(), Error> {
+        test_ok(0, versioned_hash(0x01).to_word())?;
+        test_ok(1, versioned_hash(0x02).to_word())
+    }
+
+    #[test]
+    fn blobhash_opcode_out_of_range() -> Result<(), Error> {
+        test_ok(2, Word::zero())
+    }
+
+    #[test]
+    fn blobhash_too_many_blob_versioned_hashes() {
+        let blob_hashes = (0..=MAX_BLOB_HASHES_PER_TX as u8)
+            .map(versioned_hash)
+            .collect::<Vec<_>>();
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(blob_hashes);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidBlobVersionedHashesLen(len)) if len == MAX_BLOB_HASHES_PER_TX + 1
+        ));
+    }
+}
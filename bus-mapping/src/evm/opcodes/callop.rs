@@ -1,22 +1,19 @@
 use super::Opcode;
 use crate::{
-    circuit_input_builder::{
-        CallKind, CircuitInputStateRef, CodeSource, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
-    },
+    circuit_input_builder::{CallKind, CircuitInputStateRef, CodeSource, ExecStep},
     evm::opcodes::precompiles::gen_associated_ops as precompile_associated_ops,
-    operation::{AccountField, CallContextField, MemoryOp, TxAccessListAccountOp, RW},
-    precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
+    operation::{AccountField, CallContextField, TxAccessListAccountOp},
+    precompile::{execute_precompiled, is_precompiled, PrecompileCalls, POINT_EVALUATION_ADDRESS},
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
     evm_types::{
         gas_utils::{eip150_gas, memory_expansion_gas_cost},
-        GasCost,
+        GasCost, GAS_STIPEND_CALL_WITH_VALUE,
     },
     GethExecStep, ToWord, Word,
 };
-use std::cmp::min;
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the `OpcodeId::CALL`, `OpcodeId::CALLCODE`,
@@ -172,7 +169,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
         let code_address = call.code_address();
         let is_precompile = code_address
-            .map(|ref addr| is_precompiled(addr))
+            .map(|ref addr| is_precompiled(addr, state.block.hardfork))
             .unwrap_or(false);
         // TODO: What about transfer for CALLCODE?
         // Transfer value only for CALL opcode, is_precheck_ok = true.
@@ -225,19 +222,20 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         match (!is_precheck_ok, is_precompile, is_empty_code_hash) {
             // 1. Call to precompiled.
             (false, true, _) => {
-                assert!(call.is_success, "call to precompile should not fail");
-                let caller_ctx = state.caller_ctx()?;
-                let caller_memory = caller_ctx.memory.0.clone();
                 let code_address = code_address.unwrap();
-                let (result, contract_gas_cost) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        &caller_memory[args_offset..args_offset + args_length]
+                if code_address.0[19] == POINT_EVALUATION_ADDRESS {
+                    return Err(Error::UnsupportedPrecompile(code_address));
+                }
+                let callee_gas_left_with_stipend = callee_gas_left
+                    + if has_value {
+                        GAS_STIPEND_CALL_WITH_VALUE
                     } else {
-                        &[]
-                    },
-                    callee_gas_left,
-                );
+                        0
+                    };
+                let caller_memory = state.caller_ctx()?.memory.clone();
+                let input_bytes = caller_memory.read_chunk(args_offset.into(), args_length.into());
+                let (result, contract_gas_cost) =
+                    execute_precompiled(&code_address, &input_bytes, callee_gas_left_with_stipend);
 
                 log::trace!(
                     "precompile return data len {} gas {}",
@@ -245,15 +243,6 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     contract_gas_cost
                 );
 
-                let caller_ctx_mut = state.caller_ctx_mut()?;
-                caller_ctx_mut.return_data = result.clone();
-                let length = min(result.len(), ret_length);
-                if length > 0 {
-                    caller_ctx_mut.memory.extend_at_least(ret_offset + length);
-                    caller_ctx_mut.memory.0[ret_offset..ret_offset + length]
-                        .copy_from_slice(&result[..length]);
-                }
-
                 for (field, value) in [
                     (
                         CallContextField::IsSuccess,
@@ -284,128 +273,48 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     state.call_context_write(&mut exec_step, call.call_id, field, value);
                 }
 
-                // return while restoring some of caller's context.
+                // save caller's context, which is restored at the end of the
+                // precompile step.
                 for (field, value) in [
-                    (CallContextField::LastCalleeId, call.call_id.into()),
-                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
                     (
-                        CallContextField::LastCalleeReturnDataLength,
-                        result.len().into(),
+                        CallContextField::ProgramCounter,
+                        (geth_step.pc.0 + 1).into(),
+                    ),
+                    (
+                        CallContextField::StackPointer,
+                        (geth_step.stack.stack_pointer().0 + N_ARGS - 1).into(),
+                    ),
+                    (
+                        CallContextField::GasLeft,
+                        (geth_step.gas.0 - gas_cost - callee_gas_left).into(),
+                    ),
+                    (CallContextField::MemorySize, next_memory_word_size.into()),
+                    (
+                        CallContextField::ReversibleWriteCounter,
+                        (exec_step.reversible_write_counter + 1).into(),
                     ),
                 ] {
                     state.call_context_write(&mut exec_step, current_call.call_id, field, value);
                 }
 
-                // insert a copy event (input) for this step
-                let rw_counter_start = state.block_ctx.rwc;
-                if call.is_success && call.call_data_length > 0 {
-                    let bytes: Vec<(u8, bool)> = caller_memory
-                        .iter()
-                        .skip(call.call_data_offset as usize)
-                        .take(call.call_data_length as usize)
-                        .map(|b| (*b, false))
-                        .collect();
-                    for (i, &(byte, _is_code)) in bytes.iter().enumerate() {
-                        // push caller memory read
-                        state.push_op(
-                            &mut exec_step,
-                            RW::READ,
-                            MemoryOp::new(
-                                call.caller_id,
-                                (call.call_data_offset + i as u64).into(),
-                                byte,
-                            ),
-                        );
-                        // push callee memory write
-                        state.push_op(
-                            &mut exec_step,
-                            RW::WRITE,
-                            MemoryOp::new(call.call_id, i.into(), byte),
-                        );
-                    }
-                    state.push_copy(
-                        &mut exec_step,
-                        CopyEvent {
-                            src_id: NumberOrHash::Number(call.caller_id),
-                            src_type: CopyDataType::Memory,
-                            src_addr: call.call_data_offset,
-                            src_addr_end: call.call_data_offset + call.call_data_length,
-                            dst_id: NumberOrHash::Number(call.call_id),
-                            dst_type: CopyDataType::Memory,
-                            dst_addr: 0,
-                            log_id: None,
-                            rw_counter_start,
-                            bytes,
-                        },
-                    );
-                }
-
-                // insert another copy event (output) for this step.
-                let rw_counter_start = state.block_ctx.rwc;
-                if call.is_success && call.call_data_length > 0 && length > 0 {
-                    let bytes: Vec<(u8, bool)> = caller_memory
-                        .iter()
-                        .skip(call.call_data_offset as usize)
-                        .take(length)
-                        .map(|b| (*b, false))
-                        .collect();
-                    for (i, &(byte, _is_code)) in bytes.iter().enumerate() {
-                        // push callee memory read
-                        state.push_op(
-                            &mut exec_step,
-                            RW::READ,
-                            MemoryOp::new(call.call_id, i.into(), byte),
-                        );
-                        // push caller memory write
-                        state.push_op(
-                            &mut exec_step,
-                            RW::WRITE,
-                            MemoryOp::new(
-                                call.caller_id,
-                                (call.return_data_offset + i as u64).into(),
-                                byte,
-                            ),
-                        );
-                    }
-                    state.push_copy(
-                        &mut exec_step,
-                        CopyEvent {
-                            src_id: NumberOrHash::Number(call.call_id),
-                            src_type: CopyDataType::Memory,
-                            src_addr: 0,
-                            src_addr_end: length as u64,
-                            dst_id: NumberOrHash::Number(call.caller_id),
-                            dst_type: CopyDataType::Memory,
-                            dst_addr: call.return_data_offset,
-                            log_id: None,
-                            rw_counter_start,
-                            bytes,
-                        },
-                    );
-                }
-
-                // TODO: when more precompiles are supported and each have their own different
-                // behaviour, we can separate out the logic specified here.
                 let precompile_call: PrecompileCalls = code_address.0[19].into();
-                let precompile_step = precompile_associated_ops(
+                let mut precompile_step = precompile_associated_ops(
                     state,
                     geth_steps[1].clone(),
                     call.clone(),
                     precompile_call,
+                    &input_bytes,
+                    &result,
+                    callee_gas_left_with_stipend,
+                    contract_gas_cost,
                 )?;
 
-                state.handle_return(&mut exec_step, geth_steps, false)?;
-
-                let real_cost = geth_steps[0].gas.0 - geth_steps[1].gas.0;
-                if real_cost != exec_step.gas_cost.0 {
-                    log::warn!(
-                        "precompile gas fixed from {} to {}, step {:?}",
-                        exec_step.gas_cost.0,
-                        real_cost,
-                        geth_steps[0]
-                    );
+                // The value transfer of this step is reverted at the end of the
+                // failed precompile step, before both steps are pushed.
+                if !call.is_success {
+                    state.handle_reversion(&mut [&mut exec_step, &mut precompile_step]);
                 }
-                exec_step.gas_cost = GasCost(real_cost);
+
                 Ok(vec![exec_step, precompile_step])
             }
             // 2. Call to account with empty code.
@@ -898,4 +807,45 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_point_evaluation_call() {
+        use crate::{mock::BlockData, precompile::POINT_EVALUATION_ADDRESS, Error};
+        use eth_types::{
+            chain_spec::{ChainSpec, Hardfork},
+            geth_types::GethData,
+        };
+        use mock::{
+            test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+            TestContext,
+        };
+
+        let code = PrecompileCallArgs {
+            address: Word::from(POINT_EVALUATION_ADDRESS),
+            ..Default::default()
+        }
+        .with_call_op(OpcodeId::STATICCALL);
+
+        for hardfork in [Hardfork::Shanghai, Hardfork::Cancun] {
+            let block: GethData = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(code.clone()),
+                tx_from_1_to_0,
+                |block, _tx| block.chain_spec(ChainSpec::with_hardfork(hardfork)),
+            )
+            .unwrap()
+            .into();
+
+            let mut builder =
+                BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+            let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+            // Before Cancun the address is an account without code.
+            if hardfork.is_enabled(Hardfork::Cancun) {
+                assert!(matches!(result, Err(Error::UnsupportedPrecompile(address))
+                        if address.0[19] == POINT_EVALUATION_ADDRESS));
+            } else {
+                assert!(result.is_ok());
+            }
+        }
+    }
 }
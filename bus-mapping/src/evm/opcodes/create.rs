@@ -1,22 +1,37 @@
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    error::ExecError,
     evm::Opcode,
-    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    operation::{
+        AccountField, AccountOp, CallContextField, MemoryOp, TxAccessListAccountOp,
+        TxCreatedAccountOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{evm_types::gas_utils::memory_expansion_gas_cost, GethExecStep, ToWord, Word};
+use eth_types::{
+    evm_types::{
+        gas_utils::{init_code_gas_cost, memory_expansion_gas_cost},
+        GasCost,
+    },
+    Bytecode, GethExecStep, ToBigEndian, ToWord, Word,
+};
+use ethers_core::utils::rlp;
 
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the `OpcodeId::CREATE` and `OpcodeId::CREATE2`.
 #[derive(Debug, Copy, Clone)]
-pub struct DummyCreate<const IS_CREATE2: bool>;
+pub struct Create<const IS_CREATE2: bool>;
 
-impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
+impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
     fn gen_associated_ops(
         state: &mut CircuitInputStateRef,
         geth_steps: &[GethExecStep],
     ) -> Result<Vec<ExecStep>, Error> {
-        // TODO: replace dummy create here
         let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
 
         let offset = geth_step.stack.nth_last(1)?.as_usize();
         let length = geth_step.stack.nth_last(2)?.as_usize();
@@ -30,7 +45,10 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
         }
         let next_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
 
-        let mut exec_step = state.new_step(geth_step)?;
+        // The callee's call_id is the rw_counter of this step, so the call has
+        // to be parsed before any operation is pushed.
+        let callee = state.parse_call(geth_step)?;
+        let caller = state.call()?.clone();
 
         let n_pop = if IS_CREATE2 { 4 } else { 3 };
         for i in 0..n_pop {
@@ -41,18 +59,11 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
             )?;
         }
 
-        let address = if IS_CREATE2 {
-            state.create2_address(&geth_steps[0])?
-        } else {
-            state.create_address()?
-        };
-
-        // TODO: rename this to initialization call?
-        let call = state.parse_call(geth_step)?;
+        let address = callee.address;
         state.stack_write(
             &mut exec_step,
             geth_step.stack.nth_last_filled(n_pop - 1),
-            if call.is_success {
+            if callee.is_success {
                 address.to_word()
             } else {
                 Word::zero()
@@ -60,127 +71,450 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
         )?;
 
         let tx_id = state.tx_ctx.id();
-        let current_call = state.call()?.clone();
-
-        // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
-        // > When a CREATE or CREATE2 opcode is called,
-        // > immediately (i.e. before checks are done to determine
-        // > whether or not the address is unclaimed)
-        // > add the address being created to accessed_addresses,
-        // > but gas costs of CREATE and CREATE2 are unchanged
-        let is_warm = state.sdb.check_account_in_access_list(&address);
-        state.push_op_reversible(
+        for (field, value) in [
+            (CallContextField::TxId, tx_id.into()),
+            // NOTE: For `RwCounterEndOfReversion` we use the `0` value as a
+            // placeholder, and later set the proper value in
+            // `CircuitInputBuilder::set_value_ops_call_context_rwc_eor`
+            (CallContextField::RwCounterEndOfReversion, 0.into()),
+            (
+                CallContextField::IsPersistent,
+                caller.is_persistent.to_word(),
+            ),
+            (CallContextField::IsStatic, caller.is_static.to_word()),
+            (CallContextField::Depth, caller.depth.into()),
+            (CallContextField::CalleeAddress, caller.address.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, caller.call_id, field, value);
+        }
+
+        // Read the caller balance to check if the endowment can be paid.
+        let caller_balance = state.sdb.get_account(&caller.address).1.balance;
+        state.account_read(
             &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id: state.tx_ctx.id(),
+            caller.address,
+            AccountField::Balance,
+            caller_balance,
+        );
+
+        // Precheck is OK when depth is in range and caller balance is sufficient
+        let is_precheck_ok = geth_step.depth < 1025 && caller_balance >= callee.value;
+        // The creation fails without entering the callee when there is already
+        // a contract at the new address.
+        let callee_account = state.sdb.get_account(&address).1.clone();
+        let is_address_collision = is_precheck_ok && callee_account.is_contract_address_collision();
+        if is_address_collision {
+            exec_step.error = Some(ExecError::ContractAddressCollision);
+        }
+
+        let initialization_code = if is_precheck_ok {
+            // Increase caller's nonce
+            let caller_nonce = state.sdb.get_nonce(&caller.address);
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: caller.address,
+                    field: AccountField::Nonce,
+                    value: (caller_nonce + 1).into(),
+                    value_prev: caller_nonce.into(),
+                },
+            )?;
+
+            // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
+            // > When a CREATE or CREATE2 opcode is called,
+            // > immediately (i.e. before checks are done to determine
+            // > whether or not the address is unclaimed)
+            // > add the address being created to accessed_addresses,
+            // > but gas costs of CREATE and CREATE2 are unchanged
+            let is_warm = state.sdb.check_account_in_access_list(&address);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address,
+                    is_warm: true,
+                    is_warm_prev: is_warm,
+                },
+            )?;
+
+            let initialization_code = state.code(callee.code_hash)?;
+
+            // The preimage of the contract address is looked up in the keccak
+            // table by the CreateGadget.
+            state.block.sha3_inputs.push(if IS_CREATE2 {
+                let salt = geth_step.stack.nth_last(3)?;
+                let mut preimage = vec![0xff];
+                preimage.extend_from_slice(caller.address.as_bytes());
+                preimage.extend_from_slice(&salt.to_be_bytes());
+                preimage.extend_from_slice(callee.code_hash.as_bytes());
+                preimage
+            } else {
+                let mut stream = rlp::RlpStream::new();
+                stream.begin_list(2);
+                stream.append(&caller.address);
+                stream.append(&Word::from(caller_nonce));
+                stream.out().to_vec()
+            });
+
+            if is_address_collision {
+                // Prove the collision by the nonce and code hash of the callee.
+                state.account_read(
+                    &mut exec_step,
+                    address,
+                    AccountField::Nonce,
+                    callee_account.nonce.into(),
+                );
+                state.account_read(
+                    &mut exec_step,
+                    address,
+                    AccountField::CodeHash,
+                    callee_account.code_hash.to_word(),
+                );
+                // The new address of CREATE2 depends on the hash of the
+                // initialization code.
+                if IS_CREATE2 && !initialization_code.is_empty() {
+                    handle_copy(
+                        state,
+                        &mut exec_step,
+                        caller.call_id,
+                        offset,
+                        &initialization_code,
+                    );
+                }
+                None
+            } else {
+                Some(initialization_code)
+            }
+        } else {
+            None
+        };
+
+        // Switch to callee's call context
+        state.push_call(callee.clone());
+
+        if let Some(initialization_code) = initialization_code {
+            for (field, value) in [
+                (CallContextField::RwCounterEndOfReversion, 0.into()),
+                (
+                    CallContextField::IsPersistent,
+                    callee.is_persistent.to_word(),
+                ),
+            ] {
+                state.call_context_write(&mut exec_step, callee.call_id, field, value);
+            }
+
+            // Create the callee account and transfer the endowment
+            state.transfer(
+                &mut exec_step,
+                caller.address,
                 address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
+                true,
+                true,
+                callee.value,
+            )?;
+            state.push_op_reversible(
+                &mut exec_step,
+                TxCreatedAccountOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: address,
+                    is_created: true,
+                    is_created_prev: false,
+                },
+            )?;
 
-        // Increase caller's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.caller_address);
-        state.push_op_reversible(
-            &mut exec_step,
-            AccountOp {
-                address: call.caller_address,
-                field: AccountField::Nonce,
-                value: (nonce_prev + 1).into(),
-                value_prev: nonce_prev.into(),
-            },
-        )?;
+            // Increase callee's nonce (EIP-161)
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address,
+                    field: AccountField::Nonce,
+                    value: 1.into(),
+                    value_prev: 0.into(),
+                },
+            )?;
 
-        // Add callee into access list
-        let is_warm = state.sdb.check_account_in_access_list(&call.address);
-        state.push_op_reversible(
-            &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id,
-                address: call.address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
+            if !initialization_code.is_empty() {
+                handle_copy(
+                    state,
+                    &mut exec_step,
+                    caller.call_id,
+                    offset,
+                    &initialization_code,
+                );
 
-        state.push_call(call.clone());
+                let memory_expansion_gas_cost =
+                    memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+                // CREATE2 additionally pays for hashing the initialization code.
+                let hash_gas_cost = if IS_CREATE2 {
+                    GasCost::COPY_SHA3.as_u64() * ((length as u64 + 31) / 32)
+                } else {
+                    0
+                };
+                let gas_cost = GasCost::CREATE.as_u64()
+                    + memory_expansion_gas_cost
+                    + init_code_gas_cost(state.block.hardfork, length as u64)
+                    + hash_gas_cost;
 
-        state.transfer(
-            &mut exec_step,
-            call.caller_address,
-            call.address,
-            true,
-            true,
-            call.value,
-        )?;
+                // EIP-150: all but one 64th of the caller's gas is sent to the
+                // callee.
+                let gas_available = geth_step.gas.0 - gas_cost;
+                let callee_gas_left = gas_available - gas_available / 64;
 
-        // Increase callee's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.address);
-        debug_assert!(nonce_prev == 0);
-        state.push_op_reversible(
-            &mut exec_step,
-            AccountOp {
-                address: call.address,
-                field: AccountField::Nonce,
-                value: 1.into(),
-                value_prev: 0.into(),
-            },
-        )?;
+                // Save caller's call state
+                for (field, value) in [
+                    (
+                        CallContextField::ProgramCounter,
+                        (geth_step.pc.0 + 1).into(),
+                    ),
+                    (
+                        CallContextField::StackPointer,
+                        geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
+                    ),
+                    (
+                        CallContextField::GasLeft,
+                        (gas_available - callee_gas_left).into(),
+                    ),
+                    (CallContextField::MemorySize, next_memory_word_size.into()),
+                    (
+                        CallContextField::ReversibleWriteCounter,
+                        // The caller nonce and the access list writes are
+                        // done before switching to the callee.
+                        (exec_step.reversible_write_counter + 2).into(),
+                    ),
+                ] {
+                    state.call_context_write(&mut exec_step, caller.call_id, field, value);
+                }
 
-        let memory_expansion_gas_cost =
-            memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+                // Setup callee's context
+                for (field, value) in [
+                    (CallContextField::CallerId, caller.call_id.into()),
+                    (CallContextField::TxId, tx_id.into()),
+                    (CallContextField::Depth, callee.depth.into()),
+                    (CallContextField::CallerAddress, caller.address.to_word()),
+                    (CallContextField::CalleeAddress, address.to_word()),
+                    (CallContextField::CallDataOffset, 0.into()),
+                    (CallContextField::CallDataLength, 0.into()),
+                    (CallContextField::ReturnDataOffset, 0.into()),
+                    (CallContextField::ReturnDataLength, 0.into()),
+                    (CallContextField::Value, callee.value),
+                    (CallContextField::IsSuccess, callee.is_success.to_word()),
+                    (CallContextField::IsStatic, 0.into()),
+                    (CallContextField::LastCalleeId, 0.into()),
+                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                    (CallContextField::LastCalleeReturnDataLength, 0.into()),
+                    (CallContextField::IsRoot, 0.into()),
+                    (CallContextField::IsCreate, 1.into()),
+                    (CallContextField::CodeHash, callee.code_hash.to_word()),
+                ] {
+                    state.call_context_write(&mut exec_step, callee.call_id, field, value);
+                }
 
-        // EIP-150: all but one 64th of the caller's gas is sent to the callee.
-        let caller_gas_left =
-            (geth_step.gas.0 - geth_step.gas_cost.0 - memory_expansion_gas_cost) / 64;
+                return Ok(vec![exec_step]);
+            }
+        }
 
+        // The callee is not entered when the precheck fails, the address
+        // collides or the initialization code is empty, so we return to the
+        // caller immediately.
         for (field, value) in [
-            (
-                CallContextField::ProgramCounter,
-                (geth_step.pc.0 + 1).into(),
-            ),
-            (
-                CallContextField::StackPointer,
-                geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
-            ),
-            (CallContextField::GasLeft, caller_gas_left.into()),
-            (CallContextField::MemorySize, next_memory_word_size.into()),
-            (
-                CallContextField::ReversibleWriteCounter,
-                // +3 is because we do some transfers after pushing the call. can be just push the
-                // call later?
-                (exec_step.reversible_write_counter + 3).into(),
-            ),
+            (CallContextField::LastCalleeId, 0.into()),
+            (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+            (CallContextField::LastCalleeReturnDataLength, 0.into()),
         ] {
-            state.call_context_write(&mut exec_step, current_call.call_id, field, value);
+            state.call_context_write(&mut exec_step, caller.call_id, field, value);
         }
+        state.handle_return(&mut exec_step, geth_steps, false)?;
 
-        for (field, value) in [
-            (CallContextField::CallerId, current_call.call_id.into()),
-            (CallContextField::IsSuccess, call.is_success.to_word()),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-            (CallContextField::TxId, state.tx_ctx.id().into()),
-            (
-                CallContextField::CallerAddress,
-                current_call.address.to_word(),
-            ),
-            (CallContextField::CalleeAddress, call.address.to_word()),
-            (
-                CallContextField::RwCounterEndOfReversion,
-                call.rw_counter_end_of_reversion.to_word(),
-            ),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-        ] {
-            state.call_context_write(&mut exec_step, call.call_id, field, value);
+        Ok(vec![exec_step])
+    }
+}
+
+/// Push the memory reads of the initialization code and the copy event that
+/// proves it is the bytecode of the callee.
+fn handle_copy(
+    state: &mut CircuitInputStateRef,
+    step: &mut ExecStep,
+    caller_id: usize,
+    offset: usize,
+    initialization_code: &[u8],
+) {
+    let code_hash = CodeDB::hash(initialization_code);
+    let bytes: Vec<_> = Bytecode::from(initialization_code.to_vec())
+        .code
+        .iter()
+        .map(|element| (element.value, element.is_code))
+        .collect();
+
+    let rw_counter_start = state.block_ctx.rwc;
+    for (i, (byte, _)) in bytes.iter().enumerate() {
+        state.push_op(
+            step,
+            RW::READ,
+            MemoryOp::new(caller_id, (offset + i).into(), *byte),
+        );
+    }
+
+    state.push_copy(
+        step,
+        CopyEvent {
+            rw_counter_start,
+            src_type: CopyDataType::Memory,
+            src_id: NumberOrHash::Number(caller_id),
+            src_addr: offset.try_into().unwrap(),
+            src_addr_end: (offset + initialization_code.len()).try_into().unwrap(),
+            dst_type: CopyDataType::Bytecode,
+            dst_id: NumberOrHash::Hash(code_hash),
+            dst_addr: 0,
+            log_id: None,
+            bytes,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{AccountField, AccountOp, Target, RW},
+    };
+    use eth_types::{
+        bytecode, evm_types::OpcodeId, geth_types::GethData, word, Bytecode, ToBigEndian, ToWord,
+        Word,
+    };
+    use ethers_core::utils::{get_contract_address, get_create2_address};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS,
+    };
+    use pretty_assertions::assert_eq;
+
+    // RETURN a deployed code of `[0x60; 5]`
+    fn initialization_bytecode() -> Bytecode {
+        bytecode! {
+            PUSH5(word!("0x6060606060"))
+            PUSH1(0)
+            MSTORE
+            PUSH1(5)
+            PUSH1(27)
+            RETURN
         }
+    }
 
-        if call.code_hash == CodeDB::empty_code_hash() {
-            // 1. Create with empty initcode.
-            state.handle_return(&mut exec_step, geth_steps, false)?;
-            Ok(vec![exec_step])
-        } else {
-            // 2. Create with non-empty initcode.
-            Ok(vec![exec_step])
+    fn creator_bytecode(initialization_code: &[u8], is_create2: bool) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(initialization_code))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
         }
+        code.append(&bytecode! {
+            PUSH1(initialization_code.len()) // length
+            PUSH1(32 - initialization_code.len()) // offset
+            PUSH2(23414) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code.append(&bytecode! {STOP});
+        code
+    }
+
+    fn test_ok(is_create2: bool) {
+        let initialization_code = initialization_bytecode().code();
+        let code = creator_bytecode(&initialization_code, is_create2);
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let opcode = if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        };
+        let tx = &builder.block.txs()[0];
+        let step = tx
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(opcode))
+            .unwrap();
+
+        let creator = MOCK_ACCOUNTS[0];
+        let caller_nonce = block.accounts[0].nonce.as_u64();
+        let address = if is_create2 {
+            get_create2_address(
+                creator,
+                Word::from(45).to_be_bytes(),
+                initialization_code.clone(),
+            )
+        } else {
+            get_contract_address(creator, caller_nonce)
+        };
+        assert_eq!(tx.calls()[1].address, address);
+
+        let n_pop = if is_create2 { 4 } else { 3 };
+        let container = &builder.block.container;
+        let push = &container.stack[step.bus_mapping_instance[n_pop].as_usize()];
+        assert_eq!(push.rw(), RW::WRITE);
+        assert_eq!(push.op().value(), &address.to_word());
+
+        // The caller nonce is bumped and the callee is created with nonce 1.
+        let account_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| &container.account[op_ref.as_usize()])
+            .collect::<Vec<_>>();
+        assert!(account_ops.iter().any(|op| op.op()
+            == &AccountOp {
+                address: creator,
+                field: AccountField::Nonce,
+                value: (caller_nonce + 1).into(),
+                value_prev: caller_nonce.into(),
+            }));
+        assert!(account_ops.iter().any(|op| op.op()
+            == &AccountOp {
+                address,
+                field: AccountField::Nonce,
+                value: Word::one(),
+                value_prev: Word::zero(),
+            }));
+
+        // The initialization code is copied from memory to the bytecode table.
+        let copy_event = builder.block.copy_events.first().unwrap();
+        assert_eq!(copy_event.bytes.len(), initialization_code.len());
+        assert_eq!(
+            copy_event
+                .bytes
+                .iter()
+                .map(|(byte, _)| *byte)
+                .collect::<Vec<_>>(),
+            initialization_code
+        );
+    }
+
+    #[test]
+    fn create_ok() {
+        test_ok(false);
+    }
+
+    #[test]
+    fn create2_ok() {
+        test_ok(true);
     }
 }
@@ -0,0 +1,270 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::ExecError,
+    operation::{AccountField, CallContextField, TxAccessListAccountOp},
+    Error,
+};
+use eth_types::{
+    evm_types::{
+        gas_utils::memory_expansion_gas_cost, GasCost, OpcodeId, GAS_STIPEND_CALL_WITH_VALUE,
+    },
+    GethExecStep, ToAddress, ToWord, Word,
+};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`ExecError::Depth`] of the *CALL* and CREATE*
+/// opcodes, where the callee is not entered since the call depth limit is
+/// reached.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ErrorDepth;
+
+impl Opcode for ErrorDepth {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let (n_pop, is_call) = match geth_step.op {
+            OpcodeId::CALL | OpcodeId::CALLCODE => (7, true),
+            OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => (6, true),
+            OpcodeId::CREATE => (3, false),
+            OpcodeId::CREATE2 => (4, false),
+            op => unreachable!("{op} should not happen in ErrorDepth"),
+        };
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::Depth);
+
+        let curr_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
+        if is_call {
+            let [args_offset, args_length, ret_offset, ret_length] =
+                [n_pop - 4, n_pop - 3, n_pop - 2, n_pop - 1]
+                    .map(|i| geth_step.stack.nth_last(i).map(|word| word.as_usize()));
+            state.call_expand_memory(args_offset?, args_length?, ret_offset?, ret_length?)?;
+        } else {
+            let offset = geth_step.stack.nth_last(1)?.as_usize();
+            let length = geth_step.stack.nth_last(2)?.as_usize();
+            if length != 0 {
+                state
+                    .call_ctx_mut()?
+                    .memory
+                    .extend_at_least(offset + length);
+            }
+        }
+        let next_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
+        let memory_expansion_gas_cost =
+            memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+
+        // The callee's call_id is the rw_counter of this step, so the call has
+        // to be parsed before any operation is pushed.
+        let callee = state.parse_call(geth_step)?;
+        let caller = state.call()?.clone();
+
+        let tx_id = state.tx_ctx.id();
+        for (field, value) in [
+            (CallContextField::TxId, tx_id.into()),
+            (CallContextField::Depth, caller.depth.into()),
+            // NOTE: For `RwCounterEndOfReversion` we use the `0` value as a
+            // placeholder, and later set the proper value in
+            // `CircuitInputBuilder::set_value_ops_call_context_rwc_eor`
+            (CallContextField::RwCounterEndOfReversion, 0.into()),
+            (
+                CallContextField::IsPersistent,
+                caller.is_persistent.to_word(),
+            ),
+            (CallContextField::IsStatic, caller.is_static.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, caller.call_id, field, value);
+        }
+
+        for i in 0..n_pop {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+        state.stack_write(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(n_pop - 1),
+            Word::zero(),
+        )?;
+
+        let gas_cost = if is_call {
+            let callee_address = geth_step.stack.nth_last(1)?.to_address();
+            let (_, callee_account) = state.sdb.get_account(&callee_address);
+            let callee_exists = !callee_account.is_empty();
+            state.account_read(
+                &mut exec_step,
+                callee_address,
+                AccountField::CodeHash,
+                if callee_exists {
+                    callee_account.code_hash.to_word()
+                } else {
+                    Word::zero()
+                },
+            );
+
+            // The callee is added to the access list before the call depth is
+            // checked.
+            let is_warm = state.sdb.check_account_in_access_list(&callee_address);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address: callee_address,
+                    is_warm: true,
+                    is_warm_prev: is_warm,
+                },
+            )?;
+
+            let has_value = !callee.value.is_zero() && !callee.is_delegatecall();
+            let access_gas_cost = if is_warm {
+                GasCost::WARM_ACCESS.as_u64()
+            } else {
+                GasCost::COLD_ACCOUNT_ACCESS.as_u64()
+            };
+            let value_gas_cost = if has_value {
+                GasCost::CALL_WITH_VALUE.as_u64()
+                    + if geth_step.op == OpcodeId::CALL && !callee_exists {
+                        GasCost::NEW_ACCOUNT.as_u64()
+                    } else {
+                        0
+                    }
+            } else {
+                0
+            };
+            // The gas passed to the callee is returned, including the stipend
+            // of a call with value.
+            access_gas_cost + value_gas_cost + memory_expansion_gas_cost
+                - if has_value {
+                    GAS_STIPEND_CALL_WITH_VALUE
+                } else {
+                    0
+                }
+        } else {
+            let length = geth_step.stack.nth_last(2)?.as_u64();
+            // CREATE2 additionally pays for hashing the initialization code.
+            let hash_gas_cost = if geth_step.op == OpcodeId::CREATE2 {
+                GasCost::COPY_SHA3.as_u64() * ((length + 31) / 32)
+            } else {
+                0
+            };
+            // All the gas passed to the callee is returned.
+            GasCost::CREATE.as_u64() + memory_expansion_gas_cost + hash_gas_cost
+        };
+        exec_step.gas_cost = GasCost(gas_cost);
+
+        state.push_call(callee);
+        for (field, value) in [
+            (CallContextField::LastCalleeId, 0.into()),
+            (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+            (CallContextField::LastCalleeReturnDataLength, 0.into()),
+        ] {
+            state.call_context_write(&mut exec_step, caller.call_id, field, value);
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, false)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuit_input_builder::ExecState, mock::BlockData, operation::RW};
+    use eth_types::{address, bytecode, geth_types::GethData, Bytecode};
+    use mock::TestContext;
+
+    // A contract which calls itself with all the gas until the call depth
+    // limit is reached, then runs `bottom_code` in the deepest call.
+    fn recursive_bytecode(bottom_code: Bytecode) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            ADDRESS
+            GAS
+            CALL
+            ISZERO
+            PUSH1(0x12) // bottom
+            JUMPI
+            STOP
+            JUMPDEST // bottom, reached when the CALL fails
+        };
+        code.append(&bottom_code);
+        code
+    }
+
+    fn depth_error_opcodes(bottom_code: Bytecode) -> Vec<OpcodeId> {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1]
+                    .address(address!("0x00000000000000000000000000000000000000fe"))
+                    .code(recursive_bytecode(bottom_code));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(10u64.pow(15)));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let container = &builder.block.container;
+        builder.block.txs()[0]
+            .steps()
+            .iter()
+            .filter(|step| step.error == Some(ExecError::Depth))
+            .map(|step| {
+                let operation = &container.call_context[step.bus_mapping_instance[1].as_usize()];
+                assert_eq!(operation.rw(), RW::READ);
+                assert_eq!(operation.op().field, CallContextField::Depth);
+                assert_eq!(operation.op().value, Word::from(1025));
+
+                match step.exec_state {
+                    ExecState::Op(op) => op,
+                    _ => unreachable!(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_depth_error_call() {
+        assert_eq!(
+            depth_error_opcodes(Bytecode::default()),
+            vec![OpcodeId::CALL]
+        );
+    }
+
+    #[test]
+    fn test_depth_error_create() {
+        let bottom_code = bytecode! {
+            PUSH1(0) // length
+            PUSH1(0) // offset
+            PUSH1(0) // value
+            CREATE
+            STOP
+        };
+        assert_eq!(
+            depth_error_opcodes(bottom_code),
+            vec![OpcodeId::CALL, OpcodeId::CREATE]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::ExecError,
+    evm::{Opcode, OpcodeId},
+    Error,
+};
+use eth_types::{evm_types::INVALID_INIT_CODE_FIRST_BYTE, GethExecStep};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`ExecError::InvalidCreationCode`] error of RETURN in
+/// a creation call.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ErrorCreationCode;
+
+impl Opcode for ErrorCreationCode {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let next_step = geth_steps.get(1);
+        exec_step.error = state.get_step_err(geth_step, next_step)?;
+
+        assert_eq!(exec_step.error, Some(ExecError::InvalidCreationCode));
+        assert_eq!(geth_step.op, OpcodeId::RETURN);
+
+        let offset = geth_step.stack.nth_last(0)?;
+        let length = geth_step.stack.nth_last(1)?;
+        state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(0), offset)?;
+        state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(1), length)?;
+
+        // Read the first byte of the deployed code, which must be the invalid
+        // one (EIP-3541).
+        let offset = offset.as_usize();
+        let first_byte = state.call_ctx()?.memory.0[offset];
+        assert_eq!(first_byte, INVALID_INIT_CODE_FIRST_BYTE);
+        state.memory_read(&mut exec_step, offset.into(), first_byte)?;
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    evm::Opcode,
+    operation::{CallContextField, TxAccessListAccountOp, RW},
+    Error,
+};
+use eth_types::{evm_types::OpcodeId, GethExecStep, ToAddress};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::AccountAccess`](crate::error::OogError::AccountAccess).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OOGAccountAccess;
+
+impl Opcode for OOGAccountAccess {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert!([
+            OpcodeId::BALANCE,
+            OpcodeId::EXTCODESIZE,
+            OpcodeId::EXTCODEHASH
+        ]
+        .contains(&geth_step.op));
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::AccountAccess));
+
+        let address_word = geth_step.stack.last()?;
+        state.stack_read(&mut exec_step, geth_step.stack.last_filled(), address_word)?;
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        );
+
+        // According to EIP-2929, the gas cost is different for cold and warm
+        // accounts.
+        let address = address_word.to_address();
+        let is_warm = state.sdb.check_account_in_access_list(&address);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address,
+                is_warm,
+                is_warm_prev: is_warm,
+            },
+        );
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
@@ -0,0 +1,49 @@
+use super::{Opcode, OpcodeId};
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::StaticMemoryExpansion`](crate::error::OogError::StaticMemoryExpansion)
+/// and
+/// [`OogError::DynamicMemoryExpansion`](crate::error::OogError::DynamicMemoryExpansion).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OOGMemoryExpansion;
+
+impl Opcode for OOGMemoryExpansion {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let (oog_error, n_pop) = match geth_step.op {
+            // Pop offset
+            OpcodeId::MLOAD | OpcodeId::MSTORE | OpcodeId::MSTORE8 => {
+                (OogError::StaticMemoryExpansion, 1)
+            }
+            // Pop offset and length
+            OpcodeId::RETURN | OpcodeId::REVERT => (OogError::DynamicMemoryExpansion, 2),
+            // Pop value, offset and length
+            OpcodeId::CREATE => (OogError::DynamicMemoryExpansion, 3),
+            op => unreachable!("{op} should not happen in OOGMemoryExpansion"),
+        };
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(oog_error));
+
+        for i in 0..n_pop {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
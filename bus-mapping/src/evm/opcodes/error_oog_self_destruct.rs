@@ -0,0 +1,83 @@
+use super::{Opcode, OpcodeId};
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    operation::{AccountField, CallContextField, TxAccessListAccountOp, RW},
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, H256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::SelfDestruct`](crate::error::OogError::SelfDestruct).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OOGSelfDestruct;
+
+impl Opcode for OOGSelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert_eq!(geth_step.op, OpcodeId::SELFDESTRUCT);
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::SelfDestruct));
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::CalleeAddress, sender.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value);
+        }
+
+        // The beneficiary costs an extra cold account access if it's not in
+        // the access list.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm,
+                is_warm_prev: is_warm,
+            },
+        );
+
+        // Sending a non-zero balance to an empty beneficiary costs an extra
+        // new account charge.
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_code_hash = if beneficiary_account.is_empty() {
+            H256::zero()
+        } else {
+            beneficiary_account.code_hash
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let balance = sender_account.balance;
+        state.account_read(&mut exec_step, sender, AccountField::Balance, balance);
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
@@ -0,0 +1,255 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::{evm_types::MemoryAddress, GethExecStep};
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_steps = vec![gen_mcopy_step(state, geth_step)?];
+
+        let copy_event = gen_copy_event(state, geth_step)?;
+        state.push_copy(&mut exec_steps[0], copy_event);
+        Ok(exec_steps)
+    }
+}
+
+fn gen_mcopy_step(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+) -> Result<ExecStep, Error> {
+    let mut exec_step = state.new_step(geth_step)?;
+    let dst_offset = geth_step.stack.nth_last(0)?;
+    let src_offset = geth_step.stack.nth_last(1)?;
+    let length = geth_step.stack.nth_last(2)?;
+
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(0),
+        dst_offset,
+    )?;
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(1),
+        src_offset,
+    )?;
+    state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(2), length)?;
+
+    Ok(exec_step)
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    let length = geth_step.stack.nth_last(2)?.as_u64();
+    // The offsets are only within range when there is something to copy.
+    let (dst_addr, src_addr) = if length == 0 {
+        (0, 0)
+    } else {
+        (
+            geth_step.stack.nth_last(0)?.as_u64(),
+            geth_step.stack.nth_last(1)?.as_u64(),
+        )
+    };
+
+    // The source range is read completely before the destination range is
+    // written, so that overlapping ranges copy the original bytes.
+    let bytes = if length == 0 {
+        vec![]
+    } else {
+        let memory = &mut state.call_ctx_mut()?.memory;
+        memory.extend_at_least((src_addr.max(dst_addr) + length) as usize);
+        memory.read_chunk(
+            MemoryAddress::from(src_addr as usize),
+            MemoryAddress::from(length as usize),
+        )
+    };
+
+    let mut exec_step = state.new_step(geth_step)?;
+    for (idx, byte) in bytes.iter().enumerate() {
+        state.memory_read(&mut exec_step, (src_addr as usize + idx).into(), *byte)?;
+    }
+    for (idx, byte) in bytes.iter().enumerate() {
+        state.memory_write(&mut exec_step, (dst_addr as usize + idx).into(), *byte)?;
+    }
+
+    // reconstruction
+    if length != 0 {
+        let memory = &mut state.call_ctx_mut()?.memory;
+        let dst_starts = dst_addr as usize;
+        memory[dst_starts..dst_starts + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::MemoryCopy,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        bytes: bytes.into_iter().map(|byte| (byte, false)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{MemoryOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(dst_offset: usize, src_offset: usize, copy_size: usize) {
+        let word_bytes = (1..=32).collect::<Vec<u8>>();
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&word_bytes))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from(copy_size))
+            PUSH32(Word::from(src_offset))
+            PUSH32(Word::from(dst_offset))
+            MCOPY
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+
+        let expected_call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
+
+        // 3 stack reads.
+        assert_eq!(step.bus_mapping_instance.len(), 3);
+        assert_eq!(
+            [0, 1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(expected_call_id, StackAddress::from(1021), Word::from(dst_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(expected_call_id, StackAddress::from(1022), Word::from(src_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(expected_call_id, StackAddress::from(1023), Word::from(copy_size))
+                ),
+            ]
+        );
+
+        // Memory reads/writes.
+        //
+        // 1. First 32 memory ops are RW::WRITE and come from the `MSTORE` opcode.
+        // We skip checking those.
+        //
+        // 2. Following that, all the source bytes are read before the destination
+        // bytes are written, so the copied bytes are the ones before the copy.
+        let mut memory = word_bytes;
+        memory.resize(src_offset.max(dst_offset) + copy_size, 0);
+        let copied = memory[src_offset..src_offset + copy_size].to_vec();
+        assert_eq!(builder.block.container.memory.len(), 32 + 2 * copy_size);
+        assert_eq!(
+            (32..(32 + 2 * copy_size))
+                .map(|idx| &builder.block.container.memory[idx])
+                .map(|op| (op.rw(), op.op().clone()))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+            copied
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (
+                    RW::READ,
+                    MemoryOp::new(expected_call_id, (src_offset + idx).into(), *value)
+                ))
+                .chain(copied.iter().enumerate().map(|(idx, value)| (
+                    RW::WRITE,
+                    MemoryOp::new(expected_call_id, (dst_offset + idx).into(), *value)
+                )))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+        );
+
+        let copy_events = builder.block.copy_events.clone();
+        assert_eq!(copy_events.len(), 1);
+        assert_eq!(copy_events[0].src_type, CopyDataType::Memory);
+        assert_eq!(
+            copy_events[0].src_id,
+            NumberOrHash::Number(expected_call_id)
+        );
+        assert_eq!(copy_events[0].src_addr as usize, src_offset);
+        assert_eq!(copy_events[0].src_addr_end as usize, src_offset + copy_size);
+        assert_eq!(copy_events[0].dst_type, CopyDataType::MemoryCopy);
+        assert_eq!(
+            copy_events[0].dst_id,
+            NumberOrHash::Number(expected_call_id)
+        );
+        assert_eq!(copy_events[0].dst_addr as usize, dst_offset);
+        assert_eq!(copy_events[0].rw_counter_delta() as usize, 2 * copy_size);
+        assert_eq!(
+            copy_events[0]
+                .bytes
+                .iter()
+                .map(|(value, _)| *value)
+                .collect::<Vec<u8>>(),
+            copied
+        );
+    }
+
+    #[test]
+    fn mcopy_opcode_impl() {
+        test_ok(0x40, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_opcode_impl_overlap_forward() {
+        test_ok(0x08, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_opcode_impl_overlap_backward() {
+        test_ok(0x00, 0x08, 0x20);
+    }
+}
@@ -1,24 +1,200 @@
-use eth_types::{GethExecStep, ToWord, Word};
+use eth_types::{
+    evm_types::{Gas, GasCost, ProgramCounter},
+    GethExecStep, ToWord, Word,
+};
 
 use crate::{
-    circuit_input_builder::{Call, CircuitInputStateRef, ExecState, ExecStep},
-    operation::CallContextField,
-    precompile::PrecompileCalls,
+    circuit_input_builder::{
+        Call, CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+    },
+    operation::{CallContextField, MemoryOp, RW},
+    precompile::{
+        ec_pairing_gas_cost, Blake2fAuxData, EcAddAuxData, EcMulAuxData, EcPairingAuxData,
+        EcrecoverAuxData, HashAuxData, ModExpAuxData, PrecompileAuxData, PrecompileCalls,
+        PrecompileEvent, BLAKE2F_FINAL_FLAG_OFFSET, BLAKE2F_INPUT_LENGTH, EC_PAIRING_PAIR_LENGTH,
+    },
     Error,
 };
 
+/// Generate the internal step of a call to a precompiled contract. The step
+/// reads the call context set up by the `*CALL*` step, copies the input from
+/// the caller's memory, writes the output to the callee's memory and the
+/// returned data back to the caller's memory, and finally restores the
+/// caller's context.
+#[allow(clippy::too_many_arguments)]
 pub fn gen_associated_ops(
     state: &mut CircuitInputStateRef,
     geth_step: GethExecStep,
     call: Call,
     precompile: PrecompileCalls,
+    input_bytes: &[u8],
+    output_bytes: &[u8],
+    gas_left: u64,
+    gas_cost: u64,
 ) -> Result<ExecStep, Error> {
     assert_eq!(call.code_address(), Some(precompile.into()));
     let mut exec_step = state.new_step(&geth_step)?;
     exec_step.exec_state = ExecState::Precompile(precompile);
+    // The precompile runs in a fresh context without any bytecode.
+    exec_step.pc = ProgramCounter(0);
+    exec_step.stack_size = 0;
+    exec_step.memory_size = 0;
+    exec_step.gas_left = Gas(gas_left);
+    exec_step.gas_cost = GasCost(gas_cost);
 
     common_call_ctx_reads(state, &mut exec_step, &call);
 
+    // The calls which fail with enough gas are the ones with an invalid input.
+    let required_gas = match precompile {
+        PrecompileCalls::Bn128Pairing => ec_pairing_gas_cost(call.call_data_length),
+        _ => precompile.base_gas_cost().as_u64(),
+    };
+    let has_enough_gas = gas_left >= required_gas;
+    // The input of ecPairing must be made of whole pairs.
+    let is_pairing_input_len_valid = call.call_data_length % EC_PAIRING_PAIR_LENGTH as u64 == 0;
+    match precompile {
+        PrecompileCalls::ECRecover => {
+            let aux_data = EcrecoverAuxData::new(input_bytes, output_bytes);
+            // The recovery is only attempted if the call has enough gas.
+            if call.is_success && aux_data.is_well_formed() {
+                state.push_precompile_event(PrecompileEvent::Ecrecover(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Ecrecover(aux_data));
+        }
+        PrecompileCalls::Sha256 => {
+            let aux_data = HashAuxData {
+                input_bytes: input_bytes.to_vec(),
+                output_bytes: output_bytes.to_vec(),
+            };
+            if call.is_success {
+                state.push_precompile_event(PrecompileEvent::Sha256(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Sha256(aux_data));
+        }
+        PrecompileCalls::Ripemd160 => {
+            let aux_data = HashAuxData {
+                input_bytes: input_bytes.to_vec(),
+                output_bytes: output_bytes.to_vec(),
+            };
+            if call.is_success {
+                state.push_precompile_event(PrecompileEvent::Ripemd160(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Ripemd160(aux_data));
+        }
+        PrecompileCalls::Modexp => {
+            let aux_data = ModExpAuxData::new(input_bytes, output_bytes);
+            // The gas cost and the exponentiation are only proven for
+            // operands of at most `MODEXP_SIZE_LIMIT` bytes.
+            if !aux_data.is_supported() {
+                return Err(Error::UnsupportedPrecompileInput(precompile));
+            }
+            if call.is_success {
+                state.push_precompile_event(PrecompileEvent::Modexp(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Modexp(aux_data));
+        }
+        PrecompileCalls::Bn128Add => {
+            let aux_data = EcAddAuxData::new(input_bytes, output_bytes);
+            if has_enough_gas && aux_data.is_in_field() {
+                state.push_precompile_event(PrecompileEvent::EcAdd(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::EcAdd(aux_data));
+        }
+        PrecompileCalls::Bn128Mul => {
+            let aux_data = EcMulAuxData::new(input_bytes, output_bytes);
+            if has_enough_gas && aux_data.is_in_field() {
+                state.push_precompile_event(PrecompileEvent::EcMul(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::EcMul(aux_data));
+        }
+        PrecompileCalls::Bn128Pairing => {
+            let aux_data = EcPairingAuxData::new(input_bytes, output_bytes);
+            if has_enough_gas && is_pairing_input_len_valid && !aux_data.pairs.is_empty() {
+                state.push_precompile_event(PrecompileEvent::EcPairing(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::EcPairing(aux_data));
+        }
+        PrecompileCalls::Blake2F => {
+            // The number of rounds and the final block indicator of an input
+            // with the expected length are read to check the gas and the
+            // indicator.
+            if call.call_data_length == BLAKE2F_INPUT_LENGTH as u64 {
+                for i in (0..4).chain(std::iter::once(BLAKE2F_FINAL_FLAG_OFFSET)) {
+                    state.push_op(
+                        &mut exec_step,
+                        RW::READ,
+                        MemoryOp::new(
+                            call.caller_id,
+                            (call.call_data_offset + i as u64).into(),
+                            input_bytes[i],
+                        ),
+                    );
+                }
+            }
+            let aux_data = Blake2fAuxData::new(input_bytes, output_bytes);
+            if call.is_success {
+                state.push_precompile_event(PrecompileEvent::Blake2F(aux_data.clone()));
+            }
+            exec_step.aux_data = Some(PrecompileAuxData::Blake2F(aux_data));
+        }
+        _ => {}
+    }
+
+    // Precompiles with a fixed input size read the call data right padded with
+    // zeroes, and MODEXP reads the sizes given in its header.
+    let input_len = match &exec_step.aux_data {
+        Some(PrecompileAuxData::Modexp(aux_data)) => aux_data.input_bytes.len(),
+        _ => precompile
+            .input_len()
+            .unwrap_or(call.call_data_length as usize),
+    };
+    if call.is_success {
+        gen_copy_events(
+            state,
+            &mut exec_step,
+            &call,
+            precompile,
+            input_bytes,
+            input_len,
+            output_bytes,
+        )?;
+    } else {
+        // The gas cost of MODEXP depends on its input, which is read even if
+        // the call runs out of gas. The input of ecAdd, ecMul and ecPairing is
+        // read to show that it's invalid when the call fails with enough gas.
+        let reads_input = match precompile {
+            PrecompileCalls::Modexp => true,
+            PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul => has_enough_gas,
+            PrecompileCalls::Bn128Pairing => has_enough_gas && is_pairing_input_len_valid,
+            _ => false,
+        };
+        if reads_input {
+            gen_input_copy_event(state, &mut exec_step, &call, input_bytes, input_len);
+        }
+        // Even call.rw_counter_end_of_reversion is zero for now, it will set in
+        // set_value_ops_call_context_rwc_eor later.
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::RwCounterEndOfReversion,
+            call.rw_counter_end_of_reversion.into(),
+        );
+    }
+
+    let output_bytes = if call.is_success {
+        output_bytes.to_vec()
+    } else {
+        vec![]
+    };
+    handle_precompile_return(
+        state,
+        &mut exec_step,
+        &geth_step,
+        &call,
+        output_bytes,
+        gas_left - gas_cost,
+    )?;
+
     Ok(exec_step)
 }
 
@@ -53,3 +229,255 @@ fn common_call_ctx_reads(state: &mut CircuitInputStateRef, exec_step: &mut ExecS
         state.call_context_read(exec_step, call.call_id, field, value);
     }
 }
+
+/// Push the copy events of a successful precompile call:
+/// - caller memory to the RLC of the input,
+/// - RLC of the output to the callee memory,
+/// - callee memory to the caller memory, for the returned bytes.
+///
+/// The identity precompile copies its input from the caller memory to the
+/// callee memory instead of the first two.
+fn gen_copy_events(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    call: &Call,
+    precompile: PrecompileCalls,
+    input_bytes: &[u8],
+    input_len: usize,
+    output_bytes: &[u8],
+) -> Result<(), Error> {
+    if precompile == PrecompileCalls::Identity {
+        // The input is copied as is to the callee's memory, from where it's
+        // returned.
+        if !input_bytes.is_empty() {
+            let rw_counter_start = state.block_ctx.rwc;
+            for (i, byte) in input_bytes.iter().enumerate() {
+                state.push_op(
+                    exec_step,
+                    RW::READ,
+                    MemoryOp::new(
+                        call.caller_id,
+                        (call.call_data_offset + i as u64).into(),
+                        *byte,
+                    ),
+                );
+                state.push_op(
+                    exec_step,
+                    RW::WRITE,
+                    MemoryOp::new(call.call_id, i.into(), *byte),
+                );
+            }
+            let callee_memory = &mut state.call_ctx_mut()?.memory;
+            callee_memory.extend_at_least(input_bytes.len());
+            callee_memory.0[..input_bytes.len()].copy_from_slice(input_bytes);
+            state.push_copy(
+                exec_step,
+                CopyEvent {
+                    src_id: NumberOrHash::Number(call.caller_id),
+                    src_type: CopyDataType::Memory,
+                    src_addr: call.call_data_offset,
+                    src_addr_end: call.call_data_offset + call.call_data_length,
+                    dst_id: NumberOrHash::Number(call.call_id),
+                    dst_type: CopyDataType::Memory,
+                    dst_addr: 0,
+                    log_id: None,
+                    rw_counter_start,
+                    bytes: input_bytes.iter().map(|b| (*b, false)).collect(),
+                },
+            );
+        }
+    } else {
+        gen_input_copy_event(state, exec_step, call, input_bytes, input_len);
+
+        if !output_bytes.is_empty() {
+            let rw_counter_start = state.block_ctx.rwc;
+            for (i, byte) in output_bytes.iter().enumerate() {
+                state.push_op(
+                    exec_step,
+                    RW::WRITE,
+                    MemoryOp::new(call.call_id, i.into(), *byte),
+                );
+            }
+            let callee_memory = &mut state.call_ctx_mut()?.memory;
+            callee_memory.extend_at_least(output_bytes.len());
+            callee_memory.0[..output_bytes.len()].copy_from_slice(output_bytes);
+            state.push_copy(
+                exec_step,
+                CopyEvent {
+                    src_id: NumberOrHash::Number(call.call_id),
+                    src_type: CopyDataType::RlcAcc,
+                    src_addr: 0,
+                    src_addr_end: output_bytes.len() as u64,
+                    dst_id: NumberOrHash::Number(call.call_id),
+                    dst_type: CopyDataType::Memory,
+                    dst_addr: 0,
+                    log_id: None,
+                    rw_counter_start,
+                    bytes: output_bytes.iter().map(|b| (*b, false)).collect(),
+                },
+            );
+        }
+    }
+
+    let length = std::cmp::min(call.return_data_length as usize, output_bytes.len());
+    if length > 0 {
+        let rw_counter_start = state.block_ctx.rwc;
+        for (i, byte) in output_bytes.iter().take(length).enumerate() {
+            state.push_op(
+                exec_step,
+                RW::READ,
+                MemoryOp::new(call.call_id, i.into(), *byte),
+            );
+            state.push_op(
+                exec_step,
+                RW::WRITE,
+                MemoryOp::new(
+                    call.caller_id,
+                    (call.return_data_offset + i as u64).into(),
+                    *byte,
+                ),
+            );
+        }
+        let return_data_offset = call.return_data_offset as usize;
+        let caller_memory = &mut state.caller_ctx_mut()?.memory;
+        caller_memory.extend_at_least(return_data_offset + length);
+        caller_memory.0[return_data_offset..return_data_offset + length]
+            .copy_from_slice(&output_bytes[..length]);
+        state.push_copy(
+            exec_step,
+            CopyEvent {
+                src_id: NumberOrHash::Number(call.call_id),
+                src_type: CopyDataType::Memory,
+                src_addr: 0,
+                src_addr_end: length as u64,
+                dst_id: NumberOrHash::Number(call.caller_id),
+                dst_type: CopyDataType::Memory,
+                dst_addr: call.return_data_offset,
+                log_id: None,
+                rw_counter_start,
+                bytes: output_bytes[..length].iter().map(|b| (*b, false)).collect(),
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Push the copy event of the input from the caller memory to its RLC. The
+/// first `input_len` bytes of the call data are read, right padded with
+/// zeroes.
+fn gen_input_copy_event(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    call: &Call,
+    input_bytes: &[u8],
+    input_len: usize,
+) {
+    if input_len == 0 {
+        return;
+    }
+    let mut bytes = input_bytes.to_vec();
+    bytes.resize(input_len, 0);
+    let rw_counter_start = state.block_ctx.rwc;
+    for (i, byte) in bytes
+        .iter()
+        .take(call.call_data_length as usize)
+        .enumerate()
+    {
+        state.push_op(
+            exec_step,
+            RW::READ,
+            MemoryOp::new(
+                call.caller_id,
+                (call.call_data_offset + i as u64).into(),
+                *byte,
+            ),
+        );
+    }
+    state.push_copy(
+        exec_step,
+        CopyEvent {
+            src_id: NumberOrHash::Number(call.caller_id),
+            src_type: CopyDataType::Memory,
+            src_addr: call.call_data_offset,
+            src_addr_end: call.call_data_offset + call.call_data_length,
+            dst_id: NumberOrHash::Number(call.call_id),
+            dst_type: CopyDataType::RlcAcc,
+            dst_addr: 0,
+            log_id: None,
+            rw_counter_start,
+            bytes: bytes.into_iter().map(|b| (b, false)).collect(),
+        },
+    );
+}
+
+/// Bus mapping for the RestoreContextGadget as used at the end of a
+/// precompile call. `gas_refund` is the gas left in the precompile context
+/// which is returned to the caller.
+fn handle_precompile_return(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    geth_step_next: &GethExecStep,
+    call: &Call,
+    return_data: Vec<u8>,
+    gas_refund: u64,
+) -> Result<(), Error> {
+    let caller = state.caller()?.clone();
+    state.call_context_read(
+        exec_step,
+        call.call_id,
+        CallContextField::CallerId,
+        caller.call_id.into(),
+    );
+
+    let caller_ctx = state.caller_ctx()?;
+    let (caller_memory_word_size, caller_reversible_write_counter) = (
+        caller_ctx.memory.word_size(),
+        caller_ctx.reversible_write_counter,
+    );
+    for (field, value) in [
+        (CallContextField::IsRoot, (caller.is_root as u64).into()),
+        (
+            CallContextField::IsCreate,
+            (caller.is_create() as u64).into(),
+        ),
+        (CallContextField::CodeHash, caller.code_hash.to_word()),
+        (CallContextField::ProgramCounter, geth_step_next.pc.0.into()),
+        (
+            CallContextField::StackPointer,
+            geth_step_next.stack.stack_pointer().0.into(),
+        ),
+        (
+            CallContextField::GasLeft,
+            (geth_step_next.gas.0 - gas_refund).into(),
+        ),
+        (CallContextField::MemorySize, caller_memory_word_size.into()),
+        (
+            CallContextField::ReversibleWriteCounter,
+            caller_reversible_write_counter.into(),
+        ),
+    ] {
+        state.call_context_read(exec_step, caller.call_id, field, value);
+    }
+
+    for (field, value) in [
+        (CallContextField::LastCalleeId, call.call_id.into()),
+        (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+        (
+            CallContextField::LastCalleeReturnDataLength,
+            return_data.len().into(),
+        ),
+    ] {
+        state.call_context_write(exec_step, caller.call_id, field, value);
+    }
+
+    let caller = state.caller_mut()?;
+    caller.last_callee_id = call.call_id;
+    caller.last_callee_return_data_offset = 0;
+    caller.last_callee_return_data_length = return_data.len() as u64;
+    state.caller_ctx_mut()?.return_data = return_data;
+
+    state.tx_ctx.pop_call_ctx();
+
+    Ok(())
+}
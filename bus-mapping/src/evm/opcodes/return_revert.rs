@@ -6,7 +6,7 @@ use crate::{
     state_db::CodeDB,
     Error,
 };
-use eth_types::{Bytecode, GethExecStep, ToWord, H256};
+use eth_types::{evm_types::GasCost, Bytecode, GethExecStep, ToWord, H256};
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct ReturnRevert;
@@ -82,6 +82,14 @@ impl Opcode for ReturnRevert {
 
         // Case B in the specs.
         if call.is_root {
+            // The code deposit cost is charged outside of the RETURN opcode, so
+            // it's not included in the gas cost reported by the trace.
+            if call.is_create() && call.is_success {
+                exec_step.gas_cost = GasCost(
+                    exec_step.gas_cost.0
+                        + GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * length as u64,
+                );
+            }
             state.call_context_read(
                 &mut exec_step,
                 call.call_id,
@@ -0,0 +1,367 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountOp, TxCreatedAccountOp, RW,
+    },
+    Error,
+};
+use eth_types::{chain_spec::Hardfork, GethExecStep, ToAddress, ToWord, H256, U256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+/// Since Cancun (EIP-6780), the account is only deleted if it was created in
+/// the same transaction, otherwise the balance is transferred only. The
+/// balance, nonce and code hash of a deleted account are zeroed right away,
+/// while its storage is reset when the transaction ends.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SelfDestruct;
+
+impl Opcode for SelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                call.rw_counter_end_of_reversion.into(),
+            ),
+            (
+                CallContextField::IsPersistent,
+                (call.is_persistent as u64).into(),
+            ),
+            (CallContextField::CalleeAddress, sender.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value);
+        }
+
+        // Update transaction access list for the beneficiary.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        let beneficiary_code_hash = if beneficiary_exists {
+            beneficiary_account.code_hash
+        } else {
+            H256::zero()
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let value = sender_account.balance;
+        state.account_read(&mut exec_step, sender, AccountField::Balance, value);
+
+        state.transfer(
+            &mut exec_step,
+            sender,
+            beneficiary,
+            beneficiary_exists,
+            false,
+            value,
+        )?;
+
+        // Before EIP-6780 the account is always deleted, afterwards only when
+        // it was created in the same transaction.
+        let is_deleted = if state.block.hardfork.is_enabled(Hardfork::Cancun) {
+            let is_created = state.sdb.is_account_created_in_tx(&sender);
+            state.push_op(
+                &mut exec_step,
+                RW::READ,
+                TxCreatedAccountOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: sender,
+                    is_created,
+                    is_created_prev: is_created,
+                },
+            );
+            is_created
+        } else {
+            true
+        };
+        if is_deleted {
+            // The ether sent to itself is burnt, and the account is left with
+            // the zero code hash of a non-existing account.
+            let (_, sender_account) = state.sdb.get_account(&sender);
+            let (balance_prev, nonce_prev, code_hash_prev) = (
+                sender_account.balance,
+                sender_account.nonce,
+                sender_account.code_hash,
+            );
+            for (field, value_prev) in [
+                (AccountField::Balance, balance_prev),
+                (AccountField::Nonce, nonce_prev.into()),
+                (AccountField::CodeHash, code_hash_prev.to_word()),
+            ] {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: sender,
+                        field,
+                        value: U256::zero(),
+                        value_prev,
+                    },
+                )?;
+            }
+
+            // The storage is reset when the transaction ends.
+            if call.is_persistent {
+                state.sdb.destruct_account(sender);
+            }
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{StackOp, Target, RW},
+        state_db::CodeDB,
+    };
+    use eth_types::{
+        address, bytecode,
+        chain_spec::ChainSpec,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_selfdestruct_to_existing_account() {
+        test_ok(true, Hardfork::Shanghai);
+        test_ok(true, Hardfork::Cancun);
+    }
+
+    #[test]
+    fn test_selfdestruct_to_non_existing_account() {
+        test_ok(false, Hardfork::Shanghai);
+        test_ok(false, Hardfork::Cancun);
+    }
+
+    fn test_ok(beneficiary_exists: bool, hardfork: Hardfork) {
+        // The account is not created in the transaction, so it is only deleted
+        // before EIP-6780.
+        let is_deleted = !hardfork.is_enabled(Hardfork::Cancun);
+        let beneficiary = address!("0xaabbccddee000000000000000000000000000000");
+        let contract = address!("0x0000000000000000000000000000000000000010");
+        let balance = Word::from(800u64);
+
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let code_hash = CodeDB::hash(&code.code());
+
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(contract).balance(balance).code(code);
+                if beneficiary_exists {
+                    accs[1].address(beneficiary).balance(Word::from(1u64 << 20));
+                } else {
+                    accs[1]
+                        .address(address!("0x0000000000000000000000000000000000000020"))
+                        .balance(Word::from(1u64 << 20));
+                }
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .chain_spec(ChainSpec::with_hardfork(hardfork))
+            },
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The self destructed account is reset at the end of the transaction.
+        let (_, account) = builder.sdb.get_account(&contract);
+        assert_eq!(account.is_empty(), is_deleted);
+        assert_eq!(account.balance, Word::zero());
+        let (_, account) = builder.sdb.get_account(&beneficiary);
+        let beneficiary_balance_prev = if beneficiary_exists {
+            Word::from(1u64 << 20)
+        } else {
+            Word::zero()
+        };
+        assert_eq!(account.balance, beneficiary_balance_prev + balance);
+
+        let tx_id = 1;
+        let transaction = &builder.block.txs()[tx_id - 1];
+        let call_id = transaction.calls()[0].call_id;
+
+        let step = transaction
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+        assert_eq!(
+            step.gas_cost.0,
+            5000 + 2600 + if beneficiary_exists { 0 } else { 25000 }
+        );
+        let indices = step.bus_mapping_instance.clone();
+        let container = builder.block.container;
+
+        let operation = &container.stack[indices[0].as_usize()];
+        assert_eq!(operation.rw(), RW::READ);
+        assert_eq!(
+            operation.op(),
+            &StackOp {
+                call_id,
+                address: StackAddress::from(1023u32),
+                value: beneficiary.to_word()
+            }
+        );
+
+        let operation = &container.tx_access_list_account[indices[6].as_usize()];
+        assert_eq!(operation.rw(), RW::WRITE);
+        assert_eq!(
+            operation.op(),
+            &TxAccessListAccountOp {
+                tx_id,
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: false
+            }
+        );
+
+        let account_ops = indices[7..]
+            .iter()
+            .filter(|index| index.target() == Target::Account)
+            .map(|index| container.account[index.as_usize()].op().clone())
+            .collect::<Vec<_>>();
+        let code_hash_read = AccountOp {
+            address: beneficiary,
+            field: AccountField::CodeHash,
+            value: if beneficiary_exists {
+                CodeDB::empty_code_hash().to_word()
+            } else {
+                Word::zero()
+            },
+            value_prev: if beneficiary_exists {
+                CodeDB::empty_code_hash().to_word()
+            } else {
+                Word::zero()
+            },
+        };
+        let balance_read = AccountOp {
+            address: contract,
+            field: AccountField::Balance,
+            value: balance,
+            value_prev: balance,
+        };
+        let mut expected = vec![code_hash_read, balance_read];
+        if !beneficiary_exists {
+            expected.push(AccountOp {
+                address: beneficiary,
+                field: AccountField::CodeHash,
+                value: CodeDB::empty_code_hash().to_word(),
+                value_prev: Word::zero(),
+            });
+        }
+        expected.extend([
+            AccountOp {
+                address: contract,
+                field: AccountField::Balance,
+                value: Word::zero(),
+                value_prev: balance,
+            },
+            AccountOp {
+                address: beneficiary,
+                field: AccountField::Balance,
+                value: beneficiary_balance_prev + balance,
+                value_prev: beneficiary_balance_prev,
+            },
+        ]);
+        if is_deleted {
+            expected.extend([
+                AccountOp {
+                    address: contract,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: Word::zero(),
+                },
+                AccountOp {
+                    address: contract,
+                    field: AccountField::Nonce,
+                    value: Word::zero(),
+                    value_prev: Word::zero(),
+                },
+                AccountOp {
+                    address: contract,
+                    field: AccountField::CodeHash,
+                    value: Word::zero(),
+                    value_prev: code_hash.to_word(),
+                },
+            ]);
+        }
+        assert_eq!(account_ops, expected);
+
+        let tx_created_account_ops = indices[7..]
+            .iter()
+            .filter(|index| index.target() == Target::TxCreatedAccount)
+            .map(|index| container.tx_created_account[index.as_usize()].op().clone())
+            .collect::<Vec<_>>();
+        let mut expected = vec![];
+        if hardfork.is_enabled(Hardfork::Cancun) {
+            expected.push(TxCreatedAccountOp {
+                tx_id,
+                address: contract,
+                is_created: false,
+                is_created_prev: false,
+            });
+        }
+        assert_eq!(tx_created_account_ops, expected);
+    }
+}
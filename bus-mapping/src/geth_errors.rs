@@ -6,3 +6,11 @@ pub const GETH_ERR_STACK_UNDERFLOW: &str = "stack underflow";
 pub const GETH_ERR_OUT_OF_GAS: &str = "out of gas";
 /// Geth error message for gas uint64 overflow
 pub const GETH_ERR_GAS_UINT_OVERFLOW: &str = "gas uint64 overflow";
+/// Geth error message for contract address collision
+pub const GETH_ERR_CONTRACT_ADDRESS_COLLISION: &str = "contract address collision";
+/// Geth error message for invalid code (EIP-3541)
+pub const GETH_ERR_INVALID_CODE: &str = "invalid code: must not begin with 0xef";
+/// Geth error message for max code size exceeded
+pub const GETH_ERR_MAX_CODE_SIZE_EXCEEDED: &str = "max code size exceeded";
+/// Geth error message for contract creation code storage out of gas
+pub const GETH_ERR_CODE_STORE_OUT_OF_GAS: &str = "contract creation code storage out of gas";
@@ -4,7 +4,7 @@ use crate::{
     circuit_input_builder::{get_state_accesses, Block, CircuitInputBuilder, CircuitsParams},
     state_db::{self, CodeDB, StateDB},
 };
-use eth_types::{geth_types::GethData, Word};
+use eth_types::{chain_spec::ChainSpec, geth_types::GethData, Word};
 
 /// BlockData is a type that contains all the information from a block required
 /// to build the circuit inputs.
@@ -16,6 +16,8 @@ pub struct BlockData {
     pub code_db: CodeDB,
     /// chain id
     pub chain_id: Word,
+    /// hardfork schedule
+    pub chain_spec: ChainSpec,
     /// history hashes contains most recent 256 block hashes in history, where
     /// the lastest one is at history_hashes[history_hashes.len() - 1].
     pub history_hashes: Vec<Word>,
@@ -36,6 +38,7 @@ impl BlockData {
             self.code_db.clone(),
             Block::new(
                 self.chain_id,
+                &self.chain_spec,
                 self.history_hashes.clone(),
                 Word::default(),
                 &self.eth_block,
@@ -68,6 +71,7 @@ impl BlockData {
             sdb,
             code_db,
             chain_id: geth_data.chain_id,
+            chain_spec: geth_data.chain_spec,
             history_hashes: geth_data.history_hashes,
             eth_block: geth_data.eth_block,
             geth_traces: geth_data.geth_traces,
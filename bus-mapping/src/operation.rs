@@ -115,6 +115,10 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TransientStorage.
+    TransientStorage,
+    /// Means the target of the operation is the TxCreatedAccount.
+    TxCreatedAccount,
 }
 
 impl_expr!(Target);
@@ -135,6 +139,8 @@ impl Target {
                 | Target::TxRefund
                 | Target::Account
                 | Target::Storage
+                | Target::TransientStorage
+                | Target::TxCreatedAccount
         )
     }
 }
@@ -404,6 +410,103 @@ impl Ord for StorageOp {
     }
 }
 
+/// Represents a [`READ`](RW::READ)/[`WRITE`](RW::WRITE) into the transient
+/// storage implied by a `TLOAD` or `TSTORE` step of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).
+#[derive(Clone, PartialEq, Eq)]
+pub struct TransientStorageOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Transient Storage Key
+    pub key: Word,
+    /// Transient Storage Value after the operation
+    pub value: Word,
+    /// Transient Storage Value before the operation
+    pub value_prev: Word,
+}
+
+impl fmt::Debug for TransientStorageOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransientStorageOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, key: {:?}, val_prev: 0x{:x}, val: 0x{:x}",
+            self.tx_id, self.address, self.key, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl TransientStorageOp {
+    /// Create a new instance of a `TransientStorageOp` from it's components.
+    pub const fn new(
+        address: Address,
+        key: Word,
+        value: Word,
+        value_prev: Word,
+        tx_id: usize,
+    ) -> TransientStorageOp {
+        TransientStorageOp {
+            tx_id,
+            address,
+            key,
+            value,
+            value_prev,
+        }
+    }
+
+    /// Returns the [`Target`] (operation type) of this operation.
+    pub const fn target(&self) -> Target {
+        Target::TransientStorage
+    }
+
+    /// Returns the [`Address`] corresponding to this transient storage
+    /// operation.
+    pub const fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the [`Word`] used as key for this operation.
+    pub const fn key(&self) -> &Word {
+        &self.key
+    }
+
+    /// Returns the [`Word`] read or written by this operation.
+    pub const fn value(&self) -> &Word {
+        &self.value
+    }
+
+    /// Returns the [`Word`] at key found previous to this operation.
+    pub const fn value_prev(&self) -> &Word {
+        &self.value_prev
+    }
+}
+
+impl Op for TransientStorageOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TransientStorage(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
+impl PartialOrd for TransientStorageOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TransientStorageOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address, &self.key).cmp(&(&other.tx_id, &other.address, &other.key))
+    }
+}
+
 /// Represents a change in the Account AccessList implied by a `BeginTx`,
 /// `EXTCODECOPY`, `EXTCODESIZE`, `EXTCODEHASH` `BALANCE`, `SELFDESTRUCT`,
 /// `*CALL`* or `CREATE*` step.
@@ -455,6 +558,58 @@ impl Op for TxAccessListAccountOp {
     }
 }
 
+/// Represents an account created in the current transaction by a `BeginTx` of
+/// a contract creation transaction or by a `CREATE*` step. Under EIP-6780,
+/// `SELFDESTRUCT` only deletes an account which is created in the same
+/// transaction.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxCreatedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Whether the account is created in the transaction.
+    pub is_created: bool,
+    /// Whether the account was created in the transaction before the
+    /// operation.
+    pub is_created_prev: bool,
+}
+
+impl fmt::Debug for TxCreatedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxCreatedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, is_created_prev: {:?}, is_created: {:?}",
+            self.tx_id, self.address, self.is_created_prev, self.is_created
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxCreatedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxCreatedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for TxCreatedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxCreatedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.is_created, &mut rev.is_created_prev);
+        rev
+    }
+}
+
 /// Represents a change in the Storage AccessList implied by an `SSTORE` or
 /// `SLOAD` step of the [`ExecStep`](crate::circuit_input_builder::ExecStep).
 #[derive(Clone, PartialEq, Eq)]
@@ -939,10 +1094,14 @@ pub enum OpEnum {
     Memory(MemoryOp),
     /// Storage
     Storage(StorageOp),
+    /// TransientStorage
+    TransientStorage(TransientStorageOp),
     /// TxAccessListAccount
     TxAccessListAccount(TxAccessListAccountOp),
     /// TxAccessListAccountStorage
     TxAccessListAccountStorage(TxAccessListAccountStorageOp),
+    /// TxCreatedAccount
+    TxCreatedAccount(TxCreatedAccountOp),
     /// TxRefund
     TxRefund(TxRefundOp),
     /// Account
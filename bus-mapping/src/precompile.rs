@@ -1,16 +1,43 @@
 //! precompile helpers
 
-use eth_types::{evm_types::GasCost, Address};
+use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::GasCost,
+    sign_types::{recover_pk, SECP256K1_Q},
+    Address, ToBigEndian, ToLittleEndian, Word,
+};
+use halo2_proofs::halo2curves::{
+    bn256::{Fq, Fq2, Fr, G1Affine, G2Affine, G2},
+    ff::{Field, PrimeField},
+    group::prime::PrimeCurveAffine,
+    secp256k1::Secp256k1Affine,
+    CurveAffine,
+};
+use lazy_static::lazy_static;
 use revm_precompile::{Precompile, Precompiles};
 use strum::EnumIter;
 
+/// Address of the point evaluation precompile added in Cancun (EIP-4844),
+/// which is not supported by the circuits.
+pub const POINT_EVALUATION_ADDRESS: u8 = 0x0a;
+
+/// Last address of the precompiled contracts available in the hardfork, which
+/// are stored from address 0x01.
+fn last_precompile_address(hardfork: Hardfork) -> u8 {
+    match hardfork {
+        Hardfork::London | Hardfork::Shanghai => PrecompileCalls::Blake2F as u8,
+        Hardfork::Cancun => POINT_EVALUATION_ADDRESS,
+    }
+}
+
 /// Check if address is a precompiled or not.
-pub fn is_precompiled(address: &Address) -> bool {
-    Precompiles::berlin()
-        .get(address.as_fixed_bytes())
-        .is_some()
+pub fn is_precompiled(address: &Address, hardfork: Hardfork) -> bool {
+    address.0[0..19] == [0u8; 19]
+        && (1..=last_precompile_address(hardfork)).contains(&address.0[19])
 }
 
+/// Executes one of the supported precompiled contracts, which are the ones of
+/// Berlin.
 pub(crate) fn execute_precompiled(address: &Address, input: &[u8], gas: u64) -> (Vec<u8>, u64) {
     let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
         .get(address.as_fixed_bytes())  else {
@@ -103,4 +130,602 @@ impl PrecompileCalls {
     pub fn address(&self) -> u64 {
         (*self).into()
     }
+
+    /// Maximum length of input bytes considered for the precompile call.
+    pub fn input_len(&self) -> Option<usize> {
+        match self {
+            Self::ECRecover => Some(128),
+            Self::Bn128Add => Some(128),
+            Self::Bn128Mul => Some(96),
+            _ => None,
+        }
+    }
+}
+
+lazy_static! {
+    /// Order of the secp256k1 curve as an EVM word.
+    pub static ref SECP256K1_N: Word = Word::from_little_endian(&SECP256K1_Q.to_bytes_le());
+    /// Modulus of the base field of BN254 as an EVM word.
+    pub static ref BN254_P: Word = Word::from_little_endian(&(Fq::ZERO - Fq::ONE).to_repr()) + 1;
+}
+
+/// Auxiliary data for the ecRecover precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcrecoverAuxData {
+    /// Keccak hash of the message being signed.
+    pub msg_hash: Word,
+    /// v-component of the signature.
+    pub sig_v: Word,
+    /// r-component of the signature.
+    pub sig_r: Word,
+    /// s-component of the signature.
+    pub sig_s: Word,
+    /// Address that was recovered, or zero if the recovery failed.
+    pub recovered_addr: Address,
+}
+
+impl EcrecoverAuxData {
+    /// Create a new instance of ecrecover auxiliary data from the call's input
+    /// and output bytes.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        // The input is right padded with zeroes to 128 bytes.
+        let mut input = input.to_vec();
+        input.resize(128, 0);
+
+        // On success, the precompile returns the address left padded to 32
+        // bytes, and nothing otherwise.
+        let recovered_addr = if output.len() == 32 {
+            Address::from_slice(&output[12..])
+        } else {
+            Address::zero()
+        };
+
+        Self {
+            msg_hash: Word::from_big_endian(&input[0x00..0x20]),
+            sig_v: Word::from_big_endian(&input[0x20..0x40]),
+            sig_r: Word::from_big_endian(&input[0x40..0x60]),
+            sig_s: Word::from_big_endian(&input[0x60..0x80]),
+            recovered_addr,
+        }
+    }
+
+    /// Recovery ID of the signature, which is only defined for `v` being 27
+    /// or 28.
+    pub fn recovery_id(&self) -> Option<u8> {
+        (self.sig_v == Word::from(27) || self.sig_v == Word::from(28))
+            .then(|| (self.sig_v.low_u64() - 27) as u8)
+    }
+
+    /// Whether the input is well-formed, i.e. `v` is 27 or 28 and both `r`
+    /// and `s` are in `[1, n)`, where `n` is the order of secp256k1.  Only
+    /// well-formed inputs are passed to the signature verifier, the others
+    /// fail without any recovery attempt.
+    pub fn is_well_formed(&self) -> bool {
+        let is_in_range = |value: &Word| !value.is_zero() && *value < *SECP256K1_N;
+        self.recovery_id().is_some() && is_in_range(&self.sig_r) && is_in_range(&self.sig_s)
+    }
+
+    /// Recover the public key which signed the message hash, if any.
+    pub fn recover_pk(&self) -> Option<Secp256k1Affine> {
+        let recovery_id = self.recovery_id()?;
+        recover_pk(
+            recovery_id,
+            &self.sig_r,
+            &self.sig_s,
+            &self.msg_hash.to_be_bytes(),
+        )
+        .ok()
+    }
+}
+
+/// Auxiliary data for the hash precompiles, whose digest is looked up from
+/// the RLC of the input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashAuxData {
+    /// Bytes that are hashed, i.e. the call data.
+    pub input_bytes: Vec<u8>,
+    /// Digest returned by the precompile.
+    pub output_bytes: Vec<u8>,
+}
+
+/// Maximum length of the base, exponent and modulus of the MODEXP calls which
+/// can be proven. It bounds both the lengths handled by the MODEXP gadget and
+/// the number of limbs of the modexp circuit, and the circuit input builder
+/// fails with [`Error::UnsupportedPrecompileInput`](crate::Error) on longer
+/// operands.
+pub const MODEXP_SIZE_LIMIT: usize = 32;
+
+/// Length of the header of the MODEXP input, i.e. the lengths of the base, the
+/// exponent and the modulus as 32-byte words.
+pub const MODEXP_HEADER_LENGTH: usize = 96;
+
+/// Auxiliary data for the MODEXP precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpAuxData {
+    /// Lengths of the base, exponent and modulus, from the header of the input.
+    pub input_lens: [Word; 3],
+    /// Base, exponent and modulus, which are only parsed if all their lengths
+    /// are at most `MODEXP_SIZE_LIMIT`.
+    pub inputs: [Word; 3],
+    /// Result of the exponentiation.
+    pub output: Word,
+    /// Bytes read by the precompile, i.e. the header and the base, exponent
+    /// and modulus with their lengths in it.
+    pub input_bytes: Vec<u8>,
+    /// Bytes returned by the precompile, i.e. the result left padded to the
+    /// length of the modulus.
+    pub output_bytes: Vec<u8>,
+}
+
+impl ModExpAuxData {
+    /// Create a new instance of MODEXP auxiliary data from the call's input
+    /// and output bytes.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut header = input[..input.len().min(MODEXP_HEADER_LENGTH)].to_vec();
+        header.resize(MODEXP_HEADER_LENGTH, 0);
+        let input_lens = [0, 1, 2].map(|i| Word::from_big_endian(&header[32 * i..32 * (i + 1)]));
+
+        let mut aux_data = Self {
+            input_lens,
+            input_bytes: input.to_vec(),
+            output_bytes: output.to_vec(),
+            ..Default::default()
+        };
+        // The input is right padded with zeroes, or truncated, to the lengths
+        // of the header. Calls with unsupported lengths are rejected by the
+        // circuit input builder and keep their whole input.
+        if aux_data.is_supported() {
+            let lens = input_lens.map(|len| len.as_usize());
+            aux_data
+                .input_bytes
+                .resize(MODEXP_HEADER_LENGTH + lens.iter().sum::<usize>(), 0);
+            let mut offset = MODEXP_HEADER_LENGTH;
+            aux_data.inputs = lens.map(|len| {
+                offset += len;
+                Word::from_big_endian(&aux_data.input_bytes[offset - len..offset])
+            });
+            aux_data.output = Word::from_big_endian(output);
+        }
+        aux_data
+    }
+
+    /// Whether the lengths of the base, exponent and modulus are all
+    /// supported by the circuits.
+    pub fn is_supported(&self) -> bool {
+        self.input_lens
+            .iter()
+            .all(|len| *len <= Word::from(MODEXP_SIZE_LIMIT))
+    }
+}
+
+/// Decode a BN254 G1 point from its coordinates as given to the ecAdd and
+/// ecMul precompiles, where `(0, 0)` is the point at infinity.  Returns `None`
+/// if a coordinate is not in the base field or the point is not on the curve.
+pub fn bn254_g1_point([x, y]: &[Word; 2]) -> Option<G1Affine> {
+    if x.is_zero() && y.is_zero() {
+        return Some(G1Affine::identity());
+    }
+    let x = Option::<Fq>::from(Fq::from_repr(x.to_le_bytes()))?;
+    let y = Option::<Fq>::from(Fq::from_repr(y.to_le_bytes()))?;
+    G1Affine::from_xy(x, y).into()
+}
+
+/// Read the `(x, y)` coordinates of a BN254 G1 point from 64 big-endian bytes.
+fn bn254_coordinates(bytes: &[u8]) -> [Word; 2] {
+    [
+        Word::from_big_endian(&bytes[0x00..0x20]),
+        Word::from_big_endian(&bytes[0x20..0x40]),
+    ]
+}
+
+/// Auxiliary data for the ecAdd precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddAuxData {
+    /// First point, as `(x, y)`.
+    pub p: [Word; 2],
+    /// Second point, as `(x, y)`.
+    pub q: [Word; 2],
+    /// Sum of the points, or zero if the call failed.
+    pub r: [Word; 2],
+}
+
+impl EcAddAuxData {
+    /// Create a new instance of ecAdd auxiliary data from the call's input
+    /// and output bytes.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        // The input is right padded with zeroes to 128 bytes, and nothing is
+        // returned on failure.
+        let mut input = input.to_vec();
+        input.resize(128, 0);
+        let r = if output.len() == 64 {
+            bn254_coordinates(output)
+        } else {
+            [Word::zero(); 2]
+        };
+
+        Self {
+            p: bn254_coordinates(&input[0x00..0x40]),
+            q: bn254_coordinates(&input[0x40..0x80]),
+            r,
+        }
+    }
+
+    /// Whether all the coordinates are in the base field, which is checked
+    /// before the points are given to the ECC circuit.
+    pub fn is_in_field(&self) -> bool {
+        self.p.iter().chain(self.q.iter()).all(|c| *c < *BN254_P)
+    }
+
+    /// Whether both points are valid, i.e. on the curve or at infinity.
+    pub fn is_valid(&self) -> bool {
+        bn254_g1_point(&self.p).is_some() && bn254_g1_point(&self.q).is_some()
+    }
+}
+
+/// Length of the encoding of a pair of points given to the ecPairing
+/// precompile, i.e. a G1 point as 2 words followed by a G2 point as 4 words.
+pub const EC_PAIRING_PAIR_LENGTH: usize = 192;
+
+/// Gas cost of an ecPairing call with an input of `input_len` bytes, as of
+/// EIP-1108. Only the whole pairs are charged.
+pub fn ec_pairing_gas_cost(input_len: u64) -> u64 {
+    GasCost::PRECOMPILE_BN256PAIRING.as_u64()
+        + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.as_u64()
+            * (input_len / EC_PAIRING_PAIR_LENGTH as u64)
+}
+
+/// Decode a BN254 G2 point from its coordinates as given to the ecPairing
+/// precompile, i.e. `(x_im, x_re, y_im, y_re)`, where `(0, 0, 0, 0)` is the
+/// point at infinity.  Returns `None` if a coordinate is not in the base field,
+/// or the point is not on the twist or not in the subgroup of order `r`.
+pub fn bn254_g2_point(coordinates: &[Word; 4]) -> Option<G2Affine> {
+    if coordinates.iter().all(|c| c.is_zero()) {
+        return Some(G2Affine::identity());
+    }
+    let fq = |c: &Word| Option::<Fq>::from(Fq::from_repr(c.to_le_bytes()));
+    let [x_im, x_re, y_im, y_re] = coordinates;
+    let x = Fq2 {
+        c0: fq(x_re)?,
+        c1: fq(x_im)?,
+    };
+    let y = Fq2 {
+        c0: fq(y_re)?,
+        c1: fq(y_im)?,
+    };
+    let point = Option::<G2Affine>::from(G2Affine::from_xy(x, y))?;
+    // The twist has a cofactor, so [r - 1]Q = -Q must be checked.
+    let projective = G2::from(point);
+    (projective * (Fr::ZERO - Fr::ONE) == -projective).then_some(point)
+}
+
+/// Pair of points given to the ecPairing precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingPair {
+    /// G1 point, as `(x, y)`.
+    pub g1: [Word; 2],
+    /// G2 point, as `(x_im, x_re, y_im, y_re)`.
+    pub g2: [Word; 4],
+}
+
+impl EcPairingPair {
+    /// Whether both points are valid, i.e. in their groups or at infinity.
+    pub fn is_valid(&self) -> bool {
+        bn254_g1_point(&self.g1).is_some() && bn254_g2_point(&self.g2).is_some()
+    }
+
+    /// The 192 bytes encoding the pair in the input.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.g1
+            .iter()
+            .chain(self.g2.iter())
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+/// Auxiliary data for the ecPairing precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingAuxData {
+    /// Pairs of points of the input, which are only parsed if its length is
+    /// a multiple of `EC_PAIRING_PAIR_LENGTH`.
+    pub pairs: Vec<EcPairingPair>,
+    /// Whether the product of the pairings is one, i.e. the output of a
+    /// successful call.
+    pub output: bool,
+}
+
+impl EcPairingAuxData {
+    /// Create a new instance of ecPairing auxiliary data from the call's
+    /// input and output bytes.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let pairs = if input.len() % EC_PAIRING_PAIR_LENGTH == 0 {
+            input
+                .chunks(EC_PAIRING_PAIR_LENGTH)
+                .map(|chunk| EcPairingPair {
+                    g1: bn254_coordinates(&chunk[0x00..0x40]),
+                    g2: [0x40, 0x60, 0x80, 0xa0]
+                        .map(|offset| Word::from_big_endian(&chunk[offset..offset + 0x20])),
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        // On success, the precompile returns 0 or 1 as a word, and nothing
+        // otherwise.
+        Self {
+            pairs,
+            output: output.len() == 32 && output[31] == 1,
+        }
+    }
+
+    /// Whether all the points are valid.
+    pub fn is_valid(&self) -> bool {
+        self.pairs.iter().all(EcPairingPair::is_valid)
+    }
+
+    /// The input bytes of the call.
+    pub fn input_bytes(&self) -> Vec<u8> {
+        self.pairs
+            .iter()
+            .flat_map(EcPairingPair::to_bytes)
+            .collect()
+    }
+}
+
+/// Auxiliary data for the ecMul precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulAuxData {
+    /// Point, as `(x, y)`.
+    pub p: [Word; 2],
+    /// Scalar, which isn't reduced modulo the order of the group.
+    pub s: Word,
+    /// Product of the point by the scalar, or zero if the call failed.
+    pub r: [Word; 2],
+}
+
+impl EcMulAuxData {
+    /// Create a new instance of ecMul auxiliary data from the call's input
+    /// and output bytes.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        // The input is right padded with zeroes to 96 bytes, and nothing is
+        // returned on failure.
+        let mut input = input.to_vec();
+        input.resize(96, 0);
+        let r = if output.len() == 64 {
+            bn254_coordinates(output)
+        } else {
+            [Word::zero(); 2]
+        };
+
+        Self {
+            p: bn254_coordinates(&input[0x00..0x40]),
+            s: Word::from_big_endian(&input[0x40..0x60]),
+            r,
+        }
+    }
+
+    /// Whether the coordinates of the point are in the base field, which is
+    /// checked before the point is given to the ECC circuit.
+    pub fn is_in_field(&self) -> bool {
+        self.p.iter().all(|c| *c < *BN254_P)
+    }
+
+    /// Whether the point is valid, i.e. on the curve or at infinity.
+    pub fn is_valid(&self) -> bool {
+        bn254_g1_point(&self.p).is_some()
+    }
+}
+
+/// Length of the input of the BLAKE2 F precompile, as of EIP-152: the number
+/// of rounds as a 4-byte big-endian integer, the state vector `h`, the
+/// message block `m` and the offset counters `t` as 8-byte little-endian
+/// words, and the final block indicator `f`.
+pub const BLAKE2F_INPUT_LENGTH: usize = 213;
+
+/// Offset of the final block indicator in the input of the BLAKE2 F
+/// precompile.
+pub const BLAKE2F_FINAL_FLAG_OFFSET: usize = BLAKE2F_INPUT_LENGTH - 1;
+
+/// Auxiliary data for the BLAKE2 F precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blake2fAuxData {
+    /// Number of rounds of the compression function.
+    pub rounds: u32,
+    /// State vector.
+    pub h: [u64; 8],
+    /// Message block vector.
+    pub m: [u64; 16],
+    /// Offset counters.
+    pub t: [u64; 2],
+    /// Final block indicator, which is 0 or 1 for a successful call.
+    pub f: u8,
+    /// New state vector returned by the precompile.
+    pub output: [u64; 8],
+}
+
+impl Blake2fAuxData {
+    /// Create a new instance of BLAKE2 F auxiliary data from the call's input
+    /// and output bytes. The input is only parsed if it has the expected
+    /// length, and the output is only set for a successful call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        if input.len() != BLAKE2F_INPUT_LENGTH {
+            return Self::default();
+        }
+        let words = |bytes: &[u8]| -> Vec<u64> {
+            bytes
+                .chunks(8)
+                .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                .collect()
+        };
+        let mut aux_data = Self {
+            rounds: u32::from_be_bytes(input[0..4].try_into().unwrap()),
+            h: words(&input[4..68]).try_into().unwrap(),
+            m: words(&input[68..196]).try_into().unwrap(),
+            t: words(&input[196..212]).try_into().unwrap(),
+            f: input[BLAKE2F_FINAL_FLAG_OFFSET],
+            output: [0; 8],
+        };
+        if output.len() == 64 {
+            aux_data.output = words(output).try_into().unwrap();
+        }
+        aux_data
+    }
+
+    /// The input bytes of the call.
+    pub fn input_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.rounds.to_be_bytes().to_vec();
+        for word in self.h.iter().chain(self.m.iter()).chain(self.t.iter()) {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.push(self.f);
+        bytes
+    }
+
+    /// The output bytes of a successful call.
+    pub fn output_bytes(&self) -> Vec<u8> {
+        self.output
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+}
+
+/// Auxiliary data attached to a precompile step, used to assign its
+/// execution gadget.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrecompileAuxData {
+    /// ecRecover.
+    Ecrecover(EcrecoverAuxData),
+    /// SHA2-256.
+    Sha256(HashAuxData),
+    /// RIPEMD-160.
+    Ripemd160(HashAuxData),
+    /// MODEXP.
+    Modexp(ModExpAuxData),
+    /// ecAdd.
+    EcAdd(EcAddAuxData),
+    /// ecMul.
+    EcMul(EcMulAuxData),
+    /// ecPairing.
+    EcPairing(EcPairingAuxData),
+    /// BLAKE2 F.
+    Blake2F(Blake2fAuxData),
+}
+
+/// Event of a precompile call which is verified by a dedicated sub-circuit.
+#[derive(Clone, Debug)]
+pub enum PrecompileEvent {
+    /// ecRecover with a well-formed input.
+    Ecrecover(EcrecoverAuxData),
+    /// SHA2-256 with enough gas.
+    Sha256(HashAuxData),
+    /// RIPEMD-160 with enough gas.
+    Ripemd160(HashAuxData),
+    /// MODEXP with enough gas and supported input lengths.
+    Modexp(ModExpAuxData),
+    /// ecAdd with enough gas and coordinates in the base field.
+    EcAdd(EcAddAuxData),
+    /// ecMul with enough gas and coordinates in the base field.
+    EcMul(EcMulAuxData),
+    /// ecPairing with enough gas and a non-empty input made of whole pairs.
+    EcPairing(EcPairingAuxData),
+    /// Successful BLAKE2 F.
+    Blake2F(Blake2fAuxData),
+}
+
+/// Collection of the precompile events in a block.
+#[derive(Clone, Debug, Default)]
+pub struct PrecompileEvents {
+    /// All the precompile events.
+    pub events: Vec<PrecompileEvent>,
+}
+
+impl PrecompileEvents {
+    /// Get all the ecrecover events.
+    pub fn get_ecrecover_events(&self) -> Vec<EcrecoverAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::Ecrecover(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the SHA2-256 events.
+    pub fn get_sha256_events(&self) -> Vec<HashAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::Sha256(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the RIPEMD-160 events.
+    pub fn get_ripemd160_events(&self) -> Vec<HashAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::Ripemd160(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the MODEXP events.
+    pub fn get_modexp_events(&self) -> Vec<ModExpAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::Modexp(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ecAdd events.
+    pub fn get_ec_add_events(&self) -> Vec<EcAddAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::EcAdd(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ecMul events.
+    pub fn get_ec_mul_events(&self) -> Vec<EcMulAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::EcMul(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the ecPairing events.
+    pub fn get_ec_pairing_events(&self) -> Vec<EcPairingAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::EcPairing(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all the BLAKE2 F events.
+    pub fn get_blake2f_events(&self) -> Vec<Blake2fAuxData> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                PrecompileEvent::Blake2F(aux_data) => Some(aux_data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
 }
@@ -92,6 +92,12 @@ impl Account {
     pub fn is_empty(&self) -> bool {
         self.nonce == 0 && self.balance.is_zero() && self.code_hash.eq(&EMPTY_CODE_HASH)
     }
+
+    /// Return if a contract can't be created at this account's address, which
+    /// is the case when it already has a nonce or code (EIP-684).
+    pub fn is_contract_address_collision(&self) -> bool {
+        self.nonce != 0 || !(self.code_hash.is_zero() || self.code_hash.eq(&EMPTY_CODE_HASH))
+    }
 }
 
 /// In-memory key-value database that represents the Ethereum State Trie.
@@ -108,9 +114,15 @@ pub struct StateDB {
     // state before current transaction, to calculate gas cost for some opcodes like sstore.
     // So both dirty storage and committed storage are needed.
     dirty_storage: HashMap<(Address, Word), Word>,
+    // `transient_storage` contains the EIP-1153 transient storage, which is discarded when the
+    // current transaction finishes.
+    transient_storage: HashMap<(Address, Word), Word>,
     // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
     // `true`. These accounts will be reset once `commit_tx` is called.
     destructed_account: HashSet<Address>,
+    // Accounts that have been created in the current transaction. Under EIP-6780, `SELFDESTRUCT`
+    // only deletes an account when it was created in the same transaction.
+    created_account: HashSet<Address>,
     refund: u64,
 }
 
@@ -195,6 +207,21 @@ impl StateDB {
         self.dirty_storage.insert((*addr, *key), *value);
     }
 
+    /// Get a reference to the transient storage value of `addr` at `key`.
+    /// Returns false and a zero [`Word`] when the `key` wasn't written in the
+    /// current transaction.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> (bool, &Word) {
+        match self.transient_storage.get(&(*addr, *key)) {
+            Some(v) => (true, v),
+            None => (false, &VALUE_ZERO),
+        }
+    }
+
+    /// Set transient storage value at `addr` and `key`.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
     /// Get nonce of account with `addr`.
     pub fn get_nonce(&self, addr: &Address) -> u64 {
         let (_, account) = self.get_account(addr);
@@ -248,6 +275,21 @@ impl StateDB {
         self.destructed_account.insert(addr);
     }
 
+    /// Set account as created in the current transaction.
+    pub fn mark_account_created_in_tx(&mut self, addr: Address) {
+        self.created_account.insert(addr);
+    }
+
+    /// Unset account as created in the current transaction.
+    pub fn unmark_account_created_in_tx(&mut self, addr: &Address) {
+        self.created_account.remove(addr);
+    }
+
+    /// Check whether `addr` has been created in the current transaction.
+    pub fn is_account_created_in_tx(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
     /// Retrieve refund.
     pub fn refund(&self) -> u64 {
         self.refund
@@ -258,7 +300,8 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list, refund and transient storage, and commit dirty
+    /// storage.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
@@ -269,10 +312,13 @@ impl StateDB {
             *ptr = value;
         }
         self.dirty_storage = HashMap::new();
+        self.transient_storage = HashMap::new();
         for addr in self.destructed_account.clone() {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
+        self.destructed_account = HashSet::new();
+        self.created_account = HashSet::new();
         self.refund = 0;
     }
 }
@@ -115,6 +115,11 @@ impl Bytecode {
         self
     }
 
+    /// Push zero with `PUSH0`, which has no immediate data
+    pub fn op_push0(&mut self) -> &mut Self {
+        self.write_op(OpcodeId::PUSH0)
+    }
+
     /// Add marker
     pub fn add_marker(&mut self, marker: String) -> &mut Self {
         self.insert_marker(&marker, self.num_opcodes);
@@ -218,7 +223,9 @@ impl FromStr for OpcodeWithData {
     #[allow(clippy::manual_range_contains)]
     fn from_str(op: &str) -> Result<Self, Self::Err> {
         let err = || Error::InvalidAsmError(op.to_string());
-        if let Some(push) = op.strip_prefix("PUSH") {
+        if op == "PUSH0" {
+            Ok(OpcodeWithData::Opcode(OpcodeId::PUSH0))
+        } else if let Some(push) = op.strip_prefix("PUSH") {
             let n_value: Vec<_> = push.splitn(3, ['(', ')']).collect();
             let n = n_value[0].parse::<u8>().map_err(|_| err())?;
             if n < 1 || n > 32 {
@@ -255,7 +262,7 @@ impl<'a> Iterator for BytecodeIterator<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().map(|byte| {
             let op = OpcodeId::from(byte.value);
-            if op.is_push() {
+            if op.is_push_with_data() {
                 let n = op.data_len();
                 let mut value = vec![0u8; n];
                 for value_byte in value.iter_mut() {
@@ -277,7 +284,7 @@ impl From<Vec<u8>> for Bytecode {
         while let Some(byte) = input_iter.next() {
             let op = OpcodeId::from(*byte);
             code.write_op(op);
-            if op.is_push() {
+            if op.is_push_with_data() {
                 let n = op.postfix().expect("opcode with postfix");
                 for _ in 0..n {
                     match input_iter.next() {
@@ -315,14 +322,14 @@ macro_rules! bytecode_internal {
     ($code:ident, ) => {};
     // PUSHX op codes
     ($code:ident, $x:ident ($v:expr) $($rest:tt)*) => {{
-        debug_assert!($crate::evm_types::OpcodeId::$x.is_push(), "invalid push");
+        debug_assert!($crate::evm_types::OpcodeId::$x.is_push_with_data(), "invalid push");
         let n = $crate::evm_types::OpcodeId::$x.postfix().expect("opcode with postfix");
         $code.push(n, $v);
         $crate::bytecode_internal!($code, $($rest)*);
     }};
-    // Default opcode without any inputs
+    // Default opcode without any inputs, including PUSH0
     ($code:ident, $x:ident $($rest:tt)*) => {{
-        debug_assert!(!$crate::evm_types::OpcodeId::$x.is_push(), "invalid push");
+        debug_assert!(!$crate::evm_types::OpcodeId::$x.is_push_with_data(), "invalid push");
         $code.write_op($crate::evm_types::OpcodeId::$x);
         $crate::bytecode_internal!($code, $($rest)*);
     }};
@@ -468,12 +475,17 @@ impl_other_opcodes! {
     (op_chainid, CHAINID),
     (op_selfbalance, SELFBALANCE),
     // (op_basefee, BASEFEE), ignored
+    (op_blobhash, BLOBHASH, index: I),
+    (op_blobbasefee, BLOBBASEFEE),
     (op_pop, POP),
     (op_mload, MLOAD, offset: O),
     (op_mstore, MSTORE, offset: O, value: V),
     (op_mstore8, MSTORE8, offset: O, value: V),
     (op_sload, SLOAD, offset: O),
     (op_sstore, SSTORE, offset: O, value: V),
+    (op_tload, TLOAD, offset: O),
+    (op_tstore, TSTORE, offset: O, value: V),
+    (op_mcopy, MCOPY, dest_offset: D, offset: B, size: C),
     (op_jump, JUMP, counter: C),
     (op_jumpi, JUMPI, counter: C), // branch not included
     (op_pc, PC),
@@ -540,6 +552,7 @@ mod tests {
         let code = bytecode! {
             PUSH8(0x123)
             POP
+            PUSH0
             PUSH24(0x321)
             PUSH32(0x432)
             MUL
@@ -559,6 +572,8 @@ mod tests {
             PUSH1(5)
             PUSH2(0xa)
             MUL
+            PUSH0
+            ADD
             STOP
         };
         let mut code2 = Bytecode::default();
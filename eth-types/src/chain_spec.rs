@@ -0,0 +1,137 @@
+//! Chain specification, which selects the hardfork rules applied to a block.
+
+use serde::{Deserialize, Serialize};
+
+/// Chain id of the Ethereum mainnet.
+pub const MAINNET_CHAIN_ID: u64 = 1;
+
+/// Hardforks supported by the circuits, in activation order.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Hardfork {
+    /// London: EIP-1559 fee market and the BASEFEE opcode.
+    London,
+    /// Shanghai: warm COINBASE (EIP-3651), PUSH0 (EIP-3855) and the initcode
+    /// size limit (EIP-3860).
+    Shanghai,
+    /// Cancun: transient storage (EIP-1153), blob transactions (EIP-4844,
+    /// EIP-7516) and MCOPY (EIP-5656).
+    #[default]
+    Cancun,
+}
+
+impl Hardfork {
+    /// Returns true if the rules of `hardfork` are active in this hardfork.
+    pub fn is_enabled(&self, hardfork: Hardfork) -> bool {
+        *self >= hardfork
+    }
+}
+
+/// Activation schedule of the hardforks of a chain. London is activated by
+/// block number, while later hardforks are activated by block timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSpec {
+    /// Number of the first London block.
+    pub london_block: u64,
+    /// Timestamp of the first Shanghai block, `None` if not scheduled.
+    pub shanghai_time: Option<u64>,
+    /// Timestamp of the first Cancun block, `None` if not scheduled.
+    pub cancun_time: Option<u64>,
+}
+
+impl Default for ChainSpec {
+    fn default() -> Self {
+        Self::with_hardfork(Hardfork::default())
+    }
+}
+
+impl ChainSpec {
+    /// Specification of the Ethereum mainnet.
+    pub fn mainnet() -> Self {
+        Self {
+            london_block: 12_965_000,
+            shanghai_time: Some(1_681_338_455),
+            cancun_time: Some(1_710_338_135),
+        }
+    }
+
+    /// Specification of a chain which runs `hardfork` since genesis.
+    pub fn with_hardfork(hardfork: Hardfork) -> Self {
+        let activation_time =
+            |activated_by: Hardfork| hardfork.is_enabled(activated_by).then_some(0);
+        Self {
+            london_block: 0,
+            shanghai_time: activation_time(Hardfork::Shanghai),
+            cancun_time: activation_time(Hardfork::Cancun),
+        }
+    }
+
+    /// Specification of a known chain, or `None` for other chains, whose
+    /// specification has to be provided explicitly.
+    pub fn from_chain_id(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            MAINNET_CHAIN_ID => Some(Self::mainnet()),
+            _ => None,
+        }
+    }
+
+    /// Returns the hardfork of the block with the given number and timestamp,
+    /// or `None` if the block is older than London, which is not supported.
+    pub fn hardfork(&self, number: u64, timestamp: u64) -> Option<Hardfork> {
+        let is_active = |activation_time: Option<u64>| {
+            activation_time.map_or(false, |activation_time| timestamp >= activation_time)
+        };
+        if number < self.london_block {
+            None
+        } else if is_active(self.cancun_time) {
+            Some(Hardfork::Cancun)
+        } else if is_active(self.shanghai_time) {
+            Some(Hardfork::Shanghai)
+        } else {
+            Some(Hardfork::London)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_hardforks() {
+        let spec = ChainSpec::mainnet();
+        assert_eq!(spec.hardfork(12_964_999, 1_628_166_812), None);
+        assert_eq!(
+            spec.hardfork(12_965_000, 1_628_166_822),
+            Some(Hardfork::London)
+        );
+        assert_eq!(
+            spec.hardfork(17_034_870, 1_681_338_455),
+            Some(Hardfork::Shanghai)
+        );
+        assert_eq!(
+            spec.hardfork(19_426_587, 1_710_338_135),
+            Some(Hardfork::Cancun)
+        );
+    }
+
+    #[test]
+    fn known_chains() {
+        assert_eq!(
+            ChainSpec::from_chain_id(MAINNET_CHAIN_ID),
+            Some(ChainSpec::mainnet())
+        );
+        assert_eq!(ChainSpec::from_chain_id(1337), None);
+    }
+
+    #[test]
+    fn genesis_hardforks() {
+        for hardfork in [Hardfork::London, Hardfork::Shanghai, Hardfork::Cancun] {
+            assert_eq!(
+                ChainSpec::with_hardfork(hardfork).hardfork(0, 0),
+                Some(hardfork)
+            );
+        }
+    }
+}
@@ -12,6 +12,14 @@ pub enum Error {
     TracingError(String),
     /// Block is missing information about number or base_fee
     IncompleteBlock,
+    /// Block is older than the earliest hardfork supported by the circuits
+    UnsupportedHardfork,
+    /// Chain id without a known chain specification
+    UnsupportedChain(u64),
+    /// Transaction envelope type that isn't supported (EIP-2718)
+    UnsupportedTxType(u64),
+    /// Blob base fee of the block doesn't fit in 256 bits
+    BlobBaseFeeOverflow,
     /// Denotes that the byte in the bytecode does not match with any Opcode ID.
     InvalidOpcodeIdByte(u8),
     /// Error while parsing an `Instruction/Opcode`.
@@ -76,6 +76,19 @@ impl From<Gas> for u64 {
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
+/// Maximum size in bytes of a deployed contract code (EIP-170).
+pub const MAX_CODE_SIZE: u64 = 0x6000;
+/// Maximum size in bytes of the initialization code of a contract (EIP-3860).
+pub const MAX_INIT_CODE_SIZE: u64 = 2 * MAX_CODE_SIZE;
+/// First byte of a deployed contract code that is rejected (EIP-3541).
+pub const INVALID_INIT_CODE_FIRST_BYTE: u8 = 0xef;
+/// Maximum number of blob versioned hashes a transaction can carry, bounded by
+/// the max blob gas per block over the gas per blob (EIP-4844).
+pub const MAX_BLOB_HASHES_PER_TX: usize = 6;
+/// Minimum blob base fee in wei (EIP-4844).
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Controls the maximum rate of change of the blob base fee (EIP-4844).
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
 
 /// Defines the gas consumption.
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Default)]
@@ -121,6 +134,9 @@ impl GasCost {
     /// Constant cost for copying every word, specifically in the case of SHA3
     /// opcode.
     pub const COPY_SHA3: Self = Self(6);
+    /// Cost for every word of the initialization code of a contract creation
+    /// (EIP-3860).
+    pub const INIT_CODE_WORD: Self = Self(2);
     /// Constant cost for accessing account or storage key
     pub const WARM_ACCESS: Self = Self(100);
     /// Constant cost for a cold SLOAD
@@ -177,10 +193,15 @@ impl GasCost {
     pub const PRECOMPILE_BN256ADD: Self = Self(150);
     /// Base gas price for precompile call: BN256 scalar multiplication
     pub const PRECOMPILE_BN256MUL: Self = Self(6000);
-    /// Base gas price for precompile call: BN256 pairing per point
+    /// Base gas price for precompile call: BN256 pairing check
     pub const PRECOMPILE_BN256PAIRING: Self = Self(45000);
-    /// Base gas price for precompile call: MODEXP
-    pub const PRECOMPILE_MODEXP: Self = Self(0);
+    /// Gas price per pair of points for precompile call: BN256 pairing check
+    pub const PRECOMPILE_BN256PAIRING_PER_PAIR: Self = Self(34000);
+    /// Minimum gas price for precompile call: MODEXP, see EIP-2565
+    pub const PRECOMPILE_MODEXP: Self = Self(200);
+    /// Divisor of the product of the multiplication complexity and the
+    /// iteration count in the gas price of MODEXP, see EIP-2565
+    pub const PRECOMPILE_MODEXP_QUAD_DIVISOR: Self = Self(3);
     /// Base gas price for precompile call: BLAKE2F
     pub const PRECOMPILE_BLAKE2F: Self = Self(0);
 }
@@ -1,7 +1,7 @@
 //! Utility functions to help calculate gas
 
-use super::GasCost;
-use crate::Word;
+use super::{GasCost, BLOB_BASE_FEE_UPDATE_FRACTION, MIN_BLOB_BASE_FEE};
+use crate::{chain_spec::Hardfork, Word};
 
 /// Calculate memory expansion gas cost by current and next memory word size.
 pub fn memory_expansion_gas_cost(curr_memory_word_size: u64, next_memory_word_size: u64) -> u64 {
@@ -34,6 +34,16 @@ pub fn memory_copier_gas_cost(
         }
 }
 
+/// Calculate the gas charged for the initialization code of a contract
+/// creation, which is only charged since Shanghai (EIP-3860).
+pub fn init_code_gas_cost(hardfork: Hardfork, init_code_length: u64) -> u64 {
+    if hardfork.is_enabled(Hardfork::Shanghai) {
+        GasCost::INIT_CODE_WORD.as_u64() * ((init_code_length + 31) / 32)
+    } else {
+        0
+    }
+}
+
 /// Calculate EIP 150 gas passed to callee.
 pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
     let capped_gas = gas_left - gas_left / 64;
@@ -47,3 +57,75 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
 
     capped_gas
 }
+
+/// Calculate the blob base fee from the excess blob gas of a block
+/// (EIP-4844). Returns `None` if the fee doesn't fit in 256 bits.
+pub fn blob_base_fee(excess_blob_gas: u64) -> Option<Word> {
+    fake_exponential(
+        MIN_BLOB_BASE_FEE.into(),
+        excess_blob_gas.into(),
+        BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+    )
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor
+/// expansion, as specified in EIP-4844. Returns `None` if an intermediate
+/// value overflows 256 bits.
+pub fn fake_exponential(factor: Word, numerator: Word, denominator: Word) -> Option<Word> {
+    let mut i = Word::one();
+    let mut output = Word::zero();
+    let mut numerator_accum = factor.checked_mul(denominator)?;
+    while !numerator_accum.is_zero() {
+        output = output.checked_add(numerator_accum)?;
+        numerator_accum = numerator_accum.checked_mul(numerator)? / denominator.checked_mul(i)?;
+        i += Word::one();
+    }
+    Some(output / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fake_exponential_reference_values() {
+        // Test vectors of the EIP-4844 reference implementation.
+        for (factor, numerator, denominator, expected) in [
+            (1u64, 0u64, 1u64, 1u64),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6),
+            (1, 4, 2, 6),
+            (1, 3, 1, 16),
+            (1, 6, 2, 18),
+            (1, 4, 1, 49),
+            (1, 8, 2, 50),
+            (10, 8, 2, 542),
+            (11, 8, 2, 596),
+            (1, 5, 1, 136),
+            (1, 5, 2, 11),
+            (2, 5, 2, 23),
+            (1, 50000000, 2225652, 5709098764),
+            (1, 380928, BLOB_BASE_FEE_UPDATE_FRACTION, 1),
+        ] {
+            assert_eq!(
+                fake_exponential(factor.into(), numerator.into(), denominator.into()),
+                Some(expected.into()),
+            );
+        }
+    }
+
+    #[test]
+    fn blob_base_fee_reference_values() {
+        for (excess_blob_gas, expected) in [
+            (0u64, 1u64),
+            (2314057, 1),
+            (2314058, 2),
+            (10 * 1024 * 1024, 23),
+        ] {
+            assert_eq!(blob_base_fee(excess_blob_gas), Some(expected.into()));
+        }
+        // A fee that doesn't fit in 256 bits is rejected instead of saturated.
+        assert_eq!(blob_base_fee(u64::MAX), None);
+    }
+}
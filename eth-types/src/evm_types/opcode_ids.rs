@@ -1,5 +1,5 @@
 //! Doc this
-use crate::{error::Error, evm_types::GasCost};
+use crate::{chain_spec::Hardfork, error::Error, evm_types::GasCost};
 use core::fmt::Debug;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -95,6 +95,8 @@ pub enum OpcodeId {
     JUMPDEST,
 
     // PUSHn
+    /// `PUSH0`
+    PUSH0,
     /// `PUSH1`
     PUSH1,
     /// `PUSH2`
@@ -279,10 +281,20 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
+    /// `BLOBBASEFEE`
+    BLOBBASEFEE,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
     SSTORE,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
+    /// `MCOPY`
+    MCOPY,
     /// `GAS`
     GAS,
 
@@ -315,8 +327,14 @@ pub enum OpcodeId {
 }
 
 impl OpcodeId {
-    /// Returns `true` if the `OpcodeId` is a `PUSHn`.
+    /// Returns `true` if the `OpcodeId` is a `PUSHn`, including `PUSH0`.
     pub fn is_push(&self) -> bool {
+        self.as_u8() >= Self::PUSH0.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
+    }
+
+    /// Returns `true` if the `OpcodeId` is a `PUSHn` followed by immediate
+    /// data, i.e. `PUSH1` to `PUSH32`.
+    pub fn is_push_with_data(&self) -> bool {
         self.as_u8() >= Self::PUSH1.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
     }
 
@@ -402,6 +420,7 @@ impl OpcodeId {
             OpcodeId::PC => 0x58u8,
             OpcodeId::MSIZE => 0x59u8,
             OpcodeId::JUMPDEST => 0x5bu8,
+            OpcodeId::PUSH0 => 0x5fu8,
             OpcodeId::PUSH1 => 0x60u8,
             OpcodeId::PUSH2 => 0x61u8,
             OpcodeId::PUSH3 => 0x62u8,
@@ -490,8 +509,13 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
+            OpcodeId::BLOBBASEFEE => 0x4au8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::GAS => 0x5au8,
             OpcodeId::LOG0 => 0xa0u8,
             OpcodeId::LOG1 => 0xa1u8,
@@ -568,18 +592,24 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
+            OpcodeId::BLOBBASEFEE => GasCost::QUICK,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
             OpcodeId::MSTORE8 => GasCost::FASTEST,
             OpcodeId::SLOAD => GasCost::ZERO,
             OpcodeId::SSTORE => GasCost::ZERO,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::JUMP => GasCost::MID,
             OpcodeId::JUMPI => GasCost::SLOW,
             OpcodeId::PC => GasCost::QUICK,
             OpcodeId::MSIZE => GasCost::QUICK,
             OpcodeId::GAS => GasCost::QUICK,
             OpcodeId::JUMPDEST => GasCost::ONE,
+            OpcodeId::PUSH0 => GasCost::QUICK,
             OpcodeId::PUSH1 => GasCost::FASTEST,
             OpcodeId::PUSH2 => GasCost::FASTEST,
             OpcodeId::PUSH3 => GasCost::FASTEST,
@@ -722,18 +752,24 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
+            OpcodeId::BLOBBASEFEE => (1, 1024),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
             OpcodeId::MSTORE8 => (0, 1022),
             OpcodeId::SLOAD => (0, 1023),
             OpcodeId::SSTORE => (0, 1022),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::JUMP => (0, 1023),
             OpcodeId::JUMPI => (0, 1022),
             OpcodeId::PC => (1, 1024),
             OpcodeId::MSIZE => (1, 1024),
             OpcodeId::GAS => (1, 1024),
             OpcodeId::JUMPDEST => (0, 1024),
+            OpcodeId::PUSH0 => (1, 1024),
             OpcodeId::PUSH1 => (1, 1024),
             OpcodeId::PUSH2 => (1, 1024),
             OpcodeId::PUSH3 => (1, 1024),
@@ -833,6 +869,7 @@ impl OpcodeId {
                 | OpcodeId::MSTORE8
                 | OpcodeId::CALLDATACOPY
                 | OpcodeId::RETURNDATACOPY
+                | OpcodeId::MCOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
         )
@@ -850,7 +887,7 @@ impl OpcodeId {
     /// If operation has postfix returns it, otherwise None.
     pub fn postfix(&self) -> Option<u8> {
         if self.is_push() {
-            Some(self.as_u8() - OpcodeId::PUSH1.as_u8() + 1)
+            Some(self.as_u8() - OpcodeId::PUSH0.as_u8())
         } else if self.is_dup() {
             Some(self.as_u8() - OpcodeId::DUP1.as_u8() + 1)
         } else if self.is_swap() {
@@ -866,12 +903,41 @@ impl OpcodeId {
     /// push opcodes.
     pub fn data_len(&self) -> usize {
         if self.is_push() {
-            (self.as_u8() - OpcodeId::PUSH1.as_u8() + 1) as usize
+            (self.as_u8() - OpcodeId::PUSH0.as_u8()) as usize
         } else {
             0
         }
     }
 
+    /// Returns the earliest supported hardfork in which the opcode is
+    /// available.
+    pub const fn hardfork(&self) -> Hardfork {
+        match self {
+            OpcodeId::PUSH0 => Hardfork::Shanghai,
+            OpcodeId::TLOAD
+            | OpcodeId::TSTORE
+            | OpcodeId::MCOPY
+            | OpcodeId::BLOBHASH
+            | OpcodeId::BLOBBASEFEE => Hardfork::Cancun,
+            _ => Hardfork::London,
+        }
+    }
+
+    /// Returns true if the opcode is available in the given hardfork. Opcodes
+    /// which are not available yet are executed as invalid ones.
+    pub fn is_enabled_in(&self, hardfork: Hardfork) -> bool {
+        hardfork.is_enabled(self.hardfork())
+    }
+
+    /// Returns the opcodes which are introduced after London, so they are
+    /// invalid in the blocks of the earlier hardforks.
+    pub fn hardfork_gated_opcodes() -> Vec<Self> {
+        Self::valid_opcodes()
+            .into_iter()
+            .filter(|opcode| opcode.hardfork() != Hardfork::London)
+            .collect()
+    }
+
     /// Returns the all valid opcodes.
     pub fn valid_opcodes() -> Vec<Self> {
         (u8::MIN..=u8::MAX).fold(vec![], |mut acc, val| {
@@ -936,6 +1002,7 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
+            0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
             0x62u8 => OpcodeId::PUSH3,
@@ -1024,8 +1091,13 @@ impl From<u8> for OpcodeId {
             0x46u8 => OpcodeId::CHAINID,
             0x47u8 => OpcodeId::SELFBALANCE,
             0x48u8 => OpcodeId::BASEFEE,
+            0x49u8 => OpcodeId::BLOBHASH,
+            0x4au8 => OpcodeId::BLOBBASEFEE,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
+            0x5cu8 => OpcodeId::TLOAD,
+            0x5du8 => OpcodeId::TSTORE,
+            0x5eu8 => OpcodeId::MCOPY,
             0x5au8 => OpcodeId::GAS,
             0xa0u8 => OpcodeId::LOG0,
             0xa1u8 => OpcodeId::LOG1,
@@ -1089,6 +1161,7 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
+            "PUSH0" => OpcodeId::PUSH0,
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
             "PUSH3" => OpcodeId::PUSH3,
@@ -1156,7 +1229,6 @@ impl FromStr for OpcodeId {
             "RETURN" => OpcodeId::RETURN,
             "REVERT" => OpcodeId::REVERT,
             "INVALID" => OpcodeId::INVALID(0xfe),
-            "PUSH0" => OpcodeId::INVALID(0x5f),
             "SHA3" | "KECCAK256" => OpcodeId::SHA3,
             "ADDRESS" => OpcodeId::ADDRESS,
             "BALANCE" => OpcodeId::BALANCE,
@@ -1178,6 +1250,9 @@ impl FromStr for OpcodeId {
             "GASLIMIT" => OpcodeId::GASLIMIT,
             "SLOAD" => OpcodeId::SLOAD,
             "SSTORE" => OpcodeId::SSTORE,
+            "TLOAD" => OpcodeId::TLOAD,
+            "TSTORE" => OpcodeId::TSTORE,
+            "MCOPY" => OpcodeId::MCOPY,
             "GAS" => OpcodeId::GAS,
             "LOG0" => OpcodeId::LOG0,
             "LOG1" => OpcodeId::LOG1,
@@ -1193,6 +1268,8 @@ impl FromStr for OpcodeId {
             "SELFDESTRUCT" => OpcodeId::SELFDESTRUCT,
             "CHAINID" => OpcodeId::CHAINID,
             "BASEFEE" => OpcodeId::BASEFEE,
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            "BLOBBASEFEE" => OpcodeId::BLOBBASEFEE,
             _ => {
                 // Parse an invalid opcode value as reported by geth
                 lazy_static! {
@@ -1248,6 +1325,7 @@ mod opcode_ids_tests {
 
     #[test]
     fn postfix() {
+        assert_eq!(OpcodeId::PUSH0.postfix(), Some(0));
         assert_eq!(OpcodeId::PUSH1.postfix(), Some(1));
         assert_eq!(OpcodeId::PUSH10.postfix(), Some(10));
         assert_eq!(OpcodeId::LOG2.postfix(), Some(2));
@@ -1256,9 +1334,30 @@ mod opcode_ids_tests {
 
     #[test]
     fn data_len() {
+        assert_eq!(OpcodeId::PUSH0.data_len(), 0);
         assert_eq!(OpcodeId::PUSH1.data_len(), 1);
         assert_eq!(OpcodeId::PUSH10.data_len(), 10);
         assert_eq!(OpcodeId::LOG2.data_len(), 0);
         assert_eq!(OpcodeId::CALLCODE.data_len(), 0);
     }
+
+    #[test]
+    fn is_enabled_in() {
+        assert!(OpcodeId::BASEFEE.is_enabled_in(Hardfork::London));
+        assert!(!OpcodeId::PUSH0.is_enabled_in(Hardfork::London));
+        assert!(OpcodeId::PUSH0.is_enabled_in(Hardfork::Shanghai));
+        assert!(!OpcodeId::MCOPY.is_enabled_in(Hardfork::Shanghai));
+        assert!(OpcodeId::MCOPY.is_enabled_in(Hardfork::Cancun));
+        assert_eq!(
+            OpcodeId::hardfork_gated_opcodes(),
+            vec![
+                OpcodeId::BLOBHASH,
+                OpcodeId::BLOBBASEFEE,
+                OpcodeId::TLOAD,
+                OpcodeId::TSTORE,
+                OpcodeId::MCOPY,
+                OpcodeId::PUSH0,
+            ]
+        );
+    }
 }
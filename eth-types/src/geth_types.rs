@@ -1,12 +1,18 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
+    chain_spec::ChainSpec,
+    evm_types::{gas_utils::blob_base_fee, GasCost},
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
     ToWord, Word, U64,
 };
 use ethers_core::{
-    types::{transaction::response, NameOrAddress, TransactionRequest},
+    types::{
+        transaction::{eip2718::TypedTransaction, response},
+        Eip1559TransactionRequest, Eip2930TransactionRequest, NameOrAddress, OtherFields,
+        Signature, TransactionRequest,
+    },
     utils::get_contract_address,
 };
 use ethers_signers::{LocalWallet, Signer};
@@ -74,6 +80,8 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
@@ -87,6 +95,7 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             difficulty: block.difficulty,
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            blob_base_fee: block_blob_base_fee(block)?,
         })
     }
 }
@@ -100,6 +109,7 @@ impl BlockConstants {
         difficulty: Word,
         gas_limit: Word,
         base_fee: Word,
+        blob_base_fee: Word,
     ) -> BlockConstants {
         BlockConstants {
             coinbase,
@@ -108,13 +118,59 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            blob_base_fee,
         }
     }
 }
 
+/// Blob base fee of a block derived from its `excessBlobGas` header field
+/// (EIP-4844). Blocks without the field are priced at the minimum fee.
+pub fn block_blob_base_fee<TX>(block: &Block<TX>) -> Result<Word, Error> {
+    let excess_blob_gas = block
+        .other
+        .get_deserialized::<U64>("excessBlobGas")
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    blob_base_fee(excess_blob_gas.as_u64()).ok_or(Error::BlobBaseFeeOverflow)
+}
+
+/// Type of a transaction envelope (EIP-2718).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TxType {
+    /// Legacy transaction, signed following EIP-155
+    #[default]
+    Legacy = 0,
+    /// Access list transaction (EIP-2930)
+    Eip2930 = 1,
+    /// Dynamic fee transaction (EIP-1559)
+    Eip1559 = 2,
+}
+
+impl TxType {
+    /// Returns the type of a transaction from its optional type field, where
+    /// a missing type denotes a legacy transaction, or an error for a type
+    /// that isn't supported, like blob transactions (EIP-4844).
+    pub fn from_transaction_type(transaction_type: Option<U64>) -> Result<Self, Error> {
+        match transaction_type.map(|transaction_type| transaction_type.as_u64()) {
+            None | Some(0) => Ok(Self::Legacy),
+            Some(1) => Ok(Self::Eip2930),
+            Some(2) => Ok(Self::Eip1559),
+            Some(tx_type) => Err(Error::UnsupportedTxType(tx_type)),
+        }
+    }
+}
+
+impl Serialize for TxType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(*self as u64)
+    }
+}
+
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
+    /// Transaction type
+    pub tx_type: TxType,
     /// Sender address
     pub from: Address,
     /// Recipient address (None for contract creation)
@@ -128,11 +184,12 @@ pub struct Transaction {
     pub gas_limit: U64,
     /// Transfered value
     pub value: Word,
-    /// Gas Price
+    /// Gas Price, which is the effective gas price for a dynamic fee
+    /// transaction included in a block
     pub gas_price: Word,
-    /// Gas fee cap
+    /// Gas fee cap (max fee per gas)
     pub gas_fee_cap: Word,
-    /// Gas tip cap
+    /// Gas tip cap (max priority fee per gas)
     pub gas_tip_cap: Word,
     /// The compiled code of a contract OR the first 4 bytes of the hash of the
     /// invoked method signature and encoded parameters. For details see
@@ -140,6 +197,8 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844)
+    pub blob_versioned_hashes: Vec<Hash>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -152,41 +211,47 @@ pub struct Transaction {
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
         crate::Transaction {
+            transaction_type: Some(U64::from(tx.tx_type as u64)),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.to_word(),
             gas: tx.gas_limit.to_word(),
             value: tx.value,
             gas_price: Some(tx.gas_price),
-            max_priority_fee_per_gas: Some(tx.gas_fee_cap),
-            max_fee_per_gas: Some(tx.gas_tip_cap),
+            max_priority_fee_per_gas: Some(tx.gas_tip_cap),
+            max_fee_per_gas: Some(tx.gas_fee_cap),
             input: tx.call_data.clone(),
             access_list: tx.access_list.clone(),
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
+            other: blob_versioned_hashes_to_other(&tx.blob_versioned_hashes),
             ..Default::default()
         }
     }
 }
 
-impl From<&crate::Transaction> for Transaction {
-    fn from(tx: &crate::Transaction) -> Transaction {
-        Transaction {
+impl TryFrom<&crate::Transaction> for Transaction {
+    type Error = Error;
+
+    fn try_from(tx: &crate::Transaction) -> Result<Self, Self::Error> {
+        Ok(Transaction {
+            tx_type: TxType::from_transaction_type(tx.transaction_type)?,
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.as_u64().into(),
             gas_limit: tx.gas.as_u64().into(),
             value: tx.value,
             gas_price: tx.gas_price.unwrap_or_default(),
-            gas_fee_cap: tx.max_priority_fee_per_gas.unwrap_or_default(),
-            gas_tip_cap: tx.max_fee_per_gas.unwrap_or_default(),
+            gas_fee_cap: tx.max_fee_per_gas.unwrap_or_default(),
+            gas_tip_cap: tx.max_priority_fee_per_gas.unwrap_or_default(),
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            blob_versioned_hashes: blob_versioned_hashes_from_other(&tx.other),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-        }
+        })
     }
 }
 
@@ -205,6 +270,48 @@ impl From<&Transaction> for TransactionRequest {
     }
 }
 
+impl From<&Transaction> for Eip1559TransactionRequest {
+    fn from(tx: &Transaction) -> Eip1559TransactionRequest {
+        Eip1559TransactionRequest {
+            from: Some(tx.from),
+            to: tx.to.map(NameOrAddress::Address),
+            gas: Some(tx.gas_limit.to_word()),
+            value: Some(tx.value),
+            data: Some(tx.call_data.clone()),
+            nonce: Some(tx.nonce.to_word()),
+            access_list: tx.access_list.clone().unwrap_or_default(),
+            max_priority_fee_per_gas: Some(tx.gas_tip_cap),
+            max_fee_per_gas: Some(tx.gas_fee_cap),
+            ..Default::default()
+        }
+    }
+}
+
+/// Key of the blob versioned hashes in the extra fields of an RPC transaction.
+const BLOB_VERSIONED_HASHES_KEY: &str = "blobVersionedHashes";
+
+/// Read the blob versioned hashes out of the extra fields of an RPC
+/// transaction. A missing or malformed entry is treated as no blobs.
+pub fn blob_versioned_hashes_from_other(other: &OtherFields) -> Vec<Hash> {
+    other
+        .get_deserialized(BLOB_VERSIONED_HASHES_KEY)
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Build the extra fields of an RPC transaction carrying the given blob
+/// versioned hashes. No entry is added when there are no blobs.
+pub fn blob_versioned_hashes_to_other(hashes: &[Hash]) -> OtherFields {
+    let mut other = OtherFields::default();
+    if !hashes.is_empty() {
+        other.insert(
+            BLOB_VERSIONED_HASHES_KEY.to_string(),
+            serde_json::to_value(hashes).expect("hashes are serializable"),
+        );
+    }
+    other
+}
+
 fn recover_v(v: u64, chain_id: u64) -> u64 {
     let addition = chain_id * 2 + 35;
     if v >= addition {
@@ -229,9 +336,7 @@ impl Transaction {
             secp256k1::Fq::from_repr(sig_s_le),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
+        let msg = self.rlp_unsigned(chain_id);
         let msg_hash: [u8; 32] = Keccak256::digest(&msg)
             .as_slice()
             .to_vec()
@@ -254,6 +359,71 @@ impl Transaction {
         })
     }
 
+    /// Return the encoding of the transaction which is hashed for signing.
+    /// - Legacy: rlp([nonce, gasPrice, gas, to, value, data, chain_id, 0, 0])
+    /// - EIP-2930: 0x01 || rlp([chain_id, nonce, gasPrice, gas, to, value, data, access_list])
+    /// - EIP-1559: 0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas,
+    ///   to, value, data, access_list])
+    pub fn rlp_unsigned(&self, chain_id: u64) -> Bytes {
+        self.to_typed_transaction(chain_id).rlp()
+    }
+
+    /// Return the encoding of the signed transaction, which is hashed for the
+    /// transaction hash.
+    /// - Legacy: rlp([nonce, gasPrice, gas, to, value, data, v, r, s])
+    /// - EIP-2930: 0x01 || rlp([chain_id, nonce, gasPrice, gas, to, value, data, access_list,
+    ///   y_parity, r, s])
+    /// - EIP-1559: 0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas,
+    ///   to, value, data, access_list, y_parity, r, s])
+    pub fn rlp_signed(&self, chain_id: u64) -> Bytes {
+        let signature = Signature {
+            r: self.r,
+            s: self.s,
+            v: self.v,
+        };
+        self.to_typed_transaction(chain_id).rlp_signed(&signature)
+    }
+
+    /// Convert to the transaction request of its type, ready to be signed.
+    pub fn to_typed_transaction(&self, chain_id: u64) -> TypedTransaction {
+        match self.tx_type {
+            TxType::Legacy => {
+                let req: TransactionRequest = self.into();
+                req.chain_id(chain_id).into()
+            }
+            TxType::Eip2930 => {
+                let req: TransactionRequest = self.into();
+                Eip2930TransactionRequest::new(
+                    req.chain_id(chain_id),
+                    self.access_list.clone().unwrap_or_default(),
+                )
+                .into()
+            }
+            TxType::Eip1559 => {
+                let req: Eip1559TransactionRequest = self.into();
+                req.chain_id(chain_id).into()
+            }
+        }
+    }
+
+    /// Return the max fee per gas of the transaction, which is the gas price
+    /// for a legacy or access list transaction.
+    pub fn max_fee_per_gas(&self) -> Word {
+        match self.tx_type {
+            TxType::Legacy | TxType::Eip2930 => self.gas_price,
+            TxType::Eip1559 => self.gas_fee_cap,
+        }
+    }
+
+    /// Return the max priority fee per gas of the transaction, which is the
+    /// gas price for a legacy or access list transaction.
+    pub fn max_priority_fee_per_gas(&self) -> Word {
+        match self.tx_type {
+            TxType::Legacy | TxType::Eip2930 => self.gas_price,
+            TxType::Eip1559 => self.gas_tip_cap,
+        }
+    }
+
     /// Compute call data gas cost from call data
     pub fn call_data_gas_cost(&self) -> u64 {
         self.call_data
@@ -261,6 +431,24 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
+    /// Return the number of addresses and storage keys in the access list
+    pub fn access_list_len(&self) -> (u64, u64) {
+        self.access_list
+            .iter()
+            .flat_map(|access_list| access_list.0.iter())
+            .fold((0, 0), |(addresses, storage_keys), item| {
+                (addresses + 1, storage_keys + item.storage_keys.len() as u64)
+            })
+    }
+
+    /// Compute the gas cost of warming up the accounts and storage keys in the
+    /// access list (EIP-2930)
+    pub fn access_list_gas_cost(&self) -> u64 {
+        let (addresses, storage_keys) = self.access_list_len();
+        addresses * GasCost::ACCESS_LIST_ADDRESS.as_u64()
+            + storage_keys * GasCost::ACCESS_LIST_STORAGE.as_u64()
+    }
+
     /// Get the "to" address. If `to` is None then zero adddress
     pub fn to_or_zero(&self) -> Address {
         self.to.unwrap_or_default()
@@ -283,11 +471,14 @@ impl Transaction {
         block_number: U64,
     ) -> response::Transaction {
         response::Transaction {
+            transaction_type: Some(U64::from(self.tx_type as u64)),
             from: self.from,
             to: self.to,
             value: self.value,
             input: self.call_data.clone(),
             gas_price: Some(self.gas_price),
+            max_priority_fee_per_gas: Some(self.max_priority_fee_per_gas()),
+            max_fee_per_gas: Some(self.max_fee_per_gas()),
             access_list: self.access_list.clone(),
             nonce: self.nonce.to_word(),
             gas: self.gas_limit.to_word(),
@@ -297,6 +488,7 @@ impl Transaction {
             v: U64::from(self.v),
             block_number: Some(block_number),
             chain_id: Some(chain_id),
+            other: blob_versioned_hashes_to_other(&self.blob_versioned_hashes),
             ..response::Transaction::default()
         }
     }
@@ -307,6 +499,8 @@ impl Transaction {
 pub struct GethData {
     /// chain id
     pub chain_id: Word,
+    /// hardfork schedule the traces were generated with
+    pub chain_spec: ChainSpec,
     /// history hashes contains most recent 256 block hashes in history, where
     /// the lastest one is at history_hashes[history_hashes.len() - 1].
     pub history_hashes: Vec<Word>,
@@ -325,9 +519,13 @@ impl GethData {
             let wallet = wallets.get(&tx.from).unwrap();
             assert_eq!(Word::from(wallet.chain_id()), self.chain_id);
             let geth_tx: Transaction = (&*tx).into();
-            let req: TransactionRequest = (&geth_tx).into();
-            let sig = wallet.sign_transaction_sync(&req.chain_id(self.chain_id.as_u64()).into());
-            tx.v = U64::from(sig.v);
+            let chain_id = self.chain_id.as_u64();
+            let sig = wallet.sign_transaction_sync(&geth_tx.to_typed_transaction(chain_id));
+            // Typed transactions carry the y parity instead of an EIP-155 v
+            tx.v = U64::from(match geth_tx.tx_type {
+                TxType::Legacy => sig.v,
+                _ => recover_v(sig.v, chain_id),
+            });
             tx.r = sig.r;
             tx.s = sig.s;
         }
@@ -16,6 +16,7 @@ pub mod macros;
 pub mod error;
 #[macro_use]
 pub mod bytecode;
+pub mod chain_spec;
 pub mod evm_types;
 pub mod geth_types;
 pub mod sign_types;
@@ -34,7 +35,10 @@ use ethers_core::types;
 pub use ethers_core::{
     abi::ethereum_types::{BigEndianHash, U512},
     types::{
-        transaction::{eip2930::AccessList, response::Transaction},
+        transaction::{
+            eip2930::{AccessList, AccessListItem},
+            response::Transaction,
+        },
         Address, Block, Bytes, Signature, H160, H256, H64, U256, U64,
     },
 };
@@ -731,4 +735,17 @@ mod eth_types_test {
         assert_eq!(req.to, None);
         Ok(())
     }
+
+    #[test]
+    fn unsupported_tx_type_into_geth_tx() {
+        let tx = Transaction {
+            transaction_type: Some(U64::from(3)),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            geth_types::Transaction::try_from(&tx),
+            Err(Error::UnsupportedTxType(3))
+        ));
+    }
 }
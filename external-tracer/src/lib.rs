@@ -1,6 +1,7 @@
 //! This module generates traces by connecting to an external tracer
 
 use eth_types::{
+    chain_spec::ChainSpec,
     geth_types::{Account, BlockConstants, Transaction},
     Address, Error, GethExecTrace, Word,
 };
@@ -12,6 +13,8 @@ use std::collections::HashMap;
 pub struct TraceConfig {
     /// chain id
     pub chain_id: Word,
+    /// hardfork schedule
+    pub chain_spec: ChainSpec,
     /// history hashes contains most recent 256 block hashes in history, where
     /// the lastest one is at history_hashes[history_hashes.len() - 1].
     pub history_hashes: Vec<Word>,
@@ -79,6 +82,7 @@ pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
             && !(trace.return_value.starts_with("nonce too low")
                 || trace.return_value.starts_with("nonce too high")
                 || trace.return_value.starts_with("intrinsic gas too low")
+                || trace.return_value.starts_with("max initcode size exceeded")
                 || trace
                     .return_value
                     .starts_with("insufficient funds for gas * price + value"))
@@ -13,6 +13,7 @@ import (
 	"github.com/ethereum/go-ethereum/core/vm"
 	"github.com/ethereum/go-ethereum/eth/tracers/logger"
 	"github.com/ethereum/go-ethereum/params"
+	"github.com/holiman/uint256"
 )
 
 // Copied from github.com/ethereum/go-ethereum/internal/ethapi.ExecutionResult
@@ -83,12 +84,13 @@ func FormatLogs(logs []logger.StructLog) []StructLogRes {
 }
 
 type Block struct {
-	Coinbase   common.Address `json:"coinbase"`
-	Timestamp  *hexutil.Big   `json:"timestamp"`
-	Number     *hexutil.Big   `json:"number"`
-	Difficulty *hexutil.Big   `json:"difficulty"`
-	GasLimit   *hexutil.Big   `json:"gas_limit"`
-	BaseFee    *hexutil.Big   `json:"base_fee"`
+	Coinbase    common.Address `json:"coinbase"`
+	Timestamp   *hexutil.Big   `json:"timestamp"`
+	Number      *hexutil.Big   `json:"number"`
+	Difficulty  *hexutil.Big   `json:"difficulty"`
+	GasLimit    *hexutil.Big   `json:"gas_limit"`
+	BaseFee     *hexutil.Big   `json:"base_fee"`
+	BlobBaseFee *hexutil.Big   `json:"blob_base_fee"`
 }
 
 type Account struct {
@@ -99,6 +101,7 @@ type Account struct {
 }
 
 type Transaction struct {
+	Type       uint8           `json:"tx_type"`
 	From       common.Address  `json:"from"`
 	To         *common.Address `json:"to"`
 	Nonce      hexutil.Uint64  `json:"nonce"`
@@ -110,12 +113,23 @@ type Transaction struct {
 	CallData   hexutil.Bytes   `json:"call_data"`
 	AccessList []struct {
 		Address     common.Address `json:"address"`
-		StorageKeys []common.Hash  `json:"storage_keys"`
+		StorageKeys []common.Hash  `json:"storageKeys"`
 	} `json:"access_list"`
+	BlobHashes []common.Hash `json:"blob_versioned_hashes"`
+}
+
+// ChainSpec is the activation schedule of the hardforks supported by the
+// circuits. London is activated by block number and the later hardforks by
+// block timestamp, where a nil timestamp means the hardfork is not scheduled.
+type ChainSpec struct {
+	LondonBlock  uint64  `json:"london_block"`
+	ShanghaiTime *uint64 `json:"shanghai_time"`
+	CancunTime   *uint64 `json:"cancun_time"`
 }
 
 type TraceConfig struct {
-	ChainID *hexutil.Big `json:"chain_id"`
+	ChainID   *hexutil.Big `json:"chain_id"`
+	ChainSpec ChainSpec    `json:"chain_spec"`
 	// HistoryHashes contains most recent 256 block hashes in history,
 	// where the lastest one is at HistoryHashes[len(HistoryHashes)-1].
 	HistoryHashes []*hexutil.Big             `json:"history_hashes"`
@@ -143,19 +157,30 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		IstanbulBlock:       big.NewInt(0),
 		MuirGlacierBlock:    big.NewInt(0),
 		BerlinBlock:         big.NewInt(0),
-		LondonBlock:         big.NewInt(0),
+		LondonBlock:         new(big.Int).SetUint64(config.ChainSpec.LondonBlock),
+		ShanghaiTime:        config.ChainSpec.ShanghaiTime,
+		CancunTime:          config.ChainSpec.CancunTime,
 	}
 
 	var txsGasLimit uint64
 	blockGasLimit := toBigInt(config.Block.GasLimit).Uint64()
 	messages := make([]core.Message, len(config.Transactions))
 	for i, tx := range config.Transactions {
-		// If gas price is specified directly, the tx is treated as legacy type.
-		if tx.GasPrice != nil {
+		// Legacy txs pay the gas price specified directly, while dynamic fee
+		// txs come with the effective gas price already resolved against the
+		// block base fee.
+		if tx.Type == types.LegacyTxType && tx.GasPrice != nil {
 			tx.GasFeeCap = tx.GasPrice
 			tx.GasTipCap = tx.GasPrice
 		}
 
+		// Only blob txs carry versioned hashes, which geth requires to be
+		// non-empty when present.
+		var blobHashes []common.Hash
+		if len(tx.BlobHashes) > 0 {
+			blobHashes = tx.BlobHashes
+		}
+
 		txAccessList := make(types.AccessList, len(tx.AccessList))
 		for i, accessList := range tx.AccessList {
 			txAccessList[i].Address = accessList.Address
@@ -172,6 +197,10 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 			GasTipCap: toBigInt(tx.GasTipCap),
 			Data: tx.CallData,
 			AccessList: txAccessList,
+			// The blob fee cap is left at zero, which skips its check
+			// against the blob base fee as the base fee checks are skipped.
+			BlobGasFeeCap: big.NewInt(0),
+			BlobHashes: blobHashes,
 			SkipAccountChecks: false,
 		}
 
@@ -181,6 +210,9 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		return nil, fmt.Errorf("txs total gas: %d Exceeds block gas limit: %d", txsGasLimit, blockGasLimit)
 	}
 
+	// Shanghai rules are only active after the merge, so the difficulty is
+	// also exposed as the PREVRANDAO value to keep DIFFICULTY unchanged.
+	random := common.BigToHash(toBigInt(config.Block.Difficulty))
 	blockCtx := vm.BlockContext{
 		CanTransfer: core.CanTransfer,
 		Transfer:    core.Transfer,
@@ -196,7 +228,9 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		BlockNumber: toBigInt(config.Block.Number),
 		Time:        toBigInt(config.Block.Timestamp).Uint64(),
 		Difficulty:  toBigInt(config.Block.Difficulty),
+		Random:      &random,
 		BaseFee:     toBigInt(config.Block.BaseFee),
+		BlobBaseFee: toBigInt(config.Block.BlobBaseFee),
 		GasLimit:    blockGasLimit,
 	}
 
@@ -206,7 +240,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		stateDB.SetNonce(address, uint64(account.Nonce))
 		stateDB.SetCode(address, account.Code)
 		if account.Balance != nil {
-			stateDB.SetBalance(address, toBigInt(account.Balance))
+			stateDB.SetBalance(address, uint256.MustFromBig(toBigInt(account.Balance)))
 		}
 		for key, value := range account.Storage {
 			stateDB.SetState(address, key, value)
@@ -218,7 +252,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	executionResults := make([]*ExecutionResult, len(config.Transactions))
 	for i, message := range messages {
 		tracer := logger.NewStructLogger(config.LoggerConfig)
-		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Debug: true, Tracer: tracer, NoBaseFee: true})
+		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Tracer: tracer, NoBaseFee: true})
 
 		result, err := core.ApplyMessage(evm, &message, new(core.GasPool).AddGas(message.GasLimit))
 		if err != nil {
@@ -1,10 +1,10 @@
 module main
 
-go 1.16
+go 1.20
 
 require (
-	github.com/ethereum/go-ethereum v1.11.5
-	github.com/holiman/uint256 v1.2.0
+	github.com/ethereum/go-ethereum v1.13.14
+	github.com/holiman/uint256 v1.2.4
 )
 
 // Uncomment for debugging
@@ -53,7 +53,7 @@ impl Display for Error {
 #[cfg(test)]
 mod test {
     use crate::trace;
-    use eth_types::{Error, GethExecTrace};
+    use eth_types::{evm_types::OpcodeId, word, Error, GethExecTrace, Word};
 
     #[test]
     fn valid_tx() {
@@ -170,4 +170,141 @@ mod test {
             }
         }
     }
+
+    /// Traces a call to an account holding `code`, with Cancun activated from
+    /// genesis when `cancun` is set and Shanghai otherwise.
+    fn trace_code(code: &str, cancun: bool) -> GethExecTrace {
+        let cancun_time = if cancun { "0" } else { "null" };
+        let config = format!(
+            r#"{{
+                "chain_spec": {{
+                    "london_block": 0,
+                    "shanghai_time": 0,
+                    "cancun_time": {cancun_time}
+                }},
+                "block_constants": {{
+                    "gas_limit": "0x186a00"
+                }},
+                "accounts": {{
+                    "0x00000000000000000000000000000000000000ff": {{
+                        "code": "{code}"
+                    }}
+                }},
+                "transactions": [
+                    {{
+                        "from": "0x00000000000000000000000000000000000000fe",
+                        "to": "0x00000000000000000000000000000000000000ff",
+                        "gas_limit": "0x186a0"
+                    }}
+                ]
+            }}"#
+        );
+        let trace_string = trace(&config).unwrap();
+        let mut traces: Vec<GethExecTrace> = serde_json::from_str(&trace_string)
+            .map_err(Error::SerdeError)
+            .unwrap();
+        assert_eq!(traces.len(), 1);
+        traces.remove(0)
+    }
+
+    #[test]
+    fn cancun_transient_storage() {
+        // PUSH1 0x2a PUSH1 0x01 TSTORE PUSH1 0x01 TLOAD STOP
+        let code = "0x602a60015d60015c00";
+
+        let trace = trace_code(code, true);
+        assert!(!trace.invalid);
+        assert!(!trace.failed);
+        let ops = trace
+            .struct_logs
+            .iter()
+            .map(|step| step.op)
+            .collect::<Vec<_>>();
+        assert!(ops.contains(&OpcodeId::TSTORE));
+        assert!(ops.contains(&OpcodeId::TLOAD));
+        // The value stored is read back from the transient storage.
+        let stop = trace.struct_logs.last().unwrap();
+        assert_eq!(stop.op, OpcodeId::STOP);
+        assert_eq!(stop.stack.last().unwrap(), Word::from(0x2a));
+
+        // Before Cancun TSTORE is an invalid opcode.
+        let trace = trace_code(code, false);
+        assert!(!trace.invalid);
+        assert!(trace.failed);
+    }
+
+    #[test]
+    fn cancun_mcopy() {
+        // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 PUSH1 0x20 MCOPY
+        // PUSH1 0x20 MLOAD STOP
+        let code = "0x602a6000526020600060205e60205100";
+
+        let trace = trace_code(code, true);
+        assert!(!trace.invalid);
+        assert!(!trace.failed);
+        assert!(trace
+            .struct_logs
+            .iter()
+            .any(|step| step.op == OpcodeId::MCOPY));
+        // The copied word is loaded back from the destination.
+        let stop = trace.struct_logs.last().unwrap();
+        assert_eq!(stop.op, OpcodeId::STOP);
+        assert_eq!(stop.stack.last().unwrap(), Word::from(0x2a));
+
+        // Before Cancun MCOPY is an invalid opcode.
+        let trace = trace_code(code, false);
+        assert!(!trace.invalid);
+        assert!(trace.failed);
+    }
+
+    #[test]
+    fn cancun_blob_opcodes() {
+        // PUSH1 0x00 BLOBHASH BLOBBASEFEE STOP
+        let config = r#"{
+            "chain_spec": {
+                "london_block": 0,
+                "shanghai_time": 0,
+                "cancun_time": 0
+            },
+            "block_constants": {
+                "gas_limit": "0x186a00",
+                "blob_base_fee": "0x17"
+            },
+            "accounts": {
+                "0x00000000000000000000000000000000000000fe": {
+                    "balance": "0x10000000"
+                },
+                "0x00000000000000000000000000000000000000ff": {
+                    "code": "0x6000494a00"
+                }
+            },
+            "transactions": [
+                {
+                    "from": "0x00000000000000000000000000000000000000fe",
+                    "to": "0x00000000000000000000000000000000000000ff",
+                    "gas_limit": "0x186a0",
+                    "blob_versioned_hashes": [
+                        "0x01000000000000000000000000000000000000000000000000000000000000aa"
+                    ]
+                }
+            ]
+        }"#;
+        let trace_string = trace(config).unwrap();
+        let trace: Vec<GethExecTrace> = serde_json::from_str(&trace_string)
+            .map_err(Error::SerdeError)
+            .unwrap();
+        assert!(!trace[0].invalid);
+        assert!(!trace[0].failed);
+
+        // The blob base fee is pushed on top of the first versioned hash.
+        let stop = trace[0].struct_logs.last().unwrap();
+        assert_eq!(stop.op, OpcodeId::STOP);
+        assert_eq!(
+            stop.stack.0,
+            vec![
+                word!("0x01000000000000000000000000000000000000000000000000000000000000aa"),
+                Word::from(0x17),
+            ]
+        );
+    }
 }
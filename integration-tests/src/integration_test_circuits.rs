@@ -1,9 +1,9 @@
-use crate::{get_client, GenDataOutput};
+use crate::{get_client, GenDataOutput, CHAIN_HARDFORK};
 use bus_mapping::{
     circuit_input_builder::{BuilderClient, CircuitInputBuilder, CircuitsParams},
     mock::BlockData,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{chain_spec::ChainSpec, geth_types::GethData};
 use halo2_proofs::{
     dev::{CellValue, MockProver},
     halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -60,6 +60,33 @@ const MAX_EXP_STEPS: usize = 1000;
 
 const MAX_KECCAK_ROWS: usize = 15000;
 
+/// MAX_ECRECOVER
+const MAX_ECRECOVER: usize = 0;
+
+/// MAX_SHA256_ROWS
+const MAX_SHA256_ROWS: usize = 0;
+
+/// MAX_RIPEMD160_ROWS
+const MAX_RIPEMD160_ROWS: usize = 0;
+
+/// MAX_MODEXP_ROWS
+const MAX_MODEXP_ROWS: usize = 0;
+
+/// MAX_EC_ADD
+const MAX_EC_ADD: usize = 0;
+
+/// MAX_EC_MUL
+const MAX_EC_MUL: usize = 0;
+
+/// MAX_EC_PAIRING_PAIRS
+const MAX_EC_PAIRING_PAIRS: usize = 0;
+
+/// MAX_BLAKE2F_ROUNDS
+const MAX_BLAKE2F_ROUNDS: usize = 0;
+
+/// MAX_RLP_ROWS
+const MAX_RLP_ROWS: usize = 0;
+
 const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_rws: MAX_RWS,
     max_txs: MAX_TXS,
@@ -69,6 +96,15 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_evm_rows: MAX_EVM_ROWS,
     max_exp_steps: MAX_EXP_STEPS,
     max_keccak_rows: MAX_KECCAK_ROWS,
+    max_ecrecover: MAX_ECRECOVER,
+    max_sha256_rows: MAX_SHA256_ROWS,
+    max_ripemd160_rows: MAX_RIPEMD160_ROWS,
+    max_modexp_rows: MAX_MODEXP_ROWS,
+    max_ec_add: MAX_EC_ADD,
+    max_ec_mul: MAX_EC_MUL,
+    max_ec_pairing_pairs: MAX_EC_PAIRING_PAIRS,
+    max_blake2f_rounds: MAX_BLAKE2F_ROUNDS,
+    max_rlp_rows: MAX_RLP_ROWS,
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 20;
@@ -334,7 +370,13 @@ async fn gen_inputs(
     eth_types::Block<eth_types::Transaction>,
 ) {
     let cli = get_client();
-    let cli = BuilderClient::new(cli, CIRCUITS_PARAMS).await.unwrap();
+    let cli = BuilderClient::new_with_chain_spec(
+        cli,
+        ChainSpec::with_hardfork(CHAIN_HARDFORK),
+        CIRCUITS_PARAMS,
+    )
+    .await
+    .unwrap();
 
     cli.gen_inputs(block_num).await.unwrap()
 }
@@ -10,7 +10,10 @@ use bus_mapping::{
     rpc::GethClient,
 };
 use env_logger::Env;
-use eth_types::Address;
+use eth_types::{
+    chain_spec::{ChainSpec, Hardfork},
+    Address,
+};
 use ethers::{
     abi,
     core::{k256::ecdsa::SigningKey, types::Bytes},
@@ -31,6 +34,8 @@ use url::Url;
 
 /// Geth dev chain ID
 pub const CHAIN_ID: u64 = 1337;
+/// Hardfork run by the geth dev chain since genesis
+pub const CHAIN_HARDFORK: Hardfork = Hardfork::London;
 /// Path to the test contracts
 pub const CONTRACTS_PATH: &str = "contracts";
 /// List of contracts as (ContractName, ContractSolidityFile)
@@ -151,8 +156,9 @@ pub struct CompiledContract {
 /// Build circuit input builder for a block
 pub async fn build_circuit_input_builder_block(block_num: u64) {
     let cli = get_client();
-    let cli = BuilderClient::new(
+    let cli = BuilderClient::new_with_chain_spec(
         cli,
+        ChainSpec::with_hardfork(CHAIN_HARDFORK),
         CircuitsParams {
             max_rws: 800000,
             max_txs: 10,
@@ -162,6 +168,15 @@ pub async fn build_circuit_input_builder_block(block_num: u64) {
             max_evm_rows: 0,
             max_exp_steps: 1000,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp_rows: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_ec_pairing_pairs: 0,
+            max_blake2f_rounds: 0,
+            max_rlp_rows: 0,
         },
     )
     .await
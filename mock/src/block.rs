@@ -1,7 +1,7 @@
 //! Mock Block definition and builder related methods.
 
 use crate::{MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY, MOCK_GASLIMIT};
-use eth_types::{Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
+use eth_types::{chain_spec::ChainSpec, Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
 use ethers_core::types::{Bloom, OtherFields};
 
 #[derive(Clone, Debug)]
@@ -35,6 +35,8 @@ pub struct MockBlock {
     // Also, the field is stored in the block_table since we don't have a chain_config
     // structure/table.
     pub(crate) chain_id: Word,
+    // Hardfork schedule of the chain, which is not part of the block either.
+    pub(crate) chain_spec: ChainSpec,
 }
 
 impl Default for MockBlock {
@@ -63,6 +65,7 @@ impl Default for MockBlock {
             mix_hash: Hash::zero(),
             nonce: H64::zero(),
             chain_id: *MOCK_CHAIN_ID,
+            chain_spec: ChainSpec::default(),
         }
     }
 }
@@ -90,7 +93,13 @@ impl From<MockBlock> for Block<Transaction> {
             transactions: mock
                 .transactions
                 .iter_mut()
-                .map(|mock_tx| (mock_tx.chain_id(mock.chain_id).to_owned()).into())
+                .map(|mock_tx| {
+                    (mock_tx
+                        .chain_id(mock.chain_id)
+                        .effective_gas_price(mock.base_fee_per_gas)
+                        .to_owned())
+                    .into()
+                })
                 .collect::<Vec<Transaction>>(),
             size: Some(mock.size),
             mix_hash: Some(mock.mix_hash),
@@ -274,6 +283,12 @@ impl MockBlock {
         self
     }
 
+    /// Set chain_spec field for the MockBlock.
+    pub fn chain_spec(&mut self, chain_spec: ChainSpec) -> &mut Self {
+        self.chain_spec = chain_spec;
+        self
+    }
+
     /// Finalizes the current MockBlock under construction returning a new
     /// instance to it.
     pub fn build(&mut self) -> Self {
@@ -2,6 +2,7 @@
 
 use crate::{eth, MockAccount, MockBlock, MockTransaction};
 use eth_types::{
+    chain_spec::ChainSpec,
     geth_types::{Account, BlockConstants, GethData},
     Block, Bytecode, Error, GethExecTrace, Transaction, Word,
 };
@@ -80,6 +81,8 @@ pub use external_tracer::LoggerConfig;
 pub struct TestContext<const NACC: usize, const NTX: usize> {
     /// chain id
     pub chain_id: Word,
+    /// hardfork schedule
+    pub chain_spec: ChainSpec,
     /// Account list
     pub accounts: [Account; NACC],
     /// history hashes contains most recent 256 block hashes in history, where
@@ -95,6 +98,7 @@ impl<const NACC: usize, const NTX: usize> From<TestContext<NACC, NTX>> for GethD
     fn from(ctx: TestContext<NACC, NTX>) -> GethData {
         GethData {
             chain_id: ctx.chain_id,
+            chain_spec: ctx.chain_spec,
             history_hashes: ctx.history_hashes,
             eth_block: ctx.eth_block,
             geth_traces: ctx.geth_traces.to_vec(),
@@ -157,6 +161,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         func_block(&mut block, transactions.clone()).build();
 
         let chain_id = block.chain_id;
+        let chain_spec = block.chain_spec;
         let block = Block::<Transaction>::from(block);
         let accounts: [Account; NACC] = accounts
             .iter()
@@ -168,6 +173,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
 
         let geth_traces = gen_geth_traces(
             chain_id,
+            chain_spec,
             block.clone(),
             accounts.to_vec(),
             history_hashes.clone(),
@@ -187,6 +193,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
 
         Ok(Self {
             chain_id,
+            chain_spec,
             accounts,
             history_hashes: history_hashes.unwrap_or_default(),
             eth_block: block,
@@ -239,6 +246,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
 /// Block
 pub fn gen_geth_traces(
     chain_id: Word,
+    chain_spec: ChainSpec,
     block: Block<Transaction>,
     accounts: Vec<Account>,
     history_hashes: Option<Vec<Word>>,
@@ -246,6 +254,7 @@ pub fn gen_geth_traces(
 ) -> Result<Vec<GethExecTrace>, Error> {
     let trace_config = TraceConfig {
         chain_id,
+        chain_spec,
         history_hashes: history_hashes.unwrap_or_default(),
         block_constants: BlockConstants::try_from(&block)?,
         accounts: accounts
@@ -255,8 +264,8 @@ pub fn gen_geth_traces(
         transactions: block
             .transactions
             .iter()
-            .map(eth_types::geth_types::Transaction::from)
-            .collect(),
+            .map(eth_types::geth_types::Transaction::try_from)
+            .collect::<Result<_, _>>()?,
         logger_config,
     };
     let traces = trace(&trace_config)?;
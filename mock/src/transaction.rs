@@ -2,12 +2,15 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{blob_versioned_hashes_to_other, Transaction as GethTransaction},
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::{
+        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
+        Eip2930TransactionRequest, TransactionRequest,
+    },
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -134,6 +137,7 @@ pub struct MockTransaction {
     pub s: Option<Word>,
     pub transaction_type: U64,
     pub access_list: AccessList,
+    pub blob_versioned_hashes: Vec<Hash>,
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
     pub chain_id: Word,
@@ -159,6 +163,7 @@ impl Default for MockTransaction {
             s: None,
             transaction_type: U64::zero(),
             access_list: AccessList::default(),
+            blob_versioned_hashes: Vec::new(),
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
             chain_id: *MOCK_CHAIN_ID,
@@ -189,14 +194,15 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id),
-            other: OtherFields::default(),
+            other: blob_versioned_hashes_to_other(&mock.blob_versioned_hashes),
         }
     }
 }
 
 impl From<MockTransaction> for GethTransaction {
     fn from(mock: MockTransaction) -> Self {
-        GethTransaction::from(&Transaction::from(mock))
+        GethTransaction::try_from(&Transaction::from(mock))
+            .expect("mock transaction of a supported type")
     }
 }
 
@@ -294,6 +300,12 @@ impl MockTransaction {
         self
     }
 
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
     /// Set max_priority_fee_per_gas field for the MockTransaction.
     pub fn max_priority_fee_per_gas(&mut self, max_priority_fee_per_gas: Word) -> &mut Self {
         self.max_priority_fee_per_gas = max_priority_fee_per_gas;
@@ -312,10 +324,23 @@ impl MockTransaction {
         self
     }
 
+    /// Set the gas price of a dynamic fee MockTransaction to the effective gas
+    /// price paid in a block with the given base fee.
+    pub(crate) fn effective_gas_price(&mut self, base_fee: Word) -> &mut Self {
+        if self.transaction_type.as_u64() == 2 {
+            self.gas_price = std::cmp::min(
+                self.max_priority_fee_per_gas.saturating_add(base_fee),
+                self.max_fee_per_gas,
+            );
+        }
+        self
+    }
+
     /// Consumes the mutable ref to the MockTransaction returning the structure
     /// by value.
     pub fn build(&mut self) -> Self {
-        let tx = TransactionRequest::new()
+        let chain_id = self.chain_id.low_u64();
+        let legacy_tx = TransactionRequest::new()
             .from(self.from.address())
             .to(self.to.clone().unwrap_or_default().address())
             .nonce(self.nonce)
@@ -323,7 +348,23 @@ impl MockTransaction {
             .data(self.input.clone())
             .gas(self.gas)
             .gas_price(self.gas_price)
-            .chain_id(self.chain_id.low_u64());
+            .chain_id(chain_id);
+        let tx: TypedTransaction = match self.transaction_type.as_u64() {
+            1 => Eip2930TransactionRequest::new(legacy_tx, self.access_list.clone()).into(),
+            2 => Eip1559TransactionRequest::new()
+                .from(self.from.address())
+                .to(self.to.clone().unwrap_or_default().address())
+                .nonce(self.nonce)
+                .value(self.value)
+                .data(self.input.clone())
+                .gas(self.gas)
+                .access_list(self.access_list.clone())
+                .max_priority_fee_per_gas(self.max_priority_fee_per_gas)
+                .max_fee_per_gas(self.max_fee_per_gas)
+                .chain_id(chain_id)
+                .into(),
+            _ => legacy_tx.into(),
+        };
 
         match (self.v, self.r, self.s) {
             (None, None, None) => {
@@ -332,10 +373,16 @@ impl MockTransaction {
                     let sig = self
                         .from
                         .as_wallet()
-                        .with_chain_id(self.chain_id.low_u64())
-                        .sign_transaction_sync(&tx.into());
+                        .with_chain_id(chain_id)
+                        .sign_transaction_sync(&tx);
+                    // Typed transactions carry the y parity instead of an
+                    // EIP-155 v.
+                    let v = match tx {
+                        TypedTransaction::Legacy(_) => sig.v,
+                        _ => sig.v - (chain_id * 2 + 35),
+                    };
                     // Set sig parameters
-                    self.sig_data((sig.v, sig.r, sig.s));
+                    self.sig_data((v, sig.r, sig.s));
                 }
             }
             (Some(_), Some(_), Some(_)) => (),
@@ -134,9 +134,11 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: U256::one(),
+                blob_base_fee: U256::one(),
             },
 
             transactions: vec![geth_types::Transaction {
+                tx_type: geth_types::TxType::Legacy,
                 from: st.from,
                 to: st.to,
                 nonce: U64::from(st.nonce),
@@ -147,6 +149,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: U256::zero(),
                 call_data: st.data,
                 access_list: None,
+                blob_versioned_hashes: Vec::new(),
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
@@ -239,6 +242,7 @@ pub fn run_test(
     // process the transaction
     let mut geth_data = eth_types::geth_types::GethData {
         chain_id: trace_config.chain_id,
+        chain_spec: trace_config.chain_spec,
         history_hashes: trace_config.history_hashes.clone(),
         geth_traces: geth_traces.clone(),
         accounts: trace_config.accounts.values().cloned().collect(),
@@ -257,6 +261,15 @@ pub fn run_test(
             max_evm_rows: 0,
             max_exp_steps: 5000,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp_rows: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_ec_pairing_pairs: 0,
+            max_blake2f_rounds: 0,
+            max_rlp_rows: 0,
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -282,6 +295,15 @@ pub fn run_test(
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_modexp_rows: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_ec_pairing_pairs: 0,
+            max_blake2f_rounds: 0,
+            max_rlp_rows: 0,
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr>::build(geth_data, circuits_params, Fr::from(0x100)).unwrap();
@@ -36,7 +36,6 @@ fn evm_states_stats() {
                 ExecutionState::ErrorInvalidOpcode
                     | ExecutionState::CREATE
                     | ExecutionState::CREATE2
-                    | ExecutionState::SELFDESTRUCT
             )
         },
         |opcode| match opcode {
@@ -74,7 +73,6 @@ fn state_states_stats() {
                 ExecutionState::ErrorInvalidOpcode
                     | ExecutionState::CREATE
                     | ExecutionState::CREATE2
-                    | ExecutionState::SELFDESTRUCT
             )
         },
         bytecode_prefix_op_big_rws,
@@ -98,6 +96,7 @@ fn copy_states_stats() {
                     | ExecutionState::LOG
                     | ExecutionState::CALLDATACOPY
                     | ExecutionState::EXTCODECOPY
+                    | ExecutionState::MCOPY
                     | ExecutionState::RETURN_REVERT
             )
         },
@@ -0,0 +1,699 @@
+//! The Blake2f circuit implementation, verifying the calls of the BLAKE2
+//! compression function F of the precompile of EIP-152.
+//!
+//! Each call takes `NUM_ROWS_PER_BLOCK` rows for its init block, each of its
+//! rounds and its finalization block. Every row holds the 4 words `a, b, c, d`
+//! as bits. The init block holds the input words and the initial work vector,
+//! each row of a round block holds the words mixed by one half of a call of
+//! the mixing function G, which are read from the rows where they were last
+//! mixed, and the finalization block computes the new state vector from the
+//! work vector of the last round. The state vector and the message block are
+//! carried over all the rows of the call.
+mod blake2f_bit;
+mod param;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Blake2fCircuit as TestBlake2fCircuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    blake2f_bit::{get_num_blake2f_blocks, multi_blake2f, to_bits, work_position, Blake2fRow},
+    param::*,
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::Blake2fAuxData;
+use eth_types::Field;
+use gadgets::util::{not, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+/// Blake2fConfig
+#[derive(Clone, Debug)]
+pub struct Blake2fCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_rows: [Column<Fixed>; NUM_ROWS_PER_BLOCK],
+    is_init: Column<Advice>,
+    is_round: Column<Advice>,
+    is_finalize: Column<Advice>,
+    permutations: [Column<Advice>; NUM_PERMUTATIONS],
+    rounds_left: Column<Advice>,
+    h: [Column<Advice>; NUM_WORDS_STATE],
+    m: [Column<Advice>; NUM_WORDS_MESSAGE],
+    words: [[Column<Advice>; NUM_BITS_PER_WORD]; NUM_WORDS_PER_ROW],
+    carry_a: [Column<Advice>; NUM_CARRY_BITS_A],
+    carry_c: [Column<Advice>; NUM_CARRY_BITS_C],
+    /// The columns for other circuits to lookup BLAKE2 F results
+    pub blake2f_table: Blake2fTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Blake2fCircuitConfigArgs<F: Field> {
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+/// Rotation of the row where the word of the work vector in `column` mixed on
+/// the row `offset` of a round block was last mixed, in this block or in the
+/// previous one.
+fn input_rotation(offset: usize, column: usize) -> i32 {
+    let g = offset / 2;
+    if offset % 2 == 1 {
+        return -1;
+    }
+    let word = G_WORDS[g][column];
+    let row = match (0..g).rev().find(|prev_g| G_WORDS[*prev_g][column] == word) {
+        Some(prev_g) => (2 * prev_g + 1) as i32,
+        None => work_position(word).0 as i32 - NUM_ROWS_PER_BLOCK as i32,
+    };
+    row - offset as i32
+}
+
+impl<F: Field> SubCircuitConfig<F> for Blake2fCircuitConfig<F> {
+    type ConfigArgs = Blake2fCircuitConfigArgs<F>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_rows = array_init::array_init(|_| meta.fixed_column());
+        let is_init = meta.advice_column();
+        let is_round = meta.advice_column();
+        let is_finalize = meta.advice_column();
+        let permutations = array_init::array_init(|_| meta.advice_column());
+        let rounds_left = meta.advice_column();
+        let h = array_init::array_init(|_| meta.advice_column());
+        let m = array_init::array_init(|_| meta.advice_column());
+        let words = array_init::array_init(|_| array_init::array_init(|_| meta.advice_column()));
+        let carry_a = array_init::array_init(|_| meta.advice_column());
+        let carry_c = array_init::array_init(|_| meta.advice_column());
+
+        let is_enabled = blake2f_table.is_enabled;
+        let input_rlc = blake2f_table.input_rlc;
+        let output_rlc = blake2f_table.output_rlc;
+
+        let q_start = q_rows[0];
+        let q_end = q_rows[NUM_ROWS_PER_BLOCK - 1];
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        // The bits of the word `idx` of the rows of a block, from its last row.
+        let query_word = |meta: &mut VirtualCells<F>, idx: usize, offset: i32| {
+            let rot = (idx / NUM_WORDS_PER_ROW) as i32 + offset - (NUM_ROWS_PER_BLOCK as i32 - 1);
+            query_bits(meta, &words[idx % NUM_WORDS_PER_ROW], rot)
+        };
+        // The bits of the word `v[i]` of the work vector once the block at
+        // `block_offset` blocks from the current one is done, from its last row.
+        let query_work = |meta: &mut VirtualCells<F>, i: usize, block_offset: i32| {
+            let (offset, column) = work_position(i);
+            let rot = offset as i32 + block_offset * NUM_ROWS_PER_BLOCK as i32
+                - (NUM_ROWS_PER_BLOCK as i32 - 1);
+            query_bits(meta, &words[column], rot)
+        };
+        let word_carry = || Expression::Constant(F::from_u128(1u128 << NUM_BITS_PER_WORD));
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in words
+                .iter()
+                .flatten()
+                .chain(carry_a.iter())
+                .chain(carry_c.iter())
+                .chain(permutations.iter())
+                .chain([is_init, is_round, is_finalize, is_enabled].iter())
+            {
+                cb.require_boolean("boolean bit", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Enforce fixed values on the first row
+        meta.create_gate("first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "is_finalize needs to be disabled on the dummy row",
+                meta.query_advice(is_finalize, Rotation::prev()),
+            );
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("block type", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_round = meta.query_advice(is_round, Rotation::cur());
+            let is_finalize = meta.query_advice(is_finalize, Rotation::cur());
+            cb.require_equal(
+                "a block is either an init, a round or a finalization block",
+                meta.query_advice(is_init, Rotation::cur())
+                    + is_round.clone()
+                    + is_finalize.clone(),
+                1.expr(),
+            );
+            cb.require_equal(
+                "a round block uses one of the message permutations",
+                sum::expr(
+                    permutations
+                        .iter()
+                        .map(|column| meta.query_advice(*column, Rotation::cur())),
+                ),
+                is_round,
+            );
+            // Only the last row of a finalization block is in the table.
+            cb.require_equal(
+                "is_enabled only on the last row of a finalization block",
+                meta.query_advice(is_enabled, Rotation::cur()),
+                meta.query_fixed(q_end, Rotation::cur()) * is_finalize,
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let prev_is_init = meta.query_advice(is_init, Rotation::prev());
+            let prev_rounds_left = meta.query_advice(rounds_left, Rotation::prev());
+            // A new call is started when the previous one is done or on the
+            // first block.
+            cb.require_equal(
+                "init block after a finalization block",
+                meta.query_advice(is_init, Rotation::cur()),
+                meta.query_fixed(q_first, Rotation::cur())
+                    + meta.query_advice(is_finalize, Rotation::prev()),
+            );
+            cb.condition(meta.query_advice(is_round, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "a round decrements the number of rounds left",
+                    meta.query_advice(rounds_left, Rotation::cur()),
+                    prev_rounds_left.clone() - 1.expr(),
+                );
+                // The first round uses the first permutation, and the next
+                // rounds use the next ones cyclically.
+                for (idx, column) in permutations.iter().enumerate() {
+                    let prev_column = permutations[(idx + NUM_PERMUTATIONS - 1) % NUM_PERMUTATIONS];
+                    cb.require_equal(
+                        "next message permutation",
+                        meta.query_advice(*column, Rotation::cur()),
+                        prev_is_init.clone() * (idx == 0).expr()
+                            + not::expr(prev_is_init.clone())
+                                * meta.query_advice(prev_column, Rotation::prev()),
+                    );
+                }
+            });
+            cb.condition(meta.query_advice(is_finalize, Rotation::cur()), |cb| {
+                cb.require_zero("all the rounds are done", prev_rounds_left);
+            });
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over block data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [is_init, is_round, is_finalize, rounds_left]
+                .iter()
+                .chain(permutations.iter())
+            {
+                cb.require_equal(
+                    "block data unchanged",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("carry over call data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in h.iter().chain(m.iter()).chain(std::iter::once(&input_rlc)) {
+                cb.require_equal(
+                    "call data unchanged",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                        * meta.query_advice(is_init, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("init", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // The input words, in the order of the input.
+            let input_words = (0..NUM_WORDS_INPUT)
+                .map(|idx| query_word(meta, idx, 0))
+                .collect::<Vec<_>>();
+            let [rounds, h_words @ .., t0, t1, f] = &input_words[..] else {
+                unreachable!("the input has {} words", NUM_WORDS_INPUT);
+            };
+            let (h_words, m_words) = h_words.split_at(NUM_WORDS_STATE);
+            for bit in rounds[NUM_BITS_PER_WORD / 2..].iter() {
+                cb.require_zero("the number of rounds has 4 bytes", bit.clone());
+            }
+            for bit in f[1..].iter() {
+                cb.require_zero("the final block indicator is a bit", bit.clone());
+            }
+            cb.require_equal(
+                "rounds left",
+                meta.query_advice(rounds_left, Rotation::cur()),
+                decode(rounds),
+            );
+            for (words, columns) in [(h_words, &h[..]), (m_words, &m[..])] {
+                for (word, column) in words.iter().zip(columns.iter()) {
+                    cb.require_equal(
+                        "call data",
+                        meta.query_advice(*column, Rotation::cur()),
+                        decode(word),
+                    );
+                }
+            }
+
+            // The input bytes are the number of rounds as a big-endian integer,
+            // the little-endian words and the final block indicator.
+            let input_bytes = to_le_bytes(&rounds[..NUM_BITS_PER_WORD / 2])
+                .into_iter()
+                .rev()
+                .chain(
+                    input_words[1..NUM_WORDS_INPUT - 1]
+                        .iter()
+                        .flat_map(|word| to_le_bytes(word)),
+                )
+                .chain(std::iter::once(f[0].clone()))
+                .collect::<Vec<_>>();
+            cb.require_equal(
+                "input rlc check",
+                meta.query_advice(input_rlc, Rotation::cur()),
+                rlc::expr(
+                    &input_bytes.into_iter().rev().collect::<Vec<_>>(),
+                    challenges.keccak_input(),
+                ),
+            );
+
+            // The initial work vector is the state vector and the IV, with the
+            // offset counters and the final block indicator mixed in the IV.
+            for i in 0..NUM_WORDS_WORK {
+                let bits = query_work(meta, i, 0);
+                let value = match i {
+                    0..=7 => meta.query_advice(h[i], Rotation::cur()),
+                    12 | 13 => {
+                        let t = if i == 12 { t0 } else { t1 };
+                        let iv_bits = to_bits::<NUM_BITS_PER_WORD>(IV[i - NUM_WORDS_STATE]);
+                        decode(
+                            &t.iter()
+                                .zip(iv_bits)
+                                .map(|(t, iv)| if iv { not::expr(t.clone()) } else { t.clone() })
+                                .collect::<Vec<_>>(),
+                        )
+                    }
+                    14 => {
+                        let iv = IV[i - NUM_WORDS_STATE];
+                        iv.expr() + f[0].clone() * ((!iv).expr() - iv.expr())
+                    }
+                    _ => IV[i - NUM_WORDS_STATE].expr(),
+                };
+                cb.require_equal("initial work vector", decode(&bits), value);
+            }
+            cb.gate(
+                meta.query_fixed(q_end, Rotation::cur())
+                    * meta.query_advice(is_init, Rotation::cur()),
+            )
+        });
+
+        // Each row of a round block does one half of a call of G.
+        for (offset, q_row) in q_rows.into_iter().enumerate() {
+            meta.create_gate("round", |meta| {
+                let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+                let [a, b, c, d] = array_init::array_init(|column| {
+                    query_bits(meta, &words[column], input_rotation(offset, column))
+                });
+                let [new_a, new_b, new_c, new_d] =
+                    array_init::array_init(|column| query_bits(meta, &words[column], 0));
+                let [r1, r2] = ROTATIONS[offset % 2];
+                let x = permutations
+                    .iter()
+                    .zip(SIGMA.iter())
+                    .map(|(column, sigma)| {
+                        meta.query_advice(*column, Rotation::cur())
+                            * meta.query_advice(m[sigma[offset]], Rotation::cur())
+                    })
+                    .fold(0.expr(), |acc, x| acc + x);
+                cb.require_equal(
+                    "a = a + b + x",
+                    decode(&new_a) + decode(&query_bits(meta, &carry_a, 0)) * word_carry(),
+                    decode(&a) + decode(&b) + x,
+                );
+                for (bit, xor_bit) in new_d.iter().zip(rotate_right(&xor_bits(&d, &new_a), r1)) {
+                    cb.require_equal("d = (d ^ a) >>> r1", bit.clone(), xor_bit);
+                }
+                cb.require_equal(
+                    "c = c + d",
+                    decode(&new_c) + decode(&query_bits(meta, &carry_c, 0)) * word_carry(),
+                    decode(&c) + decode(&new_d),
+                );
+                for (bit, xor_bit) in new_b.iter().zip(rotate_right(&xor_bits(&b, &new_c), r2)) {
+                    cb.require_equal("b = (b ^ c) >>> r2", bit.clone(), xor_bit);
+                }
+                cb.gate(
+                    meta.query_fixed(q_row, Rotation::cur())
+                        * meta.query_advice(is_round, Rotation::cur()),
+                )
+            });
+        }
+
+        meta.create_gate("finalize", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let mut output_bytes = vec![];
+            for i in 0..NUM_WORDS_STATE {
+                let h_bits = query_word(meta, i, FINALIZE_H_OFFSET as i32);
+                cb.require_equal(
+                    "state vector",
+                    decode(&h_bits),
+                    meta.query_advice(h[i], Rotation::cur()),
+                );
+                // h ^ v[i] ^ v[i + 8], with the work vector of the last round
+                let xor_bits_rows = query_word(meta, i, FINALIZE_XOR_OFFSET as i32);
+                for (bit, xor_bit) in xor_bits_rows
+                    .iter()
+                    .zip(xor_bits(&h_bits, &query_work(meta, i, -1)))
+                {
+                    cb.require_equal("h ^ v[i]", bit.clone(), xor_bit);
+                }
+                let new_h_bits = query_word(meta, i, FINALIZE_OUTPUT_OFFSET as i32);
+                for (bit, xor_bit) in new_h_bits.iter().zip(xor_bits(
+                    &xor_bits_rows,
+                    &query_work(meta, i + NUM_WORDS_STATE, -1),
+                )) {
+                    cb.require_equal("h ^ v[i] ^ v[i + 8]", bit.clone(), xor_bit);
+                }
+                output_bytes.extend(to_le_bytes(&new_h_bits));
+            }
+            cb.require_equal(
+                "output rlc check",
+                meta.query_advice(output_rlc, Rotation::cur()),
+                rlc::expr(
+                    &output_bytes.into_iter().rev().collect::<Vec<_>>(),
+                    challenges.keccak_input(),
+                ),
+            );
+            cb.gate(
+                meta.query_fixed(q_end, Rotation::cur())
+                    * meta.query_advice(is_finalize, Rotation::cur()),
+            )
+        });
+
+        Blake2fCircuitConfig {
+            q_enable,
+            q_first,
+            q_rows,
+            is_init,
+            is_round,
+            is_finalize,
+            permutations,
+            rounds_left,
+            h,
+            m,
+            words,
+            carry_a,
+            carry_c,
+            blake2f_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Blake2fCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Blake2fRow<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign blake2f rows",
+            |mut region| {
+                for (offset, blake2f_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, blake2f_row)?;
+                }
+                self.blake2f_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Blake2fRow<F>,
+    ) -> Result<(), Error> {
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, row.q_enable),
+            // The first block comes right after the dummy row
+            ("q_first", self.q_first, offset == 1),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (idx, column) in self.q_rows.iter().enumerate() {
+            region.assign_fixed(
+                || format!("assign q_row {} {}", idx, offset),
+                *column,
+                offset,
+                || Value::known(F::from((row.q_enable && row.offset == idx) as u64)),
+            )?;
+        }
+
+        self.blake2f_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_enabled as u64)),
+                row.input_rlc,
+                row.output_rlc,
+            ],
+        )?;
+
+        // Block and call data
+        for (name, column, value) in [
+            ("is_init", self.is_init, row.is_init as u64),
+            ("is_round", self.is_round, row.is_round as u64),
+            ("is_finalize", self.is_finalize, row.is_finalize as u64),
+            ("rounds_left", self.rounds_left, row.rounds_left as u64),
+        ]
+        .into_iter()
+        .chain(self.permutations.iter().enumerate().map(|(idx, column)| {
+            (
+                "permutation",
+                *column,
+                (row.permutation == Some(idx)) as u64,
+            )
+        }))
+        .chain(
+            self.h
+                .iter()
+                .zip(row.h)
+                .map(|(column, h)| ("h", *column, h)),
+        )
+        .chain(
+            self.m
+                .iter()
+                .zip(row.m)
+                .map(|(column, m)| ("m", *column, m)),
+        ) {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+
+        // Bits
+        for (name, columns, bits) in [
+            (
+                "a",
+                &self.words[0][..],
+                &to_bits::<NUM_BITS_PER_WORD>(row.words[0])[..],
+            ),
+            (
+                "b",
+                &self.words[1][..],
+                &to_bits::<NUM_BITS_PER_WORD>(row.words[1])[..],
+            ),
+            (
+                "c",
+                &self.words[2][..],
+                &to_bits::<NUM_BITS_PER_WORD>(row.words[2])[..],
+            ),
+            (
+                "d",
+                &self.words[3][..],
+                &to_bits::<NUM_BITS_PER_WORD>(row.words[3])[..],
+            ),
+            (
+                "carry_a",
+                &self.carry_a[..],
+                &to_bits::<NUM_CARRY_BITS_A>(row.carry_a)[..],
+            ),
+            (
+                "carry_c",
+                &self.carry_c[..],
+                &to_bits::<NUM_CARRY_BITS_C>(row.carry_c)[..],
+            ),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "BLAKE2F_q_enable", self.q_enable);
+        region.name_column(|| "BLAKE2F_q_first", self.q_first);
+        region.name_column(|| "BLAKE2F_is_init", self.is_init);
+        region.name_column(|| "BLAKE2F_is_round", self.is_round);
+        region.name_column(|| "BLAKE2F_is_finalize", self.is_finalize);
+        region.name_column(|| "BLAKE2F_rounds_left", self.rounds_left);
+        for (idx, column) in self.q_rows.iter().enumerate() {
+            region.name_column(|| format!("BLAKE2F_q_row_{}", idx), *column);
+        }
+        for (idx, column) in self.permutations.iter().enumerate() {
+            region.name_column(|| format!("BLAKE2F_permutation_{}", idx), *column);
+        }
+        for (idx, column) in self.h.iter().enumerate() {
+            region.name_column(|| format!("BLAKE2F_h_{}", idx), *column);
+        }
+        for (idx, column) in self.m.iter().enumerate() {
+            region.name_column(|| format!("BLAKE2F_m_{}", idx), *column);
+        }
+    }
+}
+
+/// Blake2fCircuit
+#[derive(Default, Clone, Debug)]
+pub struct Blake2fCircuit<F: Field> {
+    calls: Vec<Blake2fAuxData>,
+    max_rounds: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Blake2fCircuit<F> {
+    type Config = Blake2fCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The words are queried at 19 distinct rotations.
+        22
+    }
+
+    /// The `block.circuits_params.max_blake2f_rounds` parameter, when
+    /// non-zero, sets up the circuit to support a fixed number of rounds,
+    /// independently of the calls of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_blake2f_rounds,
+            block.precompile_events.get_blake2f_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let num_blocks = block
+            .precompile_events
+            .get_blake2f_events()
+            .iter()
+            .map(|aux_data| get_num_blake2f_blocks(aux_data.rounds))
+            .sum::<usize>();
+        (
+            Self::min_num_rows(num_blocks),
+            Self::min_num_rows(block.circuits_params.max_blake2f_rounds.max(num_blocks)),
+        )
+    }
+
+    /// Make the assignments to the Blake2fCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges)?;
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Blake2fCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(max_rounds: usize, calls: Vec<Blake2fAuxData>) -> Self {
+        Blake2fCircuit {
+            calls,
+            max_rounds,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the given number
+    /// of blocks, with the dummy first row.
+    pub fn min_num_rows(num_blocks: usize) -> usize {
+        1 + num_blocks * NUM_ROWS_PER_BLOCK
+    }
+
+    /// The number of blocks that can be done in this circuit, where a call
+    /// takes a block per round, its init block and its finalization block.
+    pub fn capacity(&self) -> Option<usize> {
+        if self.max_rounds > 0 {
+            Some(self.max_rounds)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the calls to verify
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Result<Vec<Blake2fRow<F>>, Error> {
+        multi_blake2f(self.calls.as_slice(), challenges, self.capacity())
+    }
+}
@@ -0,0 +1,224 @@
+use super::param::*;
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use bus_mapping::precompile::Blake2fAuxData;
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Blake2fRow
+#[derive(Clone, Debug)]
+pub(crate) struct Blake2fRow<F: Field> {
+    pub(crate) q_enable: bool,
+    /// Offset of the row in its block.
+    pub(crate) offset: usize,
+    pub(crate) is_init: bool,
+    pub(crate) is_round: bool,
+    pub(crate) is_finalize: bool,
+    /// The message permutation used by a round block.
+    pub(crate) permutation: Option<usize>,
+    pub(crate) rounds_left: u32,
+    pub(crate) h: [u64; NUM_WORDS_STATE],
+    pub(crate) m: [u64; NUM_WORDS_MESSAGE],
+    /// The words `a, b, c, d` stored as bits.
+    pub(crate) words: [u64; NUM_WORDS_PER_ROW],
+    pub(crate) carry_a: u64,
+    pub(crate) carry_c: u64,
+    pub(crate) is_enabled: bool,
+    pub(crate) input_rlc: Value<F>,
+    pub(crate) output_rlc: Value<F>,
+}
+
+impl<F: Field> Blake2fRow<F> {
+    fn new(offset: usize, aux_data: &Blake2fAuxData, input_rlc: Value<F>) -> Self {
+        Self {
+            q_enable: true,
+            offset,
+            is_init: false,
+            is_round: false,
+            is_finalize: false,
+            permutation: None,
+            rounds_left: 0,
+            h: aux_data.h,
+            m: aux_data.m,
+            words: [0; NUM_WORDS_PER_ROW],
+            carry_a: 0,
+            carry_c: 0,
+            is_enabled: false,
+            input_rlc,
+            output_rlc: Value::known(F::ZERO),
+        }
+    }
+
+    /// A row outside of any block, with all the selectors disabled.
+    fn dummy() -> Self {
+        Self {
+            q_enable: false,
+            ..Self::new(0, &Blake2fAuxData::default(), Value::known(F::ZERO))
+        }
+    }
+}
+
+/// Little-endian bits of the lowest `N` bits of `value`.
+pub(crate) fn to_bits<const N: usize>(value: u64) -> [bool; N] {
+    std::array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+/// Position of the word `v[i]` of the work vector in the rows of a block once
+/// it is done, as its offset and its column.
+pub(crate) fn work_position(i: usize) -> (usize, usize) {
+    let column = i / NUM_WORDS_PER_ROW;
+    (WORK_ROWS[(i - column) % NUM_WORDS_PER_ROW], column)
+}
+
+/// Number of blocks used by a call doing `rounds` rounds.
+pub(crate) fn get_num_blake2f_blocks(rounds: u32) -> usize {
+    rounds as usize + 2
+}
+
+/// Do one half of the mixing function G on the words `a, b, c, d` of the
+/// call `g` of G, returning the new words with the carries of `a` and `c`.
+fn g_half(
+    v: &mut [u64; NUM_WORDS_WORK],
+    g: usize,
+    half: usize,
+    x: u64,
+) -> ([u64; NUM_WORDS_PER_ROW], u64, u64) {
+    let [ia, ib, ic, id] = G_WORDS[g];
+    let [r1, r2] = ROTATIONS[half].map(|r| r as u32);
+    let sum_a = v[ia] as u128 + v[ib] as u128 + x as u128;
+    v[ia] = sum_a as u64;
+    v[id] = (v[id] ^ v[ia]).rotate_right(r1);
+    let sum_c = v[ic] as u128 + v[id] as u128;
+    v[ic] = sum_c as u64;
+    v[ib] = (v[ib] ^ v[ic]).rotate_right(r2);
+    (
+        [v[ia], v[ib], v[ic], v[id]],
+        (sum_a >> NUM_BITS_PER_WORD) as u64,
+        (sum_c >> NUM_BITS_PER_WORD) as u64,
+    )
+}
+
+fn blake2f<F: Field>(
+    rows: &mut Vec<Blake2fRow<F>>,
+    aux_data: &Blake2fAuxData,
+    challenges: Challenges<Value<F>>,
+) {
+    let r = challenges.keccak_input();
+    let input = aux_data.input_bytes();
+    let input_rlc = r.map(|r| rlc::value(input.iter().rev(), r));
+    let new_block = || -> Vec<Blake2fRow<F>> {
+        (0..NUM_ROWS_PER_BLOCK)
+            .map(|offset| Blake2fRow::new(offset, aux_data, input_rlc))
+            .collect()
+    };
+
+    // The init block holds the input words on its first rows, and the initial
+    // work vector.
+    let mut v = [0; NUM_WORDS_WORK];
+    v[..NUM_WORDS_STATE].copy_from_slice(&aux_data.h);
+    v[NUM_WORDS_STATE..].copy_from_slice(&IV);
+    v[12] ^= aux_data.t[0];
+    v[13] ^= aux_data.t[1];
+    if aux_data.f == 1 {
+        v[14] = !v[14];
+    }
+    let input_words = std::iter::once(aux_data.rounds as u64)
+        .chain(aux_data.h)
+        .chain(aux_data.m)
+        .chain(aux_data.t)
+        .chain(std::iter::once(aux_data.f as u64))
+        .collect::<Vec<_>>();
+    let mut block_rows = new_block();
+    for (idx, word) in input_words.into_iter().enumerate() {
+        block_rows[idx / NUM_WORDS_PER_ROW].words[idx % NUM_WORDS_PER_ROW] = word;
+    }
+    for (i, word) in v.iter().enumerate() {
+        let (offset, column) = work_position(i);
+        block_rows[offset].words[column] = *word;
+    }
+    for row in block_rows.iter_mut() {
+        row.is_init = true;
+        row.rounds_left = aux_data.rounds;
+    }
+    rows.extend(block_rows);
+
+    // Rounds
+    for round in 0..aux_data.rounds {
+        let mut block_rows = new_block();
+        let sigma = SIGMA[round as usize % NUM_PERMUTATIONS];
+        for (offset, row) in block_rows.iter_mut().enumerate() {
+            let (g, half) = (offset / 2, offset % 2);
+            let x = aux_data.m[sigma[offset]];
+            let (words, carry_a, carry_c) = g_half(&mut v, g, half, x);
+            row.words = words;
+            row.carry_a = carry_a;
+            row.carry_c = carry_c;
+            row.is_round = true;
+            row.permutation = Some(round as usize % NUM_PERMUTATIONS);
+            row.rounds_left = aux_data.rounds - 1 - round;
+        }
+        rows.extend(block_rows);
+    }
+
+    // The finalization block holds the state vector, xored with the halves of
+    // the work vector one after the other, on its last rows.
+    let mut block_rows = new_block();
+    let mut output = aux_data.h;
+    for (offset, half) in [
+        (FINALIZE_H_OFFSET, None),
+        (FINALIZE_XOR_OFFSET, Some(0)),
+        (FINALIZE_OUTPUT_OFFSET, Some(NUM_WORDS_STATE)),
+    ] {
+        if let Some(half) = half {
+            for (i, word) in output.iter_mut().enumerate() {
+                *word ^= v[half + i];
+            }
+        }
+        for (i, word) in output.iter().enumerate() {
+            block_rows[offset + i / NUM_WORDS_PER_ROW].words[i % NUM_WORDS_PER_ROW] = *word;
+        }
+    }
+    for row in block_rows.iter_mut() {
+        row.is_finalize = true;
+    }
+    let last_row = block_rows.last_mut().unwrap();
+    last_row.is_enabled = true;
+    let output_bytes = output
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect::<Vec<_>>();
+    last_row.output_rlc = r.map(|r| rlc::value(output_bytes.iter().rev(), r));
+    rows.extend(block_rows);
+}
+
+/// Generate the rows of the Blake2f circuit for all the calls, padded with
+/// calls of zero rounds on a zero input up to `capacity` blocks.
+pub(crate) fn multi_blake2f<F: Field>(
+    calls: &[Blake2fAuxData],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Blake2fRow<F>>, Error> {
+    // Dummy first row so that the first block starts a new call; `is_finalize`
+    // just needs to be disabled.
+    let mut rows = vec![Blake2fRow::dummy()];
+    for aux_data in calls {
+        blake2f(&mut rows, aux_data, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            blake2f(&mut rows, &Blake2fAuxData::default(), challenges);
+            rows
+        };
+        // Pad with calls of zero rounds to the expected capacity, the last
+        // one may only have its init block.
+        while rows.len() < 1 + capacity * NUM_ROWS_PER_BLOCK {
+            let num_rows = (1 + capacity * NUM_ROWS_PER_BLOCK - rows.len()).min(padding_rows.len());
+            rows.extend_from_slice(&padding_rows[..num_rows]);
+        }
+        // Check that we are not over capacity
+        if rows.len() > 1 + capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,84 @@
+pub(crate) const MAX_DEGREE: usize = 4;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 8;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+/// Number of words of the state vector `h`.
+pub(crate) const NUM_WORDS_STATE: usize = 8;
+/// Number of words of the message block vector `m`.
+pub(crate) const NUM_WORDS_MESSAGE: usize = 16;
+/// Number of words of the work vector `v`.
+pub(crate) const NUM_WORDS_WORK: usize = 16;
+/// Number of words of the input, with the number of rounds and the final
+/// block indicator, which are stored as words in the init block.
+pub(crate) const NUM_WORDS_INPUT: usize = 1 + NUM_WORDS_STATE + NUM_WORDS_MESSAGE + 2 + 1;
+/// Number of distinct message permutations, which are used cyclically by the
+/// rounds.
+pub(crate) const NUM_PERMUTATIONS: usize = 10;
+/// Number of carry bits of the new `a`, which adds 3 words.
+pub(crate) const NUM_CARRY_BITS_A: usize = 2;
+/// Number of carry bits of the new `c`, which adds 2 words.
+pub(crate) const NUM_CARRY_BITS_C: usize = 1;
+
+/// Every block of rows holds the 4 words `a, b, c, d` on each row. A round
+/// block does the 8 calls of the mixing function G of a round, each on two
+/// rows, one per half of G.
+pub(crate) const NUM_G_CALLS: usize = 8;
+pub(crate) const NUM_ROWS_PER_BLOCK: usize = 2 * NUM_G_CALLS;
+pub(crate) const NUM_WORDS_PER_ROW: usize = 4;
+
+/// Rows of a block which hold the work vector once the block is done, on which
+/// the second half of the diagonal calls of G write it in a round block.
+/// The word `v[i]` is on the row `WORK_ROWS[(i - i / 4) % 4]` of the column
+/// `i / 4`.
+pub(crate) const WORK_ROWS: [usize; NUM_WORDS_PER_ROW] = [9, 11, 13, 15];
+
+/// Offsets of the rows of the finalization block holding the state vector,
+/// the state vector xored with the first half of the work vector, and the new
+/// state vector, each on two rows.
+pub(crate) const FINALIZE_H_OFFSET: usize = 10;
+pub(crate) const FINALIZE_XOR_OFFSET: usize = 12;
+pub(crate) const FINALIZE_OUTPUT_OFFSET: usize = 14;
+
+/// Amounts of the right rotations of `d` and `b` in the first and the second
+/// half of G.
+pub(crate) const ROTATIONS: [[usize; 2]; 2] = [[32, 24], [16, 63]];
+
+/// Indices of the words of the work vector `a, b, c, d` mixed by each call of
+/// G in a round: the columns and then the diagonals.
+pub(crate) const G_WORDS: [[usize; NUM_WORDS_PER_ROW]; NUM_G_CALLS] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Message permutations, the round `i` uses `SIGMA[i % 10]`.
+pub(crate) const SIGMA: [[usize; NUM_WORDS_MESSAGE]; NUM_PERMUTATIONS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Initialization vector of BLAKE2b.
+pub(crate) const IV: [u64; NUM_WORDS_STATE] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
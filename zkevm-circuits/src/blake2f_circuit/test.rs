@@ -0,0 +1,146 @@
+use super::*;
+use crate::util::unusable_rows;
+use bus_mapping::precompile::{PrecompileCalls, BLAKE2F_INPUT_LENGTH};
+use eth_types::Address;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+use revm_precompile::{Precompile, Precompiles};
+
+#[test]
+fn blake2f_circuit_unusable_rows() {
+    assert_eq!(
+        Blake2fCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Blake2fCircuit::<Fr>>(()),
+    )
+}
+
+fn verify<F: Field>(k: u32, max_rounds: usize, calls: Vec<Blake2fAuxData>, success: bool) {
+    let circuit = Blake2fCircuit::new(max_rounds, calls);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+/// The output of the BLAKE2 F precompile.
+fn precompile_output(input: &[u8]) -> Vec<u8> {
+    let Some(Precompile::Standard(blake2f)) = Precompiles::berlin()
+        .get(Address::from(PrecompileCalls::Blake2F).as_fixed_bytes()) else {
+        unreachable!("blake2f is a standard precompile");
+    };
+    let (_, output) = blake2f(input, 100_000).unwrap();
+    output
+}
+
+/// A call on the input of the test vectors of EIP-152, i.e. the state vector
+/// of BLAKE2b-512 and the message "abc", with the given number of rounds and
+/// final block indicator.
+fn call(rounds: u32, f: bool) -> Blake2fAuxData {
+    let mut input = rounds.to_be_bytes().to_vec();
+    input.extend(
+        hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b\
+             8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        )
+        .unwrap(),
+    );
+    input.extend(b"abc");
+    input.resize(BLAKE2F_INPUT_LENGTH - 17, 0);
+    input.extend(3u64.to_le_bytes());
+    input.extend(0u64.to_le_bytes());
+    input.push(f as u8);
+    let output = precompile_output(&input);
+    Blake2fAuxData::new(&input, &output)
+}
+
+#[test]
+fn blake2f_output_rlc() {
+    let r = Fr::from(0x100);
+    let challenges = Challenges::mock(Value::known(r), Value::known(r), Value::known(r));
+    let calls = vec![
+        call(0, true),
+        call(1, false),
+        call(12, true),
+        call(12, false),
+    ];
+    let rows = multi_blake2f::<Fr>(&calls, challenges, None).unwrap();
+    let final_rows = rows.iter().filter(|row| row.is_enabled).collect::<Vec<_>>();
+    assert_eq!(final_rows.len(), calls.len());
+    for (aux_data, row) in calls.iter().zip(final_rows) {
+        let [_, input_rlc, output_rlc] = Blake2fTable::assignments(aux_data, &challenges);
+        row.input_rlc
+            .zip(input_rlc)
+            .assert_if_known(|(a, b)| a == b);
+        row.output_rlc
+            .zip(output_rlc)
+            .assert_if_known(|(a, b)| a == b);
+    }
+}
+
+#[test]
+fn blake2f_eip152_vector() {
+    let output = call(12, true).output_bytes();
+    assert_eq!(
+        hex::encode(output),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de\
+         4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+}
+
+#[test]
+fn blake2f_circuit_simple() {
+    let k = 10;
+    let calls = vec![
+        call(0, true),
+        call(1, false),
+        call(12, true),
+        call(12, false),
+    ];
+    verify::<Fr>(k, 0, calls, true);
+}
+
+#[test]
+fn blake2f_circuit_padding() {
+    let k = 10;
+    // The 5 blocks left are padded with two calls of zero rounds and an init
+    // block.
+    let calls = vec![call(12, true)];
+    verify::<Fr>(k, 19, calls, true);
+}
+
+#[test]
+fn blake2f_circuit_over_capacity() {
+    let circuit = Blake2fCircuit::<Fr>::new(13, vec![call(12, true)]);
+    assert!(matches!(
+        circuit.generate_witness(Challenges::mock(
+            Value::unknown(),
+            Value::unknown(),
+            Value::unknown()
+        )),
+        Err(Error::BoundsFailure)
+    ));
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 10;
+    let max_rounds = 40;
+    // Empty
+    let circuit = Blake2fCircuit::new(max_rounds, vec![]);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let calls = vec![call(0, true), call(1, false), call(12, true)];
+    let circuit = Blake2fCircuit::new(max_rounds, calls);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
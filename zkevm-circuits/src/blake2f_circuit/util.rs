@@ -0,0 +1,31 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::{xor, Expr};
+use halo2_proofs::plonk::Expression;
+
+/// Decode the value of little-endian bits.
+pub(crate) fn decode<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Little-endian bytes of a word given as little-endian bits.
+pub(crate) fn to_le_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits.chunks(NUM_BITS_PER_BYTE).map(decode).collect()
+}
+
+/// Bits of a word rotated right by `n`.
+pub(crate) fn rotate_right<F: Field>(bits: &[Expression<F>], n: usize) -> Vec<Expression<F>> {
+    (0..NUM_BITS_PER_WORD)
+        .map(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+        .collect()
+}
+
+/// Bitwise xor of two words given as bits.
+pub(crate) fn xor_bits<F: Field>(x: &[Expression<F>], y: &[Expression<F>]) -> Vec<Expression<F>> {
+    x.iter()
+        .zip(y)
+        .map(|(x, y)| xor::expr(x.clone(), y.clone()))
+        .collect()
+}
@@ -2,7 +2,7 @@
 use crate::{
     bytecode_circuit::{bytecode_unroller::*, circuit::BytecodeCircuit},
     table::BytecodeFieldTag,
-    util::{is_push, keccak, unusable_rows, Challenges, SubCircuit},
+    util::{is_push_with_data, keccak, unusable_rows, Challenges, SubCircuit},
 };
 use bus_mapping::evm::OpcodeId;
 use eth_types::{Bytecode, Field, Word};
@@ -63,9 +63,9 @@ fn bytecode_unrolling() {
     let k = 10;
     let mut rows = vec![];
     let mut bytecode = Bytecode::default();
-    // First add all non-push bytes, which should all be seen as code
+    // First add all bytes without push data, which should all be seen as code
     for byte in 0u8..=255u8 {
-        if !is_push(byte) {
+        if !is_push_with_data(byte) {
             bytecode.write(byte, true);
             rows.push(BytecodeRow {
                 code_hash: Word::zero(),
@@ -180,6 +180,12 @@ fn bytecode_push() {
                 OpcodeId::PUSH32.as_u8(),
                 OpcodeId::ADD.as_u8(),
             ]),
+            unroll(vec![
+                OpcodeId::PUSH0.as_u8(),
+                OpcodeId::PUSH1.as_u8(),
+                OpcodeId::PUSH0.as_u8(),
+                OpcodeId::ADD.as_u8(),
+            ]),
         ],
         true,
     );
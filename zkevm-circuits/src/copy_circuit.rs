@@ -19,7 +19,7 @@ use eth_types::{Field, Word};
 use gadgets::{
     binary_number::BinaryNumberChip,
     less_than::{LtChip, LtConfig, LtInstruction},
-    util::{and, not, or, Expr},
+    util::{and, not, sum, Expr},
 };
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
@@ -192,14 +192,22 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             );
 
             let rw_diff = and::expr([
-                or::expr([
+                sum::expr([
                     tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::MemoryCopy, Rotation::cur())(meta),
                     tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
             ]);
+            // Either the write row of a memory copy, or the read row right
+            // before it.
+            let is_memory_copy = tag.value_equals(CopyDataType::MemoryCopy, Rotation::cur())(meta)
+                + tag.value_equals(CopyDataType::MemoryCopy, Rotation::next())(meta);
             cb.condition(
-                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    not::expr(is_memory_copy.clone()),
+                ]),
                 |cb| {
                     cb.require_equal(
                         "rows[0].rw_counter + rw_diff == rows[1].rw_counter",
@@ -211,6 +219,28 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                         meta.query_advice(rwc_inc_left, Rotation::cur()) - rw_diff.clone(),
                         meta.query_advice(rwc_inc_left, Rotation::next()),
                     );
+                },
+            );
+            // The rw counters of a memory copy are not consecutive, as all the
+            // reads come before the writes, but they still end at the same one.
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    is_memory_copy,
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[0].rw_counter + rows[0].rwc_inc_left == rows[1].rw_counter + rows[1].rwc_inc_left for memory copy",
+                        meta.query_advice(rw_counter, Rotation::cur())
+                            + meta.query_advice(rwc_inc_left, Rotation::cur()),
+                        meta.query_advice(rw_counter, Rotation::next())
+                            + meta.query_advice(rwc_inc_left, Rotation::next()),
+                    );
+                },
+            );
+            cb.condition(
+                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                |cb| {
                     cb.require_equal(
                         "rows[0].rlc_acc == rows[1].rlc_acc",
                         meta.query_advice(rlc_acc, Rotation::cur()),
@@ -265,10 +295,14 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     );
                 },
             );
+            let is_rlc_acc_src = tag.value_equals(CopyDataType::RlcAcc, Rotation::cur())(meta);
             cb.condition(
-                not::expr(tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(
-                    meta,
-                )),
+                and::expr([
+                    not::expr(tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(
+                        meta,
+                    )),
+                    not::expr(is_rlc_acc_src.clone()),
+                ]),
                 |cb| {
                     cb.require_equal(
                         "write value == read value (if not rlc acc)",
@@ -277,6 +311,33 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     );
                 },
             );
+            // When the source is an RlcAcc, the read rows accumulate the bytes
+            // written in the write rows, so that the last read row holds the
+            // RLC of all the copied bytes.
+            cb.condition(
+                and::expr([
+                    is_rlc_acc_src.clone(),
+                    not::expr(meta.query_advice(is_last, Rotation::next())),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[2].value == rows[0].value * r + rows[3].value for RlcAcc source",
+                        meta.query_advice(value, Rotation(2)),
+                        meta.query_advice(value, Rotation::cur()) * challenges.keccak_input()
+                            + meta.query_advice(value, Rotation(3)),
+                    );
+                },
+            );
+            cb.condition(
+                and::expr([is_rlc_acc_src, meta.query_advice(is_last, Rotation::next())]),
+                |cb| {
+                    cb.require_equal(
+                        "value == rlc_acc at the last read row for RlcAcc source",
+                        meta.query_advice(value, Rotation::cur()),
+                        meta.query_advice(rlc_acc, Rotation::cur()),
+                    );
+                },
+            );
             cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
                 cb.require_equal(
                     "write value == read value (is_first == 1)",
@@ -284,6 +345,44 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     meta.query_advice(value, Rotation::next()),
                 );
             });
+            // For a memory copy, the reads and the writes each take consecutive
+            // rw counters, and the writes start right after the last read.
+            let is_memory_copy =
+                tag.value_equals(CopyDataType::MemoryCopy, Rotation::next())(meta);
+            cb.condition(
+                and::expr([
+                    is_memory_copy.clone(),
+                    meta.query_advice(is_first, Rotation::cur()),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[1].rw_counter == rows[0].rw_counter + bytes_left for the first memory copy step",
+                        meta.query_advice(rw_counter, Rotation::next()),
+                        meta.query_advice(rw_counter, Rotation::cur())
+                            + meta.query_advice(bytes_left, Rotation::cur()),
+                    );
+                },
+            );
+            cb.condition(
+                and::expr([
+                    is_memory_copy,
+                    not::expr(meta.query_advice(is_last, Rotation::next())),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[2].rw_counter == rows[0].rw_counter + 1 for memory copy",
+                        meta.query_advice(rw_counter, Rotation(2)),
+                        meta.query_advice(rw_counter, Rotation::cur()) + 1.expr(),
+                    );
+                    cb.require_equal(
+                        "rows[3].rw_counter - rows[2].rw_counter == rows[1].rw_counter - rows[0].rw_counter for memory copy",
+                        meta.query_advice(rw_counter, Rotation(3))
+                            - meta.query_advice(rw_counter, Rotation(2)),
+                        meta.query_advice(rw_counter, Rotation::next())
+                            - meta.query_advice(rw_counter, Rotation::cur()),
+                    );
+                },
+            );
             cb.require_zero(
                 "value == 0 when is_pad == 1 for read",
                 and::expr([
@@ -325,7 +424,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
 
         meta.lookup_any("Memory lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
-                * tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta)
+                * (tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta)
+                    + tag.value_equals(CopyDataType::MemoryCopy, Rotation::cur())(meta))
                 * not::expr(meta.query_advice(is_pad, Rotation::cur()));
             vec![
                 meta.query_advice(rw_counter, Rotation::cur()),
@@ -189,6 +189,27 @@ fn gen_tx_log_data() -> CircuitInputBuilder {
     builder
 }
 
+fn gen_mcopy_data() -> CircuitInputBuilder {
+    // The destination overlaps with the end of the source.
+    let code = bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0x00)
+        MSTORE
+        PUSH1(0x20) // size
+        PUSH1(0x00) // src offset
+        PUSH1(0x10) // dst offset
+        MCOPY
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 #[test]
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
@@ -224,6 +245,13 @@ fn copy_circuit_valid_tx_log() {
     assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_mcopy() {
+    let builder = gen_mcopy_data();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_invalid_calldatacopy() {
     let mut builder = gen_calldatacopy_data();
@@ -304,6 +332,36 @@ fn copy_circuit_invalid_tx_log() {
     );
 }
 
+#[test]
+fn copy_circuit_invalid_mcopy() {
+    let mut builder = gen_mcopy_data();
+
+    // modify first byte of first copy event
+    builder.block.copy_events[0].bytes[0].0 =
+        builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["Memory lookup", "Memory lookup"],
+    );
+}
+
+#[test]
+fn copy_circuit_invalid_mcopy_rw_counter() {
+    let mut builder = gen_mcopy_data();
+
+    // interleave the reads and the writes of the memory copy, which would
+    // overwrite the overlapping bytes before reading them
+    let copy_event = &mut builder.block.copy_events[0];
+    copy_event.dst_type = CopyDataType::Memory;
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    assert!(test_copy_circuit_from_block(10, block).is_err());
+}
+
 #[test]
 fn variadic_size_check() {
     let builder = gen_tx_log_data();
@@ -0,0 +1,992 @@
+//! The ECC circuit implementation.
+//!
+//! It verifies the additions and scalar multiplications of BN254 G1 points
+//! done by the ecAdd and ecMul precompiles, and exposes them in the ECC table.
+//! The coordinates, which the EVM circuit checks to be in the base field, are
+//! emulated with the integer chip of halo2wrong. A point which is neither on
+//! the curve nor `(0, 0)`, the point at infinity, is invalid and gives a zero
+//! result. The incomplete addition and multiplication of the ECC chip are
+//! only done on finite points for which they are well defined, the other
+//! cases being selected from the inputs.
+
+// Naming notes:
+// - *_le: Little-Endian bytes
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EccCircuit as TestEccCircuit;
+
+use crate::{
+    evm_circuit::util::rlc,
+    table::{EccTable, LookupTable},
+    tx_circuit::sign_verify::assert_equal_if,
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::{EcAddAuxData, EcMulAuxData, PrecompileCalls};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
+use eth_types::{Field, ToLittleEndian, Word};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine, G1},
+        ff::{Field as _, PrimeField},
+        group::{prime::PrimeCurveAffine, Curve, Group},
+    },
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+    poly::Rotation,
+};
+use integer::{
+    rns::{Integer as RnsInteger, Rns},
+    AssignedInteger, IntegerInstructions, Range, UnassignedInteger,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx, Term,
+};
+use num::Integer;
+use num_bigint::BigUint;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::{collections::BTreeSet, iter, marker::PhantomData, rc::Rc};
+
+const NUMBER_OF_LIMBS: usize = 4;
+const BIT_LEN_LIMB: usize = 72;
+/// Number of bytes of a limb. The last limb of a word has the remaining 5
+/// bytes.
+const NUM_BYTES_LIMB: usize = BIT_LEN_LIMB / 8;
+
+type EccChip<F> = GeneralEccChip<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedFq<F> = AssignedInteger<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedFr<F> = AssignedInteger<Fr, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedG1<F> = AssignedPoint<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+/// Configuration of the ECC circuit
+#[derive(Clone, Debug)]
+pub struct EccCircuitConfig<F> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the words, with a byte in each column of the main gate
+    q_rlc: Selector,
+    rlc: Column<Advice>,
+    /// ECC table
+    pub ecc_table: EccTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct EccCircuitConfigArgs<F: Field> {
+    /// ECC table
+    pub ecc_table: EccTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
+    type ConfigArgs = EccCircuitConfigArgs<F>;
+
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ecc_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let (rns_base, rns_scalar) = EccChip::<F>::rns();
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+            [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
+
+        // Layout of the RLC of a word, as in the signature verifier:
+        // | q_rlc |                  rlc                  |   a   |   b   |   c   |   d   |   e   |
+        // | ----- | ------------------------------------- | ----- | ----- | ----- | ----- | ----- |
+        // |   1   |                                     0 |     0 |     0 |     0 | be[0] | be[1] |
+        // |   1   |            be[0]*r^1 + ... +    be[1] | be[2] | be[3] | be[4] | be[5] | be[6] |
+        // |  ...  |                                   ... |  ...  |  ...  |  ...  |  ...  |  ...  |
+        // |   0   |           be[0]*r^31 + ... +   be[31] |       |       |       |       |       |
+        let q_rlc = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        meta.create_gate("ecc word rlc", |meta| {
+            let q_rlc = meta.query_selector(q_rlc);
+            let [a, b, c, d, e] = main_gate_config
+                .advices()
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [rlc, rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(rlc, rotation));
+            let inputs = [e, d, c, b, a, rlc];
+
+            vec![q_rlc * (rlc_next - rlc::expr(&inputs, challenges.evm_word()))]
+        });
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc,
+            rlc,
+            ecc_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EccCircuitConfig<F> {
+    /// Load the table of the range chip.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        RangeChip::<F>::new(self.range_config.clone()).load_table(layouter)
+    }
+
+    fn ecc_chip_config(&self) -> EccConfig {
+        EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+}
+
+/// Helper structure to pass around references to the chips and the residue
+/// number systems of the coordinates and the scalars.
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    ecc_chip: &'a EccChip<F>,
+    rns_base: Rc<Rns<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+    rns_scalar: Rc<Rns<Fr, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+}
+
+/// Cells of a point given by its coordinates, which may not be on the curve.
+struct AssignedInputPoint<F: Field> {
+    point: AssignedG1<F>,
+    x_le: [AssignedValue<F>; 32],
+    y_le: [AssignedValue<F>; 32],
+    is_infinity: AssignedCondition<F>,
+    is_valid: AssignedCondition<F>,
+}
+
+/// A word with its assigned little-endian bytes.
+struct AssignedWord<'a, F: Field> {
+    word: &'a Word,
+    bytes_le: [AssignedValue<F>; 32],
+}
+
+/// Cells of a row of the ECC table.
+struct AssignedEccTableRow<F: Field> {
+    op_type: AssignedValue<F>,
+    arg1_rlc: AssignedCell<F, F>,
+    arg2_rlc: AssignedCell<F, F>,
+    arg3_rlc: AssignedCell<F, F>,
+    arg4_rlc: AssignedCell<F, F>,
+    output1_rlc: AssignedCell<F, F>,
+    output2_rlc: AssignedCell<F, F>,
+    is_valid: AssignedCondition<F>,
+}
+
+/// Element of the base field from a word, which must be less than its
+/// modulus.
+fn fq(word: &Word) -> Fq {
+    Fq::from_repr(word.to_le_bytes()).unwrap()
+}
+
+/// Order of the BN254 G1 group, i.e. the modulus of its scalar field.
+fn group_order() -> BigUint {
+    BigUint::from_bytes_le(&(Fr::ZERO - Fr::ONE).to_repr()) + 1u64
+}
+
+/// Limbs of a value of at most `NUMBER_OF_LIMBS * BIT_LEN_LIMB` bits.
+fn limbs(value: &BigUint) -> [u128; NUMBER_OF_LIMBS] {
+    let mask = (BigUint::from(1u64) << BIT_LEN_LIMB) - 1u64;
+    let mut limbs = [0u128; NUMBER_OF_LIMBS];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let digits = ((value >> (i * BIT_LEN_LIMB)) & &mask).to_u64_digits();
+        *limb = digits
+            .iter()
+            .rev()
+            .fold(0u128, |acc, digit| (acc << 64) + *digit as u128);
+    }
+    limbs
+}
+
+/// ECC Circuit, verifying the ecAdd and ecMul calls of a block.
+#[derive(Clone, Debug)]
+pub struct EccCircuit<F: Field> {
+    /// Aux generator for EccChip
+    pub aux_generator: G1Affine,
+    /// Window size of the scalar multiplications
+    pub window_size: usize,
+    /// Max number of ecAdd calls, or zero to fit the ones of `add_ops`
+    pub max_add_ops: usize,
+    /// Max number of ecMul calls, or zero to fit the ones of `mul_ops`
+    pub max_mul_ops: usize,
+    /// Inputs and outputs of the ecAdd calls
+    pub add_ops: Vec<EcAddAuxData>,
+    /// Inputs and outputs of the ecMul calls
+    pub mul_ops: Vec<EcMulAuxData>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for EccCircuit<F> {
+    fn default() -> Self {
+        Self {
+            aux_generator: G1Affine::default(),
+            window_size: 4,
+            max_add_ops: 0,
+            max_mul_ops: 0,
+            add_ops: vec![],
+            mul_ops: vec![],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EccCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(
+        max_add_ops: usize,
+        max_mul_ops: usize,
+        add_ops: Vec<EcAddAuxData>,
+        mul_ops: Vec<EcMulAuxData>,
+    ) -> Self {
+        // The aux generator is a random point chosen by the prover, as in the
+        // signature verifier.
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let aux_generator = G1::random(&mut rng).to_affine();
+        Self {
+            aux_generator,
+            window_size: 4,
+            max_add_ops,
+            max_mul_ops,
+            add_ops,
+            mul_ops,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the given number
+    /// of additions and multiplications.
+    pub fn min_num_rows(num_add_ops: usize, num_mul_ops: usize) -> usize {
+        // The range chip table has a row for each value of each of its bit
+        // lengths.
+        let (rns_base, rns_scalar) = EccChip::<F>::rns();
+        let rows_range_chip_table: usize = [BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8]
+            .into_iter()
+            .chain(rns_base.overflow_lengths())
+            .chain(rns_scalar.overflow_lengths())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|bit_len| 1 << bit_len)
+            .sum();
+        // Rough upper bounds of the rows used by the aux points of the ECC
+        // chip and by each operation, including the RLCs of its words.
+        let rows_ecc_chip_aux = 300;
+        let rows_ec_add = 2_000;
+        let rows_ec_mul = 70_000;
+        std::cmp::max(
+            rows_range_chip_table,
+            rows_ecc_chip_aux + rows_ec_add * num_add_ops + rows_ec_mul * num_mul_ops,
+        )
+    }
+
+    /// The operations padded to the capacity of the circuit, with operations
+    /// on zeroes, which are valid.
+    fn padded_ops<T: Clone + Default>(
+        name: &str,
+        ops: &[T],
+        max_ops: usize,
+    ) -> Result<Vec<T>, Error> {
+        if max_ops == 0 {
+            return Ok(ops.to_vec());
+        }
+        if ops.len() > max_ops {
+            error!(
+                "{} {} calls exceed the capacity of {}",
+                ops.len(),
+                name,
+                max_ops
+            );
+            return Err(Error::Synthesis);
+        }
+        Ok(ops
+            .iter()
+            .cloned()
+            .chain(iter::repeat(T::default()))
+            .take(max_ops)
+            .collect())
+    }
+
+    fn assign_aux(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        ecc_chip: &mut EccChip<F>,
+    ) -> Result<(), Error> {
+        ecc_chip.assign_aux_generator(ctx, Value::known(self.aux_generator))?;
+        ecc_chip.assign_aux(ctx, self.window_size, 1)?;
+        Ok(())
+    }
+
+    /// Assign the little-endian bytes of a word, and return them with its
+    /// limbs.
+    fn assign_word(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        word: &Word,
+    ) -> Result<(Vec<AssignedValue<F>>, [AssignedValue<F>; 32]), Error> {
+        let mut limbs = Vec::with_capacity(NUMBER_OF_LIMBS);
+        let mut bytes_le = Vec::with_capacity(32);
+        for chunk in word.to_le_bytes().chunks(NUM_BYTES_LIMB) {
+            let value = chunk.iter().rev().fold(F::ZERO, |acc, byte| {
+                acc * F::from(256) + F::from(*byte as u64)
+            });
+            let (limb, chunk_le) =
+                chips
+                    .range_chip
+                    .decompose(ctx, Value::known(value), 8, 8 * chunk.len())?;
+            limbs.push(limb);
+            bytes_le.extend(chunk_le);
+        }
+        Ok((limbs, bytes_le.try_into().unwrap()))
+    }
+
+    /// Assign a coordinate from a word, constraining its bytes to match the
+    /// limbs of the emulated integer.
+    fn assign_coordinate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        word: &Word,
+    ) -> Result<(AssignedFq<F>, [AssignedValue<F>; 32]), Error> {
+        let (limbs, bytes_le) = self.assign_word(ctx, chips, word)?;
+        let coordinate = chips.ecc_chip.base_field_chip().assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(RnsInteger::from_fe(
+                fq(word),
+                chips.rns_base.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        for (limb, coordinate_limb) in limbs.iter().zip_eq(coordinate.limbs()) {
+            chips
+                .main_gate
+                .assert_equal(ctx, limb, coordinate_limb.as_ref())?;
+        }
+        Ok((coordinate, bytes_le))
+    }
+
+    /// Assign whether `a` is zero modulo the base field modulus, given its
+    /// value. A zero is shown by the limbs of the reduced integer, and a
+    /// non-zero by its inverse.
+    fn assign_is_zero(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        a: &AssignedFq<F>,
+        value: Fq,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let main_gate = chips.main_gate;
+        let base_chip = chips.ecc_chip.base_field_chip();
+
+        let a = base_chip.reduce(ctx, a)?;
+        let is_zero = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from(bool::from(value.is_zero()) as u64)),
+        )?;
+        let is_not_zero = main_gate.not(ctx, &is_zero)?;
+
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        for limb in a.limbs() {
+            assert_equal_if(ctx, main_gate, limb.as_ref(), &zero, &is_zero)?;
+        }
+        let inverse = base_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(RnsInteger::from_fe(
+                value.invert().unwrap_or(Fq::ONE),
+                chips.rns_base.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        let product = base_chip.mul(ctx, &a, &inverse)?;
+        let one = base_chip.assign_constant(ctx, Fq::ONE)?;
+        for (limb, one_limb) in product.limbs().iter().zip_eq(one.limbs()) {
+            assert_equal_if(
+                ctx,
+                main_gate,
+                limb.as_ref(),
+                one_limb.as_ref(),
+                &is_not_zero,
+            )?;
+        }
+
+        Ok(is_zero)
+    }
+
+    /// Assign a point given to a precompile, and whether it's valid, i.e. on
+    /// the curve or at infinity.
+    fn assign_input_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        [x_word, y_word]: &[Word; 2],
+    ) -> Result<AssignedInputPoint<F>, Error> {
+        let main_gate = chips.main_gate;
+        let base_chip = chips.ecc_chip.base_field_chip();
+
+        let (x, x_le) = self.assign_coordinate(ctx, chips, x_word)?;
+        let (y, y_le) = self.assign_coordinate(ctx, chips, y_word)?;
+
+        // The limbs are range checked, so their sum is zero iff they are all
+        // zero.
+        let limbs_sum = main_gate.compose(
+            ctx,
+            &x.limbs()
+                .iter()
+                .chain(y.limbs())
+                .map(|limb| Term::Assigned(limb.as_ref(), F::ONE))
+                .collect_vec(),
+            F::ZERO,
+        )?;
+        let is_infinity = main_gate.is_zero(ctx, &limbs_sum)?;
+
+        // The point is on the curve iff y^2 - (x^3 + 3) is zero, which never
+        // happens at infinity.
+        let x_square = base_chip.square(ctx, &x)?;
+        let x_cube = base_chip.mul(ctx, &x_square, &x)?;
+        let three = base_chip.assign_constant(ctx, Fq::from(3))?;
+        let rhs = base_chip.add(ctx, &x_cube, &three)?;
+        let y_square = base_chip.square(ctx, &y)?;
+        let diff = base_chip.sub(ctx, &y_square, &rhs)?;
+        let (x_value, y_value) = (fq(x_word), fq(y_word));
+        let diff_value = y_value.square() - (x_value.square() * x_value + Fq::from(3));
+        let is_on_curve = self.assign_is_zero(ctx, chips, &diff, diff_value)?;
+        let is_valid = main_gate.add(ctx, &is_infinity, &is_on_curve)?;
+
+        Ok(AssignedInputPoint {
+            point: AssignedPoint::new(x, y),
+            x_le,
+            y_le,
+            is_infinity,
+            is_valid,
+        })
+    }
+
+    /// Assign the point at infinity as `(0, 0)`.
+    fn assign_zero_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+    ) -> Result<AssignedG1<F>, Error> {
+        let base_chip = chips.ecc_chip.base_field_chip();
+        let x = base_chip.assign_constant(ctx, Fq::ZERO)?;
+        let y = base_chip.assign_constant(ctx, Fq::ZERO)?;
+        Ok(AssignedPoint::new(x, y))
+    }
+
+    /// Assign the bytes of the coordinates of a result, which are constrained
+    /// to be the canonical ones.
+    fn assign_output_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        point: &AssignedG1<F>,
+        words: &[Word; 2],
+    ) -> Result<[[AssignedValue<F>; 32]; 2], Error> {
+        let base_chip = chips.ecc_chip.base_field_chip();
+        let mut bytes_le = Vec::with_capacity(2);
+        for (coordinate, word) in [point.x(), point.y()].into_iter().zip_eq(words) {
+            let coordinate = base_chip.reduce(ctx, coordinate)?;
+            base_chip.assert_in_field(ctx, &coordinate)?;
+            let (limbs, word_le) = self.assign_word(ctx, chips, word)?;
+            for (limb, coordinate_limb) in limbs.iter().zip_eq(coordinate.limbs()) {
+                chips
+                    .main_gate
+                    .assert_equal(ctx, limb, coordinate_limb.as_ref())?;
+            }
+            bytes_le.push(word_le);
+        }
+        Ok(bytes_le.try_into().unwrap())
+    }
+
+    /// Assign the scalar of a multiplication, reduced modulo the group order
+    /// `n`. The bytes of the scalar `s` are constrained to be
+    /// `k * n + s_reduced` limb by limb, with small carries between limbs.
+    fn assign_scalar(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        s: &Word,
+    ) -> Result<(AssignedFr<F>, [AssignedValue<F>; 32]), Error> {
+        let main_gate = chips.main_gate;
+        let scalar_chip = chips.ecc_chip.scalar_field_chip();
+
+        let (s_limbs, s_le) = self.assign_word(ctx, chips, s)?;
+        let n = group_order();
+        let (k, s_reduced) = BigUint::from_bytes_le(&s.to_le_bytes()).div_rem(&n);
+        let reduced = scalar_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(RnsInteger::from_big(
+                s_reduced.clone(),
+                chips.rns_scalar.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        scalar_chip.assert_in_field(ctx, &reduced)?;
+
+        // Since s < 2^256 < 8 * n, k and the carries fit in 3 bits.
+        let k_value = k.to_u64_digits().first().copied().unwrap_or(0) as u128;
+        let k = main_gate.assign_value(ctx, Value::known(F::from_u128(k_value)))?;
+        main_gate.to_bits(ctx, &k, 3)?;
+        let (n_limbs, reduced_limbs) = (limbs(&n), limbs(&s_reduced));
+        let s_limb_values = limbs(&BigUint::from_bytes_le(&s.to_le_bytes()));
+        let mut carry: Option<(AssignedValue<F>, u128)> = None;
+        for (i, (s_limb, reduced_limb)) in s_limbs.iter().zip_eq(reduced.limbs()).enumerate() {
+            let carry_in = carry.as_ref().map_or(0, |(_, value)| *value);
+            let mut terms = vec![
+                Term::Assigned(&k, F::from_u128(n_limbs[i])),
+                Term::Assigned(reduced_limb.as_ref(), F::ONE),
+                Term::Assigned(s_limb, -F::ONE),
+            ];
+            if let Some((cell, _)) = carry.as_ref() {
+                terms.push(Term::Assigned(cell, F::ONE));
+            }
+            // The last limb has no carry out.
+            let carry_out = if i < NUMBER_OF_LIMBS - 1 {
+                let value = (k_value * n_limbs[i] + reduced_limbs[i] + carry_in - s_limb_values[i])
+                    >> BIT_LEN_LIMB;
+                let cell = main_gate.assign_value(ctx, Value::known(F::from_u128(value)))?;
+                main_gate.to_bits(ctx, &cell, 3)?;
+                Some((cell, value))
+            } else {
+                None
+            };
+            if let Some((cell, _)) = carry_out.as_ref() {
+                terms.push(Term::Assigned(cell, -F::from_u128(1 << BIT_LEN_LIMB)));
+            }
+            let sum = main_gate.compose(ctx, &terms, F::ZERO)?;
+            main_gate.assert_zero(ctx, &sum)?;
+            carry = carry_out;
+        }
+
+        Ok((reduced, s_le))
+    }
+
+    /// Assign the RLC of the bytes of a word, copied from their cells, with
+    /// the values taken from the word since the cells aren't in the current
+    /// phase.
+    fn assign_word_rlc(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        word: &AssignedWord<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let zero = chips.main_gate.assign_constant(ctx, F::ZERO)?;
+        let columns = config.main_gate_config.advices();
+        let bytes_be = iter::repeat((zero.cell(), 0u8))
+            .take((columns.len() - 32 % columns.len()) % columns.len())
+            .chain(
+                word.bytes_le
+                    .iter()
+                    .zip(word.word.to_le_bytes())
+                    .rev()
+                    .map(|(assigned, byte)| (assigned.cell(), byte)),
+            )
+            .collect_vec();
+
+        let challenge = challenges.evm_word();
+        let mut rlc = Value::known(F::ZERO);
+        for (chunk_idx, chunk) in bytes_be.chunks_exact(columns.len()).enumerate() {
+            ctx.enable(config.q_rlc)?;
+            let assigned_rlc = ctx.assign_advice(|| "word_rlc", config.rlc, rlc)?;
+            if chunk_idx == 0 {
+                ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
+            }
+            for (&column, &(cell, byte)) in columns.iter().zip(chunk) {
+                let copied =
+                    ctx.assign_advice(|| "word_byte", column, Value::known(F::from(byte as u64)))?;
+                ctx.constrain_equal(cell, copied.cell())?;
+            }
+            rlc = chunk.iter().fold(rlc, |acc, (_, byte)| {
+                acc * challenge + Value::known(F::from(*byte as u64))
+            });
+            ctx.next();
+        }
+
+        let assigned_rlc = ctx.assign_advice(|| "word_rlc", config.rlc, rlc)?;
+        ctx.next();
+
+        Ok(assigned_rlc)
+    }
+
+    /// Assign the RLCs of the words of the arguments and output of an
+    /// operation, in the order of the ECC table, a missing argument being
+    /// zero.
+    fn assign_word_rlcs(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        words: [Option<AssignedWord<F>>; 6],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<[AssignedCell<F, F>; 6], Error> {
+        let mut rlcs = Vec::with_capacity(words.len());
+        for word in words.iter() {
+            let rlc = match word {
+                Some(word) => self.assign_word_rlc(config, ctx, chips, word, challenges)?,
+                None => chips.main_gate.assign_constant(ctx, F::ZERO)?,
+            };
+            rlcs.push(rlc);
+        }
+        Ok(rlcs.try_into().unwrap())
+    }
+
+    fn assign_ec_add(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        aux_data: &EcAddAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEccTableRow<F>, Error> {
+        let main_gate = chips.main_gate;
+        let ecc_chip = chips.ecc_chip;
+        let base_chip = ecc_chip.base_field_chip();
+
+        let p = self.assign_input_point(ctx, chips, &aux_data.p)?;
+        let q = self.assign_input_point(ctx, chips, &aux_data.q)?;
+        let is_valid = main_gate.mul(ctx, &p.is_valid, &q.is_valid)?;
+
+        let [p_x, p_y] = aux_data.p.map(|word| fq(&word));
+        let [q_x, q_y] = aux_data.q.map(|word| fq(&word));
+        let x_diff = base_chip.sub(ctx, p.point.x(), q.point.x())?;
+        let is_x_equal = self.assign_is_zero(ctx, chips, &x_diff, p_x - q_x)?;
+        let y_diff = base_chip.sub(ctx, p.point.y(), q.point.y())?;
+        let is_y_equal = self.assign_is_zero(ctx, chips, &y_diff, p_y - q_y)?;
+
+        // The incomplete addition is done for finite points with different
+        // x-coordinates, and the doubling for equal finite points, which
+        // can't have a zero y-coordinate since the order of the group is odd.
+        let is_finite = {
+            let is_p_finite = main_gate.not(ctx, &p.is_infinity)?;
+            let is_q_finite = main_gate.not(ctx, &q.is_infinity)?;
+            let is_finite = main_gate.mul(ctx, &is_p_finite, &is_q_finite)?;
+            main_gate.mul(ctx, &is_finite, &is_valid)?
+        };
+        let is_x_different = main_gate.not(ctx, &is_x_equal)?;
+        let is_addition = main_gate.mul(ctx, &is_finite, &is_x_different)?;
+        let is_doubling = {
+            let is_doubling = main_gate.mul(ctx, &is_finite, &is_x_equal)?;
+            main_gate.mul(ctx, &is_doubling, &is_y_equal)?
+        };
+
+        // Otherwise, they are done on multiples of the generator instead.
+        let generator = ecc_chip.assign_constant(ctx, G1Affine::generator())?;
+        let double_generator =
+            ecc_chip.assign_constant(ctx, G1::generator().double().to_affine())?;
+        let lhs = ecc_chip.select(ctx, &is_addition, &p.point, &generator)?;
+        let rhs = ecc_chip.select(ctx, &is_addition, &q.point, &double_generator)?;
+        let sum = ecc_chip.add(ctx, &lhs, &rhs)?;
+        let doubled = {
+            let point = ecc_chip.select(ctx, &is_doubling, &p.point, &generator)?;
+            ecc_chip.double(ctx, &point)?
+        };
+
+        // The result is zero for invalid points and for P = -Q, Q when P is
+        // at infinity, and P when Q is at infinity.
+        let mut r = self.assign_zero_point(ctx, chips)?;
+        r = ecc_chip.select(ctx, &is_addition, &sum, &r)?;
+        r = ecc_chip.select(ctx, &is_doubling, &doubled, &r)?;
+        let is_q_infinity = main_gate.mul(ctx, &q.is_infinity, &is_valid)?;
+        r = ecc_chip.select(ctx, &is_q_infinity, &p.point, &r)?;
+        let is_p_infinity = main_gate.mul(ctx, &p.is_infinity, &is_valid)?;
+        r = ecc_chip.select(ctx, &is_p_infinity, &q.point, &r)?;
+
+        let [r_x_le, r_y_le] = self.assign_output_point(ctx, chips, &r, &aux_data.r)?;
+
+        let op_type =
+            main_gate.assign_constant(ctx, F::from(PrecompileCalls::Bn128Add.address()))?;
+        let [arg1_rlc, arg2_rlc, arg3_rlc, arg4_rlc, output1_rlc, output2_rlc] = self
+            .assign_word_rlcs(
+                config,
+                ctx,
+                chips,
+                [
+                    (&aux_data.p[0], p.x_le),
+                    (&aux_data.p[1], p.y_le),
+                    (&aux_data.q[0], q.x_le),
+                    (&aux_data.q[1], q.y_le),
+                    (&aux_data.r[0], r_x_le),
+                    (&aux_data.r[1], r_y_le),
+                ]
+                .map(|(word, bytes_le)| Some(AssignedWord { word, bytes_le })),
+                challenges,
+            )?;
+
+        Ok(AssignedEccTableRow {
+            op_type,
+            arg1_rlc,
+            arg2_rlc,
+            arg3_rlc,
+            arg4_rlc,
+            output1_rlc,
+            output2_rlc,
+            is_valid,
+        })
+    }
+
+    fn assign_ec_mul(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        aux_data: &EcMulAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEccTableRow<F>, Error> {
+        let main_gate = chips.main_gate;
+        let ecc_chip = chips.ecc_chip;
+        let scalar_chip = ecc_chip.scalar_field_chip();
+
+        let p = self.assign_input_point(ctx, chips, &aux_data.p)?;
+        let (s, s_le) = self.assign_scalar(ctx, chips, &aux_data.s)?;
+
+        // The reduced scalar is in the field, so it's zero iff its limbs are.
+        let s_limbs_sum = main_gate.compose(
+            ctx,
+            &s.limbs()
+                .iter()
+                .map(|limb| Term::Assigned(limb.as_ref(), F::ONE))
+                .collect_vec(),
+            F::ZERO,
+        )?;
+        let is_s_zero = main_gate.is_zero(ctx, &s_limbs_sum)?;
+
+        // The multiplication is done for a finite point and a non-zero
+        // scalar, and on the generator and one otherwise.
+        let is_multiplication = {
+            let is_p_finite = main_gate.not(ctx, &p.is_infinity)?;
+            let is_s_non_zero = main_gate.not(ctx, &is_s_zero)?;
+            let is_multiplication = main_gate.mul(ctx, &is_p_finite, &is_s_non_zero)?;
+            main_gate.mul(ctx, &is_multiplication, &p.is_valid)?
+        };
+        let generator = ecc_chip.assign_constant(ctx, G1Affine::generator())?;
+        let one = scalar_chip.assign_constant(ctx, Fr::ONE)?;
+        let point = ecc_chip.select(ctx, &is_multiplication, &p.point, &generator)?;
+        let scalar = scalar_chip.select(ctx, &s, &one, &is_multiplication)?;
+        let product = ecc_chip.mul(ctx, &point, &scalar, self.window_size)?;
+
+        // The result is zero otherwise.
+        let zero = self.assign_zero_point(ctx, chips)?;
+        let r = ecc_chip.select(ctx, &is_multiplication, &product, &zero)?;
+
+        let [r_x_le, r_y_le] = self.assign_output_point(ctx, chips, &r, &aux_data.r)?;
+
+        let op_type =
+            main_gate.assign_constant(ctx, F::from(PrecompileCalls::Bn128Mul.address()))?;
+        let [arg1_rlc, arg2_rlc, arg3_rlc, arg4_rlc, output1_rlc, output2_rlc] = self
+            .assign_word_rlcs(
+                config,
+                ctx,
+                chips,
+                [
+                    Some(AssignedWord {
+                        word: &aux_data.p[0],
+                        bytes_le: p.x_le,
+                    }),
+                    Some(AssignedWord {
+                        word: &aux_data.p[1],
+                        bytes_le: p.y_le,
+                    }),
+                    Some(AssignedWord {
+                        word: &aux_data.s,
+                        bytes_le: s_le,
+                    }),
+                    None,
+                    Some(AssignedWord {
+                        word: &aux_data.r[0],
+                        bytes_le: r_x_le,
+                    }),
+                    Some(AssignedWord {
+                        word: &aux_data.r[1],
+                        bytes_le: r_y_le,
+                    }),
+                ],
+                challenges,
+            )?;
+
+        Ok(AssignedEccTableRow {
+            op_type,
+            arg1_rlc,
+            arg2_rlc,
+            arg3_rlc,
+            arg4_rlc,
+            output1_rlc,
+            output2_rlc,
+            is_valid: p.is_valid,
+        })
+    }
+
+    fn assign_ecc_table(
+        &self,
+        config: &EccCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedEccTableRow<F>],
+    ) -> Result<(), Error> {
+        let ecc_table = &config.ecc_table;
+        layouter.assign_region(
+            || "ecc table",
+            |mut region| {
+                // All-zero row for the disabled lookups
+                region.assign_fixed(
+                    || "ecc table all-zero row",
+                    ecc_table.q_enable,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+                for column in <EccTable as LookupTable<F>>::advice_columns(ecc_table) {
+                    region.assign_advice(
+                        || "ecc table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (idx, row) in rows.iter().enumerate() {
+                    let offset = idx + 1;
+                    region.assign_fixed(
+                        || format!("ecc table q_enable {}", offset),
+                        ecc_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, cell) in [
+                        (ecc_table.op_type, &row.op_type),
+                        (ecc_table.arg1_rlc, &row.arg1_rlc),
+                        (ecc_table.arg2_rlc, &row.arg2_rlc),
+                        (ecc_table.arg3_rlc, &row.arg3_rlc),
+                        (ecc_table.arg4_rlc, &row.arg4_rlc),
+                        (ecc_table.output1_rlc, &row.output1_rlc),
+                        (ecc_table.output2_rlc, &row.output2_rlc),
+                        (ecc_table.is_valid, &row.is_valid),
+                    ] {
+                        cell.copy_advice(
+                            || format!("ecc table row {}", offset),
+                            &mut region,
+                            column,
+                            offset,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn assign(
+        &self,
+        config: &EccCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let add_ops = Self::padded_ops("ecAdd", &self.add_ops, self.max_add_ops)?;
+        let mul_ops = Self::padded_ops("ecMul", &self.mul_ops, self.max_mul_ops)?;
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let mut ecc_chip = EccChip::<F>::new(config.ecc_chip_config());
+        let (rns_base, rns_scalar) = EccChip::<F>::rns();
+
+        layouter.assign_region(
+            || "ecc chip aux",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                self.assign_aux(&mut ctx, &mut ecc_chip)?;
+                log::debug!("ecc chip aux: {} rows", ctx.offset());
+                Ok(())
+            },
+        )?;
+
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            rns_base: Rc::new(rns_base),
+            rns_scalar: Rc::new(rns_scalar),
+        };
+
+        let rows = layouter.assign_region(
+            || "ecc circuit",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let mut rows = Vec::with_capacity(add_ops.len() + mul_ops.len());
+                for aux_data in add_ops.iter() {
+                    rows.push(self.assign_ec_add(config, &mut ctx, &chips, aux_data, challenges)?);
+                }
+                for aux_data in mul_ops.iter() {
+                    rows.push(self.assign_ec_mul(config, &mut ctx, &chips, aux_data, challenges)?);
+                }
+                log::debug!("ecc circuit: {} rows", ctx.offset());
+                Ok(rows)
+            },
+        )?;
+
+        self.assign_ecc_table(config, layouter, &rows)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EccCircuit<F> {
+    type Config = EccCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `max_ec_add` and `max_ec_mul` circuit parameters, when non-zero,
+    /// set up the circuit to support a fixed number of calls, independently
+    /// of the ones of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ec_add,
+            block.circuits_params.max_ec_mul,
+            block.precompile_events.get_ec_add_events(),
+            block.precompile_events.get_ec_mul_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let num_add_ops = block.precompile_events.get_ec_add_events().len();
+        let num_mul_ops = block.precompile_events.get_ec_mul_events().len();
+        (
+            Self::min_num_rows(num_add_ops, num_mul_ops),
+            Self::min_num_rows(
+                block.circuits_params.max_ec_add.max(num_add_ops),
+                block.circuits_params.max_ec_mul.max(num_mul_ops),
+            ),
+        )
+    }
+
+    /// Make the assignments to the EccCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_aux_tables(layouter)?;
+        self.assign(config, layouter, challenges)
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
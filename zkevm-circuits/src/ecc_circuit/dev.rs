@@ -0,0 +1,49 @@
+pub use super::EccCircuit;
+
+use crate::{
+    ecc_circuit::{EccCircuitConfig, EccCircuitConfigArgs},
+    table::EccTable,
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for EccCircuit<F> {
+    type Config = (EccCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.max_add_ops, self.max_mul_ops, vec![], vec![])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let ecc_table = EccTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            EccCircuitConfig::new(
+                meta,
+                EccCircuitConfigArgs {
+                    ecc_table,
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
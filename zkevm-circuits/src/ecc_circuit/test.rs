@@ -0,0 +1,122 @@
+use super::*;
+use crate::util::unusable_rows;
+use bus_mapping::precompile::bn254_g1_point;
+use halo2_proofs::dev::MockProver;
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn ecc_circuit_unusable_rows() {
+    assert_eq!(
+        EccCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EccCircuit::<Fr>>(()),
+    )
+}
+
+fn verify(k: u32, circuit: EccCircuit<Fr>, success: bool) {
+    let prover = MockProver::run(k, &circuit, circuit.instance()).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+fn to_words(point: G1Affine) -> [Word; 2] {
+    [point.x, point.y].map(|c| Word::from_little_endian(&c.to_repr()))
+}
+
+fn ec_add(p: [Word; 2], q: [Word; 2]) -> EcAddAuxData {
+    let r = match (bn254_g1_point(&p), bn254_g1_point(&q)) {
+        (Some(p), Some(q)) => to_words((G1::from(p) + q).to_affine()),
+        _ => [Word::zero(); 2],
+    };
+    EcAddAuxData { p, q, r }
+}
+
+fn ec_mul(p: [Word; 2], s: Word) -> EcMulAuxData {
+    let r = match bn254_g1_point(&p) {
+        Some(point) => {
+            let s_reduced = BigUint::from_bytes_le(&s.to_le_bytes()) % group_order();
+            let s_reduced = Word::from_little_endian(&s_reduced.to_bytes_le());
+            let scalar = Fr::from_repr(s_reduced.to_le_bytes()).unwrap();
+            to_words((point * scalar).to_affine())
+        }
+        None => [Word::zero(); 2],
+    };
+    EcMulAuxData { p, s, r }
+}
+
+fn random_point(rng: &mut XorShiftRng) -> [Word; 2] {
+    to_words(G1::random(rng).to_affine())
+}
+
+#[test]
+fn ecc_circuit_ec_add() {
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let (p, q) = (random_point(&mut rng), random_point(&mut rng));
+    let p_neg = to_words(-bn254_g1_point(&p).unwrap());
+    let zero = [Word::zero(); 2];
+    // (1, 3) is in the base field but not on the curve.
+    let invalid = [Word::one(), Word::from(3)];
+    let add_ops = vec![
+        ec_add(p, q),
+        // Doubling
+        ec_add(p, p),
+        // P + (-P) = 0
+        ec_add(p, p_neg),
+        ec_add(zero, q),
+        ec_add(p, zero),
+        ec_add(zero, zero),
+        ec_add(p, invalid),
+        ec_add(invalid, zero),
+    ];
+    let circuit = EccCircuit::new(10, 0, add_ops, vec![]);
+    verify(19, circuit, true);
+}
+
+#[test]
+fn ecc_circuit_ec_add_wrong_result() {
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let (p, q) = (random_point(&mut rng), random_point(&mut rng));
+    let mut op = ec_add(p, q);
+    op.r = ec_add(p, p).r;
+    let circuit = EccCircuit::new(1, 0, vec![op], vec![]);
+    verify(19, circuit, false);
+}
+
+#[test]
+fn ecc_circuit_ec_mul() {
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let p = random_point(&mut rng);
+    let zero = [Word::zero(); 2];
+    let mul_ops = vec![
+        ec_mul(p, Word::from(0x1234)),
+        // Scalar greater than the order of the group
+        ec_mul(p, Word::MAX),
+        ec_mul(p, Word::zero()),
+        ec_mul(zero, Word::from(5)),
+        ec_mul([Word::one(), Word::from(3)], Word::from(5)),
+    ];
+    let circuit = EccCircuit::new(0, 5, vec![], mul_ops);
+    verify(20, circuit, true);
+}
+
+#[test]
+fn ecc_circuit_ec_mul_wrong_result() {
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let p = random_point(&mut rng);
+    let mut op = ec_mul(p, Word::from(2));
+    op.r = ec_mul(p, Word::from(3)).r;
+    let circuit = EccCircuit::new(0, 1, vec![], vec![op]);
+    verify(20, circuit, false);
+}
+
+#[test]
+fn ecc_circuit_too_many_ops() {
+    let circuit = EccCircuit::<Fr>::new(1, 0, vec![EcAddAuxData::default(); 2], vec![]);
+    assert!(MockProver::run(19, &circuit, circuit.instance()).is_err());
+}
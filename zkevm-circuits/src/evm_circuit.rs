@@ -22,7 +22,9 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        LookupTable, ModExpTable, PairingTable, Ripemd160Table, RwTable, Sha256Table, SigTable,
+        TxTable,
     },
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
@@ -48,6 +50,13 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     exp_table: ExpTable,
+    sig_table: SigTable,
+    sha256_table: Sha256Table,
+    ripemd160_table: Ripemd160Table,
+    modexp_table: ModExpTable,
+    ecc_table: EccTable,
+    pairing_table: PairingTable,
+    blake2f_table: Blake2fTable,
 }
 
 /// Circuit configuration arguments
@@ -68,6 +77,20 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// ExpTable
     pub exp_table: ExpTable,
+    /// SigTable
+    pub sig_table: SigTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// EccTable
+    pub ecc_table: EccTable,
+    /// PairingTable
+    pub pairing_table: PairingTable,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
 }
 
 impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
@@ -85,6 +108,13 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            pairing_table,
+            blake2f_table,
         }: Self::ConfigArgs,
     ) -> Self {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
@@ -101,6 +131,13 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &exp_table,
+            &sig_table,
+            &sha256_table,
+            &ripemd160_table,
+            &modexp_table,
+            &ecc_table,
+            &pairing_table,
+            &blake2f_table,
         ));
 
         meta.annotate_lookup_any_column(byte_table[0], || "byte_range");
@@ -114,6 +151,13 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         copy_table.annotate_columns(meta);
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
+        sig_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        ripemd160_table.annotate_columns(meta);
+        modexp_table.annotate_columns(meta);
+        ecc_table.annotate_columns(meta);
+        pairing_table.annotate_columns(meta);
+        blake2f_table.annotate_columns(meta);
 
         Self {
             fixed_table,
@@ -126,6 +170,13 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            pairing_table,
+            blake2f_table,
         }
     }
 }
@@ -375,6 +426,13 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let pairing_table = PairingTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
 
@@ -390,6 +448,13 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     exp_table,
+                    sig_table,
+                    sha256_table,
+                    ripemd160_table,
+                    modexp_table,
+                    ecc_table,
+                    pairing_table,
+                    blake2f_table,
                 },
             ),
             challenges,
@@ -431,6 +496,27 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
         config.exp_table.load(&mut layouter, block)?;
+        config
+            .sig_table
+            .dev_load(&mut layouter, block, &challenges)?;
+        config
+            .sha256_table
+            .dev_load(&mut layouter, &block.sha256_inputs, &challenges)?;
+        config
+            .ripemd160_table
+            .dev_load(&mut layouter, &block.ripemd160_inputs, &challenges)?;
+        config
+            .modexp_table
+            .dev_load(&mut layouter, &block.modexp_inputs, &challenges)?;
+        config
+            .ecc_table
+            .dev_load(&mut layouter, block, &challenges)?;
+        config
+            .pairing_table
+            .dev_load(&mut layouter, block, &challenges)?;
+        config
+            .blake2f_table
+            .dev_load(&mut layouter, block, &challenges)?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
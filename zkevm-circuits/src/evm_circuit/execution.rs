@@ -1,8 +1,10 @@
 use super::{
     param::{
-        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
-        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, N_BYTE_LOOKUPS, N_COPY_COLUMNS,
-        N_PHASE1_COLUMNS, RW_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
+        BLAKE2F_TABLE_LOOKUPS, BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS,
+        ECC_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS, FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS,
+        MODEXP_TABLE_LOOKUPS, N_BYTE_LOOKUPS, N_COPY_COLUMNS, N_PHASE1_COLUMNS,
+        PAIRING_TABLE_LOOKUPS, RIPEMD160_TABLE_LOOKUPS, RW_TABLE_LOOKUPS, SHA256_TABLE_LOOKUPS,
+        SIG_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
     },
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, CellManager, StoredExpression},
@@ -45,6 +47,8 @@ mod address;
 mod balance;
 mod begin_tx;
 mod bitwise;
+mod blobbasefee;
+mod blobhash;
 mod block_ctx;
 mod blockhash;
 mod byte;
@@ -58,17 +62,26 @@ mod chainid;
 mod codecopy;
 mod codesize;
 mod comparator;
-mod dummy;
+mod create;
 mod dup;
 mod end_block;
 mod end_tx;
+mod error_code_store;
+mod error_contract_address_collision;
+mod error_depth;
+mod error_invalid_creation_code;
 mod error_invalid_jump;
 mod error_invalid_opcode;
+mod error_oog_account_access;
 mod error_oog_call;
 mod error_oog_constant;
+mod error_oog_create2;
+mod error_oog_dynamic_memory;
 mod error_oog_exp;
 mod error_oog_log;
 mod error_oog_memory_copy;
+mod error_oog_self_destruct;
+mod error_oog_sha3;
 mod error_oog_sload_sstore;
 mod error_oog_static_memory;
 mod error_return_data_oo_bound;
@@ -85,6 +98,7 @@ mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -102,6 +116,7 @@ mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -110,6 +125,8 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
 
 use self::sha3::Sha3Gadget;
 use add_sub::AddSubGadget;
@@ -118,6 +135,8 @@ use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+use blobbasefee::BlobBaseFeeGadget;
+use blobhash::BlobHashGadget;
 use block_ctx::{BlockCtxU160Gadget, BlockCtxU256Gadget, BlockCtxU64Gadget};
 use blockhash::BlockHashGadget;
 use byte::ByteGadget;
@@ -131,18 +150,28 @@ use chainid::ChainIdGadget;
 use codecopy::CodeCopyGadget;
 use codesize::CodesizeGadget;
 use comparator::ComparatorGadget;
-use dummy::DummyGadget;
+use create::CreateGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
 use end_tx::EndTxGadget;
+use error_code_store::ErrorCodeStoreGadget;
+use error_contract_address_collision::ErrorContractAddressCollisionGadget;
+use error_depth::ErrorDepthGadget;
+use error_invalid_creation_code::ErrorInvalidCreationCodeGadget;
 use error_invalid_jump::ErrorInvalidJumpGadget;
 use error_invalid_opcode::ErrorInvalidOpcodeGadget;
+use error_oog_account_access::ErrorOOGAccountAccessGadget;
 use error_oog_call::ErrorOOGCallGadget;
 use error_oog_constant::ErrorOOGConstantGadget;
+use error_oog_create2::ErrorOOGCreate2Gadget;
+use error_oog_dynamic_memory::ErrorOOGDynamicMemoryGadget;
 use error_oog_exp::ErrorOOGExpGadget;
 use error_oog_log::ErrorOOGLogGadget;
 use error_oog_memory_copy::ErrorOOGMemoryCopyGadget;
+use error_oog_self_destruct::ErrorOOGSelfDestructGadget;
+use error_oog_sha3::ErrorOOGSha3Gadget;
 use error_oog_sload_sstore::ErrorOOGSloadSstoreGadget;
+use error_oog_static_memory::ErrorOOGStaticMemoryGadget;
 use error_return_data_oo_bound::ErrorReturnDataOutOfBoundGadget;
 use error_stack::ErrorStackGadget;
 use error_write_protection::ErrorWriteProtectionGadget;
@@ -157,6 +186,7 @@ use jump::JumpGadget;
 use jumpdest::JumpdestGadget;
 use jumpi::JumpiGadget;
 use logs::LogGadget;
+use mcopy::McopyGadget;
 use memory::MemoryGadget;
 use msize::MsizeGadget;
 use mul_div_mod::MulDivModGadget;
@@ -165,6 +195,10 @@ use opcode_not::NotGadget;
 use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
+use precompiles::{
+    Blake2fGadget, EcAddGadget, EcMulGadget, EcPairingGadget, EcrecoverGadget, IdentityGadget,
+    ModExpGadget, Ripemd160Gadget, Sha256Gadget,
+};
 use push::PushGadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
@@ -172,6 +206,7 @@ use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+use selfdestruct::SelfDestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -179,6 +214,8 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+use tload::TloadGadget;
+use tstore::TstoreGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -238,6 +275,7 @@ pub struct ExecutionConfig<F> {
     calldatasize_gadget: Box<CallDataSizeGadget<F>>,
     caller_gadget: Box<CallerGadget<F>>,
     chainid_gadget: Box<ChainIdGadget<F>>,
+    blobbasefee_gadget: Box<BlobBaseFeeGadget<F>>,
     codecopy_gadget: Box<CodeCopyGadget<F>>,
     codesize_gadget: Box<CodesizeGadget<F>>,
     comparator_gadget: Box<ComparatorGadget<F>>,
@@ -255,6 +293,7 @@ pub struct ExecutionConfig<F> {
     log_gadget: Box<LogGadget<F>>,
     memory_gadget: Box<MemoryGadget<F>>,
     msize_gadget: Box<MsizeGadget<F>>,
+    mcopy_gadget: Box<McopyGadget<F>>,
     mul_div_mod_gadget: Box<MulDivModGadget<F>>,
     mulmod_gadget: Box<MulModGadget<F>>,
     not_gadget: Box<NotGadget<F>>,
@@ -270,16 +309,19 @@ pub struct ExecutionConfig<F> {
     shl_shr_gadget: Box<ShlShrGadget<F>>,
     returndatasize_gadget: Box<ReturnDataSizeGadget<F>>,
     returndatacopy_gadget: Box<ReturnDataCopyGadget<F>>,
-    create_gadget: Box<DummyGadget<F, 3, 1, { ExecutionState::CREATE }>>,
-    create2_gadget: Box<DummyGadget<F, 4, 1, { ExecutionState::CREATE2 }>>,
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
+    create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
+    selfdestruct_gadget: Box<SelfDestructGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    tload_gadget: Box<TloadGadget<F>>,
+    tstore_gadget: Box<TstoreGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
+    blobhash_gadget: Box<BlobHashGadget<F>>,
     block_ctx_u64_gadget: Box<BlockCtxU64Gadget<F>>,
     block_ctx_u160_gadget: Box<BlockCtxU160Gadget<F>>,
     block_ctx_u256_gadget: Box<BlockCtxU256Gadget<F>>,
@@ -289,29 +331,33 @@ pub struct ExecutionConfig<F> {
     error_oog_exp: Box<ErrorOOGExpGadget<F>>,
     error_oog_memory_copy: Box<ErrorOOGMemoryCopyGadget<F>>,
     error_oog_sload_sstore: Box<ErrorOOGSloadSstoreGadget<F>>,
-    error_oog_static_memory_gadget:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasStaticMemoryExpansion }>>,
+    error_oog_static_memory_gadget: Box<ErrorOOGStaticMemoryGadget<F>>,
     error_stack: Box<ErrorStackGadget<F>>,
     error_write_protection: Box<ErrorWriteProtectionGadget<F>>,
-    error_oog_dynamic_memory_gadget:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasDynamicMemoryExpansion }>>,
+    error_oog_dynamic_memory_gadget: Box<ErrorOOGDynamicMemoryGadget<F>>,
     error_oog_log: Box<ErrorOOGLogGadget<F>>,
-    error_oog_account_access:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasAccountAccess }>>,
-    error_oog_sha3: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasSHA3 }>>,
-    error_oog_ext_codecopy: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasEXTCODECOPY }>>,
-    error_oog_create2: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasCREATE2 }>>,
-    error_oog_self_destruct:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasSELFDESTRUCT }>>,
-    error_oog_code_store: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorOutOfGasCodeStore }>>,
+    error_oog_account_access: Box<ErrorOOGAccountAccessGadget<F>>,
+    error_oog_sha3: Box<ErrorOOGSha3Gadget<F>>,
+    error_oog_create2: Box<ErrorOOGCreate2Gadget<F>>,
+    error_oog_self_destruct: Box<ErrorOOGSelfDestructGadget<F>>,
+    error_oog_code_store: Box<ErrorCodeStoreGadget<F, false>>,
+    error_max_code_size_exceeded: Box<ErrorCodeStoreGadget<F, true>>,
     error_invalid_jump: Box<ErrorInvalidJumpGadget<F>>,
     error_invalid_opcode: Box<ErrorInvalidOpcodeGadget<F>>,
-    error_depth: Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorDepth }>>,
-    error_contract_address_collision:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorContractAddressCollision }>>,
-    error_invalid_creation_code:
-        Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorInvalidCreationCode }>>,
+    error_depth: Box<ErrorDepthGadget<F>>,
+    error_contract_address_collision: Box<ErrorContractAddressCollisionGadget<F>>,
+    error_invalid_creation_code: Box<ErrorInvalidCreationCodeGadget<F>>,
     error_return_data_out_of_bound: Box<ErrorReturnDataOutOfBoundGadget<F>>,
+    // precompile calls
+    precompile_ecrecover_gadget: Box<EcrecoverGadget<F>>,
+    precompile_sha256_gadget: Box<Sha256Gadget<F>>,
+    precompile_ripemd160_gadget: Box<Ripemd160Gadget<F>>,
+    precompile_identity_gadget: Box<IdentityGadget<F>>,
+    precompile_modexp_gadget: Box<ModExpGadget<F>>,
+    precompile_ec_add_gadget: Box<EcAddGadget<F>>,
+    precompile_ec_mul_gadget: Box<EcMulGadget<F>>,
+    precompile_ec_pairing_gadget: Box<EcPairingGadget<F>>,
+    precompile_blake2f_gadget: Box<Blake2fGadget<F>>,
 }
 
 impl<F: Field> ExecutionConfig<F> {
@@ -329,6 +375,13 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        pairing_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
     ) -> Self {
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
@@ -503,6 +556,7 @@ impl<F: Field> ExecutionConfig<F> {
             calldatasize_gadget: configure_gadget!(),
             caller_gadget: configure_gadget!(),
             chainid_gadget: configure_gadget!(),
+            blobbasefee_gadget: configure_gadget!(),
             codecopy_gadget: configure_gadget!(),
             codesize_gadget: configure_gadget!(),
             comparator_gadget: configure_gadget!(),
@@ -518,6 +572,7 @@ impl<F: Field> ExecutionConfig<F> {
             log_gadget: configure_gadget!(),
             memory_gadget: configure_gadget!(),
             msize_gadget: configure_gadget!(),
+            mcopy_gadget: configure_gadget!(),
             mul_div_mod_gadget: configure_gadget!(),
             mulmod_gadget: configure_gadget!(),
             not_gadget: configure_gadget!(),
@@ -532,6 +587,7 @@ impl<F: Field> ExecutionConfig<F> {
             address_gadget: configure_gadget!(),
             balance_gadget: configure_gadget!(),
             blockhash_gadget: configure_gadget!(),
+            blobhash_gadget: configure_gadget!(),
             exp_gadget: configure_gadget!(),
             sar_gadget: configure_gadget!(),
             extcodecopy_gadget: configure_gadget!(),
@@ -547,6 +603,8 @@ impl<F: Field> ExecutionConfig<F> {
             sstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
+            tload_gadget: configure_gadget!(),
+            tstore_gadget: configure_gadget!(),
             block_ctx_u64_gadget: configure_gadget!(),
             block_ctx_u160_gadget: configure_gadget!(),
             block_ctx_u256_gadget: configure_gadget!(),
@@ -561,11 +619,11 @@ impl<F: Field> ExecutionConfig<F> {
             error_oog_memory_copy: configure_gadget!(),
             error_oog_account_access: configure_gadget!(),
             error_oog_sha3: configure_gadget!(),
-            error_oog_ext_codecopy: configure_gadget!(),
             error_oog_exp: configure_gadget!(),
             error_oog_create2: configure_gadget!(),
             error_oog_self_destruct: configure_gadget!(),
             error_oog_code_store: configure_gadget!(),
+            error_max_code_size_exceeded: configure_gadget!(),
             error_invalid_jump: configure_gadget!(),
             error_invalid_opcode: configure_gadget!(),
             error_write_protection: configure_gadget!(),
@@ -573,6 +631,16 @@ impl<F: Field> ExecutionConfig<F> {
             error_contract_address_collision: configure_gadget!(),
             error_invalid_creation_code: configure_gadget!(),
             error_return_data_out_of_bound: configure_gadget!(),
+            // precompile calls
+            precompile_ecrecover_gadget: configure_gadget!(),
+            precompile_sha256_gadget: configure_gadget!(),
+            precompile_ripemd160_gadget: configure_gadget!(),
+            precompile_identity_gadget: configure_gadget!(),
+            precompile_modexp_gadget: configure_gadget!(),
+            precompile_ec_add_gadget: configure_gadget!(),
+            precompile_ec_mul_gadget: configure_gadget!(),
+            precompile_ec_pairing_gadget: configure_gadget!(),
+            precompile_blake2f_gadget: configure_gadget!(),
             // step and presets
             step: step_curr,
             height_map,
@@ -591,6 +659,13 @@ impl<F: Field> ExecutionConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            sig_table,
+            sha256_table,
+            ripemd160_table,
+            modexp_table,
+            ecc_table,
+            pairing_table,
+            blake2f_table,
             &challenges,
             &cell_manager,
         );
@@ -807,6 +882,13 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        sig_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        ripemd160_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ecc_table: &dyn LookupTable<F>,
+        pairing_table: &dyn LookupTable<F>,
+        blake2f_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<F>,
     ) {
@@ -823,6 +905,13 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Copy => copy_table,
                         Table::Keccak => keccak_table,
                         Table::Exp => exp_table,
+                        Table::Sig => sig_table,
+                        Table::Sha256 => sha256_table,
+                        Table::Ripemd160 => ripemd160_table,
+                        Table::ModExp => modexp_table,
+                        Table::Ecc => ecc_table,
+                        Table::Pairing => pairing_table,
+                        Table::Blake2f => blake2f_table,
                     }
                     .table_exprs(meta);
                     vec![(
@@ -1036,6 +1125,13 @@ impl<F: Field> ExecutionConfig<F> {
             ("EVM_lookup_copy", COPY_TABLE_LOOKUPS),
             ("EVM_lookup_keccak", KECCAK_TABLE_LOOKUPS),
             ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
+            ("EVM_lookup_sig", SIG_TABLE_LOOKUPS),
+            ("EVM_lookup_sha256", SHA256_TABLE_LOOKUPS),
+            ("EVM_lookup_ripemd160", RIPEMD160_TABLE_LOOKUPS),
+            ("EVM_lookup_modexp", MODEXP_TABLE_LOOKUPS),
+            ("EVM_lookup_ecc", ECC_TABLE_LOOKUPS),
+            ("EVM_lookup_pairing", PAIRING_TABLE_LOOKUPS),
+            ("EVM_lookup_blake2f", BLAKE2F_TABLE_LOOKUPS),
             ("EVM_adv_phase2", N_PHASE2_COLUMNS),
             ("EVM_copy", N_COPY_COLUMNS),
             ("EVM_lookup_byte", N_BYTE_LOOKUPS),
@@ -1186,6 +1282,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::CALLER => assign_exec_step!(self.caller_gadget),
             ExecutionState::CALLVALUE => assign_exec_step!(self.call_value_gadget),
             ExecutionState::CHAINID => assign_exec_step!(self.chainid_gadget),
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blobbasefee_gadget),
             ExecutionState::CODECOPY => assign_exec_step!(self.codecopy_gadget),
             ExecutionState::CODESIZE => assign_exec_step!(self.codesize_gadget),
             ExecutionState::CMP => assign_exec_step!(self.comparator_gadget),
@@ -1202,6 +1299,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::LOG => assign_exec_step!(self.log_gadget),
             ExecutionState::MEMORY => assign_exec_step!(self.memory_gadget),
             ExecutionState::MSIZE => assign_exec_step!(self.msize_gadget),
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
             ExecutionState::MUL_DIV_MOD => assign_exec_step!(self.mul_div_mod_gadget),
             ExecutionState::MULMOD => assign_exec_step!(self.mulmod_gadget),
             ExecutionState::NOT => assign_exec_step!(self.not_gadget),
@@ -1219,6 +1317,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::BLOCKCTXU160 => assign_exec_step!(self.block_ctx_u160_gadget),
             ExecutionState::BLOCKCTXU256 => assign_exec_step!(self.block_ctx_u256_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
             // dummy gadgets
             ExecutionState::EXTCODECOPY => assign_exec_step!(self.extcodecopy_gadget),
@@ -1231,6 +1330,8 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
             ExecutionState::SLOAD => assign_exec_step!(self.sload_gadget),
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
+            ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
             // dummy errors
@@ -1261,9 +1362,6 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::ErrorOutOfGasSHA3 => {
                 assign_exec_step!(self.error_oog_sha3)
             }
-            ExecutionState::ErrorOutOfGasEXTCODECOPY => {
-                assign_exec_step!(self.error_oog_ext_codecopy)
-            }
             ExecutionState::ErrorOutOfGasEXP => {
                 assign_exec_step!(self.error_oog_exp)
             }
@@ -1277,6 +1375,9 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::ErrorOutOfGasCodeStore => {
                 assign_exec_step!(self.error_oog_code_store)
             }
+            ExecutionState::ErrorMaxCodeSizeExceeded => {
+                assign_exec_step!(self.error_max_code_size_exceeded)
+            }
             ExecutionState::ErrorStack => {
                 assign_exec_step!(self.error_stack)
             }
@@ -1305,6 +1406,34 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::ErrorReturnDataOutOfBound => {
                 assign_exec_step!(self.error_return_data_out_of_bound)
             }
+            // precompile calls
+            ExecutionState::PrecompileEcRecover => {
+                assign_exec_step!(self.precompile_ecrecover_gadget)
+            }
+            ExecutionState::PrecompileSha256 => {
+                assign_exec_step!(self.precompile_sha256_gadget)
+            }
+            ExecutionState::PrecompileRipemd160 => {
+                assign_exec_step!(self.precompile_ripemd160_gadget)
+            }
+            ExecutionState::PrecompileIdentity => {
+                assign_exec_step!(self.precompile_identity_gadget)
+            }
+            ExecutionState::PrecompileBigModExp => {
+                assign_exec_step!(self.precompile_modexp_gadget)
+            }
+            ExecutionState::PrecompileBn256Add => {
+                assign_exec_step!(self.precompile_ec_add_gadget)
+            }
+            ExecutionState::PrecompileBn256ScalarMul => {
+                assign_exec_step!(self.precompile_ec_mul_gadget)
+            }
+            ExecutionState::PrecompileBn256Pairing => {
+                assign_exec_step!(self.precompile_ec_pairing_gadget)
+            }
+            ExecutionState::PrecompileBlake2f => {
+                assign_exec_step!(self.precompile_blake2f_gadget)
+            }
 
             unimpl_state => evm_unimplemented!("unimplemented ExecutionState: {:?}", unimpl_state),
         }
@@ -1,31 +1,41 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_WORD},
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_U64, N_BYTES_WORD},
         step::ExecutionState,
         util::{
             and,
-            common_gadget::TransferWithGasFeeGadget,
+            common_gadget::{HardforkGadget, TransferWithGasFeeGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
-            is_precompiled,
             math_gadget::{
                 AddWordsGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget, LtGadget,
                 LtWordGadget, MulWordByU64Gadget,
             },
-            not, or, select, CachedRegion, Cell, StepRws, Word,
+            memory_gadget::MemoryWordSizeGadget,
+            not, or, select, sum, CachedRegion, Cell, StepRws, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::{AccountFieldTag, CallContextFieldTag, TxFieldTag as TxContextFieldTag},
+    table::{
+        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxFieldTag as TxContextFieldTag,
+    },
     util::Expr,
 };
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
+use bus_mapping::{circuit_input_builder::MAX_ACCESS_LIST_ENTRIES, precompile::is_precompiled};
+use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::{gas_utils::init_code_gas_cost, GasCost, MAX_INIT_CODE_SIZE},
+    Field, ToLittleEndian, ToScalar, U256,
+};
 use ethers_core::utils::{get_contract_address, keccak256};
 use gadgets::util::expr_from_bytes;
-use halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct BeginTxGadget<F> {
@@ -33,7 +43,18 @@ pub(crate) struct BeginTxGadget<F> {
     tx_nonce: Cell<F>,
     tx_gas: Cell<F>,
     tx_gas_price: Word<F>,
+    tx_type: Cell<F>,
+    is_legacy_tx: IsZeroGadget<F>,
+    tx_max_fee_per_gas: Word<F>,
+    tx_max_priority_fee_per_gas: Word<F>,
+    base_fee: Word<F>,
+    is_fee_cap_too_low: LtWordGadget<F>,
+    is_tip_above_fee_cap: LtWordGadget<F>,
+    base_fee_plus_tip: AddWordsGadget<F, 2, true>,
+    base_fee_plus_tip_sum: Word<F>,
+    is_tip_capped: LtWordGadget<F>,
     mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
+    mul_max_fee_by_gas: MulWordByU64Gadget<F>,
     tx_caller_address: Cell<F>,
     tx_caller_address_is_zero: IsZeroGadget<F>,
     tx_callee_address: Cell<F>,
@@ -44,6 +65,12 @@ pub(crate) struct BeginTxGadget<F> {
     tx_call_data_gas_cost: Cell<F>,
     tx_is_invalid: Cell<F>,
     tx_access_list_gas_cost: Cell<F>,
+    tx_access_list_addresses_len: Cell<F>,
+    tx_access_list_storage_keys_len: Cell<F>,
+    access_list: [AccessListEntry<F>; MAX_ACCESS_LIST_ENTRIES],
+    is_shanghai: HardforkGadget<F>,
+    init_code_word_size: MemoryWordSizeGadget<F>,
+    is_init_code_too_large: LtGadget<F, N_BYTES_U64>,
     nonce: Cell<F>,
     nonce_prev: Cell<F>,
     is_nonce_valid: IsEqualGadget<F>,
@@ -58,11 +85,31 @@ pub(crate) struct BeginTxGadget<F> {
     create: ContractCreateGadget<F, false>,
     callee_not_exists: IsZeroGadget<F>,
     is_caller_callee_equal: Cell<F>,
+    coinbase: Cell<F>,
+    is_coinbase_warm: Cell<F>,
     total_eth_cost: AddWordsGadget<F, 2, true>,
     total_eth_cost_sum: Word<F>,
     balance_not_enough: LtWordGadget<F>,
 }
 
+/// An address or a storage key of the access list of a tx, or padding when
+/// neither flag is set.
+#[derive(Clone, Debug)]
+struct AccessListEntry<F> {
+    address: Cell<F>,
+    storage_key: Cell<F>,
+    is_address: Cell<F>,
+    is_storage_key: Cell<F>,
+    is_warm_prev: Cell<F>,
+    rlc_acc: Cell<F>,
+}
+
+impl<F: Field> AccessListEntry<F> {
+    fn is_used(&self) -> Expression<F> {
+        self.is_address.expr() + self.is_storage_key.expr()
+    }
+}
+
 impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
     const NAME: &'static str = "BeginTx";
 
@@ -87,7 +134,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             reversion_info.is_persistent(),
         ); // rwc_delta += 1
 
-        let [tx_nonce, tx_gas, tx_caller_address, tx_callee_address, tx_is_create, tx_call_data_length, tx_call_data_gas_cost, tx_is_invalid, tx_access_list_gas_cost] =
+        let [tx_nonce, tx_gas, tx_caller_address, tx_callee_address, tx_is_create, tx_call_data_length, tx_call_data_gas_cost, tx_is_invalid, tx_access_list_gas_cost, tx_access_list_addresses_len, tx_access_list_storage_keys_len, tx_type] =
             [
                 TxContextFieldTag::Nonce,
                 TxContextFieldTag::Gas,
@@ -98,6 +145,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 TxContextFieldTag::CallDataGasCost,
                 TxContextFieldTag::TxInvalid,
                 TxContextFieldTag::AccessListGasCost,
+                TxContextFieldTag::AccessListAddressesLen,
+                TxContextFieldTag::AccessListStorageKeysLen,
+                TxContextFieldTag::TxType,
             ]
             .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
         let tx_caller_address_is_zero = IsZeroGadget::construct(cb, tx_caller_address.expr());
@@ -106,8 +156,28 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_caller_address_is_zero.expr(),
             false.expr(),
         );
-        let [tx_gas_price, tx_value] = [TxContextFieldTag::GasPrice, TxContextFieldTag::Value]
-            .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
+        let [tx_gas_price, tx_max_fee_per_gas, tx_max_priority_fee_per_gas, tx_value] = [
+            TxContextFieldTag::GasPrice,
+            TxContextFieldTag::MaxFeePerGas,
+            TxContextFieldTag::MaxPriorityFeePerGas,
+            TxContextFieldTag::Value,
+        ]
+        .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
+
+        // A legacy tx pays its gas price, which is both its fee cap and tip cap.
+        let is_legacy_tx = IsZeroGadget::construct(cb, tx_type.expr());
+        cb.condition(is_legacy_tx.expr(), |cb| {
+            cb.require_equal(
+                "max_fee_per_gas == gas_price for a legacy tx",
+                tx_max_fee_per_gas.expr(),
+                tx_gas_price.expr(),
+            );
+            cb.require_equal(
+                "max_priority_fee_per_gas == gas_price for a legacy tx",
+                tx_max_priority_fee_per_gas.expr(),
+                tx_gas_price.expr(),
+            );
+        });
 
         let call_callee_address = cb.query_cell();
         cb.condition(not::expr(tx_is_create.expr()), |cb| {
@@ -141,21 +211,62 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
-        // TODO: Implement EIP 1559 (currently it only supports legacy
-        // transaction format)
-        // Calculate transaction gas fee
+        // Check the fee caps against the base fee (EIP-1559)
+        let base_fee = cb.query_word_rlc();
+        cb.block_lookup(BlockContextFieldTag::BaseFee.expr(), None, base_fee.expr());
+        let is_fee_cap_too_low = LtWordGadget::construct(cb, &tx_max_fee_per_gas, &base_fee);
+        let is_tip_above_fee_cap =
+            LtWordGadget::construct(cb, &tx_max_fee_per_gas, &tx_max_priority_fee_per_gas);
+
+        // The effective gas price is min(base_fee + max_priority_fee_per_gas,
+        // max_fee_per_gas)
+        let base_fee_plus_tip_sum = cb.query_word_rlc();
+        let base_fee_plus_tip = AddWordsGadget::construct(
+            cb,
+            [base_fee.clone(), tx_max_priority_fee_per_gas.clone()],
+            base_fee_plus_tip_sum.clone(),
+        );
+        let is_tip_capped =
+            LtWordGadget::construct(cb, &tx_max_fee_per_gas, base_fee_plus_tip.sum());
+        cb.condition(not::expr(tx_is_invalid.expr()), |cb| {
+            cb.require_equal(
+                "gas_price == effective gas price",
+                tx_gas_price.expr(),
+                select::expr(
+                    is_tip_capped.expr(),
+                    tx_max_fee_per_gas.expr(),
+                    base_fee_plus_tip.sum().expr(),
+                ),
+            );
+        });
+
+        // Calculate transaction gas fee, and the max gas fee the caller must
+        // be able to afford
         let mul_gas_fee_by_gas =
             MulWordByU64Gadget::construct(cb, tx_gas_price.clone(), tx_gas.expr());
-
-        // TODO: Take gas cost of access list (EIP 2930) into consideration.
-        // Use intrinsic gas
+        let mul_max_fee_by_gas =
+            MulWordByU64Gadget::construct(cb, tx_max_fee_per_gas.clone(), tx_gas.expr());
+
+        // Use intrinsic gas, including the access list gas cost (EIP-2930), where the
+        // initialization code of a creation tx is also charged per word since Shanghai
+        // (EIP-3860).
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        let init_code_word_size = MemoryWordSizeGadget::construct(cb, tx_call_data_length.expr());
         let intrinsic_gas_cost = select::expr(
             tx_is_create.expr(),
-            GasCost::CREATION_TX.expr(),
+            GasCost::CREATION_TX.expr()
+                + is_shanghai.is_enabled()
+                    * GasCost::INIT_CODE_WORD.expr()
+                    * init_code_word_size.expr(),
             GasCost::TX.expr(),
         ) + tx_call_data_gas_cost.expr()
             + tx_access_list_gas_cost.expr();
 
+        // A creation tx with an initialization code over the limit is invalid
+        // since Shanghai (EIP-3860).
+        let is_init_code_too_large =
+            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), tx_call_data_length.expr());
+
         // Check gas_left is sufficient
         let gas_left = tx_gas.expr() - intrinsic_gas_cost.clone();
         let is_gas_not_enough = LtGadget::construct(cb, tx_gas.expr(), intrinsic_gas_cost);
@@ -179,6 +290,122 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
+        // Warm the coinbase since Shanghai (EIP-3651). It may already be warm
+        // when it is the caller or the callee, which is checked by the state
+        // circuit.
+        let coinbase = cb.query_cell();
+        let is_coinbase_warm = cb.query_bool();
+        cb.condition(is_shanghai.is_enabled(), |cb| {
+            cb.block_lookup(BlockContextFieldTag::Coinbase.expr(), None, coinbase.expr());
+            cb.account_access_list_write(
+                tx_id.expr(),
+                coinbase.expr(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            ); // rwc_delta += is_shanghai
+        });
+
+        // Warm the addresses and storage keys of the access list (EIP-2930) in
+        // the order they are listed, where a storage key belongs to the
+        // address of the entry before it. The entries are accumulated into
+        // the AccessListRLC of the tx table, and their number and gas cost are
+        // derived from them.
+        let access_list: [AccessListEntry<F>; MAX_ACCESS_LIST_ENTRIES] =
+            array_init::array_init(|_| AccessListEntry {
+                address: cb.query_cell(),
+                storage_key: cb.query_cell_phase2(),
+                is_address: cb.query_bool(),
+                is_storage_key: cb.query_bool(),
+                is_warm_prev: cb.query_bool(),
+                rlc_acc: cb.query_cell_phase2(),
+            });
+        let evm_word = cb.challenges().evm_word();
+        for (idx, entry) in access_list.iter().enumerate() {
+            cb.require_boolean(
+                "entry is either an address or a storage key",
+                entry.is_used(),
+            );
+            let rlc_acc_prev = if idx == 0 {
+                cb.require_zero(
+                    "first entry is not a storage key",
+                    entry.is_storage_key.expr(),
+                );
+                0.expr()
+            } else {
+                let prev = &access_list[idx - 1];
+                cb.require_zero(
+                    "entries are used before padding",
+                    entry.is_used() * not::expr(prev.is_used()),
+                );
+                cb.condition(entry.is_storage_key.expr(), |cb| {
+                    cb.require_equal(
+                        "storage key belongs to the previous address",
+                        entry.address.expr(),
+                        prev.address.expr(),
+                    );
+                });
+                prev.rlc_acc.expr()
+            };
+            cb.require_equal(
+                "access list rlc accumulation",
+                entry.rlc_acc.expr(),
+                select::expr(
+                    entry.is_used(),
+                    rlc_acc_prev.clone() * evm_word.clone()
+                        + entry.is_address.expr() * entry.address.expr()
+                        + entry.is_storage_key.expr() * entry.storage_key.expr(),
+                    rlc_acc_prev,
+                ),
+            );
+
+            cb.condition(entry.is_address.expr(), |cb| {
+                cb.account_access_list_write(
+                    tx_id.expr(),
+                    entry.address.expr(),
+                    1.expr(),
+                    entry.is_warm_prev.expr(),
+                    None,
+                ); // rwc_delta += is_address
+            });
+            cb.condition(entry.is_storage_key.expr(), |cb| {
+                cb.account_storage_access_list_write(
+                    tx_id.expr(),
+                    entry.address.expr(),
+                    entry.storage_key.expr(),
+                    1.expr(),
+                    entry.is_warm_prev.expr(),
+                    None,
+                ); // rwc_delta += is_storage_key
+            });
+        }
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::AccessListRLC,
+            None,
+            access_list[MAX_ACCESS_LIST_ENTRIES - 1].rlc_acc.expr(),
+        );
+        let access_list_addresses_len = sum::expr(access_list.iter().map(|e| e.is_address.expr()));
+        let access_list_storage_keys_len =
+            sum::expr(access_list.iter().map(|e| e.is_storage_key.expr()));
+        cb.require_equal(
+            "tx access list addresses len",
+            tx_access_list_addresses_len.expr(),
+            access_list_addresses_len.clone(),
+        );
+        cb.require_equal(
+            "tx access list storage keys len",
+            tx_access_list_storage_keys_len.expr(),
+            access_list_storage_keys_len.clone(),
+        );
+        cb.require_equal(
+            "tx access list gas cost",
+            tx_access_list_gas_cost.expr(),
+            GasCost::ACCESS_LIST_ADDRESS.expr() * access_list_addresses_len.clone()
+                + GasCost::ACCESS_LIST_STORAGE.expr() * access_list_storage_keys_len.clone(),
+        );
+        let access_list_len = access_list_addresses_len + access_list_storage_keys_len;
+
         // Read code_hash of callee
         let phase2_code_hash = cb.query_cell_phase2();
         let is_empty_code_hash =
@@ -248,7 +475,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let total_eth_cost_sum = cb.query_word_rlc();
         let total_eth_cost = AddWordsGadget::construct(
             cb,
-            [tx_value.clone(), mul_gas_fee_by_gas.product().clone()],
+            [tx_value.clone(), mul_max_fee_by_gas.product().clone()],
             total_eth_cost_sum.clone(),
         );
         let balance_not_enough =
@@ -259,12 +486,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         // - The transaction requires more ETH than the transaction needs
         // - The amount of gas specified in the transaction is lower than the intrinsic gas cost
         // - The transaction nonce does not match the nonce stored in the account
+        // - The initialization code of a creation transaction is too large
+        // - The max fee per gas is lower than the base fee
+        // - The max priority fee per gas is higher than the max fee per gas
         cb.require_equal(
             "is_tx_invalid is correct",
             or::expr([
                 balance_not_enough.expr(),
                 is_gas_not_enough.expr(),
                 not::expr(is_nonce_valid.expr()),
+                is_fee_cap_too_low.expr(),
+                is_tip_above_fee_cap.expr(),
+                and::expr([
+                    tx_is_create.expr(),
+                    is_shanghai.is_enabled(),
+                    is_init_code_too_large.expr(),
+                ]),
             ]),
             tx_is_invalid.expr(),
         );
@@ -301,6 +538,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             );
             cb.keccak_table_lookup(create.input_rlc(cb), create.input_length(), output_rlc);
 
+            // Record the new account as created in the transaction (EIP-6780).
+            cb.tx_created_account_write(
+                tx_id.expr(),
+                call_callee_address.expr(),
+                1.expr(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             cb.account_write(
                 call_callee_address.expr(),
                 AccountFieldTag::Nonce,
@@ -336,7 +581,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 22 + is_shanghai + a + access_list_len reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
@@ -344,7 +589,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write Account (Caller) Nonce
                 //   - Write TxAccessListAccount
                 //   - Write TxAccessListAccount
+                //   - Write TxAccessListAccount (Coinbase) since Shanghai
+                //   - Write TxAccessListAccount(Storage) of the access list
                 //   - a TransferWithGasFeeGadget
+                //   - Write TxCreatedAccount (Callee) (Reversible)
                 //   - Write Account (Callee) Nonce (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
@@ -359,16 +607,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsRoot
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
-                rw_counter: Delta(21.expr() + transfer_with_gas_fee.rw_delta()),
+                rw_counter: Delta(
+                    22.expr()
+                        + is_shanghai.is_enabled()
+                        + access_list_len.clone()
+                        + transfer_with_gas_fee.rw_delta(),
+                ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx_is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.expr()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
+                //  - Callee TxCreatedAccount
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -396,7 +650,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 );
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 8 reads and writes:
+                    // 8 + is_shanghai + access_list_len reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -404,9 +658,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount
                     //   - Write TxAccessListAccount
+                    //   - Write TxAccessListAccount (Coinbase) since Shanghai
+                    //   - Write TxAccessListAccount(Storage) of the access list
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
-                    rw_counter: Delta(8.expr() + transfer_with_gas_fee.rw_delta()),
+                    rw_counter: Delta(
+                        8.expr()
+                            + is_shanghai.is_enabled()
+                            + access_list_len.clone()
+                            + transfer_with_gas_fee.rw_delta(),
+                    ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
                 });
@@ -444,7 +705,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 }
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 21 reads and writes:
+                    // 21 + is_shanghai + access_list_len reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -452,6 +713,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount
                     //   - Write TxAccessListAccount
+                    //   - Write TxAccessListAccount (Coinbase) since Shanghai
+                    //   - Write TxAccessListAccount(Storage) of the access list
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
@@ -467,7 +730,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsRoot
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
-                    rw_counter: Delta(21.expr() + transfer_with_gas_fee.rw_delta()),
+                    rw_counter: Delta(
+                        21.expr()
+                            + is_shanghai.is_enabled()
+                            + access_list_len
+                            + transfer_with_gas_fee.rw_delta(),
+                    ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
                     is_create: To(tx_is_create.expr()),
@@ -485,7 +753,18 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_nonce,
             tx_gas,
             tx_gas_price,
+            tx_type,
+            is_legacy_tx,
+            tx_max_fee_per_gas,
+            tx_max_priority_fee_per_gas,
+            base_fee,
+            is_fee_cap_too_low,
+            is_tip_above_fee_cap,
+            base_fee_plus_tip,
+            base_fee_plus_tip_sum,
+            is_tip_capped,
             mul_gas_fee_by_gas,
+            mul_max_fee_by_gas,
             tx_caller_address,
             tx_caller_address_is_zero,
             tx_callee_address,
@@ -496,6 +775,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_call_data_gas_cost,
             tx_is_invalid,
             tx_access_list_gas_cost,
+            tx_access_list_addresses_len,
+            tx_access_list_storage_keys_len,
+            access_list,
+            is_shanghai,
+            init_code_word_size,
+            is_init_code_too_large,
             nonce,
             nonce_prev,
             is_nonce_valid,
@@ -510,6 +795,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             create,
             callee_not_exists,
             is_caller_callee_equal,
+            coinbase,
+            is_coinbase_warm,
             total_eth_cost,
             total_eth_cost_sum,
             balance_not_enough,
@@ -526,18 +813,38 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         step: &ExecStep,
     ) -> Result<(), Error> {
         let gas_fee = tx.gas_price * tx.gas;
+        let max_gas_fee = tx.max_fee_per_gas * tx.gas;
         let zero = eth_types::Word::zero();
 
+        let is_shanghai = block.context.hardfork.is_enabled(Hardfork::Shanghai);
         let mut rws = StepRws::new(block, step);
         rws.offset_add(4);
         let caller_nonce_pair = rws.next().account_value_pair();
         rws.offset_add(7);
+        let is_coinbase_warm = if is_shanghai {
+            rws.next().tx_access_list_value_pair().1
+        } else {
+            false
+        };
+        let access_list_len =
+            (tx.access_list_addresses_len + tx.access_list_storage_keys_len) as usize;
+        if access_list_len > MAX_ACCESS_LIST_ENTRIES {
+            log::error!(
+                "access list of tx {} has {} entries, more than the {} supported",
+                tx.id,
+                access_list_len,
+                MAX_ACCESS_LIST_ENTRIES
+            );
+            return Err(Error::Synthesis);
+        }
+        rws.offset_add(7 + is_shanghai as usize);
+        let access_list_rws = (0..access_list_len).map(|_| rws.next()).collect::<Vec<_>>();
+        let is_precompile = is_precompiled(&tx.callee_address, block.context.hardfork);
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.callee_address) && !tx.is_create {
+        if !is_precompile && !tx.is_create {
             callee_code_hash = rws.next().account_value_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.callee_address) || (!tx.is_create && !callee_code_hash.is_zero());
+        let callee_exists = is_precompile || (!tx.is_create && !callee_code_hash.is_zero());
         let caller_balance_sub_fee_pair = rws.next().account_value_pair();
         let must_create = tx.is_create;
         if (!callee_exists && !tx.value.is_zero()) || must_create {
@@ -559,6 +866,41 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, Some(tx.gas_price.to_le_bytes()))?;
         self.mul_gas_fee_by_gas
             .assign(region, offset, tx.gas_price, tx.gas, gas_fee)?;
+        self.tx_type
+            .assign(region, offset, Value::known(F::from(tx.tx_type as u64)))?;
+        self.is_legacy_tx
+            .assign(region, offset, F::from(tx.tx_type as u64))?;
+        self.tx_max_fee_per_gas
+            .assign(region, offset, Some(tx.max_fee_per_gas.to_le_bytes()))?;
+        self.tx_max_priority_fee_per_gas.assign(
+            region,
+            offset,
+            Some(tx.max_priority_fee_per_gas.to_le_bytes()),
+        )?;
+        let base_fee = block.context.base_fee;
+        self.base_fee
+            .assign(region, offset, Some(base_fee.to_le_bytes()))?;
+        self.is_fee_cap_too_low
+            .assign(region, offset, tx.max_fee_per_gas, base_fee)?;
+        self.is_tip_above_fee_cap.assign(
+            region,
+            offset,
+            tx.max_fee_per_gas,
+            tx.max_priority_fee_per_gas,
+        )?;
+        let base_fee_plus_tip = base_fee + tx.max_priority_fee_per_gas;
+        self.base_fee_plus_tip.assign(
+            region,
+            offset,
+            [base_fee, tx.max_priority_fee_per_gas],
+            base_fee_plus_tip,
+        )?;
+        self.base_fee_plus_tip_sum
+            .assign(region, offset, Some(base_fee_plus_tip.to_le_bytes()))?;
+        self.is_tip_capped
+            .assign(region, offset, tx.max_fee_per_gas, base_fee_plus_tip)?;
+        self.mul_max_fee_by_gas
+            .assign(region, offset, tx.max_fee_per_gas, tx.gas, max_gas_fee)?;
         let caller_address = tx
             .caller_address
             .to_scalar()
@@ -591,6 +933,18 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             offset,
             Value::known(F::from((caller_address == callee_address) as u64)),
         )?;
+        let coinbase = block
+            .context
+            .coinbase
+            .to_scalar()
+            .expect("unexpected Address -> Scalar conversion failure");
+        self.coinbase
+            .assign(region, offset, Value::known(coinbase))?;
+        self.is_coinbase_warm.assign(
+            region,
+            offset,
+            Value::known(F::from(is_coinbase_warm as u64)),
+        )?;
         self.tx_is_create
             .assign(region, offset, Value::known(F::from(tx.is_create as u64)))?;
         self.tx_call_data_length.assign(
@@ -611,6 +965,60 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             offset,
             Value::known(F::from(tx.access_list_gas_cost)),
         )?;
+        self.tx_access_list_addresses_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_addresses_len)),
+        )?;
+        self.tx_access_list_storage_keys_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_storage_keys_len)),
+        )?;
+        let mut rlc_acc = Value::known(F::ZERO);
+        for (idx, entry) in self.access_list.iter().enumerate() {
+            let (address, storage_key, is_address, is_storage_key, is_warm_prev) =
+                match access_list_rws.get(idx) {
+                    Some(rw) => {
+                        let storage_key = rw.storage_key();
+                        (
+                            rw.address().unwrap(),
+                            storage_key.unwrap_or_default(),
+                            storage_key.is_none(),
+                            storage_key.is_some(),
+                            rw.tx_access_list_value_pair().1,
+                        )
+                    }
+                    None => (Default::default(), zero, false, false, false),
+                };
+            let address = address
+                .to_scalar()
+                .expect("unexpected Address -> Scalar conversion failure");
+            let storage_key = region.word_rlc(storage_key);
+            if is_address {
+                rlc_acc = rlc_acc * region.challenges().evm_word() + Value::known(address);
+            } else if is_storage_key {
+                rlc_acc = rlc_acc * region.challenges().evm_word() + storage_key;
+            }
+            entry
+                .address
+                .assign(region, offset, Value::known(address))?;
+            entry.storage_key.assign(region, offset, storage_key)?;
+            entry
+                .is_address
+                .assign(region, offset, Value::known(F::from(is_address as u64)))?;
+            entry.is_storage_key.assign(
+                region,
+                offset,
+                Value::known(F::from(is_storage_key as u64)),
+            )?;
+            entry.is_warm_prev.assign(
+                region,
+                offset,
+                Value::known(F::from(is_warm_prev as u64)),
+            )?;
+            entry.rlc_acc.assign(region, offset, rlc_acc)?;
+        }
 
         // Increase caller's nonce if the tx is valid.
         let (nonce, nonce_prev) = caller_nonce_pair;
@@ -635,9 +1043,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             call.is_persistent,
         )?;
 
+        self.is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
+        self.init_code_word_size
+            .assign(region, offset, tx.call_data_length as u64)?;
+        self.is_init_code_too_large.assign(
+            region,
+            offset,
+            F::from(MAX_INIT_CODE_SIZE),
+            F::from(tx.call_data_length as u64),
+        )?;
         let intrinsic_gas = select::value(
             F::from(tx.is_create as u64),
-            F::from(GasCost::CREATION_TX.as_u64()),
+            F::from(
+                GasCost::CREATION_TX.as_u64()
+                    + init_code_gas_cost(block.context.hardfork, tx.call_data_length as u64),
+            ),
             F::from(GasCost::TX.as_u64()),
         ) + F::from(tx.call_data_gas_cost)
             + F::from(tx.access_list_gas_cost);
@@ -673,9 +1094,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
 
         // Check if the account ETH balance is sufficient
-        let total_eth_cost = tx.value + gas_fee;
+        let total_eth_cost = tx.value + max_gas_fee;
         self.total_eth_cost
-            .assign(region, offset, [tx.value, gas_fee], total_eth_cost)?;
+            .assign(region, offset, [tx.value, max_gas_fee], total_eth_cost)?;
         self.total_eth_cost_sum.assign(
             region,
             offset,
@@ -733,8 +1154,19 @@ mod test {
     use std::vec;
 
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use bus_mapping::{circuit_input_builder::CircuitsParams, evm::OpcodeId};
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Bytecode, Word};
+    use bus_mapping::{
+        circuit_input_builder::{CircuitsParams, MAX_ACCESS_LIST_ENTRIES},
+        evm::OpcodeId,
+        mock::BlockData,
+        Error,
+    };
+    use eth_types::{
+        self, bytecode,
+        chain_spec::{ChainSpec, Hardfork},
+        evm_types::{GasCost, MAX_INIT_CODE_SIZE},
+        geth_types::GethData,
+        word, AccessList, AccessListItem, Bytecode, Word, H256,
+    };
 
     use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
 
@@ -966,6 +1398,125 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    fn begin_tx_eip1559(max_fee_per_gas: Word, max_priority_fee_per_gas: Word) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code_with_return());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .transaction_type(2)
+                    .max_fee_per_gas(max_fee_per_gas)
+                    .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                    .gas(Word::from(0x10000))
+                    .value(eth(1));
+            },
+            |block, _tx| block.number(0xcafeu64).base_fee_per_gas(gwei(1)),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn begin_tx_eip1559_tip_not_capped() {
+        // Effective gas price is base_fee + max_priority_fee_per_gas
+        begin_tx_eip1559(gwei(4), gwei(2));
+    }
+
+    #[test]
+    fn begin_tx_eip1559_tip_capped() {
+        // Effective gas price is max_fee_per_gas
+        begin_tx_eip1559(gwei(2), gwei(2));
+    }
+
+    fn begin_tx_eip2930_ctx(access_list: AccessList) -> TestContext<2, 1> {
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code_with_return());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .transaction_type(1)
+                    .access_list(access_list)
+                    .gas(Word::from(0x10000))
+                    .value(eth(1));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+    }
+
+    fn begin_tx_eip2930(access_list: AccessList) {
+        CircuitTestBuilder::new_from_test_ctx(begin_tx_eip2930_ctx(access_list)).run();
+    }
+
+    #[test]
+    fn begin_tx_eip2930_access_list() {
+        begin_tx_eip2930(AccessList(vec![
+            AccessListItem {
+                address: MOCK_ACCOUNTS[0],
+                storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![],
+            },
+        ]));
+    }
+
+    #[test]
+    fn begin_tx_eip2930_access_list_duplicated_entries() {
+        // The callee and its storage key are warmed twice.
+        let item = AccessListItem {
+            address: MOCK_ACCOUNTS[0],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        };
+        begin_tx_eip2930(AccessList(vec![item.clone(), item]));
+    }
+
+    #[test]
+    fn begin_tx_eip2930_access_list_max_entries() {
+        begin_tx_eip2930(AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[2],
+            storage_keys: (0..MAX_ACCESS_LIST_ENTRIES as u64 - 1)
+                .map(H256::from_low_u64_be)
+                .collect(),
+        }]));
+    }
+
+    #[test]
+    fn begin_tx_eip2930_access_list_too_long() {
+        let block: GethData = begin_tx_eip2930_ctx(AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[2],
+            storage_keys: (0..MAX_ACCESS_LIST_ENTRIES as u64)
+                .map(H256::from_low_u64_be)
+                .collect(),
+        }]))
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedAccessListLen(len)) if len == MAX_ACCESS_LIST_ENTRIES + 1
+        ));
+    }
+
     fn begin_tx_deploy(nonce: u64) {
         let code = bytecode! {
             // [ADDRESS, STOP]
@@ -1058,6 +1609,72 @@ mod test {
         begin_tx_deploy(0xfffffffffffffffeu64);
     }
 
+    #[test]
+    fn begin_tx_warm_coinbase() {
+        // The coinbase is warm from the start of the tx (EIP-3651), so BALANCE
+        // of it only costs the warm access gas.
+        test_coinbase_balance(Hardfork::Shanghai);
+    }
+
+    #[test]
+    fn begin_tx_cold_coinbase_before_shanghai() {
+        test_coinbase_balance(Hardfork::London);
+    }
+
+    fn test_coinbase_balance(hardfork: Hardfork) {
+        let code = bytecode! {
+            COINBASE
+            BALANCE
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .gas(Word::from(0x10000));
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .chain_spec(ChainSpec::with_hardfork(hardfork))
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn begin_tx_coinbase_is_caller() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .value(eth(1));
+            },
+            |block, _tx| block.author(MOCK_ACCOUNTS[1]).number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     #[test]
     #[should_panic]
     fn begin_tx_disable_skipping_invalid_tx_invalid_nonce() {
@@ -1076,11 +1693,18 @@ mod test {
         begin_tx_insufficient_gas(false);
     }
 
+    #[test]
+    #[should_panic]
+    fn begin_tx_disable_skipping_invalid_tx_init_code_size_exceeded() {
+        begin_tx_init_code_size_exceeded(false);
+    }
+
     #[test]
     fn begin_tx_enable_skipping_invalid_tx() {
         begin_tx_invalid_nonce(true);
         begin_tx_not_enough_eth(true);
         begin_tx_insufficient_gas(true);
+        begin_tx_init_code_size_exceeded(true);
     }
 
     fn begin_tx_invalid_nonce(enable_skipping_invalid_tx: bool) {
@@ -1194,4 +1818,40 @@ mod test {
             })
             .run();
     }
+
+    fn begin_tx_init_code_size_exceeded(enable_skipping_invalid_tx: bool) {
+        // A creation tx whose initialization code is one byte over the limit
+        // (EIP-3860).
+        let from = MOCK_ACCOUNTS[1];
+        let init_code = vec![0u8; MAX_INIT_CODE_SIZE as usize + 1];
+
+        let ctx = TestContext::<2, 2>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(1));
+                accs[1].address(from).balance(eth(1)).nonce(1);
+            },
+            |mut txs, _| {
+                // Work around no payment to the coinbase address
+                txs[0].to(MOCK_ACCOUNTS[0]).from(from).nonce(1);
+                txs[1]
+                    .from(from)
+                    .nonce(2)
+                    .gas_price(gwei(1))
+                    .gas(Word::from(0x100000))
+                    .input(init_code.into())
+                    .enable_skipping_invalid_tx(enable_skipping_invalid_tx);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_txs: 2,
+                max_calldata: MAX_INIT_CODE_SIZE as usize + 1,
+                ..Default::default()
+            })
+            .run();
+    }
 }
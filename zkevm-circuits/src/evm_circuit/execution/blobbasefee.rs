@@ -0,0 +1,105 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{chain_spec::Hardfork, Field};
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobBaseFeeGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    blob_base_fee: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobBaseFeeGadget<F> {
+    const NAME: &'static str = "BLOBBASEFEE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBBASEFEE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // BLOBBASEFEE is only available since Cancun (EIP-7516).
+        let is_cancun = HardforkGadget::construct_enabled(cb, Hardfork::Cancun);
+
+        let blob_base_fee = cb.query_cell_phase2();
+
+        // Push the value to the stack
+        cb.stack_push(blob_base_fee.expr());
+
+        // Lookup block table with blob base fee. Its tag does not follow the
+        // opcode offset used by the block context gadgets.
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            None,
+            blob_base_fee.expr(),
+        );
+
+        // State transition
+        let opcode = cb.query_cell();
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(1.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta((-1).expr()),
+            gas_left: Delta(-OpcodeId::BLOBBASEFEE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            is_cancun,
+            blob_base_fee,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+        let blob_base_fee = block.get_rws(step, 0).stack_value();
+
+        self.blob_base_fee
+            .assign(region, offset, region.word_rlc(blob_base_fee))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::bytecode;
+    use mock::test_ctx::TestContext;
+
+    #[test]
+    fn blobbasefee_gadget_test() {
+        let bytecode = bytecode! {
+            #[start]
+            BLOBBASEFEE
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .run();
+    }
+}
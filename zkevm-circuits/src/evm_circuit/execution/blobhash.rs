@@ -0,0 +1,175 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{chain_spec::Hardfork, evm_types::MAX_BLOB_HASHES_PER_TX, Field, ToLittleEndian};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    index: WordByteCapGadget<F, 1>,
+    blob_hash: Word<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // BLOBHASH is introduced by Cancun (EIP-4844).
+        let is_cancun = HardforkGadget::construct_enabled(cb, Hardfork::Cancun);
+
+        // The tx table holds exactly MAX_BLOB_HASHES_PER_TX zero padded hashes
+        // per tx, so any index below the cap can be looked up directly.
+        let index = WordByteCapGadget::construct(cb, MAX_BLOB_HASHES_PER_TX.expr());
+        cb.stack_pop(index.original_word());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let blob_hash = cb.query_word_rlc();
+
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHashes,
+                Some(index.valid_value()),
+                blob_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero(
+                "Out of range blob index returns zero hash",
+                blob_hash.expr(),
+            );
+        });
+
+        cb.stack_push(blob_hash.expr());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+        Self {
+            same_context,
+            is_cancun,
+            tx_id,
+            index,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+
+        let [index, blob_hash] = [0, 2].map(|idx| block.get_rws(step, idx).stack_value());
+        self.index.assign(
+            region,
+            offset,
+            index,
+            F::from(MAX_BLOB_HASHES_PER_TX as u64),
+        )?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.blob_hash
+            .assign(region, offset, Some(blob_hash.to_le_bytes()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Hash, U256};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: U256, blob_hashes: Vec<Hash>) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(blob_hashes);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run()
+    }
+
+    fn blob_hashes(n: u8) -> Vec<Hash> {
+        (1..=n)
+            .map(|i| {
+                let mut hash = Hash::repeat_byte(i);
+                hash.0[0] = 0x01; // versioned hash prefix
+                hash
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blobhash_gadget_simple() {
+        test_ok(0.into(), blob_hashes(2));
+        test_ok(1.into(), blob_hashes(2));
+    }
+
+    #[test]
+    fn blobhash_gadget_padded_index() {
+        test_ok(2.into(), blob_hashes(2));
+        test_ok(5.into(), blob_hashes(6));
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        test_ok(6.into(), blob_hashes(6));
+        test_ok(0x100.into(), blob_hashes(1));
+        test_ok(U256::MAX, blob_hashes(1));
+    }
+
+    #[test]
+    fn blobhash_gadget_no_blobs() {
+        test_ok(0.into(), vec![]);
+    }
+}
@@ -1,14 +1,14 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_U64},
         step::ExecutionState,
         util::{
             and,
             common_gadget::{CommonCallGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
-                Transition::{Delta, To},
+                Transition::{Any, Delta, To},
             },
             math_gadget::{
                 ConstantDivisionGadget, IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget,
@@ -22,7 +22,7 @@ use crate::{
     table::{AccountFieldTag, CallContextFieldTag},
     util::Expr,
 };
-use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, precompile::is_precompiled};
+use bus_mapping::{evm::OpcodeId, precompile::is_precompiled};
 use eth_types::{
     evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToAddress, ToLittleEndian, ToScalar, U256,
 };
@@ -62,9 +62,6 @@ pub(crate) struct CallOpGadget<F> {
     is_code_address_zero: IsZeroGadget<F>,
     is_precompile_lt: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
     precompile_gadget: PrecompileGadget<F>,
-    precompile_return_length: Cell<F>,
-    precompile_return_length_zero: IsZeroGadget<F>,
-    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
 }
 
 impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
@@ -192,14 +189,6 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             not::expr(is_code_address_zero.expr()),
             is_precompile_lt.expr(),
         ]);
-        let precompile_return_length = cb.query_cell();
-        let precompile_return_length_zero =
-            IsZeroGadget::construct(cb, precompile_return_length.expr());
-        let return_data_copy_size = MinMaxGadget::construct(
-            cb,
-            precompile_return_length.expr(),
-            call_gadget.rd_address.length(),
-        );
 
         // Verify transfer only for CALL opcode in the successful case.  If value == 0,
         // skip the transfer (this is necessary for non-existing accounts, which
@@ -258,7 +247,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         let precompile_gadget = cb.condition(
             and::expr([is_precompile.expr(), is_precheck_ok.expr()]),
             |cb| {
-                // Write to callee's context.
+                // Setup the precompile call's context.
                 for (field_tag, value) in [
                     (
                         CallContextFieldTag::IsSuccess,
@@ -294,75 +283,63 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     );
                 }
 
-                // Save caller's call state
+                let precompile_gadget =
+                    PrecompileGadget::construct(cb, call_gadget.callee_address_expr());
+
+                // Save caller's call state, which is restored at the end of the precompile
+                // step.
                 for (field_tag, value) in [
-                    (CallContextFieldTag::LastCalleeId, callee_call_id.expr()),
-                    (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
                     (
-                        CallContextFieldTag::LastCalleeReturnDataLength,
-                        precompile_return_length.expr(),
+                        CallContextFieldTag::ProgramCounter,
+                        cb.curr.state.program_counter.expr() + 1.expr(),
+                    ),
+                    (
+                        CallContextFieldTag::StackPointer,
+                        cb.curr.state.stack_pointer.expr() + stack_pointer_delta.expr(),
+                    ),
+                    (
+                        CallContextFieldTag::GasLeft,
+                        cb.curr.state.gas_left.expr() - gas_cost.clone() - callee_gas_left.clone(),
+                    ),
+                    (
+                        CallContextFieldTag::MemorySize,
+                        memory_expansion.next_memory_word_size(),
+                    ),
+                    (
+                        CallContextFieldTag::ReversibleWriteCounter,
+                        cb.curr.state.reversible_write_counter.expr() + 1.expr(),
                     ),
                 ] {
                     cb.call_context_lookup(true.expr(), None, field_tag, value);
                 }
 
-                // copy table lookup to verify the copying of bytes:
-                // - from caller's memory (`call_data_length` bytes starting at `call_data_offset`)
-                // - to the current call's memory (`call_data_length` bytes starting at `0`).
-                cb.condition(call_gadget.cd_address.has_length(), |cb| {
-                    cb.copy_table_lookup(
-                        cb.curr.state.call_id.expr(),
-                        CopyDataType::Memory.expr(),
-                        callee_call_id.expr(),
-                        CopyDataType::Memory.expr(),
-                        call_gadget.cd_address.offset(),
-                        call_gadget.cd_address.address(),
-                        0.expr(),
-                        call_gadget.cd_address.length(),
-                        0.expr(),
-                        2.expr() * call_gadget.cd_address.length(), // reads + writes
-                    );
+                // Give gas stipend if value is not zero
+                let callee_gas_left = callee_gas_left.clone()
+                    + call_gadget.has_value.clone() * GAS_STIPEND_CALL_WITH_VALUE.expr();
+
+                // The lookups are the same as for calls to empty code, plus the 7 writes
+                // to setup the precompile call's context and the 5 writes to save the
+                // caller's state, minus the 3 writes of the last callee's fields.
+                let transfer_rwc_delta =
+                    is_call.expr() * not::expr(transfer.value_is_zero.expr()) * 2.expr();
+                let rw_counter_delta = 30.expr()
+                    + is_call.expr() * 1.expr()
+                    + transfer_rwc_delta.clone()
+                    + is_callcode.expr()
+                    + is_delegatecall.expr() * 2.expr();
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(rw_counter_delta),
+                    call_id: To(callee_call_id.expr()),
+                    is_root: To(false.expr()),
+                    is_create: To(false.expr()),
+                    // Precompiles have no bytecode.
+                    code_hash: Any,
+                    gas_left: To(callee_gas_left),
+                    reversible_write_counter: To(transfer_rwc_delta),
+                    ..StepStateTransition::new_context()
                 });
 
-                // copy table lookup to verify the copying of bytes if the precompile call was
-                // successful.
-                // - from precompile call's memory (min(rd_length, precompile_return_length) bytes
-                //   starting at `0`)
-                // - to caller's memory (min(rd_length, precompile_return_length) bytes starting at
-                //   `return_data_offset`).
-                cb.condition(
-                    and::expr([
-                        call_gadget.is_success.expr(),
-                        call_gadget.cd_address.has_length(),
-                        call_gadget.rd_address.has_length(),
-                        not::expr(precompile_return_length_zero.expr()),
-                    ]),
-                    |cb| {
-                        cb.copy_table_lookup(
-                            callee_call_id.expr(),
-                            CopyDataType::Memory.expr(),
-                            cb.curr.state.call_id.expr(),
-                            CopyDataType::Memory.expr(),
-                            0.expr(),
-                            return_data_copy_size.min(),
-                            call_gadget.rd_address.offset(),
-                            return_data_copy_size.min(),
-                            0.expr(),
-                            2.expr() * return_data_copy_size.min(), // reads + writes
-                        );
-                    },
-                );
-
-                PrecompileGadget::construct(
-                    cb,
-                    call_gadget.is_success.expr(),
-                    call_gadget.callee_address_expr(),
-                    cb.curr.state.call_id.expr(),
-                    call_gadget.cd_address.offset(),
-                    call_gadget.cd_address.length(),
-                    call_gadget.rd_address.offset(),
-                    call_gadget.rd_address.length(),
-                )
+                precompile_gadget
             },
         );
 
@@ -586,9 +563,6 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             is_code_address_zero,
             is_precompile_lt,
             precompile_gadget,
-            precompile_return_length,
-            precompile_return_length_zero,
-            return_data_copy_size,
         }
     }
 
@@ -776,40 +750,13 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             .assign(region, offset, code_address)?;
         self.is_precompile_lt
             .assign(region, offset, code_address, 0x0Au64.into())?;
-        if is_precompiled(&callee_address.to_address()) {
+        if is_precompiled(&callee_address.to_address(), block.context.hardfork) {
             self.precompile_gadget.assign(
                 region,
                 offset,
                 callee_address.to_address().0[19].into(),
             )?;
         }
-        let precompile_return_length = if is_precompiled(&callee_address.to_address()) {
-            let value_rw = block.get_rws(step, 27 + rw_offset);
-            assert_eq!(
-                value_rw.field_tag(),
-                Some(CallContextFieldTag::LastCalleeReturnDataLength as u64),
-            );
-            value_rw.call_context_value()
-        } else {
-            0.into()
-        };
-        self.precompile_return_length.assign(
-            region,
-            offset,
-            Value::known(precompile_return_length.to_scalar().unwrap()),
-        )?;
-        self.precompile_return_length_zero.assign(
-            region,
-            offset,
-            precompile_return_length.to_scalar().unwrap(),
-        )?;
-        self.return_data_copy_size.assign(
-            region,
-            offset,
-            precompile_return_length.to_scalar().unwrap(),
-            rd_length.to_scalar().unwrap(),
-        )?;
-
         Ok(())
     }
 }
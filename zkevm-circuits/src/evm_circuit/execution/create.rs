@@ -0,0 +1,810 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
+        step::ExecutionState,
+        util::{
+            and,
+            common_gadget::{HardforkGadget, TransferGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::{ConstantDivisionGadget, ContractCreateGadget, LtGadget, LtWordGadget},
+            memory_gadget::{
+                MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget,
+                MemoryWordSizeGadget,
+            },
+            not, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
+use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::{GasCost, MAX_INIT_CODE_SIZE},
+    Field, ToAddress, ToBigEndian, ToLittleEndian, ToWord, U256,
+};
+use ethers_core::utils::{keccak256, rlp};
+use gadgets::util::expr_from_bytes;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CREATE and CREATE2 opcodes, both for the successful case and
+/// the case where the precheck fails (insufficient balance or depth).
+#[derive(Clone, Debug)]
+pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    depth: Cell<F>,
+
+    value: Word<F>,
+    memory_address: MemoryAddressGadget<F>,
+    is_init_code_size_ok: LtGadget<F, N_BYTES_U64>,
+    callee_is_success: Cell<F>,
+
+    caller_balance: Word<F>,
+    is_insufficient_balance: LtWordGadget<F>,
+    is_depth_ok: LtGadget<F, N_BYTES_U64>,
+
+    was_warm: Cell<F>,
+    callee_reversion_info: ReversionInfo<F>,
+    transfer: TransferGadget<F>,
+
+    create: ContractCreateGadget<F, IS_CREATE2>,
+    keccak_output: [Cell<F>; N_BYTES_WORD],
+
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    is_shanghai: HardforkGadget<F>,
+    init_code_word_size: MemoryWordSizeGadget<F>,
+    one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
+}
+
+impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<F>
+    for CreateGadget<F, IS_CREATE2, S>
+{
+    const NAME: &'static str = if IS_CREATE2 { "CREATE2" } else { "CREATE" };
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_equal(
+            "Opcode is CREATE or CREATE2",
+            opcode.expr(),
+            if IS_CREATE2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            }
+            .expr(),
+        );
+
+        // Use rw_counter of the step which triggers next call as its call_id.
+        let callee_call_id = cb.curr.state.rw_counter.clone();
+
+        let create = ContractCreateGadget::construct(cb);
+
+        let value = cb.query_word_rlc();
+        let offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+        cb.stack_pop(value.expr());
+        cb.stack_pop(offset.expr());
+        cb.stack_pop(length.expr());
+        if IS_CREATE2 {
+            cb.stack_pop(create.salt_word_rlc(cb));
+        }
+        let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+
+        // The new contract address is the last 20 bytes of the keccak hash of
+        // the rlp encoded caller address and nonce for CREATE, or of
+        // `0xff ++ caller_address ++ salt ++ keccak(init_code)` for CREATE2.
+        let keccak_output = array_init::array_init(|_| cb.query_byte());
+        let new_address = expr_from_bytes(&keccak_output[..N_BYTES_ACCOUNT_ADDRESS]);
+        let new_address_rlc = cb.word_rlc::<N_BYTES_ACCOUNT_ADDRESS>(
+            keccak_output[..N_BYTES_ACCOUNT_ADDRESS]
+                .iter()
+                .map(Expr::expr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+        let callee_is_success = cb.query_bool();
+        cb.stack_push(callee_is_success.expr() * new_address_rlc);
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let mut reversion_info = cb.reversion_info_read(None);
+        let [is_static, depth, current_address] = [
+            CallContextFieldTag::IsStatic,
+            CallContextFieldTag::Depth,
+            CallContextFieldTag::CalleeAddress,
+        ]
+        .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_zero("CREATE/CREATE2 is not in static call stack", is_static.expr());
+        cb.require_equal(
+            "caller address is the current address",
+            create.caller_address(),
+            current_address.expr(),
+        );
+
+        let caller_balance = cb.query_word_rlc();
+        cb.account_read(
+            create.caller_address(),
+            AccountFieldTag::Balance,
+            caller_balance.expr(),
+        );
+        let is_insufficient_balance = LtWordGadget::construct(cb, &caller_balance, &value);
+        // depth < 1025
+        let is_depth_ok = LtGadget::construct(cb, depth.expr(), 1025.expr());
+        let is_precheck_ok = and::expr([
+            is_depth_ok.expr(),
+            not::expr(is_insufficient_balance.expr()),
+        ]);
+
+        let was_warm = cb.query_bool();
+        let (callee_reversion_info, transfer) = cb.condition(is_precheck_ok.expr(), |cb| {
+            // Increase caller's nonce
+            cb.account_write(
+                create.caller_address(),
+                AccountFieldTag::Nonce,
+                create.caller_nonce() + 1.expr(),
+                create.caller_nonce(),
+                Some(&mut reversion_info),
+            );
+
+            // Add callee to access list
+            cb.account_access_list_write(
+                tx_id.expr(),
+                new_address.clone(),
+                1.expr(),
+                was_warm.expr(),
+                Some(&mut reversion_info),
+            );
+
+            let keccak_output_rlc = cb.word_rlc::<N_BYTES_WORD>(
+                keccak_output
+                    .iter()
+                    .map(Expr::expr)
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            );
+            cb.keccak_table_lookup(create.input_rlc(cb), create.input_length(), keccak_output_rlc);
+
+            // Propagate rw_counter_end_of_reversion and is_persistent
+            let mut callee_reversion_info = cb.reversion_info_write(Some(callee_call_id.expr()));
+            cb.require_equal(
+                "callee_is_persistent == is_persistent ⋅ is_success",
+                callee_reversion_info.is_persistent(),
+                reversion_info.is_persistent() * callee_is_success.expr(),
+            );
+            cb.condition(
+                callee_is_success.expr() * not::expr(reversion_info.is_persistent()),
+                |cb| {
+                    cb.require_equal(
+                        "callee_rw_counter_end_of_reversion == rw_counter_end_of_reversion - reversible_write_counter",
+                        callee_reversion_info.rw_counter_end_of_reversion(),
+                        reversion_info.rw_counter_of_reversion(0.expr()),
+                    );
+                },
+            );
+
+            // Create the callee account and transfer the endowment. The
+            // balances are only updated when the value is not zero.
+            cb.account_write(
+                new_address.clone(),
+                AccountFieldTag::CodeHash,
+                cb.empty_code_hash_rlc(),
+                0.expr(),
+                Some(&mut callee_reversion_info),
+            );
+            let transfer = TransferGadget::construct(
+                cb,
+                create.caller_address(),
+                new_address.clone(),
+                1.expr(),
+                value.clone(),
+                &mut callee_reversion_info,
+            );
+
+            // Record the new account as created in the transaction (EIP-6780).
+            // Its address is not in use, so it can't be created before.
+            cb.tx_created_account_write(
+                tx_id.expr(),
+                new_address.clone(),
+                1.expr(),
+                0.expr(),
+                Some(&mut callee_reversion_info),
+            );
+
+            // EIP-161: the nonce of the new account starts at 1
+            cb.account_write(
+                new_address.clone(),
+                AccountFieldTag::Nonce,
+                1.expr(),
+                0.expr(),
+                Some(&mut callee_reversion_info),
+            );
+
+            (callee_reversion_info, transfer)
+        });
+
+        // Verify the initialization code is copied from memory into the
+        // bytecode table under its code hash.
+        cb.condition(
+            is_precheck_ok.expr() * memory_address.has_length(),
+            |cb| {
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    create.code_hash_word_rlc(cb),
+                    CopyDataType::Bytecode.expr(),
+                    memory_address.offset(),
+                    memory_address.address(),
+                    0.expr(),
+                    memory_address.length(),
+                    0.expr(),
+                    memory_address.length(),
+                );
+            },
+        );
+        cb.condition(not::expr(memory_address.has_length()), |cb| {
+            cb.require_equal(
+                "code hash of empty initialization code",
+                create.code_hash_word_rlc(cb),
+                cb.empty_code_hash_rlc(),
+            );
+        });
+
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+        // CREATE2 additionally pays for hashing the initialization code.
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+        // EIP-3860: since Shanghai the initialization code is limited in size
+        // and charged per word. Exceeding the limit is handled by the out of
+        // gas gadgets.
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        let is_init_code_size_ok =
+            LtGadget::construct(cb, memory_address.length(), (MAX_INIT_CODE_SIZE + 1).expr());
+        cb.condition(is_shanghai.is_enabled(), |cb| {
+            cb.require_equal(
+                "init code size is within the limit",
+                is_init_code_size_ok.expr(),
+                1.expr(),
+            );
+        });
+        let init_code_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
+        let gas_cost = GasCost::CREATE.expr()
+            + is_shanghai.is_enabled()
+                * GasCost::INIT_CODE_WORD.expr()
+                * init_code_word_size.expr()
+            + if IS_CREATE2 {
+                memory_copier_gas.gas_cost()
+            } else {
+                memory_expansion.gas_cost()
+            };
+
+        // EIP-150: all but one 64th of the caller's gas is sent to the callee.
+        let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
+        let one_64th_gas = ConstantDivisionGadget::construct(cb, gas_available.clone(), 64);
+        let callee_gas_left = gas_available - one_64th_gas.quotient();
+
+        let stack_pointer_delta = if IS_CREATE2 { 3.expr() } else { 2.expr() };
+        let n_pop = if IS_CREATE2 { 4.expr() } else { 3.expr() };
+        // +n_pop stack reads, +1 stack write, +6 call context reads and +1
+        // caller balance read.
+        let base_rwc = n_pop + 8.expr();
+        // If the value is not zero, `transfer` has two account writes.
+        let transfer_rwc_delta = not::expr(transfer.value_is_zero.expr()) * 2.expr();
+
+        // Enter the initialization call when it has code to execute.
+        cb.condition(
+            is_precheck_ok.expr() * memory_address.has_length(),
+            |cb| {
+                // Save caller's call state
+                for (field_tag, value) in [
+                    (
+                        CallContextFieldTag::ProgramCounter,
+                        cb.curr.state.program_counter.expr() + 1.expr(),
+                    ),
+                    (
+                        CallContextFieldTag::StackPointer,
+                        cb.curr.state.stack_pointer.expr() + stack_pointer_delta.clone(),
+                    ),
+                    (
+                        CallContextFieldTag::GasLeft,
+                        cb.curr.state.gas_left.expr() - gas_cost.clone() - callee_gas_left.clone(),
+                    ),
+                    (
+                        CallContextFieldTag::MemorySize,
+                        memory_expansion.next_memory_word_size(),
+                    ),
+                    (
+                        CallContextFieldTag::ReversibleWriteCounter,
+                        cb.curr.state.reversible_write_counter.expr() + 2.expr(),
+                    ),
+                ] {
+                    cb.call_context_lookup(true.expr(), None, field_tag, value);
+                }
+
+                // Setup next call's context.
+                for (field_tag, value) in [
+                    (CallContextFieldTag::CallerId, cb.curr.state.call_id.expr()),
+                    (CallContextFieldTag::TxId, tx_id.expr()),
+                    (CallContextFieldTag::Depth, depth.expr() + 1.expr()),
+                    (CallContextFieldTag::CallerAddress, create.caller_address()),
+                    (CallContextFieldTag::CalleeAddress, new_address.clone()),
+                    (CallContextFieldTag::CallDataOffset, 0.expr()),
+                    (CallContextFieldTag::CallDataLength, 0.expr()),
+                    (CallContextFieldTag::ReturnDataOffset, 0.expr()),
+                    (CallContextFieldTag::ReturnDataLength, 0.expr()),
+                    (CallContextFieldTag::Value, value.expr()),
+                    (CallContextFieldTag::IsSuccess, callee_is_success.expr()),
+                    (CallContextFieldTag::IsStatic, 0.expr()),
+                    (CallContextFieldTag::LastCalleeId, 0.expr()),
+                    (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
+                    (CallContextFieldTag::LastCalleeReturnDataLength, 0.expr()),
+                    (CallContextFieldTag::IsRoot, 0.expr()),
+                    (CallContextFieldTag::IsCreate, 1.expr()),
+                    (CallContextFieldTag::CodeHash, create.code_hash_word_rlc(cb)),
+                ] {
+                    cb.call_context_lookup(
+                        true.expr(),
+                        Some(callee_call_id.expr()),
+                        field_tag,
+                        value,
+                    );
+                }
+
+                // +2 caller nonce and access list writes, +2 callee reversion
+                // info writes, +3 callee code hash, created account and nonce
+                // writes, +length memory reads, +5 caller context writes and
+                // +18 callee context writes.
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(
+                        base_rwc.clone()
+                            + 7.expr()
+                            + transfer_rwc_delta.clone()
+                            + memory_address.length()
+                            + 23.expr(),
+                    ),
+                    call_id: To(callee_call_id.expr()),
+                    is_root: To(false.expr()),
+                    is_create: To(true.expr()),
+                    code_hash: To(create.code_hash_word_rlc(cb)),
+                    gas_left: To(callee_gas_left),
+                    // The callee code hash, created account, nonce and the
+                    // `transfer` writes
+                    reversible_write_counter: To(3.expr() + transfer_rwc_delta.clone()),
+                    ..StepStateTransition::new_context()
+                });
+            },
+        );
+
+        // Return to the caller immediately when the initialization code is
+        // empty.
+        cb.condition(
+            is_precheck_ok.expr() * not::expr(memory_address.has_length()),
+            |cb| {
+                cb.require_equal(
+                    "CREATE/CREATE2 with empty initialization code succeeds",
+                    callee_is_success.expr(),
+                    1.expr(),
+                );
+                for field_tag in [
+                    CallContextFieldTag::LastCalleeId,
+                    CallContextFieldTag::LastCalleeReturnDataOffset,
+                    CallContextFieldTag::LastCalleeReturnDataLength,
+                ] {
+                    cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
+                }
+
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(
+                        base_rwc.clone() + 7.expr() + transfer_rwc_delta.clone() + 3.expr(),
+                    ),
+                    program_counter: Delta(1.expr()),
+                    stack_pointer: Delta(stack_pointer_delta.clone()),
+                    gas_left: Delta(-gas_cost.clone()),
+                    memory_word_size: To(memory_expansion.next_memory_word_size()),
+                    // The caller's writes and the callee's writes, which are
+                    // merged into the caller since the callee succeeded.
+                    reversible_write_counter: Delta(5.expr() + transfer_rwc_delta),
+                    ..StepStateTransition::default()
+                });
+            },
+        );
+
+        // Handle insufficient balance and depth error cases.
+        cb.condition(not::expr(is_precheck_ok.expr()), |cb| {
+            cb.require_zero(
+                "callee is not successful when the precheck fails",
+                callee_is_success.expr(),
+            );
+            for field_tag in [
+                CallContextFieldTag::LastCalleeId,
+                CallContextFieldTag::LastCalleeReturnDataOffset,
+                CallContextFieldTag::LastCalleeReturnDataLength,
+            ] {
+                cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
+            }
+
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(base_rwc + 3.expr()),
+                program_counter: Delta(1.expr()),
+                stack_pointer: Delta(stack_pointer_delta),
+                gas_left: Delta(-gas_cost),
+                memory_word_size: To(memory_expansion.next_memory_word_size()),
+                reversible_write_counter: Delta(0.expr()),
+                ..StepStateTransition::default()
+            });
+        });
+
+        Self {
+            opcode,
+            tx_id,
+            reversion_info,
+            depth,
+            value,
+            memory_address,
+            is_init_code_size_ok,
+            callee_is_success,
+            caller_balance,
+            is_insufficient_balance,
+            is_depth_ok,
+            was_warm,
+            callee_reversion_info,
+            transfer,
+            create,
+            keccak_output,
+            memory_expansion,
+            memory_copier_gas,
+            is_shanghai,
+            init_code_word_size,
+            one_64th_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let n_pop = if IS_CREATE2 { 4 } else { 3 };
+        let [value, init_code_start, init_code_length] =
+            [0, 1, 2].map(|i| block.get_rws(step, i).stack_value());
+        let salt = if IS_CREATE2 {
+            Some(block.get_rws(step, 3).stack_value())
+        } else {
+            None
+        };
+        let callee_is_success = !block.get_rws(step, n_pop).stack_value().is_zero();
+        self.callee_is_success
+            .assign(region, offset, Value::known(F::from(callee_is_success as u64)))?;
+
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, init_code_start, init_code_length)?;
+
+        let [tx_id, _, _, _, depth, caller_address] =
+            [1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, n_pop + i).call_context_value());
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth.low_u64())))?;
+
+        let (caller_balance, _) = block.get_rws(step, n_pop + 7).account_value_pair();
+        self.caller_balance
+            .assign(region, offset, Some(caller_balance.to_le_bytes()))?;
+        self.is_insufficient_balance
+            .assign(region, offset, caller_balance, value)?;
+        self.is_depth_ok
+            .assign(region, offset, F::from(depth.low_u64()), F::from(1025))?;
+        let is_precheck_ok = depth.low_u64() < 1025 && caller_balance >= value;
+
+        let code_hash = if is_precheck_ok {
+            tx.calls
+                .iter()
+                .find(|callee| callee.call_id == step.rwc.0)
+                .map(|callee| callee.code_hash.to_word())
+                .expect("callee of CREATE/CREATE2 not found")
+        } else {
+            CodeDB::empty_code_hash().to_word()
+        };
+
+        let caller_nonce = if is_precheck_ok {
+            let (_, caller_nonce) = block.get_rws(step, n_pop + 8).account_value_pair();
+            let (_, was_warm) = block.get_rws(step, n_pop + 9).tx_access_list_value_pair();
+            self.was_warm
+                .assign(region, offset, Value::known(F::from(was_warm as u64)))?;
+
+            let [callee_rw_counter_end_of_reversion, callee_is_persistent] = [10, 11]
+                .map(|i| block.get_rws(step, n_pop + i).call_context_value());
+            self.callee_reversion_info.assign(
+                region,
+                offset,
+                callee_rw_counter_end_of_reversion.low_u64() as usize,
+                callee_is_persistent.low_u64() != 0,
+            )?;
+
+            let [caller_balance_pair, callee_balance_pair] = if value.is_zero() {
+                [(U256::zero(), U256::zero()), (U256::zero(), U256::zero())]
+            } else {
+                [13, 14].map(|i| block.get_rws(step, n_pop + i).account_value_pair())
+            };
+            self.transfer.assign(
+                region,
+                offset,
+                caller_balance_pair,
+                callee_balance_pair,
+                value,
+            )?;
+
+            caller_nonce.low_u64()
+        } else {
+            0
+        };
+
+        let caller_address = caller_address.to_address();
+        self.create.assign(
+            region,
+            offset,
+            caller_address,
+            caller_nonce,
+            Some(code_hash),
+            salt,
+        )?;
+
+        let keccak_input = if let Some(salt) = salt {
+            let mut keccak_input = vec![0xff];
+            keccak_input.extend_from_slice(caller_address.as_bytes());
+            keccak_input.extend_from_slice(&salt.to_be_bytes());
+            keccak_input.extend_from_slice(&code_hash.to_be_bytes());
+            keccak_input
+        } else {
+            let mut stream = rlp::RlpStream::new();
+            stream.begin_list(2);
+            stream.append(&caller_address);
+            stream.append(&U256::from(caller_nonce));
+            stream.out().to_vec()
+        };
+        for (c, v) in self
+            .keccak_output
+            .iter()
+            .rev()
+            .zip(keccak256(keccak_input).iter())
+        {
+            c.assign(region, offset, Value::known(F::from(*v as u64)))?;
+        }
+
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let memory_copier_gas = self.memory_copier_gas.assign(
+            region,
+            offset,
+            init_code_length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+        self.is_init_code_size_ok.assign(
+            region,
+            offset,
+            F::from(init_code_length.as_u64()),
+            F::from(MAX_INIT_CODE_SIZE + 1),
+        )?;
+        let is_shanghai = self
+            .is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
+        let init_code_word_size =
+            self.init_code_word_size
+                .assign(region, offset, init_code_length.as_u64())?;
+        let gas_cost = GasCost::CREATE.as_u64()
+            + if is_shanghai {
+                GasCost::INIT_CODE_WORD.as_u64() * init_code_word_size
+            } else {
+                0
+            }
+            + if IS_CREATE2 {
+                memory_copier_gas
+            } else {
+                memory_expansion_gas_cost
+            };
+        self.one_64th_gas
+            .assign(region, offset, (step.gas_left.0 - gas_cost) as u128)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode,
+        Word,
+    };
+    use itertools::Itertools;
+    use mock::{eth, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    // RETURN or REVERT with a piece of deployed code.
+    fn initialization_bytecode(is_success: bool) -> Bytecode {
+        let memory_bytes = [0x60; 10];
+        let memory_address = 0;
+        let memory_value = Word::from_big_endian(&memory_bytes);
+        let mut code = bytecode! {
+            PUSH10(memory_value)
+            PUSH1(memory_address)
+            MSTORE
+            PUSH2(5)
+            PUSH2(32u64 - u64::try_from(memory_bytes.len()).unwrap())
+        };
+        code.write_op(if is_success {
+            OpcodeId::RETURN
+        } else {
+            OpcodeId::REVERT
+        });
+        code
+    }
+
+    fn creator_bytecode(
+        initialization_bytecode: Bytecode,
+        value: Word,
+        is_create2: bool,
+        is_persistent: bool,
+    ) -> Bytecode {
+        let initialization_bytes = initialization_bytecode.code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH32(value) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        if !is_persistent {
+            code.append(&bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            });
+        }
+        code
+    }
+
+    fn test_context(caller: Account) -> TestContext<2, 1> {
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_create() {
+        for ((is_success, is_create2), is_persistent) in [true, false]
+            .iter()
+            .cartesian_product(&[true, false])
+            .cartesian_product(&[true, false])
+        {
+            let init_code = initialization_bytecode(*is_success);
+            let root_code = creator_bytecode(init_code, 23414.into(), *is_create2, *is_persistent);
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create_empty_init_code() {
+        for is_create2 in [true, false] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(vec![].into(), 23414.into(), is_create2, true).into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create_zero_value() {
+        for is_create2 in [true, false] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(initialization_bytecode(true), Word::zero(), is_create2, true)
+                    .into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create_insufficient_balance() {
+        let value = 0xff.into();
+        for is_create2 in [true, false] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(initialization_bytecode(true), value, is_create2, true)
+                    .into(),
+                nonce: Word::one(),
+                balance: value - 1,
+                ..Default::default()
+            };
+
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+                .params(CircuitsParams {
+                    max_rws: 4500,
+                    ..Default::default()
+                })
+                .run();
+        }
+    }
+}
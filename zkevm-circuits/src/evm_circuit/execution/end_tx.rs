@@ -92,7 +92,9 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             None,
         );
 
-        // Add gas_used * effective_tip to coinbase's balance
+        // Add gas_used * effective_tip to coinbase's balance, where the
+        // effective tip is the effective gas price minus the base fee
+        // (EIP-1559) as the base fee is burnt
         let coinbase = cb.query_cell();
         let base_fee = cb.query_word_rlc();
         for (tag, value) in [
@@ -332,7 +334,7 @@ mod test {
     use bus_mapping::circuit_input_builder::CircuitsParams;
     use eth_types::{self, bytecode};
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{eth, gwei, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
@@ -384,4 +386,33 @@ mod test {
             .unwrap(),
         );
     }
+
+    #[test]
+    fn end_tx_eip1559() {
+        // The coinbase only receives the tip above the base fee
+        test_ok(
+            TestContext::<2, 2>::new(
+                None,
+                account_0_code_account_1_no_code(bytecode! { STOP }),
+                |mut txs, accs| {
+                    txs[0]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .transaction_type(2)
+                        .max_fee_per_gas(gwei(4))
+                        .max_priority_fee_per_gas(gwei(2))
+                        .value(eth(1));
+                    txs[1]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .transaction_type(2)
+                        .max_fee_per_gas(gwei(2))
+                        .max_priority_fee_per_gas(gwei(2))
+                        .value(eth(1));
+                },
+                |block, _tx| block.number(0xcafeu64).base_fee_per_gas(gwei(1)),
+            )
+            .unwrap(),
+        );
+    }
 }
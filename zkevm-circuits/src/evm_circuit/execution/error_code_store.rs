@@ -0,0 +1,264 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId, MAX_CODE_SIZE},
+    Field,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the code deposit errors of RETURN in a creation call, which are
+/// [`ExecutionState::ErrorMaxCodeSizeExceeded`] when the deployed code is
+/// larger than `MAX_CODE_SIZE` (EIP-170), and
+/// [`ExecutionState::ErrorOutOfGasCodeStore`] when the gas left can't pay for
+/// the deposit of the deployed code.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorCodeStoreGadget<F, const IS_MAX_CODE_SIZE_EXCEEDED: bool> {
+    opcode: Cell<F>,
+    memory_address: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    max_code_size_exceeded: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field, const IS_MAX_CODE_SIZE_EXCEEDED: bool> ExecutionGadget<F>
+    for ErrorCodeStoreGadget<F, IS_MAX_CODE_SIZE_EXCEEDED>
+{
+    const NAME: &'static str = if IS_MAX_CODE_SIZE_EXCEEDED {
+        "ErrorMaxCodeSizeExceeded"
+    } else {
+        "ErrorOutOfGasCodeStore"
+    };
+
+    const EXECUTION_STATE: ExecutionState = if IS_MAX_CODE_SIZE_EXCEEDED {
+        ExecutionState::ErrorMaxCodeSizeExceeded
+    } else {
+        ExecutionState::ErrorOutOfGasCodeStore
+    };
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorCodeStore checking at RETURN",
+            opcode.expr(),
+            OpcodeId::RETURN.expr(),
+        );
+        cb.require_equal(
+            "The code is deposited in a creation call",
+            cb.curr.state.is_create.expr(),
+            1.expr(),
+        );
+
+        let offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+        cb.stack_pop(offset.expr());
+        cb.stack_pop(length.expr());
+        let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+
+        let max_code_size_exceeded =
+            LtGadget::construct(cb, MAX_CODE_SIZE.expr(), memory_address.length());
+        // The deposit cost is charged after the memory expansion of RETURN.
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr() - memory_expansion.gas_cost(),
+            GasCost::CODE_DEPOSIT_BYTE_COST.expr() * memory_address.length(),
+        );
+
+        if IS_MAX_CODE_SIZE_EXCEEDED {
+            cb.require_equal(
+                "Deployed code size > MAX_CODE_SIZE",
+                max_code_size_exceeded.expr(),
+                1.expr(),
+            );
+        } else {
+            // The code size is checked before the deposit cost.
+            cb.require_zero(
+                "Deployed code size <= MAX_CODE_SIZE",
+                max_code_size_exceeded.expr(),
+            );
+            cb.require_equal(
+                "Gas left is less than the code deposit cost",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
+        }
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 4.expr());
+
+        Self {
+            opcode,
+            memory_address,
+            memory_expansion,
+            max_code_size_exceeded,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [memory_offset, length] = [0, 1].map(|index| block.get_rws(step, index).stack_value());
+        let memory_address = self
+            .memory_address
+            .assign(region, offset, memory_offset, length)?;
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+
+        let length = length.low_u64();
+        self.max_code_size_exceeded.assign(
+            region,
+            offset,
+            F::from(MAX_CODE_SIZE),
+            F::from(length),
+        )?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0 - memory_expansion_gas_cost),
+            F::from(GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * length),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 4)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, MAX_CODE_SIZE},
+        geth_types::Account,
+        word, Address, Bytecode, Word,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    // RETURN `length` zero bytes as the deployed code.
+    fn initialization_bytecode(length: u64) -> Bytecode {
+        bytecode! {
+            PUSH32(length)
+            PUSH1(0)
+            RETURN
+        }
+    }
+
+    fn creator_bytecode(initialization_bytecode: Bytecode, is_create2: bool) -> Bytecode {
+        let initialization_bytes = initialization_bytecode.code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH1(0) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code
+    }
+
+    fn test_internal_create(length: u64, gas: Word) {
+        for is_create2 in [false, true] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(initialization_bytecode(length), is_create2).into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(eth(10));
+                    accs[1].account(&caller);
+                },
+                |mut txs, accs| {
+                    txs[0].from(accs[0].address).to(accs[1].address).gas(gas);
+                },
+                |block, _| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    fn test_root_create(length: u64, gas: Word) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .input(initialization_bytecode(length).into())
+                    .gas(gas);
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_max_code_size_exceeded() {
+        test_internal_create(MAX_CODE_SIZE + 1, word!("0x2386F26FC10000"));
+        test_root_create(MAX_CODE_SIZE + 1, word!("0x2386F26FC10000"));
+    }
+
+    #[test]
+    fn test_oog_code_store() {
+        // Enough gas to expand the memory but not to deposit the code.
+        test_internal_create(0x100, 100_000.into());
+        test_root_create(0x100, 60_000.into());
+    }
+}
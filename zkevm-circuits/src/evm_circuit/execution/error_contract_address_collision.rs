@@ -0,0 +1,556 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
+        step::ExecutionState,
+        util::{
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::{
+                ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget,
+                LtGadget, LtWordGadget,
+            },
+            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
+            not, select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
+use eth_types::{evm_types::GasCost, Field, ToAddress, ToBigEndian, ToLittleEndian, ToWord, U256};
+use ethers_core::utils::{keccak256, rlp};
+use gadgets::util::expr_from_bytes;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CREATE and CREATE2 when there is already a contract at the new
+/// address, i.e. the account has a nonce or code (EIP-684). The caller nonce is
+/// increased and the new address is added to the access list, but the callee
+/// is never entered and all the gas passed to it is consumed.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorContractAddressCollisionGadget<F> {
+    opcode: Cell<F>,
+    is_create2: IsEqualGadget<F>,
+    tx_id: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    depth: Cell<F>,
+
+    value: Word<F>,
+    memory_address: MemoryAddressGadget<F>,
+
+    caller_balance: Word<F>,
+    is_insufficient_balance: LtWordGadget<F>,
+    is_depth_ok: LtGadget<F, N_BYTES_U64>,
+    was_warm: Cell<F>,
+
+    create: ContractCreateGadget<F, false>,
+    create2: ContractCreateGadget<F, true>,
+    keccak_output: [Cell<F>; N_BYTES_WORD],
+
+    callee_nonce: Cell<F>,
+    callee_nonce_is_zero: IsZeroGadget<F>,
+    callee_code_hash: Cell<F>,
+    callee_code_hash_is_zero: IsZeroGadget<F>,
+    callee_code_hash_is_empty: IsEqualGadget<F>,
+
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorContractAddressCollisionGadget<F> {
+    const NAME: &'static str = "ErrorContractAddressCollision";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorContractAddressCollision;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_in_set(
+            "ErrorContractAddressCollision opcode must be CREATE or CREATE2",
+            opcode.expr(),
+            vec![OpcodeId::CREATE.expr(), OpcodeId::CREATE2.expr()],
+        );
+        let is_create2 = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::CREATE2.expr());
+
+        let create = ContractCreateGadget::construct(cb);
+        let create2 = ContractCreateGadget::construct(cb);
+        let caller_address = select::expr(
+            is_create2.expr(),
+            create2.caller_address(),
+            create.caller_address(),
+        );
+        let caller_nonce = select::expr(
+            is_create2.expr(),
+            create2.caller_nonce(),
+            create.caller_nonce(),
+        );
+
+        let value = cb.query_word_rlc();
+        let offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+        cb.stack_pop(value.expr());
+        cb.stack_pop(offset.expr());
+        cb.stack_pop(length.expr());
+        cb.condition(is_create2.expr(), |cb| {
+            cb.stack_pop(create2.salt_word_rlc(cb));
+        });
+        let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+        // The creation fails, so zero is pushed instead of the new address.
+        cb.stack_push(0.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let mut reversion_info = cb.reversion_info_read(None);
+        let [is_static, depth, current_address] = [
+            CallContextFieldTag::IsStatic,
+            CallContextFieldTag::Depth,
+            CallContextFieldTag::CalleeAddress,
+        ]
+        .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_zero(
+            "CREATE/CREATE2 is not in static call stack",
+            is_static.expr(),
+        );
+        cb.require_equal(
+            "caller address is the current address",
+            caller_address.expr(),
+            current_address.expr(),
+        );
+
+        // The collision is only checked when the precheck is OK.
+        let caller_balance = cb.query_word_rlc();
+        cb.account_read(
+            caller_address.expr(),
+            AccountFieldTag::Balance,
+            caller_balance.expr(),
+        );
+        let is_insufficient_balance = LtWordGadget::construct(cb, &caller_balance, &value);
+        cb.require_zero(
+            "caller balance is sufficient",
+            is_insufficient_balance.expr(),
+        );
+        let is_depth_ok = LtGadget::construct(cb, depth.expr(), 1025.expr());
+        cb.require_equal("depth < 1025", is_depth_ok.expr(), 1.expr());
+
+        // Increase caller's nonce
+        cb.account_write(
+            caller_address.expr(),
+            AccountFieldTag::Nonce,
+            caller_nonce.expr() + 1.expr(),
+            caller_nonce.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // Add callee to access list
+        let keccak_output = array_init::array_init(|_| cb.query_byte());
+        let new_address = expr_from_bytes(&keccak_output[..N_BYTES_ACCOUNT_ADDRESS]);
+        let was_warm = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            new_address.clone(),
+            1.expr(),
+            was_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        let keccak_output_rlc = cb.word_rlc::<N_BYTES_WORD>(
+            keccak_output
+                .iter()
+                .map(Expr::expr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+        cb.keccak_table_lookup(
+            select::expr(
+                is_create2.expr(),
+                create2.input_rlc(cb),
+                create.input_rlc(cb),
+            ),
+            select::expr(
+                is_create2.expr(),
+                create2.input_length(),
+                create.input_length(),
+            ),
+            keccak_output_rlc,
+        );
+
+        // The new address collides when the account has a nonce or code.
+        let callee_nonce = cb.query_cell();
+        let callee_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            new_address.clone(),
+            AccountFieldTag::Nonce,
+            callee_nonce.expr(),
+        );
+        cb.account_read(
+            new_address,
+            AccountFieldTag::CodeHash,
+            callee_code_hash.expr(),
+        );
+        let callee_nonce_is_zero = IsZeroGadget::construct(cb, callee_nonce.expr());
+        let callee_code_hash_is_zero = IsZeroGadget::construct(cb, callee_code_hash.expr());
+        let callee_code_hash_is_empty =
+            IsEqualGadget::construct(cb, callee_code_hash.expr(), cb.empty_code_hash_rlc());
+        cb.require_zero(
+            "callee has a nonce or code",
+            callee_nonce_is_zero.expr()
+                * (callee_code_hash_is_zero.expr() + callee_code_hash_is_empty.expr()),
+        );
+
+        // The new address of CREATE2 depends on the hash of the initialization
+        // code, which is copied from memory into the bytecode table.
+        cb.condition(is_create2.expr() * memory_address.has_length(), |cb| {
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                create2.code_hash_word_rlc(cb),
+                CopyDataType::Bytecode.expr(),
+                memory_address.offset(),
+                memory_address.address(),
+                0.expr(),
+                memory_address.length(),
+                0.expr(),
+                memory_address.length(),
+            );
+        });
+        cb.condition(
+            is_create2.expr() * not::expr(memory_address.has_length()),
+            |cb| {
+                cb.require_equal(
+                    "code hash of empty initialization code",
+                    create2.code_hash_word_rlc(cb),
+                    cb.empty_code_hash_rlc(),
+                );
+            },
+        );
+
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+        // CREATE2 additionally pays for hashing the initialization code.
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+        let gas_cost = GasCost::CREATE.expr()
+            + select::expr(
+                is_create2.expr(),
+                memory_copier_gas.gas_cost(),
+                memory_expansion.gas_cost(),
+            );
+        // EIP-150: all but one 64th of the caller's gas is sent to the callee,
+        // and none of it is returned.
+        let gas_available = cb.curr.state.gas_left.expr() - gas_cost;
+        let one_64th_gas = ConstantDivisionGadget::construct(cb, gas_available, 64);
+
+        for field_tag in [
+            CallContextFieldTag::LastCalleeId,
+            CallContextFieldTag::LastCalleeReturnDataOffset,
+            CallContextFieldTag::LastCalleeReturnDataLength,
+        ] {
+            cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
+        }
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(2.expr() + is_create2.expr()),
+            gas_left: To(one_64th_gas.quotient()),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            // The caller nonce and the access list writes
+            reversible_write_counter: Delta(2.expr()),
+            ..StepStateTransition::default()
+        });
+
+        Self {
+            opcode,
+            is_create2,
+            tx_id,
+            reversion_info,
+            depth,
+            value,
+            memory_address,
+            caller_balance,
+            is_insufficient_balance,
+            is_depth_ok,
+            was_warm,
+            create,
+            create2,
+            keccak_output,
+            callee_nonce,
+            callee_nonce_is_zero,
+            callee_code_hash,
+            callee_code_hash_is_zero,
+            callee_code_hash_is_empty,
+            memory_expansion,
+            memory_copier_gas,
+            one_64th_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        let is_create2 = opcode == OpcodeId::CREATE2;
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.is_create2.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()),
+            F::from(OpcodeId::CREATE2.as_u64()),
+        )?;
+
+        let n_pop = if is_create2 { 4 } else { 3 };
+        let [value, init_code_start, init_code_length] =
+            [0, 1, 2].map(|i| block.get_rws(step, i).stack_value());
+        let salt = if is_create2 {
+            Some(block.get_rws(step, 3).stack_value())
+        } else {
+            None
+        };
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, init_code_start, init_code_length)?;
+
+        let [tx_id, _, _, _, depth, caller_address] =
+            [1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, n_pop + i).call_context_value());
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth.low_u64())))?;
+
+        let (caller_balance, _) = block.get_rws(step, n_pop + 7).account_value_pair();
+        self.caller_balance
+            .assign(region, offset, Some(caller_balance.to_le_bytes()))?;
+        self.is_insufficient_balance
+            .assign(region, offset, caller_balance, value)?;
+        self.is_depth_ok
+            .assign(region, offset, F::from(depth.low_u64()), F::from(1025))?;
+
+        let (_, caller_nonce) = block.get_rws(step, n_pop + 8).account_value_pair();
+        let caller_nonce = caller_nonce.low_u64();
+        let (_, was_warm) = block.get_rws(step, n_pop + 9).tx_access_list_value_pair();
+        self.was_warm
+            .assign(region, offset, Value::known(F::from(was_warm as u64)))?;
+
+        let (callee_nonce, _) = block.get_rws(step, n_pop + 10).account_value_pair();
+        let (callee_code_hash, _) = block.get_rws(step, n_pop + 11).account_value_pair();
+        self.callee_nonce.assign(
+            region,
+            offset,
+            Value::known(F::from(callee_nonce.low_u64())),
+        )?;
+        self.callee_nonce_is_zero
+            .assign(region, offset, F::from(callee_nonce.low_u64()))?;
+        let callee_code_hash_rlc = region.word_rlc(callee_code_hash);
+        self.callee_code_hash
+            .assign(region, offset, callee_code_hash_rlc)?;
+        self.callee_code_hash_is_zero
+            .assign_value(region, offset, callee_code_hash_rlc)?;
+        self.callee_code_hash_is_empty.assign_value(
+            region,
+            offset,
+            callee_code_hash_rlc,
+            region.empty_code_hash_rlc(),
+        )?;
+
+        let code_hash = if is_create2 {
+            tx.calls
+                .iter()
+                .find(|callee| callee.call_id == step.rwc.0)
+                .map(|callee| callee.code_hash.to_word())
+                .expect("callee of CREATE2 not found")
+        } else {
+            CodeDB::empty_code_hash().to_word()
+        };
+        let caller_address = caller_address.to_address();
+        for create in [&self.create, &self.create2] {
+            create.assign(
+                region,
+                offset,
+                caller_address,
+                caller_nonce,
+                Some(code_hash),
+                salt,
+            )?;
+        }
+        let keccak_input = if let Some(salt) = salt {
+            let mut keccak_input = vec![0xff];
+            keccak_input.extend_from_slice(caller_address.as_bytes());
+            keccak_input.extend_from_slice(&salt.to_be_bytes());
+            keccak_input.extend_from_slice(&code_hash.to_be_bytes());
+            keccak_input
+        } else {
+            let mut stream = rlp::RlpStream::new();
+            stream.begin_list(2);
+            stream.append(&caller_address);
+            stream.append(&U256::from(caller_nonce));
+            stream.out().to_vec()
+        };
+        for (c, v) in self
+            .keccak_output
+            .iter()
+            .rev()
+            .zip(keccak256(keccak_input).iter())
+        {
+            c.assign(region, offset, Value::known(F::from(*v as u64)))?;
+        }
+
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let memory_copier_gas = self.memory_copier_gas.assign(
+            region,
+            offset,
+            init_code_length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+        let gas_cost = GasCost::CREATE.as_u64()
+            + if is_create2 {
+                memory_copier_gas
+            } else {
+                memory_expansion_gas_cost
+            };
+        self.one_64th_gas
+            .assign(region, offset, (step.gas_left.0 - gas_cost) as u128)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode,
+        ToBigEndian, Word,
+    };
+    use ethers_core::utils::{get_contract_address, get_create2_address};
+    use mock::{eth, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+    const SALT: u64 = 45;
+
+    // RETURN a deployed code of `[0x60; 5]`
+    fn initialization_bytecode() -> Bytecode {
+        bytecode! {
+            PUSH5(word!("0x6060606060"))
+            PUSH1(0)
+            MSTORE
+            PUSH1(5)
+            PUSH1(27)
+            RETURN
+        }
+    }
+
+    fn creator_bytecode(is_create2: bool) -> Bytecode {
+        let initialization_bytes = initialization_bytecode().code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(SALT)});
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH1(0) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code
+    }
+
+    fn test_collision(is_create2: bool, collided: impl FnOnce(Address) -> Account) {
+        let caller_nonce = 1u64;
+        let caller = Account {
+            address: CALLER_ADDRESS,
+            code: creator_bytecode(is_create2).into(),
+            nonce: caller_nonce.into(),
+            balance: eth(10),
+            ..Default::default()
+        };
+        let new_address = if is_create2 {
+            get_create2_address(
+                CALLER_ADDRESS,
+                Word::from(SALT).to_be_bytes(),
+                initialization_bytecode().code(),
+            )
+        } else {
+            get_contract_address(CALLER_ADDRESS, caller_nonce)
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+                accs[2].account(&collided(new_address));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_contract_address_collision_with_nonce() {
+        for is_create2 in [false, true] {
+            test_collision(is_create2, |address| Account {
+                address,
+                nonce: 1.into(),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn test_contract_address_collision_with_code() {
+        for is_create2 in [false, true] {
+            test_collision(is_create2, |address| Account {
+                address,
+                code: bytecode! { STOP }.into(),
+                ..Default::default()
+            });
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::{CommonCallGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::IsZeroGadget,
+            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
+            select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{
+    evm_types::{GasCost, GAS_STIPEND_CALL_WITH_VALUE},
+    Field, ToLittleEndian, U256,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the *CALL* and CREATE* opcodes which fail since the call depth
+/// limit of 1024 is reached. The callee is never entered, all the gas passed
+/// to it is returned and zero is pushed as the result, so the execution goes
+/// on in the current call.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorDepthGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_call: IsZeroGadget<F>,
+    is_callcode: IsZeroGadget<F>,
+    is_delegatecall: IsZeroGadget<F>,
+    is_staticcall: IsZeroGadget<F>,
+    is_create: IsZeroGadget<F>,
+    is_create2: IsZeroGadget<F>,
+    tx_id: Cell<F>,
+    depth: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    is_static: Cell<F>,
+    // *CALL*
+    call: CommonCallGadget<F, false>,
+    is_warm_prev: Cell<F>,
+    // CREATE*
+    value: Word<F>,
+    salt: Word<F>,
+    memory_address: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorDepthGadget<F> {
+    const NAME: &'static str = "ErrorDepth";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorDepth;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        let [is_call, is_callcode, is_delegatecall, is_staticcall, is_create, is_create2] = [
+            OpcodeId::CALL,
+            OpcodeId::CALLCODE,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::CREATE,
+            OpcodeId::CREATE2,
+        ]
+        .map(|op| IsZeroGadget::construct(cb, opcode.expr() - op.expr()));
+        let is_call_family =
+            is_call.expr() + is_callcode.expr() + is_delegatecall.expr() + is_staticcall.expr();
+        let is_create_family = is_create.expr() + is_create2.expr();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let depth = cb.call_context(None, CallContextFieldTag::Depth);
+        // The root call is at depth 1, so the callee would be at depth 1026.
+        cb.require_equal("depth == 1025", depth.expr(), 1025.expr());
+        let mut reversion_info = cb.reversion_info_read(None);
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+
+        let (call, is_warm_prev) = cb.condition(is_call_family.expr(), |cb| {
+            let call = CommonCallGadget::construct(
+                cb,
+                is_call.expr(),
+                is_callcode.expr(),
+                is_delegatecall.expr(),
+                is_staticcall.expr(),
+            );
+            cb.condition(call.has_value.clone(), |cb| {
+                cb.require_zero(
+                    "CALL with value must not be in static call stack",
+                    is_static.expr(),
+                );
+            });
+
+            // The callee is added to the access list before the call depth is
+            // checked.
+            let is_warm_prev = cb.query_bool();
+            cb.account_access_list_write(
+                tx_id.expr(),
+                call.callee_address_expr(),
+                1.expr(),
+                is_warm_prev.expr(),
+                Some(&mut reversion_info),
+            );
+
+            (call, is_warm_prev)
+        });
+
+        let (value, salt, memory_address, memory_expansion, memory_copier_gas) =
+            cb.condition(is_create_family.expr(), |cb| {
+                cb.require_zero(
+                    "CREATE/CREATE2 is not in static call stack",
+                    is_static.expr(),
+                );
+
+                let value = cb.query_word_rlc();
+                let offset = cb.query_cell_phase2();
+                let length = cb.query_word_rlc();
+                let salt = cb.query_word_rlc();
+                cb.stack_pop(value.expr());
+                cb.stack_pop(offset.expr());
+                cb.stack_pop(length.expr());
+                cb.condition(is_create2.expr(), |cb| cb.stack_pop(salt.expr()));
+                cb.stack_push(0.expr());
+
+                let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+                let memory_expansion =
+                    MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+                let memory_copier_gas = MemoryCopierGasGadget::construct(
+                    cb,
+                    memory_address.length(),
+                    memory_expansion.gas_cost(),
+                );
+
+                (
+                    value,
+                    salt,
+                    memory_address,
+                    memory_expansion,
+                    memory_copier_gas,
+                )
+            });
+
+        // All the gas passed to the callee is returned, including the stipend
+        // of a call with value.
+        let call_gas_cost = call.gas_cost_expr(is_warm_prev.expr(), is_call.expr())
+            - call.has_value.clone() * GAS_STIPEND_CALL_WITH_VALUE.expr();
+        // CREATE2 additionally pays for hashing the initialization code.
+        let create_gas_cost = GasCost::CREATE.expr()
+            + select::expr(
+                is_create2.expr(),
+                memory_copier_gas.gas_cost(),
+                memory_expansion.gas_cost(),
+            );
+        let gas_cost =
+            is_call_family.expr() * call_gas_cost + is_create_family.expr() * create_gas_cost;
+
+        for field_tag in [
+            CallContextFieldTag::LastCalleeId,
+            CallContextFieldTag::LastCalleeReturnDataOffset,
+            CallContextFieldTag::LastCalleeReturnDataLength,
+        ] {
+            cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
+        }
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(cb.stack_pointer_offset()),
+            gas_left: Delta(-gas_cost),
+            memory_word_size: To(is_call_family.expr()
+                * call.memory_expansion.next_memory_word_size()
+                + is_create_family.expr() * memory_expansion.next_memory_word_size()),
+            // The access list write of *CALL*
+            reversible_write_counter: Delta(is_call_family),
+            ..StepStateTransition::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            is_call,
+            is_callcode,
+            is_delegatecall,
+            is_staticcall,
+            is_create,
+            is_create2,
+            tx_id,
+            depth,
+            reversion_info,
+            is_static,
+            call,
+            is_warm_prev,
+            value,
+            salt,
+            memory_address,
+            memory_expansion,
+            memory_copier_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let opcode = step.opcode().unwrap();
+        for (gadget, op) in [
+            (&self.is_call, OpcodeId::CALL),
+            (&self.is_callcode, OpcodeId::CALLCODE),
+            (&self.is_delegatecall, OpcodeId::DELEGATECALL),
+            (&self.is_staticcall, OpcodeId::STATICCALL),
+            (&self.is_create, OpcodeId::CREATE),
+            (&self.is_create2, OpcodeId::CREATE2),
+        ] {
+            gadget.assign(
+                region,
+                offset,
+                F::from(opcode.as_u64()) - F::from(op.as_u64()),
+            )?;
+        }
+
+        let [tx_id, depth, is_static] =
+            [0, 1, 4].map(|index| block.get_rws(step, index).call_context_value());
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth.low_u64())))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(is_static.low_u64())))?;
+
+        if opcode.is_call() {
+            let is_call_or_callcode =
+                usize::from([OpcodeId::CALL, OpcodeId::CALLCODE].contains(&opcode));
+            let [gas, callee_address] =
+                [5, 6].map(|index| block.get_rws(step, index).stack_value());
+            let value = if is_call_or_callcode == 1 {
+                block.get_rws(step, 7).stack_value()
+            } else {
+                U256::zero()
+            };
+            let [cd_offset, cd_length, rd_offset, rd_length] =
+                [7, 8, 9, 10].map(|i| block.get_rws(step, is_call_or_callcode + i).stack_value());
+            let callee_code_hash = block
+                .get_rws(step, 12 + is_call_or_callcode)
+                .account_value_pair()
+                .0;
+            let (_, is_warm_prev) = block
+                .get_rws(step, 13 + is_call_or_callcode)
+                .tx_access_list_value_pair();
+
+            self.call.assign(
+                region,
+                offset,
+                gas,
+                callee_address,
+                value,
+                U256::zero(),
+                cd_offset,
+                cd_length,
+                rd_offset,
+                rd_length,
+                step.memory_word_size(),
+                region.word_rlc(callee_code_hash),
+            )?;
+            self.is_warm_prev
+                .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+        } else {
+            let [value, init_code_start, init_code_length] =
+                [5, 6, 7].map(|index| block.get_rws(step, index).stack_value());
+            let salt = if opcode == OpcodeId::CREATE2 {
+                block.get_rws(step, 8).stack_value()
+            } else {
+                U256::zero()
+            };
+            self.value
+                .assign(region, offset, Some(value.to_le_bytes()))?;
+            self.salt.assign(region, offset, Some(salt.to_le_bytes()))?;
+
+            let memory_address =
+                self.memory_address
+                    .assign(region, offset, init_code_start, init_code_length)?;
+            let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+                region,
+                offset,
+                step.memory_word_size(),
+                [memory_address],
+            )?;
+            self.memory_copier_gas.assign(
+                region,
+                offset,
+                init_code_length.as_u64(),
+                memory_expansion_gas_cost,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Bytecode, Word};
+    use mock::TestContext;
+
+    // A contract which calls itself with all the gas until the call depth
+    // limit is reached, then runs `bottom_code` in the deepest call.
+    fn recursive_bytecode(bottom_code: Bytecode) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            ADDRESS
+            GAS
+            CALL
+            ISZERO
+            PUSH1(0x12) // bottom
+            JUMPI
+            STOP
+            JUMPDEST // bottom, reached when the CALL fails
+        };
+        code.append(&bottom_code);
+        code
+    }
+
+    fn test_ok(bottom_code: Bytecode) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1]
+                    .address(address!("0x00000000000000000000000000000000000000fe"))
+                    .code(recursive_bytecode(bottom_code));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(10u64.pow(15)));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_depth_error_call() {
+        test_ok(Bytecode::default());
+    }
+
+    #[test]
+    fn test_depth_error_call_with_value() {
+        test_ok(bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x40) // argsOffset
+            PUSH1(1) // value
+            PUSH1(0xff) // addr
+            PUSH2(0xffff) // gas
+            CALL
+            STOP
+        });
+    }
+
+    #[test]
+    fn test_depth_error_create() {
+        test_ok(bytecode! {
+            PUSH1(0x20) // length
+            PUSH1(0) // offset
+            PUSH1(0) // value
+            CREATE
+            STOP
+        });
+    }
+
+    #[test]
+    fn test_depth_error_create2() {
+        test_ok(bytecode! {
+            PUSH1(45) // salt
+            PUSH1(0x40) // length
+            PUSH1(0x20) // offset
+            PUSH1(0) // value
+            CREATE2
+            STOP
+        });
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            memory_gadget::MemoryAddressGadget,
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{OpcodeId, INVALID_INIT_CODE_FIRST_BYTE},
+    Field,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the RETURN in a creation call whose deployed code starts with
+/// the byte 0xEF, which is rejected by EIP-3541.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorInvalidCreationCodeGadget<F> {
+    opcode: Cell<F>,
+    memory_address: MemoryAddressGadget<F>,
+    first_byte: Cell<F>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorInvalidCreationCodeGadget<F> {
+    const NAME: &'static str = "ErrorInvalidCreationCode";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorInvalidCreationCode;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorInvalidCreationCode checking at RETURN",
+            opcode.expr(),
+            OpcodeId::RETURN.expr(),
+        );
+        cb.require_equal(
+            "The code is deposited in a creation call",
+            cb.curr.state.is_create.expr(),
+            1.expr(),
+        );
+
+        let offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+        cb.stack_pop(offset.expr());
+        cb.stack_pop(length.expr());
+        let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+        cb.require_equal(
+            "Deployed code is not empty",
+            memory_address.has_length(),
+            1.expr(),
+        );
+
+        let first_byte = cb.query_cell();
+        cb.memory_lookup(0.expr(), memory_address.offset(), first_byte.expr(), None);
+        cb.require_equal(
+            "Deployed code starts with 0xEF",
+            first_byte.expr(),
+            INVALID_INIT_CODE_FIRST_BYTE.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 5.expr());
+
+        Self {
+            opcode,
+            memory_address,
+            first_byte,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [memory_offset, length] = [0, 1].map(|index| block.get_rws(step, index).stack_value());
+        self.memory_address
+            .assign(region, offset, memory_offset, length)?;
+
+        let first_byte = block.get_rws(step, 2).memory_value();
+        self.first_byte
+            .assign(region, offset, Value::known(F::from(first_byte as u64)))?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 5)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
+    };
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    // RETURN a deployed code starting with 0xEF.
+    fn initialization_bytecode() -> Bytecode {
+        bytecode! {
+            PUSH1(0xef)
+            PUSH1(0)
+            MSTORE8
+            PUSH1(5)
+            PUSH1(0)
+            RETURN
+        }
+    }
+
+    fn creator_bytecode(is_create2: bool) -> Bytecode {
+        let initialization_bytes = initialization_bytecode().code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH1(0) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code
+    }
+
+    #[test]
+    fn test_invalid_creation_code_internal() {
+        for is_create2 in [false, true] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(is_create2).into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(eth(10));
+                    accs[1].account(&caller);
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(word!("0x2386F26FC10000"));
+                },
+                |block, _| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn test_invalid_creation_code_root() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .input(initialization_bytecode().into())
+                    .gas(word!("0x2386F26FC10000"));
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -3,20 +3,26 @@ use crate::evm_circuit::{
     step::ExecutionState,
     table::{FixedTableTag, Lookup},
     util::{
-        common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder, CachedRegion,
-        Cell,
+        common_gadget::{CommonErrorGadget, HardforkGadget},
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        math_gadget::IsEqualGadget,
+        CachedRegion, Cell,
     },
     witness::{Block, Call, ExecStep, Transaction},
 };
-use eth_types::Field;
+use eth_types::{chain_spec::Hardfork, evm_types::OpcodeId, Field};
 use gadgets::util::Expr;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for invalid opcodes. It verifies by a fixed lookup for
-/// ResponsibleOpcode.
+/// ResponsibleOpcode. An opcode introduced by a hardfork is only invalid in
+/// blocks before that hardfork.
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorInvalidOpcodeGadget<F> {
     opcode: Cell<F>,
+    is_shanghai: HardforkGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    is_gated_opcodes: Vec<(OpcodeId, IsEqualGadget<F>)>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -39,10 +45,32 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
             },
         );
 
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        let is_cancun = HardforkGadget::construct(cb, Hardfork::Cancun);
+        let is_gated_opcodes = OpcodeId::hardfork_gated_opcodes()
+            .into_iter()
+            .map(|gated_opcode| {
+                let is_opcode = IsEqualGadget::construct(cb, opcode.expr(), gated_opcode.expr());
+                let is_enabled = match gated_opcode.hardfork() {
+                    Hardfork::London => 1.expr(),
+                    Hardfork::Shanghai => is_shanghai.is_enabled(),
+                    Hardfork::Cancun => is_cancun.is_enabled(),
+                };
+                cb.require_zero(
+                    "Opcode is not enabled in the block",
+                    is_opcode.expr() * is_enabled,
+                );
+                (gated_opcode, is_opcode)
+            })
+            .collect();
+
         let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 2.expr());
 
         Self {
             opcode,
+            is_shanghai,
+            is_cancun,
+            is_gated_opcodes,
             common_error_gadget,
         }
     }
@@ -59,6 +87,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
         let opcode = F::from(step.opcode().unwrap().as_u64());
         self.opcode.assign(region, offset, Value::known(opcode))?;
 
+        self.is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+        for (gated_opcode, is_opcode) in self.is_gated_opcodes.iter() {
+            is_opcode.assign(region, offset, opcode, F::from(gated_opcode.as_u64()))?;
+        }
+
         self.common_error_gadget
             .assign(region, offset, block, call, step, 2)?;
         Ok(())
@@ -68,7 +104,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode::Bytecode, Word};
+    use eth_types::{
+        bytecode::Bytecode,
+        chain_spec::{ChainSpec, Hardfork},
+        evm_types::OpcodeId,
+        Word,
+    };
     use lazy_static::lazy_static;
     use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
 
@@ -81,7 +122,7 @@ mod test {
             vec![0xf6],
             vec![0xfe],
             // Multiple invalid opcodes
-            vec![0x5c, 0x5e, 0x5f],
+            vec![0x0c, 0x0d, 0x0f],
         ];
     }
 
@@ -99,6 +140,35 @@ mod test {
         }
     }
 
+    #[test]
+    fn invalid_opcode_before_hardfork() {
+        test_hardfork_ok(OpcodeId::PUSH0, Hardfork::London);
+        test_hardfork_ok(OpcodeId::TLOAD, Hardfork::Shanghai);
+        test_hardfork_ok(OpcodeId::MCOPY, Hardfork::Shanghai);
+    }
+
+    fn test_hardfork_ok(opcode: OpcodeId, hardfork: Hardfork) {
+        let mut code = Bytecode::default();
+        code.write_op(opcode);
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(mock::MOCK_ACCOUNTS[0]).code(code);
+                accs[1]
+                    .address(mock::MOCK_ACCOUNTS[1])
+                    .balance(Word::from(1_u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.chain_spec(ChainSpec::with_hardfork(hardfork)),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn test_root_ok(invalid_code: &[u8]) {
         let mut code = Bytecode::default();
         invalid_code.iter().for_each(|b| {
@@ -0,0 +1,238 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::LtGadget,
+            select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::BALANCE`], [`OpcodeId::EXTCODESIZE`] and
+/// [`OpcodeId::EXTCODEHASH`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGAccountAccessGadget<F> {
+    opcode: Cell<F>,
+    address_word: Word<F>,
+    tx_id: Cell<F>,
+    is_warm: Cell<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGAccountAccessGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasAccountAccess";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasAccountAccess;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorOutOfGasAccountAccess opcode must be BALANCE, EXTCODESIZE or EXTCODEHASH",
+            opcode.expr(),
+            vec![
+                OpcodeId::BALANCE.expr(),
+                OpcodeId::EXTCODESIZE.expr(),
+                OpcodeId::EXTCODEHASH.expr(),
+            ],
+        );
+
+        let address_word = cb.query_word_rlc();
+        let address = from_bytes::expr(&address_word.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(address_word.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(tx_id.expr(), address, is_warm.expr());
+
+        // According to EIP-2929, the gas cost is different for cold and warm
+        // accounts.
+        let gas_cost = select::expr(
+            is_warm.expr(),
+            GasCost::WARM_ACCESS.expr(),
+            GasCost::COLD_ACCOUNT_ACCESS.expr(),
+        );
+        let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+        cb.require_equal(
+            "Gas left is less than gas cost",
+            insufficient_gas.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 5.expr());
+
+        Self {
+            opcode,
+            address_word,
+            tx_id,
+            is_warm,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let address = block.get_rws(step, 0).stack_value();
+        self.address_word
+            .assign(region, offset, Some(address.to_le_bytes()))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(transaction.id as u64)))?;
+
+        let is_warm = block.get_rws(step, 2).tx_access_list_value_pair().0;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let gas_cost = if is_warm {
+            GasCost::WARM_ACCESS
+        } else {
+            GasCost::COLD_ACCOUNT_ACCESS
+        };
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(gas_cost.0),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 5)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Bytecode, ToWord};
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+
+    const TESTING_OPCODES: &[OpcodeId] = &[
+        OpcodeId::BALANCE,
+        OpcodeId::EXTCODESIZE,
+        OpcodeId::EXTCODEHASH,
+    ];
+
+    #[test]
+    fn test_oog_account_access() {
+        for (opcode, is_warm) in TESTING_OPCODES
+            .iter()
+            .flat_map(|opcode| [(opcode, false), (opcode, true)])
+        {
+            let testing_data = TestingData::new(*opcode, is_warm);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas_cost: u64,
+    }
+
+    impl TestingData {
+        pub fn new(opcode: OpcodeId, is_warm: bool) -> Self {
+            let external_address = MOCK_ACCOUNTS[4];
+
+            let mut bytecode = bytecode! {
+                PUSH32(external_address.to_word())
+            };
+            bytecode.write_op(opcode);
+            let mut gas_cost =
+                OpcodeId::PUSH32.constant_gas_cost().0 + GasCost::COLD_ACCOUNT_ACCESS.0;
+
+            if is_warm {
+                bytecode.append(&bytecode! {
+                    PUSH32(external_address.to_word())
+                });
+                bytecode.write_op(opcode);
+                gas_cost += OpcodeId::PUSH32.constant_gas_cost().0 + GasCost::WARM_ACCESS.0;
+            }
+
+            Self { bytecode, gas_cost }
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas_cost - 1).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            // Decrease expected gas cost (by 1) to trigger out of gas error.
+            PUSH32(testing_data.gas_cost - 1) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(testing_data.bytecode.clone());
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
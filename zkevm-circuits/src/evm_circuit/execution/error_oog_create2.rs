@@ -0,0 +1,290 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64},
+        step::ExecutionState,
+        util::{
+            common_gadget::{CommonErrorGadget, HardforkGadget},
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::{
+                MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget,
+                MemoryWordSizeGadget,
+            },
+            or, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::{GasCost, OpcodeId, MAX_INIT_CODE_SIZE},
+    Field, ToLittleEndian,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the out of gas error of CREATE2, whose gas cost includes the
+/// hashing of the initialization code besides the memory expansion. An
+/// initialization code over the size limit also ends up here.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGCreate2Gadget<F> {
+    opcode: Cell<F>,
+    value: Word<F>,
+    salt: Word<F>,
+    memory_address: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    is_shanghai: HardforkGadget<F>,
+    init_code_word_size: MemoryWordSizeGadget<F>,
+    is_init_code_too_large: LtGadget<F, N_BYTES_U64>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGCreate2Gadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasCREATE2";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasCREATE2;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasCREATE2 opcode must be CREATE2",
+            opcode.expr(),
+            OpcodeId::CREATE2.expr(),
+        );
+
+        let value = cb.query_word_rlc();
+        let offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+        let salt = cb.query_word_rlc();
+        cb.stack_pop(value.expr());
+        cb.stack_pop(offset.expr());
+        cb.stack_pop(length.expr());
+        cb.stack_pop(salt.expr());
+
+        let memory_address = MemoryAddressGadget::construct(cb, offset, length);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        // The initialization code is limited in size and charged per word
+        // since Shanghai (EIP-3860).
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        let init_code_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
+        let is_init_code_too_large =
+            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            GasCost::CREATE.expr()
+                + is_shanghai.is_enabled()
+                    * GasCost::INIT_CODE_WORD.expr()
+                    * init_code_word_size.expr()
+                + memory_copier_gas.gas_cost(),
+        );
+        cb.require_equal(
+            "Gas left is less than gas cost or init code size is over the limit",
+            or::expr([
+                insufficient_gas.expr(),
+                is_shanghai.is_enabled() * is_init_code_too_large.expr(),
+            ]),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 6.expr());
+
+        Self {
+            opcode,
+            value,
+            salt,
+            memory_address,
+            memory_expansion,
+            memory_copier_gas,
+            is_shanghai,
+            init_code_word_size,
+            is_init_code_too_large,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [value, memory_offset, length, salt] =
+            [0, 1, 2, 3].map(|index| block.get_rws(step, index).stack_value());
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.salt.assign(region, offset, Some(salt.to_le_bytes()))?;
+
+        let memory_address = self
+            .memory_address
+            .assign(region, offset, memory_offset, length)?;
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let memory_copier_gas = self.memory_copier_gas.assign(
+            region,
+            offset,
+            length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+
+        let is_shanghai = self
+            .is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
+        let init_code_word_size =
+            self.init_code_word_size
+                .assign(region, offset, length.as_u64())?;
+        self.is_init_code_too_large.assign(
+            region,
+            offset,
+            F::from(MAX_INIT_CODE_SIZE),
+            F::from(length.as_u64()),
+        )?;
+
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(
+                GasCost::CREATE.as_u64()
+                    + if is_shanghai {
+                        GasCost::INIT_CODE_WORD.as_u64() * init_code_word_size
+                    } else {
+                        0
+                    }
+                    + memory_copier_gas,
+            ),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 6)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, MAX_INIT_CODE_SIZE},
+        geth_types::Account,
+        word, Address, Bytecode, ToWord, Word,
+    };
+    use mock::{eth, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+    const CALLEE_ADDRESS: Address = Address::repeat_byte(0xff);
+
+    fn create2_bytecode(offset: u64, length: u64) -> Bytecode {
+        bytecode! {
+            PUSH1(45) // salt
+            PUSH32(length)
+            PUSH32(offset)
+            PUSH1(0) // value
+            CREATE2
+        }
+    }
+
+    fn test_context(code: Bytecode, callee: Option<Account>, gas: Word) -> TestContext<3, 1> {
+        let caller = Account {
+            address: CALLER_ADDRESS,
+            code: code.into(),
+            nonce: Word::one(),
+            balance: eth(10),
+            ..Default::default()
+        };
+
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+                if let Some(callee) = callee {
+                    accs[2].account(&callee);
+                } else {
+                    accs[2]
+                        .address(address!("0x000000000000000000000000000000000000beef"))
+                        .balance(eth(1));
+                }
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[0].address).to(accs[1].address).gas(gas);
+            },
+            |block, _| block,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_oog_create2_root() {
+        // Enough gas for the constant cost of CREATE2 but not for the memory
+        // expansion and the hashing of the initialization code.
+        let gas = 21_000 + 4 * OpcodeId::PUSH32.constant_gas_cost().as_u64() + 32_005;
+        for (offset, length) in [(0, 0x20), (0x100, 0x1000)] {
+            let ctx = test_context(create2_bytecode(offset, length), None, gas.into());
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn test_oog_create2_init_code_size_exceeded() {
+        // The initialization code is over the size limit, which fails no
+        // matter how much gas is left.
+        let ctx = test_context(
+            create2_bytecode(0, MAX_INIT_CODE_SIZE + 1),
+            None,
+            word!("0x2386F26FC10000"),
+        );
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_oog_create2_internal() {
+        let callee = Account {
+            address: CALLEE_ADDRESS,
+            code: create2_bytecode(0x40, 0x2000).into(),
+            nonce: Word::one(),
+            balance: eth(1),
+            ..Default::default()
+        };
+        let code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH32(CALLEE_ADDRESS.to_word())
+            // Less than the 34952 gas needed by CREATE2
+            PUSH2(34_000)
+            CALL
+            STOP
+        };
+        let ctx = test_context(code, Some(callee), word!("0x2386F26FC10000"));
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
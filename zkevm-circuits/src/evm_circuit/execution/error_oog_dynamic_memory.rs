@@ -0,0 +1,352 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64},
+        step::ExecutionState,
+        util::{
+            common_gadget::{CommonErrorGadget, HardforkGadget},
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, LtGadget},
+            memory_gadget::{
+                MemoryExpandedAddressGadget, MemoryExpansionGadget, MemoryWordSizeGadget,
+            },
+            not, or, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::{GasCost, OpcodeId, MAX_INIT_CODE_SIZE},
+    Field, ToLittleEndian,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for RETURN, REVERT and CREATE which run out of gas due to the
+/// memory expansion, including the case that the memory address overflows.
+/// CREATE with an initialization code over the size limit also ends up here.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGDynamicMemoryGadget<F> {
+    opcode: Cell<F>,
+    is_create: IsEqualGadget<F>,
+    value: Word<F>,
+    memory_address: MemoryExpandedAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    is_shanghai: HardforkGadget<F>,
+    is_init_code_too_large: LtGadget<F, N_BYTES_U64>,
+    init_code_word_size: MemoryWordSizeGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGDynamicMemoryGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasDynamicMemoryExpansion";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasDynamicMemoryExpansion;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorOutOfGasDynamicMemoryExpansion opcode must be RETURN or REVERT or CREATE",
+            opcode.expr(),
+            vec![
+                OpcodeId::RETURN.expr(),
+                OpcodeId::REVERT.expr(),
+                OpcodeId::CREATE.expr(),
+            ],
+        );
+
+        let is_create = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::CREATE.expr());
+
+        // CREATE pops the value before the memory offset and length.
+        let value = cb.query_word_rlc();
+        cb.condition(is_create.expr(), |cb| cb.stack_pop(value.expr()));
+
+        let memory_address = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.stack_pop(memory_address.offset_rlc());
+        cb.stack_pop(memory_address.length_rlc());
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access, which is zero if the memory address overflows.
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+
+        // Since Shanghai CREATE also charges per word of the initialization
+        // code, which is only metered when it is within the size limit
+        // (EIP-3860).
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        let is_init_code_too_large =
+            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+        let init_code_word_size = MemoryWordSizeGadget::construct(
+            cb,
+            not::expr(is_init_code_too_large.expr()) * memory_address.length(),
+        );
+
+        // RETURN and REVERT have no constant gas cost.
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            is_create.expr()
+                * (OpcodeId::CREATE.constant_gas_cost().expr()
+                    + is_shanghai.is_enabled()
+                        * GasCost::INIT_CODE_WORD.expr()
+                        * init_code_word_size.expr())
+                + memory_expansion.gas_cost(),
+        );
+        cb.require_equal(
+            "Memory address is overflow, gas left is less than cost or init code size is over the limit",
+            or::expr([
+                memory_address.overflow(),
+                insufficient_gas.expr(),
+                is_create.expr() * is_shanghai.is_enabled() * is_init_code_too_large.expr(),
+            ]),
+            1.expr(),
+        );
+
+        let common_error_gadget =
+            CommonErrorGadget::construct(cb, opcode.expr(), 4.expr() + is_create.expr());
+
+        Self {
+            opcode,
+            is_create,
+            value,
+            memory_address,
+            memory_expansion,
+            is_shanghai,
+            is_init_code_too_large,
+            init_code_word_size,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let is_create = self.is_create.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()),
+            F::from(OpcodeId::CREATE.as_u64()),
+        )? == F::ONE;
+
+        let mut rw_offset = 0;
+        if is_create {
+            let value = block.get_rws(step, rw_offset).stack_value();
+            self.value
+                .assign(region, offset, Some(value.to_le_bytes()))?;
+            rw_offset += 1;
+        }
+
+        let [memory_offset, memory_length] =
+            [rw_offset, rw_offset + 1].map(|index| block.get_rws(step, index).stack_value());
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, memory_offset, memory_length)?;
+
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+
+        // The length is zero when the memory address overflows.
+        let init_code_length = if memory_address.is_zero() {
+            0
+        } else {
+            memory_length.as_u64()
+        };
+        let is_shanghai = self
+            .is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
+        let is_init_code_too_large = init_code_length > MAX_INIT_CODE_SIZE;
+        self.is_init_code_too_large.assign(
+            region,
+            offset,
+            F::from(MAX_INIT_CODE_SIZE),
+            F::from(init_code_length),
+        )?;
+        let init_code_word_size = self.init_code_word_size.assign(
+            region,
+            offset,
+            if is_init_code_too_large {
+                0
+            } else {
+                init_code_length
+            },
+        )?;
+
+        let constant_gas_cost = if is_create {
+            OpcodeId::CREATE.constant_gas_cost().0
+                + if is_shanghai {
+                    GasCost::INIT_CODE_WORD.as_u64() * init_code_word_size
+                } else {
+                    0
+                }
+        } else {
+            0
+        };
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(constant_gas_cost + memory_expansion_cost),
+        )?;
+
+        self.common_error_gadget.assign(
+            region,
+            offset,
+            block,
+            call,
+            step,
+            4 + is_create as usize,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        bytecode,
+        evm_types::{
+            gas_utils::{init_code_gas_cost, memory_expansion_gas_cost},
+            GasCost,
+        },
+        Bytecode, ToWord, U256,
+    };
+    use itertools::Itertools;
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+
+    use super::*;
+
+    const TESTING_OPCODES: &[OpcodeId] = &[OpcodeId::RETURN, OpcodeId::REVERT, OpcodeId::CREATE];
+
+    // The first pair runs out of gas for the memory expansion cost, the second
+    // one exceeds the initialization code size limit of CREATE, and the others
+    // overflow the maximum expanded memory address or u64.
+    const TESTING_OFFSET_LENGTH_PAIRS: &[(U256, U256)] = &[
+        (U256([0x2000, 0, 0, 0]), U256([0x200, 0, 0, 0])),
+        (U256([0, 0, 0, 0]), U256([MAX_INIT_CODE_SIZE + 1, 0, 0, 0])),
+        (U256([0x1FFFFFFFE0, 0, 0, 0]), U256([1, 0, 0, 0])),
+        (U256([1, 0, 0, 0]), U256([u64::MAX, 0, 0, 0])),
+        (U256([u64::MAX, 0, 0, 0]), U256([u64::MAX, 0, 0, 0])),
+        (U256::MAX, U256([1, 0, 0, 0])),
+    ];
+
+    #[test]
+    fn test_oog_dynamic_memory() {
+        for (opcode, (memory_offset, memory_length)) in TESTING_OPCODES
+            .iter()
+            .cartesian_product(TESTING_OFFSET_LENGTH_PAIRS.iter())
+        {
+            let testing_data = TestingData::new(*opcode, *memory_offset, *memory_length);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas: u64,
+    }
+
+    impl TestingData {
+        pub fn new(opcode: OpcodeId, memory_offset: U256, memory_length: U256) -> Self {
+            let mut bytecode = Bytecode::default();
+            bytecode.push(32, memory_length);
+            bytecode.push(32, memory_offset);
+            let mut gas_cost = OpcodeId::PUSH32.constant_gas_cost().0 * 2;
+            if opcode == OpcodeId::CREATE {
+                bytecode.push(32, U256::zero()); // value
+                gas_cost += OpcodeId::PUSH32.constant_gas_cost().0;
+                if memory_length <= U256::from(MAX_INIT_CODE_SIZE) {
+                    gas_cost += init_code_gas_cost(Hardfork::default(), memory_length.as_u64());
+                }
+            }
+            bytecode.write_op(opcode);
+            gas_cost += opcode.constant_gas_cost().0;
+
+            let gas = match memory_offset
+                .checked_add(memory_length)
+                .filter(|address| *address <= U256::from(0x1FFFFFFFE0u64))
+            {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                Some(address) => {
+                    gas_cost + memory_expansion_gas_cost(0, (address.as_u64() + 31) / 32) - 1
+                }
+                // The memory address overflows, so the error is triggered with
+                // sufficient gas.
+                None => gas_cost + 100_000,
+            };
+
+            Self { bytecode, gas }
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            PUSH32(testing_data.gas) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(testing_data.bytecode.clone());
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsZeroGadget, LtGadget},
+            not, select, sum, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, ToScalar,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas error for
+/// [`OpcodeId::SELFDESTRUCT`]. Besides the constant gas cost, a cold
+/// beneficiary costs an extra account access, and sending a non-zero balance
+/// to an empty beneficiary costs an extra new account charge.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary_address: Word<F>,
+    tx_id: Cell<F>,
+    callee_address: Cell<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    balance: Word<F>,
+    balance_is_zero: IsZeroGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSelfDestructGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSELFDESTRUCT opcode must be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary_address = cb.query_word_rlc();
+        let beneficiary = from_bytes::expr(&beneficiary_address.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary_address.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(tx_id.expr(), beneficiary.expr(), is_warm.expr());
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary,
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+
+        let balance = cb.query_word_rlc();
+        cb.account_read(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            balance.expr(),
+        );
+        let balance_is_zero = IsZeroGadget::construct(cb, sum::expr(&balance.cells));
+
+        let gas_cost = OpcodeId::SELFDESTRUCT.constant_gas_cost().expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr()
+                * not::expr(balance_is_zero.expr())
+                * GasCost::NEW_ACCOUNT.expr();
+        let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
+        cb.require_equal(
+            "Gas left is less than gas cost",
+            insufficient_gas.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 8.expr());
+
+        Self {
+            opcode,
+            beneficiary_address,
+            tx_id,
+            callee_address,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            balance_is_zero,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let beneficiary = block.get_rws(step, 0).stack_value();
+        self.beneficiary_address
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.address.to_scalar().unwrap()),
+        )?;
+
+        let is_warm = block.get_rws(step, 3).tx_access_list_value_pair().0;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let (beneficiary_code_hash, _) = block.get_rws(step, 4).account_value_pair();
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+
+        let (balance, _) = block.get_rws(step, 5).account_value_pair();
+        self.balance
+            .assign(region, offset, Some(balance.to_le_bytes()))?;
+        self.balance_is_zero
+            .assign(region, offset, sum::value(&balance.to_le_bytes()))?;
+
+        let gas_cost = OpcodeId::SELFDESTRUCT.constant_gas_cost().0
+            + if is_warm {
+                0
+            } else {
+                GasCost::COLD_ACCOUNT_ACCESS.0
+            }
+            + if beneficiary_code_hash.is_zero() && !balance.is_zero() {
+                GasCost::NEW_ACCOUNT.0
+            } else {
+                0
+            };
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(gas_cost),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, U256};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    const CONTRACT_ADDRESS: Address = Address::repeat_byte(0x10);
+    const EXISTING_ADDRESS: Address = Address::repeat_byte(0x20);
+
+    fn selfdestruct_bytecode(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    // The gas cost of `selfdestruct_bytecode`, where the contract itself is
+    // warm and the other beneficiaries are cold.
+    fn gas_cost(beneficiary: Address, balance: U256) -> u64 {
+        let mut gas_cost =
+            OpcodeId::PUSH20.constant_gas_cost().0 + OpcodeId::SELFDESTRUCT.constant_gas_cost().0;
+        if beneficiary != CONTRACT_ADDRESS {
+            gas_cost += GasCost::COLD_ACCOUNT_ACCESS.0;
+        }
+        if beneficiary != CONTRACT_ADDRESS && beneficiary != EXISTING_ADDRESS && !balance.is_zero()
+        {
+            gas_cost += GasCost::NEW_ACCOUNT.0;
+        }
+        gas_cost
+    }
+
+    fn test_root(beneficiary: Address, balance: U256) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(U256::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(balance)
+                    .code(selfdestruct_bytecode(beneficiary));
+                accs[2]
+                    .address(EXISTING_ADDRESS)
+                    .balance(U256::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas((GasCost::TX.0 + gas_cost(beneficiary, balance) - 1).into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(beneficiary: Address, balance: U256) {
+        let caller_code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH20(CONTRACT_ADDRESS.to_word()) // addr
+            // Decrease expected gas cost (by 1) to trigger out of gas error.
+            PUSH32(gas_cost(beneficiary, balance) - 1) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(U256::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe02"))
+                    .code(caller_code);
+                accs[2]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(balance)
+                    .code(selfdestruct_bytecode(beneficiary));
+                accs[3]
+                    .address(EXISTING_ADDRESS)
+                    .balance(U256::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(U256::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_oog_self_destruct() {
+        let beneficiaries = [
+            // existing account
+            EXISTING_ADDRESS,
+            // non-existing account, which costs the new account charge with
+            // a non-zero balance
+            address!("0x00000000000000000000000000000000000000ff"),
+            // the contract itself
+            CONTRACT_ADDRESS,
+        ];
+        let balances = [U256::zero(), U256::from(1u64 << 20)];
+        for (beneficiary, balance) in beneficiaries.iter().cartesian_product(balances.iter()) {
+            test_root(*beneficiary, *balance);
+            test_internal(*beneficiary, *balance);
+        }
+    }
+}
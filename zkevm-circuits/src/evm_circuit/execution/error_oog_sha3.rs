@@ -0,0 +1,259 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::{
+                MemoryCopierGasGadget, MemoryExpandedAddressGadget, MemoryExpansionGadget,
+            },
+            or, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas error for
+/// [`OpcodeId::SHA3`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSha3Gadget<F> {
+    opcode: Cell<F>,
+    memory_address: MemoryExpandedAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSha3Gadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSHA3";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSHA3;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSHA3 opcode must be SHA3",
+            opcode.expr(),
+            OpcodeId::SHA3.expr(),
+        );
+
+        let memory_address = MemoryExpandedAddressGadget::construct_self(cb);
+        cb.stack_pop(memory_address.offset_rlc());
+        cb.stack_pop(memory_address.length_rlc());
+
+        // The memory expansion and copier gas costs are zero if the memory
+        // address overflows.
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            OpcodeId::SHA3.constant_gas_cost().expr() + memory_copier_gas.gas_cost(),
+        );
+        cb.require_equal(
+            "Memory address is overflow or gas left is less than cost",
+            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 4.expr());
+
+        Self {
+            opcode,
+            memory_address,
+            memory_expansion,
+            memory_copier_gas,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [memory_offset, memory_length] =
+            [0, 1].map(|index| block.get_rws(step, index).stack_value());
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, memory_offset, memory_length)?;
+
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+
+        // The address is only zero for an empty or overflowed memory access,
+        // where no bytes are hashed.
+        let num_bytes = if memory_address == 0 {
+            0
+        } else {
+            memory_length.as_u64()
+        };
+        let memory_copier_gas =
+            self.memory_copier_gas
+                .assign(region, offset, num_bytes, memory_expansion_cost)?;
+
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(OpcodeId::SHA3.constant_gas_cost().0 + memory_copier_gas),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 4)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        bytecode, evm_types::gas_utils::memory_expansion_gas_cost, Bytecode, ToWord, U256,
+    };
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+
+    // The first pairs run out of gas for the copier and memory expansion
+    // costs, and the others overflow the maximum expanded memory address or
+    // u64.
+    const TESTING_OFFSET_LENGTH_PAIRS: &[(U256, U256)] = &[
+        (U256([0x20, 0, 0, 0]), U256([0, 0, 0, 0])),
+        (U256([0x40, 0, 0, 0]), U256([20, 0, 0, 0])),
+        (U256([0x2000, 0, 0, 0]), U256([0x200, 0, 0, 0])),
+        (U256([0x1FFFFFFFE0, 0, 0, 0]), U256([1, 0, 0, 0])),
+        (U256([1, 0, 0, 0]), U256([u64::MAX, 0, 0, 0])),
+        (U256::MAX, U256([1, 0, 0, 0])),
+    ];
+
+    #[test]
+    fn test_oog_sha3() {
+        for (memory_offset, memory_length) in TESTING_OFFSET_LENGTH_PAIRS {
+            let testing_data = TestingData::new(*memory_offset, *memory_length);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas: u64,
+    }
+
+    impl TestingData {
+        pub fn new(memory_offset: U256, memory_length: U256) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(memory_length)
+                PUSH32(memory_offset)
+                SHA3
+            };
+            let gas_cost =
+                OpcodeId::PUSH32.constant_gas_cost().0 * 2 + OpcodeId::SHA3.constant_gas_cost().0;
+
+            let gas = match memory_offset
+                .checked_add(memory_length)
+                .filter(|address| *address <= U256::from(0x1FFFFFFFE0u64))
+            {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                Some(address) => {
+                    let memory_word_size = if memory_length.is_zero() {
+                        0
+                    } else {
+                        (address.as_u64() + 31) / 32
+                    };
+                    gas_cost
+                        + GasCost::COPY_SHA3.0 * ((memory_length.as_u64() + 31) / 32)
+                        + memory_expansion_gas_cost(0, memory_word_size)
+                        - 1
+                }
+                // The memory address overflows, so the error is triggered with
+                // sufficient gas.
+                None => gas_cost + 100_000,
+            };
+
+            Self { bytecode, gas }
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            PUSH32(testing_data.gas) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(testing_data.bytecode.clone());
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
+            CachedRegion,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{chain_spec::Hardfork, evm_types::GasCost, Field};
+use halo2_proofs::plonk::Error;
+
+#[derive(Clone, Debug)]
+pub(crate) struct McopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    dst_memory_address: MemoryAddressGadget<F>,
+    src_memory_address: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+}
+
+impl<F: Field> ExecutionGadget<F> for McopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // MCOPY is only valid in Cancun blocks (EIP-5656).
+        let is_cancun = HardforkGadget::construct_enabled(cb, Hardfork::Cancun);
+
+        let opcode = cb.query_cell();
+
+        let dst_offset = cb.query_cell_phase2();
+        let src_offset = cb.query_cell_phase2();
+        let length = cb.query_word_rlc();
+
+        // Pop dst_offset, src_offset, length from stack
+        cb.stack_pop(dst_offset.expr());
+        cb.stack_pop(src_offset.expr());
+        cb.stack_pop(length.expr());
+
+        // Both the source and the destination ranges may expand the memory
+        let dst_memory_address = MemoryAddressGadget::construct(cb, dst_offset, length.clone());
+        let src_memory_address = MemoryAddressGadget::construct(cb, src_offset, length);
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [dst_memory_address.address(), src_memory_address.address()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_address.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        cb.condition(dst_memory_address.has_length(), |cb| {
+            // `length` memory reads followed by `length` memory writes
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::MemoryCopy.expr(),
+                src_memory_address.offset(),
+                src_memory_address.address(),
+                dst_memory_address.offset(),
+                dst_memory_address.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                2.expr() * dst_memory_address.length(),
+            );
+        });
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            // 3 stack pop + memory reads and writes of the copy
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            is_cancun,
+            dst_memory_address,
+            src_memory_address,
+            memory_expansion,
+            memory_copier_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+
+        let [dst_offset, src_offset, length] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
+        let dst_memory_address = self
+            .dst_memory_address
+            .assign(region, offset, dst_offset, length)?;
+        let src_memory_address = self
+            .src_memory_address
+            .assign(region, offset, src_offset, length)?;
+
+        // Memory expansion
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [dst_memory_address, src_memory_address],
+        )?;
+
+        self.memory_copier_gas.assign(
+            region,
+            offset,
+            length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(dst_offset: usize, src_offset: usize, length: usize) {
+        let bytecode = bytecode! {
+            PUSH32(Word::from_big_endian(&rand_bytes(32)))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&rand_bytes(32)))
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            #[start]
+            MCOPY
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn mcopy_gadget_simple() {
+        test_ok(0x40, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlap() {
+        // destination after the source
+        test_ok(0x10, 0x00, 0x30);
+        // destination before the source
+        test_ok(0x00, 0x10, 0x30);
+        // same range
+        test_ok(0x08, 0x08, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_expand_source() {
+        test_ok(0x00, 0x50, 0x41);
+    }
+
+    #[test]
+    fn mcopy_gadget_large() {
+        test_ok(0x103, 0x20, 0x101);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        test_ok(0x40, 0x40, 0);
+        test_ok(usize::MAX, usize::MAX, 0);
+    }
+}
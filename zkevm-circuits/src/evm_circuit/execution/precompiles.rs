@@ -1 +1,26 @@
+mod blake2f;
+pub(crate) use blake2f::Blake2fGadget;
 
+mod ec_add;
+pub(crate) use ec_add::EcAddGadget;
+
+mod ec_mul;
+pub(crate) use ec_mul::EcMulGadget;
+
+mod ec_pairing;
+pub(crate) use ec_pairing::EcPairingGadget;
+
+mod ecrecover;
+pub(crate) use ecrecover::EcrecoverGadget;
+
+mod identity;
+pub(crate) use identity::IdentityGadget;
+
+mod modexp;
+pub(crate) use modexp::ModExpGadget;
+
+mod ripemd160;
+pub(crate) use ripemd160::Ripemd160Gadget;
+
+mod sha256;
+pub(crate) use sha256::Sha256Gadget;
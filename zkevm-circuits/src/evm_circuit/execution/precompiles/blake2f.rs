@@ -0,0 +1,508 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{
+        PrecompileAuxData, PrecompileCalls, BLAKE2F_FINAL_FLAG_OFFSET, BLAKE2F_INPUT_LENGTH,
+    },
+};
+use eth_types::{Field, ToScalar};
+use gadgets::util::{and, not, select, sum, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget, MinMaxGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Length of the output of a successful call, i.e. the new state vector.
+const OUTPUT_LENGTH: u64 = 64;
+
+/// Number of bytes of the number of rounds at the start of the input.
+const N_BYTES_ROUNDS: usize = 4;
+
+/// Gadget for the BLAKE2 F precompile. The number of rounds and the final
+/// block indicator of an input of the expected length are read from the
+/// caller's memory to check the gas and the indicator, and the input of a
+/// successful call is copied into an RLC, whose compression is verified
+/// through a lookup to the blake2f table. The call fails if the input length
+/// isn't 213 bytes, if it runs out of gas or if the final block indicator
+/// isn't 0 or 1.
+#[derive(Clone, Debug)]
+pub(crate) struct Blake2fGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    is_input_length_valid: IsEqualGadget<F>,
+    rounds: [Cell<F>; N_BYTES_ROUNDS],
+    f: Cell<F>,
+    f_is_zero: IsZeroGadget<F>,
+    f_is_one: IsEqualGadget<F>,
+
+    input_rlc: Cell<F>,
+    output_rlc: Cell<F>,
+
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_length_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Blake2fGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBlake2f;
+
+    const NAME: &'static str = "BLAKE2F";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is blake2f",
+            callee_address.expr(),
+            PrecompileCalls::Blake2F.address().expr(),
+        );
+
+        let is_input_length_valid = IsEqualGadget::construct(
+            cb,
+            call_data_length.expr(),
+            (BLAKE2F_INPUT_LENGTH as u64).expr(),
+        );
+        let rounds = array_init::array_init(|_| cb.query_cell());
+        let f = cb.query_cell();
+        cb.condition(is_input_length_valid.expr(), |cb| {
+            // Read the number of rounds and the final block indicator from
+            // the caller's memory.
+            for (idx, byte) in (0..N_BYTES_ROUNDS)
+                .chain(std::iter::once(BLAKE2F_FINAL_FLAG_OFFSET))
+                .zip(rounds.iter().chain(std::iter::once(&f)))
+            {
+                cb.memory_lookup(
+                    0.expr(),
+                    call_data_offset.expr() + idx.expr(),
+                    byte.expr(),
+                    Some(caller_id.expr()),
+                );
+            }
+        });
+        cb.condition(not::expr(is_input_length_valid.expr()), |cb| {
+            for byte in rounds.iter().chain(std::iter::once(&f)) {
+                cb.require_zero("input not read", byte.expr());
+            }
+        });
+        // The number of rounds is a big-endian integer.
+        let num_rounds = rounds
+            .iter()
+            .fold(0.expr(), |acc, byte| acc * 256.expr() + byte.expr());
+        let f_is_zero = IsZeroGadget::construct(cb, f.expr());
+        let f_is_one = IsEqualGadget::construct(cb, f.expr(), 1.expr());
+
+        // Each round costs one gas.
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), num_rounds.clone());
+        cb.require_equal(
+            "is_success == input length is 213, gas_left >= rounds and f is 0 or 1",
+            is_success.expr(),
+            and::expr([
+                is_input_length_valid.expr(),
+                not::expr(insufficient_gas.expr()),
+                sum::expr([f_is_zero.expr(), f_is_one.expr()]),
+            ]),
+        );
+
+        let input_rlc = cb.query_cell_phase2();
+        let output_rlc = cb.query_cell_phase2();
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), OUTPUT_LENGTH.expr());
+        let return_data_length_is_zero = IsZeroGadget::construct(cb, return_data_length.expr());
+
+        cb.condition(is_success.expr(), |cb| {
+            // Copy the input from the caller's memory.
+            cb.copy_table_lookup(
+                caller_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                call_data_offset.expr(),
+                call_data_offset.expr() + call_data_length.expr(),
+                0.expr(),
+                call_data_length.expr(),
+                input_rlc.expr(),
+                call_data_length.expr(),
+            );
+            // The compression is verified by the blake2f table.
+            cb.blake2f_table_lookup(input_rlc.expr(), output_rlc.expr());
+            // Write the output to the callee's memory.
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                output_rlc.expr(),
+                OUTPUT_LENGTH.expr(),
+            );
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(is_success.expr(), num_rounds, cb.curr.state.gas_left.expr());
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            OUTPUT_LENGTH.expr() * is_success.expr(),
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            is_input_length_valid,
+            rounds,
+            f,
+            f_is_zero,
+            f_is_one,
+            input_rlc,
+            output_rlc,
+            insufficient_gas,
+            return_data_copy_size,
+            return_data_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::Blake2F(aux_data)) = &step.aux_data else {
+            unreachable!("blake2f step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let is_input_length_valid = call_data_length.as_usize() == BLAKE2F_INPUT_LENGTH;
+        self.is_input_length_valid.assign(
+            region,
+            offset,
+            F::from(call_data_length.as_u64()),
+            F::from(BLAKE2F_INPUT_LENGTH as u64),
+        )?;
+        // The auxiliary data is only parsed from an input of the expected
+        // length, and is zero otherwise like the bytes read from memory.
+        let f = aux_data.f;
+        for (cell, byte) in self.rounds.iter().zip(aux_data.rounds.to_be_bytes()) {
+            cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+        }
+        self.f
+            .assign(region, offset, Value::known(F::from(f as u64)))?;
+        self.f_is_zero.assign(region, offset, F::from(f as u64))?;
+        self.f_is_one
+            .assign(region, offset, F::from(f as u64), F::ONE)?;
+
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(aux_data.rounds as u64),
+        )?;
+
+        let [input_rlc, output_rlc] =
+            [aux_data.input_bytes(), aux_data.output_bytes()].map(|bytes| {
+                region
+                    .challenges()
+                    .keccak_input()
+                    .map(|randomness| rlc::value(bytes.iter().rev(), randomness))
+            });
+        self.input_rlc.assign(region, offset, input_rlc)?;
+        self.output_rlc.assign(region, offset, output_rlc)?;
+
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(OUTPUT_LENGTH),
+        )?;
+        self.return_data_length_is_zero.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one, both
+        // coming after the reads of the number of rounds and the final block
+        // indicator of an input of the expected length.
+        let header_reads = if is_input_length_valid {
+            N_BYTES_ROUNDS + 1
+        } else {
+            0
+        };
+        let rw_offset = if call.is_success {
+            7 + header_reads
+                + BLAKE2F_INPUT_LENGTH
+                + OUTPUT_LENGTH as usize
+                + 2 * return_data_length.as_usize().min(OUTPUT_LENGTH as usize)
+        } else {
+            7 + header_reads + 1
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::{PrecompileCalls, BLAKE2F_INPUT_LENGTH};
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    /// Offset in memory of the returned data, after the input.
+    const RETURN_DATA_OFFSET: u64 = 0x100;
+
+    /// The input of the test vectors of EIP-152, i.e. the state vector of
+    /// BLAKE2b-512 and the message "abc", with the given number of rounds and
+    /// final block indicator.
+    fn input(rounds: u32, f: u8) -> Vec<u8> {
+        let mut input = rounds.to_be_bytes().to_vec();
+        input.extend(
+            hex::decode(
+                "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e51\
+                 1f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+            )
+            .unwrap(),
+        );
+        input.extend(b"abc");
+        input.resize(BLAKE2F_INPUT_LENGTH - 17, 0);
+        input.extend(3u64.to_le_bytes());
+        input.extend(0u64.to_le_bytes());
+        input.push(f);
+        input
+    }
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        input: Vec<u8>,
+        call_data_length: u64,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            Self::with_input(input(12, 1))
+        }
+    }
+
+    impl PrecompileCallArgs {
+        fn with_input(input: Vec<u8>) -> Self {
+            Self {
+                call_data_length: input.len() as u64,
+                input,
+                return_data_length: 64,
+                gas: 1000,
+            }
+        }
+
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = Bytecode::default();
+            for (i, chunk) in self.input.chunks(32).enumerate() {
+                let mut word = chunk.to_vec();
+                word.resize(32, 0);
+                code.append(&bytecode! {
+                    PUSH32(Word::from_big_endian(&word))
+                    PUSH2(32 * i)
+                    MSTORE
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(self.return_data_length)
+                PUSH2(RETURN_DATA_OFFSET)
+                PUSH2(self.call_data_length)
+                PUSH1(0x00)
+            });
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Blake2F.address())
+                PUSH2(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH2(RETURN_DATA_OFFSET)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_blake2f_valid() {
+        let cases = [
+            PrecompileCallArgs::default(),
+            PrecompileCallArgs::with_input(input(12, 0)),
+            PrecompileCallArgs::with_input(input(0, 1)),
+            PrecompileCallArgs::with_input(input(1, 0)),
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_blake2f_return_data_length() {
+        for return_data_length in [0, 10, 32, 100] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_blake2f_invalid() {
+        let cases = [
+            // The final block indicator is neither 0 nor 1.
+            PrecompileCallArgs::with_input(input(12, 2)),
+            // The input doesn't have 213 bytes.
+            PrecompileCallArgs {
+                call_data_length: BLAKE2F_INPUT_LENGTH as u64 - 1,
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                call_data_length: BLAKE2F_INPUT_LENGTH as u64 + 1,
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                call_data_length: 0,
+                ..Default::default()
+            },
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_blake2f_insufficient_gas() {
+        test_ok(PrecompileCallArgs {
+            gas: 11,
+            ..Default::default()
+        });
+    }
+}
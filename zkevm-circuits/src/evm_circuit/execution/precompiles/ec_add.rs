@@ -0,0 +1,525 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{EcAddAuxData, PrecompileAuxData, PrecompileCalls, BN254_P},
+};
+use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget},
+            rlc, CachedRegion, Cell, Word,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Gadget for the ecAdd precompile. The addition of the points is verified
+/// through a lookup to the ECC table, which is only done for coordinates in
+/// the base field and a call with enough gas.  The call fails if a point is
+/// neither on the curve nor at infinity.
+#[derive(Clone, Debug)]
+pub(crate) struct EcAddGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    // The 128 bytes of input, i.e. the call data right padded with zeroes.
+    p_x: Word<F>,
+    p_y: Word<F>,
+    q_x: Word<F>,
+    q_y: Word<F>,
+    r_x: Word<F>,
+    r_y: Word<F>,
+    is_valid: Cell<F>,
+
+    // Whether each input coordinate is less than the base field modulus.
+    coordinates_lt_p: [LtWordGadget<F>; 4],
+
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    input_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_length_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcAddGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256Add;
+
+    const NAME: &'static str = "BN256_ADD";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is ecAdd",
+            callee_address.expr(),
+            PrecompileCalls::Bn128Add.address().expr(),
+        );
+
+        let [p_x, p_y, q_x, q_y, r_x, r_y] = [(); 6].map(|_| cb.query_word_rlc());
+        let is_valid = cb.query_bool();
+
+        let coordinates_lt_p = [&p_x, &p_y, &q_x, &q_y]
+            .map(|coordinate| LtWordGadget::construct_with_constant(cb, coordinate, *BN254_P));
+        let is_in_field = and::expr(coordinates_lt_p.iter().map(|lt| lt.expr()));
+
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            GasCost::PRECOMPILE_BN256ADD.expr(),
+        );
+        let has_enough_gas = not::expr(insufficient_gas.expr());
+
+        // The points are given to the ECC circuit if the call has enough gas
+        // and their coordinates are in the base field, and are invalid
+        // otherwise.
+        cb.condition(
+            and::expr([has_enough_gas.expr(), is_in_field.expr()]),
+            |cb| {
+                cb.ecc_table_lookup(
+                    PrecompileCalls::Bn128Add.address().expr(),
+                    p_x.expr(),
+                    p_y.expr(),
+                    q_x.expr(),
+                    q_y.expr(),
+                    r_x.expr(),
+                    r_y.expr(),
+                    is_valid.expr(),
+                );
+            },
+        );
+        cb.condition(
+            not::expr(and::expr([has_enough_gas.expr(), is_in_field])),
+            |cb| {
+                cb.require_zero("points not given to the ECC circuit", is_valid.expr());
+            },
+        );
+        cb.require_equal(
+            "is_success == gas_left >= PRECOMPILE_BN256ADD and the points are valid",
+            is_success.expr(),
+            and::expr([has_enough_gas.expr(), is_valid.expr()]),
+        );
+
+        // The output is the sum of the points.
+        let output_length = 64.expr() * is_success.expr();
+        let input_copy_size = MinMaxGadget::construct(cb, call_data_length.expr(), 128.expr());
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), 64.expr());
+        let return_data_length_is_zero = IsZeroGadget::construct(cb, return_data_length.expr());
+
+        cb.condition(has_enough_gas, |cb| {
+            // Copy the input from the caller's memory, right padded to 128 bytes.
+            let input_bytes_rlc = rlc::expr(
+                &q_y.cells
+                    .iter()
+                    .chain(q_x.cells.iter())
+                    .chain(p_y.cells.iter())
+                    .chain(p_x.cells.iter())
+                    .map(|cell| cell.expr())
+                    .collect::<Vec<_>>(),
+                cb.challenges().keccak_input(),
+            );
+            cb.copy_table_lookup(
+                caller_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                call_data_offset.expr(),
+                call_data_offset.expr() + call_data_length.expr(),
+                0.expr(),
+                128.expr(),
+                input_bytes_rlc,
+                input_copy_size.min(),
+            );
+        });
+        cb.condition(is_success.expr(), |cb| {
+            // Write the output to the callee's memory.
+            let output_bytes_rlc = rlc::expr(
+                &r_y.cells
+                    .iter()
+                    .chain(r_x.cells.iter())
+                    .map(|cell| cell.expr())
+                    .collect::<Vec<_>>(),
+                cb.challenges().keccak_input(),
+            );
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                64.expr(),
+                0.expr(),
+                64.expr(),
+                output_bytes_rlc,
+                64.expr(),
+            );
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_BN256ADD.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            output_length,
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            p_x,
+            p_y,
+            q_x,
+            q_y,
+            r_x,
+            r_y,
+            is_valid,
+            coordinates_lt_p,
+            insufficient_gas,
+            input_copy_size,
+            return_data_copy_size,
+            return_data_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::EcAdd(aux_data)) = &step.aux_data else {
+            unreachable!("ecAdd step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let EcAddAuxData { p, q, r } = aux_data;
+        for (word, value) in [
+            (&self.p_x, p[0]),
+            (&self.p_y, p[1]),
+            (&self.q_x, q[0]),
+            (&self.q_y, q[1]),
+            (&self.r_x, r[0]),
+            (&self.r_y, r[1]),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+        for (lt, coordinate) in self.coordinates_lt_p.iter().zip(p.iter().chain(q.iter())) {
+            lt.assign(region, offset, *coordinate, *BN254_P)?;
+        }
+
+        let has_enough_gas = step.gas_left.0 >= GasCost::PRECOMPILE_BN256ADD.0;
+        let is_valid = has_enough_gas && aux_data.is_in_field() && aux_data.is_valid();
+        self.is_valid
+            .assign(region, offset, Value::known(F::from(is_valid as u64)))?;
+
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(GasCost::PRECOMPILE_BN256ADD.0),
+        )?;
+        self.input_copy_size.assign(
+            region,
+            offset,
+            call_data_length.to_scalar().unwrap(),
+            F::from(128),
+        )?;
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(64),
+        )?;
+        self.return_data_length_is_zero.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one, which
+        // comes after the read of the input if the call had enough gas.
+        let input_reads = if has_enough_gas {
+            call_data_length.as_usize().min(128)
+        } else {
+            0
+        };
+        let rw_offset = if call.is_success {
+            7 + input_reads + 64 + 2 * return_data_length.as_usize().min(64)
+        } else {
+            7 + input_reads + 1
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::{PrecompileCalls, BN254_P};
+    use eth_types::{bytecode, evm_types::OpcodeId, word, Bytecode, Word};
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    /// Generator of the BN254 G1 group.
+    const G: [u64; 2] = [1, 2];
+
+    /// Double of the generator.
+    fn double_g() -> [Word; 2] {
+        [
+            word!("0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"),
+            word!("0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"),
+        ]
+    }
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        p: [Word; 2],
+        q: [Word; 2],
+        call_data_length: u64,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            Self {
+                p: G.map(Word::from),
+                q: double_g(),
+                call_data_length: 128,
+                return_data_length: 64,
+                gas: 1000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = bytecode! {
+                PUSH32(self.p[0])
+                PUSH1(0x00)
+                MSTORE
+                PUSH32(self.p[1])
+                PUSH1(0x20)
+                MSTORE
+                PUSH32(self.q[0])
+                PUSH1(0x40)
+                MSTORE
+                PUSH32(self.q[1])
+                PUSH1(0x60)
+                MSTORE
+                PUSH1(self.return_data_length)
+                PUSH1(0x80)
+                PUSH1(self.call_data_length)
+                PUSH1(0x00)
+            };
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Bn128Add.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH1(0x80)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_ec_add_valid() {
+        test_ok(PrecompileCallArgs::default());
+    }
+
+    #[test]
+    fn precompile_ec_add_doubling() {
+        test_ok(PrecompileCallArgs {
+            q: G.map(Word::from),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn precompile_ec_add_infinity() {
+        let cases = [
+            PrecompileCallArgs {
+                p: [Word::zero(); 2],
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                q: [Word::zero(); 2],
+                ..Default::default()
+            },
+            // G + (-G) = 0
+            PrecompileCallArgs {
+                q: [Word::one(), *BN254_P - 2],
+                ..Default::default()
+            },
+            // The truncated input is right padded with zeroes.
+            PrecompileCallArgs {
+                call_data_length: 64,
+                ..Default::default()
+            },
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_ec_add_return_data_length() {
+        for return_data_length in [0, 10, 96] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ec_add_invalid() {
+        let cases = [
+            // Not on the curve
+            PrecompileCallArgs {
+                q: [Word::one(), Word::from(3)],
+                ..Default::default()
+            },
+            // Coordinate not in the base field
+            PrecompileCallArgs {
+                p: [Word::one(), *BN254_P + 2],
+                ..Default::default()
+            },
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_ec_add_insufficient_gas() {
+        test_ok(PrecompileCallArgs {
+            gas: 149,
+            ..Default::default()
+        });
+    }
+}
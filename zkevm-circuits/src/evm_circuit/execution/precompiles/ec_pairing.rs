@@ -0,0 +1,544 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{ec_pairing_gas_cost, PrecompileAuxData, PrecompileCalls, EC_PAIRING_PAIR_LENGTH},
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget, MinMaxGadget, RangeCheckGadget},
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Length of the output of a successful call, i.e. 0 or 1 as a word.
+const OUTPUT_LENGTH: u64 = 32;
+
+/// Gadget for the ecPairing precompile. An input made of whole pairs is
+/// copied from the caller's memory into an RLC, whose validity and pairing
+/// check are verified through a lookup to the pairing table. The call fails
+/// if it runs out of gas, if the input length isn't a multiple of 192 bytes
+/// or if a point is invalid.
+#[derive(Clone, Debug)]
+pub(crate) struct EcPairingGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    // call_data_length == EC_PAIRING_PAIR_LENGTH * num_pairs + remainder
+    num_pairs: Cell<F>,
+    num_pairs_range: RangeCheckGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    remainder: Cell<F>,
+    remainder_lt_pair_length: LtGadget<F, 1>,
+    remainder_is_zero: IsZeroGadget<F>,
+
+    input_rlc: Cell<F>,
+    is_valid: Cell<F>,
+    output: Cell<F>,
+
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    call_data_length_is_zero: IsZeroGadget<F>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_length_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256Pairing;
+
+    const NAME: &'static str = "BN256_PAIRING";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is ecPairing",
+            callee_address.expr(),
+            PrecompileCalls::Bn128Pairing.address().expr(),
+        );
+
+        // Split the input into whole pairs and a remainder.
+        let num_pairs = cb.query_cell();
+        let num_pairs_range = RangeCheckGadget::construct(cb, num_pairs.expr());
+        let remainder = cb.query_byte();
+        let remainder_lt_pair_length =
+            LtGadget::construct(cb, remainder.expr(), (EC_PAIRING_PAIR_LENGTH as u64).expr());
+        cb.require_equal(
+            "remainder < EC_PAIRING_PAIR_LENGTH",
+            remainder_lt_pair_length.expr(),
+            1.expr(),
+        );
+        cb.require_equal(
+            "call_data_length == EC_PAIRING_PAIR_LENGTH * num_pairs + remainder",
+            call_data_length.expr(),
+            (EC_PAIRING_PAIR_LENGTH as u64).expr() * num_pairs.expr() + remainder.expr(),
+        );
+        let remainder_is_zero = IsZeroGadget::construct(cb, remainder.expr());
+
+        // Only the whole pairs are charged, even if the length is invalid.
+        let required_gas = GasCost::PRECOMPILE_BN256PAIRING.expr()
+            + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr() * num_pairs.expr();
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), required_gas.clone());
+        let reads_input = and::expr([not::expr(insufficient_gas.expr()), remainder_is_zero.expr()]);
+
+        let input_rlc = cb.query_cell_phase2();
+        let is_valid = cb.query_bool();
+        let output = cb.query_bool();
+
+        let call_data_length_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
+        cb.condition(reads_input.expr(), |cb| {
+            cb.condition(not::expr(call_data_length_is_zero.expr()), |cb| {
+                // Copy the input from the caller's memory.
+                cb.copy_table_lookup(
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    call_data_offset.expr(),
+                    call_data_offset.expr() + call_data_length.expr(),
+                    0.expr(),
+                    call_data_length.expr(),
+                    input_rlc.expr(),
+                    call_data_length.expr(),
+                );
+                // The validity of the points and the pairing check are
+                // verified by the pairing table.
+                cb.pairing_table_lookup(
+                    input_rlc.expr(),
+                    call_data_length.expr(),
+                    is_valid.expr(),
+                    output.expr(),
+                );
+            });
+            cb.condition(call_data_length_is_zero.expr(), |cb| {
+                // The empty product of pairings is one.
+                cb.require_equal("empty input is valid", is_valid.expr(), 1.expr());
+                cb.require_equal("output == 1 for empty input", output.expr(), 1.expr());
+            });
+        });
+        cb.condition(not::expr(reads_input.expr()), |cb| {
+            cb.require_zero("input not given to the pairing circuit", is_valid.expr());
+            cb.require_zero("no output without input", output.expr());
+        });
+        cb.require_equal(
+            "is_success == gas_left >= required_gas, whole pairs and valid points",
+            is_success.expr(),
+            and::expr([reads_input.expr(), is_valid.expr()]),
+        );
+
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), OUTPUT_LENGTH.expr());
+        let return_data_length_is_zero = IsZeroGadget::construct(cb, return_data_length.expr());
+
+        cb.condition(is_success.expr(), |cb| {
+            // Write the output to the callee's memory, where the RLC of the
+            // big-endian word 0 or 1 is the output itself.
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                output.expr(),
+                OUTPUT_LENGTH.expr(),
+            );
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            required_gas,
+            cb.curr.state.gas_left.expr(),
+        );
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            OUTPUT_LENGTH.expr() * is_success.expr(),
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            num_pairs,
+            num_pairs_range,
+            remainder,
+            remainder_lt_pair_length,
+            remainder_is_zero,
+            input_rlc,
+            is_valid,
+            output,
+            insufficient_gas,
+            call_data_length_is_zero,
+            return_data_copy_size,
+            return_data_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::EcPairing(aux_data)) = &step.aux_data else {
+            unreachable!("ecPairing step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let call_data_length = call_data_length.as_u64();
+        let pair_length = EC_PAIRING_PAIR_LENGTH as u64;
+        let (num_pairs, remainder) = (
+            call_data_length / pair_length,
+            call_data_length % pair_length,
+        );
+        self.num_pairs
+            .assign(region, offset, Value::known(F::from(num_pairs)))?;
+        self.num_pairs_range
+            .assign(region, offset, F::from(num_pairs))?;
+        self.remainder
+            .assign(region, offset, Value::known(F::from(remainder)))?;
+        self.remainder_lt_pair_length.assign(
+            region,
+            offset,
+            F::from(remainder),
+            F::from(pair_length),
+        )?;
+        self.remainder_is_zero
+            .assign(region, offset, F::from(remainder))?;
+
+        let required_gas = ec_pairing_gas_cost(call_data_length);
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(required_gas),
+        )?;
+
+        let reads_input = step.gas_left.0 >= required_gas && remainder == 0;
+        let input_rlc = region
+            .challenges()
+            .keccak_input()
+            .map(|randomness| rlc::value(aux_data.input_bytes().iter().rev(), randomness));
+        self.input_rlc.assign(region, offset, input_rlc)?;
+        let is_valid = reads_input && aux_data.is_valid();
+        self.is_valid
+            .assign(region, offset, Value::known(F::from(is_valid as u64)))?;
+        self.output.assign(
+            region,
+            offset,
+            Value::known(F::from((is_valid && aux_data.output) as u64)),
+        )?;
+
+        self.call_data_length_is_zero
+            .assign(region, offset, F::from(call_data_length))?;
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(OUTPUT_LENGTH),
+        )?;
+        self.return_data_length_is_zero.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one, which
+        // comes after the read of an input made of whole pairs if the call
+        // had enough gas.
+        let input_reads = if reads_input {
+            call_data_length as usize
+        } else {
+            0
+        };
+        let rw_offset = if call.is_success {
+            7 + input_reads
+                + OUTPUT_LENGTH as usize
+                + 2 * return_data_length.as_usize().min(OUTPUT_LENGTH as usize)
+        } else {
+            7 + input_reads + 1
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::{PrecompileCalls, BN254_P};
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use halo2_proofs::halo2curves::{
+        bn256::{Fq, Fr, G1Affine, G2Affine, G1, G2},
+        ff::{Field as _, PrimeField},
+        group::{prime::PrimeCurveAffine, Curve, Group},
+    };
+    use mock::TestContext;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    /// Offset in memory of the returned data, after the longest input.
+    const RETURN_DATA_OFFSET: u64 = 0x200;
+
+    fn to_word(c: Fq) -> Word {
+        Word::from_little_endian(&c.to_repr())
+    }
+
+    /// Encoding of a pair of points in the input.
+    fn pair(p: G1Affine, q: G2Affine) -> Vec<Word> {
+        vec![p.x, p.y, q.x.c1, q.x.c0, q.y.c1, q.y.c0]
+            .into_iter()
+            .map(to_word)
+            .collect()
+    }
+
+    /// The pairs `(aP, Q)` and `(-P, aQ)`, whose pairings multiply to one.
+    fn canceling_pairs() -> Vec<Word> {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let a = Fr::random(&mut rng);
+        let p = G1::random(&mut rng);
+        let q = G2::random(&mut rng);
+        [
+            pair((p * a).to_affine(), q.to_affine()),
+            pair((-p).to_affine(), (q * a).to_affine()),
+        ]
+        .concat()
+    }
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        input: Vec<Word>,
+        call_data_length: u64,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            let input = canceling_pairs();
+            Self {
+                call_data_length: 32 * input.len() as u64,
+                input,
+                return_data_length: 32,
+                gas: 200_000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        fn with_input(input: Vec<Word>) -> Self {
+            Self {
+                call_data_length: 32 * input.len() as u64,
+                input,
+                ..Default::default()
+            }
+        }
+
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = Bytecode::default();
+            for (i, word) in self.input.iter().enumerate() {
+                code.append(&bytecode! {
+                    PUSH32(*word)
+                    PUSH2(32 * i)
+                    MSTORE
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(self.return_data_length)
+                PUSH2(RETURN_DATA_OFFSET)
+                PUSH2(self.call_data_length)
+                PUSH1(0x00)
+            });
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Bn128Pairing.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH2(RETURN_DATA_OFFSET)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing_valid() {
+        let cases = [
+            // e(aP, Q) * e(-P, aQ) == 1
+            PrecompileCallArgs::default(),
+            // e(G1, G2) != 1, which is still a successful call.
+            PrecompileCallArgs::with_input(pair(G1Affine::generator(), G2Affine::generator())),
+            // The pairing with a point at infinity is one.
+            PrecompileCallArgs::with_input(pair(G1Affine::identity(), G2Affine::generator())),
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing_empty_input() {
+        test_ok(PrecompileCallArgs::with_input(vec![]));
+    }
+
+    #[test]
+    fn precompile_ec_pairing_return_data_length() {
+        for return_data_length in [0, 10, 64] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing_invalid() {
+        let mut not_on_curve = pair(G1Affine::generator(), G2Affine::generator());
+        not_on_curve[1] = Word::from(3);
+        let mut not_in_field = pair(G1Affine::generator(), G2Affine::generator());
+        not_in_field[3] = *BN254_P + 1;
+        let cases = [
+            PrecompileCallArgs::with_input(not_on_curve),
+            PrecompileCallArgs::with_input(not_in_field),
+            // The input isn't made of whole pairs.
+            PrecompileCallArgs {
+                call_data_length: 100,
+                ..Default::default()
+            },
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing_insufficient_gas() {
+        test_ok(PrecompileCallArgs {
+            gas: 112_999,
+            ..Default::default()
+        });
+    }
+}
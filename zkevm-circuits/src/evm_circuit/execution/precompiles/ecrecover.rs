@@ -0,0 +1,590 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{EcrecoverAuxData, PrecompileAuxData, PrecompileCalls, SECP256K1_N},
+};
+use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
+use gadgets::util::{and, not, select, sum, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget, LtWordGadget, MinMaxGadget},
+            rlc, CachedRegion, Cell, Word,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Gadget for the ecRecover precompile. The recovery of the address from the
+/// message hash and the signature is verified through a lookup to the sig
+/// table, which is only done for well-formed inputs, i.e. with `v` in {27, 28}
+/// and both `r` and `s` in `[1, n)`.  Malformed inputs and signatures from
+/// which no public key can be recovered return no data.
+#[derive(Clone, Debug)]
+pub(crate) struct EcrecoverGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    // The 128 bytes of input, i.e. the call data right padded with zeroes.
+    msg_hash: Word<F>,
+    sig_v: Word<F>,
+    sig_r: Word<F>,
+    sig_s: Word<F>,
+    recovered_addr: [Cell<F>; N_BYTES_ACCOUNT_ADDRESS],
+    is_recovered: Cell<F>,
+
+    sig_v_hi_is_zero: IsZeroGadget<F>,
+    sig_v_is_27: IsEqualGadget<F>,
+    sig_v_is_28: IsEqualGadget<F>,
+    sig_r_is_zero: IsZeroGadget<F>,
+    sig_r_lt_n: LtWordGadget<F>,
+    sig_s_is_zero: IsZeroGadget<F>,
+    sig_s_lt_n: LtWordGadget<F>,
+    is_well_formed: Cell<F>,
+
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    input_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_length_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileEcRecover;
+
+    const NAME: &'static str = "EC_RECOVER";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is ecRecover",
+            callee_address.expr(),
+            PrecompileCalls::ECRecover.address().expr(),
+        );
+
+        let msg_hash = cb.query_word_rlc();
+        let sig_v = cb.query_word_rlc();
+        let sig_r = cb.query_word_rlc();
+        let sig_s = cb.query_word_rlc();
+        let recovered_addr = cb.query_bytes();
+        let is_recovered = cb.query_bool();
+
+        // The input is well-formed if v is 27 or 28 and r, s are in [1, n).
+        let sig_v_hi_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_v.cells[1..]));
+        let sig_v_is_27 = IsEqualGadget::construct(cb, sig_v.cells[0].expr(), 27.expr());
+        let sig_v_is_28 = IsEqualGadget::construct(cb, sig_v.cells[0].expr(), 28.expr());
+        let sig_r_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_r.cells));
+        let sig_r_lt_n = LtWordGadget::construct_with_constant(cb, &sig_r, *SECP256K1_N);
+        let sig_s_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_s.cells));
+        let sig_s_lt_n = LtWordGadget::construct_with_constant(cb, &sig_s, *SECP256K1_N);
+        let is_well_formed = cb.query_bool();
+        cb.require_equal(
+            "is_well_formed == (v in {27, 28}) and (0 < r < n) and (0 < s < n)",
+            is_well_formed.expr(),
+            and::expr([
+                sig_v_hi_is_zero.expr(),
+                sig_v_is_27.expr() + sig_v_is_28.expr(),
+                not::expr(sig_r_is_zero.expr()),
+                sig_r_lt_n.expr(),
+                not::expr(sig_s_is_zero.expr()),
+                sig_s_lt_n.expr(),
+            ]),
+        );
+
+        // The call only fails when it runs out of gas.
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            GasCost::PRECOMPILE_EC_RECOVER_BASE.expr(),
+        );
+        cb.require_equal(
+            "is_success == gas_left >= PRECOMPILE_EC_RECOVER_BASE",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+
+        // The recovery is verified by the sig table for well-formed inputs of
+        // successful calls, and fails otherwise.
+        cb.condition(
+            and::expr([is_success.expr(), is_well_formed.expr()]),
+            |cb| {
+                cb.sig_table_lookup(
+                    msg_hash.expr(),
+                    sig_v.cells[0].expr(),
+                    sig_r.expr(),
+                    sig_s.expr(),
+                    from_bytes::expr(&recovered_addr),
+                    is_recovered.expr(),
+                );
+            },
+        );
+        cb.condition(
+            not::expr(and::expr([is_success.expr(), is_well_formed.expr()])),
+            |cb| {
+                cb.require_zero("no recovery attempted", is_recovered.expr());
+            },
+        );
+        cb.condition(not::expr(is_recovered.expr()), |cb| {
+            cb.require_zero(
+                "recovered address is zero if the recovery fails",
+                sum::expr(&recovered_addr),
+            );
+        });
+
+        // The output is the recovered address left padded to 32 bytes.
+        let output_length = 32.expr() * is_recovered.expr();
+        let input_copy_size = MinMaxGadget::construct(cb, call_data_length.expr(), 128.expr());
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), 32.expr());
+        let return_data_length_is_zero = IsZeroGadget::construct(cb, return_data_length.expr());
+
+        cb.condition(is_success.expr(), |cb| {
+            // Copy the input from the caller's memory, right padded to 128 bytes.
+            let input_bytes_rlc = rlc::expr(
+                &sig_s
+                    .cells
+                    .iter()
+                    .chain(sig_r.cells.iter())
+                    .chain(sig_v.cells.iter())
+                    .chain(msg_hash.cells.iter())
+                    .map(|cell| cell.expr())
+                    .collect::<Vec<_>>(),
+                cb.challenges().keccak_input(),
+            );
+            cb.copy_table_lookup(
+                caller_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                call_data_offset.expr(),
+                call_data_offset.expr() + call_data_length.expr(),
+                0.expr(),
+                128.expr(),
+                input_bytes_rlc,
+                input_copy_size.min(),
+            );
+        });
+        cb.condition(and::expr([is_success.expr(), is_recovered.expr()]), |cb| {
+            // Write the output to the callee's memory.
+            let output_bytes_rlc = rlc::expr(
+                &recovered_addr
+                    .iter()
+                    .map(|cell| cell.expr())
+                    .collect::<Vec<_>>(),
+                cb.challenges().keccak_input(),
+            );
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                32.expr(),
+                0.expr(),
+                32.expr(),
+                output_bytes_rlc,
+                32.expr(),
+            );
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                is_recovered.expr(),
+                not::expr(return_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_EC_RECOVER_BASE.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            output_length,
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            msg_hash,
+            sig_v,
+            sig_r,
+            sig_s,
+            recovered_addr,
+            is_recovered,
+            sig_v_hi_is_zero,
+            sig_v_is_27,
+            sig_v_is_28,
+            sig_r_is_zero,
+            sig_r_lt_n,
+            sig_s_is_zero,
+            sig_s_lt_n,
+            is_well_formed,
+            insufficient_gas,
+            input_copy_size,
+            return_data_copy_size,
+            return_data_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::Ecrecover(aux_data)) = &step.aux_data else {
+            unreachable!("ecRecover step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let EcrecoverAuxData {
+            msg_hash,
+            sig_v,
+            sig_r,
+            sig_s,
+            recovered_addr,
+        } = aux_data;
+        for (word, value) in [
+            (&self.msg_hash, msg_hash),
+            (&self.sig_v, sig_v),
+            (&self.sig_r, sig_r),
+            (&self.sig_s, sig_s),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+        for (cell, byte) in self
+            .recovered_addr
+            .iter()
+            .zip(recovered_addr.as_bytes().iter().rev())
+        {
+            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+        }
+        let is_recovered = !recovered_addr.is_zero();
+        self.is_recovered
+            .assign(region, offset, Value::known(F::from(is_recovered as u64)))?;
+
+        self.sig_v_hi_is_zero
+            .assign(region, offset, sum::value(&sig_v.to_le_bytes()[1..]))?;
+        self.sig_v_is_27.assign(
+            region,
+            offset,
+            F::from(sig_v.to_le_bytes()[0] as u64),
+            F::from(27),
+        )?;
+        self.sig_v_is_28.assign(
+            region,
+            offset,
+            F::from(sig_v.to_le_bytes()[0] as u64),
+            F::from(28),
+        )?;
+        self.sig_r_is_zero
+            .assign(region, offset, sum::value(&sig_r.to_le_bytes()))?;
+        self.sig_r_lt_n
+            .assign(region, offset, *sig_r, *SECP256K1_N)?;
+        self.sig_s_is_zero
+            .assign(region, offset, sum::value(&sig_s.to_le_bytes()))?;
+        self.sig_s_lt_n
+            .assign(region, offset, *sig_s, *SECP256K1_N)?;
+        self.is_well_formed.assign(
+            region,
+            offset,
+            Value::known(F::from(aux_data.is_well_formed() as u64)),
+        )?;
+
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(GasCost::PRECOMPILE_EC_RECOVER_BASE.0),
+        )?;
+        self.input_copy_size.assign(
+            region,
+            offset,
+            call_data_length.to_scalar().unwrap(),
+            F::from(128),
+        )?;
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(32),
+        )?;
+        self.return_data_length_is_zero.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one.
+        let rw_offset = if call.is_success {
+            let output_length = if is_recovered { 32 } else { 0 };
+            7 + call_data_length.as_usize().min(128)
+                + output_length
+                + 2 * return_data_length.as_usize().min(output_length)
+        } else {
+            8
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::{PrecompileCalls, SECP256K1_N};
+    use eth_types::{bytecode, evm_types::OpcodeId, word, Bytecode, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        msg_hash: Word,
+        sig_v: Word,
+        sig_r: Word,
+        sig_s: Word,
+        call_data_length: u64,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            // A valid signature from the go-ethereum precompile tests.
+            Self {
+                msg_hash: word!(
+                    "0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"
+                ),
+                sig_v: Word::from(28),
+                sig_r: word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"),
+                sig_s: word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"),
+                call_data_length: 128,
+                return_data_length: 32,
+                gas: 100000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = bytecode! {
+                PUSH32(self.msg_hash)
+                PUSH1(0x00)
+                MSTORE
+                PUSH32(self.sig_v)
+                PUSH1(0x20)
+                MSTORE
+                PUSH32(self.sig_r)
+                PUSH1(0x40)
+                MSTORE
+                PUSH32(self.sig_s)
+                PUSH1(0x60)
+                MSTORE
+                PUSH1(self.return_data_length)
+                PUSH1(0x80)
+                PUSH1(self.call_data_length)
+                PUSH1(0x00)
+            };
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::ECRecover.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH1(0x80)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_ecrecover_valid() {
+        test_ok(PrecompileCallArgs::default());
+    }
+
+    #[test]
+    fn precompile_ecrecover_return_data_length() {
+        for return_data_length in [0, 10, 64] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ecrecover_malformed() {
+        let cases = [
+            // v not in {27, 28}
+            PrecompileCallArgs {
+                sig_v: Word::from(29),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                sig_v: Word::from(28) + (Word::one() << 128),
+                ..Default::default()
+            },
+            // r and s not in [1, n)
+            PrecompileCallArgs {
+                sig_r: *SECP256K1_N,
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                sig_r: Word::zero(),
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                sig_s: *SECP256K1_N + 1,
+                ..Default::default()
+            },
+            PrecompileCallArgs {
+                sig_s: Word::zero(),
+                ..Default::default()
+            },
+            // The truncated input is right padded with zeroes, so s = 0.
+            PrecompileCallArgs {
+                call_data_length: 97,
+                ..Default::default()
+            },
+        ];
+        for args in cases {
+            test_ok(args);
+        }
+    }
+
+    #[test]
+    fn precompile_ecrecover_unrecoverable() {
+        // There is no point on the curve with x = 5.
+        test_ok(PrecompileCallArgs {
+            sig_r: Word::from(5),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn precompile_ecrecover_insufficient_gas() {
+        for (gas, call_data_length) in [2999, 0].into_iter().cartesian_product([128, 0]) {
+            test_ok(PrecompileCallArgs {
+                gas,
+                call_data_length,
+                ..Default::default()
+            });
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use bus_mapping::{circuit_input_builder::CopyDataType, precompile::PrecompileCalls};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget, MinMaxGadget},
+            memory_gadget::MemoryWordSizeGadget,
+            CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Gadget for the identity precompile. The input is copied from the caller's
+/// memory to the callee's memory, from where it is returned, so the copy
+/// circuit proves the whole call.
+#[derive(Clone, Debug)]
+pub(crate) struct IdentityGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    input_word_size: MemoryWordSizeGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    call_data_length_is_zero: IsZeroGadget<F>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_size_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for IdentityGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileIdentity;
+
+    const NAME: &'static str = "IDENTITY";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is identity",
+            callee_address.expr(),
+            PrecompileCalls::Identity.address().expr(),
+        );
+
+        // The call only fails when it runs out of gas.
+        let input_word_size = MemoryWordSizeGadget::construct(cb, call_data_length.expr());
+        let required_gas = GasCost::PRECOMPILE_IDENTITY_BASE.expr()
+            + GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr() * input_word_size.expr();
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), required_gas.clone());
+        cb.require_equal(
+            "is_success == gas_left >= required_gas",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+
+        let call_data_length_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), call_data_length.expr());
+        let return_data_copy_size_is_zero =
+            IsZeroGadget::construct(cb, return_data_copy_size.min());
+
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(call_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the input from the caller's memory to the callee's.
+                cb.copy_table_lookup(
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    call_data_offset.expr(),
+                    call_data_offset.expr() + call_data_length.expr(),
+                    0.expr(),
+                    call_data_length.expr(),
+                    0.expr(),
+                    2.expr() * call_data_length.expr(), // reads + writes
+                );
+            },
+        );
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_copy_size_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            required_gas,
+            cb.curr.state.gas_left.expr(),
+        );
+        // The whole input is returned.
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            call_data_length.expr() * is_success.expr(),
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            input_word_size,
+            insufficient_gas,
+            call_data_length_is_zero,
+            return_data_copy_size,
+            return_data_copy_size_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let input_word_size =
+            self.input_word_size
+                .assign(region, offset, call_data_length.as_u64())?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(
+                GasCost::PRECOMPILE_IDENTITY_BASE.0
+                    + GasCost::PRECOMPILE_IDENTITY_PER_WORD.0 * input_word_size,
+            ),
+        )?;
+        self.call_data_length_is_zero.assign(
+            region,
+            offset,
+            call_data_length.to_scalar().unwrap(),
+        )?;
+        let return_data_copy_size = return_data_length.min(call_data_length);
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            call_data_length.to_scalar().unwrap(),
+        )?;
+        self.return_data_copy_size_is_zero.assign(
+            region,
+            offset,
+            return_data_copy_size.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one.
+        let rw_offset = if call.is_success {
+            7 + 2 * call_data_length.as_usize() + 2 * return_data_copy_size.as_usize()
+        } else {
+            8
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::PrecompileCalls;
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        input: Vec<u8>,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            Self {
+                input: (0..40).collect(),
+                return_data_length: 40,
+                gas: 100000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        /// Store the input in memory, call the precompile, check that the
+        /// returned data is copied to memory and copy it again with
+        /// RETURNDATACOPY.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = Bytecode::default();
+            for (i, chunk) in self.input.chunks(32).enumerate() {
+                let mut word = [0u8; 32];
+                word[..chunk.len()].copy_from_slice(chunk);
+                code.append(&bytecode! {
+                    PUSH32(Word::from_big_endian(&word))
+                    PUSH2(32 * i)
+                    MSTORE
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(self.return_data_length)
+                PUSH2(0x200)
+                PUSH2(self.input.len())
+                PUSH1(0x00)
+            });
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Identity.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH2(0x200)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                PUSH1(0x00)
+                PUSH2(0x300)
+                RETURNDATACOPY
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_identity_simple() {
+        test_ok(PrecompileCallArgs::default());
+    }
+
+    #[test]
+    fn precompile_identity_input_length() {
+        for len in [0, 1, 31, 32, 33, 100] {
+            test_ok(PrecompileCallArgs {
+                input: (0..len).map(|i| i as u8).collect(),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_identity_return_data_length() {
+        // Return less, as much and more than the input.
+        for return_data_length in [0, 10, 40, 64] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_identity_insufficient_gas() {
+        // 15 gas for the base cost and 3 gas per word of input.
+        for (gas, len) in [14, 20, 0].into_iter().cartesian_product([0, 3, 33]) {
+            test_ok(PrecompileCallArgs {
+                input: vec![0xff; len],
+                gas,
+                ..Default::default()
+            });
+        }
+    }
+}
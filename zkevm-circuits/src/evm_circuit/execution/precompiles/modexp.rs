@@ -0,0 +1,735 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{
+        ModExpAuxData, PrecompileAuxData, PrecompileCalls, MODEXP_HEADER_LENGTH, MODEXP_SIZE_LIMIT,
+    },
+};
+use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
+use gadgets::util::{and, not, select, sum, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_WORD},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{ByteSizeGadget, IsZeroGadget, LtGadget, MinMaxGadget, RangeCheckGadget},
+            rlc, CachedRegion, Cell, Word,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Number of bytes of a word in the multiplication complexity of MODEXP.
+const N_BYTES_COMPLEXITY_WORD: u64 = 8;
+
+/// Gadget for the MODEXP precompile, for a base, exponent and modulus of at
+/// most `MODEXP_SIZE_LIMIT` bytes each. The input is copied from the caller's
+/// memory into an RLC of the header and the operands, whose lengths are given
+/// by the header, and the output written to the callee's memory is verified
+/// through a lookup to the modexp table. The gas cost follows EIP-2565.
+#[derive(Clone, Debug)]
+pub(crate) struct ModExpGadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    // One-hot lengths of the base, exponent and modulus.
+    input_len_flags: [[Cell<F>; MODEXP_SIZE_LIMIT + 1]; 3],
+    base: Word<F>,
+    exponent: Word<F>,
+    modulus: Word<F>,
+    output_rlc: Cell<F>,
+    input_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+
+    // The multiplication complexity is the square of the number of words of
+    // the longest of the base and the modulus, and the iteration count is the
+    // index of the highest bit of the exponent.
+    num_words: MinMaxGadget<F, 1>,
+    exponent_byte_size: ByteSizeGadget<F>,
+    exponent_msb_bits: [Cell<F>; 8],
+    exponent_highest_bit: [Cell<F>; 8],
+    iteration_count_is_zero: IsZeroGadget<F>,
+    gas_quotient: Cell<F>,
+    gas_remainder: Cell<F>,
+    gas_quotient_range: RangeCheckGadget<F, 2>,
+    required_gas: MinMaxGadget<F, 2>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_size_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBigModExp;
+
+    const NAME: &'static str = "MODEXP";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is modexp",
+            callee_address.expr(),
+            PrecompileCalls::Modexp.address().expr(),
+        );
+
+        // The lengths are one-hot encoded, which also checks that they are
+        // supported.
+        let input_len_flags =
+            [(); 3].map(|()| [(); MODEXP_SIZE_LIMIT + 1].map(|()| cb.query_bool()));
+        for flags in input_len_flags.iter() {
+            cb.require_equal("exactly one length flag is 1", sum::expr(flags), 1.expr());
+        }
+        let len_expr = |flags: &[Cell<F>; MODEXP_SIZE_LIMIT + 1]| {
+            sum::expr(
+                flags
+                    .iter()
+                    .enumerate()
+                    .map(|(len, flag)| len.expr() * flag.expr()),
+            )
+        };
+        let [base_len, exponent_len, modulus_len] =
+            [0, 1, 2].map(|i| len_expr(&input_len_flags[i]));
+
+        let [base, exponent, modulus] = [(); 3].map(|()| cb.query_word_rlc());
+        for (word, flags) in [&base, &exponent, &modulus]
+            .into_iter()
+            .zip(input_len_flags.iter())
+        {
+            for (idx, byte) in word.cells.iter().enumerate() {
+                cb.require_zero(
+                    "bytes above the length are zero",
+                    sum::expr(&flags[..=idx]) * byte.expr(),
+                );
+            }
+        }
+        let output_rlc = cb.query_cell_phase2();
+
+        // The input is the header, i.e. the three lengths as 32-byte words,
+        // followed by the big-endian operands, so that each operand is
+        // accumulated as the RLC of its little-endian bytes.
+        let keccak_input = cb.challenges().keccak_input();
+        let powers_of_randomness = cb
+            .challenges()
+            .keccak_powers_of_randomness::<MODEXP_SIZE_LIMIT>();
+        let randomness_pow_len = |flags: &[Cell<F>; MODEXP_SIZE_LIMIT + 1]| {
+            sum::expr(flags.iter().enumerate().map(|(len, flag)| {
+                if len == 0 {
+                    flag.expr()
+                } else {
+                    flag.expr() * powers_of_randomness[len - 1].clone()
+                }
+            }))
+        };
+        let word_rlc = |word: &Word<F>| {
+            rlc::expr(
+                &word
+                    .cells
+                    .iter()
+                    .map(|cell| cell.expr())
+                    .collect::<Vec<_>>(),
+                keccak_input.clone(),
+            )
+        };
+        let [base_rlc, exponent_rlc, modulus_rlc] = [&base, &exponent, &modulus].map(word_rlc);
+        let randomness_pow_32 = powers_of_randomness[N_BYTES_WORD - 1].clone();
+        let header_rlc = base_len.clone() * randomness_pow_32.clone() * randomness_pow_32.clone()
+            + exponent_len.clone() * randomness_pow_32
+            + modulus_len.clone();
+        let input_rlc = ((header_rlc * randomness_pow_len(&input_len_flags[0]) + base_rlc.clone())
+            * randomness_pow_len(&input_len_flags[1])
+            + exponent_rlc.clone())
+            * randomness_pow_len(&input_len_flags[2])
+            + modulus_rlc.clone();
+        let input_len = MODEXP_HEADER_LENGTH.expr() + base_len + exponent_len + modulus_len.clone();
+
+        // The input is read even if the call runs out of gas, since the gas
+        // cost depends on it.
+        let input_copy_size =
+            MinMaxGadget::construct(cb, call_data_length.expr(), input_len.clone());
+        cb.copy_table_lookup(
+            caller_id.expr(),
+            CopyDataType::Memory.expr(),
+            cb.curr.state.call_id.expr(),
+            CopyDataType::RlcAcc.expr(),
+            call_data_offset.expr(),
+            call_data_offset.expr() + call_data_length.expr(),
+            0.expr(),
+            input_len,
+            input_rlc,
+            input_copy_size.min(),
+        );
+
+        // The call only fails when it runs out of gas.
+        let num_words_expr = |flags: &[Cell<F>; MODEXP_SIZE_LIMIT + 1]| {
+            sum::expr(flags.iter().enumerate().map(|(len, flag)| {
+                ((len as u64 + N_BYTES_COMPLEXITY_WORD - 1) / N_BYTES_COMPLEXITY_WORD).expr()
+                    * flag.expr()
+            }))
+        };
+        let num_words = MinMaxGadget::construct(
+            cb,
+            num_words_expr(&input_len_flags[0]),
+            num_words_expr(&input_len_flags[2]),
+        );
+
+        let exponent_bytes = exponent.cells.clone().map(|cell| cell.expr());
+        let exponent_byte_size = ByteSizeGadget::construct(cb, exponent_bytes.clone());
+        let exponent_msb_bits = [(); 8].map(|()| cb.query_bool());
+        cb.require_equal(
+            "bits of the most significant non-zero byte of the exponent",
+            exponent_byte_size.most_significant_nonzero_byte(&exponent_bytes),
+            sum::expr(
+                exponent_msb_bits
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bit)| (1u64 << i).expr() * bit.expr()),
+            ),
+        );
+        let exponent_highest_bit = [(); 8].map(|()| cb.query_bool());
+        cb.require_equal(
+            "exactly one highest bit if the exponent isn't zero",
+            sum::expr(&exponent_highest_bit),
+            not::expr(exponent_byte_size.is_zero()),
+        );
+        for (i, highest_bit) in exponent_highest_bit.iter().enumerate() {
+            cb.condition(highest_bit.expr(), |cb| {
+                cb.require_equal("highest bit is 1", exponent_msb_bits[i].expr(), 1.expr());
+                cb.require_zero("higher bits are 0", sum::expr(&exponent_msb_bits[i + 1..]));
+            });
+        }
+        // The bit length minus one, or zero for a zero exponent.
+        let iteration_count = 8.expr()
+            * (exponent_byte_size.byte_size() - sum::expr(&exponent_highest_bit))
+            + sum::expr(
+                exponent_highest_bit
+                    .iter()
+                    .enumerate()
+                    .map(|(i, highest_bit)| i.expr() * highest_bit.expr()),
+            );
+        let iteration_count_is_zero = IsZeroGadget::construct(cb, iteration_count.clone());
+
+        let gas_quotient = cb.query_cell();
+        let gas_remainder = cb.query_cell();
+        cb.require_equal(
+            "complexity * max(iteration_count, 1) = quotient * 3 + remainder",
+            num_words.max() * num_words.max() * (iteration_count + iteration_count_is_zero.expr()),
+            gas_quotient.expr() * GasCost::PRECOMPILE_MODEXP_QUAD_DIVISOR.expr()
+                + gas_remainder.expr(),
+        );
+        cb.require_zero(
+            "remainder < 3",
+            gas_remainder.expr()
+                * (gas_remainder.expr() - 1.expr())
+                * (gas_remainder.expr() - 2.expr()),
+        );
+        let gas_quotient_range = RangeCheckGadget::construct(cb, gas_quotient.expr());
+        let required_gas =
+            MinMaxGadget::construct(cb, gas_quotient.expr(), GasCost::PRECOMPILE_MODEXP.expr());
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), required_gas.max());
+        cb.require_equal(
+            "is_success == gas_left >= required_gas",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+
+        // The output is the result left padded to the length of the modulus.
+        let modulus_len_is_zero = input_len_flags[2][0].expr();
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), modulus_len.clone());
+        let return_data_copy_size_is_zero =
+            IsZeroGadget::construct(cb, return_data_copy_size.min());
+
+        cb.condition(is_success.expr(), |cb| {
+            // The result of the exponentiation is verified by the modexp table.
+            cb.modexp_table_lookup(base_rlc, exponent_rlc, modulus_rlc, output_rlc.expr());
+            cb.condition(not::expr(modulus_len_is_zero), |cb| {
+                // Write the output to the callee's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    modulus_len.clone(),
+                    0.expr(),
+                    modulus_len.clone(),
+                    output_rlc.expr(),
+                    modulus_len.clone(),
+                );
+            });
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_copy_size_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            required_gas.max(),
+            cb.curr.state.gas_left.expr(),
+        );
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            modulus_len * is_success.expr(),
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            input_len_flags,
+            base,
+            exponent,
+            modulus,
+            output_rlc,
+            input_copy_size,
+            num_words,
+            exponent_byte_size,
+            exponent_msb_bits,
+            exponent_highest_bit,
+            iteration_count_is_zero,
+            gas_quotient,
+            gas_remainder,
+            gas_quotient_range,
+            required_gas,
+            insufficient_gas,
+            return_data_copy_size,
+            return_data_copy_size_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::Modexp(aux_data)) = &step.aux_data else {
+            unreachable!("modexp step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let ModExpAuxData {
+            input_lens,
+            inputs,
+            output_bytes,
+            ..
+        } = aux_data;
+        for (flags, len) in self.input_len_flags.iter().zip(input_lens.iter()) {
+            for (i, flag) in flags.iter().enumerate() {
+                flag.assign(
+                    region,
+                    offset,
+                    Value::known(F::from((eth_types::Word::from(i) == *len) as u64)),
+                )?;
+            }
+        }
+        let [base, exponent, modulus] = inputs;
+        for (word, value) in [
+            (&self.base, base),
+            (&self.exponent, exponent),
+            (&self.modulus, modulus),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+        let output_rlc = region
+            .challenges()
+            .keccak_input()
+            .map(|randomness| rlc::value(output_bytes.iter().rev(), randomness));
+        self.output_rlc.assign(region, offset, output_rlc)?;
+
+        let [base_len, exponent_len, modulus_len] = input_lens.map(|len| len.low_u64());
+        let input_len = MODEXP_HEADER_LENGTH as u64 + base_len + exponent_len + modulus_len;
+        self.input_copy_size.assign(
+            region,
+            offset,
+            call_data_length.to_scalar().unwrap(),
+            F::from(input_len),
+        )?;
+
+        let num_words = |len: u64| (len + N_BYTES_COMPLEXITY_WORD - 1) / N_BYTES_COMPLEXITY_WORD;
+        self.num_words.assign(
+            region,
+            offset,
+            F::from(num_words(base_len)),
+            F::from(num_words(modulus_len)),
+        )?;
+        self.exponent_byte_size.assign(region, offset, *exponent)?;
+        let byte_size = (exponent.bits() + 7) / 8;
+        let msb = if byte_size > 0 {
+            exponent.to_le_bytes()[byte_size - 1]
+        } else {
+            0
+        };
+        for (i, (bit, highest_bit)) in self
+            .exponent_msb_bits
+            .iter()
+            .zip(self.exponent_highest_bit.iter())
+            .enumerate()
+        {
+            bit.assign(
+                region,
+                offset,
+                Value::known(F::from(((msb >> i) & 1) as u64)),
+            )?;
+            highest_bit.assign(
+                region,
+                offset,
+                Value::known(F::from((msb >> i == 1) as u64)),
+            )?;
+        }
+        let iteration_count = exponent.bits().saturating_sub(1) as u64;
+        self.iteration_count_is_zero
+            .assign(region, offset, F::from(iteration_count))?;
+
+        let num_words = num_words(base_len).max(num_words(modulus_len));
+        let numerator = num_words * num_words * iteration_count.max(1);
+        let quotient = numerator / GasCost::PRECOMPILE_MODEXP_QUAD_DIVISOR.0;
+        let remainder = numerator % GasCost::PRECOMPILE_MODEXP_QUAD_DIVISOR.0;
+        self.gas_quotient
+            .assign(region, offset, Value::known(F::from(quotient)))?;
+        self.gas_remainder
+            .assign(region, offset, Value::known(F::from(remainder)))?;
+        self.gas_quotient_range
+            .assign(region, offset, F::from(quotient))?;
+        self.required_gas.assign(
+            region,
+            offset,
+            F::from(quotient),
+            F::from(GasCost::PRECOMPILE_MODEXP.0),
+        )?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(quotient.max(GasCost::PRECOMPILE_MODEXP.0)),
+        )?;
+
+        let (return_data_copy_size, _) = self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(modulus_len),
+        )?;
+        self.return_data_copy_size_is_zero
+            .assign(region, offset, return_data_copy_size)?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one, which both
+        // come after the read of the input.
+        let input_reads = call_data_length.as_usize().min(input_len as usize);
+        let rw_offset = if call.is_success {
+            let output_length = modulus_len as usize;
+            7 + input_reads + output_length + 2 * return_data_length.as_usize().min(output_length)
+        } else {
+            7 + input_reads + 1
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::{
+        mock::BlockData,
+        precompile::{PrecompileCalls, MODEXP_SIZE_LIMIT},
+        Error,
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Bytecode, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    /// The input of MODEXP, i.e. the lengths of the operands as 32-byte words
+    /// followed by the operands.
+    fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for operand in [base, exponent, modulus] {
+            let mut len = [0u8; 32];
+            Word::from(operand.len()).to_big_endian(&mut len);
+            input.extend_from_slice(&len);
+        }
+        for operand in [base, exponent, modulus] {
+            input.extend_from_slice(operand);
+        }
+        input
+    }
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        input: Vec<u8>,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            Self {
+                input: modexp_input(&[0x03], &[0x05], &[0x07]),
+                return_data_length: 32,
+                gas: 100000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = Bytecode::default();
+            for (i, chunk) in self.input.chunks(32).enumerate() {
+                let mut word = [0u8; 32];
+                word[..chunk.len()].copy_from_slice(chunk);
+                code.append(&bytecode! {
+                    PUSH32(Word::from_big_endian(&word))
+                    PUSH2(32 * i)
+                    MSTORE
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(self.return_data_length)
+                PUSH2(0x200)
+                PUSH2(self.input.len())
+                PUSH1(0x00)
+            });
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Modexp.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH2(0x200)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_simple() {
+        test_ok(PrecompileCallArgs::default());
+    }
+
+    #[test]
+    fn precompile_modexp_input_length() {
+        for (base_len, exponent_len, modulus_len) in [
+            (0, 0, 0),
+            (1, 1, 1),
+            (32, 32, 32),
+            (32, 1, 7),
+            (2, 0, 32),
+            (0, 3, 9),
+        ] {
+            test_ok(PrecompileCallArgs {
+                input: modexp_input(
+                    &vec![0xfe; base_len],
+                    &vec![0xdc; exponent_len],
+                    &vec![0xff; modulus_len],
+                ),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_special_operands() {
+        for (base, exponent, modulus) in [
+            // Zero modulus
+            (vec![0x02], vec![0x05], vec![0x00, 0x00]),
+            // Modulus one
+            (vec![0x02], vec![0x05], vec![0x01]),
+            // 0^0
+            (vec![0x00], vec![0x00], vec![0x0f]),
+            // Leading zeroes
+            (vec![0x00, 0x03], vec![0x00, 0x00, 0x05], vec![0x00, 0x07]),
+        ] {
+            test_ok(PrecompileCallArgs {
+                input: modexp_input(&base, &exponent, &modulus),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_short_call_data() {
+        // The call data is right padded with zeroes to the lengths of the
+        // header.
+        let input = modexp_input(&[0x03; 32], &[0x05; 32], &[0x07; 32]);
+        for len in [0, 10, 100, 150] {
+            test_ok(PrecompileCallArgs {
+                input: input[..len].to_vec(),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_return_data_length() {
+        for return_data_length in [0, 1, 64] {
+            test_ok(PrecompileCallArgs {
+                input: modexp_input(&[0x03; 4], &[0x05; 2], &[0x07; 4]),
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_insufficient_gas() {
+        // 200 gas for small operands, and 4 * 4 * 255 / 3 = 1360 gas for
+        // 32-byte operands with the highest bit of the exponent set.
+        let inputs = [
+            modexp_input(&[0x03], &[0x05], &[0x07]),
+            modexp_input(&[0xff; 32], &[0xff; 32], &[0xff; 32]),
+        ];
+        for (gas, input) in [0, 199, 200, 1359, 1360]
+            .into_iter()
+            .cartesian_product(inputs)
+        {
+            test_ok(PrecompileCallArgs {
+                input,
+                gas,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_modexp_oversize_operands() {
+        // Operands longer than `MODEXP_SIZE_LIMIT` can't be proven, so the
+        // circuit input builder rejects the call instead of producing a
+        // witness which doesn't verify.
+        let oversize = vec![0x03; MODEXP_SIZE_LIMIT + 1];
+        for (base, exponent, modulus) in [
+            (oversize.clone(), vec![0x05], vec![0x07]),
+            (vec![0x03], oversize.clone(), vec![0x07]),
+            (vec![0x03], vec![0x05], oversize),
+        ] {
+            let args = PrecompileCallArgs {
+                input: modexp_input(&base, &exponent, &modulus),
+                ..Default::default()
+            };
+            let block: GethData =
+                TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(&OpcodeId::CALL))
+                    .unwrap()
+                    .into();
+            let mut builder =
+                BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+            let result = builder.handle_block(&block.eth_block, &block.geth_traces);
+            assert!(matches!(
+                result,
+                Err(Error::UnsupportedPrecompileInput(PrecompileCalls::Modexp))
+            ));
+        }
+    }
+}
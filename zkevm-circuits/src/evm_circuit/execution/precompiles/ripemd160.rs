@@ -0,0 +1,430 @@
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{HashAuxData, PrecompileAuxData, PrecompileCalls},
+};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{and, not, select, Expr};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget, MinMaxGadget},
+            memory_gadget::MemoryWordSizeGadget,
+            rlc, CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Call, ExecStep, Transaction},
+};
+
+/// Length of the output of the precompile, i.e. the 20-byte digest left
+/// padded with zeroes.
+const OUTPUT_LENGTH: u64 = 32;
+
+/// Gadget for the RIPEMD-160 precompile. The leading zeroes of the output
+/// don't change its RLC, so the RLC of the 32 bytes written to the callee's
+/// memory is the digest RLC found in the ripemd160 table.
+#[derive(Clone, Debug)]
+pub(crate) struct Ripemd160Gadget<F> {
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    input_rlc: Cell<F>,
+    output_rlc: Cell<F>,
+
+    input_word_size: MemoryWordSizeGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    call_data_length_is_zero: IsZeroGadget<F>,
+    return_data_copy_size: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_length_is_zero: IsZeroGadget<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for Ripemd160Gadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileRipemd160;
+
+    const NAME: &'static str = "RIPEMD160";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Read the call context written by the *CALL* step.
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        cb.require_equal(
+            "callee address is ripemd160",
+            callee_address.expr(),
+            PrecompileCalls::Ripemd160.address().expr(),
+        );
+
+        let input_rlc = cb.query_cell_phase2();
+        let output_rlc = cb.query_cell_phase2();
+
+        // The call only fails when it runs out of gas.
+        let input_word_size = MemoryWordSizeGadget::construct(cb, call_data_length.expr());
+        let required_gas = GasCost::PRECOMPILE_RIPEMD160_BASE.expr()
+            + GasCost::PRECOMPILE_RIPEMD160_PER_WORD.expr() * input_word_size.expr();
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), required_gas.clone());
+        cb.require_equal(
+            "is_success == gas_left >= required_gas",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+
+        let call_data_length_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
+        let return_data_copy_size =
+            MinMaxGadget::construct(cb, return_data_length.expr(), OUTPUT_LENGTH.expr());
+        let return_data_length_is_zero = IsZeroGadget::construct(cb, return_data_length.expr());
+
+        cb.condition(is_success.expr(), |cb| {
+            cb.condition(not::expr(call_data_length_is_zero.expr()), |cb| {
+                // Copy the input from the caller's memory.
+                cb.copy_table_lookup(
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    call_data_offset.expr(),
+                    call_data_offset.expr() + call_data_length.expr(),
+                    0.expr(),
+                    call_data_length.expr(),
+                    input_rlc.expr(),
+                    call_data_length.expr(),
+                );
+            });
+            cb.condition(call_data_length_is_zero.expr(), |cb| {
+                cb.require_zero("input_rlc == 0 for empty input", input_rlc.expr());
+            });
+            // The digest of the input is verified by the ripemd160 table.
+            cb.ripemd160_table_lookup(input_rlc.expr(), call_data_length.expr(), output_rlc.expr());
+            // Write the output to the callee's memory.
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                0.expr(),
+                OUTPUT_LENGTH.expr(),
+                output_rlc.expr(),
+                OUTPUT_LENGTH.expr(),
+            );
+        });
+        cb.condition(
+            and::expr([
+                is_success.expr(),
+                not::expr(return_data_length_is_zero.expr()),
+            ]),
+            |cb| {
+                // Copy the returned bytes to the caller's memory.
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    return_data_copy_size.min(),
+                    return_data_offset.expr(),
+                    return_data_copy_size.min(),
+                    0.expr(),
+                    2.expr() * return_data_copy_size.min(), // reads + writes
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // A failed call consumes all the gas it was given.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            required_gas,
+            cb.curr.state.gas_left.expr(),
+        );
+        let restore_context = RestoreContextGadget::construct(
+            cb,
+            is_success.expr(),
+            0.expr(),
+            0.expr(),
+            OUTPUT_LENGTH.expr() * is_success.expr(),
+            gas_cost,
+            0.expr(),
+        );
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            input_rlc,
+            output_rlc,
+            input_word_size,
+            insufficient_gas,
+            call_data_length_is_zero,
+            return_data_copy_size,
+            return_data_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let Some(PrecompileAuxData::Ripemd160(aux_data)) = &step.aux_data else {
+            unreachable!("ripemd160 step without its auxiliary data");
+        };
+
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|i| block.get_rws(step, i).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.callee_address, callee_address),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+
+        let HashAuxData {
+            input_bytes,
+            output_bytes,
+        } = aux_data;
+        for (cell, bytes) in [
+            (&self.input_rlc, input_bytes),
+            (&self.output_rlc, output_bytes),
+        ] {
+            let value = region
+                .challenges()
+                .keccak_input()
+                .map(|randomness| rlc::value(bytes.iter().rev(), randomness));
+            cell.assign(region, offset, value)?;
+        }
+
+        let input_word_size =
+            self.input_word_size
+                .assign(region, offset, call_data_length.as_u64())?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0),
+            F::from(
+                GasCost::PRECOMPILE_RIPEMD160_BASE.0
+                    + GasCost::PRECOMPILE_RIPEMD160_PER_WORD.0 * input_word_size,
+            ),
+        )?;
+        self.call_data_length_is_zero.assign(
+            region,
+            offset,
+            call_data_length.to_scalar().unwrap(),
+        )?;
+        self.return_data_copy_size.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+            F::from(OUTPUT_LENGTH),
+        )?;
+        self.return_data_length_is_zero.assign(
+            region,
+            offset,
+            return_data_length.to_scalar().unwrap(),
+        )?;
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+
+        // The context is restored after the copies of a successful call, or
+        // after the read of RwCounterEndOfReversion of a failed one.
+        let rw_offset = if call.is_success {
+            let output_length = OUTPUT_LENGTH as usize;
+            7 + call_data_length.as_usize()
+                + output_length
+                + 2 * return_data_length.as_usize().min(output_length)
+        } else {
+            8
+        };
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bus_mapping::precompile::PrecompileCalls;
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    use crate::test_util::CircuitTestBuilder;
+
+    const TEST_CALL_OPCODES: &[OpcodeId] = &[
+        OpcodeId::CALL,
+        OpcodeId::CALLCODE,
+        OpcodeId::DELEGATECALL,
+        OpcodeId::STATICCALL,
+    ];
+
+    #[derive(Clone, Debug)]
+    struct PrecompileCallArgs {
+        input: Vec<u8>,
+        return_data_length: u64,
+        gas: u64,
+    }
+
+    impl Default for PrecompileCallArgs {
+        fn default() -> Self {
+            Self {
+                input: b"abc".to_vec(),
+                return_data_length: 32,
+                gas: 100000,
+            }
+        }
+    }
+
+    impl PrecompileCallArgs {
+        /// Store the input in memory, call the precompile and check that the
+        /// returned data is copied to memory.
+        fn bytecode(&self, opcode: &OpcodeId) -> Bytecode {
+            let mut code = Bytecode::default();
+            for (i, chunk) in self.input.chunks(32).enumerate() {
+                let mut word = [0u8; 32];
+                word[..chunk.len()].copy_from_slice(chunk);
+                code.append(&bytecode! {
+                    PUSH32(Word::from_big_endian(&word))
+                    PUSH2(32 * i)
+                    MSTORE
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(self.return_data_length)
+                PUSH2(0x200)
+                PUSH2(self.input.len())
+                PUSH1(0x00)
+            });
+            if matches!(opcode, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                code.push(1, Word::zero());
+            }
+            code.append(&bytecode! {
+                PUSH1(PrecompileCalls::Ripemd160.address())
+                PUSH3(self.gas)
+            });
+            code.write_op(*opcode);
+            code.append(&bytecode! {
+                POP
+                PUSH2(0x200)
+                MLOAD
+                POP
+                RETURNDATASIZE
+                POP
+                STOP
+            });
+            code
+        }
+    }
+
+    fn test_ok(args: PrecompileCallArgs) {
+        for opcode in TEST_CALL_OPCODES {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(args.bytecode(opcode)).unwrap();
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn precompile_ripemd160_simple() {
+        test_ok(PrecompileCallArgs::default());
+    }
+
+    #[test]
+    fn precompile_ripemd160_input_length() {
+        // Empty input, and inputs around the padding and block boundaries.
+        for len in [0, 1, 31, 55, 56, 64, 65, 128, 200] {
+            test_ok(PrecompileCallArgs {
+                input: (0..len).map(|i| i as u8).collect(),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ripemd160_known_inputs() {
+        for input in [
+            &b""[..],
+            b"a",
+            b"message digest",
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+        ] {
+            test_ok(PrecompileCallArgs {
+                input: input.to_vec(),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ripemd160_return_data_length() {
+        // The digest is in the last 20 of the 32 returned bytes.
+        for return_data_length in [0, 10, 20, 64] {
+            test_ok(PrecompileCallArgs {
+                return_data_length,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn precompile_ripemd160_insufficient_gas() {
+        // 600 gas for the base cost and 120 gas per word of input.
+        for (gas, len) in [599, 719, 0].into_iter().cartesian_product([0, 3, 33]) {
+            test_ok(PrecompileCallArgs {
+                input: vec![0xff; len],
+                gas,
+                ..Default::default()
+            });
+        }
+    }
+}
@@ -3,26 +3,27 @@ use crate::{
         execution::ExecutionGadget,
         step::ExecutionState,
         util::{
-            common_gadget::SameContextGadget,
+            common_gadget::{HardforkGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
             },
-            sum, CachedRegion, Cell, Word,
+            not, sum, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     util::Expr,
 };
 use array_init::array_init;
-use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
+use eth_types::{chain_spec::Hardfork, evm_types::OpcodeId, Field, ToLittleEndian};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct PushGadget<F> {
     same_context: SameContextGadget<F>,
     value: Word<F>,
-    selectors: [Cell<F>; 31],
+    selectors: [Cell<F>; 32],
+    is_shanghai: HardforkGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
@@ -44,7 +45,8 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         // conditionally by selectors.
         // For PUSH2 as an example, we lookup from byte0, byte1, ..., byte31,
         // where the byte2 is actually the PUSH2 itself, and lookup are only
-        // enabled for byte0 and byte1.
+        // enabled for byte0 and byte1. For PUSH0 no lookup is enabled and the
+        // value is zero.
         //
         //                    program_counter    program_counter + num_pushed(2)
         //                           ▼                     ▼
@@ -53,19 +55,15 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         for idx in 0..32 {
             let byte = &value.cells[idx];
             let index = cb.curr.state.program_counter.expr() + opcode.expr()
-                - (OpcodeId::PUSH1.as_u8() - 1 + idx as u8).expr();
-            if idx == 0 {
+                - (OpcodeId::PUSH0.as_u8() + idx as u8).expr();
+            cb.condition(selectors[idx].expr(), |cb| {
                 cb.opcode_lookup_at(index, byte.expr(), 0.expr())
-            } else {
-                cb.condition(selectors[idx - 1].expr(), |cb| {
-                    cb.opcode_lookup_at(index, byte.expr(), 0.expr())
-                });
-            }
+            });
         }
 
-        for idx in 0..31 {
+        for idx in 0..32 {
             let selector_prev = if idx == 0 {
-                // First selector will always be 1
+                // First selector is only 0 for PUSH0
                 1.expr()
             } else {
                 selectors[idx - 1].expr()
@@ -79,31 +77,46 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             // byte should be 0 when selector is 0
             cb.require_zero(
                 "Constrain byte == 0 when selector == 0",
-                value.cells[idx + 1].expr() * (1.expr() - selectors[idx].expr()),
+                value.cells[idx].expr() * (1.expr() - selectors[idx].expr()),
             );
         }
 
-        // Deduce the number of additional bytes to push than PUSH1. Note that
-        // num_additional_pushed = n - 1 where n is the suffix number of PUSH*.
-        let num_additional_pushed = opcode.expr() - OpcodeId::PUSH1.as_u64().expr();
-        // Sum of selectors needs to be exactly the number of additional bytes
-        // that needs to be pushed.
+        // Deduce the number of bytes to push. Note that num_pushed = n where n
+        // is the suffix number of PUSH*.
+        let num_pushed = opcode.expr() - OpcodeId::PUSH0.as_u64().expr();
+        // Sum of selectors needs to be exactly the number of bytes that needs
+        // to be pushed.
         cb.require_equal(
-            "Constrain sum of selectors equal to num_additional_pushed",
+            "Constrain sum of selectors equal to num_pushed",
             sum::expr(&selectors),
-            num_additional_pushed,
+            num_pushed,
+        );
+
+        // PUSH0 is only available since Shanghai (EIP-3855).
+        let is_shanghai = HardforkGadget::construct(cb, Hardfork::Shanghai);
+        cb.require_zero(
+            "PUSH0 is enabled in the block",
+            not::expr(selectors[0].expr()) * not::expr(is_shanghai.is_enabled()),
         );
 
         // Push the value on the stack
         cb.stack_push(value.expr());
 
+        // PUSH0 is the only push with its first selector disabled, and it is
+        // cheaper than the other pushes.
+        let gas_cost = OpcodeId::PUSH0.constant_gas_cost().expr()
+            + selectors[0].expr()
+                * (OpcodeId::PUSH1.constant_gas_cost().as_u64()
+                    - OpcodeId::PUSH0.constant_gas_cost().as_u64())
+                .expr();
+
         // State transition
         // `program_counter` needs to be increased by number of bytes pushed + 1
         let step_state_transition = StepStateTransition {
             rw_counter: Delta(1.expr()),
-            program_counter: Delta(opcode.expr() - (OpcodeId::PUSH1.as_u64() - 2).expr()),
+            program_counter: Delta(opcode.expr() - (OpcodeId::PUSH0.as_u64() - 1).expr()),
             stack_pointer: Delta((-1).expr()),
-            gas_left: Delta(-OpcodeId::PUSH1.constant_gas_cost().expr()),
+            gas_left: Delta(-gas_cost),
             ..Default::default()
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
@@ -112,6 +125,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             same_context,
             value,
             selectors,
+            is_shanghai,
         }
     }
 
@@ -132,14 +146,16 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         self.value
             .assign(region, offset, Some(value.to_le_bytes()))?;
 
-        let num_additional_pushed = opcode.postfix().expect("opcode with postfix") - 1;
+        let num_pushed = opcode.postfix().expect("opcode with postfix");
         for (idx, selector) in self.selectors.iter().enumerate() {
             selector.assign(
                 region,
                 offset,
-                Value::known(F::from((idx < num_additional_pushed as usize) as u64)),
+                Value::known(F::from((idx < num_pushed as usize) as u64)),
             )?;
         }
+        self.is_shanghai
+            .assign(region, offset, block.context.hardfork)?;
 
         Ok(())
     }
@@ -170,6 +186,7 @@ mod test {
 
     #[test]
     fn push_gadget_simple() {
+        test_ok(OpcodeId::PUSH0, &[]);
         test_ok(OpcodeId::PUSH1, &[1]);
         test_ok(OpcodeId::PUSH2, &[1, 2]);
         test_ok(
@@ -192,6 +209,7 @@ mod test {
     #[ignore]
     fn push_gadget_rand() {
         for (idx, opcode) in vec![
+            OpcodeId::PUSH0,
             OpcodeId::PUSH1,
             OpcodeId::PUSH2,
             OpcodeId::PUSH3,
@@ -228,7 +246,7 @@ mod test {
         .into_iter()
         .enumerate()
         {
-            test_ok(opcode, &rand_bytes(idx + 1));
+            test_ok(opcode, &rand_bytes(idx));
         }
     }
 }
@@ -9,7 +9,7 @@ use crate::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
-            math_gadget::{IsZeroGadget, MinMaxGadget},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget, MinMaxGadget},
             memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget},
             not, CachedRegion, Cell,
         },
@@ -19,7 +19,10 @@ use crate::{
     util::Expr,
 };
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
-use eth_types::{Field, ToScalar, U256};
+use eth_types::{
+    evm_types::{GasCost, INVALID_INIT_CODE_FIRST_BYTE, MAX_CODE_SIZE},
+    Field, ToScalar, U256,
+};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -40,6 +43,9 @@ pub(crate) struct ReturnRevertGadget<F> {
 
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     code_hash: Cell<F>,
+    code_size_ok: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    first_byte: Cell<F>,
+    first_byte_is_invalid: IsEqualGadget<F>,
 
     caller_id: Cell<F>,
     address: Cell<F>,
@@ -84,16 +90,32 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
         let memory_expansion = MemoryExpansionGadget::construct(cb, [range.address()]);
 
         // Case A in the specs.
+        let code_size_ok = LtGadget::construct(cb, range.length(), (MAX_CODE_SIZE + 1).expr());
         cb.condition(is_create.clone() * is_success.expr(), |cb| {
             cb.require_equal(
                 "increase rw counter once for each memory to bytecode byte copied",
                 copy_rw_increase.expr(),
                 range.length(),
             );
+            // EIP-170: the size of the deployed code is limited.
+            cb.require_equal(
+                "deployed code size <= MAX_CODE_SIZE",
+                code_size_ok.expr(),
+                1.expr(),
+            );
         });
+        // Deposit cost of the deployed code, which is only charged when the
+        // creation succeeds.
+        let code_deposit_cost = is_create.clone()
+            * is_success.expr()
+            * GasCost::CODE_DEPOSIT_BYTE_COST.expr()
+            * range.length();
 
         let is_contract_deployment =
             is_create.clone() * is_success.expr() * not::expr(copy_rw_increase_is_zero.expr());
+        let first_byte = cb.query_cell();
+        let first_byte_is_invalid =
+            IsEqualGadget::construct(cb, first_byte.expr(), INVALID_INIT_CODE_FIRST_BYTE.expr());
         let (caller_id, address, reversion_info, code_hash) =
             cb.condition(is_contract_deployment.clone(), |cb| {
                 // We don't need to place any additional constraints on code_hash because the
@@ -112,6 +134,13 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                     copy_rw_increase.expr(),
                 );
 
+                // EIP-3541: the deployed code must not start with 0xEF.
+                cb.bytecode_lookup(code_hash.expr(), 0.expr(), 1.expr(), first_byte.expr());
+                cb.require_zero(
+                    "deployed code does not start with 0xEF",
+                    first_byte_is_invalid.expr(),
+                );
+
                 let [caller_id, address] = [
                     CallContextFieldTag::CallerId,
                     CallContextFieldTag::CalleeAddress,
@@ -147,7 +176,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                         + not::expr(is_success.expr())
                             * cb.curr.state.reversible_write_counter.expr(),
                 ),
-                gas_left: Delta(-memory_expansion.gas_cost()),
+                gas_left: Delta(-memory_expansion.gas_cost() - code_deposit_cost),
                 reversible_write_counter: To(0.expr()),
                 memory_word_size: To(0.expr()),
                 ..StepStateTransition::default()
@@ -227,6 +256,9 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
             restore_context,
             memory_expansion,
             code_hash,
+            code_size_ok,
+            first_byte,
+            first_byte_is_invalid,
             address,
             caller_id,
             reversion_info,
@@ -275,10 +307,26 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
             )?;
         }
 
+        self.code_size_ok.assign(
+            region,
+            offset,
+            F::from(length.low_u64()),
+            F::from(MAX_CODE_SIZE + 1),
+        )?;
+
         if call.is_create() && call.is_success {
             let values: Vec<_> = (3..3 + length.as_usize())
                 .map(|index| block.get_rws(step, index).memory_value())
                 .collect();
+            let first_byte = values.first().copied().unwrap_or_default();
+            self.first_byte
+                .assign(region, offset, Value::known(F::from(first_byte as u64)))?;
+            self.first_byte_is_invalid.assign(
+                region,
+                offset,
+                F::from(first_byte as u64),
+                F::from(INVALID_INIT_CODE_FIRST_BYTE as u64),
+            )?;
             let mut code_hash = CodeDB::hash(&values).to_fixed_bytes();
             code_hash.reverse();
             self.code_hash.assign(
@@ -342,10 +390,14 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use bus_mapping::circuit_input_builder::{CircuitsParams, ExecState};
     use eth_types::{
         address, bytecode,
-        evm_types::OpcodeId,
+        evm_types::{OpcodeId, MAX_CODE_SIZE},
         geth_types::{Account, GethData},
         Address, Bytecode, Bytes, ToWord, Word, U256, U64,
     };
@@ -455,7 +507,7 @@ mod test {
 
     #[test]
     fn test_return_root_create() {
-        let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0)];
+        let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0), (0, 100)];
         for ((offset, length), is_return) in
             test_parameters.iter().cartesian_product(&[true, false])
         {
@@ -478,7 +530,7 @@ mod test {
 
     #[test]
     fn test_return_nonroot_create() {
-        let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0)];
+        let test_parameters = [(0, 0), (0, 10), (300, 20), (1000, 0), (0, 100)];
         for ((offset, length), is_return) in
             test_parameters.iter().cartesian_product(&[true, false])
         {
@@ -651,4 +703,90 @@ mod test {
         let text_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
         CircuitTestBuilder::new_from_test_ctx(text_ctx).run();
     }
+
+    // Initialization code which returns `length` bytes of memory starting with
+    // `first_byte` as the deployed code.
+    fn deployer_bytecode(first_byte: u8, length: usize) -> Bytecode {
+        bytecode! {
+            PUSH1(first_byte)
+            PUSH1(0)
+            MSTORE8
+            PUSH32(length)
+            PUSH1(0)
+            RETURN
+        }
+    }
+
+    // Run a creation transaction with the given initialization code and check
+    // the execution state of its RETURN, and that a failed code deposit
+    // consumes all the gas of the transaction.
+    fn test_root_create_deposit(initializer: Bytecode, execution_state: ExecutionState) {
+        const TX_GAS: u64 = 6_000_000;
+        let is_failure = execution_state != ExecutionState::RETURN_REVERT;
+
+        let new_ctx = || {
+            TestContext::<1, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .input(initializer.clone().into())
+                        .gas(TX_GAS.into());
+                },
+                |block, _| block,
+            )
+            .unwrap()
+        };
+
+        let block: GethData = new_ctx().into();
+        assert_eq!(block.geth_traces[0].failed, is_failure);
+        if is_failure {
+            assert_eq!(block.geth_traces[0].gas.0, TX_GAS);
+        }
+
+        CircuitTestBuilder::new_from_test_ctx(new_ctx())
+            .params(CircuitsParams {
+                // The deployed code is read from memory.
+                max_rws: 2 * MAX_CODE_SIZE as usize,
+                ..Default::default()
+            })
+            .block_modifier(Box::new(move |block| {
+                let steps = &block.txs[0].steps;
+                let return_step = steps
+                    .iter()
+                    .find(|step| step.exec_state == ExecState::Op(OpcodeId::RETURN))
+                    .unwrap();
+                assert_eq!(return_step.execution_state(), execution_state);
+                let end_tx_step = steps.last().unwrap();
+                assert_eq!(end_tx_step.execution_state(), ExecutionState::EndTx);
+                assert_eq!(end_tx_step.gas_left.0 == 0, is_failure);
+            }))
+            .run();
+    }
+
+    #[test]
+    fn test_return_root_create_max_code_size() {
+        // EIP-170: MAX_CODE_SIZE bytes are deployed, one more byte fails.
+        test_root_create_deposit(
+            deployer_bytecode(0, MAX_CODE_SIZE as usize),
+            ExecutionState::RETURN_REVERT,
+        );
+        test_root_create_deposit(
+            deployer_bytecode(0, MAX_CODE_SIZE as usize + 1),
+            ExecutionState::ErrorMaxCodeSizeExceeded,
+        );
+    }
+
+    #[test]
+    fn test_return_root_create_invalid_first_byte() {
+        // EIP-3541: a deployed code starting with 0xEF fails.
+        test_root_create_deposit(deployer_bytecode(0xfe, 10), ExecutionState::RETURN_REVERT);
+        test_root_create_deposit(
+            deployer_bytecode(0xef, 10),
+            ExecutionState::ErrorInvalidCreationCode,
+        );
+    }
 }
@@ -0,0 +1,640 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, RestoreContextGadget, TransferGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget, RangeCheckGadget},
+            not, select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{
+    chain_spec::Hardfork, evm_types::GasCost, Field, ToAddress, ToLittleEndian, ToScalar, U256,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for SELFDESTRUCT. The whole balance is sent to the beneficiary, and
+/// when the account is deleted its balance, nonce and code hash are zeroed,
+/// leaving it as a non-existing account for the following transactions.
+/// Since Cancun (EIP-6780) the account is only deleted when it was created in
+/// the same transaction. The storage of a deleted account isn't cleared.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary_address: Word<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    callee_address: Cell<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    balance: Word<F>,
+    transfer: TransferGadget<F>,
+    is_beneficiary_self: IsEqualGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    is_created_in_tx: Cell<F>,
+    is_deleted: Cell<F>,
+    nonce_prev: Cell<F>,
+    code_hash_prev: Cell<F>,
+    sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary_address = cb.query_word_rlc();
+        let beneficiary = from_bytes::expr(&beneficiary_address.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary_address.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("SELFDESTRUCT is not in static call stack", is_static.expr());
+        let mut reversion_info = cb.reversion_info_read(None);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            beneficiary.expr(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary.expr(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+
+        let balance = cb.query_word_rlc();
+        cb.account_read(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            balance.expr(),
+        );
+        let transfer = TransferGadget::construct(
+            cb,
+            callee_address.expr(),
+            beneficiary.expr(),
+            not::expr(beneficiary_not_exists.expr()),
+            balance.clone(),
+            &mut reversion_info,
+        );
+
+        // Zero the balance of the current account, burning the ether when it
+        // is the beneficiary itself.
+        let is_beneficiary_self =
+            IsEqualGadget::construct(cb, beneficiary.expr(), callee_address.expr());
+        let is_cancun = HardforkGadget::construct(cb, Hardfork::Cancun);
+        let is_created_in_tx = cb.query_bool();
+        cb.condition(is_cancun.is_enabled(), |cb| {
+            cb.tx_created_account_read(
+                tx_id.expr(),
+                callee_address.expr(),
+                is_created_in_tx.expr(),
+            );
+        });
+        let is_deleted = cb.copy(select::expr(
+            is_cancun.is_enabled(),
+            is_created_in_tx.expr(),
+            1.expr(),
+        ));
+        let nonce_prev = cb.query_cell();
+        let code_hash_prev = cb.query_cell_phase2();
+        cb.condition(is_deleted.expr(), |cb| {
+            cb.account_write(
+                callee_address.expr(),
+                AccountFieldTag::Balance,
+                0.expr(),
+                is_beneficiary_self.expr() * balance.expr(),
+                Some(&mut reversion_info),
+            );
+            cb.account_write(
+                callee_address.expr(),
+                AccountFieldTag::Nonce,
+                0.expr(),
+                nonce_prev.expr(),
+                Some(&mut reversion_info),
+            );
+            cb.account_write(
+                callee_address.expr(),
+                AccountFieldTag::CodeHash,
+                0.expr(),
+                code_hash_prev.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        let has_value = not::expr(transfer.value_is_zero.expr());
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr() * has_value.clone() * GasCost::NEW_ACCOUNT.expr();
+        let sufficient_gas_left =
+            RangeCheckGadget::construct(cb, cb.curr.state.gas_left.expr() - gas_cost.clone());
+
+        // +1 access list write, +transfer writes and +3 balance, nonce and
+        // code hash writes when the account is deleted.
+        let reversible_write_counter_delta = 1.expr()
+            + beneficiary_not_exists.expr() * has_value.clone()
+            + 2.expr() * has_value
+            + 3.expr() * is_deleted.expr();
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.clone()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // When it's an internal call, the gas left after SELFDESTRUCT is
+        // returned to the caller.
+        let restore_context = cb.condition(1.expr() - cb.curr.state.is_root.expr(), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_delta,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary_address,
+            tx_id,
+            is_static,
+            reversion_info,
+            callee_address,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            balance,
+            transfer,
+            is_beneficiary_self,
+            is_cancun,
+            is_created_in_tx,
+            is_deleted,
+            nonce_prev,
+            code_hash_prev,
+            sufficient_gas_left,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let beneficiary = block.get_rws(step, 0).stack_value();
+        self.beneficiary_address
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+        let beneficiary = beneficiary.to_address();
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        let callee_address = call.address.to_scalar().unwrap();
+        self.callee_address
+            .assign(region, offset, Value::known(callee_address))?;
+
+        let (_, is_warm) = block.get_rws(step, 6).tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        let (beneficiary_code_hash, _) = block.get_rws(step, 7).account_value_pair();
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+
+        let (balance, _) = block.get_rws(step, 8).account_value_pair();
+        self.balance
+            .assign(region, offset, Some(balance.to_le_bytes()))?;
+
+        let mut rw_offset = 9;
+        let [sender_balance_pair, beneficiary_balance_pair] = if balance.is_zero() {
+            [(U256::zero(), U256::zero()); 2]
+        } else {
+            if beneficiary_code_hash.is_zero() {
+                rw_offset += 1;
+            }
+            rw_offset += 2;
+            [rw_offset - 2, rw_offset - 1].map(|i| block.get_rws(step, i).account_value_pair())
+        };
+        self.transfer.assign(
+            region,
+            offset,
+            sender_balance_pair,
+            beneficiary_balance_pair,
+            balance,
+        )?;
+
+        self.is_beneficiary_self.assign(
+            region,
+            offset,
+            beneficiary.to_scalar().unwrap(),
+            callee_address,
+        )?;
+
+        let is_cancun = self
+            .is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+        let is_created_in_tx = if is_cancun {
+            let (is_created_in_tx, _) = block
+                .get_rws(step, rw_offset)
+                .tx_created_account_value_pair();
+            rw_offset += 1;
+            is_created_in_tx
+        } else {
+            false
+        };
+        self.is_created_in_tx.assign(
+            region,
+            offset,
+            Value::known(F::from(is_created_in_tx as u64)),
+        )?;
+        let is_deleted = !is_cancun || is_created_in_tx;
+        self.is_deleted
+            .assign(region, offset, Value::known(F::from(is_deleted as u64)))?;
+        let (nonce_prev, code_hash_prev) = if is_deleted {
+            let nonce_prev = block.get_rws(step, rw_offset + 1).account_value_pair().1;
+            let code_hash_prev = block.get_rws(step, rw_offset + 2).account_value_pair().1;
+            rw_offset += 3;
+            (nonce_prev, code_hash_prev)
+        } else {
+            (U256::zero(), U256::zero())
+        };
+        self.nonce_prev.assign(
+            region,
+            offset,
+            Value::known(nonce_prev.to_scalar().unwrap()),
+        )?;
+        self.code_hash_prev
+            .assign(region, offset, region.word_rlc(code_hash_prev))?;
+
+        self.sufficient_gas_left.assign(
+            region,
+            offset,
+            F::from(step.gas_left.0 - step.gas_cost.0),
+        )?;
+
+        if !call.is_root {
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        address, bytecode,
+        chain_spec::{ChainSpec, Hardfork},
+        evm_types::OpcodeId,
+        Address, Bytecode, ToWord, Word,
+    };
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    const CONTRACT_ADDRESS: Address = Address::repeat_byte(0x10);
+
+    fn selfdestruct_bytecode(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    const HARDFORKS: [Hardfork; 2] = [Hardfork::Shanghai, Hardfork::Cancun];
+
+    fn test_root_ok(beneficiary: Address, balance: Word, hardfork: Hardfork) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(balance)
+                    .code(selfdestruct_bytecode(beneficiary));
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .chain_spec(ChainSpec::with_hardfork(hardfork))
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal_ok(
+        beneficiary: Address,
+        balance: Word,
+        is_persistent: bool,
+        hardfork: Hardfork,
+    ) {
+        let mut caller_code = bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH20(CONTRACT_ADDRESS.to_word())
+            GAS
+            CALL
+        };
+        if !is_persistent {
+            caller_code.append(&bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            });
+        }
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe02"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(caller_code);
+                accs[2]
+                    .address(CONTRACT_ADDRESS)
+                    .balance(balance)
+                    .code(selfdestruct_bytecode(beneficiary));
+                accs[3]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .chain_spec(ChainSpec::with_hardfork(hardfork))
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_gadget_root() {
+        let beneficiaries = [
+            // existing account
+            address!("0x0000000000000000000000000000000000000020"),
+            // non-existing account
+            address!("0x00000000000000000000000000000000000000ff"),
+            // the contract itself
+            CONTRACT_ADDRESS,
+        ];
+        let balances = [Word::zero(), Word::from(1u64 << 20)];
+        for ((beneficiary, balance), hardfork) in beneficiaries
+            .iter()
+            .cartesian_product(balances.iter())
+            .cartesian_product(HARDFORKS)
+        {
+            test_root_ok(*beneficiary, *balance, hardfork);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_internal() {
+        let beneficiaries = [
+            address!("0x0000000000000000000000000000000000000020"),
+            address!("0x00000000000000000000000000000000000000ff"),
+            CONTRACT_ADDRESS,
+        ];
+        let balances = [Word::zero(), Word::from(1u64 << 20)];
+        for (((beneficiary, balance), is_persistent), hardfork) in beneficiaries
+            .iter()
+            .cartesian_product(balances.iter())
+            .cartesian_product([true, false])
+            .cartesian_product(HARDFORKS)
+        {
+            test_internal_ok(*beneficiary, *balance, is_persistent, hardfork);
+        }
+    }
+
+    // The contract is called again by a following transaction, which finds
+    // a non-existing account when it was deleted.
+    #[test]
+    fn selfdestruct_gadget_called_in_next_tx() {
+        let beneficiary = address!("0x0000000000000000000000000000000000000020");
+        for hardfork in HARDFORKS {
+            let ctx = TestContext::<3, 2>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x0000000000000000000000000000000000cafe01"))
+                        .balance(Word::from(1u64 << 30));
+                    accs[1]
+                        .address(CONTRACT_ADDRESS)
+                        .balance(Word::from(1u64 << 20))
+                        .code(selfdestruct_bytecode(beneficiary));
+                    accs[2].address(beneficiary).balance(Word::from(1u64 << 20));
+                },
+                |txs, accs| {
+                    for tx in txs {
+                        tx.from(accs[0].address)
+                            .to(accs[1].address)
+                            .value(Word::from(1u64 << 10))
+                            .gas(Word::from(100000));
+                    }
+                },
+                |block, _tx| {
+                    block
+                        .number(0xcafeu64)
+                        .chain_spec(ChainSpec::with_hardfork(hardfork))
+                },
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .params(CircuitsParams {
+                    max_txs: 2,
+                    ..Default::default()
+                })
+                .run();
+        }
+    }
+
+    // The account is created by a creation transaction and self destructs in
+    // its initialization code, so it is deleted in every hardfork.
+    #[test]
+    fn selfdestruct_gadget_created_in_root_tx() {
+        let beneficiary = address!("0x0000000000000000000000000000000000000020");
+        for hardfork in HARDFORKS {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x0000000000000000000000000000000000cafe01"))
+                        .balance(Word::from(1u64 << 30));
+                    accs[1].address(beneficiary).balance(Word::from(1u64 << 20));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .value(Word::from(1u64 << 10))
+                        .input(selfdestruct_bytecode(beneficiary).into())
+                        .gas(Word::from(100000));
+                },
+                |block, _tx| {
+                    block
+                        .number(0xcafeu64)
+                        .chain_spec(ChainSpec::with_hardfork(hardfork))
+                },
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    // The account is created by CREATE or CREATE2 and self destructs in its
+    // initialization code.
+    #[test]
+    fn selfdestruct_gadget_created_in_internal_call() {
+        let beneficiary = address!("0x0000000000000000000000000000000000000020");
+        let initialization_bytes = selfdestruct_bytecode(beneficiary).code();
+        for (is_create2, hardfork) in [false, true].into_iter().cartesian_product(HARDFORKS) {
+            let mut creator_code = bytecode! {
+                PUSH32(Word::from_big_endian(&initialization_bytes))
+                PUSH1(0)
+                MSTORE
+            };
+            if is_create2 {
+                creator_code.append(&bytecode! {PUSH1(45)}); // salt
+            }
+            creator_code.append(&bytecode! {
+                PUSH1(initialization_bytes.len()) // length
+                PUSH1(32 - initialization_bytes.len()) // offset
+                PUSH2(1000) // value
+            });
+            creator_code.write_op(if is_create2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            });
+
+            let ctx = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x0000000000000000000000000000000000cafe01"))
+                        .balance(Word::from(1u64 << 30));
+                    accs[1]
+                        .address(address!("0x0000000000000000000000000000000000cafe02"))
+                        .balance(Word::from(1u64 << 20))
+                        .code(creator_code);
+                    accs[2].address(beneficiary).balance(Word::from(1u64 << 20));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(Word::from(200000));
+                },
+                |block, _tx| {
+                    block
+                        .number(0xcafeu64)
+                        .chain_spec(ChainSpec::with_hardfork(hardfork))
+                },
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+}
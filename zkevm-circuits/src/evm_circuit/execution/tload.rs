@@ -0,0 +1,177 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use eth_types::{chain_spec::Hardfork, evm_types::OpcodeId, Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct TloadGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    callee_address: Cell<F>,
+    phase2_key: Cell<F>,
+    phase2_value: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for TloadGadget<F> {
+    const NAME: &'static str = "TLOAD";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::TLOAD;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Transient storage is only available since Cancun (EIP-1153).
+        let is_cancun = HardforkGadget::construct_enabled(cb, Hardfork::Cancun);
+
+        let opcode = cb.query_cell();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        let phase2_key = cb.query_cell_phase2();
+        // Pop the key from the stack
+        cb.stack_pop(phase2_key.expr());
+
+        let phase2_value = cb.query_cell_phase2();
+        cb.transient_storage_read(
+            callee_address.expr(),
+            phase2_key.expr(),
+            phase2_value.expr(),
+            tx_id.expr(),
+        );
+
+        cb.stack_push(phase2_value.expr());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(5.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::TLOAD.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            is_cancun,
+            tx_id,
+            callee_address,
+            phase2_key,
+            phase2_value,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(
+                call.address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+        let key = block.get_rws(step, 2).stack_value();
+        let value = block.get_rws(step, 4).stack_value();
+        self.phase2_key
+            .assign(region, offset, region.word_rlc(key))?;
+        self.phase2_value
+            .assign(region, offset, region.word_rlc(value))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Word};
+    use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
+
+    fn test_ok(key: Word, value: Word) {
+        // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
+        // Besides, in bytecode we use two TLOADs,
+        // the first TLOAD reads an unwritten slot, and the second reads the value
+        // written by TSTORE
+        let bytecode_success = bytecode! {
+            PUSH32(key)
+            TLOAD
+            PUSH32(value)
+            PUSH32(key)
+            TSTORE
+            PUSH32(key)
+            TLOAD
+            STOP
+        };
+        let bytecode_failure = bytecode! {
+            PUSH32(key)
+            TLOAD
+            PUSH32(value)
+            PUSH32(key)
+            TSTORE
+            PUSH32(key)
+            TLOAD
+            PUSH32(0)
+            PUSH32(0)
+            REVERT
+        };
+        for bytecode in [bytecode_success, bytecode_failure] {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(Word::from(10u64.pow(19)))
+                        .code(bytecode);
+                    accs[1]
+                        .address(MOCK_ACCOUNTS[1])
+                        .balance(Word::from(10u64.pow(19)));
+                },
+                tx_from_1_to_0,
+                |block, _txs| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn tload_gadget_simple() {
+        let key = 0x030201.into();
+        let value = 0x060504.into();
+        test_ok(key, value);
+    }
+
+    #[test]
+    fn tload_gadget_rand() {
+        let key = rand_word();
+        let value = rand_word();
+        test_ok(key, value);
+    }
+}
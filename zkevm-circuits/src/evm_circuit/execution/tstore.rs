@@ -0,0 +1,209 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use eth_types::{chain_spec::Hardfork, evm_types::OpcodeId, Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct TstoreGadget<F> {
+    same_context: SameContextGadget<F>,
+    is_cancun: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    callee_address: Cell<F>,
+    phase2_key: Cell<F>,
+    phase2_value: Cell<F>,
+    phase2_value_prev: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for TstoreGadget<F> {
+    const NAME: &'static str = "TSTORE";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::TSTORE;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // TSTORE is introduced by Cancun (EIP-1153).
+        let is_cancun = HardforkGadget::construct_enabled(cb, Hardfork::Cancun);
+
+        let opcode = cb.query_cell();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // constrain not in static call
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("is_static is false", is_static.expr());
+
+        let mut reversion_info = cb.reversion_info_read(None);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        let phase2_key = cb.query_cell_phase2();
+        // Pop the key from the stack
+        cb.stack_pop(phase2_key.expr());
+
+        let phase2_value = cb.query_cell_phase2();
+        // Pop the value from the stack
+        cb.stack_pop(phase2_value.expr());
+
+        let phase2_value_prev = cb.query_cell_phase2();
+        cb.transient_storage_write(
+            callee_address.expr(),
+            phase2_key.expr(),
+            phase2_value.expr(),
+            phase2_value_prev.expr(),
+            tx_id.expr(),
+            Some(&mut reversion_info),
+        );
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(8.expr()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(2.expr()),
+            reversible_write_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::TSTORE.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            is_cancun,
+            tx_id,
+            is_static,
+            reversion_info,
+            callee_address,
+            phase2_key,
+            phase2_value,
+            phase2_value_prev,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.is_cancun
+            .assign(region, offset, block.context.hardfork)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(
+                call.address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+
+        let key = block.get_rws(step, 5).stack_value();
+        let value = block.get_rws(step, 6).stack_value();
+        self.phase2_key
+            .assign(region, offset, region.word_rlc(key))?;
+        self.phase2_value
+            .assign(region, offset, region.word_rlc(value))?;
+
+        let (_, value_prev) = block.get_rws(step, 7).transient_storage_value_pair();
+        self.phase2_value_prev
+            .assign(region, offset, region.word_rlc(value_prev))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
+    use eth_types::{bytecode, Word};
+    use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
+
+    fn test_ok(key: Word, value: Word, value_prev: Word) {
+        // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
+        // Besides, in bytecode we use two TSTOREs, the first one sets value_prev
+        // and the second one overwrites it with value
+        let bytecode_success = bytecode! {
+            PUSH32(value_prev)
+            PUSH32(key)
+            TSTORE
+            PUSH32(value)
+            PUSH32(key)
+            TSTORE
+            STOP
+        };
+        let bytecode_failure = bytecode! {
+            PUSH32(value_prev)
+            PUSH32(key)
+            TSTORE
+            PUSH32(value)
+            PUSH32(key)
+            TSTORE
+            PUSH32(0)
+            PUSH32(0)
+            REVERT
+        };
+        for bytecode in [bytecode_success, bytecode_failure] {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(Word::from(10u64.pow(19)))
+                        .code(bytecode);
+                    accs[1]
+                        .address(MOCK_ACCOUNTS[1])
+                        .balance(Word::from(10u64.pow(19)));
+                },
+                tx_from_1_to_0,
+                |block, _txs| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn tstore_gadget_simple() {
+        test_ok(0x030201.into(), 0x060504.into(), 0x060505.into());
+    }
+
+    #[test]
+    fn tstore_gadget_clear_slot() {
+        test_ok(0x030201.into(), 0.into(), 0x060505.into());
+    }
+
+    #[test]
+    fn tstore_gadget_rand() {
+        test_ok(rand_word(), rand_word(), rand_word());
+    }
+}
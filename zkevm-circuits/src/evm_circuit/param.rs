@@ -7,7 +7,7 @@ use halo2_proofs::{
 use std::collections::HashMap;
 
 // Step dimension
-pub(crate) const STEP_WIDTH: usize = 128;
+pub(crate) const STEP_WIDTH: usize = 135;
 /// Step height
 pub const MAX_STEP_HEIGHT: usize = 24;
 /// The height of the state of a step, used by gates that connect two
@@ -35,7 +35,14 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + BLOCK_TABLE_LOOKUPS
     + COPY_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
-    + EXP_TABLE_LOOKUPS;
+    + EXP_TABLE_LOOKUPS
+    + SIG_TABLE_LOOKUPS
+    + SHA256_TABLE_LOOKUPS
+    + RIPEMD160_TABLE_LOOKUPS
+    + MODEXP_TABLE_LOOKUPS
+    + ECC_TABLE_LOOKUPS
+    + PAIRING_TABLE_LOOKUPS
+    + BLAKE2F_TABLE_LOOKUPS;
 
 /// Lookups done per row.
 pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
@@ -47,6 +54,13 @@ pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Copy, COPY_TABLE_LOOKUPS),
     (Table::Keccak, KECCAK_TABLE_LOOKUPS),
     (Table::Exp, EXP_TABLE_LOOKUPS),
+    (Table::Sig, SIG_TABLE_LOOKUPS),
+    (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::Ripemd160, RIPEMD160_TABLE_LOOKUPS),
+    (Table::ModExp, MODEXP_TABLE_LOOKUPS),
+    (Table::Ecc, ECC_TABLE_LOOKUPS),
+    (Table::Pairing, PAIRING_TABLE_LOOKUPS),
+    (Table::Blake2f, BLAKE2F_TABLE_LOOKUPS),
 ];
 
 /// Fixed Table lookups done in EVMCircuit
@@ -73,6 +87,27 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
+/// Sig Table lookups done in EVMCircuit
+pub const SIG_TABLE_LOOKUPS: usize = 1;
+
+/// Sha256 Table lookups done in EVMCircuit
+pub const SHA256_TABLE_LOOKUPS: usize = 1;
+
+/// Ripemd160 Table lookups done in EVMCircuit
+pub const RIPEMD160_TABLE_LOOKUPS: usize = 1;
+
+/// ModExp Table lookups done in EVMCircuit
+pub const MODEXP_TABLE_LOOKUPS: usize = 1;
+
+/// Ecc Table lookups done in EVMCircuit
+pub const ECC_TABLE_LOOKUPS: usize = 1;
+
+/// Pairing Table lookups done in EVMCircuit
+pub const PAIRING_TABLE_LOOKUPS: usize = 1;
+
+/// Blake2f Table lookups done in EVMCircuit
+pub const BLAKE2F_TABLE_LOOKUPS: usize = 1;
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
@@ -91,6 +126,10 @@ pub(crate) const N_BYTES_ACCOUNT_ADDRESS: usize = 20;
 pub(crate) const N_BYTES_MEMORY_ADDRESS: usize = 5;
 pub(crate) const N_BYTES_MEMORY_WORD_SIZE: usize = 4;
 
+// Maximum memory address after expansion. A larger memory size results in a
+// gas uint64 overflow in go-ethereum, which is reported as out of gas.
+pub(crate) const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
+
 pub(crate) const STACK_CAPACITY: usize = 1024;
 
 // Number of bytes that will be used of prorgam counter. Although the maximum
@@ -13,7 +13,7 @@ use bus_mapping::{
     evm::OpcodeId,
     precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+use eth_types::{Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
@@ -84,15 +84,20 @@ pub enum ExecutionState {
     BLOCKCTXU160, // COINBASE
     BLOCKCTXU256, // DIFFICULTY, BASEFEE
     CHAINID,
+    BLOBHASH,
+    BLOBBASEFEE,
     SELFBALANCE,
     POP,
     MEMORY, // MLOAD, MSTORE, MSTORE8
     SLOAD,
     SSTORE,
+    TLOAD,
+    TSTORE,
     JUMP,
     JUMPI,
     PC,
     MSIZE,
+    MCOPY,
     GAS,
     JUMPDEST,
     PUSH, // PUSH1, PUSH2, ..., PUSH32
@@ -124,7 +129,6 @@ pub enum ExecutionState {
     ErrorOutOfGasLOG,
     ErrorOutOfGasEXP,
     ErrorOutOfGasSHA3,
-    ErrorOutOfGasEXTCODECOPY,
     ErrorOutOfGasCall,
     ErrorOutOfGasSloadSstore,
     ErrorOutOfGasCREATE2,
@@ -208,13 +212,6 @@ impl From<&ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -244,6 +241,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::GASPRICE => ExecutionState::GASPRICE,
                     OpcodeId::PC => ExecutionState::PC,
                     OpcodeId::MSIZE => ExecutionState::MSIZE,
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
                     OpcodeId::CALLER => ExecutionState::CALLER,
                     OpcodeId::CALLVALUE => ExecutionState::CALLVALUE,
                     OpcodeId::EXTCODEHASH => ExecutionState::EXTCODEHASH,
@@ -261,9 +259,13 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    OpcodeId::TSTORE => ExecutionState::TSTORE,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::ISZERO => ExecutionState::ISZERO,
                     OpcodeId::CALL
                     | OpcodeId::CALLCODE
@@ -277,10 +279,9 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::RETURN | OpcodeId::REVERT => ExecutionState::RETURN_REVERT,
                     OpcodeId::RETURNDATASIZE => ExecutionState::RETURNDATASIZE,
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
-                    // dummy ops
-                    OpcodeId::CREATE => dummy!(ExecutionState::CREATE),
-                    OpcodeId::CREATE2 => dummy!(ExecutionState::CREATE2),
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::CREATE => ExecutionState::CREATE,
+                    OpcodeId::CREATE2 => ExecutionState::CREATE2,
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
@@ -340,7 +341,6 @@ impl ExecutionState {
                 | Self::ErrorOutOfGasLOG
                 | Self::ErrorOutOfGasEXP
                 | Self::ErrorOutOfGasSHA3
-                | Self::ErrorOutOfGasEXTCODECOPY
                 | Self::ErrorOutOfGasCall
                 | Self::ErrorOutOfGasSloadSstore
                 | Self::ErrorOutOfGasCREATE2
@@ -404,6 +404,8 @@ impl ExecutionState {
             Self::BLOCKCTXU160 => vec![OpcodeId::COINBASE],
             Self::BLOCKCTXU256 => vec![OpcodeId::DIFFICULTY, OpcodeId::BASEFEE],
             Self::CHAINID => vec![OpcodeId::CHAINID],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
@@ -411,13 +413,17 @@ impl ExecutionState {
             }
             Self::SLOAD => vec![OpcodeId::SLOAD],
             Self::SSTORE => vec![OpcodeId::SSTORE],
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            Self::TSTORE => vec![OpcodeId::TSTORE],
             Self::JUMP => vec![OpcodeId::JUMP],
             Self::JUMPI => vec![OpcodeId::JUMPI],
             Self::PC => vec![OpcodeId::PC],
             Self::MSIZE => vec![OpcodeId::MSIZE],
+            Self::MCOPY => vec![OpcodeId::MCOPY],
             Self::GAS => vec![OpcodeId::GAS],
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
             Self::PUSH => vec![
+                OpcodeId::PUSH0,
                 OpcodeId::PUSH1,
                 OpcodeId::PUSH2,
                 OpcodeId::PUSH3,
@@ -504,7 +510,25 @@ impl ExecutionState {
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::CREATE2 => vec![OpcodeId::CREATE2],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
+            // Opcodes introduced by a hardfork are invalid in earlier blocks.
+            Self::ErrorInvalidOpcode => [
+                OpcodeId::invalid_opcodes(),
+                OpcodeId::hardfork_gated_opcodes(),
+            ]
+            .concat(),
+            Self::ErrorInvalidCreationCode
+            | Self::ErrorMaxCodeSizeExceeded
+            | Self::ErrorOutOfGasCodeStore => vec![OpcodeId::RETURN],
+            Self::ErrorOutOfGasCREATE2 => vec![OpcodeId::CREATE2],
+            Self::ErrorContractAddressCollision => vec![OpcodeId::CREATE, OpcodeId::CREATE2],
+            Self::ErrorDepth => vec![
+                OpcodeId::CALL,
+                OpcodeId::CALLCODE,
+                OpcodeId::DELEGATECALL,
+                OpcodeId::STATICCALL,
+                OpcodeId::CREATE,
+                OpcodeId::CREATE2,
+            ],
             _ => vec![],
         }
         .into_iter()
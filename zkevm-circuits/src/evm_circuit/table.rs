@@ -142,6 +142,13 @@ pub enum Table {
     Copy,
     Keccak,
     Exp,
+    Sig,
+    Sha256,
+    Ripemd160,
+    ModExp,
+    Ecc,
+    Pairing,
+    Blake2f,
 }
 
 #[derive(Clone, Debug)]
@@ -291,6 +298,84 @@ pub(crate) enum Lookup<F> {
         exponent_lo_hi: [Expression<F>; 2],
         exponentiation_lo_hi: [Expression<F>; 2],
     },
+    /// Lookup to sig table, which verifies the public key recovery done by
+    /// the ecRecover precompile.
+    SigTable {
+        msg_hash_rlc: Expression<F>,
+        sig_v: Expression<F>,
+        sig_r_rlc: Expression<F>,
+        sig_s_rlc: Expression<F>,
+        recovered_addr: Expression<F>,
+        is_valid: Expression<F>,
+    },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// RLC representation of the SHA-256 digest of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// RLC representation of the RIPEMD-160 digest of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to modexp table.
+    ModExpTable {
+        /// RLC representation of the base.
+        base_rlc: Expression<F>,
+        /// RLC representation of the exponent.
+        exponent_rlc: Expression<F>,
+        /// RLC representation of the modulus.
+        modulus_rlc: Expression<F>,
+        /// RLC representation of the result of the modular exponentiation.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to ecc table.
+    EccTable {
+        /// Address of the precompile, 0x06 for ecAdd and 0x07 for ecMul.
+        op_type: Expression<F>,
+        /// RLC representation of the x-coordinate of the first point.
+        arg1_rlc: Expression<F>,
+        /// RLC representation of the y-coordinate of the first point.
+        arg2_rlc: Expression<F>,
+        /// RLC representation of the x-coordinate of the second point, or of
+        /// the scalar.
+        arg3_rlc: Expression<F>,
+        /// RLC representation of the y-coordinate of the second point, or
+        /// zero.
+        arg4_rlc: Expression<F>,
+        /// RLC representation of the x-coordinate of the result.
+        output1_rlc: Expression<F>,
+        /// RLC representation of the y-coordinate of the result.
+        output2_rlc: Expression<F>,
+        /// Whether the input points are valid.
+        is_valid: Expression<F>,
+    },
+    /// Lookup to pairing table.
+    PairingTable {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of the input, a non-zero multiple of 192.
+        input_len: Expression<F>,
+        /// Whether the input points are valid.
+        is_valid: Expression<F>,
+        /// Whether the product of the pairings is one.
+        output: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Accumulator to the output.
+        output_rlc: Expression<F>,
+    },
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -310,6 +395,13 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
+            Self::SigTable { .. } => Table::Sig,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::ModExpTable { .. } => Table::ModExp,
+            Self::EccTable { .. } => Table::Ecc,
+            Self::PairingTable { .. } => Table::Pairing,
+            Self::Blake2fTable { .. } => Table::Blake2f,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -421,6 +513,94 @@ impl<F: Field> Lookup<F> {
                 exponentiation_lo_hi[0].clone(),
                 exponentiation_lo_hi[1].clone(),
             ],
+            Self::SigTable {
+                msg_hash_rlc,
+                sig_v,
+                sig_r_rlc,
+                sig_s_rlc,
+                recovered_addr,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                msg_hash_rlc.clone(),
+                sig_v.clone(),
+                sig_r_rlc.clone(),
+                sig_s_rlc.clone(),
+                recovered_addr.clone(),
+                is_valid.clone(),
+            ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::ModExpTable {
+                base_rlc,
+                exponent_rlc,
+                modulus_rlc,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                base_rlc.clone(),
+                exponent_rlc.clone(),
+                modulus_rlc.clone(),
+                output_rlc.clone(),
+            ],
+            Self::EccTable {
+                op_type,
+                arg1_rlc,
+                arg2_rlc,
+                arg3_rlc,
+                arg4_rlc,
+                output1_rlc,
+                output2_rlc,
+                is_valid,
+            } => vec![
+                1.expr(), // q_enable
+                op_type.clone(),
+                arg1_rlc.clone(),
+                arg2_rlc.clone(),
+                arg3_rlc.clone(),
+                arg4_rlc.clone(),
+                output1_rlc.clone(),
+                output2_rlc.clone(),
+                is_valid.clone(),
+            ],
+            Self::PairingTable {
+                input_rlc,
+                input_len,
+                is_valid,
+                output,
+            } => vec![
+                1.expr(), // q_enable
+                input_rlc.clone(),
+                input_len.clone(),
+                is_valid.clone(),
+                output.clone(),
+            ],
+            Self::Blake2fTable {
+                input_rlc,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                output_rlc.clone(),
+            ],
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
@@ -9,7 +9,7 @@ use crate::{
     witness::{Block, ExecStep, Rw, RwMap},
 };
 use bus_mapping::state_db::CodeDB;
-use eth_types::{Address, Field, ToLittleEndian, ToWord, U256};
+use eth_types::{Field, ToLittleEndian, ToWord, U256};
 use halo2_proofs::{
     circuit::{AssignedCell, Region, Value},
     plonk::{Advice, Assigned, Column, ConstraintSystem, Error, Expression, VirtualCells},
@@ -619,10 +619,6 @@ pub(crate) fn transpose_val_ret<F, E>(value: Value<Result<F, E>>) -> Result<Valu
     ret
 }
 
-pub(crate) fn is_precompiled(address: &Address) -> bool {
-    address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
-}
-
 /// Helper struct to read rw operations from a step sequentially.
 pub(crate) struct StepRws<'a> {
     rws: &'a RwMap,
@@ -19,11 +19,11 @@ use crate::{
             not, or, Cell, CellType, Word,
         },
     },
-    table::{AccountFieldTag, CallContextFieldTag},
+    table::{AccountFieldTag, BlockContextFieldTag, CallContextFieldTag},
     util::Expr,
     witness::{Block, Call, ExecStep},
 };
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
+use eth_types::{chain_spec::Hardfork, evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -1165,3 +1165,65 @@ impl<F: Field, const VALID_BYTES: usize> WordByteRangeGadget<F, VALID_BYTES> {
         self.not_overflow.expr()
     }
 }
+
+/// Looks up the hardfork of the block and checks whether the rules of a given
+/// hardfork are active in it.
+#[derive(Clone, Debug)]
+pub(crate) struct HardforkGadget<F> {
+    hardfork: Hardfork,
+    block_hardfork: Cell<F>,
+    is_before: LtGadget<F, 1>,
+}
+
+impl<F: Field> HardforkGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, hardfork: Hardfork) -> Self {
+        let block_hardfork = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::Hardfork.expr(),
+            None,
+            block_hardfork.expr(),
+        );
+        let is_before = LtGadget::construct(cb, block_hardfork.expr(), (hardfork as u64).expr());
+
+        Self {
+            hardfork,
+            block_hardfork,
+            is_before,
+        }
+    }
+
+    /// Construct the gadget for a step which is only valid once the rules of
+    /// the hardfork are active, like an opcode introduced by it.
+    pub(crate) fn construct_enabled(cb: &mut EVMConstraintBuilder<F>, hardfork: Hardfork) -> Self {
+        let gadget = Self::construct(cb, hardfork);
+        cb.require_equal(
+            "Hardfork is enabled in the block",
+            gadget.is_enabled(),
+            1.expr(),
+        );
+        gadget
+    }
+
+    pub(crate) fn is_enabled(&self) -> Expression<F> {
+        not::expr(self.is_before.expr())
+    }
+
+    /// Return true if the rules of the hardfork are active in the block.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block_hardfork: Hardfork,
+    ) -> Result<bool, Error> {
+        self.block_hardfork
+            .assign(region, offset, Value::known(F::from(block_hardfork as u64)))?;
+        self.is_before.assign(
+            region,
+            offset,
+            F::from(block_hardfork as u64),
+            F::from(self.hardfork as u64),
+        )?;
+
+        Ok(block_hardfork.is_enabled(self.hardfork))
+    }
+}
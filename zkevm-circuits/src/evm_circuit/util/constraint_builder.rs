@@ -367,6 +367,12 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.rw_counter_offset.clone()
     }
 
+    /// Increase the rw_counter_offset by `delta` under the current condition,
+    /// for rw operations that are not looked up one by one in this step.
+    pub(crate) fn rw_counter_offset_add(&mut self, delta: Expression<F>) {
+        self.rw_counter_offset = self.rw_counter_offset.clone() + self.condition_expr() * delta;
+    }
+
     pub(crate) fn program_counter_offset(&self) -> usize {
         self.program_counter_offset
     }
@@ -873,6 +879,56 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Created accounts
+
+    pub(crate) fn tx_created_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxCreatedAccount write",
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_created_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TxCreatedAccount read",
+            false.expr(),
+            Target::TxCreatedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
     // Tx Refund
 
     pub(crate) fn tx_refund_read(&mut self, tx_id: Expression<F>, value: Expression<F>) {
@@ -1022,6 +1078,58 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Transient Storage
+
+    pub(crate) fn transient_storage_read(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "transient_storage_read",
+            false.expr(),
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    pub(crate) fn transient_storage_write(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        tx_id: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TransientStorage write",
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
     // Call context
 
     pub(crate) fn call_context(
@@ -1304,6 +1412,151 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Sig Table
+
+    pub(crate) fn sig_table_lookup(
+        &mut self,
+        msg_hash_rlc: Expression<F>,
+        sig_v: Expression<F>,
+        sig_r_rlc: Expression<F>,
+        sig_s_rlc: Expression<F>,
+        recovered_addr: Expression<F>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sig table lookup",
+            Lookup::SigTable {
+                msg_hash_rlc,
+                sig_v,
+                sig_r_rlc,
+                sig_s_rlc,
+                recovered_addr,
+                is_valid,
+            },
+        );
+    }
+
+    // Sha256 Table
+
+    pub(crate) fn sha256_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sha256 lookup",
+            Lookup::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // Ripemd160 Table
+
+    pub(crate) fn ripemd160_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ripemd160 lookup",
+            Lookup::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // ModExp Table
+
+    pub(crate) fn modexp_table_lookup(
+        &mut self,
+        base_rlc: Expression<F>,
+        exponent_rlc: Expression<F>,
+        modulus_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "modexp lookup",
+            Lookup::ModExpTable {
+                base_rlc,
+                exponent_rlc,
+                modulus_rlc,
+                output_rlc,
+            },
+        );
+    }
+
+    // Ecc Table
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn ecc_table_lookup(
+        &mut self,
+        op_type: Expression<F>,
+        arg1_rlc: Expression<F>,
+        arg2_rlc: Expression<F>,
+        arg3_rlc: Expression<F>,
+        arg4_rlc: Expression<F>,
+        output1_rlc: Expression<F>,
+        output2_rlc: Expression<F>,
+        is_valid: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ecc lookup",
+            Lookup::EccTable {
+                op_type,
+                arg1_rlc,
+                arg2_rlc,
+                arg3_rlc,
+                arg4_rlc,
+                output1_rlc,
+                output2_rlc,
+                is_valid,
+            },
+        );
+    }
+
+    // Pairing Table
+
+    pub(crate) fn pairing_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        is_valid: Expression<F>,
+        output: Expression<F>,
+    ) {
+        self.add_lookup(
+            "pairing lookup",
+            Lookup::PairingTable {
+                input_rlc,
+                input_len,
+                is_valid,
+                output,
+            },
+        );
+    }
+
+    // Blake2f Table
+
+    pub(crate) fn blake2f_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "blake2f lookup",
+            Lookup::Blake2fTable {
+                input_rlc,
+                output_rlc,
+            },
+        );
+    }
+
     // Keccak Table
 
     pub(crate) fn keccak_table_lookup(
@@ -109,6 +109,25 @@ impl<F: Field> ByteSizeGadget<F> {
                 .map(|(i, cell)| i.expr() * cell.expr()),
         )
     }
+
+    /// Whether the word is zero, i.e. its byte-size is zero.
+    pub(crate) fn is_zero(&self) -> Expression<F> {
+        self.most_significant_nonzero_byte_index[0].expr()
+    }
+
+    /// The most significant non-zero byte of the word given by `values`, or
+    /// zero if the word is zero.
+    pub(crate) fn most_significant_nonzero_byte(
+        &self,
+        values: &[Expression<F>; N_BYTES_WORD],
+    ) -> Expression<F> {
+        sum::expr(
+            self.most_significant_nonzero_byte_index[1..]
+                .iter()
+                .zip(values.iter())
+                .map(|(index, value)| index.expr() * value.clone()),
+        )
+    }
 }
 
 #[cfg(test)]
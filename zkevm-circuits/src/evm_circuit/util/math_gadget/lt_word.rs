@@ -19,16 +19,40 @@ impl<F: Field> LtWordGadget<F> {
         lhs: &util::Word<F>,
         rhs: &util::Word<F>,
     ) -> Self {
-        let comparison_hi = ComparisonGadget::construct(
+        Self::construct_from_halves(
             cb,
+            from_bytes::expr(&lhs.cells[..16]),
             from_bytes::expr(&lhs.cells[16..]),
+            from_bytes::expr(&rhs.cells[..16]),
             from_bytes::expr(&rhs.cells[16..]),
-        );
-        let lt_lo = LtGadget::construct(
+        )
+    }
+
+    /// Construct the gadget comparing a 256-bit word with a constant.
+    pub(crate) fn construct_with_constant(
+        cb: &mut EVMConstraintBuilder<F>,
+        lhs: &util::Word<F>,
+        rhs: Word,
+    ) -> Self {
+        let (rhs_lo, rhs_hi) = split_u256(&rhs);
+        Self::construct_from_halves(
             cb,
             from_bytes::expr(&lhs.cells[..16]),
-            from_bytes::expr(&rhs.cells[..16]),
-        );
+            from_bytes::expr(&lhs.cells[16..]),
+            Expression::Constant(F::from_u128(rhs_lo.as_u128())),
+            Expression::Constant(F::from_u128(rhs_hi.as_u128())),
+        )
+    }
+
+    fn construct_from_halves(
+        cb: &mut EVMConstraintBuilder<F>,
+        lhs_lo: Expression<F>,
+        lhs_hi: Expression<F>,
+        rhs_lo: Expression<F>,
+        rhs_hi: Expression<F>,
+    ) -> Self {
+        let comparison_hi = ComparisonGadget::construct(cb, lhs_hi, rhs_hi);
+        let lt_lo = LtGadget::construct(cb, lhs_lo, rhs_lo);
         Self {
             comparison_hi,
             lt_lo,
@@ -111,6 +135,36 @@ mod tests {
         }
     }
 
+    #[derive(Clone)]
+    /// LtWordConstantTestContainer: require(a < WORD_HIGH_MAX)
+    struct LtWordConstantTestContainer<F> {
+        ltword_gadget: LtWordGadget<F>,
+        a: util::Word<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for LtWordConstantTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_word_rlc();
+            let ltword_gadget = LtWordGadget::<F>::construct_with_constant(cb, &a, WORD_HIGH_MAX);
+            cb.require_equal("a < WORD_HIGH_MAX", ltword_gadget.expr(), 1.expr());
+            LtWordConstantTestContainer { ltword_gadget, a }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let a = witnesses[0];
+            let offset = 0;
+
+            self.a.assign(region, offset, Some(a.to_le_bytes()))?;
+            self.ltword_gadget.assign(region, 0, a, WORD_HIGH_MAX)?;
+
+            Ok(())
+        }
+    }
+
     #[test]
     fn test_ltword_expect() {
         try_test!(
@@ -155,4 +209,12 @@ mod tests {
             false,
         );
     }
+
+    #[test]
+    fn test_ltword_constant() {
+        try_test!(LtWordConstantTestContainer<Fr>, vec![Word::from(0)], true);
+        try_test!(LtWordConstantTestContainer<Fr>, vec![WORD_LOW_MAX], true);
+        try_test!(LtWordConstantTestContainer<Fr>, vec![WORD_HIGH_MAX], false,);
+        try_test!(LtWordConstantTestContainer<Fr>, vec![Word::MAX], false);
+    }
 }
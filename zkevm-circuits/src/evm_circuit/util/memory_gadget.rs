@@ -1,12 +1,19 @@
 use super::{constraint_builder::ConstrainBuilderCommon, CachedRegion};
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
+        param::{
+            MAX_EXPANDED_MEMORY_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS,
+            N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+        },
         util::{
+            and,
             constraint_builder::EVMConstraintBuilder,
             from_bytes,
-            math_gadget::{ConstantDivisionGadget, IsZeroGadget, MinMaxGadget, RangeCheckGadget},
-            select, sum, Cell, CellType, MemoryAddress,
+            math_gadget::{
+                AddWordsGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                RangeCheckGadget,
+            },
+            not, select, sum, Cell, CellType, MemoryAddress, Word,
         },
     },
     util::Expr,
@@ -20,16 +27,7 @@ use halo2_proofs::{
 
 /// Decodes the usable part of an address stored in a Word
 pub(crate) mod address_low {
-    use crate::evm_circuit::{
-        param::N_BYTES_MEMORY_ADDRESS,
-        util::{from_bytes, Word},
-    };
-    use eth_types::Field;
-    use halo2_proofs::plonk::Expression;
-
-    pub(crate) fn expr<F: Field>(address: &Word<F>) -> Expression<F> {
-        from_bytes::expr(&address.cells[..N_BYTES_MEMORY_ADDRESS])
-    }
+    use crate::evm_circuit::param::N_BYTES_MEMORY_ADDRESS;
 
     pub(crate) fn value(address: [u8; 32]) -> u64 {
         let mut bytes = [0; 8];
@@ -38,25 +36,6 @@ pub(crate) mod address_low {
     }
 }
 
-/// The sum of bytes of the address that are unused for most calculations on the
-/// address
-pub(crate) mod address_high {
-    use crate::evm_circuit::{
-        param::N_BYTES_MEMORY_ADDRESS,
-        util::{sum, Word},
-    };
-    use eth_types::Field;
-    use halo2_proofs::plonk::Expression;
-
-    pub(crate) fn expr<F: Field>(address: &Word<F>) -> Expression<F> {
-        sum::expr(&address.cells[N_BYTES_MEMORY_ADDRESS..])
-    }
-
-    pub(crate) fn value<F: Field>(address: [u8; 32]) -> F {
-        sum::value::<F>(&address[N_BYTES_MEMORY_ADDRESS..])
-    }
-}
-
 /// Convert the dynamic memory offset and length from random linear combination
 /// to integer. It handles the "no expansion" feature by setting the
 /// `memory_offset_bytes` to zero when `memory_length` is zero. In this case,
@@ -160,6 +139,130 @@ impl<F: Field> MemoryAddressGadget<F> {
     }
 }
 
+/// Convert the memory offset and length of a memory access which could be
+/// out of range. Instead of the 5 bytes of [`MemoryAddressGadget`], the full
+/// words are kept so that the address overflow can be detected, which is
+/// either the sum `offset + length` overflows an u64, or the memory size after
+/// expansion is larger than `MAX_EXPANDED_MEMORY_ADDRESS`. The address is zero
+/// when the length is zero or the address overflows.
+#[derive(Clone, Debug)]
+pub(crate) struct MemoryExpandedAddressGadget<F> {
+    length_is_zero: IsZeroGadget<F>,
+    offset_length_sum: AddWordsGadget<F, 2, false>,
+    sum_lt_cap: LtGadget<F, N_BYTES_U64>,
+    sum_within_u64: IsZeroGadget<F>,
+}
+
+impl<F: Field> MemoryExpandedAddressGadget<F> {
+    pub(crate) fn construct_self(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let offset = cb.query_word_rlc();
+        let length = cb.query_word_rlc();
+        let sum = cb.query_word_rlc();
+        Self::construct(cb, offset, length, sum)
+    }
+
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        offset: Word<F>,
+        length: Word<F>,
+        sum: Word<F>,
+    ) -> Self {
+        let length_is_zero = IsZeroGadget::construct(cb, sum::expr(&length.cells));
+        let offset_length_sum = AddWordsGadget::construct(cb, [offset, length], sum);
+
+        let sum_cells = &offset_length_sum.sum().cells;
+        let sum_lt_cap = LtGadget::construct(
+            cb,
+            from_bytes::expr(&sum_cells[..N_BYTES_U64]),
+            (MAX_EXPANDED_MEMORY_ADDRESS + 1).expr(),
+        );
+        let sum_within_u64 = IsZeroGadget::construct(cb, sum::expr(&sum_cells[N_BYTES_U64..]));
+
+        Self {
+            length_is_zero,
+            offset_length_sum,
+            sum_lt_cap,
+            sum_within_u64,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        memory_offset: U256,
+        memory_length: U256,
+    ) -> Result<u64, Error> {
+        self.length_is_zero
+            .assign(region, offset, sum::value(&memory_length.to_le_bytes()))?;
+
+        let (sum, sum_word_overflow) = memory_offset.overflowing_add(memory_length);
+        self.offset_length_sum
+            .assign(region, offset, [memory_offset, memory_length], sum)?;
+
+        let sum_bytes = sum.to_le_bytes();
+        self.sum_lt_cap.assign(
+            region,
+            offset,
+            F::from(sum.low_u64()),
+            F::from(MAX_EXPANDED_MEMORY_ADDRESS + 1),
+        )?;
+        self.sum_within_u64
+            .assign(region, offset, sum::value(&sum_bytes[N_BYTES_U64..]))?;
+
+        let is_overflow = sum_word_overflow
+            || sum_bytes[N_BYTES_U64..].iter().any(|byte| *byte != 0)
+            || sum.low_u64() > MAX_EXPANDED_MEMORY_ADDRESS;
+        Ok(if memory_length.is_zero() || is_overflow {
+            0
+        } else {
+            sum.low_u64()
+        })
+    }
+
+    pub(crate) fn offset_rlc(&self) -> Expression<F> {
+        self.offset_length_sum.addends()[0].expr()
+    }
+
+    pub(crate) fn length_rlc(&self) -> Expression<F> {
+        self.offset_length_sum.addends()[1].expr()
+    }
+
+    /// The memory length, which is zero if the memory address overflows.
+    pub(crate) fn length(&self) -> Expression<F> {
+        select::expr(
+            self.within_range(),
+            from_bytes::expr(&self.offset_length_sum.addends()[1].cells[..N_BYTES_U64]),
+            0.expr(),
+        )
+    }
+
+    /// The memory address after expansion, which is zero when the length is
+    /// zero or the address overflows.
+    pub(crate) fn address(&self) -> Expression<F> {
+        select::expr(
+            self.length_is_zero.expr() + self.overflow(),
+            0.expr(),
+            from_bytes::expr(&self.offset_length_sum.sum().cells[..N_BYTES_U64]),
+        )
+    }
+
+    pub(crate) fn overflow(&self) -> Expression<F> {
+        and::expr([
+            not::expr(self.length_is_zero.expr()),
+            not::expr(self.within_range()),
+        ])
+    }
+
+    fn within_range(&self) -> Expression<F> {
+        and::expr([
+            self.sum_lt_cap.expr(),
+            self.sum_within_u64.expr(),
+            not::expr(self.offset_length_sum.carry().as_ref().unwrap().expr()),
+        ])
+    }
+}
+
 /// Calculates the memory size in words required for a memory access at the
 /// specified address.
 /// `memory_word_size = ceil(address/32) = floor((address + 31) / 32)`
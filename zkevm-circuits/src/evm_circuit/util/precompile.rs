@@ -2,10 +2,12 @@ use bus_mapping::precompile::PrecompileCalls;
 use eth_types::Field;
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
+use strum::IntoEnumIterator;
 
 use super::{
     constraint_builder::EVMConstraintBuilder, math_gadget::BinaryNumberGadget, CachedRegion,
 };
+use crate::evm_circuit::step::ExecutionState;
 
 #[derive(Clone, Debug)]
 pub struct PrecompileGadget<F> {
@@ -13,20 +15,19 @@ pub struct PrecompileGadget<F> {
 }
 
 impl<F: Field> PrecompileGadget<F> {
-    #[allow(clippy::too_many_arguments)]
     pub(crate) fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        _is_success: Expression<F>,
         callee_address: Expression<F>,
-        _caller_id: Expression<F>,
-        _cd_offset: Expression<F>,
-        _cd_length: Expression<F>,
-        _rd_offset: Expression<F>,
-        _rd_length: Expression<F>,
     ) -> Self {
         let address = BinaryNumberGadget::construct(cb, callee_address.expr());
 
-        // match the `address.value_equals` `PrecompileCalls` variant and execute the code
+        // The precompile call is executed in the next step, whose execution state
+        // is determined by the callee address.
+        for precompile in PrecompileCalls::iter() {
+            cb.condition(address.value_equals(precompile), |cb| {
+                cb.require_next_state(ExecutionState::from(precompile));
+            });
+        }
 
         Self { address }
     }
@@ -0,0 +1,812 @@
+//! The MODEXP circuit implementation.
+//!
+//! The operands are split into `num_limbs` byte limbs. An exponentiation
+//! reduces the base modulo the modulus, then squares the accumulator and
+//! multiplies it by the reduced base or one for each bit of the exponent, most
+//! significant first. Each of these steps proves `a * b = q * m + r` with
+//! `r < m` on `2 * num_limbs` rows: `a` and `m` are laid out horizontally,
+//! while `b`, `q` and `r` are laid out vertically so that each row sums the
+//! products of one column of the schoolbook multiplications, with a carry to
+//! the next row. The steps aren't constrained for a zero modulus, whose result
+//! is zero.
+mod modexp_witness;
+mod param;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::{ModExpCircuit as TestModExpCircuit, ModExpCircuitParams};
+
+pub use param::DEFAULT_NUM_LIMBS;
+
+use std::marker::PhantomData;
+
+use self::{
+    modexp_witness::{multi_modexp, ModExpRow, RowPosition},
+    param::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{byte_table::ByteTable, LookupTable, ModExpTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::{Field, Word};
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+/// Returns the unusable rows of the MODEXP circuit with `num_limbs` limbs.
+pub(crate) fn modexp_unusable_rows(num_limbs: usize) -> usize {
+    // The limbs of `r` and `m_vertical` are queried at `num_limbs + 1`
+    // distinct rotations.
+    std::cmp::max(3, num_limbs + 1) + 3
+}
+
+/// ModExpConfig
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<F> {
+    num_limbs: usize,
+    q_enable: Column<Fixed>,
+    q_event_first: Column<Fixed>,
+    q_step_first: Column<Fixed>,
+    q_step_last: Column<Fixed>,
+    q_low: Column<Fixed>,
+    q_low_last: Column<Fixed>,
+    q_high: Column<Fixed>,
+    q_reduce: Column<Fixed>,
+    q_first_square: Column<Fixed>,
+    q_square: Column<Fixed>,
+    q_multiply: Column<Fixed>,
+    q_bits: Column<Fixed>,
+    q_byte_first: Column<Fixed>,
+    q_byte_last: Column<Fixed>,
+    q_first_byte: Column<Fixed>,
+    a: Vec<Column<Advice>>,
+    m: Vec<Column<Advice>>,
+    b: Column<Advice>,
+    q: Column<Advice>,
+    r: Column<Advice>,
+    carry_lo: Column<Advice>,
+    carry_hi: Column<Advice>,
+    m_vertical: Column<Advice>,
+    diff: Column<Advice>,
+    borrow: Column<Advice>,
+    base: Column<Advice>,
+    bit: Column<Advice>,
+    byte_acc: Column<Advice>,
+    exponent_acc: Column<Advice>,
+    r_rlc: Column<Advice>,
+    is_m_zero: Column<Advice>,
+    m_sum_inv: Column<Advice>,
+    /// The columns for other circuits to lookup the modular exponentiations
+    pub modexp_table: ModExpTable,
+    byte_table: ByteTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs<F: Field> {
+    /// Number of byte limbs of the base, exponent and modulus
+    pub num_limbs: usize,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// ByteTable
+    pub byte_table: ByteTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
+    type ConfigArgs = ModExpCircuitConfigArgs<F>;
+
+    /// Return a new ModExpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            num_limbs,
+            modexp_table,
+            byte_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        assert!(
+            (1..=MAX_NUM_LIMBS).contains(&num_limbs),
+            "the number of limbs must be between 1 and {}",
+            MAX_NUM_LIMBS
+        );
+        let q_enable = meta.fixed_column();
+        let q_event_first = meta.fixed_column();
+        let q_step_first = meta.fixed_column();
+        let q_step_last = meta.fixed_column();
+        let q_low = meta.fixed_column();
+        let q_low_last = meta.fixed_column();
+        let q_high = meta.fixed_column();
+        let q_reduce = meta.fixed_column();
+        let q_first_square = meta.fixed_column();
+        let q_square = meta.fixed_column();
+        let q_multiply = meta.fixed_column();
+        let q_bits = meta.fixed_column();
+        let q_byte_first = meta.fixed_column();
+        let q_byte_last = meta.fixed_column();
+        let q_first_byte = meta.fixed_column();
+        let a = (0..num_limbs)
+            .map(|_| meta.advice_column())
+            .collect::<Vec<_>>();
+        let m = (0..num_limbs)
+            .map(|_| meta.advice_column())
+            .collect::<Vec<_>>();
+        let b = meta.advice_column();
+        let q = meta.advice_column();
+        let r = meta.advice_column();
+        let carry_lo = meta.advice_column();
+        let carry_hi = meta.advice_column();
+        let m_vertical = meta.advice_column();
+        let diff = meta.advice_column();
+        let borrow = meta.advice_column();
+        let base = meta.advice_column();
+        let bit = meta.advice_column();
+        let byte_acc = meta.advice_column();
+        let exponent_acc = meta.advice_column_in(SecondPhase);
+        let r_rlc = meta.advice_column_in(SecondPhase);
+        let is_m_zero = meta.advice_column();
+        let m_sum_inv = meta.advice_column();
+
+        let rot_step = num_rows_per_step(num_limbs) as i32;
+        let query_limbs = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>]| {
+            columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation::cur()))
+                .collect::<Vec<_>>()
+        };
+        let query_fixed = |meta: &mut VirtualCells<F>, column: Column<Fixed>| {
+            meta.query_fixed(column, Rotation::cur())
+        };
+        // The carries can be negative and are stored with an offset.
+        let carry = |meta: &mut VirtualCells<F>, rot: i32| {
+            meta.query_advice(carry_lo, Rotation(rot))
+                + meta.query_advice(carry_hi, Rotation(rot)) * 256.expr()
+                - CARRY_OFFSET.expr()
+        };
+        let limbs_rlc = |limbs: &[Expression<F>]| rlc::expr(limbs, challenges.keccak_input());
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [borrow, bit] {
+                cb.require_boolean("boolean", meta.query_advice(column, Rotation::cur()));
+            }
+            cb.gate(query_fixed(meta, q_enable))
+        });
+
+        meta.create_gate("is_m_zero", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let m_sum = sum::expr(query_limbs(meta, &m));
+            let is_m_zero = meta.query_advice(is_m_zero, Rotation::cur());
+            cb.require_zero("m == 0 if is_m_zero", is_m_zero.clone() * m_sum.clone());
+            cb.require_equal(
+                "is_m_zero == 1 if m == 0",
+                is_m_zero,
+                1.expr() - m_sum * meta.query_advice(m_sum_inv, Rotation::cur()),
+            );
+            cb.gate(query_fixed(meta, q_enable))
+        });
+
+        meta.create_gate("a * b = q * m + r", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // The rows before the limbs of a step are the zero higher limbs of
+            // the previous step, or the dummy rows.
+            let product = |meta: &mut VirtualCells<F>,
+                           horizontal: &[Column<Advice>],
+                           vertical: Column<Advice>| {
+                sum::expr(
+                    query_limbs(meta, horizontal)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, limb)| limb * meta.query_advice(vertical, Rotation(-(i as i32)))),
+                )
+            };
+            let ab = product(meta, &a, b);
+            let qm = product(meta, &m, q);
+            cb.require_zero(
+                "column of a * b - q * m - r with carries",
+                not::expr(meta.query_advice(is_m_zero, Rotation::cur()))
+                    * (ab - qm - meta.query_advice(r, Rotation::cur())
+                        + not::expr(query_fixed(meta, q_step_first)) * carry(meta, -1)
+                        - carry(meta, 0) * 256.expr()),
+            );
+            cb.gate(query_fixed(meta, q_enable))
+        });
+
+        meta.create_gate("last carry", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero("no carry out of the products", carry(meta, 0));
+            cb.gate(query_fixed(meta, q_step_last))
+        });
+
+        meta.create_gate("higher limbs", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [b, q, r] {
+                cb.require_zero(
+                    "higher limb is zero",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+            cb.gate(query_fixed(meta, q_high))
+        });
+
+        // m - r - 1 is computed limb by limb, and is non-negative iff r < m.
+        meta.create_gate("r < m", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_step_first = query_fixed(meta, q_step_first);
+            cb.require_zero(
+                "diff = m - r - 1 with borrows",
+                not::expr(meta.query_advice(is_m_zero, Rotation::cur()))
+                    * (meta.query_advice(m_vertical, Rotation::cur())
+                        - meta.query_advice(r, Rotation::cur())
+                        - q_step_first.clone()
+                        - not::expr(q_step_first) * meta.query_advice(borrow, Rotation::prev())
+                        + meta.query_advice(borrow, Rotation::cur()) * 256.expr()
+                        - meta.query_advice(diff, Rotation::cur())),
+            );
+            cb.gate(query_fixed(meta, q_low))
+        });
+
+        meta.create_gate("no last borrow", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "m - r - 1 >= 0",
+                not::expr(meta.query_advice(is_m_zero, Rotation::cur()))
+                    * meta.query_advice(borrow, Rotation::cur()),
+            );
+            cb.gate(query_fixed(meta, q_low_last))
+        });
+
+        meta.create_gate("step values", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in a.iter().chain([&bit]) {
+                cb.require_equal(
+                    "value unchanged in the step",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(query_fixed(meta, q_enable) * not::expr(query_fixed(meta, q_step_first)))
+        });
+
+        meta.create_gate("exponentiation values", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in m.iter().chain([&modexp_table.base_rlc]) {
+                cb.require_equal(
+                    "value unchanged in the exponentiation",
+                    meta.query_advice(*column, Rotation::cur()),
+                    meta.query_advice(*column, Rotation::prev()),
+                );
+            }
+            cb.gate(query_fixed(meta, q_enable) * not::expr(query_fixed(meta, q_event_first)))
+        });
+
+        meta.create_gate("start of the exponentiation", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "base rlc",
+                meta.query_advice(modexp_table.base_rlc, Rotation::cur()),
+                limbs_rlc(&query_limbs(meta, &a)),
+            );
+            for (i, limb) in query_limbs(meta, &m).into_iter().enumerate() {
+                cb.require_equal(
+                    "vertical limbs of m",
+                    meta.query_advice(m_vertical, Rotation(i as i32)),
+                    limb,
+                );
+            }
+            cb.gate(query_fixed(meta, q_event_first))
+        });
+
+        meta.create_gate("reduce the base", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "b == 1",
+                meta.query_advice(b, Rotation::cur()),
+                query_fixed(meta, q_step_first),
+            );
+            cb.require_equal(
+                "reduced base == r",
+                meta.query_advice(base, Rotation::cur()),
+                meta.query_advice(r, Rotation::cur()),
+            );
+            cb.gate(query_fixed(meta, q_reduce))
+        });
+
+        meta.create_gate("carry the reduced base", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [base, m_vertical] {
+                cb.require_equal(
+                    "value unchanged from the previous step",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation(-rot_step)),
+                );
+            }
+            cb.gate(
+                query_fixed(meta, q_first_square)
+                    + query_fixed(meta, q_square)
+                    + query_fixed(meta, q_multiply),
+            )
+        });
+
+        meta.create_gate("first square", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // The accumulator starts from one.
+            let q_step_first = query_fixed(meta, q_step_first);
+            cb.require_equal(
+                "b == 1",
+                meta.query_advice(b, Rotation::cur()),
+                q_step_first.clone(),
+            );
+            cb.condition(q_step_first, |cb| {
+                for (i, limb) in query_limbs(meta, &a).into_iter().enumerate() {
+                    cb.require_equal("a == 1", limb, ((i == 0) as u64).expr());
+                }
+            });
+            cb.gate(query_fixed(meta, q_first_square))
+        });
+
+        meta.create_gate("accumulator from the previous step", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (i, limb) in query_limbs(meta, &a).into_iter().enumerate() {
+                cb.require_equal(
+                    "a == previous r",
+                    limb,
+                    meta.query_advice(r, Rotation(i as i32 - rot_step)),
+                );
+            }
+            cb.gate(
+                (query_fixed(meta, q_square) + query_fixed(meta, q_multiply))
+                    * query_fixed(meta, q_step_first),
+            )
+        });
+
+        meta.create_gate("square", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "b == previous r",
+                meta.query_advice(b, Rotation::cur()),
+                meta.query_advice(r, Rotation(-rot_step)),
+            );
+            cb.gate(query_fixed(meta, q_square))
+        });
+
+        meta.create_gate("multiply", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "b == bit ? reduced base : 1",
+                meta.query_advice(b, Rotation::cur()),
+                select::expr(
+                    meta.query_advice(bit, Rotation::cur()),
+                    meta.query_advice(base, Rotation::cur()),
+                    query_fixed(meta, q_step_first),
+                ),
+            );
+            cb.gate(query_fixed(meta, q_multiply))
+        });
+
+        // The bits of the exponent are accumulated into bytes, and the bytes
+        // into the RLC of the exponent.
+        meta.create_gate("exponent bits", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let rot_prev_bit = Rotation(-2 * rot_step);
+            let byte = meta.query_advice(byte_acc, Rotation::cur());
+            cb.require_equal(
+                "byte_acc = prev_byte_acc * 2 + bit",
+                byte.clone(),
+                not::expr(query_fixed(meta, q_byte_first))
+                    * meta.query_advice(byte_acc, rot_prev_bit)
+                    * 2.expr()
+                    + meta.query_advice(bit, Rotation::cur()),
+            );
+            let prev_exponent_acc = not::expr(query_fixed(meta, q_first_byte))
+                * meta.query_advice(exponent_acc, rot_prev_bit);
+            cb.require_equal(
+                "exponent_acc = prev_exponent_acc * r + byte_acc at the end of a byte",
+                meta.query_advice(exponent_acc, Rotation::cur()),
+                select::expr(
+                    query_fixed(meta, q_byte_last),
+                    prev_exponent_acc.clone() * challenges.keccak_input() + byte,
+                    prev_exponent_acc,
+                ),
+            );
+            cb.gate(query_fixed(meta, q_bits))
+        });
+
+        meta.create_gate("r rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "r_rlc = r + next_r_rlc * r",
+                meta.query_advice(r_rlc, Rotation::cur()),
+                meta.query_advice(r, Rotation::cur())
+                    + not::expr(query_fixed(meta, q_step_last))
+                        * meta.query_advice(r_rlc, Rotation::next())
+                        * challenges.keccak_input(),
+            );
+            cb.gate(query_fixed(meta, q_enable))
+        });
+
+        meta.create_gate("modexp table", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "exponent rlc",
+                meta.query_advice(modexp_table.exponent_rlc, Rotation::cur()),
+                meta.query_advice(exponent_acc, Rotation::cur()),
+            );
+            cb.require_equal(
+                "modulus rlc",
+                meta.query_advice(modexp_table.modulus_rlc, Rotation::cur()),
+                limbs_rlc(&query_limbs(meta, &m)),
+            );
+            cb.require_equal(
+                "output rlc is the last r, or zero for a zero modulus",
+                meta.query_advice(modexp_table.output_rlc, Rotation::cur()),
+                not::expr(meta.query_advice(is_m_zero, Rotation::cur()))
+                    * meta.query_advice(r_rlc, Rotation::cur()),
+            );
+            cb.gate(meta.query_fixed(modexp_table.q_enable, Rotation::cur()))
+        });
+
+        for (name, column) in [
+            ("q", q),
+            ("r", r),
+            ("diff", diff),
+            ("carry_lo", carry_lo),
+            ("carry_hi", carry_hi),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let value =
+                    query_fixed(meta, q_enable) * meta.query_advice(column, Rotation::cur());
+                vec![(value, byte_table.table_exprs(meta)[0].clone())]
+            });
+        }
+
+        ModExpCircuitConfig {
+            num_limbs,
+            q_enable,
+            q_event_first,
+            q_step_first,
+            q_step_last,
+            q_low,
+            q_low_last,
+            q_high,
+            q_reduce,
+            q_first_square,
+            q_square,
+            q_multiply,
+            q_bits,
+            q_byte_first,
+            q_byte_last,
+            q_first_byte,
+            a,
+            m,
+            b,
+            q,
+            r,
+            carry_lo,
+            carry_hi,
+            m_vertical,
+            diff,
+            borrow,
+            base,
+            bit,
+            byte_acc,
+            exponent_acc,
+            r_rlc,
+            is_m_zero,
+            m_sum_inv,
+            modexp_table,
+            byte_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> ModExpCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[ModExpRow<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign modexp rows",
+            |mut region| {
+                for (offset, modexp_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, modexp_row)?;
+                }
+                self.modexp_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &ModExpRow<F>,
+    ) -> Result<(), Error> {
+        let num_limbs = self.num_limbs;
+        let is = |f: &dyn Fn(usize, usize) -> bool| {
+            row.position
+                .map_or(false, |RowPosition { step, idx }| f(step, idx))
+        };
+        // Index of the exponent bit of the multiply steps
+        let bit_idx = |step: usize| (step >= 2 && step % 2 == 0).then(|| step / 2 - 1);
+        let is_bits =
+            |f: fn(usize) -> bool| is(&|step, idx| idx == 0 && bit_idx(step).map_or(false, f));
+
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, row.position.is_some()),
+            (
+                "q_event_first",
+                self.q_event_first,
+                is(&|step, idx| step == 0 && idx == 0),
+            ),
+            ("q_step_first", self.q_step_first, is(&|_, idx| idx == 0)),
+            (
+                "q_step_last",
+                self.q_step_last,
+                is(&|_, idx| idx == num_rows_per_step(num_limbs) - 1),
+            ),
+            ("q_low", self.q_low, is(&|_, idx| idx < num_limbs)),
+            (
+                "q_low_last",
+                self.q_low_last,
+                is(&|_, idx| idx == num_limbs - 1),
+            ),
+            // The dummy rows are the zero higher limbs before the first step.
+            (
+                "q_high",
+                self.q_high,
+                row.position
+                    .map_or(true, |position| position.idx >= num_limbs),
+            ),
+            ("q_reduce", self.q_reduce, is(&|step, _| step == 0)),
+            (
+                "q_first_square",
+                self.q_first_square,
+                is(&|step, _| step == 1),
+            ),
+            (
+                "q_square",
+                self.q_square,
+                is(&|step, _| step >= 3 && step % 2 == 1),
+            ),
+            (
+                "q_multiply",
+                self.q_multiply,
+                is(&|step, _| bit_idx(step).is_some()),
+            ),
+            ("q_bits", self.q_bits, is_bits(|_| true)),
+            (
+                "q_byte_first",
+                self.q_byte_first,
+                is_bits(|bit_idx| bit_idx % NUM_BITS_PER_BYTE == 0),
+            ),
+            (
+                "q_byte_last",
+                self.q_byte_last,
+                is_bits(|bit_idx| bit_idx % NUM_BITS_PER_BYTE == NUM_BITS_PER_BYTE - 1),
+            ),
+            (
+                "q_first_byte",
+                self.q_first_byte,
+                is_bits(|bit_idx| bit_idx < NUM_BITS_PER_BYTE),
+            ),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        self.modexp_table.assign_row(
+            region,
+            offset,
+            is(&|step, idx| step == num_steps(num_limbs) - 1 && idx == 0),
+            [
+                row.base_rlc,
+                row.exponent_rlc,
+                row.modulus_rlc,
+                row.output_rlc,
+            ],
+        )?;
+
+        // Horizontal limbs
+        for (name, columns, limbs) in [("a", &self.a, &row.a), ("m", &self.m, &row.m)] {
+            for (idx, (column, limb)) in columns.iter().zip(limbs.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*limb as u64)),
+                )?;
+            }
+        }
+
+        let carry = (row.carry + CARRY_OFFSET as i64) as u64;
+        let m_sum = row.m.iter().map(|limb| *limb as u64).sum::<u64>();
+        for (name, column, value) in [
+            ("b", self.b, F::from(row.b as u64)),
+            ("q", self.q, F::from(row.q as u64)),
+            ("r", self.r, F::from(row.r as u64)),
+            ("carry_lo", self.carry_lo, F::from(carry & 0xff)),
+            ("carry_hi", self.carry_hi, F::from(carry >> 8)),
+            (
+                "m_vertical",
+                self.m_vertical,
+                F::from(row.m_vertical as u64),
+            ),
+            ("diff", self.diff, F::from(row.diff as u64)),
+            ("borrow", self.borrow, F::from(row.borrow as u64)),
+            ("base", self.base, F::from(row.base as u64)),
+            ("bit", self.bit, F::from(row.bit as u64)),
+            ("byte_acc", self.byte_acc, F::from(row.byte_acc as u64)),
+            ("is_m_zero", self.is_m_zero, F::from((m_sum == 0) as u64)),
+            (
+                "m_sum_inv",
+                self.m_sum_inv,
+                F::from(m_sum).invert().unwrap_or(F::ZERO),
+            ),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        for (name, column, value) in [
+            ("exponent_acc", self.exponent_acc, row.exponent_acc),
+            ("r_rlc", self.r_rlc, row.r_rlc),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "MODEXP_q_enable", self.q_enable);
+        region.name_column(|| "MODEXP_q_event_first", self.q_event_first);
+        region.name_column(|| "MODEXP_q_step_first", self.q_step_first);
+        region.name_column(|| "MODEXP_q_step_last", self.q_step_last);
+        region.name_column(|| "MODEXP_q_low", self.q_low);
+        region.name_column(|| "MODEXP_q_high", self.q_high);
+        region.name_column(|| "MODEXP_q_reduce", self.q_reduce);
+        region.name_column(|| "MODEXP_q_square", self.q_square);
+        region.name_column(|| "MODEXP_q_multiply", self.q_multiply);
+        region.name_column(|| "MODEXP_b", self.b);
+        region.name_column(|| "MODEXP_q", self.q);
+        region.name_column(|| "MODEXP_r", self.r);
+        region.name_column(|| "MODEXP_carry_lo", self.carry_lo);
+        region.name_column(|| "MODEXP_carry_hi", self.carry_hi);
+        region.name_column(|| "MODEXP_m_vertical", self.m_vertical);
+        region.name_column(|| "MODEXP_diff", self.diff);
+        region.name_column(|| "MODEXP_borrow", self.borrow);
+        region.name_column(|| "MODEXP_base", self.base);
+        region.name_column(|| "MODEXP_bit", self.bit);
+        region.name_column(|| "MODEXP_byte_acc", self.byte_acc);
+        region.name_column(|| "MODEXP_exponent_acc", self.exponent_acc);
+        region.name_column(|| "MODEXP_r_rlc", self.r_rlc);
+        region.name_column(|| "MODEXP_is_m_zero", self.is_m_zero);
+        for (idx, column) in self.a.iter().enumerate() {
+            region.name_column(|| format!("MODEXP_a_{}", idx), *column);
+        }
+        for (idx, column) in self.m.iter().enumerate() {
+            region.name_column(|| format!("MODEXP_m_{}", idx), *column);
+        }
+    }
+}
+
+/// ModExpCircuit
+#[derive(Clone, Debug)]
+pub struct ModExpCircuit<F: Field> {
+    inputs: Vec<[Word; 3]>,
+    num_limbs: usize,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for ModExpCircuit<F> {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_LIMBS, 0, vec![])
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        modexp_unusable_rows(DEFAULT_NUM_LIMBS)
+    }
+
+    /// The `block.circuits_params.max_modexp_rows` parameter, when non-zero,
+    /// sets up the circuit to support a fixed number of exponentiations,
+    /// independently of the ones required by `inputs`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            DEFAULT_NUM_LIMBS,
+            block.circuits_params.max_modexp_rows,
+            block.modexp_inputs.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            num_rows_per_step(DEFAULT_NUM_LIMBS)
+                + block.modexp_inputs.len() * num_rows_per_exponentiation(DEFAULT_NUM_LIMBS),
+            block.circuits_params.max_modexp_rows,
+        )
+    }
+
+    /// Make the assignments to the ModExpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges)?;
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_limbs: usize, num_rows: usize, inputs: Vec<[Word; 3]>) -> Self {
+        ModExpCircuit {
+            inputs,
+            num_limbs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of exponentiations that can be done in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract the unusable rows and the dummy rows
+            Some(
+                self.num_rows.saturating_sub(
+                    modexp_unusable_rows(self.num_limbs) + num_rows_per_step(self.num_limbs),
+                ) / num_rows_per_exponentiation(self.num_limbs),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the operands of the exponentiations
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Result<Vec<ModExpRow<F>>, Error> {
+        multi_modexp(&self.inputs, self.num_limbs, challenges, self.capacity())
+    }
+}
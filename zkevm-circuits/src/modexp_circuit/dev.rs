@@ -0,0 +1,76 @@
+pub use super::ModExpCircuit;
+
+use crate::{
+    modexp_circuit::{ModExpCircuitConfig, ModExpCircuitConfigArgs, DEFAULT_NUM_LIMBS},
+    table::{byte_table::ByteTable, ModExpTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+/// MODEXP Circuit configuration parameters
+pub struct ModExpCircuitParams {
+    /// Number of byte limbs of the base, exponent and modulus
+    pub num_limbs: usize,
+}
+
+impl Default for ModExpCircuitParams {
+    fn default() -> Self {
+        Self {
+            num_limbs: DEFAULT_NUM_LIMBS,
+        }
+    }
+}
+
+impl<F: Field> Circuit<F> for ModExpCircuit<F> {
+    type Config = (ModExpCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ModExpCircuitParams;
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.num_limbs, self.num_rows, vec![])
+    }
+
+    fn params(&self) -> Self::Params {
+        ModExpCircuitParams {
+            num_limbs: self.num_limbs,
+        }
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
+        let modexp_table = ModExpTable::construct(meta);
+        let byte_table = ByteTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            ModExpCircuitConfig::new(
+                meta,
+                ModExpCircuitConfigArgs {
+                    num_limbs: params.num_limbs,
+                    modexp_table,
+                    byte_table,
+                    challenges,
+                },
+            )
+        };
+        (config, challenges)
+    }
+
+    fn configure(_meta: &mut ConstraintSystem<F>) -> Self::Config {
+        unreachable!();
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        config.byte_table.load(&mut layouter)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
@@ -0,0 +1,270 @@
+use super::param::*;
+use crate::{table::ModExpTable, util::Challenges};
+use eth_types::{Field, ToLittleEndian, Word};
+use halo2_proofs::{circuit::Value, plonk::Error};
+use num_bigint::BigUint;
+
+/// Position of a row in the steps of an exponentiation.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RowPosition {
+    /// Index of the step in the exponentiation
+    pub(crate) step: usize,
+    /// Index of the row in the step, i.e. of the limb of the vertical values
+    pub(crate) idx: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ModExpRow<F> {
+    /// Position of the row, which is `None` on the dummy rows before the first
+    /// exponentiation.
+    pub(crate) position: Option<RowPosition>,
+    pub(crate) a: Vec<u8>,
+    pub(crate) m: Vec<u8>,
+    pub(crate) b: u8,
+    pub(crate) q: u8,
+    pub(crate) r: u8,
+    pub(crate) carry: i64,
+    pub(crate) m_vertical: u8,
+    pub(crate) diff: u8,
+    pub(crate) borrow: bool,
+    pub(crate) base: u8,
+    pub(crate) bit: bool,
+    pub(crate) byte_acc: u8,
+    pub(crate) exponent_acc: Value<F>,
+    pub(crate) r_rlc: Value<F>,
+    pub(crate) base_rlc: Value<F>,
+    pub(crate) exponent_rlc: Value<F>,
+    pub(crate) modulus_rlc: Value<F>,
+    pub(crate) output_rlc: Value<F>,
+}
+
+impl<F: Field> ModExpRow<F> {
+    fn dummy(num_limbs: usize) -> Self {
+        Self {
+            position: None,
+            a: vec![0; num_limbs],
+            m: vec![0; num_limbs],
+            b: 0,
+            q: 0,
+            r: 0,
+            carry: 0,
+            m_vertical: 0,
+            diff: 0,
+            borrow: false,
+            base: 0,
+            bit: false,
+            byte_acc: 0,
+            exponent_acc: Value::known(F::ZERO),
+            r_rlc: Value::known(F::ZERO),
+            base_rlc: Value::known(F::ZERO),
+            exponent_rlc: Value::known(F::ZERO),
+            modulus_rlc: Value::known(F::ZERO),
+            output_rlc: Value::known(F::ZERO),
+        }
+    }
+}
+
+/// Little-endian limbs of a value, which is less than `256^num_limbs`.
+fn to_limbs(value: &BigUint, num_limbs: usize) -> Vec<u8> {
+    let mut limbs = value.to_bytes_le();
+    limbs.resize(num_limbs, 0);
+    limbs
+}
+
+fn rlc<F: Field>(limbs: &[u8], challenges: &Challenges<Value<F>>) -> Value<F> {
+    challenges
+        .keccak_input()
+        .map(|challenge| crate::evm_circuit::util::rlc::value(limbs, challenge))
+}
+
+/// Generate the rows of the steps of `base^exponent mod modulus`.
+fn modexp<F: Field>(
+    rows: &mut Vec<ModExpRow<F>>,
+    inputs: &[Word; 3],
+    num_limbs: usize,
+    challenges: &Challenges<Value<F>>,
+) -> Result<(), Error> {
+    let [base_limbs, exponent_limbs, modulus_limbs] = inputs.map(|value| value.to_le_bytes());
+    for limbs in [base_limbs, exponent_limbs, modulus_limbs] {
+        if limbs.iter().skip(num_limbs).any(|limb| *limb != 0) {
+            log::error!("modexp operand doesn't fit in {} limbs", num_limbs);
+            return Err(Error::Synthesis);
+        }
+    }
+    let [base, exponent, modulus] =
+        [base_limbs, exponent_limbs, modulus_limbs].map(|limbs| BigUint::from_bytes_le(&limbs));
+    let m = to_limbs(&modulus, num_limbs);
+    let exponent = to_limbs(&exponent, num_limbs);
+    let is_m_zero = modulus == BigUint::default();
+
+    let num_rows_per_step = num_rows_per_step(num_limbs);
+    let base_rlc = rlc(&to_limbs(&base, num_limbs), challenges);
+    let modulus_rlc = rlc(&m, challenges);
+    let one = BigUint::from(1u64);
+
+    let mut acc = one.clone();
+    let mut reduced_base = BigUint::default();
+    let mut byte_acc = 0u8;
+    let mut exponent_acc = Value::known(F::ZERO);
+    for step in 0..num_steps(num_limbs) {
+        // The base is reduced first, then the accumulator, starting from one,
+        // is squared and multiplied by the base or one for each bit.
+        let bit_idx = (step >= 2 && step % 2 == 0).then(|| step / 2 - 1);
+        let bit = bit_idx.map_or(false, |idx| {
+            let limb = exponent[num_limbs - 1 - idx / NUM_BITS_PER_BYTE];
+            (limb >> (NUM_BITS_PER_BYTE - 1 - idx % NUM_BITS_PER_BYTE)) & 1 == 1
+        });
+        let (a, b) = match step {
+            0 => (base.clone(), one.clone()),
+            _ if bit_idx.is_none() => (acc.clone(), acc.clone()),
+            _ if bit => (acc.clone(), reduced_base.clone()),
+            _ => (acc.clone(), one.clone()),
+        };
+        // The steps aren't constrained for a zero modulus, whose result is zero.
+        let (q, r) = if is_m_zero {
+            (BigUint::default(), BigUint::default())
+        } else {
+            (&a * &b / &modulus, &a * &b % &modulus)
+        };
+        if step == 0 {
+            reduced_base = r.clone();
+        } else {
+            acc = r.clone();
+        }
+
+        let [a, b, q, r, reduced_base] =
+            [&a, &b, &q, &r, &reduced_base].map(|value| to_limbs(value, num_limbs));
+        let vertical = |limbs: &[u8], idx: usize| limbs.get(idx).copied().unwrap_or(0);
+
+        // Carries of the columns of `a * b - q * m - r`
+        let mut carries = vec![0i64; num_rows_per_step];
+        // Borrows of `m - r - 1`, which is non-negative iff `r < m`
+        let mut borrows = vec![false; num_rows_per_step];
+        let mut diffs = vec![0u8; num_rows_per_step];
+        if !is_m_zero {
+            let mut carry = 0i64;
+            for (idx, column_carry) in carries.iter_mut().enumerate() {
+                let mut column = carry - vertical(&r, idx) as i64;
+                for i in 0..num_limbs.min(idx + 1) {
+                    column += a[i] as i64 * vertical(&b, idx - i) as i64
+                        - m[i] as i64 * vertical(&q, idx - i) as i64;
+                }
+                debug_assert_eq!(column.rem_euclid(256), 0);
+                carry = column.div_euclid(256);
+                *column_carry = carry;
+            }
+            let mut borrow = false;
+            for idx in 0..num_limbs {
+                let value = m[idx] as i64 - r[idx] as i64 - (idx == 0) as i64 - borrow as i64;
+                borrow = value < 0;
+                diffs[idx] = value.rem_euclid(256) as u8;
+                borrows[idx] = borrow;
+            }
+        }
+
+        // Exponent bits are accumulated into bytes, and the bytes into the
+        // RLC of the exponent, most significant first.
+        if let Some(idx) = bit_idx {
+            byte_acc = if idx % NUM_BITS_PER_BYTE == 0 {
+                bit as u8
+            } else {
+                byte_acc * 2 + bit as u8
+            };
+            if idx < NUM_BITS_PER_BYTE {
+                exponent_acc = Value::known(F::ZERO);
+            }
+            if idx % NUM_BITS_PER_BYTE == NUM_BITS_PER_BYTE - 1 {
+                exponent_acc = exponent_acc
+                    .zip(challenges.keccak_input())
+                    .map(|(acc, challenge)| acc * challenge + F::from(byte_acc as u64));
+            }
+        }
+
+        let mut r_rlc = vec![Value::known(F::ZERO); num_rows_per_step + 1];
+        for idx in (0..num_rows_per_step).rev() {
+            r_rlc[idx] = r_rlc[idx + 1]
+                .zip(challenges.keccak_input())
+                .map(|(acc, challenge)| acc * challenge + F::from(vertical(&r, idx) as u64));
+        }
+
+        let is_output = step == num_steps(num_limbs) - 1;
+        for idx in 0..num_rows_per_step {
+            let is_bits_row = bit_idx.is_some() && idx == 0;
+            rows.push(ModExpRow {
+                position: Some(RowPosition { step, idx }),
+                a: a.clone(),
+                m: m.clone(),
+                b: vertical(&b, idx),
+                q: vertical(&q, idx),
+                r: vertical(&r, idx),
+                carry: carries[idx],
+                m_vertical: vertical(&m, idx),
+                diff: diffs[idx],
+                borrow: borrows[idx],
+                base: vertical(&reduced_base, idx),
+                bit,
+                byte_acc: if is_bits_row { byte_acc } else { 0 },
+                exponent_acc: if is_bits_row {
+                    exponent_acc
+                } else {
+                    Value::known(F::ZERO)
+                },
+                r_rlc: r_rlc[idx],
+                base_rlc,
+                exponent_rlc: if is_output && idx == 0 {
+                    exponent_acc
+                } else {
+                    Value::known(F::ZERO)
+                },
+                modulus_rlc: if is_output && idx == 0 {
+                    modulus_rlc
+                } else {
+                    Value::known(F::ZERO)
+                },
+                output_rlc: if is_output && idx == 0 {
+                    r_rlc[0]
+                } else {
+                    Value::known(F::ZERO)
+                },
+            });
+        }
+    }
+
+    debug_assert!({
+        let mut expected = ModExpTable::modexp(inputs).to_le_bytes().to_vec();
+        expected.resize(num_limbs.max(expected.len()), 0);
+        to_limbs(&acc, num_limbs) == expected[..num_limbs]
+    });
+    Ok(())
+}
+
+/// Generate the rows of the exponentiations of `inputs`, padded with
+/// exponentiations of zeroes up to `capacity`.
+pub(crate) fn multi_modexp<F: Field>(
+    inputs: &[[Word; 3]],
+    num_limbs: usize,
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<ModExpRow<F>>, Error> {
+    if let Some(capacity) = capacity {
+        if inputs.len() > capacity {
+            log::error!(
+                "{} exponentiations exceed the capacity of {}",
+                inputs.len(),
+                capacity
+            );
+            return Err(Error::Synthesis);
+        }
+    }
+
+    let mut rows = vec![ModExpRow::dummy(num_limbs); num_rows_per_step(num_limbs)];
+    for inputs in inputs {
+        modexp(&mut rows, inputs, num_limbs, &challenges)?;
+    }
+    if let Some(capacity) = capacity {
+        for _ in inputs.len()..capacity {
+            modexp(&mut rows, &[Word::zero(); 3], num_limbs, &challenges)?;
+        }
+    }
+    Ok(rows)
+}
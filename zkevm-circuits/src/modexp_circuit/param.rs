@@ -0,0 +1,31 @@
+use bus_mapping::precompile::MODEXP_SIZE_LIMIT;
+
+pub(crate) const MAX_DEGREE: usize = 4;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+
+/// Default number of limbs, i.e. bytes, of the base, exponent and modulus,
+/// which covers the operands supported by the MODEXP gadget.
+pub const DEFAULT_NUM_LIMBS: usize = MODEXP_SIZE_LIMIT;
+
+/// The carries of the columns of the products are range checked as two bytes
+/// with an offset, and are bounded by about `255 * num_limbs`.
+pub(crate) const MAX_NUM_LIMBS: usize = 128;
+pub(crate) const CARRY_OFFSET: u64 = 1 << 15;
+
+/// Each step proves `a * b = q * m + r` with `r < m`, where `b`, `q` and `r`
+/// are laid out vertically, one limb per row, followed by as many zero limbs
+/// for the higher columns of the products.
+pub(crate) fn num_rows_per_step(num_limbs: usize) -> usize {
+    2 * num_limbs
+}
+
+/// An exponentiation reduces the base, then squares and multiplies for each
+/// bit of the exponent, most significant first.
+pub(crate) fn num_steps(num_limbs: usize) -> usize {
+    1 + 2 * num_limbs * NUM_BITS_PER_BYTE
+}
+
+pub(crate) fn num_rows_per_exponentiation(num_limbs: usize) -> usize {
+    num_steps(num_limbs) * num_rows_per_step(num_limbs)
+}
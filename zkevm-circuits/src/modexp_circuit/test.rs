@@ -0,0 +1,122 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+/// Number of limbs of the tested circuits, which fits a few exponentiations
+/// in `2^12` rows.
+const NUM_LIMBS: usize = 4;
+
+#[test]
+fn modexp_circuit_unusable_rows() {
+    assert_eq!(
+        ModExpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(ModExpCircuitParams::default()),
+    );
+    assert_eq!(
+        modexp_unusable_rows(NUM_LIMBS),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(ModExpCircuitParams {
+            num_limbs: NUM_LIMBS
+        }),
+    );
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<[Word; 3]>, success: bool) {
+    let circuit = ModExpCircuit::new(NUM_LIMBS, 2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+fn inputs(values: &[[u64; 3]]) -> Vec<[Word; 3]> {
+    values.iter().map(|inputs| inputs.map(Word::from)).collect()
+}
+
+#[test]
+fn modexp_rlc() {
+    let r = Fr::from(0x100);
+    let challenges = Challenges::mock(Value::known(r), Value::known(r), Value::known(r));
+    let inputs = inputs(&[
+        [3, 5, 7],
+        [0, 0, 1],
+        [0, 0, 0xffff],
+        [2, 5, 0],
+        [0xfedcba98, 0xffffffff, 0xfffffffb],
+    ]);
+    let rows = multi_modexp::<Fr>(&inputs, NUM_LIMBS, challenges, None).unwrap();
+    let output_rows = rows
+        .iter()
+        .filter(|row| {
+            row.position.map_or(false, |RowPosition { step, idx }| {
+                step == num_steps(NUM_LIMBS) - 1 && idx == 0
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(output_rows.len(), inputs.len());
+    for (inputs, row) in inputs.iter().zip(output_rows) {
+        let [base_rlc, exponent_rlc, modulus_rlc, output_rlc] =
+            ModExpTable::assignments(inputs, &challenges);
+        for (value, expected) in [
+            (row.base_rlc, base_rlc),
+            (row.exponent_rlc, exponent_rlc),
+            (row.modulus_rlc, modulus_rlc),
+            (row.output_rlc, output_rlc),
+        ] {
+            value.zip(expected).assert_if_known(|(a, b)| a == b);
+        }
+    }
+}
+
+#[test]
+fn modexp_circuit_simple() {
+    let k = 12;
+    let inputs = inputs(&[
+        [3, 5, 7],
+        // 0^0 = 1
+        [0, 0, 5],
+        // Modulus one
+        [123, 45, 1],
+        // Zero modulus
+        [2, 5, 0],
+        // Base greater than the modulus
+        [0xfedcba98, 0x1234, 0x10001],
+        // Full limbs
+        [0xffffffff, 0xffffffff, 0xfffffffb],
+    ]);
+    verify::<Fr>(k, inputs, true);
+}
+
+#[test]
+fn modexp_circuit_too_many_inputs() {
+    let k = 12;
+    let circuit = ModExpCircuit::<Fr>::new(NUM_LIMBS, 2usize.pow(k), vec![]);
+    let capacity = circuit.capacity().unwrap();
+    let inputs = vec![[Word::one(); 3]; capacity + 1];
+    let circuit = ModExpCircuit::<Fr>::new(NUM_LIMBS, 2usize.pow(k), inputs);
+    assert!(MockProver::<Fr>::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 12;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let circuit = ModExpCircuit::new(NUM_LIMBS, num_rows, vec![]);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = inputs(&[[3, 5, 7], [2, 5, 0], [0xffffffff, 0xffffffff, 0xfffffffb]]);
+    let circuit = ModExpCircuit::new(NUM_LIMBS, num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
@@ -0,0 +1,1222 @@
+//! The pairing circuit implementation.
+//!
+//! It verifies the pairing checks done by the ecPairing precompile, and
+//! exposes them in the pairing table. The pairs of an input take consecutive
+//! slots of the circuit, each slot accumulating the RLC of the input, its
+//! validity and the product of the Miller loops of the pairs so far, followed
+//! by the final exponentiation. A table row is exposed for each slot, so each
+//! prefix of an input made of whole pairs is proven as well.
+//!
+//! The coordinates are checked to be in the base field, which is emulated
+//! with the integer chip of halo2wrong, the G2 points being on the twist over
+//! Fq2 with the arithmetic of the `tower` module. The G2 subgroup check and
+//! the Miller loop use affine formulas with witnessed slopes, which are only
+//! done on valid finite points, the generators being used otherwise.
+
+// Naming notes:
+// - *_le: Little-Endian bytes
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+mod tower;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::PairingCircuit as TestPairingCircuit;
+
+use crate::{
+    evm_circuit::util::rlc,
+    table::{LookupTable, PairingTable},
+    tx_circuit::sign_verify::assert_equal_if,
+    util::{Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::{EcPairingAuxData, EcPairingPair, EC_PAIRING_PAIR_LENGTH};
+use eth_types::{Field, ToLittleEndian, Word};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::{
+        bn256::{Fq, Fq12, Fq2, Fr, G2Affine, G2},
+        ff::{Field as _, PrimeField},
+        group::{prime::PrimeCurveAffine, Curve, Group},
+        CurveAffine,
+    },
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+    poly::Rotation,
+};
+use integer::{rns::Rns, AssignedInteger, IntegerChip, IntegerConfig, IntegerInstructions};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx, Term,
+};
+use num_bigint::BigUint;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::{collections::BTreeSet, iter, marker::PhantomData, rc::Rc};
+use tower::{
+    fq_from_big, fq_modulus, fq_value, non_residue_power, AssignedFq12, AssignedFq2, TowerChip,
+};
+
+const NUMBER_OF_LIMBS: usize = 4;
+const BIT_LEN_LIMB: usize = 72;
+/// Number of bytes of a limb. The last limb of a word has the remaining 5
+/// bytes.
+const NUM_BYTES_LIMB: usize = BIT_LEN_LIMB / 8;
+/// Number of bytes of the input whose RLC is accumulated by a row.
+const NUM_BYTES_RLC_ROW: usize = 4;
+/// The BN254 parameter u, such that the ate loop count is 6u + 2.
+const BN_X: u64 = 4965661367192848881;
+
+type FqChip<F> = IntegerChip<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedFq<F> = AssignedInteger<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+/// Configuration of the pairing circuit
+#[derive(Clone, Debug)]
+pub struct PairingCircuitConfig<F> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC of the input, with 4 bytes in the first columns of the main gate
+    q_rlc: Selector,
+    // Start of the RLC of a pair, from the RLC of the previous pairs unless
+    // the pair is the first one of an input
+    q_rlc_chain: Selector,
+    rlc: Column<Advice>,
+    /// Pairing table
+    pub pairing_table: PairingTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct PairingCircuitConfigArgs<F: Field> {
+    /// Pairing table
+    pub pairing_table: PairingTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for PairingCircuitConfig<F> {
+    type ConfigArgs = PairingCircuitConfigArgs<F>;
+
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            pairing_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let rns = Rns::<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::construct();
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+            rns.overflow_lengths(),
+        );
+
+        // The RLC of the inputs is accumulated in the `rlc` column, chained
+        // from the previous pair unless `is_first` is set in the last advice
+        // column of the main gate on a `q_rlc_chain` row. Each following
+        // `q_rlc` row then accumulates the 4 big-endian bytes of its first
+        // advice columns, 48 rows for the 192 bytes of a pair.
+        let q_rlc = meta.selector();
+        let q_rlc_chain = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        meta.create_gate("pairing input rlc", |meta| {
+            let q_rlc = meta.query_selector(q_rlc);
+            let [a, b, c, d, _] = main_gate_config
+                .advices()
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [rlc, rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(rlc, rotation));
+            let inputs = [d, c, b, a, rlc];
+
+            vec![q_rlc * (rlc_next - rlc::expr(&inputs, challenges.keccak_input()))]
+        });
+        meta.create_gate("pairing input rlc chain", |meta| {
+            let q_rlc_chain = meta.query_selector(q_rlc_chain);
+            let is_first = meta.query_advice(main_gate_config.advices()[4], Rotation::cur());
+            let [rlc_prev, rlc_next] = [Rotation::cur(), Rotation::next()]
+                .map(|rotation| meta.query_advice(rlc, rotation));
+
+            vec![q_rlc_chain * (rlc_next - (1.expr() - is_first) * rlc_prev)]
+        });
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc,
+            q_rlc_chain,
+            rlc,
+            pairing_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> PairingCircuitConfig<F> {
+    /// Load the table of the range chip.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        RangeChip::<F>::new(self.range_config.clone()).load_table(layouter)
+    }
+
+    fn integer_config(&self) -> IntegerConfig {
+        IntegerConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+}
+
+/// Helper structure to pass around references to the chips.
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    tower: &'a TowerChip<'a, F>,
+}
+
+/// Point of the twist over Fq2.
+#[derive(Clone, Debug)]
+struct AssignedG2<F: Field> {
+    x: AssignedFq2<F>,
+    y: AssignedFq2<F>,
+}
+
+/// Cells of a pair given to the precompile, whose points may be invalid.
+struct AssignedInputPair<F: Field> {
+    g1: [AssignedFq<F>; 2],
+    g2: AssignedG2<F>,
+    bytes_be: Vec<(AssignedValue<F>, u8)>,
+    is_infinity: AssignedCondition<F>,
+    is_valid: AssignedCondition<F>,
+}
+
+/// Cells of a coordinate, which is given as a word that may not be in the
+/// base field.
+struct AssignedCoordinate<F: Field> {
+    value: AssignedFq<F>,
+    limbs: Vec<AssignedValue<F>>,
+    bytes_le: [AssignedValue<F>; 32],
+    in_field: AssignedCondition<F>,
+}
+
+/// What is accumulated over the pairs of an input, i.e. a row of the pairing
+/// table without the output, and the product of the Miller loops.
+struct AssignedPairingState<F: Field> {
+    input_rlc: AssignedCell<F, F>,
+    input_len: AssignedValue<F>,
+    is_valid: AssignedCondition<F>,
+    miller_product: AssignedFq12<F>,
+}
+
+/// Cells of a row of the pairing table.
+struct AssignedPairingTableRow<F: Field> {
+    input_rlc: AssignedCell<F, F>,
+    input_len: AssignedValue<F>,
+    is_valid: AssignedCondition<F>,
+    output: AssignedCondition<F>,
+}
+
+/// Non-adjacent form of the ate loop count 6u + 2, least significant digit
+/// first.
+fn ate_loop_count_naf() -> Vec<i8> {
+    let mut n = 6 * BN_X as u128 + 2;
+    let mut naf = vec![];
+    while n > 0 {
+        if n & 1 == 1 {
+            // 1 if n = 1 mod 4, and -1 if n = 3 mod 4
+            let digit = 2 - (n % 4) as i8;
+            naf.push(digit);
+            if digit == 1 {
+                n -= 1;
+            } else {
+                n += 1;
+            }
+        } else {
+            naf.push(0);
+        }
+        n >>= 1;
+    }
+    naf
+}
+
+/// Bits of u, most significant first.
+fn bn_x_bits() -> Vec<bool> {
+    (0..u64::BITS)
+        .rev()
+        .map(|i| (BN_X >> i) & 1 == 1)
+        .skip_while(|bit| !bit)
+        .collect()
+}
+
+/// Bits of the order of G2 minus one, most significant first.
+fn group_order_minus_one_bits() -> Vec<bool> {
+    BigUint::from_bytes_le(&(Fr::ZERO - Fr::ONE).to_repr())
+        .to_str_radix(2)
+        .chars()
+        .map(|bit| bit == '1')
+        .collect()
+}
+
+/// Pairing Circuit, verifying the ecPairing calls of a block.
+#[derive(Clone, Debug)]
+pub struct PairingCircuit<F: Field> {
+    /// Aux point of the G2 subgroup checks, which is on the twist but not in
+    /// G2.
+    pub aux_point: G2Affine,
+    /// Max number of pairs of all the ecPairing calls, or zero to fit the
+    /// ones of `ops`
+    pub max_pairs: usize,
+    /// Inputs and outputs of the ecPairing calls
+    pub ops: Vec<EcPairingAuxData>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for PairingCircuit<F> {
+    fn default() -> Self {
+        Self::new(0, vec![])
+    }
+}
+
+impl<F: Field> PairingCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(max_pairs: usize, ops: Vec<EcPairingAuxData>) -> Self {
+        // The aux point is a random point of the twist, which is outside of
+        // G2 with overwhelming probability since the twist has a cofactor.
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let aux_point = loop {
+            let x = Fq2::random(&mut rng);
+            let y = Option::<Fq2>::from((x.square() * x + G2Affine::b()).sqrt());
+            if let Some(y) = y {
+                let point = G2::from(G2Affine { x, y });
+                if point * (Fr::ZERO - Fr::ONE) != -point {
+                    break point.to_affine();
+                }
+            }
+        };
+        Self {
+            aux_point,
+            max_pairs,
+            ops,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the given number
+    /// of pairs.
+    pub fn min_num_rows(num_pairs: usize) -> usize {
+        // The range chip table has a row for each value of each of its bit
+        // lengths.
+        let rns = Rns::<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::construct();
+        let rows_range_chip_table: usize = [BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8]
+            .into_iter()
+            .chain(rns.overflow_lengths())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|bit_len| 1 << bit_len)
+            .sum();
+        // Rough upper bound of the rows used by each pair, mostly by its
+        // Miller loop, its subgroup check and the final exponentiation.
+        let rows_pair = 2_000_000;
+        std::cmp::max(rows_range_chip_table, rows_pair * num_pairs)
+    }
+
+    /// The pairs of the inputs, each with whether it's the first one of its
+    /// input, padded to the capacity of the circuit with inputs made of a
+    /// pair of zeroes, which are valid.
+    fn padded_pairs(&self) -> Result<Vec<(EcPairingPair, bool)>, Error> {
+        let pairs = self
+            .ops
+            .iter()
+            .flat_map(|op| {
+                op.pairs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, pair)| (pair.clone(), idx == 0))
+            })
+            .collect_vec();
+        if self.max_pairs == 0 {
+            return Ok(pairs);
+        }
+        if pairs.len() > self.max_pairs {
+            error!(
+                "{} ecPairing pairs exceed the capacity of {}",
+                pairs.len(),
+                self.max_pairs
+            );
+            return Err(Error::Synthesis);
+        }
+        Ok(pairs
+            .into_iter()
+            .chain(iter::repeat((EcPairingPair::default(), true)))
+            .take(self.max_pairs)
+            .collect())
+    }
+
+    /// Assign the little-endian bytes of a word, and return them with its
+    /// limbs.
+    fn assign_word(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        word: &Word,
+    ) -> Result<(Vec<AssignedValue<F>>, [AssignedValue<F>; 32]), Error> {
+        let mut limbs = Vec::with_capacity(NUMBER_OF_LIMBS);
+        let mut bytes_le = Vec::with_capacity(32);
+        for chunk in word.to_le_bytes().chunks(NUM_BYTES_LIMB) {
+            let value = chunk.iter().rev().fold(F::ZERO, |acc, byte| {
+                acc * F::from(256) + F::from(*byte as u64)
+            });
+            let (limb, chunk_le) =
+                chips
+                    .range_chip
+                    .decompose(ctx, Value::known(value), 8, 8 * chunk.len())?;
+            limbs.push(limb);
+            bytes_le.extend(chunk_le);
+        }
+        Ok((limbs, bytes_le.try_into().unwrap()))
+    }
+
+    /// Assign whether a 128-bit value `a` is less than the constant `b`. The
+    /// difference `b - a - 1`, or `a - b` otherwise, is range checked.
+    fn assign_is_lt(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        a: &AssignedValue<F>,
+        a_value: u128,
+        b: u128,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let main_gate = chips.main_gate;
+        let is_lt = main_gate.assign_bit(ctx, Value::known(F::from((a_value < b) as u64)))?;
+        // diff = is_lt * (2b - 1) - 2 * is_lt * a + a - b
+        let is_lt_a = main_gate.mul(ctx, &is_lt, a)?;
+        let b_field = F::from_u128(b);
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                Term::Assigned(&is_lt, b_field.double() - F::ONE),
+                Term::Assigned(&is_lt_a, -F::from(2)),
+                Term::Assigned(a, F::ONE),
+            ],
+            -b_field,
+        )?;
+        let diff_value = if a_value < b {
+            b - a_value - 1
+        } else {
+            a_value - b
+        };
+        let (diff_decomposed, _) =
+            chips
+                .range_chip
+                .decompose(ctx, Value::known(F::from_u128(diff_value)), 8, 128)?;
+        main_gate.assert_equal(ctx, &diff, &diff_decomposed)?;
+        Ok(is_lt)
+    }
+
+    /// Assign a coordinate from a word, and whether the word is less than the
+    /// base field modulus, in which case the limbs of the emulated integer
+    /// must match its bytes.
+    fn assign_coordinate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        word: &Word,
+    ) -> Result<AssignedCoordinate<F>, Error> {
+        let main_gate = chips.main_gate;
+        let (limbs, bytes_le) = self.assign_word(ctx, chips, word)?;
+
+        // The word is compared to the modulus by its 128-bit halves.
+        let modulus = Word::from_little_endian(&fq_modulus().to_bytes_le());
+        let [lo, hi] = [&bytes_le[..16], &bytes_le[16..]].map(|bytes| {
+            main_gate.compose(
+                ctx,
+                &bytes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| Term::Assigned(byte, F::from_u128(1u128 << (8 * i))))
+                    .collect_vec(),
+                F::ZERO,
+            )
+        });
+        let (lo, hi) = (lo?, hi?);
+        let [word_lo, word_hi, modulus_lo, modulus_hi] = [
+            word.low_u128(),
+            (*word >> 128).low_u128(),
+            modulus.low_u128(),
+            (modulus >> 128).low_u128(),
+        ];
+        let is_lt_lo = self.assign_is_lt(ctx, chips, &lo, word_lo, modulus_lo)?;
+        let is_lt_hi = self.assign_is_lt(ctx, chips, &hi, word_hi, modulus_hi)?;
+        let hi_diff = main_gate.compose(
+            ctx,
+            &[Term::Assigned(&hi, F::ONE)],
+            -F::from_u128(modulus_hi),
+        )?;
+        let is_eq_hi = main_gate.is_zero(ctx, &hi_diff)?;
+        let is_eq_hi_lt_lo = main_gate.mul(ctx, &is_eq_hi, &is_lt_lo)?;
+        let in_field = main_gate.add(ctx, &is_lt_hi, &is_eq_hi_lt_lo)?;
+
+        let value = BigUint::from_bytes_le(&word.to_le_bytes());
+        let value = chips
+            .tower
+            .fq_assign(ctx, Value::known(fq_from_big(&value)))?;
+        for (limb, value_limb) in limbs.iter().zip_eq(value.limbs()) {
+            assert_equal_if(ctx, main_gate, limb, value_limb.as_ref(), &in_field)?;
+        }
+
+        Ok(AssignedCoordinate {
+            value,
+            limbs,
+            bytes_le,
+            in_field,
+        })
+    }
+
+    /// Assign whether `a` is zero modulo the base field modulus. A zero is
+    /// shown by the limbs of the reduced integer, and a non-zero by its
+    /// inverse.
+    fn assign_is_zero(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        a: &AssignedFq<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let main_gate = chips.main_gate;
+        let fq_chip = chips.tower.fq_chip();
+
+        let value = fq_value(a);
+        let a = fq_chip.reduce(ctx, a)?;
+        let is_zero = main_gate.assign_bit(
+            ctx,
+            value.map(|value| F::from(bool::from(value.is_zero()) as u64)),
+        )?;
+        let is_not_zero = main_gate.not(ctx, &is_zero)?;
+
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        for limb in a.limbs() {
+            assert_equal_if(ctx, main_gate, limb.as_ref(), &zero, &is_zero)?;
+        }
+        let inverse = chips
+            .tower
+            .fq_assign(ctx, value.map(|value| value.invert().unwrap_or(Fq::ONE)))?;
+        let product = fq_chip.mul(ctx, &a, &inverse)?;
+        let one = fq_chip.assign_constant(ctx, Fq::ONE)?;
+        for (limb, one_limb) in product.limbs().iter().zip_eq(one.limbs()) {
+            assert_equal_if(
+                ctx,
+                main_gate,
+                limb.as_ref(),
+                one_limb.as_ref(),
+                &is_not_zero,
+            )?;
+        }
+
+        Ok(is_zero)
+    }
+
+    /// Assign whether all the given differences are zero.
+    fn assign_is_all_zero(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        diffs: &[&AssignedFq<F>],
+    ) -> Result<AssignedCondition<F>, Error> {
+        let mut is_all_zero = chips.main_gate.assign_constant(ctx, F::ONE)?;
+        for diff in diffs {
+            let is_zero = self.assign_is_zero(ctx, chips, diff)?;
+            is_all_zero = chips.main_gate.mul(ctx, &is_all_zero, &is_zero)?;
+        }
+        Ok(is_all_zero)
+    }
+
+    /// Assign whether the given limbs are all zero, which are range checked
+    /// so that it's the case iff their sum is zero.
+    fn assign_limbs_are_zero(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        limbs: &[&AssignedValue<F>],
+    ) -> Result<AssignedCondition<F>, Error> {
+        let limbs_sum = chips.main_gate.compose(
+            ctx,
+            &limbs
+                .iter()
+                .map(|limb| Term::Assigned(limb, F::ONE))
+                .collect_vec(),
+            F::ZERO,
+        )?;
+        chips.main_gate.is_zero(ctx, &limbs_sum)
+    }
+
+    fn assign_g2_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        point: G2Affine,
+    ) -> Result<AssignedG2<F>, Error> {
+        Ok(AssignedG2 {
+            x: chips.tower.fq2_constant(ctx, point.x)?,
+            y: chips.tower.fq2_constant(ctx, point.y)?,
+        })
+    }
+
+    fn select_g2(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        a: &AssignedG2<F>,
+        b: &AssignedG2<F>,
+        cond: &AssignedCondition<F>,
+    ) -> Result<AssignedG2<F>, Error> {
+        Ok(AssignedG2 {
+            x: chips.tower.fq2_select(ctx, &a.x, &b.x, cond)?,
+            y: chips.tower.fq2_select(ctx, &a.y, &b.y, cond)?,
+        })
+    }
+
+    /// Double a finite point of the twist whose y-coordinate isn't zero,
+    /// returning the slope of its tangent and the result.
+    fn double_g2(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        t: &AssignedG2<F>,
+    ) -> Result<(AssignedFq2<F>, AssignedG2<F>), Error> {
+        let tower = chips.tower;
+        // lambda * 2y = 3x^2
+        let slope = tower
+            .fq2_value(&t.x)
+            .zip(tower.fq2_value(&t.y))
+            .map(|(x, y)| {
+                let x_square = x.square();
+                (x_square.double() + x_square) * y.double().invert().unwrap_or(Fq2::ZERO)
+            });
+        let slope = tower.fq2_assign(ctx, slope)?;
+        let y_double = tower.fq2_double(ctx, &t.y)?;
+        let lhs = tower.fq2_mul(ctx, &slope, &y_double)?;
+        let x_square = tower.fq2_square(ctx, &t.x)?;
+        let x_square_double = tower.fq2_double(ctx, &x_square)?;
+        let rhs = tower.fq2_add(ctx, &x_square_double, &x_square)?;
+        tower.fq2_assert_equal(ctx, &lhs, &rhs)?;
+
+        // x3 = lambda^2 - 2x, y3 = lambda (x - x3) - y
+        let x_double = tower.fq2_double(ctx, &t.x)?;
+        let slope_square = tower.fq2_square(ctx, &slope)?;
+        let x = tower.fq2_sub(ctx, &slope_square, &x_double)?;
+        let y = tower.fq2_sub(ctx, &t.x, &x)?;
+        let y = tower.fq2_mul(ctx, &slope, &y)?;
+        let y = tower.fq2_sub(ctx, &y, &t.y)?;
+        Ok((slope, AssignedG2 { x, y }))
+    }
+
+    /// Add finite points of the twist with different x-coordinates, returning
+    /// the slope of the line through them and the result.
+    fn add_g2(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        t: &AssignedG2<F>,
+        q: &AssignedG2<F>,
+    ) -> Result<(AssignedFq2<F>, AssignedG2<F>), Error> {
+        let tower = chips.tower;
+        // lambda * (x_q - x_t) = y_q - y_t
+        let [t_x, t_y, q_x, q_y] = [&t.x, &t.y, &q.x, &q.y].map(|c| tower.fq2_value(c));
+        let slope = t_x
+            .zip(t_y)
+            .zip(q_x.zip(q_y))
+            .map(|((t_x, t_y), (q_x, q_y))| {
+                (q_y - t_y) * (q_x - t_x).invert().unwrap_or(Fq2::ZERO)
+            });
+        let slope = tower.fq2_assign(ctx, slope)?;
+        let x_diff = tower.fq2_sub(ctx, &q.x, &t.x)?;
+        let lhs = tower.fq2_mul(ctx, &slope, &x_diff)?;
+        let rhs = tower.fq2_sub(ctx, &q.y, &t.y)?;
+        tower.fq2_assert_equal(ctx, &lhs, &rhs)?;
+
+        // x3 = lambda^2 - x_t - x_q, y3 = lambda (x_t - x3) - y_t
+        let slope_square = tower.fq2_square(ctx, &slope)?;
+        let x = tower.fq2_sub(ctx, &slope_square, &t.x)?;
+        let x = tower.fq2_sub(ctx, &x, &q.x)?;
+        let y = tower.fq2_sub(ctx, &t.x, &x)?;
+        let y = tower.fq2_mul(ctx, &slope, &y)?;
+        let y = tower.fq2_sub(ctx, &y, &t.y)?;
+        Ok((slope, AssignedG2 { x, y }))
+    }
+
+    /// Multiply `f` by the line through `t` with the given slope, evaluated
+    /// at the G1 point `p`, i.e. `y_p + (-lambda x_p + (lambda x_t - y_t) v) w`.
+    fn mul_by_line(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        f: &AssignedFq12<F>,
+        slope: &AssignedFq2<F>,
+        t: &AssignedG2<F>,
+        [p_x, p_y]: &[AssignedFq<F>; 2],
+    ) -> Result<AssignedFq12<F>, Error> {
+        let tower = chips.tower;
+        let l1 = tower.fq2_mul_by_fq(ctx, slope, p_x)?;
+        let l1 = tower.fq2_neg(ctx, &l1)?;
+        let l2 = tower.fq2_mul(ctx, slope, &t.x)?;
+        let l2 = tower.fq2_sub(ctx, &l2, &t.y)?;
+        tower.fq12_mul_by_line(ctx, f, p_y, [&l1, &l2])
+    }
+
+    /// Miller loop of the optimal ate pairing of a finite G1 point and a
+    /// finite G2 point.
+    fn assign_miller_loop(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        p: &[AssignedFq<F>; 2],
+        q: &AssignedG2<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let tower = chips.tower;
+        let q_neg = AssignedG2 {
+            x: q.x.clone(),
+            y: tower.fq2_neg(ctx, &q.y)?,
+        };
+
+        let mut f = tower.fq12_constant(ctx, Fq12::ONE)?;
+        let mut t = q.clone();
+        for digit in ate_loop_count_naf().into_iter().rev().skip(1) {
+            f = tower.fq12_square(ctx, &f)?;
+            let (slope, doubled) = self.double_g2(ctx, chips, &t)?;
+            f = self.mul_by_line(ctx, chips, &f, &slope, &t, p)?;
+            t = doubled;
+            if digit != 0 {
+                let q = if digit == 1 { q } else { &q_neg };
+                let (slope, sum) = self.add_g2(ctx, chips, &t, q)?;
+                f = self.mul_by_line(ctx, chips, &f, &slope, &t, p)?;
+                t = sum;
+            }
+        }
+
+        // Q1 = pi(Q) and -Q2 = -pi^2(Q), where pi is the Frobenius
+        // endomorphism on the twist.
+        let q1 = {
+            let x = tower.fq2_conjugate(ctx, &q.x)?;
+            let x = tower.fq2_mul_by_constant(ctx, &x, non_residue_power(1, 3))?;
+            let y = tower.fq2_conjugate(ctx, &q.y)?;
+            let y = tower.fq2_mul_by_constant(ctx, &y, non_residue_power(1, 2))?;
+            AssignedG2 { x, y }
+        };
+        let q2_neg = {
+            let x = tower.fq2_mul_by_constant(ctx, &q.x, non_residue_power(2, 3))?;
+            let y = tower.fq2_mul_by_constant(ctx, &q.y, non_residue_power(2, 2))?;
+            let y = tower.fq2_neg(ctx, &y)?;
+            AssignedG2 { x, y }
+        };
+        let (slope, sum) = self.add_g2(ctx, chips, &t, &q1)?;
+        f = self.mul_by_line(ctx, chips, &f, &slope, &t, p)?;
+        t = sum;
+        let (slope, _) = self.add_g2(ctx, chips, &t, &q2_neg)?;
+        self.mul_by_line(ctx, chips, &f, &slope, &t, p)
+    }
+
+    /// `f^-u`, where u is positive, so that `f^-u = conj(f^u)` in the
+    /// cyclotomic subgroup.
+    fn exp_by_neg_x(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        f: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let tower = chips.tower;
+        let mut result = f.clone();
+        for bit in bn_x_bits().into_iter().skip(1) {
+            result = tower.fq12_square(ctx, &result)?;
+            if bit {
+                result = tower.fq12_mul(ctx, &result, f)?;
+            }
+        }
+        tower.fq12_conjugate(ctx, &result)
+    }
+
+    /// Final exponentiation of the optimal ate pairing, to the power of
+    /// `(p^12 - 1) / r`, with the addition chain of the hard part by Fuentes
+    /// et al.
+    fn assign_final_exponentiation(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        f: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let tower = chips.tower;
+
+        // Easy part: f^((p^6 - 1)(p^2 + 1))
+        let f1 = tower.fq12_conjugate(ctx, f)?;
+        let f2 = tower.fq12_invert(ctx, f)?;
+        let r = tower.fq12_mul(ctx, &f1, &f2)?;
+        let r2 = tower.fq12_frobenius_map(ctx, &r, 2)?;
+        let r = tower.fq12_mul(ctx, &r2, &r)?;
+
+        // Hard part: r^((p^4 - p^2 + 1) / r)
+        let y0 = self.exp_by_neg_x(ctx, chips, &r)?;
+        let y1 = tower.fq12_square(ctx, &y0)?;
+        let y2 = tower.fq12_square(ctx, &y1)?;
+        let y3 = tower.fq12_mul(ctx, &y2, &y1)?;
+        let y4 = self.exp_by_neg_x(ctx, chips, &y3)?;
+        let y5 = tower.fq12_square(ctx, &y4)?;
+        let y6 = self.exp_by_neg_x(ctx, chips, &y5)?;
+        let y3 = tower.fq12_conjugate(ctx, &y3)?;
+        let y6 = tower.fq12_conjugate(ctx, &y6)?;
+        let y7 = tower.fq12_mul(ctx, &y6, &y4)?;
+        let y8 = tower.fq12_mul(ctx, &y7, &y3)?;
+        let y9 = tower.fq12_mul(ctx, &y8, &y1)?;
+        let y10 = tower.fq12_mul(ctx, &y8, &y4)?;
+        let y11 = tower.fq12_mul(ctx, &y10, &r)?;
+        let y12 = tower.fq12_frobenius_map(ctx, &y9, 1)?;
+        let y13 = tower.fq12_mul(ctx, &y12, &y11)?;
+        let y8 = tower.fq12_frobenius_map(ctx, &y8, 2)?;
+        let y14 = tower.fq12_mul(ctx, &y8, &y13)?;
+        let r = tower.fq12_conjugate(ctx, &r)?;
+        let y15 = tower.fq12_mul(ctx, &r, &y9)?;
+        let y15 = tower.fq12_frobenius_map(ctx, &y15, 3)?;
+        tower.fq12_mul(ctx, &y15, &y14)
+    }
+
+    /// Assign whether a point of the twist is in G2, i.e. `[r]Q = 0`. It's
+    /// shown by `A + [r]Q = A`, computed as `[2^254]A + [r - 1]Q + Q` with
+    /// the bits of `r - 1`, A being the aux point which isn't in G2, so that
+    /// the incomplete formulas are well defined with overwhelming
+    /// probability.
+    fn assign_is_in_subgroup(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        q: &AssignedG2<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let tower = chips.tower;
+        let bits = group_order_minus_one_bits();
+        let aux_multiple = (0..bits.len())
+            .fold(G2::from(self.aux_point), |point, _| point.double())
+            .to_affine();
+
+        let mut t = self.assign_g2_constant(ctx, chips, self.aux_point)?;
+        for bit in bits {
+            t = self.double_g2(ctx, chips, &t)?.1;
+            if bit {
+                t = self.add_g2(ctx, chips, &t, q)?.1;
+            }
+        }
+        let (_, sum) = self.add_g2(ctx, chips, &t, q)?;
+
+        let expected = self.assign_g2_constant(ctx, chips, aux_multiple)?;
+        let x_diff = tower.fq2_sub(ctx, &sum.x, &expected.x)?;
+        let y_diff = tower.fq2_sub(ctx, &sum.y, &expected.y)?;
+        self.assign_is_all_zero(
+            ctx,
+            chips,
+            &[&x_diff.c0, &x_diff.c1, &y_diff.c0, &y_diff.c1],
+        )
+    }
+
+    /// Assign a pair given to the precompile, and whether its points are
+    /// valid, i.e. in their groups or at infinity.
+    fn assign_input_pair(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        pair: &EcPairingPair,
+    ) -> Result<AssignedInputPair<F>, Error> {
+        let main_gate = chips.main_gate;
+        let tower = chips.tower;
+        let fq_chip = tower.fq_chip();
+
+        let coordinates = pair
+            .g1
+            .iter()
+            .chain(pair.g2.iter())
+            .map(|word| self.assign_coordinate(ctx, chips, word))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let bytes_be = coordinates
+            .iter()
+            .zip_eq(pair.g1.iter().chain(pair.g2.iter()))
+            .flat_map(|(coordinate, word)| {
+                coordinate
+                    .bytes_le
+                    .iter()
+                    .cloned()
+                    .zip(word.to_le_bytes())
+                    .rev()
+            })
+            .collect_vec();
+        let [g1_x, g1_y, g2_x_im, g2_x_re, g2_y_im, g2_y_re]: [AssignedCoordinate<F>; 6] =
+            coordinates.try_into().ok().unwrap();
+
+        // G1: on y^2 = x^3 + 3, or (0, 0)
+        let g1_in_field = main_gate.mul(ctx, &g1_x.in_field, &g1_y.in_field)?;
+        let g1_is_infinity = self.assign_limbs_are_zero(
+            ctx,
+            chips,
+            &g1_x.limbs.iter().chain(g1_y.limbs.iter()).collect_vec(),
+        )?;
+        let g1_is_on_curve = {
+            let x_square = fq_chip.square(ctx, &g1_x.value)?;
+            let x_cube = fq_chip.mul(ctx, &x_square, &g1_x.value)?;
+            let three = fq_chip.assign_constant(ctx, Fq::from(3))?;
+            let rhs = fq_chip.add(ctx, &x_cube, &three)?;
+            let y_square = fq_chip.square(ctx, &g1_y.value)?;
+            let diff = fq_chip.sub(ctx, &y_square, &rhs)?;
+            self.assign_is_zero(ctx, chips, &diff)?
+        };
+        let g1_is_valid = main_gate.add(ctx, &g1_is_infinity, &g1_is_on_curve)?;
+        let g1_is_valid = main_gate.mul(ctx, &g1_is_valid, &g1_in_field)?;
+
+        // G2: on y^2 = x^3 + b' and in the subgroup of order r, or
+        // (0, 0, 0, 0)
+        let g2_in_field = {
+            let in_field = main_gate.mul(ctx, &g2_x_im.in_field, &g2_x_re.in_field)?;
+            let in_field = main_gate.mul(ctx, &in_field, &g2_y_im.in_field)?;
+            main_gate.mul(ctx, &in_field, &g2_y_re.in_field)?
+        };
+        let g2_is_infinity = self.assign_limbs_are_zero(
+            ctx,
+            chips,
+            &[&g2_x_im, &g2_x_re, &g2_y_im, &g2_y_re]
+                .iter()
+                .flat_map(|coordinate| coordinate.limbs.iter())
+                .collect_vec(),
+        )?;
+        let g2 = AssignedG2 {
+            x: AssignedFq2 {
+                c0: g2_x_re.value,
+                c1: g2_x_im.value,
+            },
+            y: AssignedFq2 {
+                c0: g2_y_re.value,
+                c1: g2_y_im.value,
+            },
+        };
+        let g2_is_on_twist = {
+            let x_square = tower.fq2_square(ctx, &g2.x)?;
+            let x_cube = tower.fq2_mul(ctx, &x_square, &g2.x)?;
+            let b = tower.fq2_constant(ctx, G2Affine::b())?;
+            let rhs = tower.fq2_add(ctx, &x_cube, &b)?;
+            let y_square = tower.fq2_square(ctx, &g2.y)?;
+            let diff = tower.fq2_sub(ctx, &y_square, &rhs)?;
+            self.assign_is_all_zero(ctx, chips, &[&diff.c0, &diff.c1])?
+        };
+        // The subgroup check is done on the generator for points which
+        // aren't on the twist.
+        let g2_is_on_twist = main_gate.mul(ctx, &g2_is_on_twist, &g2_in_field)?;
+        let g2_generator = self.assign_g2_constant(ctx, chips, G2Affine::generator())?;
+        let g2_on_twist = self.select_g2(ctx, chips, &g2, &g2_generator, &g2_is_on_twist)?;
+        let g2_is_in_subgroup = self.assign_is_in_subgroup(ctx, chips, &g2_on_twist)?;
+        let g2_is_valid = main_gate.mul(ctx, &g2_is_on_twist, &g2_is_in_subgroup)?;
+        let g2_is_valid = main_gate.add(ctx, &g2_is_infinity, &g2_is_valid)?;
+        let g2_is_valid = main_gate.mul(ctx, &g2_is_valid, &g2_in_field)?;
+
+        let is_valid = main_gate.mul(ctx, &g1_is_valid, &g2_is_valid)?;
+        let is_infinity = main_gate.add(ctx, &g1_is_infinity, &g2_is_infinity)?;
+        let is_infinity = {
+            let both = main_gate.mul(ctx, &g1_is_infinity, &g2_is_infinity)?;
+            main_gate.sub(ctx, &is_infinity, &both)?
+        };
+
+        Ok(AssignedInputPair {
+            g1: [g1_x.value, g1_y.value],
+            g2,
+            bytes_be,
+            is_infinity,
+            is_valid,
+        })
+    }
+
+    /// Assign the RLC of the input up to a pair, from the RLC of the previous
+    /// pairs unless it's the first one. The bytes are copied from their cells,
+    /// with the values taken from the words since the cells aren't in the
+    /// current phase.
+    fn assign_input_rlc(
+        &self,
+        config: &PairingCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        rlc_prev: &AssignedCell<F, F>,
+        is_first: &AssignedCondition<F>,
+        bytes_be: &[(AssignedValue<F>, u8)],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let columns = config.main_gate_config.advices();
+
+        ctx.enable(config.q_rlc_chain)?;
+        let copied = ctx.assign_advice(|| "rlc_prev", config.rlc, rlc_prev.value().copied())?;
+        ctx.constrain_equal(rlc_prev.cell(), copied.cell())?;
+        let copied = ctx.assign_advice(|| "is_first", columns[4], is_first.value().copied())?;
+        ctx.constrain_equal(is_first.cell(), copied.cell())?;
+        ctx.next();
+
+        let challenge = challenges.keccak_input();
+        let mut rlc = rlc_prev
+            .value()
+            .zip(is_first.value())
+            .map(|(rlc, is_first)| *rlc * (F::ONE - is_first));
+        for chunk in bytes_be.chunks_exact(NUM_BYTES_RLC_ROW) {
+            ctx.enable(config.q_rlc)?;
+            ctx.assign_advice(|| "input_rlc", config.rlc, rlc)?;
+            for (&column, (cell, byte)) in columns.iter().zip(chunk) {
+                let copied = ctx.assign_advice(
+                    || "input_byte",
+                    column,
+                    Value::known(F::from(*byte as u64)),
+                )?;
+                ctx.constrain_equal(cell.cell(), copied.cell())?;
+            }
+            rlc = chunk.iter().fold(rlc, |acc, (_, byte)| {
+                acc * challenge + Value::known(F::from(*byte as u64))
+            });
+            ctx.next();
+        }
+
+        let assigned_rlc = ctx.assign_advice(|| "input_rlc", config.rlc, rlc)?;
+        ctx.next();
+
+        Ok(assigned_rlc)
+    }
+
+    /// Assign a pair in the next slot, accumulating it into the state of the
+    /// previous slot unless it's the first pair of its input, and return the
+    /// new state with the output of the pairing check of the input so far.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_pair(
+        &self,
+        config: &PairingCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        pair: &EcPairingPair,
+        is_first: bool,
+        prev: &AssignedPairingState<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(AssignedPairingState<F>, AssignedCondition<F>), Error> {
+        let main_gate = chips.main_gate;
+        let tower = chips.tower;
+
+        let is_first = main_gate.assign_bit(ctx, Value::known(F::from(is_first as u64)))?;
+        let input = self.assign_input_pair(ctx, chips, pair)?;
+
+        // The pairing is one if a point is at infinity, and the Miller loop
+        // is done on the generators if it's not computed.
+        let is_computable = {
+            let is_finite = main_gate.not(ctx, &input.is_infinity)?;
+            main_gate.mul(ctx, &is_finite, &input.is_valid)?
+        };
+        let fq_chip = tower.fq_chip();
+        let g1_generator = [
+            fq_chip.assign_constant(ctx, Fq::ONE)?,
+            fq_chip.assign_constant(ctx, Fq::from(2))?,
+        ];
+        let g1 = [
+            fq_chip.select(ctx, &input.g1[0], &g1_generator[0], &is_computable)?,
+            fq_chip.select(ctx, &input.g1[1], &g1_generator[1], &is_computable)?,
+        ];
+        let g2_generator = self.assign_g2_constant(ctx, chips, G2Affine::generator())?;
+        let g2 = self.select_g2(ctx, chips, &input.g2, &g2_generator, &is_computable)?;
+        let miller_loop = self.assign_miller_loop(ctx, chips, &g1, &g2)?;
+        let one = tower.fq12_constant(ctx, Fq12::ONE)?;
+        let miller_loop = tower.fq12_select(ctx, &miller_loop, &one, &is_computable)?;
+
+        // Accumulation, from the neutral values for the first pair
+        let input_len = {
+            let is_not_first = main_gate.not(ctx, &is_first)?;
+            let len_prev = main_gate.mul(ctx, &is_not_first, &prev.input_len)?;
+            main_gate.compose(
+                ctx,
+                &[Term::Assigned(&len_prev, F::ONE)],
+                F::from(EC_PAIRING_PAIR_LENGTH as u64),
+            )?
+        };
+        let is_valid = {
+            let one = main_gate.assign_constant(ctx, F::ONE)?;
+            let is_valid_prev = main_gate.select(ctx, &one, &prev.is_valid, &is_first)?;
+            main_gate.mul(ctx, &is_valid_prev, &input.is_valid)?
+        };
+        let miller_product = {
+            let product_prev = tower.fq12_select(ctx, &one, &prev.miller_product, &is_first)?;
+            tower.fq12_mul(ctx, &product_prev, &miller_loop)?
+        };
+
+        // The output is whether the pairing product is one, for valid points.
+        let pairing_product = self.assign_final_exponentiation(ctx, chips, &miller_product)?;
+        let diffs = pairing_product
+            .coordinates()
+            .into_iter()
+            .zip(one.coordinates())
+            .map(|(lhs, rhs)| fq_chip.sub(ctx, lhs, rhs))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let is_one = self.assign_is_all_zero(ctx, chips, &diffs.iter().collect_vec())?;
+        let output = main_gate.mul(ctx, &is_one, &is_valid)?;
+
+        let input_rlc = self.assign_input_rlc(
+            config,
+            ctx,
+            &prev.input_rlc,
+            &is_first,
+            &input.bytes_be,
+            challenges,
+        )?;
+
+        Ok((
+            AssignedPairingState {
+                input_rlc,
+                input_len,
+                is_valid,
+                miller_product,
+            },
+            output,
+        ))
+    }
+
+    fn assign_pairing_table(
+        &self,
+        config: &PairingCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedPairingTableRow<F>],
+    ) -> Result<(), Error> {
+        let pairing_table = &config.pairing_table;
+        layouter.assign_region(
+            || "pairing table",
+            |mut region| {
+                // All-zero row for the disabled lookups
+                region.assign_fixed(
+                    || "pairing table all-zero row",
+                    pairing_table.q_enable,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+                for column in <PairingTable as LookupTable<F>>::advice_columns(pairing_table) {
+                    region.assign_advice(
+                        || "pairing table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (idx, row) in rows.iter().enumerate() {
+                    let offset = idx + 1;
+                    region.assign_fixed(
+                        || format!("pairing table q_enable {}", offset),
+                        pairing_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, cell) in [
+                        (pairing_table.input_rlc, &row.input_rlc),
+                        (pairing_table.input_len, &row.input_len),
+                        (pairing_table.is_valid, &row.is_valid),
+                        (pairing_table.output, &row.output),
+                    ] {
+                        cell.copy_advice(
+                            || format!("pairing table row {}", offset),
+                            &mut region,
+                            column,
+                            offset,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn assign(
+        &self,
+        config: &PairingCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let pairs = self.padded_pairs()?;
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let rns = Rc::new(Rns::construct());
+        let fq_chip = FqChip::<F>::new(config.integer_config(), rns.clone());
+        let tower = TowerChip::new(&fq_chip, rns);
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            tower: &tower,
+        };
+
+        let rows = layouter.assign_region(
+            || "pairing circuit",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let mut state = AssignedPairingState {
+                    input_rlc: main_gate.assign_constant(&mut ctx, F::ZERO)?,
+                    input_len: main_gate.assign_constant(&mut ctx, F::ZERO)?,
+                    is_valid: main_gate.assign_constant(&mut ctx, F::ONE)?,
+                    miller_product: tower.fq12_constant(&mut ctx, Fq12::ONE)?,
+                };
+                let mut rows = Vec::with_capacity(pairs.len());
+                for (pair, is_first) in pairs.iter() {
+                    let (next_state, output) = self.assign_pair(
+                        config, &mut ctx, &chips, pair, *is_first, &state, challenges,
+                    )?;
+                    state = next_state;
+                    rows.push(AssignedPairingTableRow {
+                        input_rlc: state.input_rlc.clone(),
+                        input_len: state.input_len.clone(),
+                        is_valid: state.is_valid.clone(),
+                        output,
+                    });
+                }
+                log::debug!("pairing circuit: {} rows", ctx.offset());
+                Ok(rows)
+            },
+        )?;
+
+        self.assign_pairing_table(config, layouter, &rows)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for PairingCircuit<F> {
+    type Config = PairingCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `max_ec_pairing_pairs` circuit parameter, when non-zero, sets up
+    /// the circuit to support a fixed number of pairs, independently of the
+    /// calls of the block.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ec_pairing_pairs,
+            block.precompile_events.get_ec_pairing_events(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let num_pairs = block
+            .precompile_events
+            .get_ec_pairing_events()
+            .iter()
+            .map(|op| op.pairs.len())
+            .sum::<usize>();
+        (
+            Self::min_num_rows(num_pairs),
+            Self::min_num_rows(block.circuits_params.max_ec_pairing_pairs.max(num_pairs)),
+        )
+    }
+
+    /// Make the assignments to the PairingCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_aux_tables(layouter)?;
+        self.assign(config, layouter, challenges)
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::{G1Affine, G1},
+};
+use rand_xorshift::XorShiftRng;
+
+#[test]
+fn pairing_circuit_unusable_rows() {
+    assert_eq!(
+        PairingCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, PairingCircuit::<Fr>>(()),
+    )
+}
+
+fn verify(k: u32, circuit: PairingCircuit<Fr>, success: bool) {
+    let prover = MockProver::run(k, &circuit, circuit.instance()).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+fn to_word(c: Fq) -> Word {
+    Word::from_little_endian(&c.to_repr())
+}
+
+fn pair(p: G1Affine, q: G2Affine) -> EcPairingPair {
+    EcPairingPair {
+        g1: [p.x, p.y].map(to_word),
+        g2: [q.x.c1, q.x.c0, q.y.c1, q.y.c0].map(to_word),
+    }
+}
+
+/// The pairs `(aP, Q)` and `(-P, aQ)`, whose pairings multiply to one.
+fn canceling_pairs(rng: &mut XorShiftRng) -> Vec<EcPairingPair> {
+    let a = Fr::random(&mut *rng);
+    let p = G1::random(&mut *rng);
+    let q = G2::random(&mut *rng);
+    vec![
+        pair((p * a).to_affine(), q.to_affine()),
+        pair((-p).to_affine(), (q * a).to_affine()),
+    ]
+}
+
+#[test]
+#[ignore = "Due to high memory requirement"]
+fn pairing_circuit_valid() {
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let ops = vec![
+        EcPairingAuxData {
+            pairs: canceling_pairs(&mut rng),
+            output: true,
+        },
+        EcPairingAuxData {
+            pairs: vec![pair(G1Affine::generator(), G2Affine::generator())],
+            output: false,
+        },
+        // The pairing with a point at infinity is one.
+        EcPairingAuxData {
+            pairs: vec![EcPairingPair::default()],
+            output: true,
+        },
+    ];
+    let circuit = PairingCircuit::new(4, ops);
+    verify(23, circuit, true);
+}
+
+#[test]
+#[ignore = "Due to high memory requirement"]
+fn pairing_circuit_invalid_points() {
+    let g1 = pair(G1Affine::generator(), G2Affine::generator()).g1;
+    let g2 = pair(G1Affine::generator(), G2Affine::generator()).g2;
+    let ops = [
+        // (1, 3) is not on the curve.
+        EcPairingPair {
+            g1: [Word::one(), Word::from(3)],
+            g2,
+        },
+        // A coordinate is not in the base field.
+        EcPairingPair {
+            g1: [g1[0] + to_word(-Fq::ONE) + Word::one(), g1[1]],
+            g2,
+        },
+        // A point of the twist which is not in G2
+        pair(
+            G1Affine::generator(),
+            PairingCircuit::<Fr>::default().aux_point,
+        ),
+    ]
+    .into_iter()
+    .map(|pair| EcPairingAuxData {
+        pairs: vec![pair],
+        output: false,
+    })
+    .collect();
+    let circuit = PairingCircuit::new(3, ops);
+    verify(22, circuit, true);
+}
+
+#[test]
+fn pairing_circuit_too_many_pairs() {
+    let op = EcPairingAuxData {
+        pairs: vec![EcPairingPair::default(); 2],
+        output: true,
+    };
+    let circuit = PairingCircuit::<Fr>::new(1, vec![op]);
+    assert!(MockProver::run(19, &circuit, circuit.instance()).is_err());
+}
@@ -0,0 +1,665 @@
+//! Arithmetic of the extensions of the BN254 base field, emulated with the
+//! integer chip of halo2wrong. The tower is the one of halo2curves:
+//! - Fq2 = Fq[u] / (u^2 + 1)
+//! - Fq6 = Fq2[v] / (v^3 - ξ), where ξ = 9 + u
+//! - Fq12 = Fq6[w] / (w^2 - v)
+
+use super::{AssignedFq, FqChip, BIT_LEN_LIMB, NUMBER_OF_LIMBS};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::Value,
+    halo2curves::{
+        bn256::{Fq, Fq12, Fq2, Fq6},
+        ff::{Field as _, PrimeField},
+    },
+    plonk::Error,
+};
+use integer::{
+    rns::{Integer as RnsInteger, Rns},
+    IntegerInstructions, Range, UnassignedInteger,
+};
+use maingate::{AssignedCondition, RegionCtx};
+use num_bigint::BigUint;
+use std::rc::Rc;
+
+/// Element of Fq2 as `c0 + c1 * u`.
+#[derive(Clone, Debug)]
+pub(crate) struct AssignedFq2<F: Field> {
+    pub(crate) c0: AssignedFq<F>,
+    pub(crate) c1: AssignedFq<F>,
+}
+
+/// Element of Fq6 as `c0 + c1 * v + c2 * v^2`.
+#[derive(Clone, Debug)]
+pub(crate) struct AssignedFq6<F: Field> {
+    pub(crate) c0: AssignedFq2<F>,
+    pub(crate) c1: AssignedFq2<F>,
+    pub(crate) c2: AssignedFq2<F>,
+}
+
+/// Element of Fq12 as `c0 + c1 * w`.
+#[derive(Clone, Debug)]
+pub(crate) struct AssignedFq12<F: Field> {
+    pub(crate) c0: AssignedFq6<F>,
+    pub(crate) c1: AssignedFq6<F>,
+}
+
+impl<F: Field> AssignedFq12<F> {
+    /// The 12 coordinates over the base field.
+    pub(crate) fn coordinates(&self) -> [&AssignedFq<F>; 12] {
+        let [a, b] = [&self.c0, &self.c1];
+        [
+            &a.c0.c0, &a.c0.c1, &a.c1.c0, &a.c1.c1, &a.c2.c0, &a.c2.c1, &b.c0.c0, &b.c0.c1,
+            &b.c1.c0, &b.c1.c1, &b.c2.c0, &b.c2.c1,
+        ]
+    }
+}
+
+/// Modulus of the base field.
+pub(crate) fn fq_modulus() -> BigUint {
+    BigUint::from_bytes_le(&(Fq::ZERO - Fq::ONE).to_repr()) + 1u64
+}
+
+/// Element of the base field from an integer, which is reduced.
+pub(crate) fn fq_from_big(value: &BigUint) -> Fq {
+    let mut bytes = (value % fq_modulus()).to_bytes_le();
+    bytes.resize(32, 0);
+    Fq::from_repr(bytes.try_into().unwrap()).unwrap()
+}
+
+/// Value of an emulated integer, computed from its limbs since it may not be
+/// reduced.
+pub(crate) fn fq_value<F: Field>(a: &AssignedFq<F>) -> Value<Fq> {
+    a.limbs()
+        .iter()
+        .rev()
+        .fold(Value::known(BigUint::from(0u64)), |acc, limb| {
+            acc.zip(limb.as_ref().value()).map(|(acc, limb)| {
+                (acc << BIT_LEN_LIMB) + BigUint::from_bytes_le(limb.to_repr().as_ref())
+            })
+        })
+        .map(|value| fq_from_big(&value))
+}
+
+/// The non-residue ξ = 9 + u defining Fq6 over Fq2.
+fn non_residue() -> Fq2 {
+    Fq2 {
+        c0: Fq::from(9),
+        c1: Fq::ONE,
+    }
+}
+
+/// Compute ξ^((p^power - 1) / divisor).
+pub(crate) fn non_residue_power(power: u32, divisor: u64) -> Fq2 {
+    let exponent = (fq_modulus().pow(power) - 1u64) / divisor;
+    non_residue().pow_vartime(exponent.to_u64_digits())
+}
+
+/// Arithmetic of the extension fields over the base field chip, with the
+/// coefficients of the Frobenius maps of powers 0 to 3.
+pub(crate) struct TowerChip<'a, F: Field> {
+    fq_chip: &'a FqChip<F>,
+    rns: Rc<Rns<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+    // ξ^((p^i - 1) / 3), ξ^((p^i - 1) * 2 / 3) and ξ^((p^i - 1) / 6)
+    frobenius_fq6_c1: [Fq2; 4],
+    frobenius_fq6_c2: [Fq2; 4],
+    frobenius_fq12_c1: [Fq2; 4],
+}
+
+impl<'a, F: Field> TowerChip<'a, F> {
+    pub(crate) fn new(
+        fq_chip: &'a FqChip<F>,
+        rns: Rc<Rns<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+    ) -> Self {
+        let coefficients = |f: fn(u32) -> Fq2| [0, 1, 2, 3].map(f);
+        Self {
+            fq_chip,
+            rns,
+            frobenius_fq6_c1: coefficients(|power| non_residue_power(power, 3)),
+            frobenius_fq6_c2: coefficients(|power| non_residue_power(power, 3).square()),
+            frobenius_fq12_c1: coefficients(|power| non_residue_power(power, 6)),
+        }
+    }
+
+    pub(crate) fn fq_chip(&self) -> &FqChip<F> {
+        self.fq_chip
+    }
+
+    // Fq
+
+    pub(crate) fn fq_assign(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<Fq>,
+    ) -> Result<AssignedFq<F>, Error> {
+        self.fq_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(value.map(|v| RnsInteger::from_fe(v, self.rns.clone()))),
+            Range::Remainder,
+        )
+    }
+
+    // Fq2
+
+    pub(crate) fn fq2_assign(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<Fq2>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_assign(ctx, value.map(|v| v.c0))?,
+            c1: self.fq_assign(ctx, value.map(|v| v.c1))?,
+        })
+    }
+
+    pub(crate) fn fq2_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Fq2,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.assign_constant(ctx, value.c0)?,
+            c1: self.fq_chip.assign_constant(ctx, value.c1)?,
+        })
+    }
+
+    pub(crate) fn fq2_value(&self, a: &AssignedFq2<F>) -> Value<Fq2> {
+        fq_value(&a.c0)
+            .zip(fq_value(&a.c1))
+            .map(|(c0, c1)| Fq2 { c0, c1 })
+    }
+
+    pub(crate) fn fq2_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.add(ctx, &a.c0, &b.c0)?,
+            c1: self.fq_chip.add(ctx, &a.c1, &b.c1)?,
+        })
+    }
+
+    pub(crate) fn fq2_sub(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.sub(ctx, &a.c0, &b.c0)?,
+            c1: self.fq_chip.sub(ctx, &a.c1, &b.c1)?,
+        })
+    }
+
+    pub(crate) fn fq2_neg(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.neg(ctx, &a.c0)?,
+            c1: self.fq_chip.neg(ctx, &a.c1)?,
+        })
+    }
+
+    pub(crate) fn fq2_double(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        self.fq2_add(ctx, a, a)
+    }
+
+    pub(crate) fn fq2_conjugate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: a.c0.clone(),
+            c1: self.fq_chip.neg(ctx, &a.c1)?,
+        })
+    }
+
+    /// Karatsuba multiplication, with 3 multiplications in the base field.
+    pub(crate) fn fq2_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        let chip = self.fq_chip;
+        let t0 = chip.mul(ctx, &a.c0, &b.c0)?;
+        let t1 = chip.mul(ctx, &a.c1, &b.c1)?;
+        let a_sum = chip.add(ctx, &a.c0, &a.c1)?;
+        let b_sum = chip.add(ctx, &b.c0, &b.c1)?;
+        let t2 = chip.mul(ctx, &a_sum, &b_sum)?;
+        let c0 = chip.sub(ctx, &t0, &t1)?;
+        let c1 = chip.sub(ctx, &t2, &t0)?;
+        let c1 = chip.sub(ctx, &c1, &t1)?;
+        Ok(AssignedFq2 { c0, c1 })
+    }
+
+    /// `(c0 + c1)(c0 - c1) + 2 c0 c1 u`
+    pub(crate) fn fq2_square(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        let chip = self.fq_chip;
+        let sum = chip.add(ctx, &a.c0, &a.c1)?;
+        let diff = chip.sub(ctx, &a.c0, &a.c1)?;
+        let c0 = chip.mul(ctx, &sum, &diff)?;
+        let product = chip.mul(ctx, &a.c0, &a.c1)?;
+        let c1 = chip.add(ctx, &product, &product)?;
+        Ok(AssignedFq2 { c0, c1 })
+    }
+
+    pub(crate) fn fq2_mul_by_fq(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        s: &AssignedFq<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.mul(ctx, &a.c0, s)?,
+            c1: self.fq_chip.mul(ctx, &a.c1, s)?,
+        })
+    }
+
+    pub(crate) fn fq2_mul_by_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: Fq2,
+    ) -> Result<AssignedFq2<F>, Error> {
+        let b = self.fq2_constant(ctx, b)?;
+        self.fq2_mul(ctx, a, &b)
+    }
+
+    /// Multiplication by ξ = 9 + u, i.e. `(9 c0 - c1) + (c0 + 9 c1) u`.
+    pub(crate) fn fq2_mul_by_non_residue(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        let chip = self.fq_chip;
+        let nine_times = |ctx: &mut RegionCtx<'_, F>, c: &AssignedFq<F>| {
+            let double = chip.add(ctx, c, c)?;
+            let quadruple = chip.add(ctx, &double, &double)?;
+            let octuple = chip.add(ctx, &quadruple, &quadruple)?;
+            chip.add(ctx, &octuple, c)
+        };
+        let c0 = nine_times(ctx, &a.c0)?;
+        let c0 = chip.sub(ctx, &c0, &a.c1)?;
+        let c1 = nine_times(ctx, &a.c1)?;
+        let c1 = chip.add(ctx, &c1, &a.c0)?;
+        Ok(AssignedFq2 { c0, c1 })
+    }
+
+    pub(crate) fn fq2_frobenius_map(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        power: usize,
+    ) -> Result<AssignedFq2<F>, Error> {
+        if power % 2 == 1 {
+            self.fq2_conjugate(ctx, a)
+        } else {
+            Ok(a.clone())
+        }
+    }
+
+    /// Select `a` if `cond` is set, and `b` otherwise.
+    pub(crate) fn fq2_select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+        cond: &AssignedCondition<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.select(ctx, &a.c0, &b.c0, cond)?,
+            c1: self.fq_chip.select(ctx, &a.c1, &b.c1, cond)?,
+        })
+    }
+
+    pub(crate) fn fq2_assert_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<(), Error> {
+        self.fq_chip.assert_equal(ctx, &a.c0, &b.c0)?;
+        self.fq_chip.assert_equal(ctx, &a.c1, &b.c1)
+    }
+
+    // Fq6
+
+    fn fq6_map(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+        f: impl Fn(
+            &Self,
+            &mut RegionCtx<'_, F>,
+            &AssignedFq2<F>,
+            &AssignedFq2<F>,
+        ) -> Result<AssignedFq2<F>, Error>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: f(self, ctx, &a.c0, &b.c0)?,
+            c1: f(self, ctx, &a.c1, &b.c1)?,
+            c2: f(self, ctx, &a.c2, &b.c2)?,
+        })
+    }
+
+    pub(crate) fn fq6_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        self.fq6_map(ctx, a, b, Self::fq2_add)
+    }
+
+    pub(crate) fn fq6_sub(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        self.fq6_map(ctx, a, b, Self::fq2_sub)
+    }
+
+    pub(crate) fn fq6_neg(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_neg(ctx, &a.c0)?,
+            c1: self.fq2_neg(ctx, &a.c1)?,
+            c2: self.fq2_neg(ctx, &a.c2)?,
+        })
+    }
+
+    /// Karatsuba multiplication, with 6 multiplications in Fq2.
+    pub(crate) fn fq6_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        let t0 = self.fq2_mul(ctx, &a.c0, &b.c0)?;
+        let t1 = self.fq2_mul(ctx, &a.c1, &b.c1)?;
+        let t2 = self.fq2_mul(ctx, &a.c2, &b.c2)?;
+        // (a1 + a2)(b1 + b2) - t1 - t2 = a1 b2 + a2 b1
+        let cross = |ctx: &mut RegionCtx<'_, F>,
+                     [a_i, a_j, b_i, b_j, t_i, t_j]: [&AssignedFq2<F>; 6]| {
+            let a_sum = self.fq2_add(ctx, a_i, a_j)?;
+            let b_sum = self.fq2_add(ctx, b_i, b_j)?;
+            let product = self.fq2_mul(ctx, &a_sum, &b_sum)?;
+            let product = self.fq2_sub(ctx, &product, t_i)?;
+            self.fq2_sub(ctx, &product, t_j)
+        };
+        let cross_12 = cross(ctx, [&a.c1, &a.c2, &b.c1, &b.c2, &t1, &t2])?;
+        let cross_01 = cross(ctx, [&a.c0, &a.c1, &b.c0, &b.c1, &t0, &t1])?;
+        let cross_02 = cross(ctx, [&a.c0, &a.c2, &b.c0, &b.c2, &t0, &t2])?;
+        // c0 = t0 + ξ (a1 b2 + a2 b1)
+        let c0 = self.fq2_mul_by_non_residue(ctx, &cross_12)?;
+        let c0 = self.fq2_add(ctx, &c0, &t0)?;
+        // c1 = a0 b1 + a1 b0 + ξ t2
+        let c1 = self.fq2_mul_by_non_residue(ctx, &t2)?;
+        let c1 = self.fq2_add(ctx, &cross_01, &c1)?;
+        // c2 = a0 b2 + a2 b0 + t1
+        let c2 = self.fq2_add(ctx, &cross_02, &t1)?;
+        Ok(AssignedFq6 { c0, c1, c2 })
+    }
+
+    /// Multiplication by `b0 + b1 * v`, with 6 multiplications in Fq2.
+    pub(crate) fn fq6_mul_by_01(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b0: &AssignedFq2<F>,
+        b1: &AssignedFq2<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        // c0 = a0 b0 + ξ a2 b1
+        let c0 = self.fq2_mul(ctx, &a.c2, b1)?;
+        let c0 = self.fq2_mul_by_non_residue(ctx, &c0)?;
+        let a0_b0 = self.fq2_mul(ctx, &a.c0, b0)?;
+        let c0 = self.fq2_add(ctx, &a0_b0, &c0)?;
+        // c1 = a0 b1 + a1 b0
+        let a0_b1 = self.fq2_mul(ctx, &a.c0, b1)?;
+        let a1_b0 = self.fq2_mul(ctx, &a.c1, b0)?;
+        let c1 = self.fq2_add(ctx, &a0_b1, &a1_b0)?;
+        // c2 = a1 b1 + a2 b0
+        let a1_b1 = self.fq2_mul(ctx, &a.c1, b1)?;
+        let a2_b0 = self.fq2_mul(ctx, &a.c2, b0)?;
+        let c2 = self.fq2_add(ctx, &a1_b1, &a2_b0)?;
+        Ok(AssignedFq6 { c0, c1, c2 })
+    }
+
+    pub(crate) fn fq6_mul_by_fq(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        s: &AssignedFq<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_mul_by_fq(ctx, &a.c0, s)?,
+            c1: self.fq2_mul_by_fq(ctx, &a.c1, s)?,
+            c2: self.fq2_mul_by_fq(ctx, &a.c2, s)?,
+        })
+    }
+
+    /// Multiplication by v, i.e. `ξ c2 + c0 v + c1 v^2`.
+    pub(crate) fn fq6_mul_by_non_residue(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_mul_by_non_residue(ctx, &a.c2)?,
+            c1: a.c0.clone(),
+            c2: a.c1.clone(),
+        })
+    }
+
+    pub(crate) fn fq6_frobenius_map(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        power: usize,
+    ) -> Result<AssignedFq6<F>, Error> {
+        let c0 = self.fq2_frobenius_map(ctx, &a.c0, power)?;
+        let c1 = self.fq2_frobenius_map(ctx, &a.c1, power)?;
+        let c1 = self.fq2_mul_by_constant(ctx, &c1, self.frobenius_fq6_c1[power])?;
+        let c2 = self.fq2_frobenius_map(ctx, &a.c2, power)?;
+        let c2 = self.fq2_mul_by_constant(ctx, &c2, self.frobenius_fq6_c2[power])?;
+        Ok(AssignedFq6 { c0, c1, c2 })
+    }
+
+    // Fq12
+
+    pub(crate) fn fq12_assign(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<Fq12>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let mut fq6_assign = |value: Value<Fq6>| -> Result<AssignedFq6<F>, Error> {
+            Ok(AssignedFq6 {
+                c0: self.fq2_assign(ctx, value.map(|v| v.c0))?,
+                c1: self.fq2_assign(ctx, value.map(|v| v.c1))?,
+                c2: self.fq2_assign(ctx, value.map(|v| v.c2))?,
+            })
+        };
+        Ok(AssignedFq12 {
+            c0: fq6_assign(value.map(|v| v.c0))?,
+            c1: fq6_assign(value.map(|v| v.c1))?,
+        })
+    }
+
+    pub(crate) fn fq12_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Fq12,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let mut fq6_constant = |value: Fq6| -> Result<AssignedFq6<F>, Error> {
+            Ok(AssignedFq6 {
+                c0: self.fq2_constant(ctx, value.c0)?,
+                c1: self.fq2_constant(ctx, value.c1)?,
+                c2: self.fq2_constant(ctx, value.c2)?,
+            })
+        };
+        Ok(AssignedFq12 {
+            c0: fq6_constant(value.c0)?,
+            c1: fq6_constant(value.c1)?,
+        })
+    }
+
+    pub(crate) fn fq12_value(&self, a: &AssignedFq12<F>) -> Value<Fq12> {
+        let fq6_value = |a: &AssignedFq6<F>| {
+            self.fq2_value(&a.c0)
+                .zip(self.fq2_value(&a.c1))
+                .zip(self.fq2_value(&a.c2))
+                .map(|((c0, c1), c2)| Fq6 { c0, c1, c2 })
+        };
+        fq6_value(&a.c0)
+            .zip(fq6_value(&a.c1))
+            .map(|(c0, c1)| Fq12 { c0, c1 })
+    }
+
+    /// Karatsuba multiplication, with 3 multiplications in Fq6.
+    pub(crate) fn fq12_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        b: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let t0 = self.fq6_mul(ctx, &a.c0, &b.c0)?;
+        let t1 = self.fq6_mul(ctx, &a.c1, &b.c1)?;
+        let a_sum = self.fq6_add(ctx, &a.c0, &a.c1)?;
+        let b_sum = self.fq6_add(ctx, &b.c0, &b.c1)?;
+        let product = self.fq6_mul(ctx, &a_sum, &b_sum)?;
+        // c0 = t0 + v t1
+        let c0 = self.fq6_mul_by_non_residue(ctx, &t1)?;
+        let c0 = self.fq6_add(ctx, &t0, &c0)?;
+        // c1 = a0 b1 + a1 b0
+        let c1 = self.fq6_sub(ctx, &product, &t0)?;
+        let c1 = self.fq6_sub(ctx, &c1, &t1)?;
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    /// Complex squaring, with 2 multiplications in Fq6.
+    pub(crate) fn fq12_square(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let t = self.fq6_mul(ctx, &a.c0, &a.c1)?;
+        let v_t = self.fq6_mul_by_non_residue(ctx, &t)?;
+        // c0 = (a0 + a1)(a0 + v a1) - t - v t = a0^2 + v a1^2
+        let a_sum = self.fq6_add(ctx, &a.c0, &a.c1)?;
+        let v_a1 = self.fq6_mul_by_non_residue(ctx, &a.c1)?;
+        let a_v_sum = self.fq6_add(ctx, &a.c0, &v_a1)?;
+        let c0 = self.fq6_mul(ctx, &a_sum, &a_v_sum)?;
+        let c0 = self.fq6_sub(ctx, &c0, &t)?;
+        let c0 = self.fq6_sub(ctx, &c0, &v_t)?;
+        // c1 = 2 a0 a1
+        let c1 = self.fq6_add(ctx, &t, &t)?;
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    /// Multiplication by a line `l0 + (l1 + l2 * v) w`, with `l0` in the base
+    /// field, as evaluated by the Miller loop.
+    pub(crate) fn fq12_mul_by_line(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        l0: &AssignedFq<F>,
+        [l1, l2]: [&AssignedFq2<F>; 2],
+    ) -> Result<AssignedFq12<F>, Error> {
+        // c0 = a0 l0 + v a1 (l1 + l2 v)
+        let a0_l0 = self.fq6_mul_by_fq(ctx, &a.c0, l0)?;
+        let a1_l = self.fq6_mul_by_01(ctx, &a.c1, l1, l2)?;
+        let v_a1_l = self.fq6_mul_by_non_residue(ctx, &a1_l)?;
+        let c0 = self.fq6_add(ctx, &a0_l0, &v_a1_l)?;
+        // c1 = a0 (l1 + l2 v) + a1 l0
+        let a0_l = self.fq6_mul_by_01(ctx, &a.c0, l1, l2)?;
+        let a1_l0 = self.fq6_mul_by_fq(ctx, &a.c1, l0)?;
+        let c1 = self.fq6_add(ctx, &a0_l, &a1_l0)?;
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    /// Conjugation `c0 - c1 w`, i.e. the Frobenius map of power 6, which is
+    /// the inverse in the cyclotomic subgroup.
+    pub(crate) fn fq12_conjugate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        Ok(AssignedFq12 {
+            c0: a.c0.clone(),
+            c1: self.fq6_neg(ctx, &a.c1)?,
+        })
+    }
+
+    pub(crate) fn fq12_frobenius_map(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        power: usize,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let c0 = self.fq6_frobenius_map(ctx, &a.c0, power)?;
+        let c1 = self.fq6_frobenius_map(ctx, &a.c1, power)?;
+        let coefficient = self.frobenius_fq12_c1[power];
+        let c1 = AssignedFq6 {
+            c0: self.fq2_mul_by_constant(ctx, &c1.c0, coefficient)?,
+            c1: self.fq2_mul_by_constant(ctx, &c1.c1, coefficient)?,
+            c2: self.fq2_mul_by_constant(ctx, &c1.c2, coefficient)?,
+        };
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    /// Inverse of a non-zero element, which is witnessed and checked.
+    pub(crate) fn fq12_invert(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let inverse = self.fq12_value(a).map(|a| a.invert().unwrap_or(Fq12::ZERO));
+        let inverse = self.fq12_assign(ctx, inverse)?;
+        let product = self.fq12_mul(ctx, a, &inverse)?;
+        let one = self.fq12_constant(ctx, Fq12::ONE)?;
+        for (lhs, rhs) in product.coordinates().into_iter().zip(one.coordinates()) {
+            self.fq_chip.assert_equal(ctx, lhs, rhs)?;
+        }
+        Ok(inverse)
+    }
+
+    /// Select `a` if `cond` is set, and `b` otherwise.
+    pub(crate) fn fq12_select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        b: &AssignedFq12<F>,
+        cond: &AssignedCondition<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let mut fq6_select = |a: &AssignedFq6<F>, b: &AssignedFq6<F>| {
+            Ok::<_, Error>(AssignedFq6 {
+                c0: self.fq2_select(ctx, &a.c0, &b.c0, cond)?,
+                c1: self.fq2_select(ctx, &a.c1, &b.c1, cond)?,
+                c2: self.fq2_select(ctx, &a.c2, &b.c2, cond)?,
+            })
+        };
+        Ok(AssignedFq12 {
+            c0: fq6_select(&a.c0, &b.c0)?,
+            c1: fq6_select(&a.c1, &b.c1)?,
+        })
+    }
+}
@@ -7,9 +7,12 @@ mod dev;
 mod test;
 
 use eth_types::{
+    chain_spec::Hardfork,
+    evm_types::MAX_BLOB_HASHES_PER_TX,
     geth_types::{BlockConstants, Transaction},
     sign_types::SignData,
-    Address, BigEndianHash, Field, ToBigEndian, ToLittleEndian, ToScalar, Word, H256,
+    AccessList, Address, BigEndianHash, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, Word,
+    H256,
 };
 use halo2_proofs::plonk::{Instance, SecondPhase};
 use keccak256::plain::Keccak;
@@ -18,9 +21,8 @@ use std::marker::PhantomData;
 
 use crate::{
     table::{BlockTable, LookupTable, TxFieldTag, TxTable},
-    tx_circuit::TX_LEN,
     util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    witness::{self, access_list_rlc},
 };
 use gadgets::{
     is_zero::IsZeroChip,
@@ -45,6 +47,8 @@ pub struct BlockValues {
     difficulty: Word,
     base_fee: Word, // NOTE: BaseFee was added by EIP-1559 and is ignored in legacy headers.
     chain_id: u64,
+    blob_base_fee: Word,
+    hardfork: Hardfork,
     history_hashes: Vec<H256>,
 }
 
@@ -54,6 +58,8 @@ pub struct TxValues {
     nonce: u64,
     gas: u64, // gas limit
     gas_price: Word,
+    max_fee_per_gas: Word,
+    max_priority_fee_per_gas: Word,
     from_addr: Address,
     to_addr: Address,
     is_create: bool,
@@ -65,6 +71,25 @@ pub struct TxValues {
     v: u64,
     r: Word,
     s: Word,
+    tx_type: u64,
+    access_list_gas_cost: u64,
+    access_list_addresses_len: u64,
+    access_list_storage_keys_len: u64,
+    access_list: Option<AccessList>,
+    blob_versioned_hashes: Vec<H256>,
+}
+
+impl TxValues {
+    /// Returns the RLC of the blob versioned hash at `index`, zero for the
+    /// padding past the hashes of the tx.
+    fn blob_versioned_hash_rlc<F: Field>(&self, index: usize, randomness: F) -> F {
+        let hash = self
+            .blob_versioned_hashes
+            .get(index)
+            .copied()
+            .unwrap_or_default();
+        rlc(hash.to_word().to_le_bytes(), randomness)
+    }
 }
 
 /// Extra values (not contained in block or tx tables)
@@ -91,6 +116,8 @@ pub struct PublicData {
     pub prev_state_root: H256,
     /// Constants related to Ethereum block
     pub block_constants: BlockConstants,
+    /// Hardfork whose rules apply to the block
+    pub hardfork: Hardfork,
 }
 
 impl Default for PublicData {
@@ -102,6 +129,7 @@ impl Default for PublicData {
             state_root: H256::zero(),
             prev_state_root: H256::zero(),
             block_constants: BlockConstants::default(),
+            hardfork: Hardfork::default(),
         }
     }
 }
@@ -125,6 +153,8 @@ impl PublicData {
             difficulty: self.block_constants.difficulty,
             base_fee: self.block_constants.base_fee,
             chain_id: self.chain_id.as_u64(),
+            blob_base_fee: self.block_constants.blob_base_fee,
+            hardfork: self.hardfork,
             history_hashes,
         }
     }
@@ -145,6 +175,8 @@ impl PublicData {
             tx_vals.push(TxValues {
                 nonce: tx.nonce.as_u64(),
                 gas_price: tx.gas_price,
+                max_fee_per_gas: tx.max_fee_per_gas(),
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
                 gas: tx.gas_limit.as_u64(),
                 from_addr: tx.from,
                 to_addr: tx.to_or_zero(),
@@ -156,6 +188,12 @@ impl PublicData {
                 r: tx.r,
                 s: tx.s,
                 v: tx.v,
+                tx_type: tx.tx_type as u64,
+                access_list_gas_cost: tx.access_list_gas_cost(),
+                access_list_addresses_len: tx.access_list_len().0,
+                access_list_storage_keys_len: tx.access_list_len().1,
+                access_list: tx.access_list.clone(),
+                blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
             });
         }
         tx_vals
@@ -171,7 +209,13 @@ impl PublicData {
     }
 
     fn txs(&self) -> Vec<Transaction> {
-        self.transactions.iter().map(Transaction::from).collect()
+        self.transactions
+            .iter()
+            .map(|tx| {
+                Transaction::try_from(tx)
+                    .expect("unsupported tx types are rejected by the circuit input builder")
+            })
+            .collect()
     }
 }
 
@@ -316,7 +360,7 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         });
 
         let offset = BLOCK_LEN + 1 + EXTRA_LEN;
-        let tx_table_len = max_txs * TX_LEN + 1;
+        let tx_table_len = max_txs * TX_ROWS + 1;
 
         //  0.3 Tx table -> {tx_id, index, value} column match with raw_public_inputs
         // at expected offset
@@ -572,7 +616,7 @@ impl<F: Field> PiCircuitConfig<F> {
     #[inline]
     fn circuit_len(&self) -> usize {
         // +1 empty row in block table, +1 empty row in tx_table
-        BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_LEN * self.max_txs + 1) + self.max_calldata
+        BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_ROWS * self.max_txs + 1) + self.max_calldata
     }
 
     fn assign_tx_empty_row(&self, region: &mut Region<'_, F>, offset: usize) -> Result<(), Error> {
@@ -688,7 +732,7 @@ impl<F: Field> PiCircuitConfig<F> {
         )?;
 
         // Assign vals to raw_public_inputs column
-        let tx_table_len = TX_LEN * self.max_txs + 1;
+        let tx_table_len = TX_ROWS * self.max_txs + 1;
 
         let id_offset = BLOCK_LEN + 1 + EXTRA_LEN;
         let index_offset = id_offset + tx_table_len;
@@ -748,7 +792,7 @@ impl<F: Field> PiCircuitConfig<F> {
         let is_final = if is_final { F::ONE } else { F::ZERO };
 
         // Assign vals to raw_public_inputs column
-        let tx_table_len = TX_LEN * self.max_txs + 1;
+        let tx_table_len = TX_ROWS * self.max_txs + 1;
         let calldata_offset = tx_table_len + offset;
 
         self.q_tx_calldata.enable(region, calldata_offset)?;
@@ -974,6 +1018,40 @@ impl<F: Field> PiCircuitConfig<F> {
         raw_pi_vals[offset] = chain_id;
         offset += 1;
 
+        // blob_base_fee
+        let blob_base_fee = rlc(block_values.blob_base_fee.to_le_bytes(), randomness);
+        region.assign_advice(
+            || "blob_base_fee",
+            self.block_table.value,
+            offset,
+            || Value::known(blob_base_fee),
+        )?;
+        region.assign_advice(
+            || "blob_base_fee",
+            self.raw_public_inputs,
+            offset,
+            || Value::known(blob_base_fee),
+        )?;
+        raw_pi_vals[offset] = blob_base_fee;
+        offset += 1;
+
+        // hardfork
+        let hardfork = F::from(block_values.hardfork as u64);
+        region.assign_advice(
+            || "hardfork",
+            self.block_table.value,
+            offset,
+            || Value::known(hardfork),
+        )?;
+        region.assign_advice(
+            || "hardfork",
+            self.raw_public_inputs,
+            offset,
+            || Value::known(hardfork),
+        )?;
+        raw_pi_vals[offset] = hardfork;
+        offset += 1;
+
         for prev_hash in block_values.history_hashes {
             let prev_hash = rlc(prev_hash.to_fixed_bytes(), randomness);
             region.assign_advice(
@@ -1144,8 +1222,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         // Column raw_public_inputs is queried at 4 distinct rotations at
         // - Rotation::cur()
         // - Rotation(BLOCK_LEN + 1 + EXTRA_LEN)
-        // - Rotation(BLOCK_LEN + 1 + EXTRA_LEN + max_txs * TX_LEN + 1)
-        // - Rotation(BLOCK_LEN + 1 + EXTRA_LEN + 2 * (max_txs * TX_LEN + 1))
+        // - Rotation(BLOCK_LEN + 1 + EXTRA_LEN + max_txs * TX_ROWS + 1)
+        // - Rotation(BLOCK_LEN + 1 + EXTRA_LEN + 2 * (max_txs * TX_ROWS + 1))
         // so returns 7 unusable rows.
         7
     }
@@ -1164,7 +1242,9 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 difficulty: block.context.difficulty,
                 gas_limit: block.context.gas_limit.into(),
                 base_fee: block.context.base_fee,
+                blob_base_fee: block.context.blob_base_fee,
             },
+            hardfork: block.context.hardfork,
         };
         let rand_rpi = gen_rand_rpi::<F>(
             block.circuits_params.max_txs,
@@ -1184,7 +1264,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         let row_num = |tx_num, calldata_len| {
-            BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_LEN * tx_num + 1) + calldata_len
+            BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_ROWS * tx_num + 1) + calldata_len
         };
         let calldata_len = block.txs.iter().map(|tx| tx.call_data.len()).sum();
         (
@@ -1206,7 +1286,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         );
         assert_eq!(
             rlc_rpi_col.len(),
-            BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_LEN * self.max_txs + 1) + self.max_calldata
+            BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_ROWS * self.max_txs + 1) + self.max_calldata
         );
 
         // Computation of raw_pulic_inputs
@@ -1330,6 +1410,14 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                             TxFieldTag::GasPrice,
                             rlc(tx.gas_price.to_le_bytes(), self.randomness),
                         ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            rlc(tx.max_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
                         (
                             TxFieldTag::CallerAddress,
                             tx.from_addr.to_scalar().expect("tx.from too big"),
@@ -1352,6 +1440,23 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         (TxFieldTag::SigV, F::from(tx.v)),
                         (TxFieldTag::SigR, rlc(tx.r.to_le_bytes(), self.randomness)),
                         (TxFieldTag::SigS, rlc(tx.s.to_le_bytes(), self.randomness)),
+                        (TxFieldTag::TxType, F::from(tx.tx_type)),
+                        (
+                            TxFieldTag::AccessListGasCost,
+                            F::from(tx.access_list_gas_cost),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            F::from(tx.access_list_addresses_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            F::from(tx.access_list_storage_keys_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListRLC,
+                            access_list_rlc(&tx.access_list, self.randomness),
+                        ),
                     ] {
                         config.assign_tx_row(
                             &mut region,
@@ -1364,6 +1469,18 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         )?;
                         offset += 1;
                     }
+                    for index in 0..MAX_BLOB_HASHES_PER_TX {
+                        config.assign_tx_row(
+                            &mut region,
+                            offset,
+                            i + 1,
+                            TxFieldTag::BlobVersionedHashes,
+                            index,
+                            tx.blob_versioned_hash_rlc(index, self.randomness),
+                            &mut raw_pi_vals,
+                        )?;
+                        offset += 1;
+                    }
                 }
                 // Tx Table CallData
                 let mut calldata_count = 0;
@@ -1427,7 +1544,7 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 }
                 // NOTE: we add this empty row so as to pass mock prover's check
                 //      otherwise it will emit CellNotAssigned Error
-                let tx_table_len = TX_LEN * self.max_txs + 1;
+                let tx_table_len = TX_ROWS * self.max_txs + 1;
                 config.assign_tx_empty_row(&mut region, tx_table_len + offset)?;
 
                 // rpi_rlc and rand_rpi cols
@@ -1466,7 +1583,7 @@ fn raw_public_inputs_col<F: Field>(
 
     let mut offset = 0;
     let mut result =
-        vec![F::ZERO; BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_LEN * max_txs + 1) + max_calldata];
+        vec![F::ZERO; BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_ROWS * max_txs + 1) + max_calldata];
 
     //  Insert Block Values
     // zero row
@@ -1493,6 +1610,12 @@ fn raw_public_inputs_col<F: Field>(
     // chain_id
     result[offset] = F::from(block.chain_id);
     offset += 1;
+    // blob_base_fee
+    result[offset] = rlc(block.blob_base_fee.to_le_bytes(), randomness);
+    offset += 1;
+    // hardfork
+    result[offset] = F::from(block.hardfork as u64);
+    offset += 1;
     // Previous block hashes
     for prev_hash in block.history_hashes {
         result[offset] = rlc(prev_hash.to_fixed_bytes(), randomness);
@@ -1510,7 +1633,7 @@ fn raw_public_inputs_col<F: Field>(
     assert!(txs.len() <= max_txs);
     let tx_default = TxValues::default();
 
-    let tx_table_len = TX_LEN * max_txs + 1;
+    let tx_table_len = TX_ROWS * max_txs + 1;
 
     let id_offset = BLOCK_LEN + 1 + EXTRA_LEN;
     let index_offset = id_offset + tx_table_len;
@@ -1530,6 +1653,8 @@ fn raw_public_inputs_col<F: Field>(
             F::from(tx.nonce),
             F::from(tx.gas),
             rlc(tx.gas_price.to_le_bytes(), randomness),
+            rlc(tx.max_fee_per_gas.to_le_bytes(), randomness),
+            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), randomness),
             tx.from_addr.to_scalar().expect("tx.from too big"),
             tx.to_addr.to_scalar().expect("tx.to too big"),
             F::from(tx.is_create as u64),
@@ -1540,11 +1665,23 @@ fn raw_public_inputs_col<F: Field>(
             F::from(tx.v),
             rlc(tx.r.to_le_bytes(), randomness),
             rlc(tx.s.to_le_bytes(), randomness),
+            F::from(tx.tx_type),
+            F::from(tx.access_list_gas_cost),
+            F::from(tx.access_list_addresses_len),
+            F::from(tx.access_list_storage_keys_len),
+            access_list_rlc(&tx.access_list, randomness),
         ] {
             result[id_offset + offset] = F::from((i + 1) as u64);
             result[index_offset + offset] = F::ZERO;
             result[value_offset + offset] = *val;
 
+            offset += 1;
+        }
+        for index in 0..MAX_BLOB_HASHES_PER_TX {
+            result[id_offset + offset] = F::from((i + 1) as u64);
+            result[index_offset + offset] = F::from(index as u64);
+            result[value_offset + offset] = tx.blob_versioned_hash_rlc(index, randomness);
+
             offset += 1;
         }
     }
@@ -1,5 +1,11 @@
+use crate::tx_circuit::TX_LEN;
+use eth_types::evm_types::MAX_BLOB_HASHES_PER_TX;
+
 /// Fixed by the spec
-pub(super) const BLOCK_LEN: usize = 7 + 256;
+pub(super) const BLOCK_LEN: usize = 9 + 256;
 pub(super) const EXTRA_LEN: usize = 2;
+/// Rows of a tx in the tx table: its static fields followed by its zero padded
+/// blob versioned hashes.
+pub(super) const TX_ROWS: usize = TX_LEN + MAX_BLOB_HASHES_PER_TX;
 pub(super) const ZERO_BYTE_GAS_COST: u64 = 4;
 pub(super) const NONZERO_BYTE_GAS_COST: u64 = 16;
@@ -70,6 +70,106 @@ fn test_simple_pi() {
     assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
 }
 
+#[test]
+fn test_blob_base_fee_pi() {
+    let max_txs = 2;
+    let max_calldata = 8;
+    let public_data = |blob_base_fee: u64| {
+        let mut public_data = PublicData::default();
+        public_data.block_constants.blob_base_fee = blob_base_fee.into();
+        public_data
+    };
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data(23)), Ok(()));
+
+    // The blob base fee is committed in the public inputs.
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    let randomness = Fr::random(&mut rng);
+    let rand_rpi = Fr::random(&mut rng);
+    let instance = |blob_base_fee: u64| {
+        PiCircuit::<Fr>::new(
+            max_txs,
+            max_calldata,
+            randomness,
+            rand_rpi,
+            public_data(blob_base_fee),
+        )
+        .instance()
+    };
+    assert_ne!(instance(1), instance(23));
+}
+
+#[test]
+fn test_hardfork_pi() {
+    let max_txs = 2;
+    let max_calldata = 8;
+    let public_data = |hardfork: Hardfork| PublicData {
+        hardfork,
+        ..Default::default()
+    };
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_calldata, public_data(Hardfork::Shanghai)),
+        Ok(())
+    );
+
+    // The hardfork is committed in the public inputs.
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    let randomness = Fr::random(&mut rng);
+    let rand_rpi = Fr::random(&mut rng);
+    let instance = |hardfork: Hardfork| {
+        PiCircuit::<Fr>::new(
+            max_txs,
+            max_calldata,
+            randomness,
+            rand_rpi,
+            public_data(hardfork),
+        )
+        .instance()
+    };
+    assert_ne!(instance(Hardfork::Shanghai), instance(Hardfork::Cancun));
+}
+
+#[test]
+fn test_blob_versioned_hashes_pi() {
+    let max_txs = 2;
+    let max_calldata = 200;
+    let public_data = |hash: H256| {
+        let mut tx = CORRECT_MOCK_TXS[0].clone();
+        tx.blob_versioned_hashes(vec![H256::repeat_byte(1), hash]);
+        PublicData {
+            chain_id: *MOCK_CHAIN_ID,
+            transactions: vec![tx.into()],
+            ..Default::default()
+        }
+    };
+
+    let k = 17;
+    assert_eq!(
+        run::<Fr>(k, max_txs, max_calldata, public_data(H256::repeat_byte(2))),
+        Ok(())
+    );
+
+    // A tampered blob versioned hash doesn't match the public inputs.
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    let randomness = Fr::random(&mut rng);
+    let rand_rpi = Fr::random(&mut rng);
+    let circuit = |hash: H256| {
+        PiCircuit::<Fr>::new(
+            max_txs,
+            max_calldata,
+            randomness,
+            rand_rpi,
+            public_data(hash),
+        )
+    };
+    let public_inputs = circuit(H256::repeat_byte(2)).instance();
+    let prover = MockProver::run(k, &circuit(H256::repeat_byte(3)), public_inputs).unwrap();
+    assert!(prover.verify().is_err());
+}
+
 fn run_size_check<F: Field>(max_txs: usize, max_calldata: usize, public_data: [PublicData; 2]) {
     let mut rng = ChaCha20Rng::seed_from_u64(2);
     let randomness = F::random(&mut rng);
@@ -0,0 +1,751 @@
+//! The RIPEMD-160 circuit implementation.
+//!
+//! Each block of 64 bytes takes `NUM_ROWS_PER_BLOCK` rows: the hash values of
+//! the previous block on the start rows, one row per round, and the new hash
+//! values on the end rows. A round of each of the two parallel lines only
+//! computes a new `b` working variable, which is stored as bits, so that the
+//! other working variables are found on the previous rows. The message words
+//! are absorbed on the first rounds and copied to the later ones following the
+//! permutations of each line.
+mod param;
+mod ripemd160_bit;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Ripemd160Circuit as TestRipemd160Circuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    param::*,
+    ripemd160_bit::{get_num_ripemd160_blocks, multi_ripemd160, Ripemd160Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Ripemd160Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+/// Ripemd160Config
+#[derive(Clone, Debug)]
+pub struct Ripemd160CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_rounds: [Column<Fixed>; NUM_ROUND_GROUPS],
+    q_rotations_left: [Column<Fixed>; NUM_ROTATIONS],
+    q_rotations_right: [Column<Fixed>; NUM_ROTATIONS],
+    q_absorb: Column<Fixed>,
+    q_padding: Column<Fixed>,
+    q_length: Column<Fixed>,
+    q_end: Column<Fixed>,
+    word_x: [Column<Advice>; NUM_BITS_PER_WORD],
+    x_left: Column<Advice>,
+    x_right: Column<Advice>,
+    word_left: [Column<Advice>; NUM_BITS_PER_WORD],
+    word_right: [Column<Advice>; NUM_BITS_PER_WORD],
+    sum_left: [Column<Advice>; NUM_BITS_PER_WORD],
+    sum_right: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry_sum_left: [Column<Advice>; NUM_CARRY_BITS_SUM],
+    carry_sum_right: [Column<Advice>; NUM_CARRY_BITS_SUM],
+    carry_left: [Column<Advice>; NUM_CARRY_BITS_WORD],
+    carry_right: [Column<Advice>; NUM_CARRY_BITS_WORD],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    /// The columns for other circuits to lookup RIPEMD-160 hash results
+    pub ripemd160_table: Ripemd160Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Ripemd160CircuitConfigArgs<F: Field> {
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Ripemd160CircuitConfig<F> {
+    type ConfigArgs = Ripemd160CircuitConfigArgs<F>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_rounds = array_init::array_init(|_| meta.fixed_column());
+        let q_rotations_left = array_init::array_init(|_| meta.fixed_column());
+        let q_rotations_right = array_init::array_init(|_| meta.fixed_column());
+        let q_absorb = meta.fixed_column();
+        let q_padding = meta.fixed_column();
+        let q_length = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let word_x = array_init::array_init(|_| meta.advice_column());
+        let x_left = meta.advice_column();
+        let x_right = meta.advice_column();
+        let word_left = array_init::array_init(|_| meta.advice_column());
+        let word_right = array_init::array_init(|_| meta.advice_column());
+        let sum_left = array_init::array_init(|_| meta.advice_column());
+        let sum_right = array_init::array_init(|_| meta.advice_column());
+        let carry_sum_left = array_init::array_init(|_| meta.advice_column());
+        let carry_sum_right = array_init::array_init(|_| meta.advice_column());
+        let carry_left = array_init::array_init(|_| meta.advice_column());
+        let carry_right = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        // The message words of the rounds are copied from the absorbed ones.
+        meta.enable_equality(x_left);
+        meta.enable_equality(x_right);
+
+        let is_final = ripemd160_table.is_enabled;
+        let length = ripemd160_table.input_len;
+        let data_rlc = ripemd160_table.input_rlc;
+        let hash_rlc = ripemd160_table.output_rlc;
+
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            // A new hash is started when the previous hash is done or on the first block
+            meta.query_fixed(q_first, Rotation::cur())
+                + meta.query_advice(is_final, Rotation::prev())
+        };
+        // The hash value stored on a start row, and on the end row of the
+        // previous block.
+        let hash_value = |bits: &[Expression<F>], is_rotated: bool| {
+            if is_rotated {
+                decode(&rotate_left(bits, ROTATION_C))
+            } else {
+                decode(bits)
+            }
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in word_x
+                .iter()
+                .chain(word_left.iter())
+                .chain(word_right.iter())
+                .chain(sum_left.iter())
+                .chain(sum_right.iter())
+                .chain(carry_sum_left.iter())
+                .chain(carry_sum_right.iter())
+                .chain(carry_left.iter())
+                .chain(carry_right.iter())
+                .chain(is_paddings.iter())
+                .chain(std::iter::once(&is_final))
+            {
+                cb.require_boolean("boolean bit", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Enforce fixed values on the first row
+        meta.create_gate("first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "is_final needs to be disabled on the dummy row",
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("is final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // Only the last row of a block can have `is_final == 1`.
+            cb.require_zero(
+                "is_final only on the last row of a block",
+                meta.query_advice(is_final, Rotation::cur()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_end, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            // Both lines start from the initial hash values for a new hash, or
+            // else from the hash values of the previous block.
+            for (idx, (h_idx, is_rotated)) in START_ROWS.into_iter().enumerate() {
+                let idx = idx as i32;
+                let bits = query_bits(meta, &word_left, idx);
+                cb.require_equal(
+                    "initial hash value",
+                    hash_value(&bits, is_rotated),
+                    select::expr(
+                        start_new_hash.clone(),
+                        (H[h_idx] as u64).expr(),
+                        decode(&query_bits(meta, &word_left, idx - NUM_END_ROWS as i32)),
+                    ),
+                );
+                cb.require_equal(
+                    "both lines start with the same hash value",
+                    decode(&query_bits(meta, &word_right, idx)),
+                    decode(&bits),
+                );
+            }
+            // The absorbed data is reset for a new hash.
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "reset absorbed data",
+                    meta.query_advice(column, Rotation::cur()),
+                    select::expr(
+                        start_new_hash.clone(),
+                        0.expr(),
+                        meta.query_advice(column, Rotation::prev()),
+                    ),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // The rounds of each group use a different boolean function and round
+        // constant in each line.
+        for (group, q_round) in q_rounds.into_iter().enumerate() {
+            meta.create_gate("round", |meta| {
+                let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+                for (word, sum, carry_sum, carry, x, q_rotations, f_idx, round_cst) in [
+                    (
+                        word_left,
+                        sum_left,
+                        carry_sum_left,
+                        carry_left,
+                        x_left,
+                        q_rotations_left,
+                        group,
+                        ROUND_CST_LEFT[group],
+                    ),
+                    (
+                        word_right,
+                        sum_right,
+                        carry_sum_right,
+                        carry_right,
+                        x_right,
+                        q_rotations_right,
+                        NUM_ROUND_GROUPS - 1 - group,
+                        ROUND_CST_RIGHT[group],
+                    ),
+                ] {
+                    let [b, c, d, e, a] =
+                        [-1, -2, -3, -4, -5].map(|rot| query_bits(meta, &word, rot));
+                    // The working variables d, e and a are the b of previous
+                    // rounds, rotated left when it became d.
+                    let [d, e, a] = [d, e, a].map(|bits| rotate_left(&bits, ROTATION_C));
+                    let sum_bits = query_bits(meta, &sum, 0);
+                    cb.require_equal(
+                        "sum = a + f(b, c, d) + x + k",
+                        decode(&sum_bits)
+                            + decode(&query_bits(meta, &carry_sum, 0))
+                                * (1u64 << NUM_BITS_PER_WORD).expr(),
+                        decode(&a)
+                            + f(f_idx, &b, &c, &d)
+                            + meta.query_advice(x, Rotation::cur())
+                            + (round_cst as u64).expr(),
+                    );
+                    let rotated_sum = q_rotations
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, q_rotation)| {
+                            meta.query_fixed(*q_rotation, Rotation::cur())
+                                * decode(&rotate_left(&sum_bits, MIN_ROTATION + idx))
+                        })
+                        .fold(0.expr(), |acc, rotated_sum| acc + rotated_sum);
+                    cb.require_equal(
+                        "new b = rotate_left(sum, s) + e",
+                        decode(&query_bits(meta, &word, 0))
+                            + decode(&query_bits(meta, &carry, 0))
+                                * (1u64 << NUM_BITS_PER_WORD).expr(),
+                        rotated_sum + decode(&e),
+                    );
+                }
+                cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+            });
+        }
+
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let rot_last = NUM_ROWS_PER_BLOCK as i32 - 1;
+            // The hash values of the previous block, from the start rows
+            let mut hs = vec![0.expr(); START_ROWS.len()];
+            for (idx, (h_idx, is_rotated)) in START_ROWS.into_iter().enumerate() {
+                let bits = query_bits(meta, &word_left, idx as i32 - rot_last);
+                hs[h_idx] = hash_value(&bits, is_rotated);
+            }
+            // The working variables of both lines after the last round
+            let [[a_l, b_l, c_l, d_l, e_l], [a_r, b_r, c_r, d_r, e_r]] = [word_left, word_right]
+                .map(|column| {
+                    let [b, c, d, e, a] = [1, 2, 3, 4, 5]
+                        .map(|idx| query_bits(meta, &column, (END_OFFSET - idx) as i32 - rot_last));
+                    let [d, e, a] = [d, e, a].map(|bits| rotate_left(&bits, ROTATION_C));
+                    [a, b, c, d, e].map(|bits| decode(&bits))
+                });
+            let new_hs = [
+                hs[1].clone() + c_l + d_r,
+                hs[2].clone() + d_l + e_r,
+                hs[3].clone() + e_l + a_r,
+                hs[4].clone() + a_l + b_r,
+                hs[0].clone() + b_l + c_r,
+            ];
+            // The new hash values are on the end rows in the order of the start rows
+            let mut hash_bytes = vec![vec![]; START_ROWS.len()];
+            for (idx, (h_idx, _)) in START_ROWS.into_iter().enumerate() {
+                let rot = (END_OFFSET + idx) as i32 - rot_last;
+                let bits = query_bits(meta, &word_left, rot);
+                cb.require_equal(
+                    "new hash value",
+                    decode(&bits)
+                        + decode(&query_bits(meta, &carry_sum_left, rot))
+                            * (1u64 << NUM_BITS_PER_WORD).expr(),
+                    new_hs[h_idx].clone(),
+                );
+                hash_bytes[h_idx] = to_le_bytes(&bits);
+            }
+            cb.require_equal(
+                "hash rlc check",
+                meta.query_advice(hash_rlc, Rotation::cur()),
+                rlc::expr(
+                    &hash_bytes.into_iter().flatten().rev().collect::<Vec<_>>(),
+                    challenges.keccak_input(),
+                ),
+            );
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // Absorb
+        let prev_is_paddings = |meta: &mut VirtualCells<F>, rot: i32| {
+            // The padding flag of the byte before each byte of the word
+            let mut prev_is_paddings =
+                vec![meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation(rot - 1))];
+            prev_is_paddings.extend(query_bits(
+                meta,
+                &is_paddings[..NUM_BYTES_PER_WORD - 1],
+                rot,
+            ));
+            prev_is_paddings
+        };
+        meta.create_gate("absorb", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let word_x = query_bits(meta, &word_x, 0);
+            // The first rounds of the left line use the message words in order.
+            cb.require_equal(
+                "x_left is the absorbed word",
+                meta.query_advice(x_left, Rotation::cur()),
+                decode(&word_x),
+            );
+
+            let bytes = to_le_bytes(&word_x);
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            for ((byte, is_padding), prev_is_padding) in bytes
+                .iter()
+                .zip(is_paddings.iter())
+                .zip(prev_is_paddings(meta, 0))
+            {
+                cb.require_zero(
+                    "is_padding can only go from 0 to 1",
+                    prev_is_padding.clone() * not::expr(is_padding.clone()),
+                );
+                cb.require_zero(
+                    "the first padding byte is 0x80",
+                    (is_padding.clone() - prev_is_padding) * (byte.clone() - 0x80.expr()),
+                );
+            }
+
+            cb.require_equal(
+                "update length",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.clone())),
+                    ),
+            );
+
+            // The data RLC absorbs the bytes before the first padding byte of the word.
+            let prev_data_rlc = meta.query_advice(data_rlc, Rotation::prev());
+            let new_data_rlc = (0..=NUM_BYTES_PER_WORD)
+                .map(|num_data_bytes| {
+                    let is_num_data_bytes = if num_data_bytes == 0 {
+                        is_paddings[0].clone()
+                    } else if num_data_bytes == NUM_BYTES_PER_WORD {
+                        not::expr(is_paddings[NUM_BYTES_PER_WORD - 1].clone())
+                    } else {
+                        is_paddings[num_data_bytes].clone()
+                            - is_paddings[num_data_bytes - 1].clone()
+                    };
+                    let data_rlc = bytes[..num_data_bytes]
+                        .iter()
+                        .fold(prev_data_rlc.clone(), |acc, byte| {
+                            acc * challenges.keccak_input() + byte.clone()
+                        });
+                    is_num_data_bytes * data_rlc
+                })
+                .fold(0.expr(), |acc, data_rlc| acc + data_rlc);
+            cb.require_equal(
+                "update data rlc",
+                meta.query_advice(data_rlc, Rotation::cur()),
+                new_data_rlc,
+            );
+            cb.gate(meta.query_fixed(q_absorb, Rotation::cur()))
+        });
+
+        meta.create_gate("padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let bytes = to_le_bytes(&query_bits(meta, &word_x, 0));
+            for (byte, prev_is_padding) in bytes.into_iter().zip(prev_is_paddings(meta, 0)) {
+                cb.require_zero(
+                    "padding bytes after the first one are zero",
+                    prev_is_padding * byte,
+                );
+            }
+            cb.gate(meta.query_fixed(q_padding, Rotation::cur()))
+        });
+
+        // The last two words of the last block of a hash hold the length in bits,
+        // low word first. The last block is the first one where the padding
+        // starts before them.
+        meta.create_gate("length", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final_block = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(PADDING_LAST_OFFSET as i32 - ABSORB_LAST_OFFSET as i32),
+            );
+            cb.require_equal(
+                "is_final is set iff the padding starts before the length",
+                meta.query_advice(
+                    is_final,
+                    Rotation((NUM_ROWS_PER_BLOCK - 1 - ABSORB_LAST_OFFSET) as i32),
+                ),
+                is_final_block.clone(),
+            );
+            cb.require_zero(
+                "the last two words are the length in bits",
+                is_final_block.clone()
+                    * (decode(&query_bits(meta, &word_x, 0)) * (1u64 << NUM_BITS_PER_WORD).expr()
+                        + decode(&query_bits(meta, &word_x, -1))
+                        - meta.query_advice(length, Rotation::cur()) * NUM_BITS_PER_BYTE.expr()),
+            );
+            for rot in [-1, 0] {
+                let bytes = to_le_bytes(&query_bits(meta, &word_x, rot));
+                for (byte, prev_is_padding) in bytes.into_iter().zip(prev_is_paddings(meta, rot)) {
+                    cb.require_zero(
+                        "padding bytes after the first one are zero if not the last block",
+                        not::expr(is_final_block.clone()) * prev_is_padding * byte,
+                    );
+                }
+            }
+            cb.gate(meta.query_fixed(q_length, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over absorbed data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "absorbed data unchanged",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                    - meta.query_fixed(q_absorb, Rotation::cur()),
+            )
+        });
+
+        Ripemd160CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_rounds,
+            q_rotations_left,
+            q_rotations_right,
+            q_absorb,
+            q_padding,
+            q_length,
+            q_end,
+            word_x,
+            x_left,
+            x_right,
+            word_left,
+            word_right,
+            sum_left,
+            sum_right,
+            carry_sum_left,
+            carry_sum_right,
+            carry_left,
+            carry_right,
+            is_paddings,
+            ripemd160_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Ripemd160CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Ripemd160Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign ripemd160 rows",
+            |mut region| {
+                let mut x_cells = Vec::with_capacity(NUM_ROUNDS);
+                for (offset, ripemd160_row) in witness.iter().enumerate() {
+                    let cells = self.set_row(&mut region, offset, ripemd160_row)?;
+                    if ripemd160_row.round.is_none() {
+                        continue;
+                    }
+                    x_cells.push(cells);
+                    if x_cells.len() == NUM_ROUNDS {
+                        // Copy the message words of the rounds from the ones
+                        // absorbed by the left line on the first rounds.
+                        for (round, [x_left, x_right]) in x_cells.iter().enumerate() {
+                            if round >= NUM_WORDS_TO_ABSORB {
+                                region.constrain_equal(
+                                    x_left.cell(),
+                                    x_cells[MSG_IDX_LEFT[round]][0].cell(),
+                                )?;
+                            }
+                            region.constrain_equal(
+                                x_right.cell(),
+                                x_cells[MSG_IDX_RIGHT[round]][0].cell(),
+                            )?;
+                        }
+                        x_cells.clear();
+                    }
+                }
+                self.ripemd160_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Ripemd160Row<F>,
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, row.q_enable),
+            // The first block comes right after the dummy row
+            ("q_first", self.q_first, offset == 1),
+            ("q_start", self.q_start, row.q_start),
+            ("q_absorb", self.q_absorb, row.q_absorb),
+            ("q_padding", self.q_padding, row.q_padding),
+            ("q_length", self.q_length, row.q_length),
+            ("q_end", self.q_end, row.q_end),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        let group = row.round.map(|round| round / NUM_ROUNDS_PER_GROUP);
+        let rotation_left = row.round.map(|round| ROTATIONS_LEFT[round] - MIN_ROTATION);
+        let rotation_right = row.round.map(|round| ROTATIONS_RIGHT[round] - MIN_ROTATION);
+        for (name, columns, idx) in [
+            ("q_round", &self.q_rounds[..], group),
+            ("q_rotation_left", &self.q_rotations_left[..], rotation_left),
+            (
+                "q_rotation_right",
+                &self.q_rotations_right[..],
+                rotation_right,
+            ),
+        ] {
+            for (i, column) in columns.iter().enumerate() {
+                region.assign_fixed(
+                    || format!("assign {} {} {}", name, i, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from((idx == Some(i)) as u64)),
+                )?;
+            }
+        }
+
+        self.ripemd160_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final as u64)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Message words
+        let cells = [
+            ("x_left", self.x_left, row.x_left),
+            ("x_right", self.x_right, row.x_right),
+        ]
+        .map(|(name, column, value)| {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )
+        });
+        let [x_left, x_right] = cells;
+
+        // Bits
+        for (name, columns, bits) in [
+            ("x", &self.word_x[..], &row.x[..]),
+            ("word_left", &self.word_left[..], &row.word_left[..]),
+            ("word_right", &self.word_right[..], &row.word_right[..]),
+            ("sum_left", &self.sum_left[..], &row.sum_left[..]),
+            ("sum_right", &self.sum_right[..], &row.sum_right[..]),
+            (
+                "carry_sum_left",
+                &self.carry_sum_left[..],
+                &row.carry_sum_left[..],
+            ),
+            (
+                "carry_sum_right",
+                &self.carry_sum_right[..],
+                &row.carry_sum_right[..],
+            ),
+            ("carry_left", &self.carry_left[..], &row.carry_left[..]),
+            ("carry_right", &self.carry_right[..], &row.carry_right[..]),
+            ("is_padding", &self.is_paddings[..], &row.is_paddings[..]),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?;
+            }
+        }
+
+        Ok([x_left?, x_right?])
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RIPEMD160_q_enable", self.q_enable);
+        region.name_column(|| "RIPEMD160_q_first", self.q_first);
+        region.name_column(|| "RIPEMD160_q_start", self.q_start);
+        region.name_column(|| "RIPEMD160_q_absorb", self.q_absorb);
+        region.name_column(|| "RIPEMD160_q_padding", self.q_padding);
+        region.name_column(|| "RIPEMD160_q_length", self.q_length);
+        region.name_column(|| "RIPEMD160_q_end", self.q_end);
+        region.name_column(|| "RIPEMD160_x_left", self.x_left);
+        region.name_column(|| "RIPEMD160_x_right", self.x_right);
+        for (idx, column) in self.q_rounds.iter().enumerate() {
+            region.name_column(|| format!("RIPEMD160_q_round_{}", idx), *column);
+        }
+    }
+}
+
+/// Ripemd160Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Ripemd160Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Ripemd160Circuit<F> {
+    type Config = Ripemd160CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The words of the lines are queried at 19 distinct rotations.
+        22
+    }
+
+    /// The `block.circuits_params.max_ripemd160_rows` parameter, when non-zero,
+    /// sets up the circuit to support a fixed number of blocks, independently
+    /// of the blocks required by `inputs`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ripemd160_rows,
+            block.ripemd160_inputs.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            1 + block
+                .ripemd160_inputs
+                .iter()
+                .map(|bytes| get_num_ripemd160_blocks(bytes.len()) * NUM_ROWS_PER_BLOCK)
+                .sum::<usize>(),
+            block.circuits_params.max_ripemd160_rows,
+        )
+    }
+
+    /// Make the assignments to the Ripemd160Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Ripemd160Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Ripemd160Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of RIPEMD-160 blocks that can be done in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract the unusable rows and the dummy first row
+            Some((self.num_rows - Self::unusable_rows() - 1) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<Ripemd160Row<F>> {
+        multi_ripemd160(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
@@ -0,0 +1,290 @@
+use super::param::*;
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Ripemd160Row
+#[derive(Clone, Debug)]
+pub(crate) struct Ripemd160Row<F: Field> {
+    pub(crate) q_enable: bool,
+    pub(crate) q_start: bool,
+    pub(crate) q_absorb: bool,
+    pub(crate) q_padding: bool,
+    pub(crate) q_length: bool,
+    pub(crate) q_end: bool,
+    /// The round done on the row, if any.
+    pub(crate) round: Option<usize>,
+    pub(crate) x: [bool; NUM_BITS_PER_WORD],
+    pub(crate) x_left: u32,
+    pub(crate) x_right: u32,
+    pub(crate) word_left: [bool; NUM_BITS_PER_WORD],
+    pub(crate) word_right: [bool; NUM_BITS_PER_WORD],
+    pub(crate) sum_left: [bool; NUM_BITS_PER_WORD],
+    pub(crate) sum_right: [bool; NUM_BITS_PER_WORD],
+    pub(crate) carry_sum_left: [bool; NUM_CARRY_BITS_SUM],
+    pub(crate) carry_sum_right: [bool; NUM_CARRY_BITS_SUM],
+    pub(crate) carry_left: [bool; NUM_CARRY_BITS_WORD],
+    pub(crate) carry_right: [bool; NUM_CARRY_BITS_WORD],
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) is_final: bool,
+    pub(crate) length: usize,
+    pub(crate) data_rlc: Value<F>,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+impl<F: Field> Ripemd160Row<F> {
+    fn new(offset: usize) -> Self {
+        Self {
+            q_enable: true,
+            q_start: offset == 0,
+            q_absorb: (ROUNDS_OFFSET..=ABSORB_LAST_OFFSET).contains(&offset),
+            q_padding: (ROUNDS_OFFSET..=PADDING_LAST_OFFSET).contains(&offset),
+            q_length: offset == ABSORB_LAST_OFFSET,
+            q_end: offset == NUM_ROWS_PER_BLOCK - 1,
+            round: (ROUNDS_OFFSET..END_OFFSET)
+                .contains(&offset)
+                .then(|| offset - ROUNDS_OFFSET),
+            x: [false; NUM_BITS_PER_WORD],
+            x_left: 0,
+            x_right: 0,
+            word_left: [false; NUM_BITS_PER_WORD],
+            word_right: [false; NUM_BITS_PER_WORD],
+            sum_left: [false; NUM_BITS_PER_WORD],
+            sum_right: [false; NUM_BITS_PER_WORD],
+            carry_sum_left: [false; NUM_CARRY_BITS_SUM],
+            carry_sum_right: [false; NUM_CARRY_BITS_SUM],
+            carry_left: [false; NUM_CARRY_BITS_WORD],
+            carry_right: [false; NUM_CARRY_BITS_WORD],
+            is_paddings: [false; NUM_BYTES_PER_WORD],
+            is_final: false,
+            length: 0,
+            data_rlc: Value::known(F::ZERO),
+            hash_rlc: Value::known(F::ZERO),
+        }
+    }
+
+    /// A row outside of any block, with all the selectors disabled.
+    fn dummy() -> Self {
+        Self {
+            q_enable: false,
+            ..Self::new(NUM_ROWS_PER_BLOCK)
+        }
+    }
+}
+
+/// Little-endian bits of the lowest `N` bits of `value`.
+pub(crate) fn to_bits<const N: usize>(value: u64) -> [bool; N] {
+    std::array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+/// The boolean function `idx` of the rounds.
+fn f(idx: usize, x: u32, y: u32, z: u32) -> u32 {
+    match idx {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        4 => x ^ (y | !z),
+        _ => unreachable!("there are only {} boolean functions", NUM_ROUND_GROUPS),
+    }
+}
+
+/// Pad the input to a multiple of the block size: a single 0x80 byte, zeroes
+/// and the length of the input in bits as a 64-bit little-endian integer.
+pub(crate) fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while (padded.len() + NUM_BYTES_PADDING_LENGTH) % RATE != 0 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_le_bytes());
+    padded
+}
+
+/// Number of blocks needed to hash `length` bytes.
+pub(crate) fn get_num_ripemd160_blocks(length: usize) -> usize {
+    (length + 1 + NUM_BYTES_PADDING_LENGTH + RATE - 1) / RATE
+}
+
+/// State of one of the two lines of the compression function.
+#[derive(Clone, Copy)]
+struct Line {
+    /// The working variables `a, b, c, d, e`.
+    state: [u32; 5],
+    msg_idx: &'static [usize; NUM_ROUNDS],
+    rotations: &'static [usize; NUM_ROUNDS],
+    round_cst: &'static [u32; NUM_ROUND_GROUPS],
+    /// Index of the boolean function of the first group of rounds, the next
+    /// groups go on in the direction of `f_step`.
+    f_first: usize,
+    f_step: isize,
+}
+
+impl Line {
+    /// Do a round, returning the sum before the rotation and the new `b`
+    /// working variable, both with their carry.
+    fn round(&mut self, round: usize, words: &[u32]) -> (u64, u64) {
+        let group = round / NUM_ROUNDS_PER_GROUP;
+        let f_idx = (self.f_first as isize + self.f_step * group as isize) as usize;
+        let [a, b, c, d, e] = self.state;
+        let sum = a as u64
+            + f(f_idx, b, c, d) as u64
+            + words[self.msg_idx[round]] as u64
+            + self.round_cst[group] as u64;
+        let new_b = (sum as u32).rotate_left(self.rotations[round] as u32) as u64 + e as u64;
+        self.state = [e, new_b as u32, b, c.rotate_left(ROTATION_C as u32), d];
+        (sum, new_b)
+    }
+}
+
+fn ripemd160<F: Field>(
+    rows: &mut Vec<Ripemd160Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let r = challenges.keccak_input();
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0;
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut is_padding = false;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let mut block_rows: Vec<Ripemd160Row<F>> =
+            (0..NUM_ROWS_PER_BLOCK).map(Ripemd160Row::new).collect();
+
+        // Start rows with the hash values of the previous block
+        for (row, (h_idx, is_rotated)) in block_rows[..ROUNDS_OFFSET].iter_mut().zip(START_ROWS) {
+            let word = if is_rotated {
+                hs[h_idx].rotate_right(ROTATION_C as u32)
+            } else {
+                hs[h_idx]
+            };
+            row.word_left = to_bits(word as u64);
+            row.word_right = to_bits(word as u64);
+        }
+
+        // Rounds
+        let words = block
+            .chunks(NUM_BYTES_PER_WORD)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let mut left = Line {
+            state: hs,
+            msg_idx: &MSG_IDX_LEFT,
+            rotations: &ROTATIONS_LEFT,
+            round_cst: &ROUND_CST_LEFT,
+            f_first: 0,
+            f_step: 1,
+        };
+        let mut right = Line {
+            state: hs,
+            msg_idx: &MSG_IDX_RIGHT,
+            rotations: &ROTATIONS_RIGHT,
+            round_cst: &ROUND_CST_RIGHT,
+            f_first: NUM_ROUND_GROUPS - 1,
+            f_step: -1,
+        };
+        for (round, row) in block_rows[ROUNDS_OFFSET..END_OFFSET].iter_mut().enumerate() {
+            if round < NUM_WORDS_TO_ABSORB {
+                row.x = to_bits(words[round] as u64);
+            }
+            row.x_left = words[MSG_IDX_LEFT[round]];
+            row.x_right = words[MSG_IDX_RIGHT[round]];
+
+            let (sum_left, word_left) = left.round(round, &words);
+            row.sum_left = to_bits(sum_left);
+            row.carry_sum_left = to_bits(sum_left >> NUM_BITS_PER_WORD);
+            row.word_left = to_bits(word_left);
+            row.carry_left = to_bits(word_left >> NUM_BITS_PER_WORD);
+
+            let (sum_right, word_right) = right.round(round, &words);
+            row.sum_right = to_bits(sum_right);
+            row.carry_sum_right = to_bits(sum_right >> NUM_BITS_PER_WORD);
+            row.word_right = to_bits(word_right);
+            row.carry_right = to_bits(word_right >> NUM_BITS_PER_WORD);
+        }
+
+        // End rows with the new hash values
+        let [a_l, b_l, c_l, d_l, e_l] = left.state;
+        let [a_r, b_r, c_r, d_r, e_r] = right.state;
+        let sums = [
+            hs[1] as u64 + c_l as u64 + d_r as u64,
+            hs[2] as u64 + d_l as u64 + e_r as u64,
+            hs[3] as u64 + e_l as u64 + a_r as u64,
+            hs[4] as u64 + a_l as u64 + b_r as u64,
+            hs[0] as u64 + b_l as u64 + c_r as u64,
+        ];
+        for (row, (h_idx, _)) in block_rows[END_OFFSET..].iter_mut().zip(START_ROWS) {
+            row.word_left = to_bits(sums[h_idx]);
+            row.carry_sum_left = to_bits(sums[h_idx] >> NUM_BITS_PER_WORD);
+        }
+        for (hash, sum) in hs.iter_mut().zip(sums) {
+            *hash = sum as u32;
+        }
+
+        // Absorb the data on the first rounds. The length, the data RLC and the
+        // last padding flag are carried over the other rows.
+        for (offset, row) in block_rows.iter_mut().enumerate() {
+            if row.q_absorb {
+                let round = offset - ROUNDS_OFFSET;
+                let word = &block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD];
+                for (byte_idx, byte) in word.iter().enumerate() {
+                    let position = idx * RATE + round * NUM_BYTES_PER_WORD + byte_idx;
+                    is_padding = position >= bytes.len();
+                    row.is_paddings[byte_idx] = is_padding;
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc * r + Value::known(F::from(*byte as u64));
+                    }
+                }
+            } else {
+                row.is_paddings[NUM_BYTES_PER_WORD - 1] = is_padding;
+            }
+            row.length = length;
+            row.data_rlc = data_rlc;
+        }
+
+        let last_row = block_rows.last_mut().unwrap();
+        last_row.is_final = idx == num_blocks - 1;
+        let hash_bytes = hs
+            .iter()
+            .flat_map(|hash| hash.to_le_bytes())
+            .collect::<Vec<_>>();
+        last_row.hash_rlc = r.map(|r| rlc::value(hash_bytes.iter().rev(), r));
+
+        rows.extend(block_rows);
+    }
+}
+
+/// Generate the rows of the RIPEMD-160 circuit for all the inputs, padded with
+/// hashes of empty inputs up to `capacity` blocks.
+pub(crate) fn multi_ripemd160<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Ripemd160Row<F>>, Error> {
+    // Dummy first row so that the first block starts a new hash; `is_final` just
+    // needs to be disabled.
+    let mut rows = vec![Ripemd160Row::dummy()];
+    for bytes in bytes {
+        ripemd160(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            ripemd160(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < 1 + capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > 1 + capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,125 @@
+use super::{ripemd160_bit::pad, *};
+use crate::util::unusable_rows;
+use bus_mapping::precompile::PrecompileCalls;
+use eth_types::Address;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+use revm_precompile::{Precompile, Precompiles};
+
+#[test]
+fn ripemd160_circuit_unusable_rows() {
+    assert_eq!(
+        Ripemd160Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Ripemd160Circuit::<Fr>>(()),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Ripemd160Circuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+/// The output of the RIPEMD-160 precompile, i.e. the digest left padded to 32
+/// bytes.
+fn precompile_output(input: &[u8]) -> Vec<u8> {
+    let Some(Precompile::Standard(ripemd160)) = Precompiles::berlin()
+        .get(Address::from(PrecompileCalls::Ripemd160).as_fixed_bytes()) else {
+        unreachable!("ripemd160 is a standard precompile");
+    };
+    let (_, output) = ripemd160(input, 100_000).unwrap();
+    output
+}
+
+#[test]
+fn ripemd160_padding() {
+    for length in [0, 1, 55, 56, 63, 64, 119, 120, 200] {
+        let input = vec![0xab; length];
+        let padded = pad(&input);
+        assert_eq!(padded.len() % RATE, 0);
+        assert_eq!(padded.len() / RATE, get_num_ripemd160_blocks(length));
+        assert_eq!(padded[length], 0x80);
+        assert_eq!(
+            padded[padded.len() - NUM_BYTES_PADDING_LENGTH..],
+            (length as u64 * 8).to_le_bytes()
+        );
+    }
+}
+
+#[test]
+fn ripemd160_hash_rlc() {
+    let r = Fr::from(0x100);
+    let challenges = Challenges::mock(Value::known(r), Value::known(r), Value::known(r));
+    let inputs = vec![
+        vec![],
+        b"abc".to_vec(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..64).collect(),
+        (0u8..200).collect(),
+    ];
+    let rows = multi_ripemd160::<Fr>(&inputs, challenges, None).unwrap();
+    let final_rows = rows.iter().filter(|row| row.is_final).collect::<Vec<_>>();
+    assert_eq!(final_rows.len(), inputs.len());
+    for (input, row) in inputs.iter().zip(final_rows) {
+        let [_, input_rlc, input_len, output_rlc] = Ripemd160Table::assignments(input, &challenges);
+        row.data_rlc.zip(input_rlc).assert_if_known(|(a, b)| a == b);
+        input_len.assert_if_known(|len| *len == Fr::from(row.length as u64));
+        row.hash_rlc
+            .zip(output_rlc)
+            .assert_if_known(|(a, b)| a == b);
+
+        // The leading zeroes of the precompile output don't change the RLC.
+        let output = precompile_output(input);
+        assert_eq!(output.len(), 32);
+        let output_rlc = rlc::value(output.iter().rev(), r);
+        row.hash_rlc
+            .assert_if_known(|hash_rlc| *hash_rlc == output_rlc);
+    }
+}
+
+#[test]
+fn ripemd160_circuit_simple() {
+    let k = 12;
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..120).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(k, inputs, true);
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 12;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let inputs = vec![];
+    let circuit = Ripemd160Circuit::new(num_rows, inputs);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    let circuit = Ripemd160Circuit::new(num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
@@ -0,0 +1,54 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::{not, or, select, xor, Expr};
+use halo2_proofs::plonk::Expression;
+
+/// Decode the value of little-endian bits.
+pub(crate) fn decode<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Little-endian bytes of a word given as little-endian bits.
+pub(crate) fn to_le_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits.chunks(NUM_BITS_PER_BYTE).map(decode).collect()
+}
+
+/// Bits of a word rotated left by `n`.
+pub(crate) fn rotate_left<F: Field>(bits: &[Expression<F>], n: usize) -> Vec<Expression<F>> {
+    (0..NUM_BITS_PER_WORD)
+        .map(|i| bits[(i + NUM_BITS_PER_WORD - n) % NUM_BITS_PER_WORD].clone())
+        .collect()
+}
+
+/// Value of the boolean function `idx` of the rounds, applied bitwise:
+/// - `x ^ y ^ z`
+/// - `(x & y) | (!x & z)`
+/// - `(x | !y) ^ z`
+/// - `(x & z) | (y & !z)`
+/// - `x ^ (y | !z)`
+pub(crate) fn f<F: Field>(
+    idx: usize,
+    x: &[Expression<F>],
+    y: &[Expression<F>],
+    z: &[Expression<F>],
+) -> Expression<F> {
+    decode(
+        &x.iter()
+            .zip(y)
+            .zip(z)
+            .map(|((x, y), z)| {
+                let (x, y, z) = (x.clone(), y.clone(), z.clone());
+                match idx {
+                    0 => xor::expr(xor::expr(x, y), z),
+                    1 => select::expr(x, y, z),
+                    2 => xor::expr(or::expr([x, not::expr(y)]), z),
+                    3 => select::expr(z, x, y),
+                    4 => xor::expr(x, or::expr([y, not::expr(z)])),
+                    _ => unreachable!("there are only {} boolean functions", NUM_ROUND_GROUPS),
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+}
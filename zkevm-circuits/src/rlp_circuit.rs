@@ -0,0 +1,1053 @@
+//! The RLP circuit implementation.
+//!
+//! The encodings of the transactions for signing and for hashing are laid out
+//! one byte per row, and the RLC of their bytes is looked up in the keccak
+//! table to expose their hashes in the RLP table. An encoding is split into
+//! fields, each made of an RLP header followed by its payload, whose value is
+//! checked against the tx table, or the block table for the chain id. The
+//! order of the fields of each transaction type and format is fixed by a ROM
+//! of the transitions between their tags. The access list is split into the
+//! headers of its lists, its addresses and its storage keys, whose RLC is
+//! checked against the tx table.
+mod param;
+mod rlp_witness;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::RlpCircuit as TestRlpCircuit;
+
+use std::{iter::once, marker::PhantomData};
+
+use self::{
+    param::*,
+    rlp_witness::{rlp_rows, tx_encoding, RlpRow},
+};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{
+        byte_table::ByteTable, BlockContextFieldTag, BlockTable, KeccakTable, LookupTable,
+        RlpTable, TxFieldTag, TxTable,
+    },
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::{self, Transaction},
+};
+use eth_types::Field;
+use gadgets::util::{not, select, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use strum::IntoEnumIterator;
+
+/// RlpCircuitConfig
+#[derive(Clone, Debug)]
+pub struct RlpCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    tx_type: Column<Advice>,
+    tag: Column<Advice>,
+    tag_idx: Column<Advice>,
+    tag_len: Column<Advice>,
+    header_len: Column<Advice>,
+    payload_len: Column<Advice>,
+    is_long: Column<Advice>,
+    is_payload: Column<Advice>,
+    is_first: Column<Advice>,
+    is_tag_start: Column<Advice>,
+    is_tag_end: Column<Advice>,
+    byte: Column<Advice>,
+    byte_idx: Column<Advice>,
+    length: Column<Advice>,
+    rlc: Column<Advice>,
+    value_acc: Column<Advice>,
+    len_acc: Column<Advice>,
+    byte_inv: Column<Advice>,
+    diff: Column<Advice>,
+    /// Index of the last byte of the access list, of its current item and of
+    /// the storage keys of the item
+    list_ends: [Column<Advice>; 3],
+    /// RLC of the addresses and storage keys of the access list
+    access_list_rlc: Column<Advice>,
+    /// Flags of the tag, in the order of `RlpTag::flags`
+    tag_flags: [Column<Advice>; NUM_TAG_FLAGS],
+    q_tag_rom: Column<Fixed>,
+    /// The tags followed by their flags
+    tag_rom: [Column<Fixed>; NUM_TAG_FLAGS + 1],
+    /// The transitions `[tx_type, format, tag, next_tag]` between the tags
+    transition_rom: [Column<Fixed>; 4],
+    /// The columns for other circuits to lookup the hashes of the encodings
+    pub rlp_table: RlpTable,
+    tx_table: TxTable,
+    block_table: BlockTable,
+    keccak_table: KeccakTable,
+    byte_table: ByteTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct RlpCircuitConfigArgs<F: Field> {
+    /// RlpTable
+    pub rlp_table: RlpTable,
+    /// TxTable
+    pub tx_table: TxTable,
+    /// BlockTable
+    pub block_table: BlockTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// ByteTable
+    pub byte_table: ByteTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for RlpCircuitConfig<F> {
+    type ConfigArgs = RlpCircuitConfigArgs<F>;
+
+    /// Return a new RlpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            rlp_table,
+            tx_table,
+            block_table,
+            keccak_table,
+            byte_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let tx_type = meta.advice_column();
+        let tag = meta.advice_column();
+        let tag_idx = meta.advice_column();
+        let tag_len = meta.advice_column();
+        let header_len = meta.advice_column();
+        let payload_len = meta.advice_column();
+        let is_long = meta.advice_column();
+        let is_payload = meta.advice_column();
+        let is_first = meta.advice_column();
+        let is_tag_start = meta.advice_column();
+        let is_tag_end = meta.advice_column();
+        let byte = meta.advice_column();
+        let byte_idx = meta.advice_column();
+        let length = meta.advice_column();
+        let rlc = meta.advice_column_in(SecondPhase);
+        let value_acc = meta.advice_column_in(SecondPhase);
+        let len_acc = meta.advice_column();
+        let byte_inv = meta.advice_column();
+        let diff = meta.advice_column();
+        let list_ends = [(); 3].map(|_| meta.advice_column());
+        let access_list_rlc = meta.advice_column_in(SecondPhase);
+        let tag_flags = [(); NUM_TAG_FLAGS].map(|_| meta.advice_column());
+        let q_tag_rom = meta.fixed_column();
+        let tag_rom = [(); NUM_TAG_FLAGS + 1].map(|_| meta.fixed_column());
+        let transition_rom = [(); 4].map(|_| meta.fixed_column());
+
+        // The flags of the tag, in the order of `RlpTag::flags`
+        let is_padding = tag_flags[0];
+        let is_list = tag_flags[1];
+        let is_list_prefix = tag_flags[2];
+        let is_raw = tag_flags[3];
+        let is_word = tag_flags[4];
+        let is_scalar = tag_flags[5];
+        let is_tx_field = tag_flags[6];
+        let tx_field_tag = tag_flags[7];
+        let is_to = tag_flags[8];
+        let is_data = tag_flags[9];
+        let is_chain_id = tag_flags[10];
+        let is_empty = tag_flags[11];
+        let is_access_list = tag_flags[12];
+        let is_access_list_item = tag_flags[13];
+        let is_access_list_address = tag_flags[14];
+        let is_storage_keys = tag_flags[15];
+        let is_storage_key = tag_flags[16];
+        let is_last = rlp_table.is_enabled;
+        let tx_id = rlp_table.tx_id;
+        let format = rlp_table.format;
+        let hash_rlc = rlp_table.hash_rlc;
+
+        let query_fixed = |meta: &mut VirtualCells<F>, column: Column<Fixed>| {
+            meta.query_fixed(column, Rotation::cur())
+        };
+        let cur = |meta: &mut VirtualCells<F>, column: Column<Advice>| {
+            meta.query_advice(column, Rotation::cur())
+        };
+        let prev = |meta: &mut VirtualCells<F>, column: Column<Advice>| {
+            meta.query_advice(column, Rotation::prev())
+        };
+        // Rows of the encodings, which aren't padding
+        let q_encoding = |meta: &mut VirtualCells<F>| {
+            query_fixed(meta, q_enable) * not::expr(cur(meta, is_padding))
+        };
+        let address_len_inv = Expression::Constant(F::from(ADDRESS_LEN).invert().unwrap());
+
+        meta.create_gate("rlp row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [
+                is_padding,
+                is_first,
+                is_tag_start,
+                is_tag_end,
+                is_last,
+                is_payload,
+                is_long,
+            ] {
+                cb.require_boolean("boolean", cur(meta, column));
+            }
+            // The encodings and their fields start after the end of the previous ones
+            let q_first = query_fixed(meta, q_first);
+            cb.require_equal(
+                "is_first = q_first or previous is_last",
+                cur(meta, is_first),
+                q_first.clone() + not::expr(q_first.clone()) * prev(meta, is_last),
+            );
+            cb.require_equal(
+                "is_tag_start = q_first or previous is_tag_end",
+                cur(meta, is_tag_start),
+                q_first.clone() + not::expr(q_first.clone()) * prev(meta, is_tag_end),
+            );
+            cb.require_zero(
+                "padding is followed by padding",
+                not::expr(q_first) * prev(meta, is_padding) * not::expr(cur(meta, is_padding)),
+            );
+            cb.condition(cur(meta, is_padding), |cb| {
+                cb.require_zero("no last row in padding", cur(meta, is_last));
+                cb.require_equal("padding rows end", cur(meta, is_tag_end), 1.expr());
+                cb.require_zero("no payload in padding", cur(meta, is_payload));
+                cb.require_zero("no long header in padding", cur(meta, is_long));
+            });
+            cb.gate(query_fixed(meta, q_enable))
+        });
+
+        meta.create_gate("rlp encoding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_first = cur(meta, is_first);
+            cb.condition(is_first.clone(), |cb| {
+                cb.require_equal("byte_idx starts at 1", cur(meta, byte_idx), 1.expr());
+                cb.require_equal("rlc = byte", cur(meta, rlc), cur(meta, byte));
+            });
+            cb.condition(not::expr(is_first), |cb| {
+                for column in [tx_id, format, tx_type, length] {
+                    cb.require_equal(
+                        "value unchanged in the encoding",
+                        cur(meta, column),
+                        prev(meta, column),
+                    );
+                }
+                cb.require_equal(
+                    "byte_idx = previous byte_idx + 1",
+                    cur(meta, byte_idx),
+                    prev(meta, byte_idx) + 1.expr(),
+                );
+                cb.require_equal(
+                    "rlc = previous rlc * r + byte",
+                    cur(meta, rlc),
+                    prev(meta, rlc) * challenges.keccak_input() + cur(meta, byte),
+                );
+            });
+            cb.condition(cur(meta, is_last), |cb| {
+                cb.require_equal("last field ends", cur(meta, is_tag_end), 1.expr());
+                cb.require_equal(
+                    "byte_idx = length on the last row",
+                    cur(meta, byte_idx),
+                    cur(meta, length),
+                );
+            });
+            cb.gate(q_encoding(meta))
+        });
+
+        meta.create_gate("rlp field", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_tag_start = cur(meta, is_tag_start);
+            let is_payload_expr = cur(meta, is_payload);
+            let byte_expr = cur(meta, byte);
+            cb.condition(is_tag_start.clone(), |cb| {
+                cb.require_equal("tag_idx starts at 1", cur(meta, tag_idx), 1.expr());
+                cb.require_zero("len_acc starts at 0", cur(meta, len_acc));
+                cb.require_equal(
+                    "value_acc starts with the first byte of the payload",
+                    cur(meta, value_acc),
+                    is_payload_expr.clone() * byte_expr.clone(),
+                );
+            });
+            cb.condition(not::expr(is_tag_start.clone()), |cb| {
+                for column in [tag, tag_len, header_len, payload_len, is_long] {
+                    cb.require_equal(
+                        "value unchanged in the field",
+                        cur(meta, column),
+                        prev(meta, column),
+                    );
+                }
+                cb.require_equal(
+                    "tag_idx = previous tag_idx + 1",
+                    cur(meta, tag_idx),
+                    prev(meta, tag_idx) + 1.expr(),
+                );
+                cb.require_zero(
+                    "the payload follows the header",
+                    prev(meta, is_payload) * not::expr(is_payload_expr.clone()),
+                );
+                // The length of a long header is accumulated from its bytes
+                // after the first one.
+                cb.require_equal(
+                    "len_acc = previous len_acc * 256 + byte in the header",
+                    cur(meta, len_acc),
+                    select::expr(
+                        is_payload_expr.clone(),
+                        prev(meta, len_acc),
+                        prev(meta, len_acc) * 256.expr() + byte_expr.clone(),
+                    ),
+                );
+                // Words are accumulated as the RLC of their bytes, and other
+                // values as their big-endian integer.
+                let mult = 256.expr() + cur(meta, is_word) * (challenges.evm_word() - 256.expr());
+                cb.require_equal(
+                    "value_acc = previous value_acc * mult + byte in the payload",
+                    cur(meta, value_acc),
+                    select::expr(
+                        is_payload_expr.clone(),
+                        prev(meta, value_acc) * mult + byte_expr,
+                        prev(meta, value_acc),
+                    ),
+                );
+            });
+            cb.condition(cur(meta, is_tag_end), |cb| {
+                cb.require_equal(
+                    "tag_idx = tag_len at the end of the field",
+                    cur(meta, tag_idx),
+                    cur(meta, tag_len),
+                );
+                cb.require_equal(
+                    "tag_len = header_len + payload_len, or header_len for a list",
+                    cur(meta, tag_len),
+                    cur(meta, header_len) + not::expr(cur(meta, is_list)) * cur(meta, payload_len),
+                );
+                cb.require_zero(
+                    "header_len bytes in a field without payload",
+                    not::expr(cur(meta, is_payload)) * (cur(meta, tag_idx) - cur(meta, header_len)),
+                );
+                cb.require_zero(
+                    "payload_len is the length in a long header",
+                    cur(meta, is_long) * (cur(meta, len_acc) - cur(meta, payload_len)),
+                );
+                cb.require_zero(
+                    "to is an address or empty",
+                    cur(meta, is_to)
+                        * cur(meta, payload_len)
+                        * (cur(meta, payload_len) - ADDRESS_LEN.expr()),
+                );
+                cb.require_zero(
+                    "empty payload",
+                    cur(meta, is_empty) * cur(meta, payload_len),
+                );
+                cb.require_zero(
+                    "access list address length",
+                    cur(meta, is_access_list_address)
+                        * (cur(meta, payload_len) - ADDRESS_LEN.expr()),
+                );
+                cb.require_zero(
+                    "access list storage key length",
+                    cur(meta, is_storage_key) * (cur(meta, payload_len) - STORAGE_KEY_LEN.expr()),
+                );
+                cb.require_zero(
+                    "the list prefix is the header of the rest of the encoding",
+                    cur(meta, is_list_prefix)
+                        * (cur(meta, payload_len) - cur(meta, length) + cur(meta, byte_idx)),
+                );
+            });
+            cb.require_zero(
+                "header_len bytes before the payload",
+                is_payload_expr.clone()
+                    * (is_tag_start.clone()
+                        + not::expr(is_tag_start) * not::expr(prev(meta, is_payload)))
+                    * (cur(meta, tag_idx) - cur(meta, header_len) - 1.expr()),
+            );
+            cb.require_zero("no payload in a list", cur(meta, is_list) * is_payload_expr);
+            cb.require_zero(
+                "short header of a scalar",
+                cur(meta, is_scalar) * cur(meta, is_long),
+            );
+            cb.gate(q_encoding(meta))
+        });
+
+        meta.create_gate("rlp field start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_payload = cur(meta, is_payload);
+            let is_long = cur(meta, is_long);
+            let byte_next = meta.query_advice(byte, Rotation::next());
+            let byte = cur(meta, byte);
+            // A single byte below 0x80 is its own encoding, and the type of a
+            // typed transaction is a raw byte.
+            cb.condition(is_payload.clone(), |cb| {
+                cb.require_equal("single byte payload", cur(meta, payload_len), 1.expr());
+                cb.require_zero("a list has a header", cur(meta, is_list));
+                cb.require_zero(
+                    "single byte < 0x80",
+                    not::expr(cur(meta, is_raw)) * (cur(meta, diff) - (0x7f.expr() - byte.clone())),
+                );
+                cb.require_zero(
+                    "raw byte is the tx type",
+                    cur(meta, is_raw) * (byte.clone() - cur(meta, tx_type)),
+                );
+                cb.require_zero(
+                    "scalar has no leading zero",
+                    cur(meta, is_scalar) * (byte.clone() * cur(meta, byte_inv) - 1.expr()),
+                );
+            });
+            cb.condition(not::expr(is_payload), |cb| {
+                cb.require_zero("a raw byte has no header", cur(meta, is_raw));
+                let header_len = cur(meta, header_len);
+                let payload_len = cur(meta, payload_len);
+                cb.require_equal(
+                    "first byte of the header",
+                    byte,
+                    0x80.expr()
+                        + cur(meta, is_list) * 0x40.expr()
+                        + select::expr(
+                            is_long.clone(),
+                            MAX_SHORT_PAYLOAD_LEN.expr() + header_len.clone() - 1.expr(),
+                            payload_len.clone(),
+                        ),
+                );
+                cb.require_zero(
+                    "a short header is a single byte",
+                    not::expr(is_long.clone()) * (header_len.clone() - 1.expr()),
+                );
+                cb.require_equal(
+                    "diff bounds the length of the payload or of its length",
+                    cur(meta, diff),
+                    select::expr(
+                        is_long.clone(),
+                        (MAX_LEN_OF_LEN + 1).expr() - header_len,
+                        MAX_SHORT_PAYLOAD_LEN.expr() - payload_len,
+                    ),
+                );
+                cb.require_zero(
+                    "scalar or length of a long header has no leading zero",
+                    (cur(meta, is_scalar) + is_long)
+                        * not::expr(cur(meta, is_tag_end))
+                        * (byte_next * cur(meta, byte_inv) - 1.expr()),
+                );
+            });
+            cb.gate(q_encoding(meta) * cur(meta, is_tag_start))
+        });
+
+        // The headers of the lists of the access list are followed by their
+        // payload, which ends at the end of the last field in the list.
+        let next = |meta: &mut VirtualCells<F>, column: Column<Advice>| {
+            not::expr(cur(meta, is_last)) * meta.query_advice(column, Rotation::next())
+        };
+        let is_access_list_end = |meta: &mut VirtualCells<F>| {
+            let is_in_access_list = |meta: &mut VirtualCells<F>, rotation: Rotation| {
+                [
+                    is_access_list_item,
+                    is_access_list_address,
+                    is_storage_keys,
+                    is_storage_key,
+                ]
+                .map(|column| meta.query_advice(column, rotation))
+                .into_iter()
+                .fold(0.expr(), |acc, flag| acc + flag)
+            };
+            cur(meta, is_tag_end)
+                * (cur(meta, is_access_list) + is_in_access_list(meta, Rotation::cur()))
+                * not::expr(
+                    not::expr(cur(meta, is_last)) * is_in_access_list(meta, Rotation::next()),
+                )
+        };
+        meta.create_gate("rlp access list", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_first = cur(meta, is_first);
+            let is_tag_end = cur(meta, is_tag_end);
+            for (flag, list_end) in [is_access_list, is_access_list_item, is_storage_keys]
+                .into_iter()
+                .zip(list_ends)
+            {
+                let is_header_end = is_tag_end.clone() * cur(meta, flag);
+                cb.condition(is_header_end.clone(), |cb| {
+                    cb.require_equal(
+                        "list_end = byte_idx + payload_len at the end of the header",
+                        cur(meta, list_end),
+                        cur(meta, byte_idx) + cur(meta, payload_len),
+                    );
+                });
+                cb.condition(
+                    not::expr(is_first.clone()) * not::expr(is_header_end),
+                    |cb| {
+                        cb.require_equal(
+                            "list_end unchanged",
+                            cur(meta, list_end),
+                            prev(meta, list_end),
+                        );
+                    },
+                );
+            }
+            // An item ends with its storage keys, which end before the next
+            // item or the end of the access list.
+            cb.condition(
+                is_tag_end.clone()
+                    * (cur(meta, is_storage_keys) + cur(meta, is_storage_key))
+                    * not::expr(next(meta, is_storage_key)),
+                |cb| {
+                    for list_end in [list_ends[1], list_ends[2]] {
+                        cb.require_equal(
+                            "the item and its storage keys end",
+                            cur(meta, byte_idx),
+                            cur(meta, list_end),
+                        );
+                    }
+                },
+            );
+            cb.condition(is_access_list_end(meta), |cb| {
+                cb.require_equal(
+                    "the access list ends",
+                    cur(meta, byte_idx),
+                    cur(meta, list_ends[0]),
+                );
+            });
+
+            // The addresses and storage keys are accumulated in the order they
+            // are listed.
+            cb.condition(cur(meta, is_access_list), |cb| {
+                cb.require_zero("access_list_rlc starts at 0", cur(meta, access_list_rlc));
+            });
+            cb.condition(
+                not::expr(is_first) * not::expr(cur(meta, is_access_list)),
+                |cb| {
+                    cb.require_equal(
+                        "access_list_rlc accumulates the addresses and storage keys",
+                        cur(meta, access_list_rlc),
+                        select::expr(
+                            is_tag_end
+                                * (cur(meta, is_access_list_address) + cur(meta, is_storage_key)),
+                            prev(meta, access_list_rlc) * challenges.evm_word()
+                                + cur(meta, value_acc),
+                            prev(meta, access_list_rlc),
+                        ),
+                    );
+                },
+            );
+            cb.gate(q_encoding(meta))
+        });
+
+        for (name, column) in [("rlp byte", byte), ("rlp diff", diff)] {
+            meta.lookup_any(name, |meta| {
+                let value = query_fixed(meta, q_enable) * cur(meta, column);
+                vec![(value, byte_table.table_exprs(meta)[0].clone())]
+            });
+        }
+
+        meta.lookup_any("rlp tag rom", |meta| {
+            let q_enable = query_fixed(meta, q_enable);
+            let input = once(q_enable.clone())
+                .chain(
+                    once(tag)
+                        .chain(tag_flags)
+                        .map(|column| q_enable.clone() * cur(meta, column))
+                        .collect::<Vec<_>>(),
+                )
+                .collect::<Vec<_>>();
+            let table = once(q_tag_rom)
+                .chain(tag_rom)
+                .map(|column| query_fixed(meta, column))
+                .collect::<Vec<_>>();
+            input.into_iter().zip(table).collect()
+        });
+
+        meta.lookup_any("rlp first tag", |meta| {
+            let enable = q_encoding(meta) * cur(meta, is_first);
+            let input = [
+                cur(meta, tx_type),
+                cur(meta, format),
+                RlpTag::Padding.expr(),
+                cur(meta, tag),
+            ];
+            let table = transition_rom.map(|column| query_fixed(meta, column));
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("rlp next tag", |meta| {
+            let enable = q_encoding(meta) * cur(meta, is_tag_end);
+            let input = [
+                cur(meta, tx_type),
+                cur(meta, format),
+                cur(meta, tag),
+                not::expr(cur(meta, is_last)) * meta.query_advice(tag, Rotation::next()),
+            ];
+            let table = transition_rom.map(|column| query_fixed(meta, column));
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("rlp keccak", |meta| {
+            let enable = query_fixed(meta, q_enable) * cur(meta, is_last);
+            let input = [
+                1.expr(),
+                cur(meta, rlc),
+                cur(meta, length),
+                cur(meta, hash_rlc),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        let tx_lookup =
+            |meta: &mut VirtualCells<F>, enable: Expression<F>, input: [Expression<F>; 4]| {
+                input
+                    .into_iter()
+                    .zip(tx_table.table_exprs(meta))
+                    .map(|(input, table)| (enable.clone() * input, table))
+                    .collect::<Vec<_>>()
+            };
+        let field_end = |meta: &mut VirtualCells<F>, flag: Column<Advice>| {
+            query_fixed(meta, q_enable) * cur(meta, is_tag_end) * cur(meta, flag)
+        };
+
+        meta.lookup_any("rlp tx type", |meta| {
+            let enable = q_encoding(meta) * cur(meta, is_first);
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::TxType.expr(),
+                0.expr(),
+                cur(meta, tx_type),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp tx field", |meta| {
+            let enable = field_end(meta, is_tx_field);
+            let input = [
+                cur(meta, tx_id),
+                cur(meta, tx_field_tag),
+                0.expr(),
+                cur(meta, value_acc),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        // The callee address is only checked when the `to` field isn't empty,
+        // which is a contract creation otherwise.
+        meta.lookup_any("rlp callee address", |meta| {
+            let enable = field_end(meta, is_to) * cur(meta, payload_len) * address_len_inv.clone();
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::CalleeAddress.expr(),
+                0.expr(),
+                cur(meta, value_acc),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp is create", |meta| {
+            let enable = field_end(meta, is_to);
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::IsCreate.expr(),
+                0.expr(),
+                (ADDRESS_LEN.expr() - cur(meta, payload_len)) * address_len_inv.clone(),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp call data length", |meta| {
+            let enable = field_end(meta, is_data);
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::CallDataLength.expr(),
+                0.expr(),
+                cur(meta, payload_len),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp call data", |meta| {
+            let enable = query_fixed(meta, q_enable) * cur(meta, is_data) * cur(meta, is_payload);
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::CallData.expr(),
+                cur(meta, tag_idx) - cur(meta, header_len) - 1.expr(),
+                cur(meta, byte),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp access list rlc", |meta| {
+            let enable = query_fixed(meta, q_enable) * is_access_list_end(meta);
+            let input = [
+                cur(meta, tx_id),
+                TxFieldTag::AccessListRLC.expr(),
+                0.expr(),
+                cur(meta, access_list_rlc),
+            ];
+            tx_lookup(meta, enable, input)
+        });
+
+        meta.lookup_any("rlp chain id", |meta| {
+            let enable = field_end(meta, is_chain_id);
+            let input = [
+                BlockContextFieldTag::ChainId.expr(),
+                0.expr(),
+                cur(meta, value_acc),
+            ];
+            input
+                .into_iter()
+                .zip(block_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            tx_type,
+            tag,
+            tag_idx,
+            tag_len,
+            header_len,
+            payload_len,
+            is_long,
+            is_payload,
+            is_first,
+            is_tag_start,
+            is_tag_end,
+            byte,
+            byte_idx,
+            length,
+            rlc,
+            value_acc,
+            len_acc,
+            byte_inv,
+            diff,
+            list_ends,
+            access_list_rlc,
+            tag_flags,
+            q_tag_rom,
+            tag_rom,
+            transition_rom,
+            rlp_table,
+            tx_table,
+            block_table,
+            keccak_table,
+            byte_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> RlpCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[RlpRow<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "rlp roms",
+            |mut region| {
+                for (offset, tag) in RlpTag::iter().enumerate() {
+                    region.assign_fixed(
+                        || format!("assign q_tag_rom {}", offset),
+                        self.q_tag_rom,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, value) in
+                        self.tag_rom.iter().zip(once(tag as u64).chain(tag.flags()))
+                    {
+                        region.assign_fixed(
+                            || format!("assign tag rom {}", offset),
+                            *column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                for (offset, row) in rlp_transitions().into_iter().enumerate() {
+                    for (column, value) in self.transition_rom.iter().zip(row) {
+                        region.assign_fixed(
+                            || format!("assign transition rom {}", offset),
+                            *column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "assign rlp rows",
+            |mut region| {
+                for (offset, row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, row)?;
+                }
+                self.rlp_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &RlpRow<F>,
+    ) -> Result<(), Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        for (name, column, value) in [
+            (
+                "is_last",
+                self.rlp_table.is_enabled,
+                F::from(row.is_last as u64),
+            ),
+            ("tx_id", self.rlp_table.tx_id, F::from(row.tx_id as u64)),
+            ("format", self.rlp_table.format, F::from(row.format as u64)),
+            ("tx_type", self.tx_type, F::from(row.tx_type as u64)),
+            ("tag", self.tag, F::from(row.tag as u64)),
+            ("tag_idx", self.tag_idx, F::from(row.tag_idx as u64)),
+            ("tag_len", self.tag_len, F::from(row.tag_len as u64)),
+            (
+                "header_len",
+                self.header_len,
+                F::from(row.header_len as u64),
+            ),
+            (
+                "payload_len",
+                self.payload_len,
+                F::from(row.payload_len as u64),
+            ),
+            ("is_long", self.is_long, F::from(row.is_long as u64)),
+            (
+                "is_payload",
+                self.is_payload,
+                F::from(row.is_payload as u64),
+            ),
+            ("is_first", self.is_first, F::from(row.is_first as u64)),
+            (
+                "is_tag_start",
+                self.is_tag_start,
+                F::from(row.is_tag_start as u64),
+            ),
+            (
+                "is_tag_end",
+                self.is_tag_end,
+                F::from(row.is_tag_end as u64),
+            ),
+            ("byte", self.byte, F::from(row.byte as u64)),
+            ("byte_idx", self.byte_idx, F::from(row.byte_idx as u64)),
+            ("length", self.length, F::from(row.length as u64)),
+            ("len_acc", self.len_acc, F::from(row.len_acc)),
+            ("byte_inv", self.byte_inv, row.byte_inv),
+            ("diff", self.diff, F::from(row.diff)),
+            (
+                "access_list_end",
+                self.list_ends[0],
+                F::from(row.list_ends[0] as u64),
+            ),
+            (
+                "item_end",
+                self.list_ends[1],
+                F::from(row.list_ends[1] as u64),
+            ),
+            (
+                "storage_keys_end",
+                self.list_ends[2],
+                F::from(row.list_ends[2] as u64),
+            ),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+        for (idx, (column, value)) in self.tag_flags.iter().zip(row.tag.flags()).enumerate() {
+            region.assign_advice(
+                || format!("assign tag flag {} {}", idx, offset),
+                *column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("rlc", self.rlc, row.rlc),
+            ("value_acc", self.value_acc, row.value_acc),
+            ("access_list_rlc", self.access_list_rlc, row.access_list_rlc),
+            ("hash_rlc", self.rlp_table.hash_rlc, row.hash_rlc),
+        ] {
+            region.assign_advice(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RLP_q_enable", self.q_enable);
+        region.name_column(|| "RLP_q_first", self.q_first);
+        region.name_column(|| "RLP_tx_type", self.tx_type);
+        region.name_column(|| "RLP_tag", self.tag);
+        region.name_column(|| "RLP_tag_idx", self.tag_idx);
+        region.name_column(|| "RLP_tag_len", self.tag_len);
+        region.name_column(|| "RLP_header_len", self.header_len);
+        region.name_column(|| "RLP_payload_len", self.payload_len);
+        region.name_column(|| "RLP_is_long", self.is_long);
+        region.name_column(|| "RLP_is_payload", self.is_payload);
+        region.name_column(|| "RLP_is_first", self.is_first);
+        region.name_column(|| "RLP_is_tag_start", self.is_tag_start);
+        region.name_column(|| "RLP_is_tag_end", self.is_tag_end);
+        region.name_column(|| "RLP_byte", self.byte);
+        region.name_column(|| "RLP_byte_idx", self.byte_idx);
+        region.name_column(|| "RLP_length", self.length);
+        region.name_column(|| "RLP_rlc", self.rlc);
+        region.name_column(|| "RLP_value_acc", self.value_acc);
+        region.name_column(|| "RLP_len_acc", self.len_acc);
+        region.name_column(|| "RLP_byte_inv", self.byte_inv);
+        region.name_column(|| "RLP_diff", self.diff);
+        region.name_column(|| "RLP_access_list_end", self.list_ends[0]);
+        region.name_column(|| "RLP_item_end", self.list_ends[1]);
+        region.name_column(|| "RLP_storage_keys_end", self.list_ends[2]);
+        region.name_column(|| "RLP_access_list_rlc", self.access_list_rlc);
+        for (idx, column) in self.tag_flags.iter().enumerate() {
+            region.name_column(|| format!("RLP_tag_flag_{}", idx), *column);
+        }
+    }
+}
+
+/// RlpCircuit
+#[derive(Clone, Debug)]
+pub struct RlpCircuit<F: Field> {
+    /// The transactions whose encodings are hashed
+    pub txs: Vec<Transaction>,
+    /// Max number of supported transactions
+    pub max_txs: usize,
+    /// Max number of supported calldata bytes
+    pub max_calldata: usize,
+    /// Chain ID
+    pub chain_id: u64,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for RlpCircuit<F> {
+    fn default() -> Self {
+        Self::new(0, 0, 0, 0, vec![])
+    }
+}
+
+impl<F: Field> SubCircuit<F> for RlpCircuit<F> {
+    type Config = RlpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    /// The `block.circuits_params.max_rlp_rows` parameter, when non-zero,
+    /// sets up the circuit to support a fixed number of bytes of the
+    /// encodings, independently of the ones required by the transactions.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_txs,
+            block.circuits_params.max_calldata,
+            block.context.chain_id.as_u64(),
+            block.circuits_params.max_rlp_rows,
+            block.txs.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let chain_id = block.context.chain_id.as_u64();
+        let num_bytes: usize = block
+            .txs
+            .iter()
+            .flat_map(|tx| TX_FORMATS.map(|format| tx_encoding(tx, chain_id, format).len()))
+            .sum();
+        (
+            (num_bytes + 1).max(rlp_transitions().len()),
+            block.circuits_params.max_rlp_rows,
+        )
+    }
+
+    /// Make the assignments to the RlpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges)?;
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> RlpCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        num_rows: usize,
+        txs: Vec<Transaction>,
+    ) -> Self {
+        RlpCircuit {
+            txs,
+            max_txs,
+            max_calldata,
+            chain_id,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the witness using the encodings of the transactions
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Result<Vec<RlpRow<F>>, Error> {
+        rlp_rows(
+            &self.txs,
+            self.chain_id,
+            challenges,
+            (self.num_rows > 0).then(|| {
+                self.num_rows
+                    .saturating_sub(<Self as SubCircuit<F>>::unusable_rows())
+            }),
+        )
+    }
+}
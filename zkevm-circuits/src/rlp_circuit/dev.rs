@@ -0,0 +1,89 @@
+pub use super::RlpCircuit;
+
+use super::{param::TX_FORMATS, rlp_witness::tx_encoding};
+use crate::{
+    rlp_circuit::{RlpCircuitConfig, RlpCircuitConfigArgs},
+    table::{byte_table::ByteTable, BlockTable, KeccakTable, RlpTable, TxTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::BlockContext,
+};
+use eth_types::{Field, Word};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for RlpCircuit<F> {
+    type Config = (RlpCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(
+            self.max_txs,
+            self.max_calldata,
+            self.chain_id,
+            self.num_rows,
+            vec![],
+        )
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let rlp_table = RlpTable::construct(meta);
+        let tx_table = TxTable::construct(meta);
+        let block_table = BlockTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let byte_table = ByteTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            RlpCircuitConfig::new(
+                meta,
+                RlpCircuitConfigArgs {
+                    rlp_table,
+                    tx_table,
+                    block_table,
+                    keccak_table,
+                    byte_table,
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        config.tx_table.load(
+            &mut layouter,
+            &self.txs,
+            self.max_txs,
+            self.max_calldata,
+            &challenges,
+        )?;
+        let block = BlockContext {
+            chain_id: Word::from(self.chain_id),
+            ..Default::default()
+        };
+        config
+            .block_table
+            .load(&mut layouter, &block, challenges.evm_word())?;
+        let keccak_inputs: Vec<_> = self
+            .txs
+            .iter()
+            .flat_map(|tx| TX_FORMATS.map(|format| tx_encoding(tx, self.chain_id, format)))
+            .collect();
+        config
+            .keccak_table
+            .dev_load(&mut layouter, &keccak_inputs, &challenges)?;
+        config.byte_table.load(&mut layouter)?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    impl_expr,
+    table::{RlpTxFormat, TxFieldTag},
+};
+use eth_types::geth_types::TxType;
+use halo2_proofs::plonk::Expression;
+use strum_macros::EnumIter;
+
+pub(crate) const MAX_DEGREE: usize = 9;
+
+/// Maximum length of a payload with a short header
+pub(crate) const MAX_SHORT_PAYLOAD_LEN: u64 = 55;
+
+/// Maximum number of bytes of the length in a long header
+pub(crate) const MAX_LEN_OF_LEN: u64 = 8;
+
+/// Length of the payload of a `to` field, when it's not a contract creation,
+/// and of an address of the access list
+pub(crate) const ADDRESS_LEN: u64 = 20;
+
+/// Length of the payload of a storage key of the access list
+pub(crate) const STORAGE_KEY_LEN: u64 = 32;
+
+/// Number of flags of a tag in the tag ROM
+pub(crate) const NUM_TAG_FLAGS: usize = 17;
+
+/// The transaction types encoded by the circuit
+pub(crate) const TX_TYPES: [TxType; 3] = [TxType::Legacy, TxType::Eip2930, TxType::Eip1559];
+
+/// The encodings of a transaction, in the order they're laid out
+pub(crate) const TX_FORMATS: [RlpTxFormat; 2] = [RlpTxFormat::TxSign, RlpTxFormat::TxHash];
+
+/// Tag of the field encoded by a row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EnumIter)]
+pub(crate) enum RlpTag {
+    /// Padding rows after the encodings
+    #[default]
+    Padding = 0,
+    /// Type of a typed transaction (EIP-2718), as a raw byte before the list
+    TxType,
+    /// Header of the list of the fields
+    ListPrefix,
+    ChainId,
+    Nonce,
+    GasPrice,
+    MaxPriorityFeePerGas,
+    MaxFeePerGas,
+    Gas,
+    /// Callee address, empty for a contract creation
+    To,
+    Value,
+    Data,
+    /// Header of the list of the items of the access list (EIP-2930)
+    AccessList,
+    /// Header of the list of an address and its storage keys in the access
+    /// list
+    AccessListItem,
+    AccessListAddress,
+    /// Header of the list of the storage keys of an item of the access list
+    AccessListStorageKeys,
+    AccessListStorageKey,
+    /// Zero `r` of an unsigned legacy transaction (EIP-155)
+    ZeroR,
+    /// Zero `s` of an unsigned legacy transaction (EIP-155)
+    ZeroS,
+    SigV,
+    SigR,
+    SigS,
+}
+impl_expr!(RlpTag);
+
+impl RlpTag {
+    /// Field of the tx table equal to the value of the tag
+    pub(crate) fn tx_field_tag(&self) -> Option<TxFieldTag> {
+        match self {
+            Self::Nonce => Some(TxFieldTag::Nonce),
+            Self::GasPrice => Some(TxFieldTag::GasPrice),
+            Self::MaxPriorityFeePerGas => Some(TxFieldTag::MaxPriorityFeePerGas),
+            Self::MaxFeePerGas => Some(TxFieldTag::MaxFeePerGas),
+            Self::Gas => Some(TxFieldTag::Gas),
+            Self::Value => Some(TxFieldTag::Value),
+            Self::SigV => Some(TxFieldTag::SigV),
+            Self::SigR => Some(TxFieldTag::SigR),
+            Self::SigS => Some(TxFieldTag::SigS),
+            _ => None,
+        }
+    }
+
+    /// Whether the value is a word, accumulated as the RLC of its bytes
+    /// instead of their big-endian integer.
+    pub(crate) fn is_word(&self) -> bool {
+        matches!(
+            self,
+            Self::ChainId
+                | Self::GasPrice
+                | Self::MaxPriorityFeePerGas
+                | Self::MaxFeePerGas
+                | Self::Value
+                | Self::AccessListStorageKey
+                | Self::SigR
+                | Self::SigS
+        )
+    }
+
+    /// Whether the value is an integer, whose big-endian bytes have no
+    /// leading zero.
+    pub(crate) fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            Self::ChainId
+                | Self::Nonce
+                | Self::GasPrice
+                | Self::MaxPriorityFeePerGas
+                | Self::MaxFeePerGas
+                | Self::Gas
+                | Self::Value
+                | Self::ZeroR
+                | Self::ZeroS
+                | Self::SigV
+                | Self::SigR
+                | Self::SigS
+        )
+    }
+
+    /// Whether the field is a list header, whose payload is made of the
+    /// following fields
+    pub(crate) fn is_list(&self) -> bool {
+        matches!(
+            self,
+            Self::ListPrefix
+                | Self::AccessList
+                | Self::AccessListItem
+                | Self::AccessListStorageKeys
+        )
+    }
+
+    /// Flags of the tag, checked against the tag ROM:
+    /// `[is_padding, is_list, is_list_prefix, is_raw, is_word, is_scalar,
+    /// is_tx_field, tx_field_tag, is_to, is_data, is_chain_id, is_empty,
+    /// is_access_list, is_access_list_item, is_access_list_address,
+    /// is_storage_keys, is_storage_key]`
+    pub(crate) fn flags(&self) -> [u64; NUM_TAG_FLAGS] {
+        [
+            (*self == Self::Padding) as u64,
+            self.is_list() as u64,
+            (*self == Self::ListPrefix) as u64,
+            (*self == Self::TxType) as u64,
+            self.is_word() as u64,
+            self.is_scalar() as u64,
+            self.tx_field_tag().is_some() as u64,
+            self.tx_field_tag().map_or(0, |tag| tag as u64),
+            (*self == Self::To) as u64,
+            (*self == Self::Data) as u64,
+            (*self == Self::ChainId) as u64,
+            matches!(self, Self::ZeroR | Self::ZeroS) as u64,
+            (*self == Self::AccessList) as u64,
+            (*self == Self::AccessListItem) as u64,
+            (*self == Self::AccessListAddress) as u64,
+            (*self == Self::AccessListStorageKeys) as u64,
+            (*self == Self::AccessListStorageKey) as u64,
+        ]
+    }
+}
+
+/// Tags of the fields of an encoding of a transaction, in order, where the
+/// access list stands for its header followed by its items.
+pub(crate) fn rlp_tags(tx_type: TxType, format: RlpTxFormat) -> Vec<RlpTag> {
+    let (prefix, fields, suffix) = match tx_type {
+        TxType::Legacy => (
+            vec![RlpTag::ListPrefix],
+            vec![
+                RlpTag::Nonce,
+                RlpTag::GasPrice,
+                RlpTag::Gas,
+                RlpTag::To,
+                RlpTag::Value,
+                RlpTag::Data,
+            ],
+            vec![RlpTag::ChainId, RlpTag::ZeroR, RlpTag::ZeroS],
+        ),
+        TxType::Eip2930 => (
+            vec![RlpTag::TxType, RlpTag::ListPrefix],
+            vec![
+                RlpTag::ChainId,
+                RlpTag::Nonce,
+                RlpTag::GasPrice,
+                RlpTag::Gas,
+                RlpTag::To,
+                RlpTag::Value,
+                RlpTag::Data,
+                RlpTag::AccessList,
+            ],
+            vec![],
+        ),
+        TxType::Eip1559 => (
+            vec![RlpTag::TxType, RlpTag::ListPrefix],
+            vec![
+                RlpTag::ChainId,
+                RlpTag::Nonce,
+                RlpTag::MaxPriorityFeePerGas,
+                RlpTag::MaxFeePerGas,
+                RlpTag::Gas,
+                RlpTag::To,
+                RlpTag::Value,
+                RlpTag::Data,
+                RlpTag::AccessList,
+            ],
+            vec![],
+        ),
+    };
+    let suffix = match format {
+        RlpTxFormat::TxSign => suffix,
+        RlpTxFormat::TxHash => vec![RlpTag::SigV, RlpTag::SigR, RlpTag::SigS],
+    };
+    [prefix, fields, suffix].concat()
+}
+
+/// Transitions between the tags of the encodings, as rows `[tx_type, format,
+/// tag, next_tag]`, starting and ending with a padding tag. The access list is
+/// followed by any number of items, each of them an address followed by any
+/// number of storage keys.
+pub(crate) fn rlp_transitions() -> Vec<[u64; 4]> {
+    TX_TYPES
+        .iter()
+        .flat_map(|&tx_type| {
+            TX_FORMATS.iter().flat_map(move |&format| {
+                let tags = [
+                    vec![RlpTag::Padding],
+                    rlp_tags(tx_type, format),
+                    vec![RlpTag::Padding],
+                ]
+                .concat();
+                tags.windows(2)
+                    .flat_map(|tags| match tags {
+                        [RlpTag::AccessList, next] => vec![
+                            (RlpTag::AccessList, RlpTag::AccessListItem),
+                            (RlpTag::AccessList, *next),
+                            (RlpTag::AccessListItem, RlpTag::AccessListAddress),
+                            (RlpTag::AccessListAddress, RlpTag::AccessListStorageKeys),
+                            (RlpTag::AccessListStorageKeys, RlpTag::AccessListStorageKey),
+                            (RlpTag::AccessListStorageKeys, RlpTag::AccessListItem),
+                            (RlpTag::AccessListStorageKeys, *next),
+                            (RlpTag::AccessListStorageKey, RlpTag::AccessListStorageKey),
+                            (RlpTag::AccessListStorageKey, RlpTag::AccessListItem),
+                            (RlpTag::AccessListStorageKey, *next),
+                        ],
+                        _ => vec![(tags[0], tags[1])],
+                    })
+                    .map(|(tag, next_tag)| {
+                        [tx_type as u64, format as u64, tag as u64, next_tag as u64]
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect()
+}
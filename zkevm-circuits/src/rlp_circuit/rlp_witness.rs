@@ -0,0 +1,408 @@
+use super::param::*;
+use crate::{
+    evm_circuit::util::rlc,
+    table::RlpTxFormat,
+    util::{keccak, Challenges},
+    witness::Transaction,
+};
+use eth_types::{geth_types::TxType, AccessList, Field, ToBigEndian, ToLittleEndian, Word};
+use halo2_proofs::{circuit::Value, plonk::Error};
+use std::iter::once;
+
+/// A field of an encoding, made of its RLP header followed by its payload.
+#[derive(Clone, Debug)]
+pub(crate) struct RlpField {
+    pub(crate) tag: RlpTag,
+    pub(crate) header: Vec<u8>,
+    pub(crate) payload: Vec<u8>,
+    /// Length of the payload, which for the list prefix is the length of the
+    /// following fields.
+    pub(crate) payload_len: usize,
+}
+
+impl RlpField {
+    fn raw(tag: RlpTag, byte: u8) -> Self {
+        Self {
+            tag,
+            header: vec![],
+            payload: vec![byte],
+            payload_len: 1,
+        }
+    }
+
+    fn string(tag: RlpTag, bytes: Vec<u8>) -> Self {
+        // A single byte below 0x80 is its own encoding
+        let header = if bytes.len() == 1 && bytes[0] < 0x80 {
+            vec![]
+        } else {
+            header(bytes.len(), false)
+        };
+        Self {
+            tag,
+            header,
+            payload_len: bytes.len(),
+            payload: bytes,
+        }
+    }
+
+    fn scalar(tag: RlpTag, value: Word) -> Self {
+        Self::string(tag, be_bytes(value))
+    }
+
+    /// Header of a list, whose payload is made of the following fields
+    fn list(tag: RlpTag, payload_len: usize) -> Self {
+        Self {
+            tag,
+            header: header(payload_len, true),
+            payload: vec![],
+            payload_len,
+        }
+    }
+
+    /// Whether the length of the payload follows the first byte of the header
+    pub(crate) fn is_long(&self) -> bool {
+        self.header.len() > 1
+    }
+
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        [self.header.as_slice(), self.payload.as_slice()].concat()
+    }
+}
+
+/// Big-endian bytes of an integer without leading zeros
+fn be_bytes(value: Word) -> Vec<u8> {
+    value
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect()
+}
+
+/// RLP header of a string or list payload of length `payload_len`
+fn header(payload_len: usize, is_list: bool) -> Vec<u8> {
+    let offset = if is_list { 0xc0 } else { 0x80 };
+    if payload_len as u64 <= MAX_SHORT_PAYLOAD_LEN {
+        vec![offset + payload_len as u8]
+    } else {
+        let len_bytes = be_bytes(Word::from(payload_len));
+        [
+            vec![offset + MAX_SHORT_PAYLOAD_LEN as u8 + len_bytes.len() as u8],
+            len_bytes,
+        ]
+        .concat()
+    }
+}
+
+/// Length of the encoding of the fields
+fn fields_len(fields: &[RlpField]) -> usize {
+    fields
+        .iter()
+        .map(|field| field.header.len() + field.payload.len())
+        .sum()
+}
+
+/// Fields of the list of `[address, [storage_keys]]` of an access list
+fn access_list_fields(access_list: &Option<AccessList>) -> Vec<RlpField> {
+    let items = access_list
+        .iter()
+        .flat_map(|access_list| access_list.0.iter())
+        .flat_map(|item| {
+            let storage_keys = item
+                .storage_keys
+                .iter()
+                .map(|key| RlpField::string(RlpTag::AccessListStorageKey, key.as_bytes().to_vec()))
+                .collect::<Vec<_>>();
+            let fields = [
+                vec![
+                    RlpField::string(RlpTag::AccessListAddress, item.address.as_bytes().to_vec()),
+                    RlpField::list(RlpTag::AccessListStorageKeys, fields_len(&storage_keys)),
+                ],
+                storage_keys,
+            ]
+            .concat();
+            once(RlpField::list(RlpTag::AccessListItem, fields_len(&fields))).chain(fields)
+        })
+        .collect::<Vec<_>>();
+    once(RlpField::list(RlpTag::AccessList, fields_len(&items)))
+        .chain(items)
+        .collect()
+}
+
+/// Fields of an encoding of a transaction
+pub(crate) fn tx_fields(tx: &Transaction, chain_id: u64, format: RlpTxFormat) -> Vec<RlpField> {
+    let mut fields = rlp_tags(tx.tx_type, format)
+        .into_iter()
+        .flat_map(|tag| match tag {
+            RlpTag::AccessList => access_list_fields(&tx.access_list),
+            tag => vec![tx_field(tx, chain_id, tag)],
+        })
+        .collect::<Vec<_>>();
+
+    // The list prefix is the header of the list of the following fields
+    let list_prefix_idx = fields
+        .iter()
+        .position(|field| field.tag == RlpTag::ListPrefix)
+        .expect("encoding has a list prefix");
+    fields[list_prefix_idx] = RlpField::list(
+        RlpTag::ListPrefix,
+        fields_len(&fields[list_prefix_idx + 1..]),
+    );
+    fields
+}
+
+/// Field of an encoding of a transaction, other than the access list
+fn tx_field(tx: &Transaction, chain_id: u64, tag: RlpTag) -> RlpField {
+    match tag {
+        RlpTag::TxType => RlpField::raw(tag, tx.tx_type as u8),
+        // Set once the following fields are known
+        RlpTag::ListPrefix => RlpField::list(tag, 0),
+        RlpTag::ChainId => RlpField::scalar(tag, Word::from(chain_id)),
+        RlpTag::Nonce => RlpField::scalar(tag, Word::from(tx.nonce)),
+        RlpTag::GasPrice => RlpField::scalar(tag, tx.gas_price),
+        RlpTag::MaxPriorityFeePerGas => RlpField::scalar(tag, tx.max_priority_fee_per_gas),
+        RlpTag::MaxFeePerGas => RlpField::scalar(tag, tx.max_fee_per_gas),
+        RlpTag::Gas => RlpField::scalar(tag, Word::from(tx.gas)),
+        RlpTag::To => RlpField::string(
+            tag,
+            if tx.is_create {
+                vec![]
+            } else {
+                tx.callee_address.as_bytes().to_vec()
+            },
+        ),
+        RlpTag::Value => RlpField::scalar(tag, tx.value),
+        RlpTag::Data => RlpField::string(tag, tx.call_data.clone()),
+        RlpTag::ZeroR | RlpTag::ZeroS => RlpField::scalar(tag, Word::zero()),
+        RlpTag::SigV => RlpField::scalar(tag, Word::from(tx.v)),
+        RlpTag::SigR => RlpField::scalar(tag, tx.r),
+        RlpTag::SigS => RlpField::scalar(tag, tx.s),
+        RlpTag::Padding
+        | RlpTag::AccessList
+        | RlpTag::AccessListItem
+        | RlpTag::AccessListAddress
+        | RlpTag::AccessListStorageKeys
+        | RlpTag::AccessListStorageKey => unreachable!("{:?} isn't a field of the tx", tag),
+    }
+}
+
+/// Encoding of a transaction
+pub(crate) fn tx_encoding(tx: &Transaction, chain_id: u64, format: RlpTxFormat) -> Vec<u8> {
+    tx_fields(tx, chain_id, format)
+        .iter()
+        .flat_map(|field| field.bytes())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RlpRow<F> {
+    pub(crate) tx_id: usize,
+    pub(crate) format: RlpTxFormat,
+    pub(crate) tx_type: TxType,
+    pub(crate) tag: RlpTag,
+    /// Index of the byte in the field, starting at 1
+    pub(crate) tag_idx: usize,
+    pub(crate) tag_len: usize,
+    pub(crate) header_len: usize,
+    pub(crate) payload_len: usize,
+    pub(crate) is_long: bool,
+    pub(crate) is_payload: bool,
+    pub(crate) is_first: bool,
+    pub(crate) is_tag_start: bool,
+    pub(crate) is_tag_end: bool,
+    pub(crate) is_last: bool,
+    pub(crate) byte: u8,
+    /// Index of the byte in the encoding, starting at 1
+    pub(crate) byte_idx: usize,
+    pub(crate) length: usize,
+    pub(crate) rlc: Value<F>,
+    pub(crate) value_acc: Value<F>,
+    pub(crate) len_acc: u64,
+    pub(crate) byte_inv: F,
+    pub(crate) diff: u64,
+    /// Index of the last byte of the access list, of its current item and of
+    /// the storage keys of the item
+    pub(crate) list_ends: [usize; 3],
+    pub(crate) access_list_rlc: Value<F>,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+impl<F: Field> RlpRow<F> {
+    fn padding() -> Self {
+        Self {
+            tx_id: 0,
+            format: RlpTxFormat::TxSign,
+            tx_type: TxType::Legacy,
+            tag: RlpTag::Padding,
+            tag_idx: 0,
+            tag_len: 0,
+            header_len: 0,
+            payload_len: 0,
+            is_long: false,
+            is_payload: false,
+            is_first: false,
+            is_tag_start: false,
+            is_tag_end: true,
+            is_last: false,
+            byte: 0,
+            byte_idx: 0,
+            length: 0,
+            rlc: Value::known(F::ZERO),
+            value_acc: Value::known(F::ZERO),
+            len_acc: 0,
+            byte_inv: F::ZERO,
+            diff: 0,
+            list_ends: [0; 3],
+            access_list_rlc: Value::known(F::ZERO),
+            hash_rlc: Value::known(F::ZERO),
+        }
+    }
+}
+
+/// Lay out the bytes of an encoding of a transaction, one per row
+fn encode<F: Field>(
+    rows: &mut Vec<RlpRow<F>>,
+    tx: &Transaction,
+    chain_id: u64,
+    format: RlpTxFormat,
+    challenges: &Challenges<Value<F>>,
+) {
+    let fields = tx_fields(tx, chain_id, format);
+    let encoding = tx_encoding(tx, chain_id, format);
+    let hash_rlc = challenges
+        .evm_word()
+        .map(|challenge| rlc::value(&keccak(&encoding).to_le_bytes(), challenge));
+
+    let mut byte_idx = 0;
+    let mut rlc = Value::known(F::ZERO);
+    let mut list_ends = [0; 3];
+    let mut access_list_rlc = Value::known(F::ZERO);
+    for field in fields.iter() {
+        let bytes = field.bytes();
+        let mult = if field.tag.is_word() {
+            challenges.evm_word()
+        } else {
+            Value::known(F::from(256))
+        };
+        let mut value_acc = Value::known(F::ZERO);
+        let mut len_acc = 0;
+        for (idx, &byte) in bytes.iter().enumerate() {
+            byte_idx += 1;
+            let is_payload = idx >= field.header.len();
+            if is_payload {
+                value_acc = value_acc * mult + Value::known(F::from(byte as u64));
+            } else if idx > 0 {
+                len_acc = len_acc * 256 + byte as u64;
+            }
+            rlc = rlc * challenges.keccak_input() + Value::known(F::from(byte as u64));
+            let is_tag_end = idx + 1 == bytes.len();
+
+            // The lists of the access list end after the fields of their
+            // payload, and its addresses and storage keys are accumulated.
+            let list_idx = match field.tag {
+                RlpTag::AccessList => Some(0),
+                RlpTag::AccessListItem => Some(1),
+                RlpTag::AccessListStorageKeys => Some(2),
+                _ => None,
+            };
+            if let Some(list_idx) = list_idx.filter(|_| is_tag_end) {
+                list_ends[list_idx] = byte_idx + field.payload_len;
+            }
+            if field.tag == RlpTag::AccessList {
+                access_list_rlc = Value::known(F::ZERO);
+            } else if is_tag_end
+                && matches!(
+                    field.tag,
+                    RlpTag::AccessListAddress | RlpTag::AccessListStorageKey
+                )
+            {
+                access_list_rlc = access_list_rlc * challenges.evm_word() + value_acc;
+            }
+
+            // The first byte of a field bounds the length of the payload, and the
+            // leading byte of a scalar or of the length of a long header isn't zero.
+            let (byte_inv, diff) = if idx == 0 {
+                let leading_byte = if is_payload {
+                    byte
+                } else {
+                    bytes.get(1).copied().unwrap_or_default()
+                };
+                let diff = if is_payload {
+                    0x7f - byte as u64
+                } else if field.is_long() {
+                    MAX_LEN_OF_LEN + 1 - field.header.len() as u64
+                } else {
+                    MAX_SHORT_PAYLOAD_LEN - field.payload_len as u64
+                };
+                (
+                    F::from(leading_byte as u64).invert().unwrap_or(F::ZERO),
+                    diff,
+                )
+            } else {
+                (F::ZERO, 0)
+            };
+
+            rows.push(RlpRow {
+                tx_id: tx.id,
+                format,
+                tx_type: tx.tx_type,
+                tag: field.tag,
+                tag_idx: idx + 1,
+                tag_len: bytes.len(),
+                header_len: field.header.len(),
+                payload_len: field.payload_len,
+                is_long: field.is_long(),
+                is_payload,
+                is_first: false,
+                is_tag_start: false,
+                is_tag_end,
+                is_last: byte_idx == encoding.len(),
+                byte,
+                byte_idx,
+                length: encoding.len(),
+                rlc,
+                value_acc,
+                len_acc,
+                byte_inv,
+                diff,
+                list_ends,
+                access_list_rlc,
+                hash_rlc,
+            });
+        }
+    }
+}
+
+/// Lay out the encodings for signing and hashing of the transactions, padded
+/// to `num_rows` rows or followed by a padding row.
+pub(crate) fn rlp_rows<F: Field>(
+    txs: &[Transaction],
+    chain_id: u64,
+    challenges: Challenges<Value<F>>,
+    num_rows: Option<usize>,
+) -> Result<Vec<RlpRow<F>>, Error> {
+    let mut rows = Vec::new();
+    for tx in txs {
+        for format in TX_FORMATS {
+            encode(&mut rows, tx, chain_id, format, &challenges);
+        }
+    }
+
+    let num_rows = num_rows.unwrap_or(rows.len() + 1);
+    if rows.len() > num_rows {
+        log::error!(
+            "{} rows of the encodings exceed the capacity of {} rows",
+            rows.len(),
+            num_rows
+        );
+        return Err(Error::Synthesis);
+    }
+    rows.resize(num_rows, RlpRow::padding());
+
+    // Encodings and fields start after the end of the previous ones
+    let mut prev_ends = (true, true);
+    for row in rows.iter_mut() {
+        (row.is_first, row.is_tag_start) = prev_ends;
+        prev_ends = (row.is_last, row.is_tag_end);
+    }
+    Ok(rows)
+}
@@ -0,0 +1,198 @@
+use super::*;
+use crate::{
+    evm_circuit::witness::block_convert, table::RlpTxFormat, util::unusable_rows, witness::Block,
+};
+use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+use eth_types::{
+    address, geth_types, geth_types::GethData, AccessList, AccessListItem, Bytes, Word, H256,
+};
+use ethers_signers::{LocalWallet, Signer};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+use mock::{eth, MockAccount, MockTransaction, TestContext, MOCK_CHAIN_ID};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashMap;
+
+const MAX_TXS: usize = 4;
+const MAX_CALLDATA: usize = 256;
+
+#[test]
+fn rlp_circuit_unusable_rows() {
+    assert_eq!(
+        RlpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, RlpCircuit::<Fr>>(()),
+    )
+}
+
+/// Witness block of the transactions set up by `func_tx`, signed by the
+/// second account.
+fn gen_signed_block<const NTX: usize>(
+    max_rlp_rows: usize,
+    func_tx: impl FnOnce(Vec<&mut MockTransaction>, [MockAccount; 2]),
+) -> Block<Fr> {
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    let chain_id = (*MOCK_CHAIN_ID).as_u64();
+    let wallet = LocalWallet::new(&mut rng).with_chain_id(chain_id);
+    let addr_a = wallet.address();
+    let addr_b = address!("0x000000000000000000000000000000000000BBBB");
+    let mut wallets = HashMap::new();
+    wallets.insert(addr_a, wallet);
+
+    let mut block: GethData = TestContext::<2, NTX>::new(
+        None,
+        |accs| {
+            accs[0].address(addr_b).balance(eth(10));
+            accs[1].address(addr_a).balance(eth(10));
+        },
+        func_tx,
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into();
+    block.sign(&wallets);
+
+    let mut builder = BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            max_txs: MAX_TXS,
+            max_calldata: MAX_CALLDATA,
+            max_rlp_rows,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    block_convert::<Fr>(&builder.block, &builder.code_db).unwrap()
+}
+
+/// Witness block of signed transactions: a legacy call, a legacy contract
+/// creation with a long call data, an access list transaction and a dynamic
+/// fee transaction.
+fn gen_block(max_rlp_rows: usize) -> Block<Fr> {
+    gen_signed_block::<4>(max_rlp_rows, |mut txs, accs| {
+        txs[0]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .value(eth(1))
+            .input(Bytes::from(b"hello"));
+        // Zero init code of more than 55 bytes, which has a long header
+        txs[1]
+            .from(accs[1].address)
+            .input(Bytes::from(vec![0; 100]));
+        txs[2]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .transaction_type(1)
+            .access_list(AccessList(vec![
+                AccessListItem {
+                    address: accs[0].address,
+                    storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+                },
+                AccessListItem {
+                    address: accs[0].address,
+                    storage_keys: vec![],
+                },
+            ]));
+        txs[3]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .transaction_type(2)
+            .max_priority_fee_per_gas(Word::from(0x4d2))
+            .max_fee_per_gas(Word::from(0x9a4))
+            .value(Word::from(0x3e8));
+    })
+}
+
+fn run(k: u32, block: &Block<Fr>) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = RlpCircuit::<Fr>::new_from_block(block);
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    prover.verify()
+}
+
+#[test]
+fn rlp_circuit_tx_encodings() {
+    let block = gen_block(0);
+    let chain_id = block.context.chain_id.as_u64();
+    for (tx, eth_tx) in block.txs.iter().zip(block.eth_block.transactions.iter()) {
+        let geth_tx = geth_types::Transaction::try_from(eth_tx).unwrap();
+        assert_eq!(
+            tx_encoding(tx, chain_id, RlpTxFormat::TxSign),
+            geth_tx.rlp_unsigned(chain_id).to_vec()
+        );
+        assert_eq!(
+            tx_encoding(tx, chain_id, RlpTxFormat::TxHash),
+            geth_tx.rlp_signed(chain_id).to_vec()
+        );
+    }
+}
+
+#[test]
+fn rlp_circuit_valid() {
+    let block = gen_block(0);
+    assert_eq!(run(12, &block), Ok(()));
+}
+
+#[test]
+fn rlp_circuit_fixed_rows() {
+    let block = gen_block(2000);
+    assert_eq!(run(12, &block), Ok(()));
+}
+
+#[test]
+fn variadic_size_check() {
+    let block = gen_block(2000);
+    let circuit = RlpCircuit::<Fr>::new_from_block(&block);
+    let prover1 = MockProver::<Fr>::run(12, &circuit, vec![]).unwrap();
+
+    let circuit = RlpCircuit::<Fr> {
+        txs: block.txs[..1].to_vec(),
+        ..circuit
+    };
+    let prover2 = MockProver::<Fr>::run(12, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
+
+#[test]
+fn rlp_circuit_access_lists() {
+    // An empty access list, and an access list with a repeated address and
+    // storage keys of more than 55 bytes, which have a long header.
+    let block = gen_signed_block::<2>(0, |mut txs, accs| {
+        txs[0]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .transaction_type(1)
+            .access_list(AccessList(vec![]));
+        txs[1]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .transaction_type(2)
+            .max_priority_fee_per_gas(Word::from(0x4d2))
+            .max_fee_per_gas(Word::from(0x9a4))
+            .access_list(AccessList(vec![
+                AccessListItem {
+                    address: accs[0].address,
+                    storage_keys: vec![],
+                },
+                AccessListItem {
+                    address: accs[0].address,
+                    storage_keys: (1..=3).map(H256::from_low_u64_be).collect(),
+                },
+            ]));
+    });
+    let chain_id = block.context.chain_id.as_u64();
+    for (tx, eth_tx) in block.txs.iter().zip(block.eth_block.transactions.iter()) {
+        let geth_tx = geth_types::Transaction::try_from(eth_tx).unwrap();
+        assert_eq!(
+            tx_encoding(tx, chain_id, RlpTxFormat::TxHash),
+            geth_tx.rlp_signed(chain_id).to_vec()
+        );
+    }
+    assert_eq!(run(12, &block), Ok(()));
+}
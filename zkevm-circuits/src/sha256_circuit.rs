@@ -0,0 +1,612 @@
+//! The SHA-256 circuit implementation.
+//!
+//! Each block of 64 bytes takes `NUM_ROWS_PER_BLOCK` rows: the hash values of
+//! the previous block on the start rows, one row per round, and the new hash
+//! values on the end rows. The working variables `a` and `e` of a round, as
+//! well as the message schedule word `w`, are stored as bits, so the variables
+//! `b, c, d` and `f, g, h` are found on the previous rows.
+mod param;
+mod sha256_bit;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    param::*,
+    sha256_bit::{get_num_sha256_blocks, multi_sha256, Sha256Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Sha256Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+/// Sha256Config
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_absorb: Column<Fixed>,
+    q_padding: Column<Fixed>,
+    q_length: Column<Fixed>,
+    q_extend: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_end_last: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD],
+    word_a: [Column<Advice>; NUM_BITS_PER_WORD],
+    word_e: [Column<Advice>; NUM_BITS_PER_WORD],
+    carry_a: [Column<Advice>; NUM_CARRY_BITS_A],
+    carry_e: [Column<Advice>; NUM_CARRY_BITS_E],
+    carry_w: [Column<Advice>; NUM_CARRY_BITS_W],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    /// The columns for other circuits to lookup SHA-256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_absorb = meta.fixed_column();
+        let q_padding = meta.fixed_column();
+        let q_length = meta.fixed_column();
+        let q_extend = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_end_last = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let word_a = array_init::array_init(|_| meta.advice_column());
+        let word_e = array_init::array_init(|_| meta.advice_column());
+        let carry_a = array_init::array_init(|_| meta.advice_column());
+        let carry_e = array_init::array_init(|_| meta.advice_column());
+        let carry_w = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+
+        let is_final = sha256_table.is_enabled;
+        let length = sha256_table.input_len;
+        let data_rlc = sha256_table.input_rlc;
+        let hash_rlc = sha256_table.output_rlc;
+
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let start_new_hash = |meta: &mut VirtualCells<F>| {
+            // A new hash is started when the previous hash is done or on the first block
+            meta.query_fixed(q_first, Rotation::cur())
+                + meta.query_advice(is_final, Rotation::prev())
+        };
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in word_w
+                .iter()
+                .chain(word_a.iter())
+                .chain(word_e.iter())
+                .chain(carry_a.iter())
+                .chain(carry_e.iter())
+                .chain(carry_w.iter())
+                .chain(is_paddings.iter())
+                .chain(std::iter::once(&is_final))
+            {
+                cb.require_boolean("boolean bit", meta.query_advice(*column, Rotation::cur()));
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Enforce fixed values on the first row
+        meta.create_gate("first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "is_final needs to be disabled on the dummy row",
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("is final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // Only the last row of a block can have `is_final == 1`.
+            cb.require_zero(
+                "is_final only on the last row of a block",
+                meta.query_advice(is_final, Rotation::cur()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_end_last, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta);
+            // The working variables are initialized with the initial hash values
+            // for a new hash, or else with the hash values of the previous block.
+            for (idx, (h_a, h_e)) in [(H[3], H[7]), (H[2], H[6]), (H[1], H[5]), (H[0], H[4])]
+                .into_iter()
+                .enumerate()
+            {
+                let idx = idx as i32;
+                for (column, h) in [(word_a, h_a), (word_e, h_e)] {
+                    cb.require_equal(
+                        "initial working variable",
+                        decode(&query_bits(meta, &column, idx)),
+                        select::expr(
+                            start_new_hash.clone(),
+                            (h as u64).expr(),
+                            decode(&query_bits(meta, &column, idx - NUM_START_ROWS as i32)),
+                        ),
+                    );
+                }
+            }
+            // The absorbed data is reset for a new hash.
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "reset absorbed data",
+                    meta.query_advice(column, Rotation::cur()),
+                    select::expr(
+                        start_new_hash.clone(),
+                        0.expr(),
+                        meta.query_advice(column, Rotation::prev()),
+                    ),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a, b, c, d] = [-1, -2, -3, -4].map(|rot| query_bits(meta, &word_a, rot));
+            let [e, f, g, h] = [-1, -2, -3, -4].map(|rot| query_bits(meta, &word_e, rot));
+            let w = decode(&query_bits(meta, &word_w, 0));
+            let t1 = decode(&h)
+                + big_sigma1(&e)
+                + ch(&e, &f, &g)
+                + meta.query_fixed(round_cst, Rotation::cur())
+                + w;
+            let t2 = big_sigma0(&a) + maj(&a, &b, &c);
+            cb.require_equal(
+                "new e = d + t1",
+                decode(&query_bits(meta, &word_e, 0))
+                    + decode(&query_bits(meta, &carry_e, 0)) * (1u64 << NUM_BITS_PER_WORD).expr(),
+                decode(&d) + t1.clone(),
+            );
+            cb.require_equal(
+                "new a = t1 + t2",
+                decode(&query_bits(meta, &word_a, 0))
+                    + decode(&query_bits(meta, &carry_a, 0)) * (1u64 << NUM_BITS_PER_WORD).expr(),
+                t1 + t2,
+            );
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [w_2, w_7, w_15, w_16] =
+                [-2, -7, -15, -16].map(|rot| query_bits(meta, &word_w, rot));
+            cb.require_equal(
+                "w = sigma1(w[-2]) + w[-7] + sigma0(w[-15]) + w[-16]",
+                decode(&query_bits(meta, &word_w, 0))
+                    + decode(&query_bits(meta, &carry_w, 0)) * (1u64 << NUM_BITS_PER_WORD).expr(),
+                sigma1(&w_2) + decode(&w_7) + sigma0(&w_15) + decode(&w_16),
+            );
+            cb.gate(meta.query_fixed(q_extend, Rotation::cur()))
+        });
+
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // The new hash values are the sum of the working variables after the
+            // last round and the hash values on the start rows.
+            for (column, carry) in [(word_a, &carry_a[..]), (word_e, &carry_e[..])] {
+                cb.require_equal(
+                    "new hash value",
+                    decode(&query_bits(meta, &column, 0))
+                        + decode(&query_bits(meta, carry, 0)) * (1u64 << NUM_BITS_PER_WORD).expr(),
+                    decode(&query_bits(meta, &column, -(NUM_END_ROWS as i32)))
+                        + decode(&query_bits(meta, &column, -(END_OFFSET as i32))),
+                );
+            }
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        meta.create_gate("hash rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            // The hash values h0, .., h3 and h4, .., h7 are on the end rows backwards.
+            let hash_bytes = [word_a, word_e]
+                .iter()
+                .flat_map(|column| {
+                    (0..NUM_END_ROWS as i32)
+                        .flat_map(|rot| to_be_bytes(&query_bits(meta, column, -rot)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            cb.require_equal(
+                "hash rlc check",
+                meta.query_advice(hash_rlc, Rotation::cur()),
+                rlc::expr(
+                    &hash_bytes.into_iter().rev().collect::<Vec<_>>(),
+                    challenges.keccak_input(),
+                ),
+            );
+            cb.gate(meta.query_fixed(q_end_last, Rotation::cur()))
+        });
+
+        // Absorb
+        let prev_is_paddings = |meta: &mut VirtualCells<F>, rot: i32| {
+            // The padding flag of the byte before each byte of the word
+            let mut prev_is_paddings =
+                vec![meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation(rot - 1))];
+            prev_is_paddings.extend(query_bits(
+                meta,
+                &is_paddings[..NUM_BYTES_PER_WORD - 1],
+                rot,
+            ));
+            prev_is_paddings
+        };
+        meta.create_gate("absorb", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let bytes = to_be_bytes(&query_bits(meta, &word_w, 0));
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            for ((byte, is_padding), prev_is_padding) in bytes
+                .iter()
+                .zip(is_paddings.iter())
+                .zip(prev_is_paddings(meta, 0))
+            {
+                cb.require_zero(
+                    "is_padding can only go from 0 to 1",
+                    prev_is_padding.clone() * not::expr(is_padding.clone()),
+                );
+                cb.require_zero(
+                    "the first padding byte is 0x80",
+                    (is_padding.clone() - prev_is_padding) * (byte.clone() - 0x80.expr()),
+                );
+            }
+
+            cb.require_equal(
+                "update length",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.clone())),
+                    ),
+            );
+
+            // The data RLC absorbs the bytes before the first padding byte of the word.
+            let prev_data_rlc = meta.query_advice(data_rlc, Rotation::prev());
+            let new_data_rlc = (0..=NUM_BYTES_PER_WORD)
+                .map(|num_data_bytes| {
+                    let is_num_data_bytes = if num_data_bytes == 0 {
+                        is_paddings[0].clone()
+                    } else if num_data_bytes == NUM_BYTES_PER_WORD {
+                        not::expr(is_paddings[NUM_BYTES_PER_WORD - 1].clone())
+                    } else {
+                        is_paddings[num_data_bytes].clone()
+                            - is_paddings[num_data_bytes - 1].clone()
+                    };
+                    let data_rlc = bytes[..num_data_bytes]
+                        .iter()
+                        .fold(prev_data_rlc.clone(), |acc, byte| {
+                            acc * challenges.keccak_input() + byte.clone()
+                        });
+                    is_num_data_bytes * data_rlc
+                })
+                .fold(0.expr(), |acc, data_rlc| acc + data_rlc);
+            cb.require_equal(
+                "update data rlc",
+                meta.query_advice(data_rlc, Rotation::cur()),
+                new_data_rlc,
+            );
+            cb.gate(meta.query_fixed(q_absorb, Rotation::cur()))
+        });
+
+        meta.create_gate("padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let bytes = to_be_bytes(&query_bits(meta, &word_w, 0));
+            for (byte, prev_is_padding) in bytes.into_iter().zip(prev_is_paddings(meta, 0)) {
+                cb.require_zero(
+                    "padding bytes after the first one are zero",
+                    prev_is_padding * byte,
+                );
+            }
+            cb.gate(meta.query_fixed(q_padding, Rotation::cur()))
+        });
+
+        // The last two words of the last block of a hash hold the length in bits.
+        // The last block is the first one where the padding starts before them.
+        meta.create_gate("length", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final_block = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(PADDING_LAST_OFFSET as i32 - ABSORB_LAST_OFFSET as i32),
+            );
+            cb.require_equal(
+                "is_final is set iff the padding starts before the length",
+                meta.query_advice(
+                    is_final,
+                    Rotation((NUM_ROWS_PER_BLOCK - 1 - ABSORB_LAST_OFFSET) as i32),
+                ),
+                is_final_block.clone(),
+            );
+            cb.require_zero(
+                "the last two words are the length in bits",
+                is_final_block.clone()
+                    * (decode(&query_bits(meta, &word_w, -1)) * (1u64 << NUM_BITS_PER_WORD).expr()
+                        + decode(&query_bits(meta, &word_w, 0))
+                        - meta.query_advice(length, Rotation::cur()) * NUM_BITS_PER_BYTE.expr()),
+            );
+            for rot in [-1, 0] {
+                let bytes = to_be_bytes(&query_bits(meta, &word_w, rot));
+                for (byte, prev_is_padding) in bytes.into_iter().zip(prev_is_paddings(meta, rot)) {
+                    cb.require_zero(
+                        "padding bytes after the first one are zero if not the last block",
+                        not::expr(is_final_block.clone()) * prev_is_padding * byte,
+                    );
+                }
+            }
+            cb.gate(meta.query_fixed(q_length, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over absorbed data", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "absorbed data unchanged",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                    - meta.query_fixed(q_absorb, Rotation::cur()),
+            )
+        });
+
+        Sha256CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_round,
+            q_absorb,
+            q_padding,
+            q_length,
+            q_extend,
+            q_end,
+            q_end_last,
+            round_cst,
+            word_w,
+            word_a,
+            word_e,
+            carry_a,
+            carry_e,
+            carry_w,
+            is_paddings,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                for (offset, sha256_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, sha256_row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<(), Error> {
+        // Fixed selectors
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, F::from(row.q_enable as u64)),
+            // The first block comes right after the dummy row
+            ("q_first", self.q_first, F::from((offset == 1) as u64)),
+            ("q_start", self.q_start, F::from(row.q_start as u64)),
+            ("q_round", self.q_round, F::from(row.q_round as u64)),
+            ("q_absorb", self.q_absorb, F::from(row.q_absorb as u64)),
+            ("q_padding", self.q_padding, F::from(row.q_padding as u64)),
+            ("q_length", self.q_length, F::from(row.q_length as u64)),
+            ("q_extend", self.q_extend, F::from(row.q_extend as u64)),
+            ("q_end", self.q_end, F::from(row.q_end as u64)),
+            (
+                "q_end_last",
+                self.q_end_last,
+                F::from(row.q_end_last as u64),
+            ),
+            ("round_cst", self.round_cst, row.round_cst),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        self.sha256_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final as u64)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits
+        for (name, columns, bits) in [
+            ("w", &self.word_w[..], &row.w[..]),
+            ("a", &self.word_a[..], &row.a[..]),
+            ("e", &self.word_e[..], &row.e[..]),
+            ("carry_a", &self.carry_a[..], &row.carry_a[..]),
+            ("carry_e", &self.carry_e[..], &row.carry_e[..]),
+            ("carry_w", &self.carry_w[..], &row.carry_w[..]),
+            ("is_padding", &self.is_paddings[..], &row.is_paddings[..]),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_enable", self.q_enable);
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_start", self.q_start);
+        region.name_column(|| "SHA256_q_round", self.q_round);
+        region.name_column(|| "SHA256_q_absorb", self.q_absorb);
+        region.name_column(|| "SHA256_q_padding", self.q_padding);
+        region.name_column(|| "SHA256_q_length", self.q_length);
+        region.name_column(|| "SHA256_q_extend", self.q_extend);
+        region.name_column(|| "SHA256_q_end", self.q_end);
+        region.name_column(|| "SHA256_q_end_last", self.q_end_last);
+        region.name_column(|| "SHA256_round_cst", self.round_cst);
+    }
+}
+
+/// Sha256Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // The words a and e are queried at 9 distinct rotations.
+        12
+    }
+
+    /// The `block.circuits_params.max_sha256_rows` parameter, when non-zero,
+    /// sets up the circuit to support a fixed number of blocks, independently
+    /// of the blocks required by `inputs`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_sha256_rows,
+            block.sha256_inputs.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            1 + block
+                .sha256_inputs
+                .iter()
+                .map(|bytes| get_num_sha256_blocks(bytes.len()) * NUM_ROWS_PER_BLOCK)
+                .sum::<usize>(),
+            block.circuits_params.max_sha256_rows,
+        )
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Sha256Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of SHA-256 blocks that can be done in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract the unusable rows and the dummy first row
+            Some((self.num_rows - Self::unusable_rows() - 1) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Sha256Row<F>> {
+        multi_sha256(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
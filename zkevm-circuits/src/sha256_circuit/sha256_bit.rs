@@ -0,0 +1,250 @@
+use super::param::*;
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Sha256Row
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Row<F: Field> {
+    pub(crate) q_enable: bool,
+    pub(crate) q_start: bool,
+    pub(crate) q_round: bool,
+    pub(crate) q_absorb: bool,
+    pub(crate) q_padding: bool,
+    pub(crate) q_length: bool,
+    pub(crate) q_extend: bool,
+    pub(crate) q_end: bool,
+    pub(crate) q_end_last: bool,
+    pub(crate) round_cst: F,
+    pub(crate) w: [bool; NUM_BITS_PER_WORD],
+    pub(crate) a: [bool; NUM_BITS_PER_WORD],
+    pub(crate) e: [bool; NUM_BITS_PER_WORD],
+    pub(crate) carry_a: [bool; NUM_CARRY_BITS_A],
+    pub(crate) carry_e: [bool; NUM_CARRY_BITS_E],
+    pub(crate) carry_w: [bool; NUM_CARRY_BITS_W],
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) is_final: bool,
+    pub(crate) length: usize,
+    pub(crate) data_rlc: Value<F>,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+impl<F: Field> Sha256Row<F> {
+    fn new(offset: usize) -> Self {
+        Self {
+            q_enable: true,
+            q_start: offset == 0,
+            q_round: (ROUNDS_OFFSET..END_OFFSET).contains(&offset),
+            q_absorb: (ROUNDS_OFFSET..=ABSORB_LAST_OFFSET).contains(&offset),
+            q_padding: (ROUNDS_OFFSET..=PADDING_LAST_OFFSET).contains(&offset),
+            q_length: offset == ABSORB_LAST_OFFSET,
+            q_extend: (ABSORB_LAST_OFFSET + 1..END_OFFSET).contains(&offset),
+            q_end: (END_OFFSET..NUM_ROWS_PER_BLOCK).contains(&offset),
+            q_end_last: offset == NUM_ROWS_PER_BLOCK - 1,
+            round_cst: if (ROUNDS_OFFSET..END_OFFSET).contains(&offset) {
+                F::from(ROUND_CST[offset - ROUNDS_OFFSET] as u64)
+            } else {
+                F::ZERO
+            },
+            w: [false; NUM_BITS_PER_WORD],
+            a: [false; NUM_BITS_PER_WORD],
+            e: [false; NUM_BITS_PER_WORD],
+            carry_a: [false; NUM_CARRY_BITS_A],
+            carry_e: [false; NUM_CARRY_BITS_E],
+            carry_w: [false; NUM_CARRY_BITS_W],
+            is_paddings: [false; NUM_BYTES_PER_WORD],
+            is_final: false,
+            length: 0,
+            data_rlc: Value::known(F::ZERO),
+            hash_rlc: Value::known(F::ZERO),
+        }
+    }
+
+    /// A row outside of any block, with all the selectors disabled.
+    fn dummy() -> Self {
+        Self {
+            q_enable: false,
+            ..Self::new(NUM_ROWS_PER_BLOCK)
+        }
+    }
+}
+
+/// Little-endian bits of the lowest `N` bits of `value`.
+pub(crate) fn to_bits<const N: usize>(value: u64) -> [bool; N] {
+    std::array::from_fn(|i| (value >> i) & 1 == 1)
+}
+
+fn sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+fn ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+fn maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+/// Pad the input to a multiple of the block size: a single 0x80 byte, zeroes
+/// and the length of the input in bits as a 64-bit big-endian integer.
+pub(crate) fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while (padded.len() + NUM_BYTES_PADDING_LENGTH) % RATE != 0 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_be_bytes());
+    padded
+}
+
+/// Number of blocks needed to hash `length` bytes.
+pub(crate) fn get_num_sha256_blocks(length: usize) -> usize {
+    (length + 1 + NUM_BYTES_PADDING_LENGTH + RATE - 1) / RATE
+}
+
+fn sha256<F: Field>(rows: &mut Vec<Sha256Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let r = challenges.keccak_input();
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0;
+    let mut data_rlc = Value::known(F::ZERO);
+    let mut is_padding = false;
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let mut block_rows: Vec<Sha256Row<F>> =
+            (0..NUM_ROWS_PER_BLOCK).map(Sha256Row::new).collect();
+
+        // Start rows with the hash values of the previous block
+        for (offset, row) in block_rows[..ROUNDS_OFFSET].iter_mut().enumerate() {
+            row.a = to_bits(hs[3 - offset] as u64);
+            row.e = to_bits(hs[7 - offset] as u64);
+        }
+
+        // Rounds
+        let mut ws: Vec<u32> = Vec::with_capacity(NUM_ROUNDS);
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hs;
+        for (round, row) in block_rows[ROUNDS_OFFSET..END_OFFSET].iter_mut().enumerate() {
+            let (w, carry_w) = if round < NUM_WORDS_TO_ABSORB {
+                let word = &block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD];
+                (u32::from_be_bytes(word.try_into().unwrap()), 0)
+            } else {
+                let sum = sigma1(ws[round - 2]) as u64
+                    + ws[round - 7] as u64
+                    + sigma0(ws[round - 15]) as u64
+                    + ws[round - 16] as u64;
+                (sum as u32, sum >> 32)
+            };
+            ws.push(w);
+
+            let t1 = h as u64
+                + big_sigma1(e) as u64
+                + ch(e, f, g) as u64
+                + ROUND_CST[round] as u64
+                + w as u64;
+            let t2 = big_sigma0(a) as u64 + maj(a, b, c) as u64;
+            let new_e = d as u64 + t1;
+            let new_a = t1 + t2;
+            (h, g, f, e) = (g, f, e, new_e as u32);
+            (d, c, b, a) = (c, b, a, new_a as u32);
+
+            row.w = to_bits(w as u64);
+            row.a = to_bits(a as u64);
+            row.e = to_bits(e as u64);
+            row.carry_a = to_bits(new_a >> 32);
+            row.carry_e = to_bits(new_e >> 32);
+            row.carry_w = to_bits(carry_w);
+        }
+
+        // End rows with the new hash values
+        let state = [a, b, c, d, e, f, g, h];
+        for (offset, row) in block_rows[END_OFFSET..].iter_mut().enumerate() {
+            let (idx_a, idx_e) = (3 - offset, 7 - offset);
+            let sum_a = hs[idx_a] as u64 + state[idx_a] as u64;
+            let sum_e = hs[idx_e] as u64 + state[idx_e] as u64;
+            row.a = to_bits(sum_a);
+            row.e = to_bits(sum_e);
+            row.carry_a = to_bits(sum_a >> 32);
+            row.carry_e = to_bits(sum_e >> 32);
+        }
+        for (hash, word) in hs.iter_mut().zip(state) {
+            *hash = hash.wrapping_add(word);
+        }
+
+        // Absorb the data on the first rounds. The length, the data RLC and the
+        // last padding flag are carried over the other rows.
+        for (offset, row) in block_rows.iter_mut().enumerate() {
+            if row.q_absorb {
+                let round = offset - ROUNDS_OFFSET;
+                let word = &block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD];
+                for (byte_idx, byte) in word.iter().enumerate() {
+                    let position = idx * RATE + round * NUM_BYTES_PER_WORD + byte_idx;
+                    is_padding = position >= bytes.len();
+                    row.is_paddings[byte_idx] = is_padding;
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc * r + Value::known(F::from(*byte as u64));
+                    }
+                }
+            } else {
+                row.is_paddings[NUM_BYTES_PER_WORD - 1] = is_padding;
+            }
+            row.length = length;
+            row.data_rlc = data_rlc;
+        }
+
+        let last_row = block_rows.last_mut().unwrap();
+        last_row.is_final = idx == num_blocks - 1;
+        let hash_bytes = hs
+            .iter()
+            .flat_map(|hash| hash.to_be_bytes())
+            .collect::<Vec<_>>();
+        last_row.hash_rlc = r.map(|r| rlc::value(hash_bytes.iter().rev(), r));
+
+        rows.extend(block_rows);
+    }
+}
+
+/// Generate the rows of the SHA-256 circuit for all the inputs, padded with
+/// hashes of empty inputs up to `capacity` blocks.
+pub(crate) fn multi_sha256<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    // Dummy first row so that the first block starts a new hash; `is_final` just
+    // needs to be disabled.
+    let mut rows = vec![Sha256Row::dummy()];
+    for bytes in bytes {
+        sha256(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            sha256(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < 1 + capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > 1 + capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
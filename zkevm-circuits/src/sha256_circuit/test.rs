@@ -0,0 +1,94 @@
+use super::{sha256_bit::pad, *};
+use crate::util::unusable_rows;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+#[test]
+fn sha256_circuit_unusable_rows() {
+    assert_eq!(
+        Sha256Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha256Circuit::<Fr>>(()),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Sha256Circuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn sha256_padding() {
+    for length in [0, 1, 55, 56, 63, 64, 119, 120, 200] {
+        let input = vec![0xab; length];
+        let padded = pad(&input);
+        assert_eq!(padded.len() % RATE, 0);
+        assert_eq!(padded.len() / RATE, get_num_sha256_blocks(length));
+        assert_eq!(padded[length], 0x80);
+    }
+}
+
+#[test]
+fn sha256_hash_rlc() {
+    let r = Fr::from(0x100);
+    let challenges = Challenges::mock(Value::known(r), Value::known(r), Value::known(r));
+    let inputs = vec![vec![], (0u8..55).collect::<Vec<_>>(), (0u8..200).collect()];
+    let rows = multi_sha256::<Fr>(&inputs, challenges, None).unwrap();
+    let final_rows = rows.iter().filter(|row| row.is_final).collect::<Vec<_>>();
+    assert_eq!(final_rows.len(), inputs.len());
+    for (input, row) in inputs.iter().zip(final_rows) {
+        let [_, input_rlc, input_len, output_rlc] = Sha256Table::assignments(input, &challenges);
+        row.data_rlc.zip(input_rlc).assert_if_known(|(a, b)| a == b);
+        input_len.assert_if_known(|len| *len == Fr::from(row.length as u64));
+        row.hash_rlc
+            .zip(output_rlc)
+            .assert_if_known(|(a, b)| a == b);
+    }
+}
+
+#[test]
+fn sha256_circuit_simple() {
+    let k = 12;
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..120).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(k, inputs, true);
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 12;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let inputs = vec![];
+    let circuit = Sha256Circuit::new(num_rows, inputs);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    let circuit = Sha256Circuit::new(num_rows, inputs);
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
@@ -0,0 +1,97 @@
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::{not, select, xor, Expr};
+use halo2_proofs::plonk::Expression;
+
+/// Decode the value of little-endian bits.
+pub(crate) fn decode<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+    bits.iter()
+        .rev()
+        .fold(0.expr(), |acc, bit| acc * 2.expr() + bit.clone())
+}
+
+/// Big-endian bytes of a word given as little-endian bits.
+pub(crate) fn to_be_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits.chunks(NUM_BITS_PER_BYTE).rev().map(decode).collect()
+}
+
+/// Bits of a word rotated right by `n`.
+pub(crate) fn rotate<F: Field>(bits: &[Expression<F>], n: usize) -> Vec<Expression<F>> {
+    (0..NUM_BITS_PER_WORD)
+        .map(|i| bits[(i + n) % NUM_BITS_PER_WORD].clone())
+        .collect()
+}
+
+/// Bits of a word shifted right by `n`.
+pub(crate) fn shift<F: Field>(bits: &[Expression<F>], n: usize) -> Vec<Expression<F>> {
+    (0..NUM_BITS_PER_WORD)
+        .map(|i| bits.get(i + n).cloned().unwrap_or_else(|| 0.expr()))
+        .collect()
+}
+
+/// Bitwise XOR of three words.
+pub(crate) fn xor3<F: Field>(
+    a: &[Expression<F>],
+    b: &[Expression<F>],
+    c: &[Expression<F>],
+) -> Vec<Expression<F>> {
+    a.iter()
+        .zip(b)
+        .zip(c)
+        .map(|((a, b), c)| xor::expr(xor::expr(a.clone(), b.clone()), c.clone()))
+        .collect()
+}
+
+/// Value of `ROTR^2(a) ^ ROTR^13(a) ^ ROTR^22(a)`.
+pub(crate) fn big_sigma0<F: Field>(a: &[Expression<F>]) -> Expression<F> {
+    decode(&xor3(&rotate(a, 2), &rotate(a, 13), &rotate(a, 22)))
+}
+
+/// Value of `ROTR^6(e) ^ ROTR^11(e) ^ ROTR^25(e)`.
+pub(crate) fn big_sigma1<F: Field>(e: &[Expression<F>]) -> Expression<F> {
+    decode(&xor3(&rotate(e, 6), &rotate(e, 11), &rotate(e, 25)))
+}
+
+/// Value of `ROTR^7(w) ^ ROTR^18(w) ^ SHR^3(w)`.
+pub(crate) fn sigma0<F: Field>(w: &[Expression<F>]) -> Expression<F> {
+    decode(&xor3(&rotate(w, 7), &rotate(w, 18), &shift(w, 3)))
+}
+
+/// Value of `ROTR^17(w) ^ ROTR^19(w) ^ SHR^10(w)`.
+pub(crate) fn sigma1<F: Field>(w: &[Expression<F>]) -> Expression<F> {
+    decode(&xor3(&rotate(w, 17), &rotate(w, 19), &shift(w, 10)))
+}
+
+/// Value of `(e & f) ^ (!e & g)`.
+pub(crate) fn ch<F: Field>(
+    e: &[Expression<F>],
+    f: &[Expression<F>],
+    g: &[Expression<F>],
+) -> Expression<F> {
+    decode(
+        &e.iter()
+            .zip(f)
+            .zip(g)
+            .map(|((e, f), g)| select::expr(e.clone(), f.clone(), g.clone()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Value of `(a & b) ^ (a & c) ^ (b & c)`.
+pub(crate) fn maj<F: Field>(
+    a: &[Expression<F>],
+    b: &[Expression<F>],
+    c: &[Expression<F>],
+) -> Expression<F> {
+    decode(
+        &a.iter()
+            .zip(b)
+            .zip(c)
+            .map(|((a, b), c)| {
+                // The majority is `b` when `a == c`, else it's `a`.
+                let a_eq_c = not::expr(xor::expr(a.clone(), c.clone()));
+                select::expr(a_eq_c, c.clone(), b.clone())
+            })
+            .collect::<Vec<_>>(),
+    )
+}
@@ -14,6 +14,13 @@
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
+//! - [x] SHA256 Circuit
+//! - [x] RIPEMD160 Circuit
+//! - [x] RLP Circuit
+//! - [x] MODEXP Circuit
+//! - [x] ECC Circuit
+//! - [x] Pairing Circuit
+//! - [x] Blake2f Circuit
 //! - [ ] MPT Circuit
 //! - [x] PublicInputs Circuit
 //!
@@ -33,6 +40,7 @@
 //!   - [x] EVM Circuit
 //!   - [x] Copy Circuit
 //!   - [x] PublicInputs Circuit
+//!   - [x] RLP Circuit
 //! - [x] Bytecode Table
 //!   - [x] Bytecode Circuit
 //!   - [x] EVM Circuit
@@ -42,7 +50,25 @@
 //!   - [x] PublicInputs Circuit
 //! - [ ] MPT Table
 //!   - [ ] MPT Circuit
+//! - [x] SHA256 Table
+//!   - [x] SHA256 Circuit
+//!   - [x] EVM Circuit
 //!   - [ ] State Circuit
+//! - [x] MODEXP Table
+//!   - [x] MODEXP Circuit
+//!   - [x] EVM Circuit
+//! - [x] ECC Table
+//!   - [x] ECC Circuit
+//!   - [x] EVM Circuit
+//! - [x] RLP Table
+//!   - [x] RLP Circuit
+//!   - [x] Tx Circuit
+//! - [x] Pairing Table
+//!   - [x] Pairing Circuit
+//!   - [x] EVM Circuit
+//! - [x] Blake2f Table
+//!   - [x] Blake2f Circuit
+//!   - [x] EVM Circuit
 //! - [x] Keccak Table
 //!   - [ ] Keccak Circuit
 //!   - [ ] EVM Circuit
@@ -54,17 +80,28 @@
 pub(crate) mod test;
 
 use crate::{
+    blake2f_circuit::{Blake2fCircuit, Blake2fCircuitConfig, Blake2fCircuitConfigArgs},
     bytecode_circuit::circuit::{
         BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs,
     },
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
+    ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    modexp_circuit::{
+        ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs, DEFAULT_NUM_LIMBS,
+    },
+    pairing_circuit::{PairingCircuit, PairingCircuitConfig, PairingCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    ripemd160_circuit::{Ripemd160Circuit, Ripemd160CircuitConfig, Ripemd160CircuitConfigArgs},
+    rlp_circuit::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
+        byte_table::ByteTable, Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable,
+        ExpTable, KeccakTable, ModExpTable, MptTable, PairingTable, Ripemd160Table, RlpTable,
+        RwTable, Sha256Table, SigTable, TxTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
@@ -93,6 +130,14 @@ pub struct SuperCircuitConfig<F: Field> {
     bytecode_circuit: BytecodeCircuitConfig<F>,
     copy_circuit: CopyCircuitConfig<F>,
     keccak_circuit: KeccakCircuitConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    ripemd160_circuit: Ripemd160CircuitConfig<F>,
+    rlp_circuit: RlpCircuitConfig<F>,
+    modexp_circuit: ModExpCircuitConfig<F>,
+    ecc_circuit: EccCircuitConfig<F>,
+    pairing_circuit: PairingCircuitConfig<F>,
+    blake2f_circuit: Blake2fCircuitConfig<F>,
+    byte_table: ByteTable,
     pi_circuit: PiCircuitConfig<F>,
     exp_circuit: ExpCircuitConfig<F>,
 }
@@ -128,6 +173,15 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let exp_table = ExpTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ecc_table = EccTable::construct(meta);
+        let pairing_table = PairingTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
+        let rlp_table = RlpTable::construct(meta);
+        let byte_table = ByteTable::construct(meta);
 
         // Use a mock randomness instead of the randomness derived from the challange
         // (either from mock or real prover) to help debugging assignments.
@@ -147,6 +201,29 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 challenges: challenges.clone(),
             },
         );
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table: sha256_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table: ripemd160_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let modexp_circuit = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                num_limbs: DEFAULT_NUM_LIMBS,
+                modexp_table: modexp_table.clone(),
+                byte_table: byte_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
 
         let pi_circuit = PiCircuitConfig::new(
             meta,
@@ -157,11 +234,45 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 tx_table: tx_table.clone(),
             },
         );
+        let rlp_circuit = RlpCircuitConfig::new(
+            meta,
+            RlpCircuitConfigArgs {
+                rlp_table: rlp_table.clone(),
+                tx_table: tx_table.clone(),
+                block_table: block_table.clone(),
+                keccak_table: keccak_table.clone(),
+                byte_table: byte_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
         let tx_circuit = TxCircuitConfig::new(
             meta,
             TxCircuitConfigArgs {
                 tx_table: tx_table.clone(),
                 keccak_table: keccak_table.clone(),
+                sig_table,
+                rlp_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let ecc_circuit = EccCircuitConfig::new(
+            meta,
+            EccCircuitConfigArgs {
+                ecc_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let pairing_circuit = PairingCircuitConfig::new(
+            meta,
+            PairingCircuitConfigArgs {
+                pairing_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table: blake2f_table.clone(),
                 challenges: challenges.clone(),
             },
         );
@@ -204,6 +315,13 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 copy_table,
                 keccak_table,
                 exp_table,
+                sig_table,
+                sha256_table,
+                ripemd160_table,
+                modexp_table,
+                ecc_table,
+                pairing_table,
+                blake2f_table,
             },
         );
 
@@ -216,6 +334,14 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             tx_circuit,
             bytecode_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            rlp_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            pairing_circuit,
+            blake2f_circuit,
+            byte_table,
             pi_circuit,
             exp_circuit,
         }
@@ -241,6 +367,20 @@ pub struct SuperCircuit<F: Field> {
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// SHA256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// RIPEMD160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// RLP Circuit
+    pub rlp_circuit: RlpCircuit<F>,
+    /// MODEXP Circuit
+    pub modexp_circuit: ModExpCircuit<F>,
+    /// ECC Circuit
+    pub ecc_circuit: EccCircuit<F>,
+    /// Pairing Circuit
+    pub pairing_circuit: PairingCircuit<F>,
+    /// Blake2f Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
     /// Circuits Parameters
     pub circuits_params: CircuitsParams,
     /// Mock randomness
@@ -273,6 +413,13 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             CopyCircuit::<F>::unusable_rows(),
             ExpCircuit::<F>::unusable_rows(),
             KeccakCircuit::<F>::unusable_rows(),
+            Sha256Circuit::<F>::unusable_rows(),
+            Ripemd160Circuit::<F>::unusable_rows(),
+            RlpCircuit::<F>::unusable_rows(),
+            ModExpCircuit::<F>::unusable_rows(),
+            EccCircuit::<F>::unusable_rows(),
+            PairingCircuit::<F>::unusable_rows(),
+            Blake2fCircuit::<F>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -286,6 +433,13 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let rlp_circuit = RlpCircuit::new_from_block(block);
+        let modexp_circuit = ModExpCircuit::new_from_block(block);
+        let ecc_circuit = EccCircuit::new_from_block(block);
+        let pairing_circuit = PairingCircuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
 
         SuperCircuit::<_> {
             evm_circuit,
@@ -296,6 +450,13 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            rlp_circuit,
+            modexp_circuit,
+            ecc_circuit,
+            pairing_circuit,
+            blake2f_circuit,
             circuits_params: block.circuits_params,
             mock_randomness: block.randomness,
         }
@@ -307,6 +468,8 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         instance.extend_from_slice(&self.keccak_circuit.instance());
         instance.extend_from_slice(&self.pi_circuit.instance());
         instance.extend_from_slice(&self.tx_circuit.instance());
+        instance.extend_from_slice(&self.ecc_circuit.instance());
+        instance.extend_from_slice(&self.pairing_circuit.instance());
         instance.extend_from_slice(&self.bytecode_circuit.instance());
         instance.extend_from_slice(&self.copy_circuit.instance());
         instance.extend_from_slice(&self.state_circuit.instance());
@@ -323,11 +486,21 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         let bytecode = BytecodeCircuit::min_num_rows_block(block);
         let copy = CopyCircuit::min_num_rows_block(block);
         let keccak = KeccakCircuit::min_num_rows_block(block);
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
+        let rlp = RlpCircuit::min_num_rows_block(block);
+        let modexp = ModExpCircuit::min_num_rows_block(block);
+        let ecc = EccCircuit::min_num_rows_block(block);
+        let pairing = PairingCircuit::min_num_rows_block(block);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
         let tx = TxCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
         let pi = PiCircuit::min_num_rows_block(block);
 
-        let rows: Vec<(usize, usize)> = vec![evm, state, bytecode, copy, keccak, tx, exp, pi];
+        let rows: Vec<(usize, usize)> = vec![
+            evm, state, bytecode, copy, keccak, sha256, ripemd160, rlp, modexp, ecc, pairing,
+            blake2f, tx, exp, pi,
+        ];
         let (rows_without_padding, rows_with_padding): (Vec<usize>, Vec<usize>) =
             rows.into_iter().unzip();
         (
@@ -345,6 +518,20 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
     ) -> Result<(), Error> {
         self.keccak_circuit
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
+        self.rlp_circuit
+            .synthesize_sub(&config.rlp_circuit, challenges, layouter)?;
+        self.modexp_circuit
+            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        self.ecc_circuit
+            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
+        self.pairing_circuit
+            .synthesize_sub(&config.pairing_circuit, challenges, layouter)?;
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
         self.bytecode_circuit
             .synthesize_sub(&config.bytecode_circuit, challenges, layouter)?;
         self.tx_circuit
@@ -428,6 +615,8 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             Value::known(block.randomness),
         )?;
 
+        config.byte_table.load(&mut layouter)?;
+
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
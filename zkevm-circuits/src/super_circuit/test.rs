@@ -137,6 +137,15 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -153,6 +162,15 @@ fn serial_test_super_circuit_1tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -169,6 +187,15 @@ fn serial_test_super_circuit_2tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -0,0 +1,108 @@
+use super::*;
+
+use bus_mapping::precompile::Blake2fAuxData;
+
+/// Blake2f Table, used to verify the BLAKE2 compression function F from its
+/// RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Input as `RLC(reversed(input))`, the input having the 213 bytes of
+    /// EIP-152.
+    pub input_rlc: Column<Advice>,
+    /// New state vector as `RLC(reversed(output))`, the output being its 64
+    /// little-endian bytes.
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the blake2f table assignments from a BLAKE2 F call.
+    pub fn assignments<F: Field>(
+        aux_data: &Blake2fAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 3] {
+        let input = aux_data.input_bytes();
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let output = aux_data.output_bytes();
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        [Value::known(F::ONE), input_rlc, output_rlc]
+    }
+
+    /// Assign a table row for blake2f table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 3],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Blake2fTable as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full blake2f circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                self.assign_row(&mut region, 0, [(); 3].map(|_| Value::known(F::ZERO)))?;
+                for (offset, aux_data) in block
+                    .precompile_events
+                    .get_blake2f_events()
+                    .iter()
+                    .enumerate()
+                {
+                    self.assign_row(
+                        &mut region,
+                        offset + 1,
+                        Self::assignments(aux_data, challenges),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
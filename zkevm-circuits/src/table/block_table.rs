@@ -21,6 +21,11 @@ pub enum BlockContextFieldTag {
     /// Chain ID field.  Although this is not a field in the block header, we
     /// add it here for convenience.
     ChainId,
+    /// Blob Base Fee field (EIP-4844)
+    BlobBaseFee,
+    /// Hardfork whose rules apply to the block. Like the chain id, it is not a
+    /// field in the block header.
+    Hardfork,
 }
 impl_expr!(BlockContextFieldTag);
 
@@ -11,7 +11,7 @@ pub struct CopyTable {
     pub is_first: Column<Advice>,
     /// The relevant ID for the read-write row, represented as a random linear
     /// combination. The ID may be one of the below:
-    /// 1. Call ID/Caller ID for CopyDataType::Memory
+    /// 1. Call ID/Caller ID for CopyDataType::Memory, CopyDataType::MemoryCopy
     /// 2. RLC encoding of bytecode hash for CopyDataType::Bytecode
     /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog
     pub id: Column<Advice>,
@@ -61,7 +61,9 @@ impl CopyTable {
     ) -> Vec<(CopyDataType, CopyTableRow<F>, CopyCircuitRow<F>)> {
         let mut assignments = Vec::new();
         // rlc_acc
-        let rlc_acc = if copy_event.dst_type == CopyDataType::RlcAcc {
+        let rlc_acc = if copy_event.src_type == CopyDataType::RlcAcc
+            || copy_event.dst_type == CopyDataType::RlcAcc
+        {
             let values = copy_event
                 .bytes
                 .iter()
@@ -154,6 +156,14 @@ impl CopyTable {
                         + Value::known(F::from(copy_step.value as u64));
                     value_acc
                 }
+            } else if copy_event.src_type == CopyDataType::RlcAcc {
+                if is_read_step {
+                    value_acc = value_acc * challenges.keccak_input()
+                        + Value::known(F::from(copy_step.value as u64));
+                    value_acc
+                } else {
+                    Value::known(F::from(copy_step.value as u64))
+                }
             } else {
                 Value::known(F::from(copy_step.value as u64))
             };
@@ -0,0 +1,189 @@
+use super::*;
+
+use bus_mapping::precompile::{EcAddAuxData, EcMulAuxData, PrecompileCalls};
+
+/// The ECC table is used to verify the additions and scalar multiplications
+/// of BN254 G1 points done by the ecAdd and ecMul precompiles.  Points are
+/// given by their coordinates, `(0, 0)` being the point at infinity.
+#[derive(Clone, Copy, Debug)]
+pub struct EccTable {
+    /// Whether the row is enabled.
+    pub q_enable: Column<Fixed>,
+    /// Address of the precompile doing the operation, i.e. 0x06 for an
+    /// addition and 0x07 for a scalar multiplication.
+    pub op_type: Column<Advice>,
+    /// RLC of the x-coordinate of the first point.
+    pub arg1_rlc: Column<Advice>,
+    /// RLC of the y-coordinate of the first point.
+    pub arg2_rlc: Column<Advice>,
+    /// RLC of the x-coordinate of the second point for an addition, or of the
+    /// scalar for a multiplication.
+    pub arg3_rlc: Column<Advice>,
+    /// RLC of the y-coordinate of the second point for an addition, or zero
+    /// for a multiplication.
+    pub arg4_rlc: Column<Advice>,
+    /// RLC of the x-coordinate of the result.
+    pub output1_rlc: Column<Advice>,
+    /// RLC of the y-coordinate of the result.
+    pub output2_rlc: Column<Advice>,
+    /// Whether the input points are on the curve, or at infinity.  The
+    /// result is zero for invalid points.
+    pub is_valid: Column<Advice>,
+}
+
+impl EccTable {
+    /// Construct the EccTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
+            op_type: meta.advice_column(),
+            arg1_rlc: meta.advice_column_in(SecondPhase),
+            arg2_rlc: meta.advice_column_in(SecondPhase),
+            arg3_rlc: meta.advice_column_in(SecondPhase),
+            arg4_rlc: meta.advice_column_in(SecondPhase),
+            output1_rlc: meta.advice_column_in(SecondPhase),
+            output2_rlc: meta.advice_column_in(SecondPhase),
+            is_valid: meta.advice_column(),
+        };
+        // The rows are copied from the cells of the ECC circuit.
+        for column in <EccTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    fn word_rlc<F: Field>(word: &Word, challenges: &Challenges<Value<F>>) -> Value<F> {
+        challenges
+            .evm_word()
+            .map(|challenge| rlc::value(&word.to_le_bytes(), challenge))
+    }
+
+    /// Generate the ECC table assignments of the advice columns from an ecAdd
+    /// call.
+    pub fn ec_add_assignments<F: Field>(
+        aux_data: &EcAddAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 8] {
+        let [arg1, arg2, arg3, arg4, output1, output2] = [
+            &aux_data.p[0],
+            &aux_data.p[1],
+            &aux_data.q[0],
+            &aux_data.q[1],
+            &aux_data.r[0],
+            &aux_data.r[1],
+        ]
+        .map(|word| Self::word_rlc(word, challenges));
+        [
+            Value::known(F::from(PrecompileCalls::Bn128Add.address())),
+            arg1,
+            arg2,
+            arg3,
+            arg4,
+            output1,
+            output2,
+            Value::known(F::from(aux_data.is_valid() as u64)),
+        ]
+    }
+
+    /// Generate the ECC table assignments of the advice columns from an ecMul
+    /// call.
+    pub fn ec_mul_assignments<F: Field>(
+        aux_data: &EcMulAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 8] {
+        let [arg1, arg2, arg3, output1, output2] = [
+            &aux_data.p[0],
+            &aux_data.p[1],
+            &aux_data.s,
+            &aux_data.r[0],
+            &aux_data.r[1],
+        ]
+        .map(|word| Self::word_rlc(word, challenges));
+        [
+            Value::known(F::from(PrecompileCalls::Bn128Mul.address())),
+            arg1,
+            arg2,
+            arg3,
+            Value::known(F::ZERO),
+            output1,
+            output2,
+            Value::known(F::from(aux_data.is_valid() as u64)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume an ECC
+    /// table but without running the full ECC circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecc table (dev load)",
+            |mut region| {
+                let advice_columns = <EccTable as LookupTable<F>>::advice_columns(self);
+                let ec_add_events = block.precompile_events.get_ec_add_events();
+                let ec_mul_events = block.precompile_events.get_ec_mul_events();
+                let rows = once([(); 8].map(|_| Value::known(F::ZERO)))
+                    .chain(
+                        ec_add_events
+                            .iter()
+                            .map(|aux_data| Self::ec_add_assignments(aux_data, challenges)),
+                    )
+                    .chain(
+                        ec_mul_events
+                            .iter()
+                            .map(|aux_data| Self::ec_mul_assignments(aux_data, challenges)),
+                    );
+                for (offset, row) in rows.enumerate() {
+                    region.assign_fixed(
+                        || format!("ecc table q_enable {}", offset),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::from((offset > 0) as u64)),
+                    )?;
+                    for (&column, value) in advice_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("ecc table row {}", offset),
+                            column,
+                            offset,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for EccTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.op_type.into(),
+            self.arg1_rlc.into(),
+            self.arg2_rlc.into(),
+            self.arg3_rlc.into(),
+            self.arg4_rlc.into(),
+            self.output1_rlc.into(),
+            self.output2_rlc.into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("op_type"),
+            String::from("arg1_rlc"),
+            String::from("arg2_rlc"),
+            String::from("arg3_rlc"),
+            String::from("arg4_rlc"),
+            String::from("output1_rlc"),
+            String::from("output2_rlc"),
+            String::from("is_valid"),
+        ]
+    }
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+use num_bigint::BigUint;
+
+/// ModExp Table, used to verify the modular exponentiations of the MODEXP
+/// precompile from the RLCs of their operands.
+#[derive(Clone, Debug)]
+pub struct ModExpTable {
+    /// True on the rows of the exponentiations
+    pub q_enable: Column<Fixed>,
+    /// Base as `RLC(le_bytes)`
+    pub base_rlc: Column<Advice>,
+    /// Exponent as `RLC(le_bytes)`
+    pub exponent_rlc: Column<Advice>,
+    /// Modulus as `RLC(le_bytes)`
+    pub modulus_rlc: Column<Advice>,
+    /// Result as `RLC(le_bytes)`, which is zero for a zero modulus.  It's also
+    /// the RLC of the result left padded to the length of the modulus, as
+    /// returned by the precompile.
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for ModExpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.base_rlc.into(),
+            self.exponent_rlc.into(),
+            self.modulus_rlc.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("base_rlc"),
+            String::from("exponent_rlc"),
+            String::from("modulus_rlc"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl ModExpTable {
+    /// Construct a new ModExpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            base_rlc: meta.advice_column_in(SecondPhase),
+            exponent_rlc: meta.advice_column_in(SecondPhase),
+            modulus_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Result of the modular exponentiation, which is zero for a zero modulus.
+    pub fn modexp([base, exponent, modulus]: &[Word; 3]) -> Word {
+        if modulus.is_zero() {
+            return Word::zero();
+        }
+        let [base, exponent, modulus] =
+            [base, exponent, modulus].map(|value| BigUint::from_bytes_le(&value.to_le_bytes()));
+        Word::from_little_endian(&base.modpow(&exponent, &modulus).to_bytes_le())
+    }
+
+    /// Generate the modexp table assignments of the advice columns from the
+    /// base, exponent and modulus.
+    pub fn assignments<F: Field>(
+        inputs: &[Word; 3],
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 4] {
+        let [base, exponent, modulus] = inputs;
+        [base, exponent, modulus, &Self::modexp(inputs)].map(|value| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(&value.to_le_bytes(), challenge))
+        })
+    }
+
+    /// Assign a table row for modexp table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        q_enable: bool,
+        values: [Value<F>; 4],
+    ) -> Result<(), Error> {
+        region.assign_fixed(
+            || format!("assign q_enable {}", offset),
+            self.q_enable,
+            offset,
+            || Value::known(F::from(q_enable as u64)),
+        )?;
+        for (&column, value) in <ModExpTable as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a modexp
+    /// table but without running the full modexp circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a [Word; 3]> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                self.assign_row(
+                    &mut region,
+                    0,
+                    false,
+                    [(); 4].map(|_| Value::known(F::ZERO)),
+                )?;
+                for (offset, inputs) in inputs.clone().into_iter().enumerate() {
+                    self.assign_row(
+                        &mut region,
+                        offset + 1,
+                        true,
+                        Self::assignments(inputs, challenges),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+use bus_mapping::precompile::EcPairingAuxData;
+
+/// The pairing table is used to verify the pairing checks done by the
+/// ecPairing precompile, given the RLC of their input.
+#[derive(Clone, Copy, Debug)]
+pub struct PairingTable {
+    /// Whether the row is enabled.
+    pub q_enable: Column<Fixed>,
+    /// Input as `RLC(reversed(input))`, i.e. the RLC accumulated by the copy
+    /// circuit.
+    pub input_rlc: Column<Advice>,
+    /// Length of the input, which is a non-zero multiple of 192.
+    pub input_len: Column<Advice>,
+    /// Whether all the points of the input are valid, i.e. in their group or
+    /// at infinity.
+    pub is_valid: Column<Advice>,
+    /// Whether the product of the pairings is one, which is zero for an
+    /// invalid input.
+    pub output: Column<Advice>,
+}
+
+impl PairingTable {
+    /// Construct the PairingTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            is_valid: meta.advice_column(),
+            output: meta.advice_column(),
+        };
+        // The rows are copied from the cells of the pairing circuit.
+        for column in <PairingTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the pairing table assignments of the advice columns from an
+    /// ecPairing call.
+    pub fn assignments<F: Field>(
+        aux_data: &EcPairingAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 4] {
+        let input = aux_data.input_bytes();
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let is_valid = aux_data.is_valid();
+        [
+            input_rlc,
+            Value::known(F::from(input.len() as u64)),
+            Value::known(F::from(is_valid as u64)),
+            Value::known(F::from((is_valid && aux_data.output) as u64)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume a pairing
+    /// table but without running the full pairing circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "pairing table (dev load)",
+            |mut region| {
+                let advice_columns = <PairingTable as LookupTable<F>>::advice_columns(self);
+                let rows = once([(); 4].map(|_| Value::known(F::ZERO))).chain(
+                    block
+                        .precompile_events
+                        .get_ec_pairing_events()
+                        .iter()
+                        .map(|aux_data| Self::assignments(aux_data, challenges)),
+                );
+                for (offset, row) in rows.enumerate() {
+                    region.assign_fixed(
+                        || format!("pairing table q_enable {}", offset),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::from((offset > 0) as u64)),
+                    )?;
+                    for (&column, value) in advice_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("pairing table row {}", offset),
+                            column,
+                            offset,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for PairingTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.is_valid.into(),
+            self.output.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("is_valid"),
+            String::from("output"),
+        ]
+    }
+}
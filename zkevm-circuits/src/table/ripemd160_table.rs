@@ -0,0 +1,108 @@
+use super::*;
+
+use ripemd::{Digest, Ripemd160};
+
+/// Ripemd160 Table, used to verify RIPEMD-160 hashing from RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Digest as `RLC(reversed(digest))`, which is also the RLC of the digest
+    /// left padded to 32 bytes as returned by the precompile.
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Ripemd160Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Ripemd160Table {
+    /// Construct a new Ripemd160Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the ripemd160 table assignments from a byte array input.
+    pub fn assignments<F: Field>(input: &[u8], challenges: &Challenges<Value<F>>) -> [Value<F>; 4] {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+        let output = Ripemd160::digest(input);
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        [
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(input_len),
+            output_rlc,
+        ]
+    }
+
+    /// Assign a table row for ripemd160 table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Ripemd160Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a ripemd160
+    /// table but without running the full ripemd160 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ripemd160 table",
+            |mut region| {
+                self.assign_row(&mut region, 0, [(); 4].map(|_| Value::known(F::ZERO)))?;
+                for (offset, input) in inputs.clone().into_iter().enumerate() {
+                    self.assign_row(
+                        &mut region,
+                        offset + 1,
+                        Self::assignments(input, challenges),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
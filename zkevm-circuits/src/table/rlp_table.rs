@@ -0,0 +1,128 @@
+use super::*;
+
+use crate::util::keccak;
+use eth_types::geth_types;
+
+/// Encoding of a transaction hashed in the RLP table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RlpTxFormat {
+    /// Encoding of the unsigned transaction, whose hash is signed
+    TxSign = 0,
+    /// Encoding of the signed transaction, whose hash is the transaction hash
+    TxHash,
+}
+impl_expr!(RlpTxFormat);
+
+/// RLP Table, used to verify the hashes of the RLP encodings of the
+/// transactions.
+#[derive(Clone, Debug)]
+pub struct RlpTable {
+    /// True on the last row of an encoding
+    pub is_enabled: Column<Advice>,
+    /// Tx ID
+    pub tx_id: Column<Advice>,
+    /// Format of the encoding (RlpTxFormat)
+    pub format: Column<Advice>,
+    /// RLC of the hash of the encoding
+    pub hash_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for RlpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.tx_id.into(),
+            self.format.into(),
+            self.hash_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("tx_id"),
+            String::from("format"),
+            String::from("hash_rlc"),
+        ]
+    }
+}
+
+impl RlpTable {
+    /// Construct a new RlpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            tx_id: meta.advice_column(),
+            format: meta.advice_column(),
+            hash_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the rlp table assignments of the encodings of a transaction.
+    pub fn assignments<F: Field>(
+        tx_id: usize,
+        tx: &geth_types::Transaction,
+        chain_id: u64,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        [
+            (RlpTxFormat::TxSign, tx.rlp_unsigned(chain_id)),
+            (RlpTxFormat::TxHash, tx.rlp_signed(chain_id)),
+        ]
+        .into_iter()
+        .map(|(format, encoding)| {
+            let hash = keccak(&encoding);
+            [
+                Value::known(F::ONE),
+                Value::known(F::from(tx_id as u64)),
+                Value::known(F::from(format as u64)),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&hash.to_le_bytes(), challenge)),
+            ]
+        })
+        .collect()
+    }
+
+    /// Provide this function for the case that we want to consume a rlp
+    /// table but without running the full rlp circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        txs: &[geth_types::Transaction],
+        chain_id: u64,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "rlp table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <RlpTable as LookupTable<F>>::advice_columns(self) {
+                    region.assign_advice(
+                        || "rlp table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let rlp_table_columns = <RlpTable as LookupTable<F>>::advice_columns(self);
+                for (i, tx) in txs.iter().enumerate() {
+                    for row in Self::assignments(i + 1, tx, chain_id, challenges) {
+                        for (&column, value) in rlp_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("rlp table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
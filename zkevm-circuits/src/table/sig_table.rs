@@ -0,0 +1,130 @@
+use super::*;
+
+use crate::witness::Block;
+use bus_mapping::precompile::EcrecoverAuxData;
+
+/// The sig table is used to verify the recovery of an address from a message
+/// hash and a signature, as done by the ecRecover precompile.
+#[derive(Clone, Copy, Debug)]
+pub struct SigTable {
+    /// Whether the row is enabled.
+    pub q_enable: Column<Fixed>,
+    /// RLC of the message hash.
+    pub msg_hash_rlc: Column<Advice>,
+    /// The signature's `v` component, which is 27 or 28 in enabled rows.
+    pub sig_v: Column<Advice>,
+    /// RLC of the signature's `r` component.
+    pub sig_r_rlc: Column<Advice>,
+    /// RLC of the signature's `s` component.
+    pub sig_s_rlc: Column<Advice>,
+    /// The recovered address, or zero if the recovery failed.
+    pub recovered_addr: Column<Advice>,
+    /// Whether a public key could be recovered from the signature.
+    pub is_valid: Column<Advice>,
+}
+
+impl SigTable {
+    /// Construct the SigTable.
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
+            msg_hash_rlc: meta.advice_column_in(SecondPhase),
+            sig_v: meta.advice_column(),
+            sig_r_rlc: meta.advice_column_in(SecondPhase),
+            sig_s_rlc: meta.advice_column_in(SecondPhase),
+            recovered_addr: meta.advice_column(),
+            is_valid: meta.advice_column(),
+        };
+        // The rows are copied from the cells of the signature verifier.
+        for column in <SigTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the sig table assignments of the advice columns from an
+    /// ecRecover call.
+    pub fn assignments<F: Field>(
+        aux_data: &EcrecoverAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 6] {
+        let word_rlc = |word: &Word| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&word.to_le_bytes(), challenge))
+        };
+        [
+            word_rlc(&aux_data.msg_hash),
+            Value::known(F::from(aux_data.sig_v.low_u64())),
+            word_rlc(&aux_data.sig_r),
+            word_rlc(&aux_data.sig_s),
+            Value::known(aux_data.recovered_addr.to_scalar().unwrap()),
+            Value::known(F::from(!aux_data.recovered_addr.is_zero() as u64)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume a sig
+    /// table but without running the full signature verifier
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sig table (dev load)",
+            |mut region| {
+                let advice_columns = <SigTable as LookupTable<F>>::advice_columns(self);
+                let events = block.precompile_events.get_ecrecover_events();
+                let rows = once([(); 6].map(|_| Value::known(F::ZERO))).chain(
+                    events
+                        .iter()
+                        .map(|aux_data| Self::assignments(aux_data, challenges)),
+                );
+                for (offset, row) in rows.enumerate() {
+                    region.assign_fixed(
+                        || format!("sig table q_enable {}", offset),
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::from((offset > 0) as u64)),
+                    )?;
+                    for (&column, value) in advice_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("sig table row {}", offset),
+                            column,
+                            offset,
+                            || value,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for SigTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.msg_hash_rlc.into(),
+            self.sig_v.into(),
+            self.sig_r_rlc.into(),
+            self.sig_s_rlc.into(),
+            self.recovered_addr.into(),
+            self.is_valid.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("msg_hash_rlc"),
+            String::from("sig_v"),
+            String::from("sig_r_rlc"),
+            String::from("sig_s_rlc"),
+            String::from("recovered_addr"),
+            String::from("is_valid"),
+        ]
+    }
+}
@@ -10,7 +10,7 @@ pub enum TxFieldTag {
     Nonce,
     /// Gas
     Gas,
-    /// GasPrice
+    /// GasPrice: the effective gas price paid by the tx
     GasPrice,
     /// CallerAddress
     CallerAddress,
@@ -39,6 +39,20 @@ pub enum TxFieldTag {
     TxInvalid,
     /// AccessListGasCost
     AccessListGasCost,
+    /// BlobVersionedHashes
+    BlobVersionedHashes,
+    /// TxType: the EIP-2718 transaction type
+    TxType,
+    /// MaxFeePerGas (EIP-1559), equal to GasPrice for a legacy tx
+    MaxFeePerGas,
+    /// MaxPriorityFeePerGas (EIP-1559), equal to GasPrice for a legacy tx
+    MaxPriorityFeePerGas,
+    /// Number of addresses in the access list (EIP-2930)
+    AccessListAddressesLen,
+    /// Number of storage keys in the access list (EIP-2930)
+    AccessListStorageKeysLen,
+    /// RLC of the addresses and storage keys in the access list (EIP-2930)
+    AccessListRLC,
 }
 impl_expr!(TxFieldTag);
 
@@ -76,7 +90,7 @@ pub struct TxTable {
     pub tx_id: Column<Advice>,
     /// Tag (TxContextFieldTag)
     pub tag: Column<Fixed>,
-    /// Index for Tag = CallData
+    /// Index for Tag = CallData and Tag = BlobVersionedHashes
     pub index: Column<Advice>,
     /// Value
     pub value: Column<Advice>,
@@ -176,7 +190,10 @@ impl TxTable {
         // region that has a size parametrized by max_calldata with all
         // the tx calldata.  This is required to achieve a constant fixed column tag
         // regardless of the number of input txs or the calldata size of each tx.
+        // A third region holds `MAX_BLOB_HASHES_PER_TX` blob versioned hash rows
+        // per tx, zero padded, so its size only depends on max_txs.
         let mut calldata_assignments: Vec<[Value<F>; 4]> = Vec::new();
+        let mut blob_hashes_assignments: Vec<[Value<F>; 4]> = Vec::new();
         // Assign Tx data (all tx fields except for calldata)
         let padding_txs: Vec<_> = (txs.len()..max_txs)
             .map(|i| Transaction {
@@ -185,12 +202,13 @@ impl TxTable {
             })
             .collect();
         for tx in txs.iter().chain(padding_txs.iter()) {
-            let [tx_data, tx_calldata] = tx.table_assignments(*challenges);
+            let [tx_data, tx_calldata, tx_blob_hashes] = tx.table_assignments(*challenges)?;
             for row in tx_data {
                 assign_row(region, offset, &advice_columns, &self.tag, &row, "")?;
                 offset += 1;
             }
             calldata_assignments.extend(tx_calldata.iter());
+            blob_hashes_assignments.extend(tx_blob_hashes.iter());
         }
         // Assign Tx calldata
         let padding_calldata = (sum_txs_calldata..max_calldata).map(|_| {
@@ -205,6 +223,11 @@ impl TxTable {
             assign_row(region, offset, &advice_columns, &self.tag, &row, "")?;
             offset += 1;
         }
+        // Assign Tx blob versioned hashes
+        for row in blob_hashes_assignments {
+            assign_row(region, offset, &advice_columns, &self.tag, &row, "")?;
+            offset += 1;
+        }
         Ok(())
     }
 }
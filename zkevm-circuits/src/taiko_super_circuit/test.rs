@@ -198,6 +198,15 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     block.eth_block.hash = Some(block_hash);
     block.eth_block.parent_hash = parent_hash;
@@ -221,6 +230,15 @@ fn taiko_serial_test_super_circuit_1tx_3max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     test_super_circuit(block, protocol_instance, circuits_params);
 }
@@ -241,6 +259,15 @@ fn taiko_serial_test_super_circuit_2tx_3max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_modexp_rows: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_ec_pairing_pairs: 0,
+        max_blake2f_rounds: 0,
+        max_rlp_rows: 0,
     };
     test_super_circuit(block, protocol_instance, circuits_params);
 }
@@ -14,26 +14,40 @@ mod test;
 pub use dev::TxCircuit as TestTxCircuit;
 
 use crate::{
-    table::{KeccakTable, TxFieldTag, TxTable},
-    util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    table::{KeccakTable, LookupTable, RlpTable, RlpTxFormat, SigTable, TxFieldTag, TxTable},
+    util::{random_linear_combine_word as rlc, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness::{self, access_list_rlc},
+};
+use bus_mapping::precompile::EcrecoverAuxData;
+use eth_types::{
+    evm_types::{GasCost, MAX_BLOB_HASHES_PER_TX},
+    geth_types::Transaction,
+    sign_types::SignData,
+    Field, ToLittleEndian, ToScalar, ToWord,
+};
+use gadgets::{
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    util::not,
 };
-use eth_types::{geth_types::Transaction, sign_types::SignData, Field, ToLittleEndian, ToScalar};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector},
+    poly::Rotation,
 };
 use itertools::Itertools;
 use log::error;
 use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
 use std::marker::PhantomData;
 
-/// Number of static fields per tx: [nonce, gas, gas_price,
-/// caller_address, callee_address, is_create, value, call_data_length,
-/// call_data_gas_cost, tx_sign_hash, r, s, v].
+/// Number of static fields per tx: [nonce, gas, gas_price, max_fee_per_gas,
+/// max_priority_fee_per_gas, caller_address, callee_address, is_create, value,
+/// call_data_length, call_data_gas_cost, tx_sign_hash, r, s, v, tx_type,
+/// access_list_gas_cost, access_list_addresses_len,
+/// access_list_storage_keys_len, access_list_rlc].
 /// Note that call data bytes are layed out in the TxTable after all the static
-/// fields arranged by txs.
-pub(crate) const TX_LEN: usize = 13;
+/// fields arranged by txs, followed by `MAX_BLOB_HASHES_PER_TX` blob versioned
+/// hash rows per tx.
+pub(crate) const TX_LEN: usize = 20;
 
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
@@ -42,10 +56,14 @@ pub struct TxCircuitConfig<F: Field> {
     tag: Column<Fixed>,
     index: Column<Advice>,
     value: Column<Advice>,
+    q_access_list_gas_cost: Selector,
+    q_tx_sign_hash: Selector,
+    tx_sign_hash_is_zero: IsZeroConfig<F>,
     sign_verify: SignVerifyConfig,
     _marker: PhantomData<F>,
     // External tables
     keccak_table: KeccakTable,
+    rlp_table: RlpTable,
 }
 
 /// Circuit configuration arguments
@@ -54,6 +72,10 @@ pub struct TxCircuitConfigArgs<F: Field> {
     pub tx_table: TxTable,
     /// KeccakTable
     pub keccak_table: KeccakTable,
+    /// SigTable
+    pub sig_table: SigTable,
+    /// RlpTable
+    pub rlp_table: RlpTable,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
 }
@@ -67,6 +89,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         Self::ConfigArgs {
             tx_table,
             keccak_table,
+            sig_table,
+            rlp_table,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -76,15 +100,64 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let value = tx_table.value;
         meta.enable_equality(value);
 
-        let sign_verify = SignVerifyConfig::new(meta, keccak_table.clone(), challenges);
+        // The access list gas cost row is followed by the number of addresses
+        // and storage keys in the access list (EIP-2930)
+        let q_access_list_gas_cost = meta.selector();
+        meta.create_gate("access list gas cost", |meta| {
+            let q_access_list_gas_cost = meta.query_selector(q_access_list_gas_cost);
+            let gas_cost = meta.query_advice(value, Rotation::cur());
+            let addresses_len = meta.query_advice(value, Rotation::next());
+            let storage_keys_len = meta.query_advice(value, Rotation(2));
+
+            vec![
+                q_access_list_gas_cost
+                    * (gas_cost
+                        - addresses_len * GasCost::ACCESS_LIST_ADDRESS.expr()
+                        - storage_keys_len * GasCost::ACCESS_LIST_STORAGE.expr()),
+            ]
+        });
+
+        // The signed hash is the hash of the encoding of the tx for signing,
+        // except for the padding txs whose hash is zero. Note that the hash
+        // in the tx table is reduced modulo the order of secp256k1, so a tx
+        // whose hash is above it can't be proved.
+        let q_tx_sign_hash = meta.complex_selector();
+        let tx_sign_hash_inv = meta.advice_column_in(SecondPhase);
+        let tx_sign_hash_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_tx_sign_hash),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            tx_sign_hash_inv,
+        );
+        meta.lookup_any("tx sign hash in rlp table", |meta| {
+            let enable =
+                meta.query_selector(q_tx_sign_hash) * not::expr(tx_sign_hash_is_zero.expr());
+            let input = [
+                1.expr(),
+                meta.query_advice(tx_id, Rotation::cur()),
+                RlpTxFormat::TxSign.expr(),
+                meta.query_advice(value, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(rlp_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table.clone(), sig_table, challenges);
 
         Self {
             tx_id,
             tag,
             index,
             value,
+            q_access_list_gas_cost,
+            q_tx_sign_hash,
+            tx_sign_hash_is_zero,
             sign_verify,
             keccak_table,
+            rlp_table,
             _marker: PhantomData,
         }
     }
@@ -150,6 +223,9 @@ pub struct TxCircuit<F: Field> {
     pub txs: Vec<Transaction>,
     /// Chain ID
     pub chain_id: u64,
+    /// ecRecover calls whose public key recovery is verified by the SignVerify
+    /// chip
+    pub ecrecover_events: Vec<EcrecoverAuxData>,
 }
 
 impl<F: Field> TxCircuit<F> {
@@ -158,17 +234,51 @@ impl<F: Field> TxCircuit<F> {
         TxCircuit::<F> {
             max_txs,
             max_calldata,
-            sign_verify: SignVerifyChip::new(max_txs),
+            sign_verify: SignVerifyChip::new(max_txs, 0),
+            txs,
+            chain_id,
+            ecrecover_events: Vec::new(),
+        }
+    }
+
+    /// Return a new TxCircuit that also verifies the public key recoveries of
+    /// ecRecover calls
+    pub fn new_with_ecrecover(
+        max_txs: usize,
+        max_calldata: usize,
+        max_ecrecover: usize,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+        ecrecover_events: Vec<EcrecoverAuxData>,
+    ) -> Self {
+        TxCircuit::<F> {
+            max_txs,
+            max_calldata,
+            sign_verify: SignVerifyChip::new(max_txs, max_ecrecover),
             txs,
             chain_id,
+            ecrecover_events,
         }
     }
 
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
-        let tx_table_len = txs_len * TX_LEN + call_data_len;
-        std::cmp::max(tx_table_len, SignVerifyChip::<F>::min_num_rows(txs_len))
+        Self::min_num_rows_with_ecrecover(txs_len, call_data_len, 0)
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size, including the ecRecover calls.
+    pub fn min_num_rows_with_ecrecover(
+        txs_len: usize,
+        call_data_len: usize,
+        ecrecover_len: usize,
+    ) -> usize {
+        let tx_table_len = txs_len * (TX_LEN + MAX_BLOB_HASHES_PER_TX) + call_data_len;
+        std::cmp::max(
+            tx_table_len,
+            SignVerifyChip::<F>::min_num_rows(txs_len + ecrecover_len),
+        )
     }
 
     fn assign_tx_table(
@@ -213,6 +323,18 @@ impl<F: Field> TxCircuit<F> {
                                 .evm_word()
                                 .map(|challenge| rlc(tx.gas_price.to_le_bytes(), challenge)),
                         ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            challenges.evm_word().map(|challenge| {
+                                rlc(tx.max_fee_per_gas().to_le_bytes(), challenge)
+                            }),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            challenges.evm_word().map(|challenge| {
+                                rlc(tx.max_priority_fee_per_gas().to_le_bytes(), challenge)
+                            }),
+                        ),
                         (
                             TxFieldTag::CallerAddress,
                             Value::known(tx.from.to_scalar().expect("tx.from too big")),
@@ -256,10 +378,28 @@ impl<F: Field> TxCircuit<F> {
                                 .evm_word()
                                 .map(|challenge| rlc(tx.s.to_le_bytes(), challenge)),
                         ),
+                        (TxFieldTag::TxType, Value::known(F::from(tx.tx_type as u64))),
+                        (
+                            TxFieldTag::AccessListGasCost,
+                            Value::known(F::from(tx.access_list_gas_cost())),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            Value::known(F::from(tx.access_list_len().0)),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            Value::known(F::from(tx.access_list_len().1)),
+                        ),
+                        (
+                            TxFieldTag::AccessListRLC,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| access_list_rlc(&tx.access_list, challenge)),
+                        ),
                     ] {
                         let assigned_cell =
                             config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
-                        offset += 1;
 
                         // Ref. spec 0. Copy constraints using fixed offsets between the tx rows and
                         // the SignVerifyChip
@@ -268,12 +408,24 @@ impl<F: Field> TxCircuit<F> {
                                 assigned_cell.cell(),
                                 assigned_sig_verif.address.cell(),
                             )?,
-                            TxFieldTag::TxSignHash => region.constrain_equal(
-                                assigned_cell.cell(),
-                                assigned_sig_verif.msg_hash_rlc.cell(),
-                            )?,
+                            TxFieldTag::TxSignHash => {
+                                region.constrain_equal(
+                                    assigned_cell.cell(),
+                                    assigned_sig_verif.msg_hash_rlc.cell(),
+                                )?;
+                                config.q_tx_sign_hash.enable(&mut region, offset)?;
+                                IsZeroChip::construct(config.tx_sign_hash_is_zero.clone()).assign(
+                                    &mut region,
+                                    offset,
+                                    value,
+                                )?;
+                            }
+                            TxFieldTag::AccessListGasCost => {
+                                config.q_access_list_gas_cost.enable(&mut region, offset)?
+                            }
                             _ => (),
                         }
+                        offset += 1;
                     }
                 }
 
@@ -305,6 +457,31 @@ impl<F: Field> TxCircuit<F> {
                     )?;
                     offset += 1;
                 }
+
+                // Assign blob versioned hashes, zero padded for every tx. They
+                // are not part of the signed payload of the supported tx types,
+                // and are committed in the public inputs by the PI circuit.
+                for i in 0..assigned_sig_verifs.len() {
+                    let blob_hashes = self
+                        .txs
+                        .get(i)
+                        .map(|tx| tx.blob_versioned_hashes.as_slice())
+                        .unwrap_or_default();
+                    for index in 0..MAX_BLOB_HASHES_PER_TX {
+                        let hash = blob_hashes.get(index).copied().unwrap_or_default();
+                        config.assign_row(
+                            &mut region,
+                            offset,
+                            i + 1, // tx_id
+                            TxFieldTag::BlobVersionedHashes,
+                            index,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(hash.to_word().to_le_bytes(), challenge)),
+                        )?;
+                        offset += 1;
+                    }
+                }
                 Ok(())
             },
         )
@@ -321,29 +498,36 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
     }
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(
+        Self::new_with_ecrecover(
             block.circuits_params.max_txs,
             block.circuits_params.max_calldata,
+            block.circuits_params.max_ecrecover,
             block.context.chain_id.as_u64(),
             block
                 .eth_block
                 .transactions
                 .iter()
-                .map(|tx| tx.into())
+                .map(|tx| {
+                    Transaction::try_from(tx)
+                        .expect("unsupported tx types are rejected by the circuit input builder")
+                })
                 .collect(),
+            block.precompile_events.get_ecrecover_events(),
         )
     }
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         (
-            Self::min_num_rows(
+            Self::min_num_rows_with_ecrecover(
                 block.txs.len(),
                 block.txs.iter().map(|tx| tx.call_data.len()).sum(),
+                block.precompile_events.get_ecrecover_events().len(),
             ),
-            Self::min_num_rows(
+            Self::min_num_rows_with_ecrecover(
                 block.circuits_params.max_txs,
                 block.circuits_params.max_calldata,
+                block.circuits_params.max_ecrecover,
             ),
         )
     }
@@ -368,9 +552,13 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             .try_collect()?;
 
         config.load_aux_tables(layouter)?;
-        let assigned_sig_verifs =
-            self.sign_verify
-                .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
+        let assigned_sig_verifs = self.sign_verify.assign(
+            &config.sign_verify,
+            layouter,
+            &sign_datas,
+            &self.ecrecover_events,
+            challenges,
+        )?;
         self.assign_tx_table(config, challenges, layouter, assigned_sig_verifs)?;
         Ok(())
     }
@@ -1,11 +1,11 @@
 pub use super::TxCircuit;
 
 use crate::{
-    table::{KeccakTable, TxTable},
+    table::{KeccakTable, RlpTable, SigTable, TxTable},
     tx_circuit::{TxCircuitConfig, TxCircuitConfigArgs},
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
-use bus_mapping::circuit_input_builder::keccak_inputs_tx_circuit;
+use bus_mapping::circuit_input_builder::{keccak_inputs_ecrecover, keccak_inputs_tx_circuit};
 use eth_types::Field;
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
@@ -25,6 +25,8 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let tx_table = TxTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let rlp_table = RlpTable::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
@@ -34,6 +36,8 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
                 TxCircuitConfigArgs {
                     tx_table,
                     keccak_table,
+                    sig_table,
+                    rlp_table,
                     challenges,
                 },
             )
@@ -49,14 +53,18 @@ impl<F: Field> Circuit<F> for TxCircuit<F> {
     ) -> Result<(), Error> {
         let challenges = challenges.values(&mut layouter);
 
-        config.keccak_table.dev_load(
-            &mut layouter,
-            &keccak_inputs_tx_circuit(&self.txs[..], self.chain_id).map_err(|e| {
+        let mut keccak_inputs =
+            keccak_inputs_tx_circuit(&self.txs[..], self.chain_id).map_err(|e| {
                 error!("keccak_inputs_tx_circuit error: {:?}", e);
                 Error::Synthesis
-            })?,
-            &challenges,
-        )?;
+            })?;
+        keccak_inputs.extend(keccak_inputs_ecrecover(&self.ecrecover_events));
+        config
+            .keccak_table
+            .dev_load(&mut layouter, &keccak_inputs, &challenges)?;
+        config
+            .rlp_table
+            .dev_load(&mut layouter, &self.txs, self.chain_id, &challenges)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
@@ -1,4 +1,5 @@
-//! Circuit to verify multiple ECDSA secp256k1 signatures.
+//! Circuit to verify multiple ECDSA secp256k1 signatures, and the public key
+//! recoveries done by the ecRecover precompile.
 
 // Naming notes:
 // - *_be: Big-Endian bytes
@@ -6,21 +7,22 @@
 
 use crate::{
     evm_circuit::util::{not, rlc},
-    table::KeccakTable,
+    table::{KeccakTable, LookupTable, SigTable},
     util::{Challenges, Expr},
 };
+use bus_mapping::precompile::EcrecoverAuxData;
 use ecc::{maingate, EccConfig, GeneralEccChip};
 use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
 use eth_types::{
     self,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Field,
+    Field, ToLittleEndian, Word,
 };
 use halo2_proofs::{
     arithmetic::CurveAffine,
     circuit::{AssignedCell, Cell, Layouter, Value},
     halo2curves::{
-        ff::PrimeField,
+        ff::{Field as _, PrimeField},
         group::{Curve, Group},
         secp256k1,
         secp256k1::Secp256k1Affine,
@@ -28,7 +30,10 @@ use halo2_proofs::{
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
     poly::Rotation,
 };
-use integer::{AssignedInteger, IntegerChip, IntegerConfig, IntegerInstructions, Range};
+use integer::{
+    rns::Integer as RnsInteger, AssignedInteger, IntegerChip, IntegerConfig, IntegerInstructions,
+    Range, UnassignedInteger,
+};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 
@@ -40,7 +45,8 @@ use maingate::{
     RangeInstructions, RegionCtx,
 };
 use num::Integer;
-use std::{iter, marker::PhantomData};
+use num_bigint::BigUint;
+use std::{iter, marker::PhantomData, rc::Rc};
 
 /// Auxiliary Gadget to verify a that a message hash is signed by the public
 /// key corresponding to an Ethereum Address.
@@ -52,13 +58,15 @@ pub struct SignVerifyChip<F: Field> {
     pub window_size: usize,
     /// Max number of verifications
     pub max_verif: usize,
+    /// Max number of public key recoveries done by ecRecover calls
+    pub max_ecrecover: usize,
     /// Marker
     pub _marker: PhantomData<F>,
 }
 
 impl<F: Field> SignVerifyChip<F> {
     /// Return a new SignVerifyChip
-    pub fn new(max_verif: usize) -> Self {
+    pub fn new(max_verif: usize, max_ecrecover: usize) -> Self {
         // TODO: Investigate if it is safe to use a random point as aux generator that
         // is choosen by the prover.  If this is unsafe, we will need to update the
         // EccChip to calculate an aux generator using the challange API.
@@ -70,6 +78,7 @@ impl<F: Field> SignVerifyChip<F> {
             aux_generator,
             window_size: 4,
             max_verif,
+            max_ecrecover,
             _marker: PhantomData,
         }
     }
@@ -103,6 +112,7 @@ impl<F: Field> Default for SignVerifyChip<F> {
             aux_generator: Secp256k1Affine::default(),
             window_size: 4,
             max_verif: 0,
+            max_ecrecover: 0,
             _marker: PhantomData::default(),
         }
     }
@@ -125,12 +135,15 @@ pub(crate) struct SignVerifyConfig {
     // Keccak
     q_keccak: Selector,
     keccak_table: KeccakTable,
+    // Recoveries of the ecRecover calls, copied from the ECDSA chips
+    sig_table: SigTable,
 }
 
 impl SignVerifyConfig {
     pub(crate) fn new<F: Field>(
         meta: &mut ConstraintSystem<F>,
         keccak_table: KeccakTable,
+        sig_table: SigTable,
         challenges: Challenges<Expression<F>>,
     ) -> Self {
         // ECDSA config
@@ -209,6 +222,7 @@ impl SignVerifyConfig {
             q_rlc_keccak_input,
             rlc,
             q_keccak,
+            sig_table,
         }
     }
 
@@ -312,6 +326,100 @@ pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) msg_hash_rlc: AssignedValue<F>,
 }
 
+pub(crate) struct AssignedEcrecover<F: Field> {
+    ecdsa: AssignedECDSA<F>,
+    sig_r_le: [AssignedValue<F>; 32],
+    sig_s_le: [AssignedValue<F>; 32],
+    sig_v: AssignedValue<F>,
+    is_valid: AssignedValue<F>,
+}
+
+/// Cells of a row of the sig table.
+#[derive(Debug)]
+pub(crate) struct AssignedSigTableRow<F: Field> {
+    msg_hash_rlc: AssignedValue<F>,
+    sig_v: AssignedValue<F>,
+    sig_r_rlc: AssignedValue<F>,
+    sig_s_rlc: AssignedValue<F>,
+    recovered_addr: AssignedValue<F>,
+    is_valid: AssignedValue<F>,
+}
+
+/// Witness of the public key recovery of an ecRecover call, or of a padding
+/// slot when `is_enabled` is false.
+#[derive(Clone, Debug, Default)]
+struct EcrecoverWitness {
+    is_enabled: bool,
+    recovery_id: u8,
+    msg_hash: Word,
+    sig_r: Word,
+    sig_s: Word,
+    /// Recovered public key, which is `None` when there's no point on the
+    /// curve with x-coordinate `sig_r`.
+    pk: Option<Secp256k1Affine>,
+}
+
+impl EcrecoverWitness {
+    fn new(aux_data: Option<&EcrecoverAuxData>) -> Self {
+        match aux_data {
+            Some(aux_data) => Self {
+                is_enabled: true,
+                recovery_id: aux_data
+                    .recovery_id()
+                    .expect("ecrecover events have a valid v"),
+                msg_hash: aux_data.msg_hash,
+                sig_r: aux_data.sig_r,
+                sig_s: aux_data.sig_s,
+                pk: aux_data.recover_pk(),
+            },
+            None => Self::default(),
+        }
+    }
+
+    fn sig_r(&self) -> secp256k1::Fq {
+        secp256k1::Fq::from_repr(self.sig_r.to_le_bytes()).unwrap()
+    }
+
+    fn sig_s(&self) -> secp256k1::Fq {
+        secp256k1::Fq::from_repr(self.sig_s.to_le_bytes()).unwrap()
+    }
+
+    /// Square root of `-(r^3 + 7)`, which exists when there's no point with
+    /// x-coordinate `r`.
+    fn non_residue_witness(&self) -> secp256k1::Fp {
+        let r = secp256k1::Fp::from_repr(self.sig_r.to_le_bytes()).unwrap();
+        (-(r.square() * r + secp256k1::Fp::from(7)))
+            .sqrt()
+            .unwrap_or(secp256k1::Fp::ZERO)
+    }
+}
+
+// Constrain `a == b` when `cond` is set.
+pub(crate) fn assert_equal_if<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    main_gate: &MainGate<F>,
+    a: &AssignedValue<F>,
+    b: &AssignedValue<F>,
+    cond: &AssignedValue<F>,
+) -> Result<(), Error> {
+    let selected = main_gate.select(ctx, a, b, cond)?;
+    main_gate.assert_equal(ctx, &selected, b)
+}
+
+// Return the terms of the little endian bytes of a word, with the values
+// taken from the word rather than the assigned cells.
+fn word_terms_le<F: Field>(
+    assigned_le: &[AssignedValue<F>; 32],
+    word: &Word,
+) -> impl Iterator<Item = Term<F>> {
+    assigned_le
+        .iter()
+        .zip(word.to_le_bytes())
+        .map(|(assigned, byte)| Term::assigned(assigned.cell(), Value::known(F::from(byte as u64))))
+        .collect_vec()
+        .into_iter()
+}
+
 // Return an array of bytes that corresponds to the little endian representation
 // of the integer, adding the constraints to verify the correctness of the
 // conversion (byte range check included).
@@ -416,6 +524,149 @@ impl<F: Field> SignVerifyChip<F> {
         })
     }
 
+    fn assign_ecrecover(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        ecrecover: &EcrecoverWitness,
+    ) -> Result<AssignedEcrecover<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+            scalar_chip,
+            ecdsa_chip: _,
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
+        let (rns_base, rns_scalar) =
+            GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+        let (rns_base, rns_scalar) = (Rc::new(rns_base), Rc::new(rns_scalar));
+        let padding = SignData::default();
+
+        let is_enabled =
+            main_gate.assign_bit(ctx, Value::known(F::from(ecrecover.is_enabled as u64)))?;
+        let is_valid =
+            main_gate.assign_bit(ctx, Value::known(F::from(ecrecover.pk.is_some() as u64)))?;
+        let recovery_id =
+            main_gate.assign_bit(ctx, Value::known(F::from(ecrecover.recovery_id as u64)))?;
+        // v is 27 or 28 for ecRecover calls, and 0 for padding.
+        let sig_v = {
+            let v = main_gate.add_constant(ctx, &recovery_id, F::from(27))?;
+            main_gate.mul(ctx, &is_enabled, &v)?
+        };
+
+        // The message hash is not reduced modulo n, so that its bytes are the
+        // ones given to the precompile.
+        let msg_hash = {
+            let msg_hash = BigUint::from_bytes_le(&ecrecover.msg_hash.to_le_bytes());
+            let integer =
+                UnassignedInteger::from(Value::known(RnsInteger::from_big(msg_hash, rns_scalar)));
+            scalar_chip.assign_integer(ctx, integer, Range::Remainder)?
+        };
+        let sig_r = scalar_chip.assign_integer(
+            ctx,
+            ecc_chip.new_unassigned_scalar(Value::known(ecrecover.sig_r())),
+            Range::Remainder,
+        )?;
+        let sig_s = scalar_chip.assign_integer(
+            ctx,
+            ecc_chip.new_unassigned_scalar(Value::known(ecrecover.sig_s())),
+            Range::Remainder,
+        )?;
+
+        // When the recovery fails, the point multiplications are done on the
+        // padding signature instead, for which they are well defined.
+        let (r, s, z) = {
+            let padding_r = scalar_chip.assign_constant(ctx, padding.signature.0)?;
+            let padding_s = scalar_chip.assign_constant(ctx, padding.signature.1)?;
+            let padding_z = scalar_chip.assign_constant(ctx, padding.msg_hash)?;
+            (
+                scalar_chip.select(ctx, &sig_r, &padding_r, &is_valid)?,
+                scalar_chip.select(ctx, &sig_s, &padding_s, &is_valid)?,
+                scalar_chip.select(ctx, &msg_hash, &padding_z, &is_valid)?,
+            )
+        };
+        let pk = ecc_chip.assign_point(ctx, Value::known(ecrecover.pk.unwrap_or(padding.pk)))?;
+
+        // The recovered public key is pk = r^-1 (s R - z G), where R is the
+        // point with x-coordinate r and y-coordinate parity v - 27. Instead,
+        // we compute R = s^-1 z G + s^-1 r pk and check its coordinates.
+        scalar_chip.assert_not_zero(ctx, &s)?;
+        let (s_inv, _) = scalar_chip.invert(ctx, &s)?;
+        let u1 = scalar_chip.mul(ctx, &z, &s_inv)?;
+        let u2 = scalar_chip.mul(ctx, &r, &s_inv)?;
+        let generator = ecc_chip.assign_constant(ctx, Secp256k1Affine::generator())?;
+        let point_r = ecc_chip.mul_batch_1d_horizontal(
+            ctx,
+            vec![(generator, u1), (pk.clone(), u2)],
+            self.window_size,
+        )?;
+        let point_r_x = base_chip.reduce(ctx, point_r.x())?;
+        base_chip.assert_in_field(ctx, &point_r_x)?;
+        let point_r_y = base_chip.reduce(ctx, point_r.y())?;
+        base_chip.assert_in_field(ctx, &point_r_y)?;
+        for (x_limb, r_limb) in point_r_x.limbs().iter().zip_eq(sig_r.limbs()) {
+            assert_equal_if(ctx, main_gate, x_limb.as_ref(), r_limb.as_ref(), &is_valid)?;
+        }
+        let y_bits = main_gate.to_bits(ctx, point_r_y.limbs()[0].as_ref(), BIT_LEN_LIMB)?;
+        assert_equal_if(ctx, main_gate, &y_bits[0], &recovery_id, &is_valid)?;
+
+        // Since p = 3 mod 4, -1 is not a square modulo p, so there's no point
+        // with x-coordinate r iff -(r^3 + 7) is a square.
+        let sig_r_base = base_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(RnsInteger::from_big(
+                BigUint::from_bytes_le(&ecrecover.sig_r.to_le_bytes()),
+                rns_base.clone(),
+            ))),
+            Range::Remainder,
+        )?;
+        for (base_limb, scalar_limb) in sig_r_base.limbs().iter().zip_eq(sig_r.limbs()) {
+            main_gate.assert_equal(ctx, base_limb.as_ref(), scalar_limb.as_ref())?;
+        }
+        let y = base_chip.assign_integer(
+            ctx,
+            UnassignedInteger::from(Value::known(RnsInteger::from_fe(
+                ecrecover.non_residue_witness(),
+                rns_base,
+            ))),
+            Range::Remainder,
+        )?;
+        let r_square = base_chip.square(ctx, &sig_r_base)?;
+        let r_cube = base_chip.mul(ctx, &r_square, &sig_r_base)?;
+        let y_square = base_chip.square(ctx, &y)?;
+        let seven = base_chip.assign_constant(ctx, secp256k1::Fp::from(7))?;
+        let sum = base_chip.add(ctx, &r_cube, &y_square)?;
+        let sum = base_chip.add(ctx, &sum, &seven)?;
+        let sum = base_chip.reduce(ctx, &sum)?;
+        let is_not_recoverable = {
+            let is_invalid = main_gate.not(ctx, &is_valid)?;
+            main_gate.mul(ctx, &is_enabled, &is_invalid)?
+        };
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        for limb in sum.limbs() {
+            assert_equal_if(ctx, main_gate, limb.as_ref(), &zero, &is_not_recoverable)?;
+        }
+
+        let msg_hash_le = integer_to_bytes_le(ctx, range_chip, &msg_hash)?;
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &sig_r)?;
+        let sig_s_le = integer_to_bytes_le(ctx, range_chip, &sig_s)?;
+        let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk.x())?;
+        let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk.y())?;
+
+        Ok(AssignedEcrecover {
+            ecdsa: AssignedECDSA {
+                pk_x_le,
+                pk_y_le,
+                msg_hash_le,
+            },
+            sig_r_le,
+            sig_s_le,
+            sig_v,
+            is_valid,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn assign_rlc_le(
         &self,
@@ -486,27 +737,24 @@ impl<F: Field> SignVerifyChip<F> {
         Ok(())
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn assign_signature_verify(
+    /// Assign the address of the public key, which is zero when `pk` is
+    /// `None`, and enable the keccak lookup that verifies it otherwise.
+    /// Returns the address and whether it's zero.
+    fn assign_address(
         &self,
         config: &SignVerifyConfig,
         ctx: &mut RegionCtx<F>,
         chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-        sign_data: Option<&SignData>,
+        pk: Option<&Secp256k1Affine>,
         assigned_ecdsa: &AssignedECDSA<F>,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<AssignedSignatureVerify<F>, Error> {
+    ) -> Result<(AssignedValue<F>, AssignedValue<F>), Error> {
         let main_gate = chips.main_gate;
 
-        let (padding, sign_data) = match sign_data {
-            Some(sign_data) => (false, sign_data.clone()),
-            None => (true, SignData::default()),
-        };
-
-        let pk_le = pk_bytes_le(&sign_data.pk);
-        let pk_be = pk_bytes_swap_endianness(&pk_le);
-        let pk_hash = (!padding)
-            .then(|| {
+        let pk_hash = pk
+            .map(|pk| {
+                let pk_le = pk_bytes_le(pk);
+                let pk_be = pk_bytes_swap_endianness(&pk_le);
                 let mut keccak = Keccak::default();
                 keccak.update(&pk_be);
                 let hash: [_; 32] = keccak.digest().try_into().expect("vec to array of size 32");
@@ -539,40 +787,15 @@ impl<F: Field> SignVerifyChip<F> {
         };
         let is_address_zero = main_gate.is_zero(ctx, &address)?;
 
-        // Ref. spec SignVerifyChip 3. Verify that the signed message in the ecdsa_chip
-        // with RLC encoding corresponds to msg_hash_rlc
-        let msg_hash_rlc = {
-            let zero = main_gate.assign_constant(ctx, F::ZERO)?;
-            let assigned_msg_hash_le = assigned_ecdsa
-                .msg_hash_le
-                .iter()
-                .map(|byte| main_gate.select(ctx, &zero, byte, &is_address_zero))
-                .collect::<Result<Vec<_>, _>>()?;
-            let msg_hash_le = (!padding)
-                .then(|| sign_data.msg_hash.to_bytes())
-                .unwrap_or_default()
-                .map(|byte| Value::known(F::from(byte as u64)));
-            self.assign_rlc_le(
-                config,
-                ctx,
-                chips,
-                "msg_hash",
-                config.q_rlc_evm_word,
-                challenges.evm_word(),
-                assigned_msg_hash_le
-                    .iter()
-                    .zip(msg_hash_le)
-                    .map(|(assigned, byte)| Term::assigned(assigned.cell(), byte)),
-            )?
-        };
-
+        // The assigned public key is the padding one when `pk` is `None`.
+        let pk = pk.copied().unwrap_or(SignData::default().pk);
         let pk_rlc = {
             let assigned_pk_le = iter::empty()
                 .chain(&assigned_ecdsa.pk_y_le)
                 .chain(&assigned_ecdsa.pk_x_le);
             let pk_le = iter::empty()
-                .chain(sign_data.pk.y.to_bytes())
-                .chain(sign_data.pk.x.to_bytes())
+                .chain(pk.y.to_bytes())
+                .chain(pk.x.to_bytes())
                 .map(|byte| Value::known(F::from(byte as u64)));
             self.assign_rlc_le(
                 config,
@@ -601,17 +824,193 @@ impl<F: Field> SignVerifyChip<F> {
 
         self.enable_keccak_lookup(config, ctx, &is_address_zero, &pk_rlc, &pk_hash_rlc)?;
 
+        Ok((address, is_address_zero))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_signature_verify(
+        &self,
+        config: &SignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        sign_data: Option<&SignData>,
+        assigned_ecdsa: &AssignedECDSA<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedSignatureVerify<F>, Error> {
+        let main_gate = chips.main_gate;
+
+        let (padding, sign_data) = match sign_data {
+            Some(sign_data) => (false, sign_data.clone()),
+            None => (true, SignData::default()),
+        };
+
+        let (address, is_address_zero) = self.assign_address(
+            config,
+            ctx,
+            chips,
+            (!padding).then_some(&sign_data.pk),
+            assigned_ecdsa,
+            challenges,
+        )?;
+
+        // Ref. spec SignVerifyChip 3. Verify that the signed message in the ecdsa_chip
+        // with RLC encoding corresponds to msg_hash_rlc
+        let msg_hash_rlc = {
+            let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+            let assigned_msg_hash_le = assigned_ecdsa
+                .msg_hash_le
+                .iter()
+                .map(|byte| main_gate.select(ctx, &zero, byte, &is_address_zero))
+                .collect::<Result<Vec<_>, _>>()?;
+            let msg_hash_le = (!padding)
+                .then(|| sign_data.msg_hash.to_bytes())
+                .unwrap_or_default()
+                .map(|byte| Value::known(F::from(byte as u64)));
+            self.assign_rlc_le(
+                config,
+                ctx,
+                chips,
+                "msg_hash",
+                config.q_rlc_evm_word,
+                challenges.evm_word(),
+                assigned_msg_hash_le
+                    .iter()
+                    .zip(msg_hash_le)
+                    .map(|(assigned, byte)| Term::assigned(assigned.cell(), byte)),
+            )?
+        };
+
         Ok(AssignedSignatureVerify {
             address,
             msg_hash_rlc,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn assign_ecrecover_verify(
+        &self,
+        config: &SignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        ecrecover: &EcrecoverWitness,
+        assigned_ecrecover: &AssignedEcrecover<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedSigTableRow<F>, Error> {
+        let main_gate = chips.main_gate;
+
+        let (recovered_addr, is_address_zero) = self.assign_address(
+            config,
+            ctx,
+            chips,
+            ecrecover.pk.as_ref(),
+            &assigned_ecrecover.ecdsa,
+            challenges,
+        )?;
+        // The recovered address is zero iff the recovery failed.
+        let sum = main_gate.add(ctx, &assigned_ecrecover.is_valid, &is_address_zero)?;
+        main_gate.assert_one(ctx, &sum)?;
+
+        let word_rlc = |ctx: &mut RegionCtx<F>, name, assigned_le, word| {
+            self.assign_rlc_le(
+                config,
+                ctx,
+                chips,
+                name,
+                config.q_rlc_evm_word,
+                challenges.evm_word(),
+                word_terms_le(assigned_le, word),
+            )
+        };
+        let msg_hash_rlc = word_rlc(
+            ctx,
+            "ecrecover_msg_hash",
+            &assigned_ecrecover.ecdsa.msg_hash_le,
+            &ecrecover.msg_hash,
+        )?;
+        let sig_r_rlc = word_rlc(
+            ctx,
+            "ecrecover_sig_r",
+            &assigned_ecrecover.sig_r_le,
+            &ecrecover.sig_r,
+        )?;
+        let sig_s_rlc = word_rlc(
+            ctx,
+            "ecrecover_sig_s",
+            &assigned_ecrecover.sig_s_le,
+            &ecrecover.sig_s,
+        )?;
+
+        Ok(AssignedSigTableRow {
+            msg_hash_rlc,
+            sig_v: assigned_ecrecover.sig_v.clone(),
+            sig_r_rlc,
+            sig_s_rlc,
+            recovered_addr,
+            is_valid: assigned_ecrecover.is_valid.clone(),
+        })
+    }
+
+    fn assign_sig_table(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        rows: &[AssignedSigTableRow<F>],
+    ) -> Result<(), Error> {
+        let sig_table = &config.sig_table;
+        layouter.assign_region(
+            || "sig table",
+            |mut region| {
+                // All-zero row for the disabled lookups
+                region.assign_fixed(
+                    || "sig table all-zero row",
+                    sig_table.q_enable,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+                for column in <SigTable as LookupTable<F>>::advice_columns(sig_table) {
+                    region.assign_advice(
+                        || "sig table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (idx, row) in rows.iter().enumerate() {
+                    let offset = idx + 1;
+                    region.assign_fixed(
+                        || format!("sig table q_enable {}", offset),
+                        sig_table.q_enable,
+                        offset,
+                        || Value::known(F::ONE),
+                    )?;
+                    for (column, cell) in [
+                        (sig_table.msg_hash_rlc, &row.msg_hash_rlc),
+                        (sig_table.sig_v, &row.sig_v),
+                        (sig_table.sig_r_rlc, &row.sig_r_rlc),
+                        (sig_table.sig_s_rlc, &row.sig_s_rlc),
+                        (sig_table.recovered_addr, &row.recovered_addr),
+                        (sig_table.is_valid, &row.is_valid),
+                    ] {
+                        cell.copy_advice(
+                            || format!("sig table row {}", offset),
+                            &mut region,
+                            column,
+                            offset,
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
     pub(crate) fn assign(
         &self,
         config: &SignVerifyConfig,
         layouter: &mut impl Layouter<F>,
         signatures: &[SignData],
+        ecrecover_events: &[EcrecoverAuxData],
         challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
         if signatures.len() > self.max_verif {
@@ -622,6 +1021,18 @@ impl<F: Field> SignVerifyChip<F> {
             );
             return Err(Error::Synthesis);
         }
+        if ecrecover_events.len() > self.max_ecrecover {
+            error!(
+                "ecrecover_events.len() = {} > max_ecrecover = {}",
+                ecrecover_events.len(),
+                self.max_ecrecover
+            );
+            return Err(Error::Synthesis);
+        }
+        // None when padding
+        let ecrecovers = (0..self.max_ecrecover)
+            .map(|i| EcrecoverWitness::new(ecrecover_events.get(i)))
+            .collect_vec();
         let main_gate = MainGate::new(config.main_gate_config.clone());
         let range_chip = RangeChip::new(config.range_config.clone());
         let mut ecc_chip = GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(
@@ -650,7 +1061,7 @@ impl<F: Field> SignVerifyChip<F> {
             ecdsa_chip: &ecdsa_chip,
         };
 
-        let assigned_ecdsas = layouter.assign_region(
+        let (assigned_ecdsas, assigned_ecrecovers) = layouter.assign_region(
             || "ecdsa chip verification",
             |region| {
                 let mut assigned_ecdsas = Vec::new();
@@ -665,12 +1076,16 @@ impl<F: Field> SignVerifyChip<F> {
                     let assigned_ecdsa = self.assign_ecdsa(&mut ctx, &chips, &signature)?;
                     assigned_ecdsas.push(assigned_ecdsa);
                 }
+                let assigned_ecrecovers = ecrecovers
+                    .iter()
+                    .map(|ecrecover| self.assign_ecrecover(&mut ctx, &chips, ecrecover))
+                    .collect::<Result<Vec<_>, _>>()?;
                 log::debug!("ecdsa chip verification: {} rows", ctx.offset());
-                Ok(assigned_ecdsas)
+                Ok((assigned_ecdsas, assigned_ecrecovers))
             },
         )?;
 
-        layouter.assign_region(
+        let (assigned_sig_verifs, sig_table_rows) = layouter.assign_region(
             || "signature address verify",
             |region| {
                 let mut assigned_sig_verifs = Vec::new();
@@ -687,10 +1102,28 @@ impl<F: Field> SignVerifyChip<F> {
                     )?;
                     assigned_sig_verifs.push(assigned_sig_verif);
                 }
+                let sig_table_rows = ecrecovers
+                    .iter()
+                    .zip_eq(assigned_ecrecovers.iter())
+                    .map(|(ecrecover, assigned_ecrecover)| {
+                        self.assign_ecrecover_verify(
+                            config,
+                            &mut ctx,
+                            &chips,
+                            ecrecover,
+                            assigned_ecrecover,
+                            challenges,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
                 log::debug!("signature address verify: {} rows", ctx.offset());
-                Ok(assigned_sig_verifs)
+                Ok((assigned_sig_verifs, sig_table_rows))
             },
-        )
+        )?;
+
+        self.assign_sig_table(config, layouter, &sig_table_rows)?;
+
+        Ok(assigned_sig_verifs)
     }
 }
 
@@ -704,8 +1137,8 @@ fn pub_key_hash_to_address<F: Field>(pk_hash: &[u8]) -> F {
 mod sign_verify_tests {
     use super::*;
     use crate::util::Challenges;
-    use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
-    use eth_types::sign_types::sign;
+    use bus_mapping::circuit_input_builder::{keccak_inputs_ecrecover, keccak_inputs_sign_verify};
+    use eth_types::{sign_types::sign, Address};
     use halo2_proofs::{
         arithmetic::Field as HaloField,
         circuit::SimpleFloorPlanner,
@@ -730,11 +1163,12 @@ mod sign_verify_tests {
     impl TestCircuitSignVerifyConfig {
         pub(crate) fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
             let keccak_table = KeccakTable::construct(meta);
+            let sig_table = SigTable::construct(meta);
             let challenges = Challenges::construct(meta);
 
             let sign_verify = {
                 let challenges = challenges.exprs(meta);
-                SignVerifyConfig::new(meta, keccak_table, challenges)
+                SignVerifyConfig::new(meta, keccak_table, sig_table, challenges)
             };
 
             TestCircuitSignVerifyConfig {
@@ -748,6 +1182,7 @@ mod sign_verify_tests {
     struct TestCircuitSignVerify<F: Field> {
         sign_verify: SignVerifyChip<F>,
         signatures: Vec<SignData>,
+        ecrecover_events: Vec<EcrecoverAuxData>,
     }
 
     impl<F: Field> Circuit<F> for TestCircuitSignVerify<F> {
@@ -774,11 +1209,14 @@ mod sign_verify_tests {
                 &config.sign_verify,
                 &mut layouter,
                 &self.signatures,
+                &self.ecrecover_events,
                 &challenges,
             )?;
             config.sign_verify.keccak_table.dev_load(
                 &mut layouter,
-                &keccak_inputs_sign_verify(&self.signatures),
+                keccak_inputs_sign_verify(&self.signatures)
+                    .iter()
+                    .chain(keccak_inputs_ecrecover(&self.ecrecover_events).iter()),
                 &challenges,
             )?;
             config.sign_verify.load_range(&mut layouter)?;
@@ -786,7 +1224,13 @@ mod sign_verify_tests {
         }
     }
 
-    fn run<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) {
+    fn run<F: Field>(
+        k: u32,
+        max_verif: usize,
+        signatures: Vec<SignData>,
+        max_ecrecover: usize,
+        ecrecover_events: Vec<EcrecoverAuxData>,
+    ) {
         let mut rng = XorShiftRng::seed_from_u64(2);
         let aux_generator =
             <Secp256k1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
@@ -797,9 +1241,11 @@ mod sign_verify_tests {
                 aux_generator,
                 window_size: 4,
                 max_verif,
+                max_ecrecover,
                 _marker: PhantomData,
             },
             signatures,
+            ecrecover_events,
         };
 
         let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
@@ -861,6 +1307,61 @@ mod sign_verify_tests {
         }
 
         let k = 19;
-        run::<Fr>(k, MAX_VERIF, signatures);
+        run::<Fr>(k, MAX_VERIF, signatures, 0, vec![]);
+    }
+
+    #[test]
+    fn sign_verify_ecrecover() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        const MAX_ECRECOVER: usize = 4;
+        let mut ecrecover_events = Vec::new();
+        for _ in 0..2 {
+            let (sk, pk) = gen_key_pair(&mut rng);
+            let msg_hash = gen_msg_hash(&mut rng);
+            let (r, s) = sign_with_rng(&mut rng, sk, msg_hash);
+            let aux_data = ecrecover_aux_data(msg_hash, r, s, &pk);
+            assert_eq!(aux_data.recover_pk(), Some(pk));
+            ecrecover_events.push(aux_data);
+        }
+        // No point on the curve has x-coordinate 5, since 5^3 + 7 is not a
+        // square modulo p.
+        let not_recoverable = EcrecoverAuxData {
+            msg_hash: Word::from(1),
+            sig_v: Word::from(27),
+            sig_r: Word::from(5),
+            sig_s: Word::from(1),
+            recovered_addr: Address::zero(),
+        };
+        assert_eq!(not_recoverable.recover_pk(), None);
+        ecrecover_events.push(not_recoverable);
+
+        let k = 20;
+        run::<Fr>(k, 0, vec![], MAX_ECRECOVER, ecrecover_events);
+    }
+
+    // Build the ecRecover input of a signature, finding its recovery id.
+    fn ecrecover_aux_data(
+        msg_hash: secp256k1::Fq,
+        r: secp256k1::Fq,
+        s: secp256k1::Fq,
+        pk: &Secp256k1Affine,
+    ) -> EcrecoverAuxData {
+        let word = |fq: secp256k1::Fq| Word::from_little_endian(&fq.to_bytes());
+        let pk_le = pk_bytes_le(pk);
+        let pk_be = pk_bytes_swap_endianness(&pk_le);
+        let mut keccak = Keccak::default();
+        keccak.update(&pk_be);
+        let recovered_addr = Address::from_slice(&keccak.digest()[12..]);
+        [27u64, 28]
+            .into_iter()
+            .map(|v| EcrecoverAuxData {
+                msg_hash: word(msg_hash),
+                sig_v: Word::from(v),
+                sig_r: word(r),
+                sig_s: word(s),
+                recovered_addr,
+            })
+            .find(|aux_data| aux_data.recover_pk() == Some(*pk))
+            .expect("one of the recovery ids gives the public key")
     }
 }
@@ -114,3 +114,50 @@ fn variadic_size_check() {
     assert_eq!(prover1.fixed(), prover2.fixed());
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
+
+#[test]
+fn tx_circuit_1tx_1max_eip1559_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+
+    let tx: Transaction = mock::MockTransaction::default()
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(0x103u64)
+        .value(eth_types::word!("0x3e8"))
+        .transaction_type(2)
+        .max_priority_fee_per_gas(eth_types::word!("0x4d2"))
+        .max_fee_per_gas(eth_types::word!("0x9a4"))
+        .input(eth_types::Bytes::from(b"hello"))
+        .build()
+        .into();
+
+    assert_eq!(run::<Fr>(vec![tx], chain_id, MAX_TXS, MAX_CALLDATA), Ok(()));
+}
+
+#[test]
+fn tx_circuit_1tx_1max_eip2930_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id: u64 = mock::MOCK_CHAIN_ID.as_u64();
+
+    let tx: Transaction = mock::MockTransaction::default()
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(0x103u64)
+        .value(eth_types::word!("0x3e8"))
+        .gas_price(eth_types::word!("0x4d2"))
+        .transaction_type(1)
+        .access_list(eth_types::AccessList(vec![eth_types::AccessListItem {
+            address: mock::MOCK_ACCOUNTS[1],
+            storage_keys: vec![eth_types::H256::from_low_u64_be(1)],
+        }]))
+        .input(eth_types::Bytes::from(b"hello"))
+        .build()
+        .into();
+
+    assert_eq!(run::<Fr>(vec![tx], chain_id, MAX_TXS, MAX_CALLDATA), Ok(()));
+}
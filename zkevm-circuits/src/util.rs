@@ -213,12 +213,12 @@ pub(crate) fn keccak(msg: &[u8]) -> Word {
     Word::from_big_endian(keccak.digest().as_slice())
 }
 
-pub(crate) fn is_push(byte: u8) -> bool {
-    OpcodeId::from(byte).is_push()
+pub(crate) fn is_push_with_data(byte: u8) -> bool {
+    OpcodeId::from(byte).is_push_with_data()
 }
 
 pub(crate) fn get_push_size(byte: u8) -> u64 {
-    if is_push(byte) {
+    if is_push_with_data(byte) {
         byte as u64 - OpcodeId::PUSH1.as_u64() + 1
     } else {
         0u64
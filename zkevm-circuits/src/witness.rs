@@ -13,6 +13,7 @@ pub use bus_mapping::circuit_input_builder::ExecStep;
 pub use rw::{Rw, RwMap, RwRow};
 mod tx;
 pub use bus_mapping::circuit_input_builder::Call;
+pub(crate) use tx::access_list_rlc;
 pub use tx::Transaction;
 mod protocol_instance;
 pub use protocol_instance::{left_shift, MetaHash, ProtocolInstance};
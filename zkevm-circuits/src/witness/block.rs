@@ -8,9 +8,13 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::{self, CircuitsParams, CopyEvent, ExpEvent},
+    precompile::PrecompileEvents,
     Error,
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, ToWord, Word};
+use eth_types::{
+    chain_spec::Hardfork, evm_types::MAX_BLOB_HASHES_PER_TX, Address, Field, ToLittleEndian,
+    ToScalar, ToWord, Word,
+};
 use halo2_proofs::circuit::Value;
 
 use super::{tx::tx_convert, Bytecode, ExecStep, ProtocolInstance, Rw, RwMap, Transaction};
@@ -41,6 +45,8 @@ pub struct Block<F> {
     pub exp_events: Vec<ExpEvent>,
     /// Pad exponentiation circuit to make selectors fixed.
     pub exp_circuit_pad_to: usize,
+    /// Precompile events, verified by dedicated circuits.
+    pub precompile_events: PrecompileEvents,
     /// Circuit Setup Parameters
     pub circuits_params: CircuitsParams,
     /// Inputs to the SHA3 opcode
@@ -49,6 +55,12 @@ pub struct Block<F> {
     pub prev_state_root: Word, // TODO: Make this H256
     /// Keccak inputs
     pub keccak_inputs: Vec<Vec<u8>>,
+    /// Inputs to the SHA2-256 precompile
+    pub sha256_inputs: Vec<Vec<u8>>,
+    /// Inputs to the RIPEMD-160 precompile
+    pub ripemd160_inputs: Vec<Vec<u8>>,
+    /// Inputs to the MODEXP precompile, as `[base, exponent, modulus]`
+    pub modexp_inputs: Vec<[Word; 3]>,
     /// Original Block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
     /// Protocol Instance
@@ -80,8 +92,11 @@ impl<F: Field> Block<F> {
         let num_rows_required_for_copy_table: usize =
             self.copy_events.iter().map(|c| c.bytes.len() * 2).sum();
         let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
-        let num_rows_required_for_tx_table: usize =
-            self.txs.iter().map(|tx| 9 + tx.call_data.len()).sum();
+        let num_rows_required_for_tx_table: usize = self
+            .txs
+            .iter()
+            .map(|tx| 9 + tx.call_data.len() + MAX_BLOB_HASHES_PER_TX)
+            .sum();
         let num_rows_required_for_exp_table: usize = self
             .exp_events
             .iter()
@@ -132,10 +147,14 @@ pub struct BlockContext {
     pub difficulty: Word,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
+    /// The blob base fee, the price per unit of blob gas (EIP-4844)
+    pub blob_base_fee: Word,
     /// The hash of previous blocks
     pub history_hashes: Vec<Word>,
     /// The chain id
     pub chain_id: Word,
+    /// The hardfork whose rules apply to the block
+    pub hardfork: Hardfork,
     /// The block hash
     pub block_hash: Word,
 }
@@ -183,6 +202,18 @@ impl BlockContext {
                     randomness
                         .map(|randomness| rlc::value(&self.chain_id.to_le_bytes(), randomness)),
                 ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    Value::known(F::ZERO),
+                    randomness.map(|randomness| {
+                        rlc::value(&self.blob_base_fee.to_le_bytes(), randomness)
+                    }),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::Hardfork as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.hardfork as u64)),
+                ],
                 [
                     Value::known(F::from(BlockContextFieldTag::BlockHash as u64)),
                     Value::known(self.number.to_scalar().unwrap()),
@@ -219,8 +250,10 @@ impl From<&circuit_input_builder::Block> for BlockContext {
             timestamp: block.timestamp,
             difficulty: block.difficulty,
             base_fee: block.base_fee,
+            blob_base_fee: block.blob_base_fee,
             history_hashes: block.history_hashes.clone(),
             chain_id: block.chain_id,
+            hardfork: block.hardfork,
             block_hash: block
                 .eth_block
                 .hash
@@ -263,11 +296,30 @@ pub fn block_convert<F: Field>(
         sha3_inputs: block.sha3_inputs.clone(),
         circuits_params: block.circuits_params,
         exp_circuit_pad_to: <usize>::default(),
+        precompile_events: block.precompile_events.clone(),
         prev_state_root: block.prev_state_root,
         // Use EVM Circuit's related inputs for keccak inputs
         // Otherwise, it will fail due to tx.v in keccak_inputs_tx_circuit
         // keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
         keccak_inputs: block.sha3_inputs.clone(),
+        sha256_inputs: block
+            .precompile_events
+            .get_sha256_events()
+            .into_iter()
+            .map(|aux_data| aux_data.input_bytes)
+            .collect(),
+        ripemd160_inputs: block
+            .precompile_events
+            .get_ripemd160_events()
+            .into_iter()
+            .map(|aux_data| aux_data.input_bytes)
+            .collect(),
+        modexp_inputs: block
+            .precompile_events
+            .get_modexp_events()
+            .into_iter()
+            .map(|aux_data| aux_data.inputs)
+            .collect(),
         eth_block: block.eth_block.clone(),
         protocol_instance: ProtocolInstance::default(),
     })
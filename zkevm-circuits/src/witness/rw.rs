@@ -180,6 +180,15 @@ pub enum Rw {
         is_warm: bool,
         is_warm_prev: bool,
     },
+    /// TxCreatedAccount
+    TxCreatedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_created: bool,
+        is_created_prev: bool,
+    },
     /// TxRefund
     TxRefund {
         rw_counter: usize,
@@ -208,6 +217,16 @@ pub enum Rw {
         tx_id: usize,
         committed_value: Word,
     },
+    /// TransientStorage
+    TransientStorage {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        storage_key: Word,
+        value: Word,
+        value_prev: Word,
+    },
     /// CallContext
     CallContext {
         rw_counter: usize,
@@ -318,6 +337,17 @@ impl Rw {
         }
     }
 
+    pub(crate) fn tx_created_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxCreatedAccount {
+                is_created,
+                is_created_prev,
+                ..
+            } => (*is_created, *is_created_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -360,6 +390,15 @@ impl Rw {
         }
     }
 
+    pub(crate) fn transient_storage_value_pair(&self) -> (Word, Word) {
+        match self {
+            Self::TransientStorage {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn call_context_value(&self) -> Word {
         match self {
             Self::CallContext { value, .. } => *value,
@@ -450,8 +489,10 @@ impl Rw {
             | Self::Memory { rw_counter, .. }
             | Self::Stack { rw_counter, .. }
             | Self::AccountStorage { rw_counter, .. }
+            | Self::TransientStorage { rw_counter, .. }
             | Self::TxAccessListAccount { rw_counter, .. }
             | Self::TxAccessListAccountStorage { rw_counter, .. }
+            | Self::TxCreatedAccount { rw_counter, .. }
             | Self::TxRefund { rw_counter, .. }
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
@@ -466,8 +507,10 @@ impl Rw {
             Self::Memory { is_write, .. }
             | Self::Stack { is_write, .. }
             | Self::AccountStorage { is_write, .. }
+            | Self::TransientStorage { is_write, .. }
             | Self::TxAccessListAccount { is_write, .. }
             | Self::TxAccessListAccountStorage { is_write, .. }
+            | Self::TxCreatedAccount { is_write, .. }
             | Self::TxRefund { is_write, .. }
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
@@ -482,8 +525,10 @@ impl Rw {
             Self::Memory { .. } => Target::Memory,
             Self::Stack { .. } => Target::Stack,
             Self::AccountStorage { .. } => Target::Storage,
+            Self::TransientStorage { .. } => Target::TransientStorage,
             Self::TxAccessListAccount { .. } => Target::TxAccessListAccount,
             Self::TxAccessListAccountStorage { .. } => Target::TxAccessListAccountStorage,
+            Self::TxCreatedAccount { .. } => Target::TxCreatedAccount,
             Self::TxRefund { .. } => Target::TxRefund,
             Self::Account { .. } => Target::Account,
             Self::CallContext { .. } => Target::CallContext,
@@ -495,8 +540,10 @@ impl Rw {
     pub(crate) fn id(&self) -> Option<usize> {
         match self {
             Self::AccountStorage { tx_id, .. }
+            | Self::TransientStorage { tx_id, .. }
             | Self::TxAccessListAccount { tx_id, .. }
             | Self::TxAccessListAccountStorage { tx_id, .. }
+            | Self::TxCreatedAccount { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
             | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
@@ -515,11 +562,17 @@ impl Rw {
             | Self::TxAccessListAccountStorage {
                 account_address, ..
             }
+            | Self::TxCreatedAccount {
+                account_address, ..
+            }
             | Self::Account {
                 account_address, ..
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::TransientStorage {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(U256::from(*memory_address).to_address()),
             Self::Stack { stack_pointer, .. } => {
@@ -550,8 +603,10 @@ impl Rw {
             | Self::Memory { .. }
             | Self::Stack { .. }
             | Self::AccountStorage { .. }
+            | Self::TransientStorage { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
+            | Self::TxCreatedAccount { .. }
             | Self::TxRefund { .. }
             | Self::TxLog { .. } => None,
         }
@@ -560,6 +615,7 @@ impl Rw {
     pub(crate) fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
+            | Self::TransientStorage { storage_key, .. }
             | Self::TxAccessListAccountStorage { storage_key, .. } => Some(*storage_key),
             Self::Start { .. }
             | Self::CallContext { .. }
@@ -568,6 +624,7 @@ impl Rw {
             | Self::TxRefund { .. }
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
+            | Self::TxCreatedAccount { .. }
             | Self::TxLog { .. }
             | Self::TxReceipt { .. } => None,
         }
@@ -596,9 +653,9 @@ impl Rw {
                 }
                 AccountFieldTag::Nonce | AccountFieldTag::NonExisting => value.to_scalar().unwrap(),
             },
-            Self::AccountStorage { value, .. } | Self::Stack { value, .. } => {
-                rlc::value(&value.to_le_bytes(), randomness)
-            }
+            Self::AccountStorage { value, .. }
+            | Self::TransientStorage { value, .. }
+            | Self::Stack { value, .. } => rlc::value(&value.to_le_bytes(), randomness),
 
             Self::TxLog {
                 field_tag, value, ..
@@ -609,6 +666,7 @@ impl Rw {
 
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => F::from(*is_warm as u64),
+            Self::TxCreatedAccount { is_created, .. } => F::from(*is_created as u64),
             Self::Memory { byte, .. } => F::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => F::from(*value),
         }
@@ -628,13 +686,16 @@ impl Rw {
                     value_prev.to_scalar().unwrap()
                 }
             }),
-            Self::AccountStorage { value_prev, .. } => {
+            Self::AccountStorage { value_prev, .. } | Self::TransientStorage { value_prev, .. } => {
                 Some(rlc::value(&value_prev.to_le_bytes(), randomness))
             }
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(F::from(*is_warm_prev as u64))
             }
+            Self::TxCreatedAccount {
+                is_created_prev, ..
+            } => Some(F::from(*is_created_prev as u64)),
             Self::TxRefund { value_prev, .. } => Some(F::from(*value_prev)),
             Self::Start { .. }
             | Self::Stack { .. }
@@ -684,6 +745,21 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TxCreatedAccount,
+            container
+                .tx_created_account
+                .iter()
+                .map(|op| Rw::TxCreatedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_created: op.op().is_created,
+                    is_created_prev: op.op().is_created_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             Target::TxAccessListAccountStorage,
             container
@@ -750,6 +826,22 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TransientStorage,
+            container
+                .transient_storage
+                .iter()
+                .map(|op| Rw::TransientStorage {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    storage_key: op.op().key,
+                    value: op.op().value,
+                    value_prev: op.op().value_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             Target::CallContext,
             container
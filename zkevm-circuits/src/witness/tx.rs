@@ -1,8 +1,9 @@
 use bus_mapping::circuit_input_builder;
 use eth_types::{
-    sign_types::SignData, Address, Field, ToBigEndian, ToLittleEndian, ToScalar, Word, H256,
+    evm_types::MAX_BLOB_HASHES_PER_TX, geth_types::TxType, sign_types::SignData, AccessList,
+    Address, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, Word, H256,
 };
-use halo2_proofs::circuit::Value;
+use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
     evm_circuit::util::rlc,
@@ -17,12 +18,18 @@ use super::{Call, ExecStep};
 pub struct Transaction {
     /// The transaction identifier in the block
     pub id: usize,
+    /// The transaction type
+    pub tx_type: TxType,
     /// The sender account nonce of the transaction
     pub nonce: u64,
     /// The gas limit of the transaction
     pub gas: u64,
-    /// The gas price
+    /// The effective gas price
     pub gas_price: Word,
+    /// The max fee per gas, which is the gas price for a legacy tx
+    pub max_fee_per_gas: Word,
+    /// The max priority fee per gas, which is the gas price for a legacy tx
+    pub max_priority_fee_per_gas: Word,
     /// The caller address
     pub caller_address: Address,
     /// The callee address
@@ -41,6 +48,14 @@ pub struct Transaction {
     pub invalid_tx: bool,
     /// AccessListGasCost
     pub access_list_gas_cost: u64,
+    /// The access list of the transaction (EIP-2930)
+    pub access_list: Option<AccessList>,
+    /// The number of addresses in the access list
+    pub access_list_addresses_len: u64,
+    /// The number of storage keys in the access list
+    pub access_list_storage_keys_len: u64,
+    /// The versioned hashes of the blobs carried by the transaction
+    pub blob_versioned_hashes: Vec<H256>,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -57,11 +72,13 @@ pub struct Transaction {
 
 impl Transaction {
     /// Assignments for tx table, split into tx_data (all fields except
-    /// calldata) and tx_calldata
+    /// calldata and blob hashes), tx_calldata and tx_blob_hashes. The blob
+    /// hashes are padded with zeros to `MAX_BLOB_HASHES_PER_TX` rows, and a tx
+    /// with more hashes than that is an error.
     pub fn table_assignments<F: Field>(
         &self,
         challenges: Challenges<Value<F>>,
-    ) -> [Vec<[Value<F>; 4]>; 2] {
+    ) -> Result<[Vec<[Value<F>; 4]>; 3], Error> {
         let tx_data = vec![
             [
                 Value::known(F::from(self.id as u64)),
@@ -83,6 +100,22 @@ impl Transaction {
                     .evm_word()
                     .map(|challenge| rlc::value(&self.gas_price.to_le_bytes(), challenge)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerGas as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.max_fee_per_gas.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxPriorityFeePerGas as u64)),
+                Value::known(F::ZERO),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_priority_fee_per_gas.to_le_bytes(), challenge)
+                }),
+            ],
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::CallerAddress as u64)),
@@ -150,6 +183,12 @@ impl Transaction {
                 Value::known(F::ZERO),
                 rlc_be_bytes(&self.s.to_be_bytes(), challenges.evm_word()),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::TxType as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.tx_type as u64)),
+            ],
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::TxInvalid as u64)),
@@ -162,6 +201,26 @@ impl Transaction {
                 Value::known(F::ZERO),
                 Value::known(F::from(self.access_list_gas_cost)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesLen as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.access_list_addresses_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysLen as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.access_list_storage_keys_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListRLC as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .evm_word()
+                    .map(|challenge| access_list_rlc(&self.access_list, challenge)),
+            ],
         ];
         let tx_calldata = self
             .call_data
@@ -176,10 +235,50 @@ impl Transaction {
                 ]
             })
             .collect();
-        [tx_data, tx_calldata]
+        if self.blob_versioned_hashes.len() > MAX_BLOB_HASHES_PER_TX {
+            log::error!(
+                "tx {} has {} blob versioned hashes, more than the {} allowed",
+                self.id,
+                self.blob_versioned_hashes.len(),
+                MAX_BLOB_HASHES_PER_TX
+            );
+            return Err(Error::Synthesis);
+        }
+        let tx_blob_hashes = (0..MAX_BLOB_HASHES_PER_TX)
+            .map(|idx| {
+                let hash = self
+                    .blob_versioned_hashes
+                    .get(idx)
+                    .copied()
+                    .unwrap_or_default();
+                [
+                    Value::known(F::from(self.id as u64)),
+                    Value::known(F::from(TxContextFieldTag::BlobVersionedHashes as u64)),
+                    Value::known(F::from(idx as u64)),
+                    challenges
+                        .evm_word()
+                        .map(|challenge| rlc::value(&hash.to_word().to_le_bytes(), challenge)),
+                ]
+            })
+            .collect();
+        Ok([tx_data, tx_calldata, tx_blob_hashes])
     }
 }
 
+/// Return the RLC of the addresses and storage keys in an access list, in the
+/// order they are listed.
+pub(crate) fn access_list_rlc<F: Field>(access_list: &Option<AccessList>, randomness: F) -> F {
+    access_list
+        .iter()
+        .flat_map(|access_list| access_list.0.iter())
+        .fold(F::ZERO, |acc, item| {
+            let acc = acc * randomness + item.address.to_scalar().unwrap();
+            item.storage_keys.iter().fold(acc, |acc, key| {
+                acc * randomness + rlc::value(&key.to_word().to_le_bytes(), randomness)
+            })
+        })
+}
+
 pub(super) fn tx_convert(
     tx: &circuit_input_builder::Transaction,
     chain_id: u64,
@@ -187,11 +286,15 @@ pub(super) fn tx_convert(
 ) -> Transaction {
     let sign_data: Option<SignData> = tx.tx.sign_data(chain_id).ok();
     let tx_sign_hash = sign_data.map(|sign_data| H256::from(&sign_data.msg_hash.to_bytes()));
+    let (access_list_addresses_len, access_list_storage_keys_len) = tx.tx.access_list_len();
     Transaction {
         id,
+        tx_type: tx.tx.tx_type,
         nonce: tx.tx.nonce.as_u64(),
         gas: tx.gas(),
         gas_price: tx.tx.gas_price,
+        max_fee_per_gas: tx.tx.max_fee_per_gas(),
+        max_priority_fee_per_gas: tx.tx.max_priority_fee_per_gas(),
         caller_address: tx.tx.from,
         callee_address: tx.tx.to_or_contract_addr(),
         is_create: tx.is_create(),
@@ -201,6 +304,10 @@ pub(super) fn tx_convert(
         call_data_gas_cost: tx.tx.call_data_gas_cost(),
         invalid_tx: tx.invalid_tx,
         access_list_gas_cost: tx.access_list_gas_cost,
+        access_list: tx.tx.access_list.clone(),
+        access_list_addresses_len,
+        access_list_storage_keys_len,
+        blob_versioned_hashes: tx.tx.blob_versioned_hashes.clone(),
         calls: tx.calls().to_vec(),
         steps: tx.steps().to_vec(),
         v: tx.tx.v,
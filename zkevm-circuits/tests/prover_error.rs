@@ -6,6 +6,7 @@
 use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
 use env_logger::Env;
 use eth_types::{
+    chain_spec::{ChainSpec, Hardfork},
     geth_types::{Account, GethData},
     Block, Bytes, Error, Transaction, Word, H160, U256,
 };
@@ -38,7 +39,9 @@ fn prover_error() {
     // change any of these values to your needs
     const MOCK_RANDOMNESS: u64 = 0x100;
     let k = 19;
-    let chain_id = Word::from(99);
+    let default_chain_id = Word::from(99);
+    // Hardfork of the chain when its spec isn't known from its chain id
+    let default_hardfork = Hardfork::London;
     let circuit_params = CircuitsParams {
         max_txs: 1,
         max_calldata: 256,
@@ -51,6 +54,11 @@ fn prover_error() {
     let mut eth_block: Block<Transaction> = from_value(eth_block).unwrap();
     eth_block.base_fee_per_gas = Some(U256::zero());
     println!("block {:#?}", eth_block);
+    let chain_id = eth_block
+        .transactions
+        .iter()
+        .find_map(|tx| tx.chain_id)
+        .unwrap_or(default_chain_id);
     let history_hashes: Vec<U256> = {
         let hashes = load_json("../block/block_hashes.json");
         let mut hashes: Vec<U256> = from_value(hashes).unwrap();
@@ -77,8 +85,11 @@ fn prover_error() {
             accounts.push(account);
         }
     }
+    let chain_spec = ChainSpec::from_chain_id(chain_id.as_u64())
+        .unwrap_or_else(|| ChainSpec::with_hardfork(default_hardfork));
     let geth_traces = gen_geth_traces(
         chain_id,
+        chain_spec,
         eth_block.clone(),
         accounts.clone(),
         Some(history_hashes.clone()),
@@ -87,6 +98,7 @@ fn prover_error() {
     .expect("gen_geth_traces");
     let geth_data = GethData {
         chain_id,
+        chain_spec,
         history_hashes,
         eth_block,
         geth_traces,